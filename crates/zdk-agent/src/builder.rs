@@ -1,16 +1,35 @@
 use crate::builder_common::AgentBuilderCore;
-use crate::llm_agent::LLMAgent;
+use crate::llm_agent::{
+    AfterModelCallback, AfterToolCallback, BeforeModelCallback, BeforeToolCallback,
+    DEFAULT_MAX_REFLECTIONS, DEFAULT_RETRY_BACKOFF, LLMAgent, Reflector,
+};
 use std::collections::HashMap;
 use std::sync::Arc;
-use zdk_core::{Agent, Error, LLM, Result, Tool, Toolset};
+use std::time::Duration;
+use zdk_core::{Agent, Capability, Error, GenerateConfig, LLM, Result, Tool, Toolset};
 
 pub struct LLMAgentBuilder {
     core: AgentBuilderCore,
     model: Option<Arc<dyn LLM>>,
     system_instruction: Option<String>,
+    generate_config: Option<GenerateConfig>,
     sub_agents: Vec<Arc<dyn Agent>>,
     tools: HashMap<String, Arc<dyn Tool>>,
+    /// Names registered more than once, reported by `build`
+    duplicate_tools: Vec<String>,
     toolsets: Vec<Arc<dyn Toolset>>,
+    max_retries: u32,
+    retry_backoff: Duration,
+    tool_timeout: Option<Duration>,
+    max_iterations: Option<usize>,
+    max_tool_response_bytes: Option<usize>,
+    trim_context: bool,
+    reflector: Option<Reflector>,
+    max_reflections: usize,
+    before_model: Vec<BeforeModelCallback>,
+    after_model: Vec<AfterModelCallback>,
+    before_tool: Vec<BeforeToolCallback>,
+    after_tool: Vec<AfterToolCallback>,
 }
 
 impl LLMAgentBuilder {
@@ -19,9 +38,23 @@ impl LLMAgentBuilder {
             core: AgentBuilderCore::new(),
             model: None,
             system_instruction: None,
+            generate_config: None,
             sub_agents: Vec::new(),
             tools: HashMap::new(),
+            duplicate_tools: Vec::new(),
             toolsets: Vec::new(),
+            max_retries: 0,
+            retry_backoff: DEFAULT_RETRY_BACKOFF,
+            tool_timeout: None,
+            max_iterations: None,
+            max_tool_response_bytes: None,
+            trim_context: false,
+            reflector: None,
+            max_reflections: DEFAULT_MAX_REFLECTIONS,
+            before_model: Vec::new(),
+            after_model: Vec::new(),
+            before_tool: Vec::new(),
+            after_tool: Vec::new(),
         }
     }
 
@@ -45,42 +78,185 @@ impl LLMAgentBuilder {
         self
     }
 
+    /// Sampling parameters (temperature, max tokens, ...) sent with every request
+    pub fn generate_config(mut self, config: GenerateConfig) -> Self {
+        self.generate_config = Some(config);
+        self
+    }
+
     pub fn sub_agent(mut self, agent: Arc<dyn Agent>) -> Self {
         self.sub_agents.push(agent);
         self
     }
 
+    /// Register a tool; names must be unique across `tool` and `tools`
     pub fn tool(mut self, tool: Arc<dyn Tool>) -> Self {
-        self.tools.insert(tool.name().to_string(), tool);
+        self.add_tool(tool);
         self
     }
 
     pub fn tools(mut self, tools: Vec<Arc<dyn Tool>>) -> Self {
         for tool in tools {
-            self.tools.insert(tool.name().to_string(), tool);
+            self.add_tool(tool);
         }
         self
     }
 
+    /// Tools registered here take precedence over same-named toolset tools
     pub fn toolset(mut self, toolset: Arc<dyn Toolset>) -> Self {
         self.toolsets.push(toolset);
         self
     }
 
+    /// Retry transient LLM errors (HTTP 429/500/502/503) up to `max_retries` times
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Initial delay between retries; doubles after each attempt
+    pub fn retry_backoff(mut self, backoff: Duration) -> Self {
+        self.retry_backoff = backoff;
+        self
+    }
+
+    /// Abort tool executions that run longer than `timeout`
+    ///
+    /// A timed-out call is reported to the model as an error response.
+    pub fn tool_timeout(mut self, timeout: Duration) -> Self {
+        self.tool_timeout = Some(timeout);
+        self
+    }
+
+    /// Maximum number of model calls per invocation
+    ///
+    /// Falls back to the runner's `max_tool_iterations` default, then to 10.
+    ///
+    /// If the model still requests tools when the limit is reached, the agent
+    /// stops with a `MAX_ITERATIONS` error event.
+    pub fn max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = Some(max_iterations);
+        self
+    }
+
+    /// Truncate tool responses whose JSON exceeds `max_bytes` before sending them to the model
+    pub fn max_tool_response_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_tool_response_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Drop the oldest turns of long conversations to fit the model's context window
+    ///
+    /// Room for `generate_config.max_tokens` of output is kept free. Models
+    /// that don't report a context window are sent the full conversation.
+    pub fn trim_context(mut self, enabled: bool) -> Self {
+        self.trim_context = enabled;
+        self
+    }
+
+    /// Check the final answer and retry with feedback when it falls short
+    ///
+    /// The reflector returns `Some(feedback)` to reject the answer, which is
+    /// sent back to the model as a user turn, or `None` to accept it.
+    pub fn reflect(mut self, reflector: Reflector) -> Self {
+        self.reflector = Some(reflector);
+        self
+    }
+
+    /// Maximum number of rejected answers before the last one is accepted (defaults to 3)
+    pub fn max_reflections(mut self, max_reflections: usize) -> Self {
+        self.max_reflections = max_reflections;
+        self
+    }
+
+    /// Inspect or modify each request before it is sent to the model
+    ///
+    /// Runs before every model call in the tool loop, e.g. to add to the
+    /// system instruction or trim history. Callbacks run in the order added.
+    pub fn before_model(mut self, callback: BeforeModelCallback) -> Self {
+        self.before_model.push(callback);
+        self
+    }
+
+    /// Inspect or modify each model response before it is emitted
+    ///
+    /// Sees every streamed chunk, so it can log usage or redact content
+    /// before it reaches events, history and tool execution.
+    pub fn after_model(mut self, callback: AfterModelCallback) -> Self {
+        self.after_model.push(callback);
+        self
+    }
+
+    /// Inspect, veto or answer each tool call before the tool runs
+    ///
+    /// Receives the tool name and arguments. The first callback returning
+    /// `Some` supplies the response and the tool itself is not invoked.
+    pub fn before_tool(mut self, callback: BeforeToolCallback) -> Self {
+        self.before_tool.push(callback);
+        self
+    }
+
+    /// Observe each tool response, e.g. for auditing
+    ///
+    /// Also sees responses supplied by a `before_tool` callback. Calls that
+    /// fail outright (timeouts, unknown tools) produce no response.
+    pub fn after_tool(mut self, callback: AfterToolCallback) -> Self {
+        self.after_tool.push(callback);
+        self
+    }
+
+    fn add_tool(&mut self, tool: Arc<dyn Tool>) {
+        let name = tool.name().to_string();
+        if self.tools.insert(name.clone(), tool).is_some() && !self.duplicate_tools.contains(&name)
+        {
+            self.duplicate_tools.push(name);
+        }
+    }
+
     pub fn build(self) -> Result<LLMAgent> {
         let (name, description) = self.core.validate("LLMAgent", "An LLM-powered agent")?;
+        if !self.duplicate_tools.is_empty() {
+            return Err(Error::Config(format!(
+                "Duplicate tool names: {}",
+                self.duplicate_tools.join(", ")
+            )));
+        }
         let model = self
             .model
             .ok_or_else(|| Error::Config("Model is required".to_string()))?;
 
+        // Models that don't report capabilities are trusted
+        if let Some(capabilities) = model.capabilities()
+            && !capabilities.contains(&Capability::TextGeneration)
+        {
+            return Err(Error::Config(format!(
+                "Model '{}' does not support text generation (capabilities: {:?})",
+                model.name(),
+                capabilities
+            )));
+        }
+
         Ok(LLMAgent {
             name: Arc::from(name),
             description: Arc::from(description),
             model,
             system_instruction: self.system_instruction,
+            generate_config: self.generate_config,
             sub_agents: self.sub_agents,
             tools: self.tools,
             toolsets: self.toolsets,
+            max_retries: self.max_retries,
+            retry_backoff: self.retry_backoff,
+            tool_timeout: self.tool_timeout,
+            max_iterations: self.max_iterations,
+            max_tool_response_bytes: self.max_tool_response_bytes,
+            trim_context: self.trim_context,
+            reflector: self.reflector,
+            max_reflections: self.max_reflections,
+            before_model: self.before_model,
+            after_model: self.after_model,
+            before_tool: self.before_tool,
+            after_tool: self.after_tool,
         })
     }
 }
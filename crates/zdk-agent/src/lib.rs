@@ -9,18 +9,33 @@ pub mod utils;
 pub mod workflow;
 
 pub use builder::LLMAgentBuilder;
-pub use llm_agent::LLMAgent;
+pub use llm_agent::{
+    AfterModelCallback, AfterToolCallback, BeforeModelCallback, BeforeToolCallback, LLMAgent,
+    Reflector,
+};
 pub use workflow::{
-    LoopAgent, LoopAgentBuilder, ParallelAgent, ParallelAgentBuilder, SequentialAgent,
-    SequentialAgentBuilder,
+    BranchAgent, BranchAgentBuilder, BranchCondition, LoopAgent, LoopAgentBuilder, ParallelAgent,
+    ParallelAgentBuilder, SequentialAgent, SequentialAgentBuilder,
 };
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::testing::MockLLM;
+    use crate::testing::{FlakyLLM, LoopingLLM, MockContext, MockLLM, RecordingLLM, ScriptedLLM};
+    use crate::utils::is_retryable_llm_error;
+    use futures::StreamExt;
     use std::sync::Arc;
-    use zdk_core::Agent;
+    use std::time::Duration;
+    use zdk_core::{Agent, Content, Error, Event, GenerateConfig, Part, Result};
+
+    async fn collect_events(agent: &LLMAgent) -> Vec<Result<Event>> {
+        let mut stream = agent.run(Arc::new(MockContext::new("Hello"))).await;
+        let mut events = Vec::new();
+        while let Some(event) = stream.next().await {
+            events.push(event);
+        }
+        events
+    }
 
     #[test]
     fn test_builder_creates_agent() {
@@ -52,4 +67,1036 @@ mod tests {
 
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_builder_rejects_embedding_only_model() {
+        use zdk_core::{GeminiAuth, GeminiProvider, providers::gemini::GeminiConfig};
+
+        let gemini = |model: &str| {
+            Arc::new(GeminiProvider::new(
+                GeminiAuth::ApiKey("key".to_string()),
+                GeminiConfig::default_api_key(model.to_string()),
+            ))
+        };
+
+        let Err(err) = LLMAgent::builder()
+            .name("test-agent")
+            .model(gemini("text-embedding-004"))
+            .build()
+        else {
+            panic!("Expected an embedding-only model to be rejected");
+        };
+        assert!(matches!(err, Error::Config(_)));
+        assert!(
+            err.to_string()
+                .contains("'text-embedding-004' does not support text generation")
+        );
+
+        // Listed text models and models without reported capabilities are accepted
+        assert!(
+            LLMAgent::builder()
+                .name("test-agent")
+                .model(gemini("gemini-2.0-flash-exp"))
+                .build()
+                .is_ok()
+        );
+        assert!(
+            LLMAgent::builder()
+                .name("test-agent")
+                .model(gemini("some-future-model"))
+                .build()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_retryable_llm_errors() {
+        let retryable = [
+            "OpenAI API error 429 Too Many Requests: slow down",
+            "OpenAI API error 503 Service Unavailable: overloaded",
+            "Gemini API error: Internal error (code: 500)",
+            "Gemini API error: Bad gateway (code: 502)",
+        ];
+        for message in retryable {
+            assert!(is_retryable_llm_error(&Error::LLMError(message.into())));
+        }
+
+        let not_retryable = [
+            "OpenAI API error 400 Bad Request: invalid model",
+            "Gemini API error: API key not valid (code: 4290)",
+            "Failed to parse response: expected value",
+        ];
+        for message in not_retryable {
+            assert!(!is_retryable_llm_error(&Error::LLMError(message.into())));
+        }
+        assert!(!is_retryable_llm_error(&Error::Config("500".into())));
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_llm_errors() {
+        let model = Arc::new(FlakyLLM::new(
+            2,
+            "OpenAI API error 503 Service Unavailable: overloaded",
+        ));
+
+        let agent = LLMAgent::builder()
+            .name("retry-agent")
+            .model(model.clone())
+            .max_retries(3)
+            .retry_backoff(Duration::from_millis(1))
+            .build()
+            .unwrap();
+
+        let events = collect_events(&agent).await;
+
+        assert_eq!(model.calls(), 3);
+        assert_eq!(events.len(), 1);
+        let event = events[0].as_ref().unwrap();
+        assert!(event.is_final_response());
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_retries() {
+        let model = Arc::new(FlakyLLM::new(
+            5,
+            "Gemini API error: Resource exhausted (code: 429)",
+        ));
+
+        let agent = LLMAgent::builder()
+            .name("retry-agent")
+            .model(model.clone())
+            .max_retries(2)
+            .retry_backoff(Duration::from_millis(1))
+            .build()
+            .unwrap();
+
+        let events = collect_events(&agent).await;
+
+        assert_eq!(model.calls(), 3);
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], Err(Error::LLMError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_non_retryable_error_propagates_immediately() {
+        let model = Arc::new(FlakyLLM::new(
+            1,
+            "OpenAI API error 401 Unauthorized: invalid key",
+        ));
+
+        let agent = LLMAgent::builder()
+            .name("retry-agent")
+            .model(model.clone())
+            .max_retries(3)
+            .retry_backoff(Duration::from_millis(1))
+            .build()
+            .unwrap();
+
+        let events = collect_events(&agent).await;
+
+        assert_eq!(model.calls(), 1);
+        assert!(matches!(events[0], Err(Error::LLMError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_max_iterations_stops_tool_loop() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+        use zdk_core::ToolResponse;
+
+        let model = Arc::new(LoopingLLM::new("lookup"));
+
+        let executions = Arc::new(AtomicU32::new(0));
+        let counter = executions.clone();
+        let tool = zdk_tool::FunctionTool::builder()
+            .name("lookup")
+            .description("Always needs another lookup")
+            .execute(move |_ctx, _params| {
+                let counter = counter.clone();
+                async move {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    Ok(ToolResponse::new(serde_json::json!({"status": "incomplete"})))
+                }
+            })
+            .build()
+            .unwrap();
+
+        let agent = LLMAgent::builder()
+            .name("looping-agent")
+            .model(model.clone())
+            .tool(Arc::new(tool))
+            .max_iterations(3)
+            .build()
+            .unwrap();
+
+        let events = collect_events(&agent).await;
+
+        assert_eq!(model.calls(), 3);
+        assert_eq!(executions.load(Ordering::SeqCst), 3);
+
+        let last = events.last().unwrap().as_ref().unwrap();
+        assert_eq!(last.error_code, "MAX_ITERATIONS");
+        assert!(last.turn_complete);
+    }
+
+    #[tokio::test]
+    async fn test_max_tool_response_bytes_truncates_response() {
+        use zdk_core::ToolResponse;
+
+        let tool = zdk_tool::FunctionTool::builder()
+            .name("lookup")
+            .description("Returns a large payload")
+            .execute(|_ctx, _params| async move {
+                Ok(ToolResponse::new(serde_json::json!({"data": "x".repeat(1000)})))
+            })
+            .build()
+            .unwrap();
+
+        let agent = LLMAgent::builder()
+            .name("looping-agent")
+            .model(Arc::new(LoopingLLM::new("lookup")))
+            .tool(Arc::new(tool))
+            .max_iterations(1)
+            .max_tool_response_bytes(64)
+            .build()
+            .unwrap();
+
+        let events = collect_events(&agent).await;
+
+        let response = events
+            .iter()
+            .filter_map(|e| e.as_ref().ok()?.content.as_ref())
+            .flat_map(|c| c.parts.iter())
+            .find_map(|p| match p {
+                Part::FunctionResponse { function_response } => {
+                    Some(function_response.response.clone())
+                }
+                _ => None,
+            })
+            .expect("tool response event");
+        assert_eq!(response["truncated"], true);
+        assert_eq!(response["original_bytes"], 1011);
+        assert_eq!(response["preview"].as_str().unwrap().len(), 64);
+    }
+
+    #[tokio::test]
+    async fn test_tool_error_kind_in_event() {
+        use zdk_core::{ToolErrorKind, ToolResponse};
+
+        let tool = zdk_tool::FunctionTool::builder()
+            .name("lookup")
+            .description("Can't find anything")
+            .execute(|_ctx, _params| async move {
+                Ok(ToolResponse::error(
+                    ToolErrorKind::NotFound,
+                    "no such record",
+                ))
+            })
+            .build()
+            .unwrap();
+
+        let agent = LLMAgent::builder()
+            .name("looping-agent")
+            .model(Arc::new(LoopingLLM::new("lookup")))
+            .tool(Arc::new(tool))
+            .max_iterations(1)
+            .build()
+            .unwrap();
+
+        let events = collect_events(&agent).await;
+
+        let tool_event = events
+            .iter()
+            .filter_map(|e| e.as_ref().ok())
+            .find(|e| e.content.as_ref().is_some_and(|c| c.role == "function"))
+            .expect("tool response event");
+        assert_eq!(tool_event.error_code, "TOOL_ERROR_NOT_FOUND");
+        assert!(tool_event.error_message.contains("no such record"));
+    }
+
+    fn status_tool(status: &'static str) -> Arc<dyn zdk_core::Tool> {
+        Arc::new(
+            zdk_tool::FunctionTool::builder()
+                .name("lookup")
+                .description("Reports where it came from")
+                .execute(move |_ctx, _params| async move {
+                    Ok(zdk_core::ToolResponse::new(serde_json::json!({ "status": status })))
+                })
+                .build()
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_duplicate_tool_names_fail_build() {
+        let result = LLMAgent::builder()
+            .name("test-agent")
+            .model(Arc::new(MockLLM::new()))
+            .tool(status_tool("first"))
+            .tools(vec![status_tool("second"), status_tool("third")])
+            .build();
+
+        let Err(err) = result else {
+            panic!("Expected duplicate tool names to be rejected");
+        };
+        assert!(matches!(err, Error::Config(_)));
+        assert!(err.to_string().contains("Duplicate tool names: lookup"));
+    }
+
+    #[tokio::test]
+    async fn test_registered_tool_wins_over_toolset() {
+        struct StaticToolset(Arc<dyn zdk_core::Tool>);
+
+        #[async_trait::async_trait]
+        impl zdk_core::Toolset for StaticToolset {
+            fn name(&self) -> &str {
+                "static"
+            }
+
+            async fn get_tools(
+                &self,
+                _ctx: &dyn zdk_core::InvocationContext,
+            ) -> Result<Vec<Arc<dyn zdk_core::Tool>>> {
+                Ok(vec![self.0.clone()])
+            }
+        }
+
+        let agent = LLMAgent::builder()
+            .name("looping-agent")
+            .model(Arc::new(LoopingLLM::new("lookup")))
+            .toolset(Arc::new(StaticToolset(status_tool("toolset"))))
+            .tool(status_tool("registered"))
+            .max_iterations(1)
+            .build()
+            .unwrap();
+
+        let events = collect_events(&agent).await;
+
+        let response = events
+            .iter()
+            .filter_map(|e| e.as_ref().ok()?.content.as_ref())
+            .flat_map(|c| &c.parts)
+            .find_map(|part| match part {
+                Part::FunctionResponse { function_response } => {
+                    Some(function_response.response.clone())
+                }
+                _ => None,
+            })
+            .expect("tool response event");
+        assert_eq!(response["status"], "registered");
+    }
+
+    #[tokio::test]
+    async fn test_failed_tool_reported_to_model() {
+        let tool = zdk_tool::FunctionTool::builder()
+            .name("lookup")
+            .description("Always fails")
+            .execute(|_ctx, _params| async move {
+                Err(Error::Other(anyhow::anyhow!("database unreachable")))
+            })
+            .build()
+            .unwrap();
+
+        let model = Arc::new(LoopingLLM::new("lookup"));
+        let agent = LLMAgent::builder()
+            .name("looping-agent")
+            .model(model.clone())
+            .tool(Arc::new(tool))
+            .max_iterations(2)
+            .build()
+            .unwrap();
+
+        collect_events(&agent).await;
+
+        // The retry sees the failure instead of a missing response
+        let requests = model.requests();
+        assert_eq!(requests.len(), 2);
+        let last = requests[1].contents.last().unwrap();
+        assert_eq!(last.role, "function");
+        let Part::FunctionResponse { function_response } = &last.parts[0] else {
+            panic!("Expected a function response");
+        };
+        assert_eq!(function_response.name, "lookup");
+        assert_eq!(function_response.response["kind"], "internal");
+        assert!(
+            function_response.response["error"]
+                .as_str()
+                .unwrap()
+                .contains("database unreachable")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tool_errors_answer_their_own_call() {
+        let tool = zdk_tool::FunctionTool::builder()
+            .name("lookup")
+            .description("Always fails")
+            .execute(|_ctx, _params| async move {
+                Err(Error::Other(anyhow::anyhow!("database unreachable")))
+            })
+            .build()
+            .unwrap();
+        let tool: Arc<dyn zdk_core::Tool> = Arc::new(tool);
+
+        // Two calls to the same tool in one turn, plus one to a missing tool
+        let model = Arc::new(LoopingLLM::new("lookup").with_calls_per_turn(2));
+        let missing = Arc::new(LoopingLLM::new("missing"));
+        for (model, expected) in [
+            (model, vec!["call_0_0", "call_0_1"]),
+            (missing, vec!["call_0_0"]),
+        ] {
+            let agent = LLMAgent::builder()
+                .name("looping-agent")
+                .model(model.clone())
+                .tool(tool.clone())
+                .max_iterations(2)
+                .build()
+                .unwrap();
+            collect_events(&agent).await;
+
+            let requests = model.requests();
+            let ids: Vec<_> = requests[1]
+                .contents
+                .iter()
+                .filter(|content| content.role == "function")
+                .flat_map(|content| &content.parts)
+                .map(|part| match part {
+                    Part::FunctionResponse { function_response } => {
+                        function_response.id.clone().unwrap()
+                    }
+                    other => panic!("Expected a function response, got {:?}", other),
+                })
+                .collect();
+            assert_eq!(ids, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_before_tool_vetoes_call() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+        use zdk_core::{ToolErrorKind, ToolResponse};
+
+        let executions = Arc::new(AtomicU32::new(0));
+        let counter = executions.clone();
+        let tool = zdk_tool::FunctionTool::builder()
+            .name("lookup")
+            .description("Looks something up")
+            .execute(move |_ctx, _params| {
+                let counter = counter.clone();
+                async move {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    Ok(ToolResponse::new(serde_json::json!({"status": "ran"})))
+                }
+            })
+            .build()
+            .unwrap();
+
+        let audited = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let audit = audited.clone();
+        let agent = LLMAgent::builder()
+            .name("guarded")
+            .model(Arc::new(LoopingLLM::new("lookup")))
+            .tool(Arc::new(tool))
+            .max_iterations(1)
+            .before_tool(Arc::new(|name, _args| {
+                (name == "lookup").then(|| {
+                    ToolResponse::error(ToolErrorKind::InvalidInput, "lookup is not allowed")
+                })
+            }))
+            .after_tool(Arc::new(move |name, response| {
+                audit
+                    .lock()
+                    .unwrap()
+                    .push((name.to_string(), response.result.clone()));
+            }))
+            .build()
+            .unwrap();
+
+        let events = collect_events(&agent).await;
+
+        // The tool never ran; the callback's response stood in for it
+        assert_eq!(executions.load(Ordering::SeqCst), 0);
+        let tool_event = events
+            .iter()
+            .filter_map(|e| e.as_ref().ok())
+            .find(|e| e.content.as_ref().is_some_and(|c| c.role == "function"))
+            .expect("tool response event");
+        assert_eq!(tool_event.error_code, "TOOL_ERROR_INVALID_INPUT");
+        assert!(tool_event.error_message.contains("lookup is not allowed"));
+
+        let audited = audited.lock().unwrap();
+        assert_eq!(audited.len(), 1);
+        assert_eq!(audited[0].0, "lookup");
+        assert_eq!(audited[0].1["error"], "lookup is not allowed");
+    }
+
+    #[tokio::test]
+    async fn test_before_tool_passes_through() {
+        use zdk_core::ToolResponse;
+
+        let tool = zdk_tool::FunctionTool::builder()
+            .name("lookup")
+            .description("Looks something up")
+            .execute(|_ctx, _params| async move {
+                Ok(ToolResponse::new(serde_json::json!({"status": "ran"})))
+            })
+            .build()
+            .unwrap();
+
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let before = seen.clone();
+        let after = seen.clone();
+        let agent = LLMAgent::builder()
+            .name("audited")
+            .model(Arc::new(LoopingLLM::new("lookup")))
+            .tool(Arc::new(tool))
+            .max_iterations(1)
+            .before_tool(Arc::new(move |name, _args| {
+                before.lock().unwrap().push(format!("before {}", name));
+                None
+            }))
+            .after_tool(Arc::new(move |name, response| {
+                after
+                    .lock()
+                    .unwrap()
+                    .push(format!("after {} {}", name, response.result["status"]));
+            }))
+            .build()
+            .unwrap();
+
+        collect_events(&agent).await;
+
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![
+                "before lookup".to_string(),
+                "after lookup \"ran\"".to_string()
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_function_call_event_precedes_response() {
+        use zdk_core::ToolResponse;
+
+        let tool = zdk_tool::FunctionTool::builder()
+            .name("lookup")
+            .description("Looks something up")
+            .execute(|_ctx, _params| async move {
+                Ok(ToolResponse::new(serde_json::json!({"status": "ok"})))
+            })
+            .build()
+            .unwrap();
+
+        let agent = LLMAgent::builder()
+            .name("looping-agent")
+            .model(Arc::new(LoopingLLM::new("lookup")))
+            .tool(Arc::new(tool))
+            .max_iterations(2)
+            .build()
+            .unwrap();
+
+        let events: Vec<_> = collect_events(&agent)
+            .await
+            .into_iter()
+            .map(|e| e.unwrap())
+            .collect();
+
+        let has_part = |event: &zdk_core::Event, call: bool| {
+            event.content.as_ref().is_some_and(|c| {
+                c.parts.iter().any(|p| match p {
+                    Part::FunctionCall { .. } => call,
+                    Part::FunctionResponse { .. } => !call,
+                    _ => false,
+                })
+            })
+        };
+
+        let mut responses = 0;
+        let mut pending_call = false;
+        for event in &events {
+            if has_part(event, true) {
+                assert_eq!(event.author, "looping-agent");
+                assert!(!event.turn_complete);
+                assert!(!event.partial);
+                pending_call = true;
+            } else if has_part(event, false) {
+                assert!(pending_call, "function response without a preceding call");
+                pending_call = false;
+                responses += 1;
+            }
+        }
+        assert_eq!(responses, 2);
+
+        // Each call is announced exactly once
+        assert_eq!(events.iter().filter(|e| has_part(e, true)).count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_no_max_iterations_event_when_model_finishes() {
+        let agent = LLMAgent::builder()
+            .name("simple-agent")
+            .model(Arc::new(MockLLM::new()))
+            .max_iterations(1)
+            .build()
+            .unwrap();
+
+        let events = collect_events(&agent).await;
+
+        assert_eq!(events.len(), 1);
+        assert!(events[0].as_ref().unwrap().error_code.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_system_instruction_sent_to_model() {
+        let model = Arc::new(RecordingLLM::new());
+
+        let agent = LLMAgent::builder()
+            .name("pirate")
+            .model(model.clone())
+            .system_instruction("You are a pirate. Answer like one.")
+            .build()
+            .unwrap();
+
+        collect_events(&agent).await;
+
+        let requests = model.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(
+            requests[0].system_instruction.as_deref(),
+            Some("You are a pirate. Answer like one.")
+        );
+        // The system prompt is not duplicated into the conversation
+        assert_eq!(requests[0].contents.len(), 1);
+        assert_eq!(requests[0].contents[0].role, "user");
+    }
+
+    #[tokio::test]
+    async fn test_cut_short_answers_surface_event() {
+        use zdk_core::FinishReason;
+
+        for (reason, code) in [
+            (FinishReason::Safety, "SAFETY"),
+            (FinishReason::Length, "MAX_TOKENS"),
+        ] {
+            let agent = LLMAgent::builder()
+                .name("writer")
+                .model(Arc::new(
+                    MockLLM::with_response("Partial ans").finishing_with(reason),
+                ))
+                .build()
+                .unwrap();
+
+            let events: Vec<Event> = collect_events(&agent)
+                .await
+                .into_iter()
+                .map(|e| e.unwrap())
+                .collect();
+
+            // The text so far, then a separate event naming why it stopped
+            assert_eq!(events.len(), 2);
+            assert_eq!(events[0].content.as_ref().unwrap().text(), "Partial ans");
+            assert!(events[0].error_code.is_empty());
+            assert_eq!(events[1].error_code, code);
+            assert!(events[1].content.is_none());
+        }
+
+        // A normal stop adds nothing
+        let agent = LLMAgent::builder()
+            .name("writer")
+            .model(Arc::new(MockLLM::new()))
+            .build()
+            .unwrap();
+        assert_eq!(collect_events(&agent).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_trim_context_fits_model_window() {
+        let history: Vec<Content> = (0..10)
+            .map(|i| Content::new_user_text(format!("{} {}", i, "x".repeat(100))))
+            .collect();
+        let ctx = || Arc::new(MockContext::new("Latest question").with_history(history.clone()));
+
+        let model = Arc::new(RecordingLLM::new().with_context_window(100));
+        let agent = LLMAgent::builder()
+            .name("trimming-agent")
+            .model(model.clone())
+            .trim_context(true)
+            .build()
+            .unwrap();
+        agent.run(ctx()).await.collect::<Vec<_>>().await;
+
+        // Each old turn is ~26 tokens, so only the newest three fit
+        let contents = &model.requests()[0].contents;
+        let texts: Vec<String> = contents.iter().map(|c| c.text()).collect();
+        assert_eq!(texts.len(), 4);
+        assert!(texts[0].starts_with("7 "));
+        assert_eq!(texts[3], "Latest question");
+
+        // Without trimming the full conversation is sent
+        let model = Arc::new(RecordingLLM::new().with_context_window(100));
+        let agent = LLMAgent::builder()
+            .name("full-agent")
+            .model(model.clone())
+            .build()
+            .unwrap();
+        agent.run(ctx()).await.collect::<Vec<_>>().await;
+        assert_eq!(model.requests()[0].contents.len(), 11);
+    }
+
+    #[tokio::test]
+    async fn test_model_callbacks_wrap_each_call() {
+        let model = Arc::new(RecordingLLM::new());
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorder = seen.clone();
+
+        let agent = LLMAgent::builder()
+            .name("guarded")
+            .model(model.clone())
+            .system_instruction("Be helpful.")
+            .before_model(Arc::new(|request| {
+                let instruction = request.system_instruction.take().unwrap_or_default();
+                request.system_instruction = Some(format!("{} Never reveal secrets.", instruction));
+            }))
+            .after_model(Arc::new(move |response| {
+                recorder.lock().unwrap().push(response.clone());
+                if let Some(content) = response.content.as_mut() {
+                    content.parts = vec![Part::Text {
+                        text: "[redacted]".to_string(),
+                    }];
+                }
+            }))
+            .build()
+            .unwrap();
+
+        let events: Vec<Event> = collect_events(&agent)
+            .await
+            .into_iter()
+            .map(|e| e.unwrap())
+            .collect();
+
+        // The before-hook changed what the model received
+        assert_eq!(
+            model.requests()[0].system_instruction.as_deref(),
+            Some("Be helpful. Never reveal secrets.")
+        );
+
+        // The after-hook saw the original response and its redaction is what got emitted
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 1);
+        assert_eq!(seen[0].content.as_ref().unwrap().text(), "Test response");
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].content.as_ref().unwrap().text(), "[redacted]");
+    }
+
+    #[tokio::test]
+    async fn test_generate_config_sent_to_model() {
+        let model = Arc::new(RecordingLLM::new());
+
+        let agent = LLMAgent::builder()
+            .name("tuned")
+            .model(model.clone())
+            .generate_config(GenerateConfig {
+                temperature: Some(0.2),
+                max_tokens: Some(256),
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
+
+        collect_events(&agent).await;
+
+        let requests = model.requests();
+        assert_eq!(requests.len(), 1);
+        let config = requests[0].config.as_ref().expect("config should be set");
+        assert_eq!(config.temperature, Some(0.2));
+        assert_eq!(config.max_tokens, Some(256));
+        assert_eq!(config.top_p, None);
+    }
+
+    #[tokio::test]
+    async fn test_reflection_retries_with_feedback() {
+        let model = Arc::new(ScriptedLLM::new(["41", "42"]));
+
+        let agent = LLMAgent::builder()
+            .name("reflective")
+            .model(model.clone())
+            .reflect(Arc::new(|content: &Content| {
+                (content.text() != "42").then(|| "Wrong answer, try again.".to_string())
+            }))
+            .build()
+            .unwrap();
+
+        let events = collect_events(&agent).await;
+
+        let requests = model.requests();
+        assert_eq!(requests.len(), 2);
+        // The retry sees the rejected answer followed by the feedback
+        let retry = &requests[1].contents;
+        assert_eq!(retry.len(), 3);
+        assert_eq!(retry[1].text(), "41");
+        assert_eq!(retry[2].role, "user");
+        assert_eq!(retry[2].text(), "Wrong answer, try again.");
+
+        let last = events.last().unwrap().as_ref().unwrap();
+        assert_eq!(last.content.as_ref().unwrap().text(), "42");
+        assert!(events.iter().all(|e| e.is_ok()));
+    }
+
+    #[tokio::test]
+    async fn test_reflection_capped() {
+        let model = Arc::new(ScriptedLLM::new(["nope"]));
+
+        let agent = LLMAgent::builder()
+            .name("stubborn")
+            .model(model.clone())
+            .reflect(Arc::new(|_: &Content| Some("Try again.".to_string())))
+            .max_reflections(2)
+            .build()
+            .unwrap();
+
+        collect_events(&agent).await;
+
+        // One initial answer plus two rejected retries
+        assert_eq!(model.requests().len(), 3);
+    }
+
+    /// Run an agent with a weather tool against `model`, returning the final text
+    async fn tool_round_trip(model: Arc<dyn zdk_core::LLM>) -> String {
+        let tool = zdk_tool::FunctionTool::builder()
+            .name("get_weather")
+            .description("Get the weather for a city")
+            .execute(|_ctx, _params| async move {
+                Ok(zdk_core::ToolResponse::new(serde_json::json!({ "temp": 21 })))
+            })
+            .build()
+            .unwrap();
+
+        let agent = LLMAgent::builder()
+            .name("weather-agent")
+            .model(model)
+            .tool(Arc::new(tool))
+            .max_iterations(3)
+            .build()
+            .unwrap();
+
+        collect_events(&agent)
+            .await
+            .into_iter()
+            .filter_map(|event| event.unwrap().content)
+            .flat_map(|content| content.parts)
+            .filter_map(|part| match part {
+                Part::Text { text } => Some(text),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_tool_round_trip_keeps_call_ids() {
+        use serde_json::json;
+        use zdk_core::providers::{
+            AnthropicProvider, GeminiAuth, GeminiProvider, OpenAIProvider,
+            anthropic::AnthropicConfig, gemini::GeminiConfig, openai::OpenAIConfig,
+        };
+
+        let sse = |events: Vec<serde_json::Value>| {
+            events
+                .into_iter()
+                .map(|event| format!("data: {}\n\n", event))
+                .collect::<String>()
+        };
+        let mut server = mockito::Server::new_async().await;
+
+        // OpenAI: the tool message must answer the call's own ID
+        let openai_chunk = |delta: serde_json::Value, finish_reason: serde_json::Value| {
+            json!({
+                "id": "chatcmpl-1",
+                "object": "chat.completion.chunk",
+                "created": 0,
+                "model": "gpt-test",
+                "choices": [{ "index": 0, "delta": delta, "finish_reason": finish_reason }]
+            })
+        };
+        let openai_call = server
+            .mock("POST", "/openai/chat/completions")
+            .expect(1)
+            .with_body(
+                sse(vec![
+                    openai_chunk(
+                        json!({ "role": "assistant", "tool_calls": [{
+                            "index": 0,
+                            "id": "call_abc123",
+                            "type": "function",
+                            "function": { "name": "get_weather", "arguments": "{\"city\":\"Paris\"}" }
+                        }] }),
+                        json!(null),
+                    ),
+                    openai_chunk(json!({}), json!("tool_calls")),
+                ]) + "data: [DONE]\n\n",
+            )
+            .create_async()
+            .await;
+        let openai_answer = server
+            .mock("POST", "/openai/chat/completions")
+            .match_body(mockito::Matcher::PartialJson(json!({
+                "messages": [
+                    { "role": "user" },
+                    { "role": "assistant", "tool_calls": [{ "id": "call_abc123" }] },
+                    { "role": "tool", "tool_call_id": "call_abc123" }
+                ]
+            })))
+            .expect(1)
+            .with_body(
+                sse(vec![
+                    openai_chunk(
+                        json!({ "role": "assistant", "content": "It is 21 degrees." }),
+                        json!(null),
+                    ),
+                    openai_chunk(json!({}), json!("stop")),
+                ]) + "data: [DONE]\n\n",
+            )
+            .create_async()
+            .await;
+
+        // Anthropic: the tool result must reference the tool use ID
+        let anthropic_message = |blocks: Vec<serde_json::Value>, stop_reason: &str| {
+            let mut events = vec![json!({
+                "type": "message_start",
+                "message": {
+                    "id": "msg_1",
+                    "type": "message",
+                    "role": "assistant",
+                    "model": "claude-3-5-haiku-latest",
+                    "content": [],
+                    "stop_reason": null,
+                    "usage": { "input_tokens": 10, "output_tokens": 1 }
+                }
+            })];
+            for (index, (start, delta)) in blocks.chunks(2).map(|b| (&b[0], &b[1])).enumerate() {
+                events.push(json!({ "type": "content_block_start", "index": index, "content_block": start }));
+                events
+                    .push(json!({ "type": "content_block_delta", "index": index, "delta": delta }));
+                events.push(json!({ "type": "content_block_stop", "index": index }));
+            }
+            events.push(json!({
+                "type": "message_delta",
+                "delta": { "stop_reason": stop_reason, "stop_sequence": null },
+                "usage": { "output_tokens": 5 }
+            }));
+            events.push(json!({ "type": "message_stop" }));
+            sse(events)
+        };
+        let anthropic_call = server
+            .mock("POST", "/anthropic/messages")
+            .expect(1)
+            .with_header("content-type", "text/event-stream")
+            .with_body(anthropic_message(
+                vec![
+                    json!({ "type": "tool_use", "id": "toolu_01XyZ", "name": "get_weather", "input": {} }),
+                    json!({ "type": "input_json_delta", "partial_json": "{\"city\": \"Paris\"}" }),
+                ],
+                "tool_use",
+            ))
+            .create_async()
+            .await;
+        let anthropic_answer = server
+            .mock("POST", "/anthropic/messages")
+            .match_body(mockito::Matcher::PartialJson(json!({
+                "messages": [
+                    { "role": "user" },
+                    { "role": "assistant", "content": [{ "type": "tool_use", "id": "toolu_01XyZ" }] },
+                    { "role": "user", "content": [{ "type": "tool_result", "tool_use_id": "toolu_01XyZ" }] }
+                ]
+            })))
+            .expect(1)
+            .with_header("content-type", "text/event-stream")
+            .with_body(anthropic_message(
+                vec![
+                    json!({ "type": "text", "text": "" }),
+                    json!({ "type": "text_delta", "text": "It is 21 degrees." }),
+                ],
+                "end_turn",
+            ))
+            .create_async()
+            .await;
+
+        // Gemini: IDs are left out of the request entirely
+        let gemini_call = server
+            .mock("POST", "/gemini/gemini-test:streamGenerateContent")
+            .match_query(mockito::Matcher::Any)
+            .expect(1)
+            .with_body(
+                json!([{
+                    "candidates": [{
+                        "content": {
+                            "role": "model",
+                            "parts": [{
+                                "functionCall": { "name": "get_weather", "args": { "city": "Paris" }, "id": "gem-call-1" }
+                            }]
+                        },
+                        "finishReason": "STOP"
+                    }]
+                }])
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let gemini_answer = server
+            .mock("POST", "/gemini/gemini-test:streamGenerateContent")
+            .match_query(mockito::Matcher::Any)
+            .match_request(|request| {
+                let body = request.utf8_lossy_body().unwrap();
+                body.contains("functionResponse") && !body.contains("gem-call-1")
+            })
+            .expect(1)
+            .with_body(
+                json!([{
+                    "candidates": [{
+                        "content": { "role": "model", "parts": [{ "text": "It is 21 degrees." }] },
+                        "finishReason": "STOP"
+                    }]
+                }])
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let openai = OpenAIProvider::new(
+            "key".to_string(),
+            OpenAIConfig::with_base_url("gpt-test".to_string(), format!("{}/openai", server.url())),
+        );
+        let anthropic = AnthropicProvider::new(
+            "key".to_string(),
+            AnthropicConfig::with_base_url(
+                "claude-3-5-haiku-latest".to_string(),
+                format!("{}/anthropic", server.url()),
+            ),
+        );
+        let mut gemini_config = GeminiConfig::default_api_key("gemini-test".to_string());
+        gemini_config.base_url = format!("{}/gemini", server.url());
+        let gemini = GeminiProvider::new(GeminiAuth::ApiKey("key".to_string()), gemini_config);
+
+        let models: Vec<Arc<dyn zdk_core::LLM>> =
+            vec![Arc::new(openai), Arc::new(anthropic), Arc::new(gemini)];
+        for model in models {
+            let name = model.name().to_string();
+            assert_eq!(
+                tool_round_trip(model).await,
+                "It is 21 degrees.",
+                "{}",
+                name
+            );
+        }
+
+        for mock in [
+            openai_call,
+            openai_answer,
+            anthropic_call,
+            anthropic_answer,
+            gemini_call,
+            gemini_answer,
+        ] {
+            mock.assert_async().await;
+        }
+    }
 }
@@ -1,26 +1,67 @@
 use crate::builder::LLMAgentBuilder;
-use crate::utils::load_toolsets;
+use crate::utils::{
+    RunTimer, is_retryable_llm_error, limit_tool_response, load_toolsets, tool_error_response,
+};
 use async_stream::stream;
 use async_trait::async_trait;
 use futures::stream::{Stream, StreamExt};
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use zdk_core::{
-    Agent, Content, Event, FunctionCall, InvocationContext, LLM, LLMRequest, Part, Result, Tool,
-    Toolset,
+    Agent, Content, ContextTrimmer, Event, FinishReason, FunctionCall, GenerateConfig,
+    InvocationContext, LLM, LLMRequest, LLMResponse, Part, Result, TokenUsage, Tool, ToolErrorKind,
+    ToolResponse, Toolset,
 };
+use zdk_core::providers::retry_delay;
 use zdk_telemetry::{LLMSpanAttributes, trace_llm_call};
 
+pub use zdk_core::providers::DEFAULT_RETRY_BACKOFF;
+
+/// Default number of model calls allowed per invocation
+pub const DEFAULT_MAX_ITERATIONS: usize = 10;
+
+/// Default number of times a reflector may reject the agent's answer
+pub const DEFAULT_MAX_REFLECTIONS: usize = 3;
+
+/// Checks a final answer, returning feedback to retry with or `None` to accept it
+pub type Reflector = Arc<dyn Fn(&Content) -> Option<String> + Send + Sync>;
+
+/// Runs on every request before it is sent to the model
+pub type BeforeModelCallback = Arc<dyn Fn(&mut LLMRequest) + Send + Sync>;
+
+/// Runs on every model response before it becomes an event
+pub type AfterModelCallback = Arc<dyn Fn(&mut LLMResponse) + Send + Sync>;
+
+/// Runs before a tool call; returning `Some` answers the call without running the tool
+pub type BeforeToolCallback =
+    Arc<dyn Fn(&str, &serde_json::Value) -> Option<ToolResponse> + Send + Sync>;
+
+/// Runs after a tool call with the response it produced
+pub type AfterToolCallback = Arc<dyn Fn(&str, &ToolResponse) + Send + Sync>;
+
 pub struct LLMAgent {
     pub(crate) name: Arc<str>,
     pub(crate) description: Arc<str>,
     pub(crate) model: Arc<dyn LLM>,
-    #[allow(dead_code)]
     pub(crate) system_instruction: Option<String>,
+    pub(crate) generate_config: Option<GenerateConfig>,
     #[allow(dead_code)]
     pub(crate) sub_agents: Vec<Arc<dyn Agent>>,
     pub(crate) tools: HashMap<String, Arc<dyn Tool>>,
     pub(crate) toolsets: Vec<Arc<dyn Toolset>>,
+    pub(crate) max_retries: u32,
+    pub(crate) retry_backoff: Duration,
+    pub(crate) tool_timeout: Option<Duration>,
+    pub(crate) max_iterations: Option<usize>,
+    pub(crate) max_tool_response_bytes: Option<usize>,
+    pub(crate) trim_context: bool,
+    pub(crate) reflector: Option<Reflector>,
+    pub(crate) max_reflections: usize,
+    pub(crate) before_model: Vec<BeforeModelCallback>,
+    pub(crate) after_model: Vec<AfterModelCallback>,
+    pub(crate) before_tool: Vec<BeforeToolCallback>,
+    pub(crate) after_tool: Vec<AfterToolCallback>,
 }
 
 impl LLMAgent {
@@ -39,9 +80,22 @@ impl LLMAgent {
             description: Arc::from(description),
             model,
             system_instruction,
+            generate_config: None,
             sub_agents: Vec::new(),
             tools: HashMap::new(),
             toolsets: Vec::new(),
+            max_retries: 0,
+            retry_backoff: DEFAULT_RETRY_BACKOFF,
+            tool_timeout: None,
+            max_iterations: None,
+            max_tool_response_bytes: None,
+            trim_context: false,
+            reflector: None,
+            max_reflections: DEFAULT_MAX_REFLECTIONS,
+            before_model: Vec::new(),
+            after_model: Vec::new(),
+            before_tool: Vec::new(),
+            after_tool: Vec::new(),
         }
     }
 }
@@ -66,15 +120,65 @@ impl Agent for LLMAgent {
         let mut tools = self.tools.clone();
         let toolsets = self.toolsets.clone();
         let ctx_clone = ctx.clone();
+        let execute_tools = ctx.run_config().execute_tools;
+        let max_retries = self.max_retries;
+        let retry_backoff = self.retry_backoff;
+        let system_instruction = self.system_instruction.clone();
+
+        // Agent settings take precedence over runner-level defaults
+        let defaults = ctx.runner_defaults();
+        let tool_timeout = self.tool_timeout.or(defaults.tool_timeout);
+        let max_iterations = self
+            .max_iterations
+            .or(defaults.max_tool_iterations)
+            .unwrap_or(DEFAULT_MAX_ITERATIONS);
+        let max_tool_response_bytes = self
+            .max_tool_response_bytes
+            .or(defaults.max_tool_response_bytes);
+        let generate_config = self
+            .generate_config
+            .clone()
+            .or_else(|| defaults.generate_config.clone());
+        // Leave room in the context window for the response
+        let trimmer = self
+            .model
+            .context_window()
+            .filter(|_| self.trim_context)
+            .map(|window| {
+                let reserved = generate_config
+                    .as_ref()
+                    .and_then(|config| config.max_tokens)
+                    .unwrap_or(0) as usize;
+                ContextTrimmer::new(window.saturating_sub(reserved))
+            });
+        let reflector = self.reflector.clone();
+        let max_reflections = self.max_reflections;
+        let before_model = self.before_model.clone();
+        let after_model = self.after_model.clone();
+        let before_tool = self.before_tool.clone();
+        let after_tool = self.after_tool.clone();
 
         Box::new(Box::pin(stream! {
+            let _run_timer = RunTimer::start(agent_name.clone());
+
             // Load tools from toolsets in parallel for better performance
             let loaded_tools = load_toolsets(&toolsets, &ctx_clone, &invocation_id).await;
-            tools.extend(loaded_tools);
+            for (name, tool) in loaded_tools {
+                // Directly registered tools win over toolset tools of the same name
+                if tools.contains_key(&name) {
+                    tracing::warn!(
+                        invocation_id = %invocation_id,
+                        tool_name = %name,
+                        "Toolset tool shadowed by a registered tool with the same name"
+                    );
+                    continue;
+                }
+                tools.insert(name, tool);
+            }
 
             // Build LLM request from context
-            // Pre-allocate for typical conversation size (2-10 turns)
-            let mut conversation = Vec::with_capacity(10);
+            // Start from prior turns (possibly compacted by the runner)
+            let mut conversation = ctx.history().to_vec();
 
             // Add user content if available
             if let Some(user_content) = ctx.user_content() {
@@ -82,6 +186,9 @@ impl Agent for LLMAgent {
             }
 
             let session_id = ctx.session_id().to_string();
+            // Session state shared by this invocation's tool calls
+            let state = zdk_tool::SharedState::new(std::sync::RwLock::new(ctx.state()));
+            let artifacts = ctx.artifacts();
 
             tracing::info!(
                 invocation_id = %invocation_id,
@@ -90,18 +197,26 @@ impl Agent for LLMAgent {
                 "Starting LLM agent execution"
             );
 
-            // Tool execution loop
-            let max_iterations = 10; // Prevent infinite loops
+            let mut reflections = 0;
+
+            // Tool execution loop, bounded to prevent infinite loops
             for iteration in 0..max_iterations {
                 // Convert tools HashMap to Vec for LLMRequest
                 let tool_list: Vec<Arc<dyn Tool>> = tools.values().cloned().collect();
 
-                let request = LLMRequest {
+                let mut request = LLMRequest {
                     model: model.name().to_string(),
+                    system_instruction: system_instruction.clone(),
                     contents: conversation.clone(),
-                    config: None,
+                    config: generate_config.clone(),
                     tools: tool_list,
                 };
+                for callback in &before_model {
+                    callback(&mut request);
+                }
+                if let Some(trimmer) = &trimmer {
+                    trimmer.trim_request(&mut request);
+                }
 
                 tracing::debug!(
                     invocation_id = %invocation_id,
@@ -111,62 +226,113 @@ impl Agent for LLMAgent {
                     "Calling LLM"
                 );
 
-                let mut llm_stream = model.generate_content(request.clone(), true).await;
                 let mut accumulated_content: Option<Content> = None;
                 let mut function_calls: Vec<FunctionCall> = Vec::new();
                 let mut turn_is_complete = false;
                 let mut last_event_id: Option<String> = None;
+                let mut usage: Option<TokenUsage> = None;
+                let mut finish_reason: Option<FinishReason> = None;
+                let mut attempt = 0;
+
+                'attempt: loop {
+                    let mut llm_stream = model.generate_content(request.clone(), true).await;
+
+                    // Stream LLM responses
+                    while let Some(llm_result) = llm_stream.next().await {
+                        match llm_result {
+                            Ok(mut llm_response) => {
+                                for callback in &after_model {
+                                    callback(&mut llm_response);
+                                }
 
-                // Stream LLM responses
-                while let Some(llm_result) = llm_stream.next().await {
-                    match llm_result {
-                        Ok(llm_response) => {
-                            let mut event = Event::new(
-                                invocation_id.clone(),
-                                agent_name.to_string(),
-                            );
+                                let mut event = Event::new(
+                                    invocation_id.clone(),
+                                    agent_name.to_string(),
+                                );
 
-                            last_event_id = Some(event.id.clone());
+                                last_event_id = Some(event.id.clone());
 
-                            event.content = llm_response.content.clone();
-                            event.partial = llm_response.partial;
-                            event.turn_complete = llm_response.turn_complete;
-                            event.interrupted = llm_response.interrupted;
+                                event.content = llm_response.content.clone();
+                                event.partial = llm_response.partial;
+                                event.turn_complete = llm_response.turn_complete;
+                                event.interrupted = llm_response.interrupted;
 
-                            if let Some(code) = llm_response.error_code {
-                                event.error_code = code;
-                            }
-                            if let Some(msg) = llm_response.error_message {
-                                event.error_message = msg;
-                            }
+                                if let Some(code) = llm_response.error_code {
+                                    event.error_code = code;
+                                }
+                                if let Some(msg) = llm_response.error_message {
+                                    event.error_message = msg;
+                                }
 
-                            // Accumulate content for tool extraction
-                            if let Some(ref content) = llm_response.content {
-                                accumulated_content = Some(content.clone());
+                                // Accumulate content for tool extraction
+                                if let Some(ref content) = llm_response.content {
+                                    accumulated_content = Some(content.clone());
 
-                                // Extract function calls
-                                for part in &content.parts {
-                                    if let Part::FunctionCall { function_call } = part {
-                                        function_calls.push(function_call.clone());
+                                    // Extract function calls
+                                    for part in &content.parts {
+                                        if let Part::FunctionCall { function_call } = part {
+                                            function_calls.push(function_call.clone());
+                                        }
                                     }
                                 }
-                            }
 
-                            turn_is_complete = llm_response.turn_complete;
+                                // Function calls are emitted together in one event once the
+                                // response is complete, so leave them out of the streamed ones
+                                if let Some(content) = event.content.as_mut() {
+                                    content.parts.retain(|p| !matches!(p, Part::FunctionCall { .. }));
+                                    if content.parts.is_empty() {
+                                        event.content = None;
+                                    }
+                                }
 
-                            yield Ok(event);
-                        }
-                        Err(e) => {
-                            tracing::error!(
-                                error = %e,
-                                invocation_id = %invocation_id,
-                                session_id = %session_id,
-                                "LLM call failed"
-                            );
-                            yield Err(e);
-                            return;
+                                turn_is_complete = llm_response.turn_complete;
+                                if llm_response.usage.is_some() {
+                                    usage = llm_response.usage;
+                                }
+                                if llm_response.finish_reason.is_some() {
+                                    finish_reason = llm_response.finish_reason.clone();
+                                }
+
+                                // A partial chunk that only carried function calls has nothing left to show
+                                if event.partial && event.content.is_none() {
+                                    continue;
+                                }
+
+                                yield Ok(event);
+                            }
+                            Err(e) => {
+                                // Only retry if nothing from this call has been emitted yet
+                                if last_event_id.is_none()
+                                    && attempt < max_retries
+                                    && is_retryable_llm_error(&e)
+                                {
+                                    let delay = retry_delay(retry_backoff, attempt);
+                                    attempt += 1;
+                                    tracing::warn!(
+                                        error = %e,
+                                        invocation_id = %invocation_id,
+                                        session_id = %session_id,
+                                        attempt = attempt,
+                                        delay_ms = delay.as_millis() as u64,
+                                        "Retryable LLM error, retrying"
+                                    );
+                                    tokio::time::sleep(delay).await;
+                                    continue 'attempt;
+                                }
+
+                                tracing::error!(
+                                    error = %e,
+                                    invocation_id = %invocation_id,
+                                    session_id = %session_id,
+                                    "LLM call failed"
+                                );
+                                yield Err(e);
+                                return;
+                            }
                         }
                     }
+
+                    break;
                 }
 
                 // Trace the LLM call after stream completes
@@ -193,6 +359,8 @@ impl Agent for LLMAgent {
                         response_json,
                         top_p: request.config.as_ref().and_then(|c| c.top_p.map(|p| p as f64)),
                         max_tokens: request.config.as_ref().and_then(|c| c.max_tokens.map(|t| t as i64)),
+                        input_tokens: usage.map(|u| u.prompt_tokens as u64),
+                        output_tokens: usage.map(|u| u.completion_tokens as u64),
                     });
 
                     tracing::debug!(
@@ -204,19 +372,117 @@ impl Agent for LLMAgent {
                     );
                 }
 
+                // Tell callers the answer was cut short rather than finished
+                if let Some(code) = finish_reason.as_ref().and_then(FinishReason::error_code) {
+                    tracing::warn!(
+                        invocation_id = %invocation_id,
+                        session_id = %session_id,
+                        finish_reason = ?finish_reason,
+                        "Model stopped before finishing its answer"
+                    );
+
+                    let mut stop_event = Event::new(invocation_id.clone(), agent_name.to_string());
+                    stop_event.error_code = code.to_string();
+                    stop_event.error_message = match finish_reason {
+                        Some(FinishReason::Safety) => "Response blocked by the model's safety filters".to_string(),
+                        _ => "Response cut off at the model's output token limit".to_string(),
+                    };
+                    yield Ok(stop_event);
+                }
+
                 // Add model response to conversation
-                if let Some(content) = accumulated_content {
-                    conversation.push(content);
+                if let Some(ref content) = accumulated_content {
+                    conversation.push(content.clone());
                 }
 
                 // If no function calls, we're done
                 if function_calls.is_empty() {
+                    // Let the reflector reject the answer and retry with its feedback
+                    let feedback = match (&reflector, &accumulated_content) {
+                        (Some(reflect), Some(content)) if reflections < max_reflections => {
+                            reflect(content)
+                        }
+                        _ => None,
+                    };
+
+                    if let Some(feedback) = feedback {
+                        reflections += 1;
+                        tracing::info!(
+                            invocation_id = %invocation_id,
+                            session_id = %session_id,
+                            reflection = reflections,
+                            "Reflection rejected output, retrying with feedback"
+                        );
+
+                        let feedback = Content::new_user_text(feedback);
+                        let mut feedback_event = Event::new(
+                            invocation_id.clone(),
+                            agent_name.to_string(),
+                        );
+                        feedback_event.content = Some(feedback.clone());
+                        yield Ok(feedback_event);
+
+                        conversation.push(feedback);
+                        continue;
+                    }
+
                     tracing::info!(
                         invocation_id = %invocation_id,
                         session_id = %session_id,
                         "Agent execution completed"
                     );
-                    break;
+                    return;
+                }
+
+                // Announce the calls before running them so UIs can show progress
+                let mut call_event = Event::new(
+                    invocation_id.clone(),
+                    agent_name.to_string(),
+                );
+                call_event.content = Some(Content {
+                    role: "model".to_string(),
+                    parts: function_calls
+                        .iter()
+                        .map(|fc| Part::FunctionCall { function_call: fc.clone() })
+                        .collect(),
+                });
+                call_event.turn_complete = false;
+                yield Ok(call_event);
+
+                // Plan-only mode: surface the calls but answer them with placeholders
+                if !execute_tools {
+                    tracing::info!(
+                        invocation_id = %invocation_id,
+                        session_id = %session_id,
+                        num_function_calls = function_calls.len(),
+                        "Tool execution disabled, skipping function calls"
+                    );
+
+                    let placeholders: Vec<Part> = function_calls
+                        .into_iter()
+                        .map(|fc| Part::FunctionResponse {
+                            function_response: zdk_core::FunctionResponse {
+                                name: fc.name,
+                                response: serde_json::json!({
+                                    "status": "skipped",
+                                    "reason": "Tool execution is disabled for this run",
+                                }),
+                                id: fc.id,
+                            },
+                        })
+                        .collect();
+
+                    let mut skipped_event = Event::new(
+                        invocation_id.clone(),
+                        agent_name.to_string(),
+                    );
+                    skipped_event.content = Some(Content {
+                        role: "function".to_string(),
+                        parts: placeholders,
+                    });
+                    skipped_event.turn_complete = true;
+                    yield Ok(skipped_event);
+                    return;
                 }
 
                 tracing::debug!(
@@ -242,19 +508,82 @@ impl Agent for LLMAgent {
                         );
 
                         // Create tool context
-                        let tool_ctx = Arc::new(zdk_tool::DefaultToolContext::new(
-                            call_id.clone(),
-                            invocation_id.clone(),
-                        ));
+                        let mut tool_ctx = zdk_tool::DefaultToolContext::new(call_id.clone(), invocation_id.clone())
+                            .with_state(state.clone());
+                        if let Some(artifacts) = &artifacts {
+                            tool_ctx = tool_ctx.with_artifacts(artifacts.clone());
+                        }
+                        let tool_ctx = Arc::new(tool_ctx);
+
+                        // A before_tool callback may answer the call in place of the tool
+                        let overridden = before_tool.iter().find_map(|callback| callback(&fc.name, &fc.args));
+                        let result = match overridden {
+                            Some(response) => {
+                                tracing::debug!(
+                                    invocation_id = %invocation_id,
+                                    session_id = %session_id,
+                                    tool_name = %fc.name,
+                                    "Tool call answered by before_tool callback"
+                                );
+                                Ok(response)
+                            }
+                            None => {
+                                // Execute tool, bounded by the configured timeout
+                                let execution = tool.execute(tool_ctx.clone(), fc.args.clone());
+                                match tool_timeout {
+                                    Some(limit) => match tokio::time::timeout(limit, execution).await {
+                                        Ok(result) => result,
+                                        Err(_) => {
+                                            tracing::warn!(
+                                                invocation_id = %invocation_id,
+                                                session_id = %session_id,
+                                                tool_name = %fc.name,
+                                                timeout_ms = limit.as_millis() as u64,
+                                                "Tool execution timed out"
+                                            );
+
+                                            let mut timeout_event = Event::new(
+                                                invocation_id.clone(),
+                                                agent_name.to_string(),
+                                            );
+                                            timeout_event.error_code = "TOOL_TIMEOUT".to_string();
+                                            timeout_event.error_message = format!("Tool {} timed out after {:?}", fc.name, limit);
+                                            timeout_event.actions.state_delta = tool_ctx.take_state_delta();
+
+                                            // Tell the model so the conversation can continue
+                                            let response = tool_error_response(
+                                                &fc,
+                                                ToolErrorKind::Internal,
+                                                format!("Tool execution timed out after {:?}", limit),
+                                            );
+                                            timeout_event.content = Some(Content {
+                                                role: "function".to_string(),
+                                                parts: vec![response.clone()],
+                                            });
+                                            function_responses.push(response);
+                                            yield Ok(timeout_event);
+                                            continue;
+                                        }
+                                    },
+                                    None => execution.await,
+                                }
+                            }
+                        };
 
-                        // Execute tool
-                        match tool.execute(tool_ctx, fc.args.clone()).await {
+                        match result {
                             Ok(response) => {
+                                for callback in &after_tool {
+                                    callback(&fc.name, &response);
+                                }
+                                let tool_error = response.error;
                                 function_responses.push(Part::FunctionResponse {
                                     function_response: zdk_core::FunctionResponse {
                                         name: fc.name.clone(),
-                                        response: response.result,
-                                        id: None, // Don't include ID for Gemini compatibility
+                                        response: match max_tool_response_bytes {
+                                            Some(max_bytes) => limit_tool_response(response.result, max_bytes),
+                                            None => response.result,
+                                        },
+                                        id: fc.id.clone(),
                                     },
                                 });
 
@@ -267,6 +596,14 @@ impl Agent for LLMAgent {
                                     role: "function".to_string(),
                                     parts: vec![function_responses.last().unwrap().clone()],
                                 });
+                                // Surface the failure category of a tool that ran but failed
+                                if let Some(tool_error) = tool_error {
+                                    tool_event.error_code = tool_error.kind.error_code();
+                                    tool_event.error_message = format!("Tool {} failed: {}", fc.name, tool_error.message);
+                                }
+                                // Persist the tool's state writes with its event
+                                tool_event.actions.state_delta = tool_ctx.take_state_delta();
+                                tool_event.actions.escalate = tool_ctx.escalated();
                                 yield Ok(tool_event);
                             }
                             Err(e) => {
@@ -277,6 +614,14 @@ impl Agent for LLMAgent {
                                 );
                                 error_event.error_code = "TOOL_ERROR".to_string();
                                 error_event.error_message = format!("Tool {} failed: {}", fc.name, e);
+                                error_event.actions.state_delta = tool_ctx.take_state_delta();
+
+                                let response = tool_error_response(&fc, ToolErrorKind::Internal, error_event.error_message.clone());
+                                error_event.content = Some(Content {
+                                    role: "function".to_string(),
+                                    parts: vec![response.clone()],
+                                });
+                                function_responses.push(response);
                                 yield Ok(error_event);
                             }
                         }
@@ -288,6 +633,13 @@ impl Agent for LLMAgent {
                         );
                         error_event.error_code = "TOOL_NOT_FOUND".to_string();
                         error_event.error_message = format!("Tool {} not found", fc.name);
+
+                        let response = tool_error_response(&fc, ToolErrorKind::NotFound, error_event.error_message.clone());
+                        error_event.content = Some(Content {
+                            role: "function".to_string(),
+                            parts: vec![response.clone()],
+                        });
+                        function_responses.push(response);
                         yield Ok(error_event);
                     }
                 }
@@ -302,6 +654,23 @@ impl Agent for LLMAgent {
 
                 // Continue to next iteration for LLM to process tool results
             }
+
+            // Only reachable when the model still had function calls pending
+            tracing::warn!(
+                invocation_id = %invocation_id,
+                session_id = %session_id,
+                max_iterations = max_iterations,
+                "Agent reached max iterations"
+            );
+
+            let mut limit_event = Event::new(invocation_id.clone(), agent_name.to_string());
+            limit_event.error_code = "MAX_ITERATIONS".to_string();
+            limit_event.error_message = format!(
+                "Agent stopped after {} iterations with function calls still pending",
+                max_iterations
+            );
+            limit_event.turn_complete = true;
+            yield Ok(limit_event);
         }))
     }
 }
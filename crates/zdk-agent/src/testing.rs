@@ -6,9 +6,12 @@
 use async_stream::stream;
 use async_trait::async_trait;
 use futures::stream::Stream;
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
 use zdk_core::{
-    Agent, Content, Event, InvocationContext, LLM, LLMRequest, LLMResponse, Part, Result,
+    Agent, Content, Error, Event, FinishReason, FunctionCall, InvocationContext, LLM, LLMRequest,
+    LLMResponse, Part, ReadonlyContext, Result,
 };
 
 /// Mock LLM for testing
@@ -16,22 +19,28 @@ use zdk_core::{
 /// Returns a simple test response for any request.
 pub struct MockLLM {
     response_text: String,
+    finish_reason: FinishReason,
 }
 
 impl MockLLM {
     /// Create a new MockLLM with default response
     pub fn new() -> Self {
-        Self {
-            response_text: "Test response".to_string(),
-        }
+        Self::with_response("Test response")
     }
 
     /// Create a MockLLM with custom response text
     pub fn with_response(response: impl Into<String>) -> Self {
         Self {
             response_text: response.into(),
+            finish_reason: FinishReason::Stop,
         }
     }
+
+    /// Report a different finish reason, e.g. a safety block
+    pub fn finishing_with(mut self, finish_reason: FinishReason) -> Self {
+        self.finish_reason = finish_reason;
+        self
+    }
 }
 
 impl Default for MockLLM {
@@ -52,6 +61,7 @@ impl LLM for MockLLM {
         _stream: bool,
     ) -> Box<dyn Stream<Item = Result<LLMResponse>> + Send + Unpin> {
         let response_text = self.response_text.clone();
+        let finish_reason = self.finish_reason.clone();
         Box::new(Box::pin(stream! {
             yield Ok(LLMResponse {
                 content: Some(Content {
@@ -61,14 +71,300 @@ impl LLM for MockLLM {
                 partial: false,
                 turn_complete: true,
                 interrupted: false,
-                finish_reason: Some("STOP".to_string()),
+                finish_reason: Some(finish_reason),
+                error_code: None,
+                error_message: None,
+                usage: None,
+            });
+        }))
+    }
+}
+
+/// Mock LLM that fails a fixed number of times before succeeding
+///
+/// Each failing call yields `Error::LLMError` with the configured message.
+pub struct FlakyLLM {
+    failures_left: AtomicU32,
+    error_message: String,
+    calls: AtomicU32,
+}
+
+impl FlakyLLM {
+    /// Create a FlakyLLM that fails `failures` times with `error_message`
+    pub fn new(failures: u32, error_message: impl Into<String>) -> Self {
+        Self {
+            failures_left: AtomicU32::new(failures),
+            error_message: error_message.into(),
+            calls: AtomicU32::new(0),
+        }
+    }
+
+    /// Number of times `generate_content` has been called
+    pub fn calls(&self) -> u32 {
+        self.calls.load(Ordering::SeqCst)
+    }
+}
+
+#[async_trait]
+impl LLM for FlakyLLM {
+    fn name(&self) -> &str {
+        "flaky"
+    }
+
+    async fn generate_content(
+        &self,
+        request: LLMRequest,
+        stream: bool,
+    ) -> Box<dyn Stream<Item = Result<LLMResponse>> + Send + Unpin> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+
+        let should_fail = self
+            .failures_left
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            .is_ok();
+
+        if should_fail {
+            let message = self.error_message.clone();
+            return Box::new(Box::pin(stream! {
+                yield Err(Error::LLMError(message));
+            }));
+        }
+
+        MockLLM::new().generate_content(request, stream).await
+    }
+}
+
+/// Mock LLM that records every request and answers like [`MockLLM`]
+#[derive(Default)]
+pub struct RecordingLLM {
+    requests: std::sync::Mutex<Vec<LLMRequest>>,
+    context_window: Option<usize>,
+}
+
+impl RecordingLLM {
+    /// Create a RecordingLLM with no recorded requests
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Report a context window of `tokens`
+    pub fn with_context_window(mut self, tokens: usize) -> Self {
+        self.context_window = Some(tokens);
+        self
+    }
+
+    /// Requests received so far
+    pub fn requests(&self) -> Vec<LLMRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl LLM for RecordingLLM {
+    fn name(&self) -> &str {
+        "recording"
+    }
+
+    fn context_window(&self) -> Option<usize> {
+        self.context_window
+    }
+
+    async fn generate_content(
+        &self,
+        request: LLMRequest,
+        stream: bool,
+    ) -> Box<dyn Stream<Item = Result<LLMResponse>> + Send + Unpin> {
+        self.requests.lock().unwrap().push(request.clone());
+        MockLLM::new().generate_content(request, stream).await
+    }
+}
+
+/// Mock LLM that answers with a fixed sequence of texts and records requests
+///
+/// Once the sequence is exhausted the last text is repeated.
+pub struct ScriptedLLM {
+    responses: Vec<String>,
+    requests: std::sync::Mutex<Vec<LLMRequest>>,
+}
+
+impl ScriptedLLM {
+    /// Create a ScriptedLLM answering with `responses` in order
+    pub fn new(responses: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            responses: responses.into_iter().map(Into::into).collect(),
+            requests: std::sync::Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Requests received so far
+    pub fn requests(&self) -> Vec<LLMRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl LLM for ScriptedLLM {
+    fn name(&self) -> &str {
+        "scripted"
+    }
+
+    async fn generate_content(
+        &self,
+        request: LLMRequest,
+        stream: bool,
+    ) -> Box<dyn Stream<Item = Result<LLMResponse>> + Send + Unpin> {
+        let index = {
+            let mut requests = self.requests.lock().unwrap();
+            requests.push(request.clone());
+            (requests.len() - 1).min(self.responses.len().saturating_sub(1))
+        };
+
+        let text = self.responses.get(index).cloned().unwrap_or_default();
+        MockLLM::with_response(text)
+            .generate_content(request, stream)
+            .await
+    }
+}
+
+/// Mock LLM that requests the same tool on every call and records requests
+pub struct LoopingLLM {
+    tool_name: String,
+    calls_per_turn: usize,
+    calls: AtomicU32,
+    requests: std::sync::Mutex<Vec<LLMRequest>>,
+}
+
+impl LoopingLLM {
+    /// Create a LoopingLLM that always calls `tool_name`
+    pub fn new(tool_name: impl Into<String>) -> Self {
+        Self {
+            tool_name: tool_name.into(),
+            calls_per_turn: 1,
+            calls: AtomicU32::new(0),
+            requests: std::sync::Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Call the tool `n` times per turn, each call with its own ID
+    pub fn with_calls_per_turn(mut self, n: usize) -> Self {
+        self.calls_per_turn = n.max(1);
+        self
+    }
+
+    /// Number of times `generate_content` has been called
+    pub fn calls(&self) -> u32 {
+        self.calls.load(Ordering::SeqCst)
+    }
+
+    /// Requests received so far
+    pub fn requests(&self) -> Vec<LLMRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl LLM for LoopingLLM {
+    fn name(&self) -> &str {
+        "looping"
+    }
+
+    async fn generate_content(
+        &self,
+        request: LLMRequest,
+        _stream: bool,
+    ) -> Box<dyn Stream<Item = Result<LLMResponse>> + Send + Unpin> {
+        let turn = self.calls.fetch_add(1, Ordering::SeqCst);
+        self.requests.lock().unwrap().push(request);
+        let parts = (0..self.calls_per_turn)
+            .map(|i| Part::FunctionCall {
+                function_call: FunctionCall {
+                    name: self.tool_name.clone(),
+                    args: serde_json::json!({}),
+                    id: Some(format!("call_{}_{}", turn, i)),
+                },
+            })
+            .collect();
+
+        Box::new(Box::pin(stream! {
+            yield Ok(LLMResponse {
+                content: Some(Content {
+                    role: "model".to_string(),
+                    parts,
+                }),
+                partial: false,
+                turn_complete: false,
+                interrupted: false,
+                finish_reason: None,
                 error_code: None,
                 error_message: None,
+                usage: None,
             });
         }))
     }
 }
 
+/// Minimal invocation context for running agents in tests
+pub struct MockContext {
+    user_content: Content,
+    history: Vec<Content>,
+    state: HashMap<String, serde_json::Value>,
+}
+
+impl MockContext {
+    /// Create a context with the given user message
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            user_content: Content::new_user_text(message),
+            history: Vec::new(),
+            state: HashMap::new(),
+        }
+    }
+
+    /// Set the prior conversation turns
+    pub fn with_history(mut self, history: Vec<Content>) -> Self {
+        self.history = history;
+        self
+    }
+
+    /// Add a session state entry
+    pub fn with_state(mut self, key: impl Into<String>, value: serde_json::Value) -> Self {
+        self.state.insert(key.into(), value);
+        self
+    }
+}
+
+impl InvocationContext for MockContext {
+    fn invocation_id(&self) -> &str {
+        "test-invocation"
+    }
+
+    fn user_content(&self) -> Option<&Content> {
+        Some(&self.user_content)
+    }
+
+    fn history(&self) -> &[Content] {
+        &self.history
+    }
+
+    fn state(&self) -> HashMap<String, serde_json::Value> {
+        self.state.clone()
+    }
+}
+
+impl ReadonlyContext for MockContext {
+    fn app_name(&self) -> &str {
+        "test-app"
+    }
+
+    fn user_id(&self) -> &str {
+        "test-user"
+    }
+
+    fn session_id(&self) -> &str {
+        "test-session"
+    }
+}
+
 /// Mock Agent for testing workflows
 ///
 /// Returns a configurable response and supports escalation flag.
@@ -76,6 +372,8 @@ pub struct MockAgent {
     name: String,
     response: String,
     escalate: bool,
+    escalate_on_run: Option<u32>,
+    runs: AtomicU32,
 }
 
 impl MockAgent {
@@ -85,6 +383,8 @@ impl MockAgent {
             name: name.into(),
             response: "Mock agent response".to_string(),
             escalate: false,
+            escalate_on_run: None,
+            runs: AtomicU32::new(0),
         }
     }
 
@@ -99,6 +399,12 @@ impl MockAgent {
         self.escalate = escalate;
         self
     }
+
+    /// Escalate only on the given run (1-based), e.g. a loop's second iteration
+    pub fn with_escalate_on_run(mut self, run: u32) -> Self {
+        self.escalate_on_run = Some(run);
+        self
+    }
 }
 
 #[async_trait]
@@ -116,7 +422,8 @@ impl Agent for MockAgent {
         ctx: Arc<dyn InvocationContext>,
     ) -> Box<dyn Stream<Item = Result<Event>> + Send + Unpin> {
         let response = self.response.clone();
-        let escalate = self.escalate;
+        let run = self.runs.fetch_add(1, Ordering::SeqCst) + 1;
+        let escalate = self.escalate || self.escalate_on_run == Some(run);
         let invocation_id = ctx.invocation_id().to_string();
         let name = self.name.clone();
 
@@ -1,8 +1,41 @@
 //! Utility functions for agent implementations
 
+use serde_json::{Value, json};
 use std::collections::HashMap;
+use std::collections::hash_map::Entry;
 use std::sync::Arc;
-use zdk_core::{InvocationContext, Tool, Toolset};
+use std::time::Instant;
+use zdk_core::{
+    FunctionCall, FunctionResponse, InvocationContext, Part, Tool, ToolErrorKind, ToolResponse,
+    Toolset,
+};
+
+pub use zdk_core::providers::is_retryable_llm_error;
+
+/// Records an agent run's duration metric when dropped
+///
+/// Create one at the start of a run's event stream so the run is measured
+/// however it ends, including when the caller drops the stream early.
+pub struct RunTimer {
+    agent_name: Arc<str>,
+    started: Instant,
+}
+
+impl RunTimer {
+    /// Start timing a run of `agent_name`
+    pub fn start(agent_name: Arc<str>) -> Self {
+        Self {
+            agent_name,
+            started: Instant::now(),
+        }
+    }
+}
+
+impl Drop for RunTimer {
+    fn drop(&mut self) {
+        zdk_telemetry::record_agent_run(&self.agent_name, self.started.elapsed());
+    }
+}
 
 /// Load tools from multiple toolsets in parallel
 ///
@@ -68,12 +101,60 @@ pub async fn load_toolsets(
 
     let toolset_results = futures::future::join_all(toolset_futures).await;
 
-    // Merge loaded tools into the tools HashMap
+    // Merge loaded tools into the tools HashMap; on a name collision the
+    // toolset registered first wins
     for tools_opt in toolset_results.into_iter().flatten() {
         for tool in tools_opt {
-            tools.insert(tool.name().to_string(), tool);
+            match tools.entry(tool.name().to_string()) {
+                Entry::Occupied(entry) => tracing::warn!(
+                    invocation_id = %invocation_id,
+                    tool_name = %entry.key(),
+                    "Tool provided by more than one toolset, keeping the first"
+                ),
+                Entry::Vacant(entry) => {
+                    entry.insert(tool);
+                }
+            }
         }
     }
 
     tools
 }
+
+/// Cap a tool response at `max_bytes` of serialized JSON
+///
+/// Oversized responses are replaced by an object holding a truncated preview
+/// of the JSON text, so the model knows the result was cut short.
+pub fn limit_tool_response(response: Value, max_bytes: usize) -> Value {
+    let serialized = response.to_string();
+    if serialized.len() <= max_bytes {
+        return response;
+    }
+
+    let mut cut = max_bytes;
+    while !serialized.is_char_boundary(cut) {
+        cut -= 1;
+    }
+
+    json!({
+        "truncated": true,
+        "original_bytes": serialized.len(),
+        "preview": &serialized[..cut],
+    })
+}
+
+/// Function response telling the model a tool call failed
+///
+/// Every call gets a response, even when the tool is missing or errors, so
+/// the conversation and the persisted transcript stay well-formed. The payload
+/// matches [`ToolResponse::error`] so the model sees one error shape, and it
+/// carries the call's ID so providers can pair it with the call.
+pub fn tool_error_response(call: &FunctionCall, kind: ToolErrorKind, message: String) -> Part {
+    Part::FunctionResponse {
+        function_response: FunctionResponse {
+            name: call.name.clone(),
+            response: ToolResponse::error(kind, message).result,
+            id: call.id.clone(),
+        },
+    }
+}
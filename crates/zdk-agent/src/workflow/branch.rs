@@ -0,0 +1,224 @@
+use crate::builder_common::AgentBuilderCore;
+use async_trait::async_trait;
+use futures::stream::Stream;
+use std::sync::Arc;
+use zdk_core::{Agent, Error, Event, InvocationContext, Result};
+
+/// Decides which branch a BranchAgent takes for an invocation
+pub type BranchCondition = Arc<dyn Fn(&dyn InvocationContext) -> bool + Send + Sync>;
+
+/// BranchAgent runs one of two sub-agents, chosen by a condition.
+///
+/// The condition is evaluated once per invocation, typically against session
+/// state, e.g. to route verified users to one agent and everyone else to
+/// another. Only the selected branch runs and emits events.
+pub struct BranchAgent {
+    pub(crate) name: Arc<str>,
+    pub(crate) description: Arc<str>,
+    pub(crate) condition: BranchCondition,
+    /// The branch taken when the condition holds, then the other one
+    pub(crate) sub_agents: Vec<Arc<dyn Agent>>,
+}
+
+impl BranchAgent {
+    pub fn builder() -> BranchAgentBuilder {
+        BranchAgentBuilder::new()
+    }
+}
+
+#[async_trait]
+impl Agent for BranchAgent {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    async fn run(
+        &self,
+        ctx: Arc<dyn InvocationContext>,
+    ) -> Box<dyn Stream<Item = Result<Event>> + Send + Unpin> {
+        let branch = if (self.condition)(ctx.as_ref()) {
+            &self.sub_agents[0]
+        } else {
+            &self.sub_agents[1]
+        };
+
+        tracing::debug!(
+            agent = %self.name,
+            branch = %branch.name(),
+            "Branch selected"
+        );
+
+        branch.run(ctx).await
+    }
+
+    fn sub_agents(&self) -> &[Arc<dyn Agent>] {
+        &self.sub_agents
+    }
+}
+
+/// Builder for BranchAgent
+pub struct BranchAgentBuilder {
+    core: AgentBuilderCore,
+    condition: Option<BranchCondition>,
+    if_true: Option<Arc<dyn Agent>>,
+    if_false: Option<Arc<dyn Agent>>,
+}
+
+impl BranchAgentBuilder {
+    pub fn new() -> Self {
+        Self {
+            core: AgentBuilderCore::new(),
+            condition: None,
+            if_true: None,
+            if_false: None,
+        }
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.core.with_name(name);
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.core.with_description(description);
+        self
+    }
+
+    /// Choose the branch with a predicate over the invocation context
+    pub fn condition(mut self, condition: BranchCondition) -> Self {
+        self.condition = Some(condition);
+        self
+    }
+
+    /// Take the `if_true` branch when a session state key holds a truthy value
+    ///
+    /// `false`, `null`, `0`, `""` and missing keys count as false.
+    pub fn when_state(self, key: impl Into<String>) -> Self {
+        let key = key.into();
+        self.condition(Arc::new(move |ctx| {
+            ctx.state().get(&key).is_some_and(is_truthy)
+        }))
+    }
+
+    /// Agent to run when the condition holds
+    pub fn if_true(mut self, agent: Arc<dyn Agent>) -> Self {
+        self.if_true = Some(agent);
+        self
+    }
+
+    /// Agent to run otherwise
+    pub fn if_false(mut self, agent: Arc<dyn Agent>) -> Self {
+        self.if_false = Some(agent);
+        self
+    }
+
+    pub fn build(self) -> Result<BranchAgent> {
+        let (name, description) = self.core.validate(
+            "BranchAgent",
+            "A branch agent that runs one of two sub-agents",
+        )?;
+
+        let condition = self
+            .condition
+            .ok_or_else(|| Error::Config("BranchAgent requires a condition".to_string()))?;
+        let (Some(if_true), Some(if_false)) = (self.if_true, self.if_false) else {
+            return Err(Error::Config(
+                "BranchAgent requires both an if_true and an if_false sub-agent".to_string(),
+            ));
+        };
+
+        Ok(BranchAgent {
+            name: Arc::from(name),
+            description: Arc::from(description),
+            condition,
+            sub_agents: vec![if_true, if_false],
+        })
+    }
+}
+
+impl Default for BranchAgentBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn is_truthy(value: &serde_json::Value) -> bool {
+    match value {
+        serde_json::Value::Null => false,
+        serde_json::Value::Bool(b) => *b,
+        serde_json::Value::Number(n) => n.as_f64().is_some_and(|n| n != 0.0),
+        serde_json::Value::String(s) => !s.is_empty(),
+        serde_json::Value::Array(_) | serde_json::Value::Object(_) => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{MockAgent, MockContext};
+    use futures::StreamExt;
+
+    fn router() -> BranchAgentBuilder {
+        BranchAgent::builder()
+            .name("router")
+            .if_true(Arc::new(
+                MockAgent::new("premium").with_response("Priority"),
+            ))
+            .if_false(Arc::new(MockAgent::new("standard").with_response("Queued")))
+    }
+
+    async fn authors(agent: &BranchAgent, ctx: MockContext) -> Vec<String> {
+        let mut stream = agent.run(Arc::new(ctx)).await;
+        let mut authors = Vec::new();
+        while let Some(result) = stream.next().await {
+            authors.push(result.unwrap().author);
+        }
+        authors
+    }
+
+    #[tokio::test]
+    async fn test_predicate_selects_branch() {
+        let agent = router()
+            .condition(Arc::new(|ctx| {
+                ctx.state().get("premium") == Some(&serde_json::json!(true))
+            }))
+            .build()
+            .unwrap();
+
+        let premium = MockContext::new("Help").with_state("premium", serde_json::json!(true));
+        assert_eq!(authors(&agent, premium).await, ["premium"]);
+
+        let standard = MockContext::new("Help").with_state("premium", serde_json::json!(false));
+        assert_eq!(authors(&agent, standard).await, ["standard"]);
+    }
+
+    #[tokio::test]
+    async fn test_when_state_checks_truthiness() {
+        let agent = router().when_state("premium").build().unwrap();
+
+        let premium = MockContext::new("Help").with_state("premium", serde_json::json!("gold"));
+        assert_eq!(authors(&agent, premium).await, ["premium"]);
+
+        // A missing key takes the other branch
+        assert_eq!(
+            authors(&agent, MockContext::new("Help")).await,
+            ["standard"]
+        );
+    }
+
+    #[test]
+    fn test_branch_agent_requires_both_branches() {
+        let result = BranchAgent::builder()
+            .name("router")
+            .when_state("premium")
+            .if_true(Arc::new(MockAgent::new("premium")))
+            .build();
+
+        assert!(result.is_err());
+        assert!(router().build().is_err());
+    }
+}
@@ -1,20 +1,33 @@
+use super::FinalOutputs;
 use crate::builder_common::AgentBuilderCore;
 use async_stream::stream;
 use async_trait::async_trait;
 use futures::stream::{Stream, StreamExt};
+use serde_json::Value;
+use std::collections::HashMap;
 use std::sync::Arc;
-use zdk_core::{Agent, Error, Event, InvocationContext, Result};
+use zdk_core::{
+    Agent, ArtifactStore, Content, Error, Event, InvocationContext, MemoryStore, ReadonlyContext,
+    Result, RunConfig, RunnerDefaults, SessionStore,
+};
 
 /// LoopAgent repeatedly runs its sub-agents in sequence for a specified number
 /// of iterations or until a termination condition is met.
 ///
 /// Use the LoopAgent when your workflow involves repetition or iterative
 /// refinement, such as revising code or iteratively improving responses.
+///
+/// A sub-agent ends the loop early by emitting an event with
+/// `actions.escalate` set; an `LLMAgent` does so when one of its tools calls
+/// `ToolContext::escalate`. The loop stops once that sub-agent's turn
+/// completes, skipping the remaining sub-agents and iterations.
 pub struct LoopAgent {
     pub(crate) name: Arc<str>,
     pub(crate) description: Arc<str>,
     pub(crate) sub_agents: Vec<Arc<dyn Agent>>,
     pub(crate) max_iterations: u32,
+    /// Show each sub-agent's final answer to the sub-agents after it
+    pub(crate) forward_outputs: bool,
 }
 
 impl LoopAgent {
@@ -39,9 +52,11 @@ impl Agent for LoopAgent {
     ) -> Box<dyn Stream<Item = Result<Event>> + Send + Unpin> {
         let sub_agents = self.sub_agents.clone();
         let max_iterations = self.max_iterations;
+        let forward_outputs = self.forward_outputs;
 
         Box::new(Box::pin(stream! {
             let mut count = max_iterations;
+            let mut forwarded = Vec::new();
 
             // Loop indefinitely if max_iterations is 0, otherwise loop count times
             loop {
@@ -49,7 +64,13 @@ impl Agent for LoopAgent {
 
                 // Run each sub-agent in sequence
                 for sub_agent in &sub_agents {
-                    let mut sub_stream = sub_agent.run(ctx.clone()).await;
+                    let sub_ctx: Arc<dyn InvocationContext> = if forwarded.is_empty() {
+                        ctx.clone()
+                    } else {
+                        Arc::new(ForwardedContext::new(ctx.clone(), &forwarded))
+                    };
+                    let mut sub_stream = sub_agent.run(sub_ctx).await;
+                    let mut outputs = FinalOutputs::default();
 
                     while let Some(result) = sub_stream.next().await {
                         match result {
@@ -58,6 +79,9 @@ impl Agent for LoopAgent {
                                 if event.actions.escalate {
                                     should_exit = true;
                                 }
+                                if forward_outputs {
+                                    outputs.record(&event);
+                                }
 
                                 yield Ok(event);
                             }
@@ -71,6 +95,13 @@ impl Agent for LoopAgent {
                     if should_exit {
                         return;
                     }
+
+                    if let Some((author, text)) = outputs.into_last() {
+                        forwarded.push(Content::new_user_text(format!(
+                            "For context: [{}] said: {}",
+                            author, text
+                        )));
+                    }
                 }
 
                 // Decrement count and check if we should exit
@@ -89,6 +120,80 @@ impl Agent for LoopAgent {
     }
 }
 
+/// Invocation context for a later sub-agent, with earlier answers appended
+///
+/// The earlier answers follow the user content in `history`, so the
+/// conversation still ends with a user turn and `user_content` is `None`.
+struct ForwardedContext {
+    inner: Arc<dyn InvocationContext>,
+    history: Vec<Content>,
+}
+
+impl ForwardedContext {
+    fn new(inner: Arc<dyn InvocationContext>, forwarded: &[Content]) -> Self {
+        let history = inner
+            .history()
+            .iter()
+            .chain(inner.user_content())
+            .chain(forwarded)
+            .cloned()
+            .collect();
+        Self { inner, history }
+    }
+}
+
+impl InvocationContext for ForwardedContext {
+    fn invocation_id(&self) -> &str {
+        self.inner.invocation_id()
+    }
+
+    fn user_content(&self) -> Option<&Content> {
+        None
+    }
+
+    fn history(&self) -> &[Content] {
+        &self.history
+    }
+
+    fn run_config(&self) -> &RunConfig {
+        self.inner.run_config()
+    }
+
+    fn runner_defaults(&self) -> &RunnerDefaults {
+        self.inner.runner_defaults()
+    }
+
+    fn state(&self) -> HashMap<String, Value> {
+        self.inner.state()
+    }
+
+    fn artifacts(&self) -> Option<Arc<dyn ArtifactStore>> {
+        self.inner.artifacts()
+    }
+
+    fn session(&self) -> Option<Arc<dyn SessionStore>> {
+        self.inner.session()
+    }
+
+    fn memory(&self) -> Option<Arc<dyn MemoryStore>> {
+        self.inner.memory()
+    }
+}
+
+impl ReadonlyContext for ForwardedContext {
+    fn app_name(&self) -> &str {
+        self.inner.app_name()
+    }
+
+    fn user_id(&self) -> &str {
+        self.inner.user_id()
+    }
+
+    fn session_id(&self) -> &str {
+        self.inner.session_id()
+    }
+}
+
 /// Builder for LoopAgent
 pub struct LoopAgentBuilder {
     core: AgentBuilderCore,
@@ -146,6 +251,7 @@ impl LoopAgentBuilder {
             description: Arc::from(description),
             sub_agents: self.sub_agents,
             max_iterations: self.max_iterations,
+            forward_outputs: false,
         })
     }
 }
@@ -159,7 +265,16 @@ impl Default for LoopAgentBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::testing::MockAgent;
+    use crate::testing::{LoopingLLM, MockAgent, MockContext};
+
+    async fn collect(agent: &LoopAgent) -> Vec<Event> {
+        let mut stream = agent.run(Arc::new(MockContext::new("go"))).await;
+        let mut events = Vec::new();
+        while let Some(result) = stream.next().await {
+            events.push(result.unwrap());
+        }
+        events
+    }
 
     #[test]
     fn test_loop_agent_builder() {
@@ -194,6 +309,74 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_escalation_stops_loop_early() {
+        let writer = Arc::new(MockAgent::new("writer").with_response("Draft"));
+        let critic = Arc::new(
+            MockAgent::new("critic")
+                .with_response("Looks good")
+                .with_escalate_on_run(2),
+        );
+
+        let loop_agent = LoopAgent::builder()
+            .name("refine")
+            .sub_agent(writer)
+            .sub_agent(critic)
+            .max_iterations(5)
+            .build()
+            .unwrap();
+
+        let events = collect(&loop_agent).await;
+
+        // Two iterations of writer + critic, then the critic's escalation ends the loop
+        let authors: Vec<_> = events.iter().map(|e| e.author.as_str()).collect();
+        assert_eq!(authors, ["writer", "critic", "writer", "critic"]);
+        assert!(!events[1].actions.escalate);
+        assert!(events[3].actions.escalate);
+    }
+
+    #[tokio::test]
+    async fn test_tool_escalation_stops_loop() {
+        use crate::LLMAgent;
+        use zdk_core::ToolResponse;
+
+        let tool = zdk_tool::FunctionTool::builder()
+            .name("approve")
+            .description("Approves the draft")
+            .execute(|ctx, _params| async move {
+                ctx.escalate();
+                Ok(ToolResponse::new(serde_json::json!({"approved": true})))
+            })
+            .build()
+            .unwrap();
+
+        let reviewer = LLMAgent::builder()
+            .name("reviewer")
+            .model(Arc::new(LoopingLLM::new("approve")))
+            .tool(Arc::new(tool))
+            .max_iterations(1)
+            .build()
+            .unwrap();
+        let after = Arc::new(MockAgent::new("after"));
+
+        let loop_agent = LoopAgent::builder()
+            .name("review_loop")
+            .sub_agent(Arc::new(reviewer))
+            .sub_agent(after)
+            .max_iterations(5)
+            .build()
+            .unwrap();
+
+        let events = collect(&loop_agent).await;
+
+        assert!(events.iter().all(|e| e.author == "reviewer"));
+        let tool_event = events
+            .iter()
+            .find(|e| e.content.as_ref().is_some_and(|c| c.role == "function"))
+            .expect("tool response event");
+        assert!(tool_event.actions.escalate);
+    }
+
     #[test]
     fn test_loop_agent_requires_sub_agents() {
         let result = LoopAgent::builder().name("test_loop").build();
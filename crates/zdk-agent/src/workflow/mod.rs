@@ -1,9 +1,77 @@
 //! Workflow agents for multi-agent orchestration
 
+pub mod branch;
 pub mod loop_agent;
 pub mod parallel;
 pub mod sequential;
 
+pub use branch::{BranchAgent, BranchAgentBuilder, BranchCondition};
 pub use loop_agent::{LoopAgent, LoopAgentBuilder};
 pub use parallel::{ParallelAgent, ParallelAgentBuilder};
 pub use sequential::{SequentialAgent, SequentialAgentBuilder};
+
+use std::collections::HashMap;
+use zdk_core::{Content, Event, Part};
+
+/// Final text of each sub-agent, tracked as their events interleave
+#[derive(Default)]
+pub(crate) struct FinalOutputs {
+    finals: HashMap<String, String>,
+    /// Author of the most recent final text
+    last_author: Option<String>,
+    /// Streamed text not yet closed by a non-partial event
+    pending: HashMap<String, String>,
+}
+
+impl FinalOutputs {
+    pub(crate) fn record(&mut self, event: &Event) {
+        let text = event
+            .content
+            .as_ref()
+            .map(Content::text)
+            .unwrap_or_default();
+
+        if event.partial {
+            self.pending
+                .entry(event.author.clone())
+                .or_default()
+                .push_str(&text);
+            return;
+        }
+
+        let streamed = self.pending.remove(&event.author).unwrap_or_default();
+        let text = if text.is_empty() { streamed } else { text };
+        if !text.is_empty() {
+            self.finals.insert(event.author.clone(), text);
+            self.last_author = Some(event.author.clone());
+        }
+    }
+
+    /// One `[author]` headed text part per sub-agent that produced text
+    pub(crate) fn into_content(mut self, authors: &[String]) -> Content {
+        let parts = authors
+            .iter()
+            .filter_map(|author| {
+                let text = self
+                    .finals
+                    .remove(author)
+                    .or_else(|| self.pending.remove(author))?;
+                Some(Part::Text {
+                    text: format!("[{}]\n{}", author, text),
+                })
+            })
+            .collect();
+
+        Content {
+            role: "model".to_string(),
+            parts,
+        }
+    }
+
+    /// The most recent final text with its author, e.g. a sub-agent's answer
+    pub(crate) fn into_last(mut self) -> Option<(String, String)> {
+        let author = self.last_author?;
+        let text = self.finals.remove(&author)?;
+        Some((author, text))
+    }
+}
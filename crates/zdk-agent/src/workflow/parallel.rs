@@ -1,3 +1,4 @@
+use super::FinalOutputs;
 use crate::builder_common::AgentBuilderCore;
 use async_stream::stream;
 use async_trait::async_trait;
@@ -12,10 +13,15 @@ use zdk_core::{Agent, Error, Event, InvocationContext, Result};
 /// attempts on a single task, such as:
 /// - Running different algorithms simultaneously
 /// - Generating multiple responses for review by a subsequent evaluation agent
+///
+/// With `merge_outputs` enabled, a final event authored by the ParallelAgent
+/// collects each sub-agent's final text into one `Content`, one part per
+/// sub-agent in the order they were added.
 pub struct ParallelAgent {
     pub(crate) name: Arc<str>,
     pub(crate) description: Arc<str>,
     pub(crate) sub_agents: Vec<Arc<dyn Agent>>,
+    pub(crate) merge_outputs: bool,
 }
 
 impl ParallelAgent {
@@ -39,6 +45,10 @@ impl Agent for ParallelAgent {
         ctx: Arc<dyn InvocationContext>,
     ) -> Box<dyn Stream<Item = Result<Event>> + Send + Unpin> {
         let sub_agents = self.sub_agents.clone();
+        let authors: Vec<String> = sub_agents.iter().map(|a| a.name().to_string()).collect();
+        let merge_outputs = self.merge_outputs;
+        let agent_name = self.name.clone();
+        let invocation_id = ctx.invocation_id().to_string();
         let (tx, mut rx) = mpsc::unbounded_channel();
 
         // Spawn a task for each sub-agent
@@ -62,9 +72,21 @@ impl Agent for ParallelAgent {
         drop(tx);
 
         Box::new(Box::pin(stream! {
+            let mut outputs = FinalOutputs::default();
+
             while let Some(result) = rx.recv().await {
+                if merge_outputs && let Ok(event) = &result {
+                    outputs.record(event);
+                }
                 yield result;
             }
+
+            if merge_outputs {
+                let mut merged = Event::new(invocation_id, agent_name.to_string());
+                merged.content = Some(outputs.into_content(&authors));
+                merged.turn_complete = true;
+                yield Ok(merged);
+            }
         }))
     }
 
@@ -77,6 +99,7 @@ impl Agent for ParallelAgent {
 pub struct ParallelAgentBuilder {
     core: AgentBuilderCore,
     sub_agents: Vec<Arc<dyn Agent>>,
+    merge_outputs: bool,
 }
 
 impl ParallelAgentBuilder {
@@ -84,6 +107,7 @@ impl ParallelAgentBuilder {
         Self {
             core: AgentBuilderCore::new(),
             sub_agents: Vec::new(),
+            merge_outputs: false,
         }
     }
 
@@ -107,6 +131,12 @@ impl ParallelAgentBuilder {
         self
     }
 
+    /// Emit a final event combining every sub-agent's final text (defaults to false)
+    pub fn merge_outputs(mut self, merge: bool) -> Self {
+        self.merge_outputs = merge;
+        self
+    }
+
     pub fn build(self) -> Result<ParallelAgent> {
         let (name, description) = self.core.validate(
             "ParallelAgent",
@@ -123,6 +153,7 @@ impl ParallelAgentBuilder {
             name: Arc::from(name),
             description: Arc::from(description),
             sub_agents: self.sub_agents,
+            merge_outputs: self.merge_outputs,
         })
     }
 }
@@ -136,7 +167,8 @@ impl Default for ParallelAgentBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::testing::MockAgent;
+    use crate::testing::{MockAgent, MockContext};
+    use zdk_core::Part;
 
     #[test]
     fn test_parallel_agent_builder() {
@@ -162,6 +194,41 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_merged_outputs_keyed_by_author() {
+        let parallel_agent = ParallelAgent::builder()
+            .name("research")
+            .sub_agent(Arc::new(MockAgent::new("pros").with_response("Fast")))
+            .sub_agent(Arc::new(MockAgent::new("cons").with_response("Costly")))
+            .merge_outputs(true)
+            .build()
+            .unwrap();
+
+        let mut stream = parallel_agent
+            .run(Arc::new(MockContext::new("Compare")))
+            .await;
+        let mut events = Vec::new();
+        while let Some(result) = stream.next().await {
+            events.push(result.unwrap());
+        }
+
+        // Both sub-agent events, then the merged one last
+        assert_eq!(events.len(), 3);
+        let merged = events.last().unwrap();
+        assert_eq!(merged.author, "research");
+        assert!(merged.is_final_response());
+
+        let parts: Vec<_> = merged
+            .content
+            .as_ref()
+            .unwrap()
+            .parts
+            .iter()
+            .filter_map(Part::as_text)
+            .collect();
+        assert_eq!(parts, ["[pros]\nFast", "[cons]\nCostly"]);
+    }
+
     #[test]
     fn test_parallel_agent_requires_sub_agents() {
         let result = ParallelAgent::builder().name("test_parallel").build();
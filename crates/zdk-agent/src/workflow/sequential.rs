@@ -9,6 +9,10 @@ use zdk_core::{Agent, Error, Event, InvocationContext, Result};
 ///
 /// Use the SequentialAgent when you want execution to occur in a fixed,
 /// strict order. This is internally implemented as a LoopAgent with max_iterations=1.
+///
+/// Each sub-agent's final answer is passed on to the sub-agents after it,
+/// appended to their conversation as context. Disable this with
+/// `forward_outputs(false)` for pipelines whose steps are independent.
 pub struct SequentialAgent {
     inner: LoopAgent,
 }
@@ -45,6 +49,7 @@ impl Agent for SequentialAgent {
 pub struct SequentialAgentBuilder {
     core: AgentBuilderCore,
     sub_agents: Vec<Arc<dyn Agent>>,
+    forward_outputs: bool,
 }
 
 impl SequentialAgentBuilder {
@@ -52,6 +57,7 @@ impl SequentialAgentBuilder {
         Self {
             core: AgentBuilderCore::new(),
             sub_agents: Vec::new(),
+            forward_outputs: true,
         }
     }
 
@@ -75,6 +81,12 @@ impl SequentialAgentBuilder {
         self
     }
 
+    /// Pass each sub-agent's final answer to the ones after it (defaults to true)
+    pub fn forward_outputs(mut self, forward: bool) -> Self {
+        self.forward_outputs = forward;
+        self
+    }
+
     pub fn build(self) -> Result<SequentialAgent> {
         let (name, description) = self.core.validate(
             "SequentialAgent",
@@ -93,6 +105,7 @@ impl SequentialAgentBuilder {
             description: Arc::from(description),
             sub_agents: self.sub_agents,
             max_iterations: 1,
+            forward_outputs: self.forward_outputs,
         };
 
         Ok(SequentialAgent { inner })
@@ -108,7 +121,49 @@ impl Default for SequentialAgentBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::testing::MockAgent;
+    use crate::testing::{MockAgent, MockContext};
+    use async_stream::stream;
+    use futures::StreamExt;
+    use zdk_core::Content;
+
+    /// Answers with the text of the conversation it was given
+    struct EchoAgent;
+
+    #[async_trait]
+    impl Agent for EchoAgent {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn description(&self) -> &str {
+            "Echoes its conversation"
+        }
+
+        async fn run(
+            &self,
+            ctx: Arc<dyn InvocationContext>,
+        ) -> Box<dyn Stream<Item = Result<Event>> + Send + Unpin> {
+            let seen: Vec<String> = ctx
+                .history()
+                .iter()
+                .chain(ctx.user_content())
+                .map(Content::text)
+                .collect();
+            let mut event = Event::new(ctx.invocation_id().to_string(), "echo".to_string());
+            event.content = Some(Content::new_model_text(seen.join(" | ")));
+            event.turn_complete = true;
+            Box::new(Box::pin(stream! { yield Ok(event); }))
+        }
+    }
+
+    async fn final_text(agent: &SequentialAgent) -> String {
+        let mut stream = agent.run(Arc::new(MockContext::new("Write a haiku"))).await;
+        let mut last = String::new();
+        while let Some(result) = stream.next().await {
+            last = result.unwrap().content.unwrap().text();
+        }
+        last
+    }
 
     #[test]
     fn test_sequential_agent_builder() {
@@ -134,6 +189,38 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_output_forwarded_to_next_step() {
+        let pipeline = SequentialAgent::builder()
+            .name("pipeline")
+            .sub_agent(Arc::new(
+                MockAgent::new("writer").with_response("Autumn moon"),
+            ))
+            .sub_agent(Arc::new(EchoAgent))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            final_text(&pipeline).await,
+            "Write a haiku | For context: [writer] said: Autumn moon"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_forwarding_can_be_disabled() {
+        let pipeline = SequentialAgent::builder()
+            .name("pipeline")
+            .sub_agent(Arc::new(
+                MockAgent::new("writer").with_response("Autumn moon"),
+            ))
+            .sub_agent(Arc::new(EchoAgent))
+            .forward_outputs(false)
+            .build()
+            .unwrap();
+
+        assert_eq!(final_text(&pipeline).await, "Write a haiku");
+    }
+
     #[test]
     fn test_sequential_agent_requires_sub_agents() {
         let result = SequentialAgent::builder().name("test_sequential").build();
@@ -0,0 +1,273 @@
+//! Behaviour shared by every `ArtifactService` backend
+//!
+//! Each backend's tests call [`run_all`] so versioning, namespacing and
+//! listing stay identical across storage implementations.
+
+use crate::*;
+use tokio::io::AsyncReadExt;
+
+const APP: &str = "test_app";
+const USER: &str = "user1";
+
+fn save_req(session_id: &str, file_name: &str, part: ArtifactPart) -> SaveRequest {
+    SaveRequest {
+        app_name: APP.to_string(),
+        user_id: USER.to_string(),
+        session_id: session_id.to_string(),
+        file_name: file_name.to_string(),
+        part,
+        version: None,
+    }
+}
+
+fn load_req(session_id: &str, file_name: &str, version: Option<i64>) -> LoadRequest {
+    LoadRequest {
+        app_name: APP.to_string(),
+        user_id: USER.to_string(),
+        session_id: session_id.to_string(),
+        file_name: file_name.to_string(),
+        version,
+    }
+}
+
+fn list_req(session_id: &str) -> ListRequest {
+    ListRequest {
+        app_name: APP.to_string(),
+        user_id: USER.to_string(),
+        session_id: session_id.to_string(),
+    }
+}
+
+fn versions_req(session_id: &str, file_name: &str) -> VersionsRequest {
+    VersionsRequest {
+        app_name: APP.to_string(),
+        user_id: USER.to_string(),
+        session_id: session_id.to_string(),
+        file_name: file_name.to_string(),
+    }
+}
+
+async fn load_text(service: &dyn ArtifactService, req: LoadRequest) -> String {
+    match service.load(req).await.unwrap().part {
+        ArtifactPart::Text(text) => text,
+        other => panic!("Expected text part, got {:?}", other),
+    }
+}
+
+/// Run every conformance check against `service`, which must start empty
+pub(crate) async fn run_all(service: &dyn ArtifactService) {
+    save_and_load(service).await;
+    versioning(service).await;
+    binary_artifact(service).await;
+    user_namespaced_artifacts(service).await;
+    delete_version_and_all(service).await;
+    list_and_list_detailed(service).await;
+    save_and_load_stream(service).await;
+}
+
+async fn save_and_load(service: &dyn ArtifactService) {
+    let resp = service
+        .save(save_req(
+            "s_load",
+            "test.txt",
+            ArtifactPart::text("Hello, world!"),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.version, 1);
+
+    assert_eq!(
+        load_text(service, load_req("s_load", "test.txt", None)).await,
+        "Hello, world!"
+    );
+    assert!(matches!(
+        service.load(load_req("s_load", "missing.txt", None)).await,
+        Err(ArtifactError::NotFound(_))
+    ));
+}
+
+async fn versioning(service: &dyn ArtifactService) {
+    for text in ["Version 1", "Version 2"] {
+        service
+            .save(save_req("s_versions", "test.txt", ArtifactPart::text(text)))
+            .await
+            .unwrap();
+    }
+
+    let versions = service
+        .versions(versions_req("s_versions", "test.txt"))
+        .await
+        .unwrap();
+    assert_eq!(versions.versions, vec![1, 2]);
+
+    assert_eq!(
+        load_text(service, load_req("s_versions", "test.txt", None)).await,
+        "Version 2"
+    );
+    assert_eq!(
+        load_text(service, load_req("s_versions", "test.txt", Some(1))).await,
+        "Version 1"
+    );
+    assert!(
+        service
+            .load(load_req("s_versions", "test.txt", Some(3)))
+            .await
+            .is_err()
+    );
+}
+
+async fn binary_artifact(service: &dyn ArtifactService) {
+    let data = vec![0u8, 1, 2, 255];
+    service
+        .save(save_req(
+            "s_binary",
+            "image.png",
+            ArtifactPart::binary("image/png", data.clone()),
+        ))
+        .await
+        .unwrap();
+
+    match service
+        .load(load_req("s_binary", "image.png", None))
+        .await
+        .unwrap()
+        .part
+    {
+        ArtifactPart::Binary {
+            mime_type,
+            data: loaded,
+        } => {
+            assert_eq!(mime_type, "image/png");
+            assert_eq!(loaded, data);
+        }
+        other => panic!("Expected binary part, got {:?}", other),
+    }
+}
+
+async fn user_namespaced_artifacts(service: &dyn ArtifactService) {
+    // A dedicated user keeps the user-scoped file out of the other checks' listings
+    let for_user = |user_id: &str, session_id: &str| LoadRequest {
+        user_id: user_id.to_string(),
+        ..load_req(session_id, "user:profile.json", None)
+    };
+
+    service
+        .save(SaveRequest {
+            user_id: "user_ns".to_string(),
+            ..save_req(
+                "s_user_a",
+                "user:profile.json",
+                ArtifactPart::text("User profile"),
+            )
+        })
+        .await
+        .unwrap();
+
+    // Visible from any session of the same user, but not to other users
+    assert_eq!(
+        load_text(service, for_user("user_ns", "s_user_b")).await,
+        "User profile"
+    );
+    assert!(service.load(for_user(USER, "s_user_b")).await.is_err());
+}
+
+async fn delete_version_and_all(service: &dyn ArtifactService) {
+    for text in ["one", "two", "three"] {
+        service
+            .save(save_req("s_delete", "test.txt", ArtifactPart::text(text)))
+            .await
+            .unwrap();
+    }
+
+    let delete = |version| DeleteRequest {
+        app_name: APP.to_string(),
+        user_id: USER.to_string(),
+        session_id: "s_delete".to_string(),
+        file_name: "test.txt".to_string(),
+        version,
+    };
+
+    service.delete(delete(Some(2))).await.unwrap();
+    let versions = service
+        .versions(versions_req("s_delete", "test.txt"))
+        .await
+        .unwrap();
+    assert_eq!(versions.versions, vec![1, 3]);
+
+    service.delete(delete(None)).await.unwrap();
+    assert!(
+        service
+            .load(load_req("s_delete", "test.txt", None))
+            .await
+            .is_err()
+    );
+
+    // Deleting a missing artifact is not an error
+    service.delete(delete(None)).await.unwrap();
+}
+
+async fn list_and_list_detailed(service: &dyn ArtifactService) {
+    for text in ["draft", "final text"] {
+        service
+            .save(save_req("s_list", "notes.txt", ArtifactPart::text(text)))
+            .await
+            .unwrap();
+    }
+    service
+        .save(save_req(
+            "s_list",
+            "image.png",
+            ArtifactPart::binary("image/png", vec![0u8; 42]),
+        ))
+        .await
+        .unwrap();
+
+    let listed = service.list(list_req("s_list")).await.unwrap();
+    assert_eq!(listed.file_names, vec!["image.png", "notes.txt"]);
+
+    assert_eq!(
+        service.list_detailed(list_req("s_list")).await.unwrap(),
+        vec![
+            ArtifactMetadata {
+                file_name: "image.png".to_string(),
+                latest_version: 1,
+                mime_type: Some("image/png".to_string()),
+                size_bytes: 42,
+            },
+            ArtifactMetadata {
+                file_name: "notes.txt".to_string(),
+                latest_version: 2,
+                mime_type: Some("text/plain".to_string()),
+                size_bytes: 10,
+            },
+        ]
+    );
+}
+
+async fn save_and_load_stream(service: &dyn ArtifactService) {
+    let data: Vec<u8> = (0..=255).cycle().take(10_000).collect();
+    let req = SaveStreamRequest {
+        app_name: APP.to_string(),
+        user_id: USER.to_string(),
+        session_id: "s_stream".to_string(),
+        file_name: "blob.bin".to_string(),
+        mime_type: "application/octet-stream".to_string(),
+        version: None,
+    };
+
+    let resp = service
+        .save_stream(req, Box::new(std::io::Cursor::new(data.clone())))
+        .await
+        .unwrap();
+    assert_eq!(resp.version, 1);
+
+    let mut loaded = service
+        .load_stream(load_req("s_stream", "blob.bin", None))
+        .await
+        .unwrap();
+    assert_eq!(loaded.mime_type, "application/octet-stream");
+
+    let mut bytes = Vec::new();
+    loaded.reader.read_to_end(&mut bytes).await.unwrap();
+    assert_eq!(bytes, data);
+}
@@ -3,6 +3,7 @@
 use crate::*;
 use async_trait::async_trait;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 use tokio::fs;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
@@ -10,8 +11,18 @@ use tokio::io::{AsyncReadExt, AsyncWriteExt};
 ///
 /// Stores artifacts as files on the local file system.
 /// Directory structure: `base_path/app_name/user_id/session_id/file_name/version`
+///
+/// Artifacts saved with `save` are stored as `{version}.json`. Artifacts saved with
+/// `save_stream` are written directly to `{version}.bin`, with the MIME type kept
+/// alongside in `{version}.mime`, so large payloads never pass through memory.
+///
+/// Every version is written to a temporary file and renamed into place once
+/// complete, so an interrupted write never becomes a visible version. Saves
+/// that fail with a transient I/O error are retried with a short backoff.
 pub struct FileSystemArtifactService {
     base_path: PathBuf,
+    max_save_retries: u32,
+    retry_backoff: Duration,
 }
 
 impl FileSystemArtifactService {
@@ -19,9 +30,18 @@ impl FileSystemArtifactService {
     pub fn new(base_path: impl Into<PathBuf>) -> Self {
         Self {
             base_path: base_path.into(),
+            max_save_retries: 3,
+            retry_backoff: Duration::from_millis(50),
         }
     }
 
+    /// Set how many times a save is retried after a transient I/O error
+    pub fn with_save_retries(mut self, max_retries: u32, backoff: Duration) -> Self {
+        self.max_save_retries = max_retries;
+        self.retry_backoff = backoff;
+        self
+    }
+
     /// Get the directory path for an artifact
     fn get_artifact_dir(
         &self,
@@ -52,6 +72,77 @@ impl FileSystemArtifactService {
         base_dir.join(format!("{}.json", version))
     }
 
+    /// Get the raw data file path for a specific version of a streamed artifact
+    fn get_binary_file(&self, base_dir: &Path, version: i64) -> PathBuf {
+        base_dir.join(format!("{}.bin", version))
+    }
+
+    /// Get the MIME type file path for a specific version of a streamed artifact
+    fn get_mime_file(&self, base_dir: &Path, version: i64) -> PathBuf {
+        base_dir.join(format!("{}.mime", version))
+    }
+
+    /// Write a file atomically: write to `{path}.tmp`, sync, then rename into place
+    async fn write_atomic(&self, path: &Path, contents: &[u8]) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        let mut tmp_name = path.as_os_str().to_owned();
+        tmp_name.push(".tmp");
+        let tmp_path = PathBuf::from(tmp_name);
+
+        let result = async {
+            let mut file = fs::File::create(&tmp_path).await?;
+            #[cfg(not(test))]
+            let write = write_and_sync(&mut file, contents);
+            #[cfg(test)]
+            let write = tests::interruptible_write(&mut file, contents);
+            write.await
+        }
+        .await;
+
+        match result {
+            Ok(()) => fs::rename(&tmp_path, path).await,
+            Err(e) => {
+                let _ = fs::remove_file(&tmp_path).await;
+                Err(e)
+            }
+        }
+    }
+
+    /// Write a file atomically, retrying on transient I/O errors
+    async fn write_with_retry(&self, path: &Path, contents: &[u8]) -> Result<()> {
+        let mut attempt = 0;
+        loop {
+            match self.write_atomic(path, contents).await {
+                Ok(()) => return Ok(()),
+                Err(e) if is_transient(&e) && attempt < self.max_save_retries => {
+                    attempt += 1;
+                    let delay = self
+                        .retry_backoff
+                        .saturating_mul(2u32.saturating_pow(attempt - 1));
+                    tokio::time::sleep(delay).await;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    /// Remove every file stored for a specific version
+    async fn remove_version_files(&self, base_dir: &Path, version: i64) -> Result<()> {
+        for path in [
+            self.get_artifact_file(base_dir, version),
+            self.get_binary_file(base_dir, version),
+            self.get_mime_file(base_dir, version),
+        ] {
+            if path.exists() {
+                fs::remove_file(&path).await?;
+            }
+        }
+        Ok(())
+    }
+
     /// Get all versions for an artifact
     async fn list_versions(&self, artifact_dir: &Path) -> Result<Vec<i64>> {
         if !artifact_dir.exists() {
@@ -63,8 +154,10 @@ impl FileSystemArtifactService {
 
         while let Some(entry) = entries.next_entry().await? {
             let path = entry.path();
-            if path.extension().and_then(|s| s.to_str()) == Some("json")
-                && let Some(stem) = path.file_stem().and_then(|s| s.to_str())
+            if matches!(
+                path.extension().and_then(|s| s.to_str()),
+                Some("json") | Some("bin")
+            ) && let Some(stem) = path.file_stem().and_then(|s| s.to_str())
                 && let Ok(version) = stem.parse::<i64>()
             {
                 versions.push(version);
@@ -72,9 +165,85 @@ impl FileSystemArtifactService {
         }
 
         versions.sort();
+        versions.dedup();
         Ok(versions)
     }
 
+    /// Resolve the requested version, falling back to the latest one
+    async fn resolve_version(&self, artifact_dir: &Path, req: &LoadRequest) -> Result<i64> {
+        if let Some(v) = req.version {
+            return Ok(v);
+        }
+
+        self.find_latest_version(artifact_dir)
+            .await?
+            .ok_or_else(|| {
+                ArtifactError::NotFound(format!(
+                    "Artifact not found: {}/{}/{}/{}",
+                    req.app_name, req.user_id, req.session_id, req.file_name
+                ))
+            })
+    }
+
+    /// Collect metadata for the latest version of every artifact under a directory
+    async fn collect_metadata(
+        &self,
+        dir: &Path,
+        metadata: &mut std::collections::BTreeMap<String, ArtifactMetadata>,
+    ) -> Result<()> {
+        if !dir.exists() {
+            return Ok(());
+        }
+
+        let mut entries = fs::read_dir(dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            if !entry.file_type().await?.is_dir() {
+                continue;
+            }
+            let Some(file_name) = entry.file_name().to_str().map(|s| s.to_string()) else {
+                continue;
+            };
+
+            let artifact_dir = entry.path();
+            let Some(version) = self.find_latest_version(&artifact_dir).await? else {
+                continue;
+            };
+
+            let binary_path = self.get_binary_file(&artifact_dir, version);
+            let (mime_type, size_bytes) = if binary_path.exists() {
+                // Streamed artifacts: size comes straight from the file system
+                let size = fs::metadata(&binary_path).await?.len();
+                (self.read_mime_type(&artifact_dir, version).await?, size)
+            } else {
+                let contents =
+                    fs::read_to_string(self.get_artifact_file(&artifact_dir, version)).await?;
+                let part: ArtifactPart = serde_json::from_str(&contents)?;
+                (part.mime_type().to_string(), part.size_bytes())
+            };
+
+            metadata.insert(
+                file_name.clone(),
+                ArtifactMetadata {
+                    file_name,
+                    latest_version: version,
+                    mime_type: Some(mime_type),
+                    size_bytes,
+                },
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Read the MIME type stored for a streamed artifact version
+    async fn read_mime_type(&self, artifact_dir: &Path, version: i64) -> Result<String> {
+        let mime_path = self.get_mime_file(artifact_dir, version);
+        if !mime_path.exists() {
+            return Ok("application/octet-stream".to_string());
+        }
+        Ok(fs::read_to_string(&mime_path).await?.trim().to_string())
+    }
+
     /// Find the latest version
     async fn find_latest_version(&self, artifact_dir: &Path) -> Result<Option<i64>> {
         let versions = self.list_versions(artifact_dir).await?;
@@ -103,12 +272,15 @@ impl ArtifactService for FileSystemArtifactService {
                 .unwrap_or(1)
         };
 
+        // Overwriting an explicit version may replace a previously streamed one
+        self.remove_version_files(&artifact_dir, next_version)
+            .await?;
+
         let file_path = self.get_artifact_file(&artifact_dir, next_version);
 
         // Serialize and write the artifact
         let json = serde_json::to_string(&req.part)?;
-        let mut file = fs::File::create(&file_path).await?;
-        file.write_all(json.as_bytes()).await?;
+        self.write_with_retry(&file_path, json.as_bytes()).await?;
 
         Ok(SaveResponse {
             version: next_version,
@@ -121,22 +293,20 @@ impl ArtifactService for FileSystemArtifactService {
         let artifact_dir =
             self.get_artifact_dir(&req.app_name, &req.user_id, &req.session_id, &req.file_name);
 
-        let version = if let Some(v) = req.version {
-            v
-        } else {
-            // Load latest version
-            self.find_latest_version(&artifact_dir)
-                .await?
-                .ok_or_else(|| {
-                    ArtifactError::NotFound(format!(
-                        "Artifact not found: {}/{}/{}/{}",
-                        req.app_name, req.user_id, req.session_id, req.file_name
-                    ))
-                })?
-        };
+        let version = self.resolve_version(&artifact_dir, &req).await?;
 
         let file_path = self.get_artifact_file(&artifact_dir, version);
 
+        // Streamed artifacts are stored raw; buffer them into a binary part
+        let binary_path = self.get_binary_file(&artifact_dir, version);
+        if !file_path.exists() && binary_path.exists() {
+            let data = fs::read(&binary_path).await?;
+            let mime_type = self.read_mime_type(&artifact_dir, version).await?;
+            return Ok(LoadResponse {
+                part: ArtifactPart::Binary { mime_type, data },
+            });
+        }
+
         if !file_path.exists() {
             return Err(ArtifactError::NotFound(format!(
                 "Artifact not found: {}/{}/{}/{} version {}",
@@ -166,10 +336,7 @@ impl ArtifactService for FileSystemArtifactService {
 
         if let Some(version) = req.version {
             // Delete specific version
-            let file_path = self.get_artifact_file(&artifact_dir, version);
-            if file_path.exists() {
-                fs::remove_file(&file_path).await?;
-            }
+            self.remove_version_files(&artifact_dir, version).await?;
         } else {
             // Delete all versions (entire directory)
             fs::remove_dir_all(&artifact_dir).await?;
@@ -226,6 +393,28 @@ impl ArtifactService for FileSystemArtifactService {
         Ok(ListResponse { file_names })
     }
 
+    async fn list_detailed(&self, req: ListRequest) -> Result<Vec<ArtifactMetadata>> {
+        req.validate()?;
+
+        let session_dir = self
+            .base_path
+            .join(&req.app_name)
+            .join(&req.user_id)
+            .join(&req.session_id);
+
+        let user_dir = self
+            .base_path
+            .join(&req.app_name)
+            .join(&req.user_id)
+            .join(USER_SCOPED_ARTIFACT_KEY);
+
+        let mut metadata = std::collections::BTreeMap::new();
+        self.collect_metadata(&session_dir, &mut metadata).await?;
+        self.collect_metadata(&user_dir, &mut metadata).await?;
+
+        Ok(metadata.into_values().collect())
+    }
+
     async fn versions(&self, req: VersionsRequest) -> Result<VersionsResponse> {
         req.validate()?;
 
@@ -236,13 +425,147 @@ impl ArtifactService for FileSystemArtifactService {
 
         Ok(VersionsResponse { versions })
     }
+
+    async fn save_stream(
+        &self,
+        req: SaveStreamRequest,
+        mut reader: ArtifactReader,
+    ) -> Result<SaveResponse> {
+        req.validate()?;
+
+        let artifact_dir =
+            self.get_artifact_dir(&req.app_name, &req.user_id, &req.session_id, &req.file_name);
+
+        // Ensure directory exists
+        fs::create_dir_all(&artifact_dir).await?;
+
+        let next_version = if let Some(version) = req.version {
+            version
+        } else {
+            // Find the current latest version and increment
+            self.find_latest_version(&artifact_dir)
+                .await?
+                .map(|v| v + 1)
+                .unwrap_or(1)
+        };
+
+        // Stream into a temporary file so a failed copy never leaves a partial version
+        let tmp_path = artifact_dir.join(format!("{}.bin.tmp", next_version));
+        let mut file = fs::File::create(&tmp_path).await?;
+        let written = match tokio::io::copy(&mut reader, &mut file).await {
+            Ok(n) => n,
+            Err(e) => {
+                drop(file);
+                let _ = fs::remove_file(&tmp_path).await;
+                return Err(e.into());
+            }
+        };
+        file.sync_all().await?;
+        drop(file);
+
+        if written == 0 {
+            fs::remove_file(&tmp_path).await?;
+            return Err(ArtifactError::InvalidPart(
+                "Stream must contain binary data".into(),
+            ));
+        }
+
+        self.remove_version_files(&artifact_dir, next_version)
+            .await?;
+        let mime_path = self.get_mime_file(&artifact_dir, next_version);
+        if let Err(e) = self
+            .write_with_retry(&mime_path, req.mime_type.as_bytes())
+            .await
+        {
+            let _ = fs::remove_file(&tmp_path).await;
+            return Err(e);
+        }
+        fs::rename(&tmp_path, self.get_binary_file(&artifact_dir, next_version)).await?;
+
+        Ok(SaveResponse {
+            version: next_version,
+        })
+    }
+
+    async fn load_stream(&self, req: LoadRequest) -> Result<LoadStreamResponse> {
+        req.validate()?;
+
+        let artifact_dir =
+            self.get_artifact_dir(&req.app_name, &req.user_id, &req.session_id, &req.file_name);
+
+        let version = self.resolve_version(&artifact_dir, &req).await?;
+
+        // Streamed artifacts are read straight from disk
+        let binary_path = self.get_binary_file(&artifact_dir, version);
+        if binary_path.exists() {
+            let file = fs::File::open(&binary_path).await?;
+            let mime_type = self.read_mime_type(&artifact_dir, version).await?;
+            return Ok(LoadStreamResponse {
+                mime_type,
+                reader: Box::new(file),
+            });
+        }
+
+        let resp = self
+            .load(LoadRequest {
+                version: Some(version),
+                ..req
+            })
+            .await?;
+        Ok(into_stream_response(resp.part))
+    }
+}
+
+/// Write the whole contents and flush them to disk
+async fn write_and_sync(file: &mut fs::File, contents: &[u8]) -> std::io::Result<()> {
+    file.write_all(contents).await?;
+    file.sync_all().await
+}
+
+/// Whether an I/O error is worth retrying
+fn is_transient(error: &std::io::Error) -> bool {
+    use std::io::ErrorKind;
+
+    matches!(
+        error.kind(),
+        ErrorKind::Interrupted
+            | ErrorKind::WouldBlock
+            | ErrorKind::TimedOut
+            | ErrorKind::StorageFull
+            | ErrorKind::ResourceBusy
+    )
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::cell::Cell;
     use tempfile::TempDir;
 
+    thread_local! {
+        /// Number of upcoming writes on this thread to interrupt
+        static FAIL_WRITES: Cell<u32> = const { Cell::new(0) };
+    }
+
+    /// [`write_and_sync`] that can be told to fail after a partial flush
+    pub(super) async fn interruptible_write(
+        file: &mut fs::File,
+        contents: &[u8],
+    ) -> std::io::Result<()> {
+        let remaining = FAIL_WRITES.get();
+        if remaining == 0 {
+            return write_and_sync(file, contents).await;
+        }
+        FAIL_WRITES.set(remaining - 1);
+
+        file.write_all(&contents[..contents.len() / 2]).await?;
+        file.flush().await?;
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Interrupted,
+            "simulated write interruption",
+        ))
+    }
+
     #[tokio::test]
     async fn test_save_and_load() {
         let temp_dir = TempDir::new().unwrap();
@@ -433,4 +756,299 @@ mod tests {
 
         assert!(service.load(load_req).await.is_err());
     }
+
+    #[tokio::test]
+    async fn test_list_detailed_mixed_session() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = FileSystemArtifactService::new(temp_dir.path());
+
+        for text in ["draft", "final text"] {
+            let save_req = SaveRequest {
+                app_name: "test_app".to_string(),
+                user_id: "user1".to_string(),
+                session_id: "session1".to_string(),
+                file_name: "notes.txt".to_string(),
+                part: ArtifactPart::text(text),
+                version: None,
+            };
+            service.save(save_req).await.unwrap();
+        }
+
+        let save_req = SaveRequest {
+            app_name: "test_app".to_string(),
+            user_id: "user1".to_string(),
+            session_id: "session1".to_string(),
+            file_name: "image.png".to_string(),
+            part: ArtifactPart::binary("image/png", vec![0u8; 42]),
+            version: None,
+        };
+        service.save(save_req).await.unwrap();
+
+        let save_req = SaveStreamRequest {
+            app_name: "test_app".to_string(),
+            user_id: "user1".to_string(),
+            session_id: "session1".to_string(),
+            file_name: "video.mp4".to_string(),
+            mime_type: "video/mp4".to_string(),
+            version: None,
+        };
+        service
+            .save_stream(save_req, Box::new(std::io::Cursor::new(vec![7u8; 1024])))
+            .await
+            .unwrap();
+
+        let list_req = ListRequest {
+            app_name: "test_app".to_string(),
+            user_id: "user1".to_string(),
+            session_id: "session1".to_string(),
+        };
+
+        let metadata = service.list_detailed(list_req).await.unwrap();
+        assert_eq!(
+            metadata,
+            vec![
+                ArtifactMetadata {
+                    file_name: "image.png".to_string(),
+                    latest_version: 1,
+                    mime_type: Some("image/png".to_string()),
+                    size_bytes: 42,
+                },
+                ArtifactMetadata {
+                    file_name: "notes.txt".to_string(),
+                    latest_version: 2,
+                    mime_type: Some("text/plain".to_string()),
+                    size_bytes: 10,
+                },
+                ArtifactMetadata {
+                    file_name: "video.mp4".to_string(),
+                    latest_version: 1,
+                    mime_type: Some("video/mp4".to_string()),
+                    size_bytes: 1024,
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_save_and_load_stream() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = FileSystemArtifactService::new(temp_dir.path());
+
+        let data: Vec<u8> = (0..=255).cycle().take(100_000).collect();
+        let save_req = SaveStreamRequest {
+            app_name: "test_app".to_string(),
+            user_id: "user1".to_string(),
+            session_id: "session1".to_string(),
+            file_name: "video.mp4".to_string(),
+            mime_type: "video/mp4".to_string(),
+            version: None,
+        };
+
+        let save_resp = service
+            .save_stream(save_req, Box::new(std::io::Cursor::new(data.clone())))
+            .await
+            .unwrap();
+        assert_eq!(save_resp.version, 1);
+
+        let load_req = LoadRequest {
+            app_name: "test_app".to_string(),
+            user_id: "user1".to_string(),
+            session_id: "session1".to_string(),
+            file_name: "video.mp4".to_string(),
+            version: None,
+        };
+
+        // Stream back from disk
+        let mut load_resp = service.load_stream(load_req.clone()).await.unwrap();
+        assert_eq!(load_resp.mime_type, "video/mp4");
+        let mut loaded = Vec::new();
+        load_resp.reader.read_to_end(&mut loaded).await.unwrap();
+        assert_eq!(loaded, data);
+
+        // The buffered API sees the same artifact
+        match service.load(load_req).await.unwrap().part {
+            ArtifactPart::Binary {
+                mime_type,
+                data: loaded,
+            } => {
+                assert_eq!(mime_type, "video/mp4");
+                assert_eq!(loaded, data);
+            }
+            _ => panic!("Expected binary part"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stream_versions_mix_with_buffered_saves() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = FileSystemArtifactService::new(temp_dir.path());
+
+        let save_req = SaveRequest {
+            app_name: "test_app".to_string(),
+            user_id: "user1".to_string(),
+            session_id: "session1".to_string(),
+            file_name: "notes.txt".to_string(),
+            part: ArtifactPart::text("Version 1"),
+            version: None,
+        };
+        service.save(save_req).await.unwrap();
+
+        let save_req = SaveStreamRequest {
+            app_name: "test_app".to_string(),
+            user_id: "user1".to_string(),
+            session_id: "session1".to_string(),
+            file_name: "notes.txt".to_string(),
+            mime_type: "text/markdown".to_string(),
+            version: None,
+        };
+        let save_resp = service
+            .save_stream(
+                save_req,
+                Box::new(std::io::Cursor::new(b"# Version 2".to_vec())),
+            )
+            .await
+            .unwrap();
+        assert_eq!(save_resp.version, 2);
+
+        let versions_req = VersionsRequest {
+            app_name: "test_app".to_string(),
+            user_id: "user1".to_string(),
+            session_id: "session1".to_string(),
+            file_name: "notes.txt".to_string(),
+        };
+        let versions_resp = service.versions(versions_req).await.unwrap();
+        assert_eq!(versions_resp.versions, vec![1, 2]);
+
+        // Text versions can be streamed too
+        let load_req = LoadRequest {
+            app_name: "test_app".to_string(),
+            user_id: "user1".to_string(),
+            session_id: "session1".to_string(),
+            file_name: "notes.txt".to_string(),
+            version: Some(1),
+        };
+        let mut load_resp = service.load_stream(load_req).await.unwrap();
+        assert_eq!(load_resp.mime_type, "text/plain");
+        let mut loaded = String::new();
+        load_resp.reader.read_to_string(&mut loaded).await.unwrap();
+        assert_eq!(loaded, "Version 1");
+    }
+
+    #[tokio::test]
+    async fn test_save_stream_rejects_empty_stream() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = FileSystemArtifactService::new(temp_dir.path());
+
+        let save_req = SaveStreamRequest {
+            app_name: "test_app".to_string(),
+            user_id: "user1".to_string(),
+            session_id: "session1".to_string(),
+            file_name: "empty.bin".to_string(),
+            mime_type: "application/octet-stream".to_string(),
+            version: None,
+        };
+
+        let result = service
+            .save_stream(save_req, Box::new(std::io::Cursor::new(Vec::new())))
+            .await;
+        assert!(matches!(result, Err(ArtifactError::InvalidPart(_))));
+    }
+
+    #[tokio::test]
+    async fn test_interrupted_save_leaves_no_partial_version() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = FileSystemArtifactService::new(temp_dir.path())
+            .with_save_retries(2, Duration::from_millis(1));
+
+        // Fail more times than the service retries
+        FAIL_WRITES.set(3);
+
+        let save_req = SaveRequest {
+            app_name: "test_app".to_string(),
+            user_id: "user1".to_string(),
+            session_id: "session1".to_string(),
+            file_name: "report.json".to_string(),
+            part: ArtifactPart::text("a fairly long artifact body"),
+            version: None,
+        };
+        assert!(service.save(save_req).await.is_err());
+
+        let versions_req = VersionsRequest {
+            app_name: "test_app".to_string(),
+            user_id: "user1".to_string(),
+            session_id: "session1".to_string(),
+            file_name: "report.json".to_string(),
+        };
+        let versions_resp = service.versions(versions_req).await.unwrap();
+        assert!(versions_resp.versions.is_empty());
+
+        // Neither a partial version nor a leftover temp file is on disk
+        let artifact_dir = service.get_artifact_dir("test_app", "user1", "session1", "report.json");
+        let mut entries = fs::read_dir(&artifact_dir).await.unwrap();
+        assert!(entries.next_entry().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_interrupted_mime_write_leaves_no_partial_version() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = FileSystemArtifactService::new(temp_dir.path())
+            .with_save_retries(1, Duration::from_millis(1));
+
+        FAIL_WRITES.set(2);
+
+        let save_req = SaveStreamRequest {
+            app_name: "test_app".to_string(),
+            user_id: "user1".to_string(),
+            session_id: "session1".to_string(),
+            file_name: "clip.mp4".to_string(),
+            mime_type: "video/mp4".to_string(),
+            version: None,
+        };
+        let result = service
+            .save_stream(save_req, Box::new(std::io::Cursor::new(vec![1u8; 64])))
+            .await;
+        assert!(result.is_err());
+
+        let artifact_dir = service.get_artifact_dir("test_app", "user1", "session1", "clip.mp4");
+        let mut entries = fs::read_dir(&artifact_dir).await.unwrap();
+        assert!(entries.next_entry().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_save_retries_transient_failure() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = FileSystemArtifactService::new(temp_dir.path())
+            .with_save_retries(3, Duration::from_millis(1));
+
+        FAIL_WRITES.set(2);
+
+        let save_req = SaveRequest {
+            app_name: "test_app".to_string(),
+            user_id: "user1".to_string(),
+            session_id: "session1".to_string(),
+            file_name: "report.json".to_string(),
+            part: ArtifactPart::text("retried body"),
+            version: None,
+        };
+        let save_resp = service.save(save_req).await.unwrap();
+        assert_eq!(save_resp.version, 1);
+
+        let load_req = LoadRequest {
+            app_name: "test_app".to_string(),
+            user_id: "user1".to_string(),
+            session_id: "session1".to_string(),
+            file_name: "report.json".to_string(),
+            version: None,
+        };
+        match service.load(load_req).await.unwrap().part {
+            ArtifactPart::Text(text) => assert_eq!(text, "retried body"),
+            _ => panic!("Expected text part"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_conformance() {
+        let temp_dir = TempDir::new().unwrap();
+        crate::conformance::run_all(&FileSystemArtifactService::new(temp_dir.path())).await;
+    }
 }
@@ -6,21 +6,39 @@
 //!
 //! ## Features
 //!
-//! - **Multiple Storage Backends**: In-memory, file system, and cloud storage
+//! - **Multiple Storage Backends**: In-memory, file system, SQLite (`sqlite` feature), and cloud storage
 //! - **Versioning**: Automatic version tracking for all artifacts
 //! - **User Namespacing**: Special "user:" prefix for user-scoped artifacts
 //! - **Async/Await**: Fully asynchronous API using tokio
+//! - **Streaming**: `save_stream`/`load_stream` for large binary payloads
+//!
+//! ## Large Files
+//!
+//! `ArtifactPart::Binary` holds the whole payload in memory. For files larger than a
+//! few megabytes prefer [`ArtifactService::save_stream`] and
+//! [`ArtifactService::load_stream`], which let backends such as
+//! [`FileSystemArtifactService`] move data to and from disk without buffering it.
 
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use tokio::io::AsyncRead;
 
+#[cfg(test)]
+mod conformance;
 mod filesystem;
 mod memory;
+mod scoped;
 mod service;
+#[cfg(feature = "sqlite")]
+mod sqlite;
 
 pub use filesystem::FileSystemArtifactService;
 pub use memory::InMemoryArtifactService;
+pub use scoped::ScopedArtifactStore;
 pub use service::*;
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteArtifactService;
+pub use zdk_core::ArtifactPart;
 
 /// Errors that can occur during artifact operations
 #[derive(Debug, Error)]
@@ -47,62 +65,16 @@ pub enum ArtifactError {
 /// Result type for artifact operations
 pub type Result<T> = std::result::Result<T, ArtifactError>;
 
-/// Represents an artifact part (either text or binary data)
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(untagged)]
-pub enum ArtifactPart {
-    /// Text content
-    Text(String),
-    /// Binary data with MIME type
-    Binary {
-        mime_type: String,
-        #[serde(with = "base64_serde")]
-        data: Vec<u8>,
-    },
-}
-
-mod base64_serde {
-    use base64::{Engine as _, engine::general_purpose};
-    use serde::{Deserialize, Deserializer, Serializer};
+/// Convert a part into a stream response over its bytes
+pub(crate) fn into_stream_response(part: ArtifactPart) -> LoadStreamResponse {
+    let (mime_type, data) = match part {
+        ArtifactPart::Text(text) => ("text/plain".to_string(), text.into_bytes()),
+        ArtifactPart::Binary { mime_type, data } => (mime_type, data),
+    };
 
-    pub fn serialize<S>(data: &Vec<u8>, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        serializer.serialize_str(&general_purpose::STANDARD.encode(data))
-    }
-
-    pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        let s = String::deserialize(deserializer)?;
-        general_purpose::STANDARD
-            .decode(&s)
-            .map_err(serde::de::Error::custom)
-    }
-}
-
-impl ArtifactPart {
-    /// Create a text artifact part
-    pub fn text(content: impl Into<String>) -> Self {
-        Self::Text(content.into())
-    }
-
-    /// Create a binary artifact part
-    pub fn binary(mime_type: impl Into<String>, data: Vec<u8>) -> Self {
-        Self::Binary {
-            mime_type: mime_type.into(),
-            data,
-        }
-    }
-
-    /// Check if this part is empty
-    pub fn is_empty(&self) -> bool {
-        match self {
-            Self::Text(s) => s.is_empty(),
-            Self::Binary { data, .. } => data.is_empty(),
-        }
+    LoadStreamResponse {
+        mime_type,
+        reader: Box::new(std::io::Cursor::new(data)),
     }
 }
 
@@ -199,6 +171,66 @@ pub struct LoadResponse {
     pub part: ArtifactPart,
 }
 
+/// Boxed async reader used for streaming artifact data
+pub type ArtifactReader = Box<dyn AsyncRead + Send + Unpin>;
+
+/// Request to save a binary artifact from a stream
+#[derive(Debug, Clone)]
+pub struct SaveStreamRequest {
+    pub app_name: String,
+    pub user_id: String,
+    pub session_id: String,
+    pub file_name: String,
+    pub mime_type: String,
+    /// Optional: specific version to save (if unset, creates new version)
+    pub version: Option<i64>,
+}
+
+impl SaveStreamRequest {
+    /// Validate the save stream request
+    pub fn validate(&self) -> Result<()> {
+        let mut missing = Vec::new();
+
+        if self.app_name.is_empty() {
+            missing.push("app_name");
+        }
+        if self.user_id.is_empty() {
+            missing.push("user_id");
+        }
+        if self.session_id.is_empty() {
+            missing.push("session_id");
+        }
+        if self.file_name.is_empty() {
+            missing.push("file_name");
+        }
+        if self.mime_type.is_empty() {
+            missing.push("mime_type");
+        }
+
+        if !missing.is_empty() {
+            return Err(ArtifactError::MissingField(missing.join(", ")));
+        }
+
+        Ok(())
+    }
+}
+
+/// Response from loading an artifact as a stream
+pub struct LoadStreamResponse {
+    /// MIME type of the data (`text/plain` for text artifacts)
+    pub mime_type: String,
+    /// Reader over the artifact bytes
+    pub reader: ArtifactReader,
+}
+
+impl std::fmt::Debug for LoadStreamResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LoadStreamResponse")
+            .field("mime_type", &self.mime_type)
+            .finish_non_exhaustive()
+    }
+}
+
 /// Request to delete an artifact
 #[derive(Debug, Clone)]
 pub struct DeleteRequest {
@@ -273,6 +305,17 @@ pub struct ListResponse {
     pub file_names: Vec<String>,
 }
 
+/// Metadata describing the latest version of an artifact
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtifactMetadata {
+    pub file_name: String,
+    pub latest_version: i64,
+    /// MIME type of the latest version (`text/plain` for text artifacts)
+    pub mime_type: Option<String>,
+    /// Payload size of the latest version in bytes
+    pub size_bytes: u64,
+}
+
 /// Request to list versions of an artifact
 #[derive(Debug, Clone)]
 pub struct VersionsRequest {
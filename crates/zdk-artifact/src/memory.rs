@@ -4,6 +4,7 @@ use crate::*;
 use async_trait::async_trait;
 use std::collections::BTreeMap;
 use std::sync::{Arc, RwLock};
+use tokio::io::AsyncReadExt;
 
 /// Artifact key for unique identification
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -268,6 +269,34 @@ impl ArtifactService for InMemoryArtifactService {
         Ok(ListResponse { file_names })
     }
 
+    async fn list_detailed(&self, req: ListRequest) -> Result<Vec<ArtifactMetadata>> {
+        req.validate()?;
+
+        let artifacts = self.artifacts.read().unwrap();
+
+        // Keys are ordered by version, so the last entry per file is the latest
+        let mut latest: BTreeMap<String, ArtifactMetadata> = BTreeMap::new();
+
+        for (key, part) in artifacts.iter() {
+            if key.app_name == req.app_name
+                && key.user_id == req.user_id
+                && (key.session_id == req.session_id || key.session_id == USER_SCOPED_ARTIFACT_KEY)
+            {
+                latest.insert(
+                    key.file_name.clone(),
+                    ArtifactMetadata {
+                        file_name: key.file_name.clone(),
+                        latest_version: key.version,
+                        mime_type: Some(part.mime_type().to_string()),
+                        size_bytes: part.size_bytes(),
+                    },
+                );
+            }
+        }
+
+        Ok(latest.into_values().collect())
+    }
+
     async fn versions(&self, req: VersionsRequest) -> Result<VersionsResponse> {
         req.validate()?;
 
@@ -299,6 +328,33 @@ impl ArtifactService for InMemoryArtifactService {
 
         Ok(VersionsResponse { versions })
     }
+
+    async fn save_stream(
+        &self,
+        req: SaveStreamRequest,
+        mut reader: ArtifactReader,
+    ) -> Result<SaveResponse> {
+        req.validate()?;
+
+        // Everything lives in memory anyway, so buffer the stream and reuse `save`
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).await?;
+
+        self.save(SaveRequest {
+            app_name: req.app_name,
+            user_id: req.user_id,
+            session_id: req.session_id,
+            file_name: req.file_name,
+            part: ArtifactPart::binary(req.mime_type, data),
+            version: req.version,
+        })
+        .await
+    }
+
+    async fn load_stream(&self, req: LoadRequest) -> Result<LoadStreamResponse> {
+        let resp = self.load(req).await?;
+        Ok(into_stream_response(resp.part))
+    }
 }
 
 #[cfg(test)]
@@ -502,4 +558,97 @@ mod tests {
 
         assert!(service.load(load_req).await.is_err());
     }
+
+    #[tokio::test]
+    async fn test_list_detailed_mixed_session() {
+        let service = InMemoryArtifactService::new();
+
+        for text in ["draft", "final text"] {
+            let save_req = SaveRequest {
+                app_name: "test_app".to_string(),
+                user_id: "user1".to_string(),
+                session_id: "session1".to_string(),
+                file_name: "notes.txt".to_string(),
+                part: ArtifactPart::text(text),
+                version: None,
+            };
+            service.save(save_req).await.unwrap();
+        }
+
+        let save_req = SaveRequest {
+            app_name: "test_app".to_string(),
+            user_id: "user1".to_string(),
+            session_id: "session1".to_string(),
+            file_name: "image.png".to_string(),
+            part: ArtifactPart::binary("image/png", vec![0u8; 42]),
+            version: None,
+        };
+        service.save(save_req).await.unwrap();
+
+        let list_req = ListRequest {
+            app_name: "test_app".to_string(),
+            user_id: "user1".to_string(),
+            session_id: "session1".to_string(),
+        };
+
+        let metadata = service.list_detailed(list_req).await.unwrap();
+        assert_eq!(
+            metadata,
+            vec![
+                ArtifactMetadata {
+                    file_name: "image.png".to_string(),
+                    latest_version: 1,
+                    mime_type: Some("image/png".to_string()),
+                    size_bytes: 42,
+                },
+                ArtifactMetadata {
+                    file_name: "notes.txt".to_string(),
+                    latest_version: 2,
+                    mime_type: Some("text/plain".to_string()),
+                    size_bytes: 10,
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_save_and_load_stream() {
+        let service = InMemoryArtifactService::new();
+
+        let data: Vec<u8> = (0..=255).cycle().take(10_000).collect();
+        let save_req = SaveStreamRequest {
+            app_name: "test_app".to_string(),
+            user_id: "user1".to_string(),
+            session_id: "session1".to_string(),
+            file_name: "blob.bin".to_string(),
+            mime_type: "application/octet-stream".to_string(),
+            version: None,
+        };
+
+        let save_resp = service
+            .save_stream(save_req, Box::new(std::io::Cursor::new(data.clone())))
+            .await
+            .unwrap();
+        assert_eq!(save_resp.version, 1);
+
+        let load_req = LoadRequest {
+            app_name: "test_app".to_string(),
+            user_id: "user1".to_string(),
+            session_id: "session1".to_string(),
+            file_name: "blob.bin".to_string(),
+            version: None,
+        };
+
+        let mut load_resp = service.load_stream(load_req).await.unwrap();
+        assert_eq!(load_resp.mime_type, "application/octet-stream");
+
+        let mut loaded = Vec::new();
+        load_resp.reader.read_to_end(&mut loaded).await.unwrap();
+        assert_eq!(loaded, data);
+    }
+
+    #[tokio::test]
+    async fn test_conformance() {
+        crate::conformance::run_all(&InMemoryArtifactService::new()).await;
+    }
 }
@@ -0,0 +1,106 @@
+//! Artifact service scoped to a single session
+
+use crate::*;
+use async_trait::async_trait;
+use std::sync::Arc;
+use zdk_core::ArtifactStore;
+
+/// [`ArtifactStore`] that saves to and loads from one session of an [`ArtifactService`]
+///
+/// The runner hands one of these to each invocation so tools can store the
+/// files they produce without knowing the app, user or session.
+#[derive(Clone)]
+pub struct ScopedArtifactStore {
+    service: Arc<dyn ArtifactService>,
+    app_name: String,
+    user_id: String,
+    session_id: String,
+}
+
+impl ScopedArtifactStore {
+    /// Scope `service` to the given app, user and session
+    pub fn new(
+        service: Arc<dyn ArtifactService>,
+        app_name: impl Into<String>,
+        user_id: impl Into<String>,
+        session_id: impl Into<String>,
+    ) -> Self {
+        Self {
+            service,
+            app_name: app_name.into(),
+            user_id: user_id.into(),
+            session_id: session_id.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl ArtifactStore for ScopedArtifactStore {
+    async fn save_artifact(&self, file_name: &str, part: ArtifactPart) -> zdk_core::Result<i64> {
+        let response = self
+            .service
+            .save(SaveRequest {
+                app_name: self.app_name.clone(),
+                user_id: self.user_id.clone(),
+                session_id: self.session_id.clone(),
+                file_name: file_name.to_string(),
+                part,
+                version: None,
+            })
+            .await
+            .map_err(|e| zdk_core::Error::ArtifactError(e.to_string()))?;
+        Ok(response.version)
+    }
+
+    async fn load_artifact(
+        &self,
+        file_name: &str,
+        version: Option<i64>,
+    ) -> zdk_core::Result<ArtifactPart> {
+        let response = self
+            .service
+            .load(LoadRequest {
+                app_name: self.app_name.clone(),
+                user_id: self.user_id.clone(),
+                session_id: self.session_id.clone(),
+                file_name: file_name.to_string(),
+                version,
+            })
+            .await
+            .map_err(|e| zdk_core::Error::ArtifactError(e.to_string()))?;
+        Ok(response.part)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_scoped_store_round_trip() {
+        let service = Arc::new(InMemoryArtifactService::new());
+        let store = ScopedArtifactStore::new(service.clone(), "app", "user", "session");
+
+        let version = store
+            .save_artifact("notes.txt", ArtifactPart::text("hello"))
+            .await
+            .unwrap();
+        assert_eq!(version, 1);
+
+        // Visible through the underlying service under the same scope
+        let loaded = service
+            .load(LoadRequest {
+                app_name: "app".to_string(),
+                user_id: "user".to_string(),
+                session_id: "session".to_string(),
+                file_name: "notes.txt".to_string(),
+                version: None,
+            })
+            .await
+            .unwrap();
+        assert!(matches!(loaded.part, ArtifactPart::Text(ref text) if text == "hello"));
+
+        let err = store.load_artifact("missing.txt", None).await.unwrap_err();
+        assert!(matches!(err, zdk_core::Error::ArtifactError(_)));
+    }
+}
@@ -8,6 +8,9 @@ use async_trait::async_trait;
 /// An artifact is a file identified by an application name, a user ID, a session ID,
 /// and a filename. The service provides basic storage operations for artifacts,
 /// such as Save, Load, Delete, and List. It also supports versioning of artifacts.
+///
+/// `save`/`load` move whole payloads through memory. For large binary files prefer
+/// `save_stream`/`load_stream`, which backends can implement without buffering.
 #[async_trait]
 pub trait ArtifactService: Send + Sync {
     /// Save an artifact to storage.
@@ -24,8 +27,29 @@ pub trait ArtifactService: Send + Sync {
     /// List all artifact filenames within a session.
     async fn list(&self, req: ListRequest) -> Result<ListResponse>;
 
+    /// List all artifacts within a session along with metadata for their latest version.
+    ///
+    /// Results are sorted by file name, matching `list`.
+    async fn list_detailed(&self, req: ListRequest) -> Result<Vec<ArtifactMetadata>>;
+
     /// List all versions of an artifact.
     async fn versions(&self, req: VersionsRequest) -> Result<VersionsResponse>;
+
+    /// Save a binary artifact by reading it from `reader`.
+    ///
+    /// Preferred over `save` for large files. Reading an empty stream is an error,
+    /// mirroring the empty-part check in `save`.
+    async fn save_stream(
+        &self,
+        req: SaveStreamRequest,
+        reader: ArtifactReader,
+    ) -> Result<SaveResponse>;
+
+    /// Load an artifact as a stream of bytes.
+    ///
+    /// Preferred over `load` for large files. Text artifacts are returned as
+    /// UTF-8 bytes with a `text/plain` MIME type.
+    async fn load_stream(&self, req: LoadRequest) -> Result<LoadStreamResponse>;
 }
 
 /// Check if a filename has a user namespace prefix
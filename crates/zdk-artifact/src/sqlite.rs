@@ -0,0 +1,373 @@
+//! SQLite artifact service implementation
+
+use crate::*;
+use async_trait::async_trait;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::{Pool, Row, Sqlite};
+use std::path::Path;
+use tokio::io::AsyncReadExt;
+
+/// SQL for creating the artifacts table
+const CREATE_ARTIFACTS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS artifacts (
+    app_name TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    session_id TEXT NOT NULL,
+    file_name TEXT NOT NULL,
+    version INTEGER NOT NULL,
+    is_text INTEGER NOT NULL,
+    mime_type TEXT NOT NULL,
+    data BLOB NOT NULL,
+    PRIMARY KEY (app_name, user_id, session_id, file_name, version)
+);
+"#;
+
+/// SQLite artifact service implementation.
+///
+/// Stores every artifact version as a row in a single database file, which is
+/// easy to copy or back up. Versioning and `user:` namespacing behave exactly
+/// like [`InMemoryArtifactService`] and [`FileSystemArtifactService`].
+///
+/// Rows hold the whole payload, so `save_stream` buffers the stream before
+/// inserting it.
+#[derive(Clone)]
+pub struct SqliteArtifactService {
+    pool: Pool<Sqlite>,
+}
+
+impl SqliteArtifactService {
+    /// Open (or create) the artifact database at `path`
+    pub async fn new(path: impl AsRef<Path>) -> Result<Self> {
+        let options = SqliteConnectOptions::new()
+            .filename(path)
+            .create_if_missing(true);
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(5)
+            .connect_with(options)
+            .await
+            .map_err(db_error)?;
+
+        Self::from_pool(pool).await
+    }
+
+    /// Create from an existing pool, creating the artifacts table if needed
+    pub async fn from_pool(pool: Pool<Sqlite>) -> Result<Self> {
+        sqlx::query(CREATE_ARTIFACTS_TABLE)
+            .execute(&pool)
+            .await
+            .map_err(db_error)?;
+
+        Ok(Self { pool })
+    }
+
+    /// Insert a version, allocating the next version number when none is given
+    async fn insert(
+        &self,
+        app_name: &str,
+        user_id: &str,
+        session_id: &str,
+        file_name: &str,
+        version: Option<i64>,
+        part: ArtifactPart,
+    ) -> Result<SaveResponse> {
+        let session_id = scoped_session_id(session_id, file_name);
+        let (is_text, mime_type, data) = match part {
+            ArtifactPart::Text(text) => (true, "text/plain".to_string(), text.into_bytes()),
+            ArtifactPart::Binary { mime_type, data } => (false, mime_type, data),
+        };
+
+        // Allocate the version inside the insert so concurrent saves cannot collide
+        let version: i64 = sqlx::query_scalar(
+            r#"
+            INSERT OR REPLACE INTO artifacts
+                (app_name, user_id, session_id, file_name, version, is_text, mime_type, data)
+            VALUES (?, ?, ?, ?, COALESCE(?, (
+                SELECT COALESCE(MAX(version), 0) + 1 FROM artifacts
+                WHERE app_name = ? AND user_id = ? AND session_id = ? AND file_name = ?
+            )), ?, ?, ?)
+            RETURNING version
+            "#,
+        )
+        .bind(app_name)
+        .bind(user_id)
+        .bind(session_id)
+        .bind(file_name)
+        .bind(version)
+        .bind(app_name)
+        .bind(user_id)
+        .bind(session_id)
+        .bind(file_name)
+        .bind(is_text)
+        .bind(mime_type)
+        .bind(data)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(SaveResponse { version })
+    }
+}
+
+/// Session ID under which an artifact is stored, honouring the `user:` namespace
+fn scoped_session_id<'a>(session_id: &'a str, file_name: &str) -> &'a str {
+    if file_has_user_namespace(file_name) {
+        USER_SCOPED_ARTIFACT_KEY
+    } else {
+        session_id
+    }
+}
+
+fn db_error(e: sqlx::Error) -> ArtifactError {
+    ArtifactError::Other(format!("Database error: {}", e))
+}
+
+#[async_trait]
+impl ArtifactService for SqliteArtifactService {
+    async fn save(&self, req: SaveRequest) -> Result<SaveResponse> {
+        req.validate()?;
+
+        self.insert(
+            &req.app_name,
+            &req.user_id,
+            &req.session_id,
+            &req.file_name,
+            req.version,
+            req.part,
+        )
+        .await
+    }
+
+    async fn load(&self, req: LoadRequest) -> Result<LoadResponse> {
+        req.validate()?;
+
+        let session_id = scoped_session_id(&req.session_id, &req.file_name);
+
+        let row = sqlx::query(
+            r#"
+            SELECT is_text, mime_type, data FROM artifacts
+            WHERE app_name = ? AND user_id = ? AND session_id = ? AND file_name = ?
+                AND (? IS NULL OR version = ?)
+            ORDER BY version DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(&req.app_name)
+        .bind(&req.user_id)
+        .bind(session_id)
+        .bind(&req.file_name)
+        .bind(req.version)
+        .bind(req.version)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        let Some(row) = row else {
+            let mut message = format!(
+                "Artifact not found: {}/{}/{}/{}",
+                req.app_name, req.user_id, req.session_id, req.file_name
+            );
+            if let Some(version) = req.version {
+                message.push_str(&format!(" version {}", version));
+            }
+            return Err(ArtifactError::NotFound(message));
+        };
+
+        let data: Vec<u8> = row.get("data");
+        let part = if row.get::<bool, _>("is_text") {
+            let text = String::from_utf8(data).map_err(|e| {
+                ArtifactError::Other(format!("Invalid UTF-8 in text artifact: {}", e))
+            })?;
+            ArtifactPart::Text(text)
+        } else {
+            ArtifactPart::Binary {
+                mime_type: row.get("mime_type"),
+                data,
+            }
+        };
+
+        Ok(LoadResponse { part })
+    }
+
+    async fn delete(&self, req: DeleteRequest) -> Result<()> {
+        req.validate()?;
+
+        let session_id = scoped_session_id(&req.session_id, &req.file_name);
+
+        sqlx::query(
+            r#"
+            DELETE FROM artifacts
+            WHERE app_name = ? AND user_id = ? AND session_id = ? AND file_name = ?
+                AND (? IS NULL OR version = ?)
+            "#,
+        )
+        .bind(&req.app_name)
+        .bind(&req.user_id)
+        .bind(session_id)
+        .bind(&req.file_name)
+        .bind(req.version)
+        .bind(req.version)
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(())
+    }
+
+    async fn list(&self, req: ListRequest) -> Result<ListResponse> {
+        req.validate()?;
+
+        let file_names = sqlx::query_scalar(
+            r#"
+            SELECT DISTINCT file_name FROM artifacts
+            WHERE app_name = ? AND user_id = ? AND (session_id = ? OR session_id = ?)
+            ORDER BY file_name
+            "#,
+        )
+        .bind(&req.app_name)
+        .bind(&req.user_id)
+        .bind(&req.session_id)
+        .bind(USER_SCOPED_ARTIFACT_KEY)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(ListResponse { file_names })
+    }
+
+    async fn list_detailed(&self, req: ListRequest) -> Result<Vec<ArtifactMetadata>> {
+        req.validate()?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT file_name, version, mime_type, length(data) AS size_bytes
+            FROM artifacts AS a
+            WHERE app_name = ? AND user_id = ? AND (session_id = ? OR session_id = ?)
+                AND version = (
+                    SELECT MAX(version) FROM artifacts AS b
+                    WHERE b.app_name = a.app_name AND b.user_id = a.user_id
+                        AND b.session_id = a.session_id AND b.file_name = a.file_name
+                )
+            ORDER BY file_name
+            "#,
+        )
+        .bind(&req.app_name)
+        .bind(&req.user_id)
+        .bind(&req.session_id)
+        .bind(USER_SCOPED_ARTIFACT_KEY)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(rows
+            .iter()
+            .map(|row| ArtifactMetadata {
+                file_name: row.get("file_name"),
+                latest_version: row.get("version"),
+                mime_type: Some(row.get("mime_type")),
+                size_bytes: row.get::<i64, _>("size_bytes") as u64,
+            })
+            .collect())
+    }
+
+    async fn versions(&self, req: VersionsRequest) -> Result<VersionsResponse> {
+        req.validate()?;
+
+        let session_id = scoped_session_id(&req.session_id, &req.file_name);
+
+        let versions = sqlx::query_scalar(
+            r#"
+            SELECT version FROM artifacts
+            WHERE app_name = ? AND user_id = ? AND session_id = ? AND file_name = ?
+            ORDER BY version
+            "#,
+        )
+        .bind(&req.app_name)
+        .bind(&req.user_id)
+        .bind(session_id)
+        .bind(&req.file_name)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(VersionsResponse { versions })
+    }
+
+    async fn save_stream(
+        &self,
+        req: SaveStreamRequest,
+        mut reader: ArtifactReader,
+    ) -> Result<SaveResponse> {
+        req.validate()?;
+
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).await?;
+
+        self.save(SaveRequest {
+            app_name: req.app_name,
+            user_id: req.user_id,
+            session_id: req.session_id,
+            file_name: req.file_name,
+            part: ArtifactPart::binary(req.mime_type, data),
+            version: req.version,
+        })
+        .await
+    }
+
+    async fn load_stream(&self, req: LoadRequest) -> Result<LoadStreamResponse> {
+        let resp = self.load(req).await?;
+        Ok(into_stream_response(resp.part))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_conformance() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = SqliteArtifactService::new(temp_dir.path().join("artifacts.db"))
+            .await
+            .unwrap();
+
+        crate::conformance::run_all(&service).await;
+    }
+
+    #[tokio::test]
+    async fn test_persists_across_reopen() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("artifacts.db");
+
+        let service = SqliteArtifactService::new(&path).await.unwrap();
+        service
+            .save(SaveRequest {
+                app_name: "test_app".to_string(),
+                user_id: "user1".to_string(),
+                session_id: "session1".to_string(),
+                file_name: "test.txt".to_string(),
+                part: ArtifactPart::text("Persisted"),
+                version: None,
+            })
+            .await
+            .unwrap();
+        drop(service);
+
+        let reopened = SqliteArtifactService::new(&path).await.unwrap();
+        let resp = reopened
+            .load(LoadRequest {
+                app_name: "test_app".to_string(),
+                user_id: "user1".to_string(),
+                session_id: "session1".to_string(),
+                file_name: "test.txt".to_string(),
+                version: None,
+            })
+            .await
+            .unwrap();
+        match resp.part {
+            ArtifactPart::Text(text) => assert_eq!(text, "Persisted"),
+            _ => panic!("Expected text part"),
+        }
+    }
+}
@@ -0,0 +1,93 @@
+//! Artifact content and session-scoped artifact storage
+
+use crate::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// Represents an artifact part (either text or binary data)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ArtifactPart {
+    /// Text content
+    Text(String),
+    /// Binary data with MIME type
+    Binary {
+        mime_type: String,
+        #[serde(with = "base64_serde")]
+        data: Vec<u8>,
+    },
+}
+
+mod base64_serde {
+    use base64::{Engine as _, engine::general_purpose};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(data: &Vec<u8>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&general_purpose::STANDARD.encode(data))
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        general_purpose::STANDARD
+            .decode(&s)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+impl ArtifactPart {
+    /// Create a text artifact part
+    pub fn text(content: impl Into<String>) -> Self {
+        Self::Text(content.into())
+    }
+
+    /// Create a binary artifact part
+    pub fn binary(mime_type: impl Into<String>, data: Vec<u8>) -> Self {
+        Self::Binary {
+            mime_type: mime_type.into(),
+            data,
+        }
+    }
+
+    /// Check if this part is empty
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Self::Text(s) => s.is_empty(),
+            Self::Binary { data, .. } => data.is_empty(),
+        }
+    }
+
+    /// MIME type of this part (`text/plain` for text)
+    pub fn mime_type(&self) -> &str {
+        match self {
+            Self::Text(_) => "text/plain",
+            Self::Binary { mime_type, .. } => mime_type,
+        }
+    }
+
+    /// Size of the part's payload in bytes
+    pub fn size_bytes(&self) -> u64 {
+        match self {
+            Self::Text(s) => s.len() as u64,
+            Self::Binary { data, .. } => data.len() as u64,
+        }
+    }
+}
+
+/// Artifact storage scoped to the app, user and session of one invocation
+///
+/// Implemented on top of an artifact service by `zdk-artifact` and handed to
+/// tools through their [`ToolContext`](crate::ToolContext).
+#[async_trait]
+pub trait ArtifactStore: Send + Sync {
+    /// Save `part` as a new version of `file_name`, returning the version
+    async fn save_artifact(&self, file_name: &str, part: ArtifactPart) -> Result<i64>;
+
+    /// Load `file_name` at `version`, or the latest version if unset
+    async fn load_artifact(&self, file_name: &str, version: Option<i64>) -> Result<ArtifactPart>;
+}
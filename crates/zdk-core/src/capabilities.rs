@@ -3,6 +3,7 @@
 //! Defines types for different provider capabilities: embeddings, transcription,
 //! image generation, and audio generation.
 
+use crate::{Error, Result};
 use serde::{Deserialize, Serialize};
 
 /// Embedding vector result
@@ -20,6 +21,33 @@ impl EmbeddingVector {
         let dimensions = vector.len();
         Self { vector, dimensions }
     }
+
+    /// Cosine similarity with `other`, from -1.0 to 1.0
+    ///
+    /// Fails when the dimensions differ, e.g. for vectors from different
+    /// embedding models. A zero vector has similarity 0.0 with everything.
+    pub fn cosine_similarity(&self, other: &EmbeddingVector) -> Result<f32> {
+        if self.vector.len() != other.vector.len() {
+            return Err(Error::DimensionMismatch {
+                left: self.vector.len(),
+                right: other.vector.len(),
+            });
+        }
+
+        let dot: f32 = self
+            .vector
+            .iter()
+            .zip(&other.vector)
+            .map(|(a, b)| a * b)
+            .sum();
+        let magnitude = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+        let magnitudes = magnitude(&self.vector) * magnitude(&other.vector);
+        if magnitudes == 0.0 {
+            return Ok(0.0);
+        }
+
+        Ok(dot / magnitudes)
+    }
 }
 
 /// Audio input for transcription
@@ -31,6 +59,35 @@ pub struct AudioInput {
     pub format: String,
     /// Optional language hint for transcription
     pub language: Option<String>,
+    /// Request segment timestamps, at the cost of a larger response
+    pub include_segments: bool,
+}
+
+impl AudioInput {
+    /// Extract audio from a content part created with [`Part::audio`](crate::Part::audio)
+    ///
+    /// Returns `None` if the part is not inline audio data.
+    pub fn from_part(part: &crate::Part) -> Option<crate::Result<Self>> {
+        let crate::Part::InlineData { inline_data } = part else {
+            return None;
+        };
+        let subtype = inline_data.mime_type.strip_prefix("audio/")?;
+
+        let format = match subtype {
+            "mpeg" | "mp3" => "mp3",
+            "wav" | "x-wav" | "wave" => "wav",
+            "mp4" | "m4a" | "x-m4a" => "m4a",
+            other => other,
+        }
+        .to_string();
+
+        Some(inline_data.decode().map(|data| Self {
+            data,
+            format,
+            language: None,
+            include_segments: false,
+        }))
+    }
 }
 
 /// Transcription result
@@ -82,7 +139,7 @@ pub struct ImageResult {
 /// A single generated image
 #[derive(Debug, Clone)]
 pub struct GeneratedImage {
-    /// Image data as PNG bytes
+    /// Image data as PNG bytes (empty when the provider only returned a URL)
     pub data: Vec<u8>,
     /// Optional URL if image is hosted
     pub url: Option<String>,
@@ -99,6 +156,8 @@ pub struct AudioRequest {
     pub voice: Option<String>,
     /// Speed of speech (0.25 to 4.0, 1.0 is normal)
     pub speed: Option<f32>,
+    /// Output format (e.g., "mp3", "opus"); provider default if unset
+    pub format: Option<String>,
 }
 
 /// Audio generation result
@@ -108,4 +167,52 @@ pub struct AudioResult {
     pub data: Vec<u8>,
     /// Audio format (e.g., "mp3", "opus", "aac", "flac")
     pub format: String,
+    /// MIME type of the audio data (e.g., "audio/mpeg")
+    pub mime_type: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cosine_similarity() {
+        let a = EmbeddingVector::new(vec![0.3, -1.2, 2.0]);
+        let same = a.cosine_similarity(&a.clone()).unwrap();
+        assert!((same - 1.0).abs() < 1e-6);
+
+        let x = EmbeddingVector::new(vec![1.0, 0.0]);
+        let y = EmbeddingVector::new(vec![0.0, 2.5]);
+        assert_eq!(x.cosine_similarity(&y).unwrap(), 0.0);
+
+        let zero = EmbeddingVector::new(vec![0.0, 0.0]);
+        assert_eq!(x.cosine_similarity(&zero).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn test_audio_input_from_part() {
+        let part = crate::Part::audio("audio/x-wav", [1u8, 2, 3]);
+        let audio = AudioInput::from_part(&part).unwrap().unwrap();
+        assert_eq!(audio.data, vec![1, 2, 3]);
+        assert_eq!(audio.format, "wav");
+        assert_eq!(audio.language, None);
+
+        let image = crate::Part::inline_data("image/png", [1u8, 2, 3]);
+        assert!(AudioInput::from_part(&image).is_none());
+        assert!(AudioInput::from_part(&crate::Part::Text { text: "hi".into() }).is_none());
+    }
+
+    #[test]
+    fn test_cosine_similarity_rejects_dimension_mismatch() {
+        let a = EmbeddingVector::new(vec![1.0, 0.0, 0.0]);
+        let b = EmbeddingVector::new(vec![1.0, 0.0]);
+
+        assert_eq!(a.dimensions, 3);
+        let err = a.cosine_similarity(&b).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::DimensionMismatch { left: 3, right: 2 }
+        ));
+        assert!(err.to_string().contains("dimension mismatch: 3 vs 2"));
+    }
 }
@@ -1,3 +1,4 @@
+use base64::{Engine as _, engine::general_purpose};
 use serde::{Deserialize, Serialize};
 
 /// Content represents a message with multiple parts
@@ -21,6 +22,83 @@ impl Content {
             parts: vec![Part::Text { text: text.into() }],
         }
     }
+
+    /// Create a user message from arbitrary parts, e.g. text plus images
+    pub fn new_user_parts(parts: Vec<Part>) -> Self {
+        Self {
+            role: "user".to_string(),
+            parts,
+        }
+    }
+
+    /// Create a user message carrying raw audio (e.g. `audio/wav`)
+    pub fn new_user_audio(data: impl AsRef<[u8]>, mime_type: impl Into<String>) -> Self {
+        Self {
+            role: "user".to_string(),
+            parts: vec![Part::audio(mime_type, data)],
+        }
+    }
+
+    /// Concatenated text of all text parts, ignoring other parts
+    pub fn text(&self) -> String {
+        self.parts.iter().filter_map(Part::as_text).collect()
+    }
+
+    /// Function calls carried by this content, in order
+    pub fn function_calls(&self) -> Vec<&FunctionCall> {
+        self.parts
+            .iter()
+            .filter_map(|part| match part {
+                Part::FunctionCall { function_call } => Some(function_call),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl Part {
+    /// The text of a text part
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Part::Text { text } => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Create an inline data part from raw bytes, base64-encoding them
+    pub fn inline_data(mime_type: impl Into<String>, data: impl AsRef<[u8]>) -> Self {
+        Part::InlineData {
+            inline_data: InlineData {
+                mime_type: mime_type.into(),
+                data: general_purpose::STANDARD.encode(data),
+            },
+        }
+    }
+
+    /// Create an audio part from raw bytes (e.g. `audio/mpeg`, `audio/wav`)
+    pub fn audio(mime_type: impl Into<String>, data: impl AsRef<[u8]>) -> Self {
+        Self::inline_data(mime_type, data)
+    }
+
+    /// Create an image part from raw bytes (e.g. `image/png`, `image/jpeg`)
+    pub fn image_from_bytes(mime_type: impl Into<String>, data: impl AsRef<[u8]>) -> Self {
+        Self::inline_data(mime_type, data)
+    }
+
+    /// Whether this part is inline audio data
+    pub fn is_audio(&self) -> bool {
+        matches!(self, Part::InlineData { inline_data } if inline_data.mime_type.starts_with("audio/"))
+    }
+
+    /// Create a part referencing an uploaded file by URI
+    pub fn file_uri(uri: impl Into<String>, mime_type: impl Into<String>) -> Self {
+        Part::FileUri {
+            file_data: FileData {
+                mime_type: mime_type.into(),
+                file_uri: uri.into(),
+            },
+        }
+    }
 }
 
 /// Part represents a single part of content
@@ -34,6 +112,11 @@ pub enum Part {
         #[serde(rename = "inlineData")]
         inline_data: InlineData,
     },
+    /// Reference to a file uploaded via `Provider::upload_file`
+    FileUri {
+        #[serde(rename = "fileData")]
+        file_data: FileData,
+    },
     FunctionCall {
         #[serde(rename = "functionCall")]
         function_call: FunctionCall,
@@ -51,6 +134,22 @@ pub struct InlineData {
     pub data: String, // base64 encoded
 }
 
+impl InlineData {
+    /// Decode the base64 payload into raw bytes
+    pub fn decode(&self) -> crate::Result<Vec<u8>> {
+        general_purpose::STANDARD
+            .decode(&self.data)
+            .map_err(|e| crate::Error::Other(anyhow::anyhow!("Invalid base64 inline data: {}", e)))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileData {
+    pub mime_type: String,
+    pub file_uri: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FunctionCall {
     pub name: String,
@@ -66,3 +165,83 @@ pub struct FunctionResponse {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub id: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_user_audio() {
+        let bytes = vec![0x52, 0x49, 0x46, 0x46, 0x00, 0xff];
+        let content = Content::new_user_audio(&bytes, "audio/wav");
+
+        assert_eq!(content.role, "user");
+        assert_eq!(content.parts.len(), 1);
+        assert!(content.parts[0].is_audio());
+
+        match &content.parts[0] {
+            Part::InlineData { inline_data } => {
+                assert_eq!(inline_data.mime_type, "audio/wav");
+                assert_eq!(inline_data.decode().unwrap(), bytes);
+            }
+            other => panic!("Expected inline data, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_text_and_function_call_accessors() {
+        let content = Content {
+            role: "model".to_string(),
+            parts: vec![
+                Part::Text {
+                    text: "Checking the ".to_string(),
+                },
+                Part::FunctionCall {
+                    function_call: FunctionCall {
+                        name: "get_weather".to_string(),
+                        args: serde_json::json!({"city": "Paris"}),
+                        id: None,
+                    },
+                },
+                Part::inline_data("image/png", b"abc"),
+                Part::Text {
+                    text: "weather".to_string(),
+                },
+                Part::FunctionCall {
+                    function_call: FunctionCall {
+                        name: "get_time".to_string(),
+                        args: serde_json::json!({}),
+                        id: Some("call-2".to_string()),
+                    },
+                },
+            ],
+        };
+
+        assert_eq!(content.text(), "Checking the weather");
+        let calls = content.function_calls();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].name, "get_weather");
+        assert_eq!(calls[1].id.as_deref(), Some("call-2"));
+
+        assert_eq!(content.parts[0].as_text(), Some("Checking the "));
+        assert_eq!(content.parts[1].as_text(), None);
+        assert_eq!(content.parts[2].as_text(), None);
+
+        let empty = Content {
+            role: "model".to_string(),
+            parts: vec![],
+        };
+        assert_eq!(empty.text(), "");
+        assert!(empty.function_calls().is_empty());
+    }
+
+    #[test]
+    fn test_audio_part_serializes_as_inline_data() {
+        let part = Part::audio("audio/mpeg", b"abc");
+        let json = serde_json::to_value(&part).unwrap();
+
+        assert_eq!(json["inlineData"]["mimeType"], "audio/mpeg");
+        assert_eq!(json["inlineData"]["data"], "YWJj");
+        assert!(!Part::inline_data("image/png", b"abc").is_audio());
+    }
+}
@@ -1,5 +1,15 @@
-use super::Content;
+use super::{
+    ArtifactPart, ArtifactStore, Content, Error, MemoryStore, Result, RunConfig, RunnerDefaults,
+    SessionStore,
+};
 use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+static DEFAULT_RUN_CONFIG: Lazy<RunConfig> = Lazy::new(RunConfig::default);
+static DEFAULT_RUNNER_DEFAULTS: Lazy<RunnerDefaults> = Lazy::new(RunnerDefaults::default);
 
 /// Invocation context provided to agents during execution
 #[async_trait]
@@ -9,6 +19,55 @@ pub trait InvocationContext: ReadonlyContext + Send + Sync {
 
     /// Returns the user content that triggered this invocation
     fn user_content(&self) -> Option<&Content>;
+
+    /// Returns prior conversation turns to include ahead of the user content
+    ///
+    /// Default implementation returns no history.
+    fn history(&self) -> &[Content] {
+        &[]
+    }
+
+    /// Returns the run configuration for this invocation
+    ///
+    /// Default implementation returns `RunConfig::default()`.
+    fn run_config(&self) -> &RunConfig {
+        &DEFAULT_RUN_CONFIG
+    }
+
+    /// Returns the runner-level defaults for settings the agent leaves unset
+    ///
+    /// Default implementation returns `RunnerDefaults::default()`.
+    fn runner_defaults(&self) -> &RunnerDefaults {
+        &DEFAULT_RUNNER_DEFAULTS
+    }
+
+    /// Returns the session state as of the start of this invocation
+    ///
+    /// Default implementation returns an empty state.
+    fn state(&self) -> HashMap<String, Value> {
+        HashMap::new()
+    }
+
+    /// Returns artifact storage scoped to this invocation's session
+    ///
+    /// Default implementation returns `None` (no artifact service configured).
+    fn artifacts(&self) -> Option<Arc<dyn ArtifactStore>> {
+        None
+    }
+
+    /// Returns the session service scoped to this invocation's session
+    ///
+    /// Default implementation returns `None` (no session service configured).
+    fn session(&self) -> Option<Arc<dyn SessionStore>> {
+        None
+    }
+
+    /// Returns memory search scoped to this invocation's app and user
+    ///
+    /// Default implementation returns `None` (no memory service configured).
+    fn memory(&self) -> Option<Arc<dyn MemoryStore>> {
+        None
+    }
 }
 
 /// Read-only context for callbacks and tools
@@ -24,7 +83,53 @@ pub trait ReadonlyContext: Send + Sync {
 }
 
 /// Tool context provided during tool execution
+#[async_trait]
 pub trait ToolContext: Send + Sync {
     fn function_call_id(&self) -> &str;
     fn invocation_id(&self) -> &str;
+
+    /// Returns the current session state, including writes made earlier in
+    /// this invocation
+    ///
+    /// Default implementation returns an empty state.
+    fn state(&self) -> HashMap<String, Value> {
+        HashMap::new()
+    }
+
+    /// Sets a session state key
+    ///
+    /// The write is visible to later tools in the same invocation and is
+    /// persisted with the tool's event. Default implementation discards it.
+    fn set_state(&self, key: &str, value: Value) {
+        let _ = (key, value);
+    }
+
+    /// Saves an artifact in the current session, returning its version
+    ///
+    /// Default implementation fails because no artifact service is configured.
+    async fn save_artifact(&self, file_name: &str, part: ArtifactPart) -> Result<i64> {
+        let _ = part;
+        Err(Error::ArtifactError(format!(
+            "Cannot save '{}': no artifact service configured",
+            file_name
+        )))
+    }
+
+    /// Loads an artifact from the current session, the latest version if
+    /// `version` is unset
+    ///
+    /// Default implementation fails because no artifact service is configured.
+    async fn load_artifact(&self, file_name: &str, version: Option<i64>) -> Result<ArtifactPart> {
+        let _ = version;
+        Err(Error::ArtifactError(format!(
+            "Cannot load '{}': no artifact service configured",
+            file_name
+        )))
+    }
+
+    /// Asks the enclosing workflow to stop, e.g. a `LoopAgent` whose exit
+    /// condition this tool has detected
+    ///
+    /// Sets `escalate` on the tool's event. Default implementation ignores it.
+    fn escalate(&self) {}
 }
@@ -0,0 +1,204 @@
+//! Fitting a conversation into a model's context window
+
+use crate::{Content, LLMRequest, Part};
+
+/// Drops the oldest turns of a conversation until it fits a token budget
+///
+/// A turn is a user message with the model and function messages that follow
+/// it, and is dropped as a whole so no function response is left without its
+/// call. The system instruction, `system` messages and the current turn (the
+/// latest user message and everything after it) are always kept. Tokens are
+/// estimated at about four characters each, like the rate limiter does.
+#[derive(Debug, Clone, Copy)]
+pub struct ContextTrimmer {
+    max_tokens: usize,
+}
+
+impl ContextTrimmer {
+    /// Trim to at most `max_tokens` estimated tokens
+    pub fn new(max_tokens: usize) -> Self {
+        Self { max_tokens }
+    }
+
+    /// The token budget
+    pub fn max_tokens(&self) -> usize {
+        self.max_tokens
+    }
+
+    /// Drop the oldest turns of `contents` so they fit alongside `system_instruction`
+    ///
+    /// If the kept messages alone exceed the budget they are returned anyway,
+    /// leaving the provider to reject the request.
+    pub fn trim(&self, system_instruction: Option<&str>, contents: Vec<Content>) -> Vec<Content> {
+        let reserved = system_instruction.map(estimate_text_tokens).unwrap_or(0);
+        let mut total = reserved + contents.iter().map(estimate_tokens).sum::<usize>();
+        if total <= self.max_tokens {
+            return contents;
+        }
+
+        // Everything from the latest user message on is the turn in progress
+        let current_turn = contents.iter().rposition(|c| c.role == "user").unwrap_or(0);
+
+        let mut keep = vec![true; contents.len()];
+        let mut start = 0;
+        while total > self.max_tokens && start < current_turn {
+            // A turn is a user message plus the model and function messages
+            // answering it, so calls and their responses go together
+            let end = (start + 1..current_turn)
+                .find(|&i| contents[i].role == "user")
+                .unwrap_or(current_turn);
+            for i in start..end {
+                if contents[i].role != "system" {
+                    keep[i] = false;
+                    total -= estimate_tokens(&contents[i]);
+                }
+            }
+            start = end;
+        }
+
+        let dropped = keep.iter().filter(|k| !**k).count();
+        tracing::debug!(
+            dropped,
+            estimated_tokens = total,
+            max_tokens = self.max_tokens,
+            "Trimmed conversation to fit the context window"
+        );
+
+        contents
+            .into_iter()
+            .zip(keep)
+            .filter_map(|(content, keep)| keep.then_some(content))
+            .collect()
+    }
+
+    /// Trim `request.contents` in place to fit the budget
+    pub fn trim_request(&self, request: &mut LLMRequest) {
+        let contents = std::mem::take(&mut request.contents);
+        request.contents = self.trim(request.system_instruction.as_deref(), contents);
+    }
+}
+
+/// Rough token estimate for one message
+fn estimate_tokens(content: &Content) -> usize {
+    content
+        .parts
+        .iter()
+        .map(|part| match part {
+            Part::Text { text } => estimate_text_tokens(text),
+            Part::FunctionCall { function_call } => {
+                estimate_text_tokens(&function_call.args.to_string())
+            }
+            Part::FunctionResponse { function_response } => {
+                estimate_text_tokens(&function_response.response.to_string())
+            }
+            _ => 0,
+        })
+        .sum()
+}
+
+fn estimate_text_tokens(text: &str) -> usize {
+    text.len().div_ceil(4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A message of about `tokens` estimated tokens
+    fn message(role: &str, label: &str, tokens: usize) -> Content {
+        let mut text = label.to_string();
+        text.push_str(&" ".repeat(tokens * 4 - label.len()));
+        Content {
+            role: role.to_string(),
+            parts: vec![Part::Text { text }],
+        }
+    }
+
+    fn labels(contents: &[Content]) -> Vec<String> {
+        contents
+            .iter()
+            .map(|c| c.text().trim().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_fitting_conversation_untouched() {
+        let contents = vec![message("user", "a", 10), message("model", "b", 10)];
+        let trimmed = ContextTrimmer::new(20).trim(None, contents);
+        assert_eq!(labels(&trimmed), ["a", "b"]);
+    }
+
+    #[test]
+    fn test_oldest_messages_dropped_first() {
+        let contents = vec![
+            message("user", "q1", 10),
+            message("model", "a1", 10),
+            message("user", "q2", 10),
+            message("model", "a2", 10),
+            message("user", "q3", 10),
+        ];
+
+        // The 20-token system instruction leaves room for three messages
+        let system = " ".repeat(80);
+        let trimmed = ContextTrimmer::new(50).trim(Some(&system), contents);
+        assert_eq!(labels(&trimmed), ["q2", "a2", "q3"]);
+    }
+
+    #[test]
+    fn test_system_and_latest_user_message_kept() {
+        let contents = vec![
+            message("system", "rules", 10),
+            message("user", "q1", 10),
+            message("model", "a1", 10),
+            message("user", "q2", 30),
+        ];
+
+        let trimmed = ContextTrimmer::new(25).trim(None, contents);
+        assert_eq!(labels(&trimmed), ["rules", "q2"]);
+    }
+
+    #[test]
+    fn test_whole_turns_dropped_with_their_function_calls() {
+        let call = Content {
+            role: "model".to_string(),
+            parts: vec![Part::FunctionCall {
+                function_call: crate::FunctionCall {
+                    name: "lookup".to_string(),
+                    args: serde_json::json!({}),
+                    id: None,
+                },
+            }],
+        };
+        let contents = vec![
+            message("user", "q1", 10),
+            call,
+            message("function", "result", 10),
+            message("model", "a1", 10),
+            message("user", "q2", 10),
+        ];
+
+        let mut request = LLMRequest {
+            model: "test".to_string(),
+            system_instruction: None,
+            contents,
+            config: None,
+            tools: Vec::new(),
+        };
+        ContextTrimmer::new(20).trim_request(&mut request);
+        assert_eq!(labels(&request.contents), ["q2"]);
+    }
+
+    #[test]
+    fn test_current_turn_never_trimmed() {
+        let contents = vec![
+            message("user", "q1", 10),
+            message("model", "a1", 10),
+            message("user", "q2", 10),
+            message("model", "thinking", 10),
+            message("function", "result", 30),
+        ];
+
+        let trimmed = ContextTrimmer::new(20).trim(None, contents);
+        assert_eq!(labels(&trimmed), ["q2", "thinking", "result"]);
+    }
+}
@@ -26,6 +26,19 @@ pub enum Error {
     #[error("Session error: {0}")]
     SessionError(String),
 
+    #[error("Session {0} not found")]
+    SessionNotFound(String),
+
+    #[error("Session {session_id} has {actual} events, expected {expected}")]
+    SessionConflict {
+        session_id: String,
+        expected: usize,
+        actual: usize,
+    },
+
+    #[error("Embedding dimension mismatch: {left} vs {right}")]
+    DimensionMismatch { left: usize, right: usize },
+
     #[error("Artifact error: {0}")]
     ArtifactError(String),
 
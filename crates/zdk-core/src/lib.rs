@@ -2,34 +2,45 @@
 //!
 //! This crate provides the foundational abstractions for building AI agents.
 
+pub mod artifact;
 pub mod auth;
 pub mod capabilities;
 pub mod config;
 pub mod content;
 pub mod context;
+pub mod context_trimmer;
 pub mod error;
 pub mod event;
 pub mod extensions;
+pub mod memory;
 pub mod providers;
+pub mod run_config;
+pub mod session;
 pub mod traits;
 
 // Re-exports
+pub use artifact::{ArtifactPart, ArtifactStore};
 pub use auth::{ApiKeyConfig, AuthCredentials, AuthProvider, GCloudConfig};
 pub use capabilities::{
     AudioInput, AudioRequest, AudioResult, EmbeddingVector, GeneratedImage, ImageRequest,
     ImageResult, TranscriptionResult, TranscriptionSegment,
 };
 pub use config::ZConfig;
-pub use content::{Content, FunctionCall, FunctionResponse, InlineData, Part};
+pub use content::{Content, FileData, FunctionCall, FunctionResponse, InlineData, Part};
 pub use context::{InvocationContext, ReadonlyContext, ToolContext};
+pub use context_trimmer::ContextTrimmer;
 pub use error::{Error, Result};
 pub use event::{Event, EventActions};
 pub use extensions::ZConfigExt;
+pub use memory::{MemoryEntry, MemoryStore};
 pub use providers::{
-    Capability, GeminiAuth, GeminiProvider, ModelInfo, OpenAIProvider, Provider, ProviderFactory,
-    ProviderMetadata, ProviderRegistry,
+    AnthropicProvider, Capability, GeminiAuth, GeminiProvider, LoggingLLM, ModelInfo,
+    OpenAIProvider, Provider, ProviderFactory, ProviderMetadata, ProviderRegistry,
+    RecordingProvider, ReplayProvider, RetryingLLM,
 };
+pub use run_config::{RunConfig, RunnerDefaults};
+pub use session::SessionStore;
 pub use traits::{
-    Agent, GeminiBuiltinToolType, GenerateConfig, LLM, LLMRequest, LLMResponse, Tool, ToolResponse,
-    Toolset,
+    Agent, FinishReason, GeminiBuiltinToolType, GenerateConfig, LLM, LLMRequest, LLMResponse,
+    TokenUsage, Tool, ToolError, ToolErrorKind, ToolResponse, Toolset,
 };
@@ -0,0 +1,37 @@
+//! Memory entries and user-scoped memory search
+
+use crate::{Content, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+/// A single memory entry
+#[derive(Debug, Clone)]
+pub struct MemoryEntry {
+    /// Content of the memory
+    pub content: Option<Content>,
+    /// Author of the memory
+    pub author: String,
+    /// Timestamp when the original content happened
+    pub timestamp: DateTime<Utc>,
+}
+
+impl MemoryEntry {
+    /// Create a new memory entry
+    pub fn new(content: Option<Content>, author: String, timestamp: DateTime<Utc>) -> Self {
+        Self {
+            content,
+            author,
+            timestamp,
+        }
+    }
+}
+
+/// Memory search scoped to the app and user of one invocation
+///
+/// Implemented on top of a memory service by `zdk-memory` and handed to
+/// agents through their [`InvocationContext`](crate::InvocationContext).
+#[async_trait]
+pub trait MemoryStore: Send + Sync {
+    /// Memories from the user's past sessions that match `query`
+    async fn search_memory(&self, query: &str) -> Result<Vec<MemoryEntry>>;
+}
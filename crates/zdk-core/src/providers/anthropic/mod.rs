@@ -0,0 +1,112 @@
+//! Anthropic provider
+//!
+//! Supports text generation with Claude models (claude-3-5-sonnet,
+//! claude-3-5-haiku, etc.) through the Messages API, including tool use and
+//! SSE streaming.
+
+pub mod provider;
+pub mod types;
+
+pub use provider::AnthropicProvider;
+
+use super::rate_limiter::RateLimiter;
+use super::response_limit::DEFAULT_MAX_RESPONSE_BYTES;
+use std::sync::Arc;
+
+/// Default `anthropic-version` header value
+pub const DEFAULT_API_VERSION: &str = "2023-06-01";
+
+/// Default output token limit, used when the request doesn't set `max_tokens`
+pub const DEFAULT_MAX_TOKENS: u32 = 4096;
+
+/// Anthropic configuration
+#[derive(Clone, Debug)]
+pub struct AnthropicConfig {
+    /// Model name for text generation
+    pub model: String,
+    /// Base URL for API requests
+    pub base_url: String,
+    /// Value sent in the `anthropic-version` header
+    pub api_version: String,
+    /// Output token limit when the request doesn't set one (the API requires it)
+    pub default_max_tokens: u32,
+    /// Hard cap on generated text per response, in bytes
+    pub max_response_bytes: usize,
+}
+
+impl AnthropicConfig {
+    /// Create default configuration
+    pub fn default(model: String) -> Self {
+        Self::with_base_url(model, "https://api.anthropic.com/v1".to_string())
+    }
+
+    /// Create configuration with custom base URL (e.g., for a proxy)
+    pub fn with_base_url(model: String, base_url: String) -> Self {
+        Self {
+            model,
+            base_url,
+            api_version: DEFAULT_API_VERSION.to_string(),
+            default_max_tokens: DEFAULT_MAX_TOKENS,
+            max_response_bytes: DEFAULT_MAX_RESPONSE_BYTES,
+        }
+    }
+}
+
+/// Builder for AnthropicProvider
+pub struct AnthropicBuilder {
+    api_key: Option<String>,
+    config: Option<AnthropicConfig>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+}
+
+impl AnthropicBuilder {
+    /// Create a new builder
+    pub fn new() -> Self {
+        Self {
+            api_key: None,
+            config: None,
+            rate_limiter: None,
+        }
+    }
+
+    /// Set API key
+    pub fn with_api_key(mut self, api_key: String, model: String) -> Self {
+        self.api_key = Some(api_key);
+        self.config = Some(AnthropicConfig::default(model));
+        self
+    }
+
+    /// Set custom configuration
+    pub fn with_config(mut self, config: AnthropicConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Share a rate limiter across providers so they respect one quota
+    pub fn rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
+
+    /// Build the provider
+    pub fn build(self) -> crate::Result<AnthropicProvider> {
+        let api_key = self
+            .api_key
+            .ok_or_else(|| crate::Error::config_error("API key is required"))?;
+        let config = self
+            .config
+            .ok_or_else(|| crate::Error::config_error("Configuration is required"))?;
+
+        let provider = AnthropicProvider::new(api_key, config);
+        Ok(match self.rate_limiter {
+            Some(limiter) => provider.with_rate_limiter(limiter),
+            None => provider,
+        })
+    }
+}
+
+impl Default for AnthropicBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
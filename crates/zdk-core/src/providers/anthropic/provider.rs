@@ -0,0 +1,886 @@
+//! Anthropic provider implementation
+
+use super::{AnthropicConfig, types::*};
+use crate::{
+    Content, FinishReason, FunctionCall, LLMRequest, LLMResponse, Part, Result, TokenUsage,
+    providers::provider::{Capability, ModelInfo, Provider, ProviderMetadata},
+    providers::rate_limiter::{RateLimiter, estimate_request_tokens},
+    providers::response_limit::limit_response_size,
+    providers::sse::{MAX_SSE_LINE_BYTES, SseBuffer},
+};
+use async_trait::async_trait;
+use futures::stream::Stream;
+use reqwest::Client;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Anthropic provider for Claude models
+pub struct AnthropicProvider {
+    client: Client,
+    api_key: String,
+    config: AnthropicConfig,
+    rate_limiter: Option<Arc<RateLimiter>>,
+}
+
+impl AnthropicProvider {
+    /// Create a new Anthropic provider
+    pub fn new(api_key: String, config: AnthropicConfig) -> Self {
+        Self {
+            client: Client::new(),
+            api_key,
+            config,
+            rate_limiter: None,
+        }
+    }
+
+    /// Share a rate limiter with other providers
+    pub fn with_rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
+
+    /// Wait for rate limiter quota, if one is configured
+    async fn throttle(&self, estimated_tokens: u32) {
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.acquire(estimated_tokens).await;
+        }
+    }
+
+    /// Convert an LLMRequest to a Messages API request
+    fn build_request(&self, request: &LLMRequest, stream: bool) -> Result<AnthropicRequest> {
+        let (extra_system, messages) = convert_contents_to_messages(&request.contents)?;
+
+        // Contents with the system role are folded into the top-level system prompt
+        let system = match (request.system_instruction.clone(), extra_system) {
+            (Some(instruction), Some(extra)) => Some(format!("{}\n\n{}", instruction, extra)),
+            (instruction, extra) => instruction.or(extra),
+        };
+
+        let tools = request
+            .tools
+            .iter()
+            .filter(|tool| tool.gemini_builtin_type().is_none())
+            .map(|tool| AnthropicTool {
+                name: tool.name().to_string(),
+                description: tool.description().to_string(),
+                input_schema: tool.schema(),
+            })
+            .collect();
+
+        let config = request.config.as_ref();
+        Ok(AnthropicRequest {
+            model: self.config.model.clone(),
+            max_tokens: config
+                .and_then(|c| c.max_tokens)
+                .unwrap_or(self.config.default_max_tokens),
+            messages,
+            system,
+            temperature: config.and_then(|c| c.temperature),
+            top_p: config.and_then(|c| c.top_p),
+            top_k: config.and_then(|c| c.top_k),
+            tools,
+            stream: Some(stream),
+        })
+    }
+
+    /// Get static metadata (for factory)
+    pub fn static_metadata() -> ProviderMetadata {
+        ProviderMetadata {
+            name: "anthropic".to_string(),
+            display_name: "Anthropic".to_string(),
+            capabilities: vec![Capability::TextGeneration],
+            models: vec![
+                ModelInfo {
+                    id: "claude-3-5-sonnet-latest".to_string(),
+                    display_name: "Claude 3.5 Sonnet".to_string(),
+                    capabilities: vec![Capability::TextGeneration],
+                    context_window: Some(200_000),
+                    embedding_dimensions: None,
+                },
+                ModelInfo {
+                    id: "claude-3-5-haiku-latest".to_string(),
+                    display_name: "Claude 3.5 Haiku".to_string(),
+                    capabilities: vec![Capability::TextGeneration],
+                    context_window: Some(200_000),
+                    embedding_dimensions: None,
+                },
+            ],
+        }
+    }
+}
+
+/// Tool use ID of a call or response, which Anthropic needs to pair them up
+fn tool_use_id(id: &Option<String>, name: &str) -> Result<String> {
+    id.clone().ok_or_else(|| {
+        crate::Error::LLMError(format!(
+            "Tool call {} has no ID; Anthropic requires tool use IDs",
+            name
+        ))
+    })
+}
+
+/// Convert ZDK contents to Anthropic messages
+///
+/// Returns the text of any `system` contents separately, since the Messages API
+/// only accepts a top-level system prompt. Consecutive contents with the same
+/// role are merged, as the API expects user and assistant turns to alternate.
+fn convert_contents_to_messages(
+    contents: &[Content],
+) -> Result<(Option<String>, Vec<AnthropicMessage>)> {
+    let mut system_texts = Vec::new();
+    let mut messages: Vec<AnthropicMessage> = Vec::new();
+
+    for content in contents {
+        let role = match content.role.as_str() {
+            "model" | "assistant" => "assistant",
+            "system" => {
+                system_texts.extend(content.parts.iter().filter_map(|part| match part {
+                    Part::Text { text } => Some(text.clone()),
+                    _ => None,
+                }));
+                continue;
+            }
+            // Tool results are sent back on the user side
+            _ => "user",
+        };
+
+        let mut blocks = Vec::with_capacity(content.parts.len());
+        for part in &content.parts {
+            let block = match part {
+                Part::Text { text } if text.is_empty() => None,
+                Part::Text { text } => Some(AnthropicContentBlock::Text { text: text.clone() }),
+                Part::InlineData { inline_data } if inline_data.mime_type.starts_with("image/") => {
+                    Some(AnthropicContentBlock::Image {
+                        source: AnthropicImageSource {
+                            source_type: "base64".to_string(),
+                            media_type: inline_data.mime_type.clone(),
+                            data: inline_data.data.clone(),
+                        },
+                    })
+                }
+                Part::FunctionCall { function_call } => Some(AnthropicContentBlock::ToolUse {
+                    id: tool_use_id(&function_call.id, &function_call.name)?,
+                    name: function_call.name.clone(),
+                    input: function_call.args.clone(),
+                }),
+                Part::FunctionResponse { function_response } => {
+                    Some(AnthropicContentBlock::ToolResult {
+                        tool_use_id: tool_use_id(&function_response.id, &function_response.name)?,
+                        content: function_response.response.to_string(),
+                    })
+                }
+                other => {
+                    tracing::warn!(?other, "Skipping content part not supported by Anthropic");
+                    None
+                }
+            };
+            blocks.extend(block);
+        }
+
+        if blocks.is_empty() {
+            continue;
+        }
+
+        match messages.last_mut() {
+            Some(last) if last.role == role => last.content.extend(blocks),
+            _ => messages.push(AnthropicMessage {
+                role: role.to_string(),
+                content: blocks,
+            }),
+        }
+    }
+
+    let system = (!system_texts.is_empty()).then(|| system_texts.join("\n\n"));
+    Ok((system, messages))
+}
+
+/// Convert Anthropic content blocks to ZDK content
+fn convert_blocks_to_content(blocks: &[AnthropicContentBlock]) -> Content {
+    let parts = blocks
+        .iter()
+        .filter_map(|block| match block {
+            AnthropicContentBlock::Text { text } => Some(Part::Text { text: text.clone() }),
+            AnthropicContentBlock::ToolUse { id, name, input } => Some(Part::FunctionCall {
+                function_call: FunctionCall {
+                    name: name.clone(),
+                    args: input.clone(),
+                    id: Some(id.clone()),
+                },
+            }),
+            _ => None,
+        })
+        .collect();
+
+    Content {
+        role: "model".to_string(),
+        parts,
+    }
+}
+
+/// Tool call being streamed as JSON fragments
+struct PendingToolCall {
+    id: String,
+    name: String,
+    input_json: String,
+}
+
+/// Turns Messages API stream events into LLM responses
+///
+/// Text deltas are forwarded as partial responses straight away. Tool calls
+/// arrive as JSON fragments, so they are collected and returned together in
+/// the final response, alongside the stop reason and token usage.
+#[derive(Default)]
+struct StreamState {
+    tool_calls: BTreeMap<usize, PendingToolCall>,
+    usage: AnthropicUsage,
+    stop_reason: Option<String>,
+    finished: bool,
+}
+
+impl StreamState {
+    fn handle(&mut self, event: AnthropicStreamEvent) -> Result<Option<LLMResponse>> {
+        match event {
+            AnthropicStreamEvent::MessageStart { message } => {
+                self.usage = message.usage;
+                Ok(None)
+            }
+            AnthropicStreamEvent::ContentBlockStart {
+                index,
+                content_block,
+            } => match content_block {
+                AnthropicContentBlock::ToolUse { id, name, .. } => {
+                    self.tool_calls.insert(
+                        index,
+                        PendingToolCall {
+                            id,
+                            name,
+                            input_json: String::new(),
+                        },
+                    );
+                    Ok(None)
+                }
+                AnthropicContentBlock::Text { text } if !text.is_empty() => {
+                    Ok(Some(text_response(text)))
+                }
+                _ => Ok(None),
+            },
+            AnthropicStreamEvent::ContentBlockDelta { index, delta } => match delta {
+                AnthropicDelta::TextDelta { text } => Ok(Some(text_response(text))),
+                AnthropicDelta::InputJsonDelta { partial_json } => {
+                    if let Some(call) = self.tool_calls.get_mut(&index) {
+                        call.input_json.push_str(&partial_json);
+                    }
+                    Ok(None)
+                }
+                AnthropicDelta::Unknown => Ok(None),
+            },
+            AnthropicStreamEvent::MessageDelta { delta, usage } => {
+                self.stop_reason = delta.stop_reason;
+                self.usage.output_tokens = usage.output_tokens;
+                Ok(None)
+            }
+            AnthropicStreamEvent::MessageStop => {
+                self.finished = true;
+                self.final_response().map(Some)
+            }
+            AnthropicStreamEvent::Error { error } => Err(crate::Error::LLMError(format!(
+                "Anthropic API error ({}): {}",
+                error.error_type, error.message
+            ))),
+            AnthropicStreamEvent::ContentBlockStop { .. }
+            | AnthropicStreamEvent::Ping
+            | AnthropicStreamEvent::Unknown => Ok(None),
+        }
+    }
+
+    fn final_response(&mut self) -> Result<LLMResponse> {
+        let parts = std::mem::take(&mut self.tool_calls)
+            .into_values()
+            .map(|call| {
+                // A tool without parameters streams no input at all
+                let args = if call.input_json.trim().is_empty() {
+                    serde_json::json!({})
+                } else {
+                    serde_json::from_str(&call.input_json).map_err(|e| {
+                        crate::Error::LLMError(format!(
+                            "Invalid input JSON for tool call '{}': {}",
+                            call.name, e
+                        ))
+                    })?
+                };
+                Ok(Part::FunctionCall {
+                    function_call: FunctionCall {
+                        name: call.name,
+                        args,
+                        id: Some(call.id),
+                    },
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(LLMResponse {
+            content: (!parts.is_empty()).then(|| Content {
+                role: "model".to_string(),
+                parts,
+            }),
+            partial: false,
+            turn_complete: true,
+            interrupted: false,
+            finish_reason: self.stop_reason.as_deref().map(FinishReason::parse),
+            error_code: None,
+            error_message: None,
+            usage: Some(TokenUsage::from(&self.usage)),
+        })
+    }
+}
+
+fn text_response(text: String) -> LLMResponse {
+    LLMResponse {
+        content: Some(Content {
+            role: "model".to_string(),
+            parts: vec![Part::Text { text }],
+        }),
+        partial: true,
+        turn_complete: false,
+        interrupted: false,
+        finish_reason: None,
+        error_code: None,
+        error_message: None,
+        usage: None,
+    }
+}
+
+#[async_trait]
+impl crate::LLM for AnthropicProvider {
+    fn name(&self) -> &str {
+        &self.config.model
+    }
+
+    fn capabilities(&self) -> Option<Vec<crate::Capability>> {
+        Self::static_metadata().model_capabilities(&self.config.model)
+    }
+
+    fn context_window(&self) -> Option<usize> {
+        Self::static_metadata().model_context_window(&self.config.model)
+    }
+
+    async fn generate_content(
+        &self,
+        request: crate::LLMRequest,
+        do_stream: bool,
+    ) -> Box<dyn futures::stream::Stream<Item = crate::Result<crate::LLMResponse>> + Send + Unpin>
+    {
+        <Self as Provider>::generate_content(self, request, do_stream)
+            .await
+            .unwrap() // Safe because our implementation never returns Err at this level
+    }
+}
+
+#[async_trait]
+impl Provider for AnthropicProvider {
+    fn metadata(&self) -> ProviderMetadata {
+        Self::static_metadata()
+    }
+
+    async fn ping(&self) -> Result<()> {
+        // Listing a single model is the cheapest authenticated call
+        let response = self
+            .client
+            .get(format!("{}/models", self.config.base_url))
+            .query(&[("limit", "1")])
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", &self.config.api_version)
+            .send()
+            .await
+            .map_err(|e| crate::Error::LLMError(format!("Ping request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(crate::Error::LLMError(format!(
+                "Ping API error {}: {}",
+                status, error_text
+            )));
+        }
+        Ok(())
+    }
+
+    async fn generate_content(
+        &self,
+        request: LLMRequest,
+        do_stream: bool,
+    ) -> Result<Box<dyn Stream<Item = Result<LLMResponse>> + Send + Unpin>> {
+        use async_stream::stream;
+        use futures::stream::StreamExt;
+
+        self.throttle(estimate_request_tokens(&request)).await;
+
+        let url = format!("{}/messages", self.config.base_url);
+        let anthropic_req = self.build_request(&request, do_stream)?;
+        let max_response_bytes = self.config.max_response_bytes;
+        let http_request = self
+            .client
+            .post(&url)
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", &self.config.api_version)
+            .header("Content-Type", "application/json")
+            .json(&anthropic_req);
+
+        let stream: Box<dyn Stream<Item = Result<LLMResponse>> + Send + Unpin> = if do_stream {
+            Box::new(Box::pin(stream! {
+                let resp = match http_request.send().await {
+                    Ok(resp) => resp,
+                    Err(e) => {
+                        yield Err(crate::Error::LLMError(format!("Request failed: {}", e)));
+                        return;
+                    }
+                };
+
+                if !resp.status().is_success() {
+                    let status = resp.status();
+                    let error_text = resp.text().await.unwrap_or_default();
+                    yield Err(crate::Error::LLMError(format!("Anthropic API error {}: {}", status, error_text)));
+                    return;
+                }
+
+                let mut bytes_stream = resp.bytes_stream();
+                let mut sse = SseBuffer::new(MAX_SSE_LINE_BYTES);
+                let mut state = StreamState::default();
+
+                while let Some(chunk) = bytes_stream.next().await {
+                    let bytes = match chunk {
+                        Ok(bytes) => bytes,
+                        Err(e) => {
+                            yield Err(crate::Error::LLMError(format!("Stream error: {}", e)));
+                            return;
+                        }
+                    };
+                    sse.push(&bytes);
+
+                    loop {
+                        let data = match sse.next_data() {
+                            Ok(Some(data)) => data,
+                            Ok(None) => break,
+                            Err(e) => {
+                                yield Err(e);
+                                return;
+                            }
+                        };
+                        let event = match serde_json::from_str::<AnthropicStreamEvent>(&data) {
+                            Ok(event) => event,
+                            Err(e) => {
+                                tracing::debug!(error = %e, "Skipping unparseable SSE event");
+                                continue;
+                            }
+                        };
+
+                        match state.handle(event) {
+                            Ok(Some(response)) => yield Ok(response),
+                            Ok(None) => {}
+                            Err(e) => {
+                                yield Err(e);
+                                return;
+                            }
+                        }
+
+                        if state.finished {
+                            return;
+                        }
+                    }
+                }
+
+                yield Err(crate::Error::LLMError(
+                    "Anthropic stream ended before message_stop".to_string(),
+                ));
+            }))
+        } else {
+            Box::new(Box::pin(stream! {
+                let resp = match http_request.send().await {
+                    Ok(resp) => resp,
+                    Err(e) => {
+                        yield Err(crate::Error::LLMError(format!("Request failed: {}", e)));
+                        return;
+                    }
+                };
+
+                if !resp.status().is_success() {
+                    let status = resp.status();
+                    let error_text = resp.text().await.unwrap_or_default();
+                    yield Err(crate::Error::LLMError(format!("Anthropic API error {}: {}", status, error_text)));
+                    return;
+                }
+
+                match resp.json::<AnthropicResponse>().await {
+                    Ok(anthropic_resp) => {
+                        yield Ok(LLMResponse {
+                            content: Some(convert_blocks_to_content(&anthropic_resp.content)),
+                            partial: false,
+                            turn_complete: true,
+                            interrupted: false,
+                            finish_reason: anthropic_resp.stop_reason.as_deref().map(FinishReason::parse),
+                            error_code: None,
+                            error_message: None,
+                            usage: Some(TokenUsage::from(&anthropic_resp.usage)),
+                        });
+                    }
+                    Err(e) => {
+                        yield Err(crate::Error::LLMError(format!("Failed to parse response: {}", e)));
+                    }
+                }
+            }))
+        };
+
+        Ok(limit_response_size(stream, max_response_bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FunctionResponse, GenerateConfig, Tool, ToolContext, ToolResponse};
+    use serde_json::{Value, json};
+
+    struct WeatherTool;
+
+    #[async_trait]
+    impl Tool for WeatherTool {
+        fn name(&self) -> &str {
+            "get_weather"
+        }
+
+        fn description(&self) -> &str {
+            "Get the weather for a city"
+        }
+
+        fn schema(&self) -> Value {
+            json!({ "type": "object", "properties": { "city": { "type": "string" } } })
+        }
+
+        async fn execute(
+            &self,
+            _ctx: Arc<dyn ToolContext>,
+            _params: Value,
+        ) -> Result<ToolResponse> {
+            unreachable!()
+        }
+    }
+
+    fn provider() -> AnthropicProvider {
+        AnthropicProvider::new(
+            "key".to_string(),
+            AnthropicConfig::default("claude-3-5-sonnet-latest".to_string()),
+        )
+    }
+
+    #[test]
+    fn test_request_conversion() {
+        let request = LLMRequest {
+            model: "claude-3-5-sonnet-latest".to_string(),
+            system_instruction: Some("Be terse.".to_string()),
+            contents: vec![
+                Content::new_user_text("Weather in Paris?"),
+                Content {
+                    role: "model".to_string(),
+                    parts: vec![
+                        Part::Text {
+                            text: "Checking.".to_string(),
+                        },
+                        Part::FunctionCall {
+                            function_call: FunctionCall {
+                                name: "get_weather".to_string(),
+                                args: json!({ "city": "Paris" }),
+                                id: Some("toolu_01".to_string()),
+                            },
+                        },
+                    ],
+                },
+                Content {
+                    role: "function".to_string(),
+                    parts: vec![Part::FunctionResponse {
+                        function_response: FunctionResponse {
+                            name: "get_weather".to_string(),
+                            response: json!({ "temp": 21 }),
+                            id: Some("toolu_01".to_string()),
+                        },
+                    }],
+                },
+                Content::new_user_text("Thanks"),
+            ],
+            config: Some(GenerateConfig {
+                temperature: Some(0.2),
+                max_tokens: Some(256),
+                ..Default::default()
+            }),
+            tools: vec![Arc::new(WeatherTool)],
+        };
+
+        let body = serde_json::to_value(provider().build_request(&request, true).unwrap()).unwrap();
+        assert_eq!(
+            body,
+            json!({
+                "model": "claude-3-5-sonnet-latest",
+                "max_tokens": 256,
+                "system": "Be terse.",
+                "temperature": 0.2f32,
+                "stream": true,
+                "tools": [{
+                    "name": "get_weather",
+                    "description": "Get the weather for a city",
+                    "input_schema": { "type": "object", "properties": { "city": { "type": "string" } } }
+                }],
+                "messages": [
+                    { "role": "user", "content": [{ "type": "text", "text": "Weather in Paris?" }] },
+                    { "role": "assistant", "content": [
+                        { "type": "text", "text": "Checking." },
+                        { "type": "tool_use", "id": "toolu_01", "name": "get_weather", "input": { "city": "Paris" } }
+                    ] },
+                    // The tool result and following user text merge into one user turn
+                    { "role": "user", "content": [
+                        { "type": "tool_result", "tool_use_id": "toolu_01", "content": "{\"temp\":21}" },
+                        { "type": "text", "text": "Thanks" }
+                    ] }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_tool_result_without_id_is_rejected() {
+        let request = LLMRequest {
+            model: "claude-3-5-sonnet-latest".to_string(),
+            system_instruction: None,
+            contents: vec![Content {
+                role: "function".to_string(),
+                parts: vec![Part::FunctionResponse {
+                    function_response: FunctionResponse {
+                        name: "get_weather".to_string(),
+                        response: json!({ "temp": 21 }),
+                        id: None,
+                    },
+                }],
+            }],
+            config: None,
+            tools: vec![],
+        };
+
+        let err = provider().build_request(&request, false).unwrap_err();
+        assert!(err.to_string().contains("get_weather"));
+    }
+
+    #[test]
+    fn test_default_max_tokens_and_system_contents() {
+        let request = LLMRequest {
+            model: "claude-3-5-haiku-latest".to_string(),
+            system_instruction: None,
+            contents: vec![
+                Content {
+                    role: "system".to_string(),
+                    parts: vec![Part::Text {
+                        text: "Answer in French.".to_string(),
+                    }],
+                },
+                Content::new_user_text("Hi"),
+            ],
+            config: None,
+            tools: vec![],
+        };
+
+        let body = provider().build_request(&request, false).unwrap();
+        assert_eq!(body.max_tokens, super::super::DEFAULT_MAX_TOKENS);
+        assert_eq!(body.system.as_deref(), Some("Answer in French."));
+        assert_eq!(body.messages.len(), 1);
+        assert!(body.tools.is_empty());
+    }
+
+    const SSE_BODY: &str = concat!(
+        "event: message_start\n",
+        "data: {\"type\":\"message_start\",\"message\":{\"id\":\"msg_1\",\"type\":\"message\",\"role\":\"assistant\",\"model\":\"claude-3-5-sonnet-latest\",\"content\":[],\"stop_reason\":null,\"usage\":{\"input_tokens\":25,\"output_tokens\":1}}}\n\n",
+        "event: content_block_start\n",
+        "data: {\"type\":\"content_block_start\",\"index\":0,\"content_block\":{\"type\":\"text\",\"text\":\"\"}}\n\n",
+        "event: ping\n",
+        "data: {\"type\":\"ping\"}\n\n",
+        "event: content_block_delta\n",
+        "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"Let me \"}}\n\n",
+        "event: content_block_delta\n",
+        "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"check ☀.\"}}\n\n",
+        "event: content_block_stop\n",
+        "data: {\"type\":\"content_block_stop\",\"index\":0}\n\n",
+        "event: content_block_start\n",
+        "data: {\"type\":\"content_block_start\",\"index\":1,\"content_block\":{\"type\":\"tool_use\",\"id\":\"toolu_01\",\"name\":\"get_weather\",\"input\":{}}}\n\n",
+        "event: content_block_delta\n",
+        "data: {\"type\":\"content_block_delta\",\"index\":1,\"delta\":{\"type\":\"input_json_delta\",\"partial_json\":\"{\\\"city\\\": \"}}\n\n",
+        "event: content_block_delta\n",
+        "data: {\"type\":\"content_block_delta\",\"index\":1,\"delta\":{\"type\":\"input_json_delta\",\"partial_json\":\"\\\"Paris\\\"}\"}}\n\n",
+        "event: content_block_stop\n",
+        "data: {\"type\":\"content_block_stop\",\"index\":1}\n\n",
+        "event: message_delta\n",
+        "data: {\"type\":\"message_delta\",\"delta\":{\"stop_reason\":\"tool_use\",\"stop_sequence\":null},\"usage\":{\"output_tokens\":40}}\n\n",
+        "event: message_stop\n",
+        "data: {\"type\":\"message_stop\"}\n\n",
+    );
+
+    #[test]
+    fn test_sse_chunk_parsing() {
+        // Feed the body byte by byte so events, and the multi-byte character,
+        // straddle chunk boundaries
+        let mut sse = SseBuffer::new(MAX_SSE_LINE_BYTES);
+        let mut state = StreamState::default();
+        let mut responses = Vec::new();
+        for chunk in SSE_BODY.as_bytes().chunks(1) {
+            sse.push(chunk);
+            while let Some(data) = sse.next_data().unwrap() {
+                let event: AnthropicStreamEvent = serde_json::from_str(&data).unwrap();
+                if let Some(response) = state.handle(event).unwrap() {
+                    responses.push(response);
+                }
+            }
+        }
+        assert!(state.finished);
+
+        let texts: Vec<_> = responses
+            .iter()
+            .filter(|r| r.partial)
+            .map(|r| match &r.content.as_ref().unwrap().parts[0] {
+                Part::Text { text } => text.as_str(),
+                other => panic!("unexpected part {:?}", other),
+            })
+            .collect();
+        assert_eq!(texts, vec!["Let me ", "check ☀."]);
+
+        let last = responses.last().unwrap();
+        assert!(last.turn_complete);
+        assert_eq!(last.finish_reason, Some(FinishReason::ToolCalls));
+        assert_eq!(
+            last.usage,
+            Some(TokenUsage {
+                prompt_tokens: 25,
+                completion_tokens: 40,
+                total_tokens: 65,
+            })
+        );
+        match &last.content.as_ref().unwrap().parts[..] {
+            [Part::FunctionCall { function_call }] => {
+                assert_eq!(function_call.name, "get_weather");
+                assert_eq!(function_call.args, json!({ "city": "Paris" }));
+                assert_eq!(function_call.id.as_deref(), Some("toolu_01"));
+            }
+            other => panic!("expected one function call, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ping_checks_credentials() {
+        let mut server = mockito::Server::new_async().await;
+        let ok = server
+            .mock("GET", "/models")
+            .match_query(mockito::Matcher::UrlEncoded("limit".into(), "1".into()))
+            .match_header("x-api-key", "key")
+            .with_body(json!({ "data": [], "has_more": true }).to_string())
+            .create_async()
+            .await;
+        let rejected = server
+            .mock("GET", "/models")
+            .match_query(mockito::Matcher::Any)
+            .match_header("x-api-key", "bad-key")
+            .with_status(401)
+            .with_body(r#"{"type":"error","error":{"type":"authentication_error"}}"#)
+            .create_async()
+            .await;
+
+        let config =
+            AnthropicConfig::with_base_url("claude-3-5-haiku-latest".to_string(), server.url());
+        AnthropicProvider::new("key".to_string(), config.clone())
+            .ping()
+            .await
+            .unwrap();
+        let err = AnthropicProvider::new("bad-key".to_string(), config)
+            .ping()
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("401"));
+
+        ok.assert_async().await;
+        rejected.assert_async().await;
+    }
+
+    #[test]
+    fn test_stream_error_event() {
+        let mut state = StreamState::default();
+        let event: AnthropicStreamEvent = serde_json::from_str(
+            r#"{"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#,
+        )
+        .unwrap();
+        let err = state.handle(event).unwrap_err();
+        assert!(err.to_string().contains("overloaded_error"));
+    }
+
+    #[tokio::test]
+    async fn test_generate_content_over_http() {
+        use futures::StreamExt;
+
+        let mut server = mockito::Server::new_async().await;
+        let stream_mock = server
+            .mock("POST", "/messages")
+            .match_header("x-api-key", "key")
+            .match_header("anthropic-version", super::super::DEFAULT_API_VERSION)
+            .match_body(mockito::Matcher::PartialJson(json!({ "stream": true })))
+            .with_header("content-type", "text/event-stream")
+            .with_body(SSE_BODY)
+            .create_async()
+            .await;
+        let message_mock = server
+            .mock("POST", "/messages")
+            .match_body(mockito::Matcher::PartialJson(json!({ "stream": false })))
+            .with_body(
+                json!({
+                    "id": "msg_2",
+                    "type": "message",
+                    "role": "assistant",
+                    "model": "claude-3-5-haiku-latest",
+                    "content": [{ "type": "text", "text": "Bonjour." }],
+                    "stop_reason": "end_turn",
+                    "usage": { "input_tokens": 10, "output_tokens": 3 }
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let provider = AnthropicProvider::new(
+            "key".to_string(),
+            AnthropicConfig::with_base_url("claude-3-5-sonnet-latest".to_string(), server.url()),
+        );
+        let request = LLMRequest {
+            model: "claude-3-5-sonnet-latest".to_string(),
+            system_instruction: None,
+            contents: vec![Content::new_user_text("Hi")],
+            config: None,
+            tools: vec![],
+        };
+
+        let streamed: Vec<_> = Provider::generate_content(&provider, request.clone(), true)
+            .await
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect()
+            .await;
+        assert_eq!(streamed.len(), 3);
+        assert_eq!(streamed[2].finish_reason, Some(FinishReason::ToolCalls));
+
+        let single: Vec<_> = Provider::generate_content(&provider, request, false)
+            .await
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect()
+            .await;
+        assert_eq!(single.len(), 1);
+        assert_eq!(single[0].finish_reason, Some(FinishReason::Stop));
+        assert_eq!(single[0].usage.unwrap().total_tokens, 13);
+
+        stream_mock.assert_async().await;
+        message_mock.assert_async().await;
+    }
+}
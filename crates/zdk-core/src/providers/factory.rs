@@ -15,6 +15,7 @@ static REGISTRY: Lazy<ProviderRegistry> = Lazy::new(|| {
     // Register all providers (all included by default, no feature flags)
     registry.register("gemini", Box::new(GeminiFactory));
     registry.register("openai", Box::new(OpenAIFactory));
+    registry.register("anthropic", Box::new(AnthropicFactory));
 
     registry
 });
@@ -177,21 +178,28 @@ impl ProviderFactory for OpenAIFactory {
     fn create(&self, config: &ZConfig) -> Result<Arc<dyn Provider>> {
         use crate::providers::openai::{OpenAIConfig, OpenAIProvider};
 
-        let api_key = config
-            .openai_api_key
-            .clone()
-            .ok_or_else(|| {
-                Error::config_error(
-                    "OpenAI API key not found. Set openai_api_key in config.toml or OPENAI_API_KEY env var"
-                )
-            })?;
-
-        let openai_config = if let Some(ref base_url) = config.openai_base_url {
-            OpenAIConfig::with_base_url(config.model.model_name.clone(), base_url.clone())
-        } else {
-            OpenAIConfig::default(config.model.model_name.clone())
+        let (api_key, mut openai_config) = match (&config.openai_api_key, &config.openai_base_url) {
+            (key, Some(base_url)) => (
+                // Local OpenAI-compatible servers (e.g. Ollama) don't check the key
+                key.clone().unwrap_or_default(),
+                OpenAIConfig::with_base_url(config.model.model_name.clone(), base_url.clone()),
+            ),
+            (Some(key), None) => (
+                key.clone(),
+                OpenAIConfig::default(config.model.model_name.clone()),
+            ),
+            (None, None) => {
+                return Err(Error::config_error(
+                    "OpenAI API key not found. Set openai_api_key in config.toml or OPENAI_API_KEY env var",
+                ));
+            }
         };
 
+        if let Some(model) = &config.openai_embedding_model {
+            openai_config.embedding_model = Some(model.clone());
+        }
+        openai_config.embedding_dimensions = config.openai_embedding_dimensions;
+
         Ok(Arc::new(OpenAIProvider::new(api_key, openai_config)))
     }
 
@@ -200,3 +208,31 @@ impl ProviderFactory for OpenAIFactory {
         Ok(OpenAIProvider::static_metadata())
     }
 }
+
+/// Anthropic provider factory
+struct AnthropicFactory;
+
+impl ProviderFactory for AnthropicFactory {
+    fn create(&self, config: &ZConfig) -> Result<Arc<dyn Provider>> {
+        use crate::providers::anthropic::{AnthropicConfig, AnthropicProvider};
+
+        let api_key = config.anthropic_api_key.clone().ok_or_else(|| {
+            Error::config_error(
+                "Anthropic API key not found. Set anthropic_api_key in config.toml or ANTHROPIC_API_KEY env var",
+            )
+        })?;
+
+        let model = config.model.model_name.clone();
+        let anthropic_config = match &config.anthropic_base_url {
+            Some(base_url) => AnthropicConfig::with_base_url(model, base_url.clone()),
+            None => AnthropicConfig::default(model),
+        };
+
+        Ok(Arc::new(AnthropicProvider::new(api_key, anthropic_config)))
+    }
+
+    fn metadata(&self) -> Result<ProviderMetadata> {
+        use crate::providers::anthropic::AnthropicProvider;
+        Ok(AnthropicProvider::static_metadata())
+    }
+}
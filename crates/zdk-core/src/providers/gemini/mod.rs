@@ -8,10 +8,19 @@
 
 pub mod auth;
 pub mod provider;
+pub(crate) mod stream;
 pub mod types;
 
 pub use auth::GeminiAuth;
 pub use provider::GeminiProvider;
+pub use types::{HarmBlockThreshold, HarmCategory, SafetySetting};
+
+use super::rate_limiter::RateLimiter;
+use super::response_limit::DEFAULT_MAX_RESPONSE_BYTES;
+use std::sync::Arc;
+
+/// Default cap on buffered stream data without a complete JSON object (16 MiB)
+pub const DEFAULT_MAX_STREAM_BUFFER_BYTES: usize = 16 * 1024 * 1024;
 
 /// Gemini configuration
 #[derive(Clone, Debug)]
@@ -24,6 +33,14 @@ pub struct GeminiConfig {
     pub embedding_model: Option<String>,
     /// Audio/transcription model name
     pub audio_model: Option<String>,
+    /// Files API upload endpoint (not available on Vertex AI)
+    pub upload_url: Option<String>,
+    /// Hard cap on generated text per response, in bytes
+    pub max_response_bytes: usize,
+    /// Maximum bytes buffered while waiting for a complete streamed JSON object
+    pub max_stream_buffer_bytes: usize,
+    /// Safety filters sent with every generation request (API defaults if empty)
+    pub safety_settings: Vec<SafetySetting>,
 }
 
 impl GeminiConfig {
@@ -34,6 +51,12 @@ impl GeminiConfig {
             base_url: "https://generativelanguage.googleapis.com/v1/models".to_string(),
             embedding_model: Some("text-embedding-004".to_string()),
             audio_model: Some("gemini-pro-audio".to_string()),
+            upload_url: Some(
+                "https://generativelanguage.googleapis.com/upload/v1beta/files".to_string(),
+            ),
+            max_response_bytes: DEFAULT_MAX_RESPONSE_BYTES,
+            max_stream_buffer_bytes: DEFAULT_MAX_STREAM_BUFFER_BYTES,
+            safety_settings: Vec::new(),
         }
     }
 
@@ -47,14 +70,30 @@ impl GeminiConfig {
             ),
             embedding_model: Some("text-embedding-004".to_string()),
             audio_model: Some("gemini-pro-audio".to_string()),
+            upload_url: None,
+            max_response_bytes: DEFAULT_MAX_RESPONSE_BYTES,
+            max_stream_buffer_bytes: DEFAULT_MAX_STREAM_BUFFER_BYTES,
+            safety_settings: Vec::new(),
         }
     }
+
+    /// Block content in `category` at or above `threshold`
+    pub fn with_safety_setting(
+        mut self,
+        category: HarmCategory,
+        threshold: HarmBlockThreshold,
+    ) -> Self {
+        self.safety_settings
+            .push(SafetySetting::new(category, threshold));
+        self
+    }
 }
 
 /// Builder for GeminiProvider
 pub struct GeminiBuilder {
     auth: Option<GeminiAuth>,
     config: Option<GeminiConfig>,
+    rate_limiter: Option<Arc<RateLimiter>>,
 }
 
 impl GeminiBuilder {
@@ -63,6 +102,7 @@ impl GeminiBuilder {
         Self {
             auth: None,
             config: None,
+            rate_limiter: None,
         }
     }
 
@@ -92,6 +132,12 @@ impl GeminiBuilder {
         self
     }
 
+    /// Share a rate limiter across providers so they respect one quota
+    pub fn rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
+
     /// Build the provider
     pub fn build(self) -> crate::Result<GeminiProvider> {
         let auth = self
@@ -101,7 +147,11 @@ impl GeminiBuilder {
             .config
             .ok_or_else(|| crate::Error::config_error("Configuration is required"))?;
 
-        Ok(GeminiProvider::new(auth, config))
+        let provider = GeminiProvider::new(auth, config);
+        Ok(match self.rate_limiter {
+            Some(limiter) => provider.with_rate_limiter(limiter),
+            None => provider,
+        })
     }
 }
 
@@ -1,19 +1,24 @@
 //! Gemini provider implementation
 
-use super::{GeminiConfig, auth::GeminiAuth, types::*};
+use super::{GeminiConfig, auth::GeminiAuth, stream::StreamParser, types::*};
 use crate::{
-    EmbeddingVector, GeminiBuiltinToolType, LLMRequest, LLMResponse, Result,
+    EmbeddingVector, FinishReason, GeminiBuiltinToolType, LLMRequest, LLMResponse, Result,
+    TokenUsage,
     providers::provider::{Capability, ModelInfo, Provider, ProviderMetadata},
+    providers::rate_limiter::{RateLimiter, estimate_request_tokens},
+    providers::response_limit::limit_response_size,
 };
 use async_trait::async_trait;
 use futures::stream::Stream;
 use reqwest::Client;
+use std::sync::Arc;
 
 /// Gemini provider with multi-capability support
 pub struct GeminiProvider {
     client: Client,
     auth: GeminiAuth,
     config: GeminiConfig,
+    rate_limiter: Option<Arc<RateLimiter>>,
 }
 
 impl GeminiProvider {
@@ -23,9 +28,68 @@ impl GeminiProvider {
             client: Client::new(),
             auth,
             config,
+            rate_limiter: None,
         }
     }
 
+    /// Share a rate limiter with other providers
+    pub fn with_rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
+
+    /// Wait for rate limiter quota, if one is configured
+    async fn throttle(&self, estimated_tokens: u32) {
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.acquire(estimated_tokens).await;
+        }
+    }
+
+    /// Query the models endpoint, following pagination
+    async fn fetch_models(&self) -> Result<Vec<ModelInfo>> {
+        let mut models = Vec::new();
+        let mut page_token: Option<String> = None;
+
+        loop {
+            let mut req_builder = self.client.get(&self.config.base_url);
+            if let Some(token) = &page_token {
+                req_builder = req_builder.query(&[("pageToken", token.as_str())]);
+            }
+            req_builder = self.auth.apply(req_builder);
+
+            let response = req_builder.send().await.map_err(|e| {
+                crate::Error::LLMError(format!("List models request failed: {}", e.without_url()))
+            })?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let error_text = response
+                    .text()
+                    .await
+                    .unwrap_or_else(|_| "Unknown error".to_string());
+                return Err(crate::Error::LLMError(format!(
+                    "List models API error {}: {}",
+                    status, error_text
+                )));
+            }
+
+            let json: serde_json::Value = response.json().await.map_err(|e| {
+                crate::Error::LLMError(format!("Failed to parse models response: {}", e))
+            })?;
+
+            if let Some(entries) = json["models"].as_array() {
+                models.extend(entries.iter().filter_map(parse_model_info));
+            }
+
+            match json["nextPageToken"].as_str() {
+                Some(token) if !token.is_empty() => page_token = Some(token.to_string()),
+                _ => break,
+            }
+        }
+
+        Ok(models)
+    }
+
     /// Get static metadata (for factory)
     pub fn static_metadata() -> ProviderMetadata {
         ProviderMetadata {
@@ -90,6 +154,14 @@ impl crate::LLM for GeminiProvider {
         &self.config.model
     }
 
+    fn capabilities(&self) -> Option<Vec<crate::Capability>> {
+        Self::static_metadata().model_capabilities(&self.config.model)
+    }
+
+    fn context_window(&self) -> Option<usize> {
+        Self::static_metadata().model_context_window(&self.config.model)
+    }
+
     async fn generate_content(
         &self,
         request: crate::LLMRequest,
@@ -108,6 +180,22 @@ impl Provider for GeminiProvider {
         Self::static_metadata()
     }
 
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        match self.fetch_models().await {
+            Ok(models) if !models.is_empty() => Ok(models),
+            Ok(_) => Ok(Self::static_metadata().models),
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to list Gemini models, using static list");
+                Ok(Self::static_metadata().models)
+            }
+        }
+    }
+
+    async fn ping(&self) -> Result<()> {
+        // Unlike list_models, surface the failure instead of falling back
+        self.fetch_models().await.map(|_| ())
+    }
+
     async fn generate_content(
         &self,
         request: LLMRequest,
@@ -116,9 +204,13 @@ impl Provider for GeminiProvider {
         use async_stream::stream;
         use futures::stream::StreamExt;
 
+        self.throttle(estimate_request_tokens(&request)).await;
+
         let url = self.build_url(do_stream);
         let client = self.client.clone();
         let auth = self.auth.clone();
+        let max_response_bytes = self.config.max_response_bytes;
+        let max_stream_buffer_bytes = self.config.max_stream_buffer_bytes;
 
         // Separate regular tools from Gemini built-in tools
         let mut function_tools = Vec::new();
@@ -183,20 +275,30 @@ impl Provider for GeminiProvider {
 
         // Convert LLMRequest to GeminiRequest
         let gemini_req = GeminiRequest {
-            contents: request.contents,
+            contents: strip_call_ids(request.contents),
             generation_config: request.config.map(|c| GenerationConfig {
                 temperature: c.temperature,
                 max_output_tokens: c.max_tokens,
                 top_p: c.top_p,
                 top_k: c.top_k,
+                // Gemini only honours a schema on JSON responses
+                response_mime_type: c.response_mime_type.or_else(|| {
+                    c.response_schema
+                        .as_ref()
+                        .map(|_| "application/json".to_string())
+                }),
+                response_schema: c.response_schema,
+            }),
+            system_instruction: request.system_instruction.map(|text| SystemInstruction {
+                parts: vec![SystemPart { text }],
             }),
-            system_instruction: None,
             tools,
+            safety_settings: self.config.safety_settings.clone(),
         };
 
-        if do_stream {
+        let stream: Box<dyn Stream<Item = Result<LLMResponse>> + Send + Unpin> = if do_stream {
             // Streaming response
-            Ok(Box::new(Box::pin(stream! {
+            Box::new(Box::pin(stream! {
                 let mut req_builder = client.post(&url).json(&gemini_req);
 
                 // Apply authentication
@@ -207,49 +309,67 @@ impl Provider for GeminiProvider {
                 match response {
                     Ok(resp) => {
                         let mut stream = resp.bytes_stream();
-                        let mut buffer = String::new();
+                        let mut parser = StreamParser::new(max_stream_buffer_bytes);
+                        let mut usage = None;
+                        let mut finish_reason = None;
 
                         while let Some(chunk) = stream.next().await {
                             match chunk {
                                 Ok(bytes) => {
-                                    if let Ok(text) = std::str::from_utf8(&bytes) {
-                                        buffer.push_str(text);
-
-                                        // Parse JSON objects from buffer
-                                        while let Some(json_str) = extract_json(&mut buffer) {
-                                            match serde_json::from_str::<GeminiResponse>(&json_str) {
-                                                Ok(gemini_resp) => {
-                                                    // Check for API error
-                                                    if let Some(error) = gemini_resp.error {
-                                                        yield Err(crate::Error::LLMError(format!(
-                                                            "Gemini API error: {} (code: {})",
-                                                            error.message,
-                                                            error.code.unwrap_or(0)
-                                                        )));
-                                                        return;
-                                                    }
+                                    parser.push(&bytes);
+
+                                    // Parse complete JSON objects from the buffered bytes
+                                    loop {
+                                        let json_str = match parser.next_object() {
+                                            Ok(Some(json_str)) => json_str,
+                                            Ok(None) => break,
+                                            Err(e) => {
+                                                yield Err(e);
+                                                return;
+                                            }
+                                        };
+                                        match serde_json::from_str::<GeminiResponse>(&json_str) {
+                                            Ok(gemini_resp) => {
+                                                // Check for API error
+                                                if let Some(error) = gemini_resp.error {
+                                                    yield Err(crate::Error::LLMError(format!(
+                                                        "Gemini API error: {} (code: {})",
+                                                        error.message,
+                                                        error.code.unwrap_or(0)
+                                                    )));
+                                                    return;
+                                                }
 
-                                                    if let Some(candidate) = gemini_resp.candidates.first() {
-                                                        yield Ok(LLMResponse {
-                                                            content: Some(candidate.content.clone()),
-                                                            partial: true,
-                                                            turn_complete: false,
-                                                            interrupted: false,
-                                                            finish_reason: candidate.finish_reason.clone(),
-                                                            error_code: None,
-                                                            error_message: None,
-                                                        });
-                                                    }
+                                                // Each chunk carries the running totals
+                                                if let Some(ref metadata) = gemini_resp.usage_metadata {
+                                                    usage = Some(TokenUsage::from(metadata));
                                                 }
-                                                Err(e) => {
-                                                    yield Err(crate::Error::LLMError(format!("Failed to parse response: {}", e)));
+
+                                                if let Some(candidate) = gemini_resp.candidates.first() {
+                                                    let reason = candidate.finish_reason.as_deref().map(FinishReason::parse);
+                                                    if reason.is_some() {
+                                                        finish_reason = reason.clone();
+                                                    }
+                                                    yield Ok(LLMResponse {
+                                                        content: Some(candidate.content.clone()),
+                                                        partial: true,
+                                                        turn_complete: false,
+                                                        interrupted: false,
+                                                        finish_reason: reason,
+                                                        error_code: None,
+                                                        error_message: None,
+                                                        usage: None,
+                                                    });
                                                 }
                                             }
+                                            Err(e) => {
+                                                yield Err(crate::Error::LLMError(format!("Failed to parse response: {}", e)));
+                                            }
                                         }
                                     }
                                 }
                                 Err(e) => {
-                                    yield Err(crate::Error::LLMError(format!("Stream error: {}", e)));
+                                    yield Err(crate::Error::LLMError(format!("Stream error: {}", e.without_url())));
                                 }
                             }
                         }
@@ -260,19 +380,21 @@ impl Provider for GeminiProvider {
                             partial: false,
                             turn_complete: true,
                             interrupted: false,
-                            finish_reason: Some("STOP".to_string()),
+                            // The last chunk's reason, e.g. MAX_TOKENS or SAFETY
+                            finish_reason: Some(finish_reason.unwrap_or(FinishReason::Stop)),
                             error_code: None,
                             error_message: None,
+                            usage,
                         });
                     }
                     Err(e) => {
-                        yield Err(crate::Error::LLMError(format!("Request failed: {}", e)));
+                        yield Err(crate::Error::LLMError(format!("Request failed: {}", e.without_url())));
                     }
                 }
-            })))
+            }))
         } else {
             // Non-streaming response
-            Ok(Box::new(Box::pin(stream! {
+            Box::new(Box::pin(stream! {
                 let mut req_builder = client.post(&url).json(&gemini_req);
 
                 // Apply authentication
@@ -302,9 +424,10 @@ impl Provider for GeminiProvider {
                                         partial: false,
                                         turn_complete: true,
                                         interrupted: false,
-                                        finish_reason: candidate.finish_reason.clone(),
+                                        finish_reason: candidate.finish_reason.as_deref().map(FinishReason::parse),
                                         error_code: None,
                                         error_message: None,
+                                        usage: gemini_resp.usage_metadata.as_ref().map(TokenUsage::from),
                                     });
                                 }
                             }
@@ -314,16 +437,21 @@ impl Provider for GeminiProvider {
                         }
                     }
                     Err(e) => {
-                        yield Err(crate::Error::LLMError(format!("Request failed: {}", e)));
+                        yield Err(crate::Error::LLMError(format!("Request failed: {}", e.without_url())));
                     }
                 }
-            })))
-        }
+            }))
+        };
+
+        Ok(limit_response_size(stream, max_response_bytes))
     }
 
     async fn embed_texts(&self, texts: Vec<String>) -> Result<Vec<EmbeddingVector>> {
         use serde_json::json;
 
+        let chars: usize = texts.iter().map(|t| t.len()).sum();
+        self.throttle((chars / 4).max(1) as u32).await;
+
         let embedding_model = self
             .config
             .embedding_model
@@ -359,7 +487,9 @@ impl Provider for GeminiProvider {
         let response = req_builder
             .send()
             .await
-            .map_err(|e| crate::Error::LLMError(format!("Embedding request failed: {}", e)))?;
+            .map_err(|e| {
+                crate::Error::LLMError(format!("Embedding request failed: {}", e.without_url()))
+            })?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -401,6 +531,52 @@ impl Provider for GeminiProvider {
         Ok(results)
     }
 
+    async fn upload_file(&self, data: Vec<u8>, mime_type: &str) -> Result<String> {
+        let upload_url = self.config.upload_url.as_ref().ok_or_else(|| {
+            crate::Error::config_error("File uploads are not configured for this Gemini endpoint")
+        })?;
+
+        self.throttle(1).await;
+
+        let mut req_builder = self
+            .client
+            .post(upload_url)
+            .query(&[("uploadType", "media")])
+            .header("Content-Type", mime_type)
+            .body(data);
+
+        // Apply authentication
+        req_builder = self.auth.apply(req_builder);
+
+        let response = req_builder
+            .send()
+            .await
+            .map_err(|e| {
+                crate::Error::LLMError(format!("File upload failed: {}", e.without_url()))
+            })?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(crate::Error::LLMError(format!(
+                "File upload API error {}: {}",
+                status, error_text
+            )));
+        }
+
+        let json: serde_json::Value = response.json().await.map_err(|e| {
+            crate::Error::LLMError(format!("Failed to parse upload response: {}", e))
+        })?;
+
+        json["file"]["uri"]
+            .as_str()
+            .map(|uri| uri.to_string())
+            .ok_or_else(|| crate::Error::LLMError("Missing file uri in upload response".into()))
+    }
+
     fn embedding_dimensions(&self) -> Option<usize> {
         Some(768)
     }
@@ -410,39 +586,52 @@ impl Provider for GeminiProvider {
     }
 }
 
-/// Helper function to extract JSON from SSE format
-fn extract_json(buffer: &mut String) -> Option<String> {
-    // Find the start of a JSON object
-    let start = buffer.find('{')?;
-
-    // Track brace depth to find the matching closing brace
-    let mut depth = 0;
-    let mut in_string = false;
-    let mut escape_next = false;
-
-    for (i, c) in buffer[start..].char_indices() {
-        if escape_next {
-            escape_next = false;
-            continue;
+/// Remove tool call IDs, which Gemini rejects in function calls and responses
+fn strip_call_ids(mut contents: Vec<crate::Content>) -> Vec<crate::Content> {
+    for part in contents.iter_mut().flat_map(|content| content.parts.iter_mut()) {
+        match part {
+            crate::Part::FunctionCall { function_call } => function_call.id = None,
+            crate::Part::FunctionResponse { function_response } => function_response.id = None,
+            _ => {}
         }
+    }
+    contents
+}
 
-        match c {
-            '\\' if in_string => escape_next = true,
-            '"' => in_string = !in_string,
-            '{' if !in_string => depth += 1,
-            '}' if !in_string => {
-                depth -= 1;
-                if depth == 0 {
-                    // Found complete JSON object
-                    let end = start + i + 1;
-                    let json_str = buffer[start..end].to_string();
-                    buffer.drain(..end);
-                    return Some(json_str);
-                }
-            }
-            _ => {}
+/// Convert an entry of the Gemini models list into `ModelInfo`
+fn parse_model_info(model: &serde_json::Value) -> Option<ModelInfo> {
+    let name = model["name"].as_str()?;
+    let id = name.strip_prefix("models/").unwrap_or(name).to_string();
+
+    let mut capabilities = Vec::new();
+    for method in model["supportedGenerationMethods"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|m| m.as_str())
+    {
+        let capability = match method {
+            "generateContent" | "streamGenerateContent" => Capability::TextGeneration,
+            "embedContent" | "batchEmbedContents" => Capability::Embedding,
+            _ => continue,
+        };
+        if !capabilities.contains(&capability) {
+            capabilities.push(capability);
         }
     }
 
-    None
+    // The endpoint does not report embedding sizes; reuse known values
+    let embedding_dimensions = GeminiProvider::static_metadata()
+        .models
+        .into_iter()
+        .find(|m| m.id == id)
+        .and_then(|m| m.embedding_dimensions);
+
+    Some(ModelInfo {
+        display_name: model["displayName"].as_str().unwrap_or(&id).to_string(),
+        context_window: model["inputTokenLimit"].as_u64().map(|n| n as usize),
+        id,
+        capabilities,
+        embedding_dimensions,
+    })
 }
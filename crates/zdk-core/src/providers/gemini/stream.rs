@@ -0,0 +1,114 @@
+//! Incremental parser for Gemini streaming responses
+//!
+//! `streamGenerateContent` sends its chunks either as a JSON array streamed
+//! element by element (`[{...},\r\n{...}]`) or, with `alt=sse`, as
+//! server-sent events (`data: {...}\r\n\r\n`). Network chunks split these at
+//! arbitrary byte offsets, including inside a JSON string or a multi-byte
+//! character, so bytes are buffered until a whole element is available.
+
+use crate::Result;
+
+/// Buffers raw stream bytes and yields one complete JSON element at a time
+pub(crate) struct StreamParser {
+    buffer: Vec<u8>,
+    max_buffer_bytes: usize,
+}
+
+impl StreamParser {
+    /// Errors once `max_buffer_bytes` are buffered without a complete element,
+    /// so a malformed or adversarial stream cannot grow the buffer without bound
+    pub(crate) fn new(max_buffer_bytes: usize) -> Self {
+        Self {
+            buffer: Vec::new(),
+            max_buffer_bytes,
+        }
+    }
+
+    /// Append a chunk as received from the network
+    pub(crate) fn push(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Take the next complete JSON object, or `None` until more bytes arrive
+    pub(crate) fn next_object(&mut self) -> Result<Option<String>> {
+        self.skip_framing();
+
+        if self.buffer.first() != Some(&b'{') {
+            return self.overflow();
+        }
+        let Some(end) = object_end(&self.buffer) else {
+            return self.overflow();
+        };
+
+        let object: Vec<u8> = self.buffer.drain(..end).collect();
+        String::from_utf8(object)
+            .map(Some)
+            .map_err(|e| crate::Error::LLMError(format!("Stream contained invalid UTF-8: {}", e)))
+    }
+
+    /// Drop array brackets, separators, whitespace and SSE field prefixes
+    /// ahead of the next object
+    fn skip_framing(&mut self) {
+        let mut start = 0;
+        while start < self.buffer.len() {
+            let rest = &self.buffer[start..];
+            match rest[0] {
+                b'{' => break,
+                b'[' | b']' | b',' => start += 1,
+                b if b.is_ascii_whitespace() => start += 1,
+                _ if rest.starts_with(b"data:") => start += b"data:".len(),
+                // Wait for the rest of what may be a `data:` prefix
+                _ if b"data:".starts_with(rest) => break,
+                // Other SSE lines (`event:`, `: keep-alive`) carry no payload
+                _ => match rest.iter().position(|&b| b == b'\n') {
+                    Some(newline) => start += newline + 1,
+                    None => break,
+                },
+            }
+        }
+        self.buffer.drain(..start);
+    }
+
+    fn overflow(&self) -> Result<Option<String>> {
+        if self.buffer.len() > self.max_buffer_bytes {
+            Err(crate::Error::LLMError(format!(
+                "Stream buffer exceeded {} bytes without a complete JSON object",
+                self.max_buffer_bytes
+            )))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// Byte offset just past the object starting at `bytes[0]`, if it is complete
+///
+/// Structural characters are ASCII, so scanning bytes is safe even when a
+/// multi-byte character is cut off at the end of the buffer.
+fn object_end(bytes: &[u8]) -> Option<usize> {
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escape_next = false;
+
+    for (i, &b) in bytes.iter().enumerate() {
+        if escape_next {
+            escape_next = false;
+            continue;
+        }
+
+        match b {
+            b'\\' if in_string => escape_next = true,
+            b'"' => in_string = !in_string,
+            b'{' if !in_string => depth += 1,
+            b'}' if !in_string => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
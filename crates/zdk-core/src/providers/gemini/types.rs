@@ -12,6 +12,47 @@ pub struct GeminiRequest {
     pub system_instruction: Option<SystemInstruction>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub tools: Vec<GeminiTool>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub safety_settings: Vec<SafetySetting>,
+}
+
+/// Blocking threshold for one harm category
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SafetySetting {
+    pub category: HarmCategory,
+    pub threshold: HarmBlockThreshold,
+}
+
+impl SafetySetting {
+    pub fn new(category: HarmCategory, threshold: HarmBlockThreshold) -> Self {
+        Self { category, threshold }
+    }
+}
+
+/// Harm categories Gemini can filter on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HarmCategory {
+    #[serde(rename = "HARM_CATEGORY_HARASSMENT")]
+    Harassment,
+    #[serde(rename = "HARM_CATEGORY_HATE_SPEECH")]
+    HateSpeech,
+    #[serde(rename = "HARM_CATEGORY_SEXUALLY_EXPLICIT")]
+    SexuallyExplicit,
+    #[serde(rename = "HARM_CATEGORY_DANGEROUS_CONTENT")]
+    DangerousContent,
+    #[serde(rename = "HARM_CATEGORY_CIVIC_INTEGRITY")]
+    CivicIntegrity,
+}
+
+/// Probability at and above which content is blocked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum HarmBlockThreshold {
+    BlockLowAndAbove,
+    BlockMediumAndAbove,
+    BlockOnlyHigh,
+    BlockNone,
+    Off,
 }
 
 /// Tool definition for Gemini API
@@ -64,6 +105,10 @@ pub struct GenerationConfig {
     pub top_p: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub top_k: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_mime_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_schema: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -106,3 +151,17 @@ pub struct UsageMetadata {
     pub candidates_token_count: Option<u32>,
     pub total_token_count: Option<u32>,
 }
+
+impl From<&UsageMetadata> for crate::TokenUsage {
+    fn from(usage: &UsageMetadata) -> Self {
+        let prompt_tokens = usage.prompt_token_count.unwrap_or(0);
+        let completion_tokens = usage.candidates_token_count.unwrap_or(0);
+        Self {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens: usage
+                .total_token_count
+                .unwrap_or(prompt_tokens + completion_tokens),
+        }
+    }
+}
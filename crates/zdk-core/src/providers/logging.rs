@@ -0,0 +1,181 @@
+//! Request and response logging for any model
+//!
+//! [`LoggingLLM`] wraps a model and logs each outgoing request and every
+//! streamed response as JSON through `tracing`, leaving both untouched. Useful
+//! for debugging a provider without editing it.
+
+use crate::providers::replay::RecordedRequest;
+use crate::{LLM, LLMRequest, LLMResponse, Result};
+use async_stream::stream;
+use async_trait::async_trait;
+use futures::stream::{Stream, StreamExt};
+use std::sync::Arc;
+use tracing::Level;
+
+/// Decorator that logs the traffic of the wrapped model
+pub struct LoggingLLM {
+    inner: Arc<dyn LLM>,
+    level: Level,
+}
+
+impl LoggingLLM {
+    /// Log calls to `inner` at `DEBUG` level
+    pub fn new(inner: Arc<dyn LLM>) -> Self {
+        Self {
+            inner,
+            level: Level::DEBUG,
+        }
+    }
+
+    /// Log at `level` instead
+    pub fn with_level(mut self, level: Level) -> Self {
+        self.level = level;
+        self
+    }
+}
+
+#[async_trait]
+impl LLM for LoggingLLM {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn capabilities(&self) -> Option<Vec<crate::Capability>> {
+        self.inner.capabilities()
+    }
+
+    fn context_window(&self) -> Option<usize> {
+        self.inner.context_window()
+    }
+
+    async fn generate_content(
+        &self,
+        request: LLMRequest,
+        stream: bool,
+    ) -> Box<dyn Stream<Item = Result<LLMResponse>> + Send + Unpin> {
+        let level = self.level;
+        let model = request.model.clone();
+        if enabled(level) {
+            // Tools are logged by name, as in recorded cassettes
+            let payload = RecordedRequest::from_request(&request)
+                .and_then(|recorded| Ok(serde_json::to_string(&recorded)?))
+                .unwrap_or_else(|e| format!("<unserializable request: {}>", e));
+            log(level, &model, "LLM request", &payload);
+        }
+
+        let mut inner_stream = self.inner.generate_content(request, stream).await;
+
+        Box::new(Box::pin(stream! {
+            while let Some(item) = inner_stream.next().await {
+                if enabled(level) {
+                    let payload = match &item {
+                        Ok(response) => serde_json::to_string(response)
+                            .unwrap_or_else(|e| format!("<unserializable response: {}>", e)),
+                        Err(e) => format!("<error: {}>", e),
+                    };
+                    log(level, &model, "LLM response", &payload);
+                }
+                yield item;
+            }
+        }))
+    }
+}
+
+/// Whether a log line at `level` would be recorded, so payloads are only
+/// serialized when someone is listening
+fn enabled(level: Level) -> bool {
+    match level {
+        Level::ERROR => tracing::enabled!(Level::ERROR),
+        Level::WARN => tracing::enabled!(Level::WARN),
+        Level::INFO => tracing::enabled!(Level::INFO),
+        Level::DEBUG => tracing::enabled!(Level::DEBUG),
+        Level::TRACE => tracing::enabled!(Level::TRACE),
+    }
+}
+
+/// Emit one log line at a level chosen at runtime
+fn log(level: Level, model: &str, message: &str, payload: &str) {
+    match level {
+        Level::ERROR => tracing::error!(model, payload, "{}", message),
+        Level::WARN => tracing::warn!(model, payload, "{}", message),
+        Level::INFO => tracing::info!(model, payload, "{}", message),
+        Level::DEBUG => tracing::debug!(model, payload, "{}", message),
+        Level::TRACE => tracing::trace!(model, payload, "{}", message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Content, FinishReason};
+
+    /// Streams two chunks, then fails
+    struct ChunkedLLM;
+
+    #[async_trait]
+    impl LLM for ChunkedLLM {
+        fn name(&self) -> &str {
+            "chunked-llm"
+        }
+
+        async fn generate_content(
+            &self,
+            _request: LLMRequest,
+            _stream: bool,
+        ) -> Box<dyn Stream<Item = Result<LLMResponse>> + Send + Unpin> {
+            Box::new(Box::pin(stream! {
+                for response in responses() {
+                    yield Ok(response);
+                }
+                yield Err(crate::Error::LLMError("connection reset".to_string()));
+            }))
+        }
+    }
+
+    fn responses() -> Vec<LLMResponse> {
+        ["Hello ", "world"]
+            .iter()
+            .enumerate()
+            .map(|(i, text)| LLMResponse {
+                content: Some(Content::new_model_text(*text)),
+                partial: i == 0,
+                turn_complete: i == 1,
+                interrupted: false,
+                finish_reason: (i == 1).then_some(FinishReason::Stop),
+                error_code: None,
+                error_message: None,
+                usage: None,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_logging_llm_passes_responses_through() {
+        let llm = LoggingLLM::new(Arc::new(ChunkedLLM)).with_level(Level::INFO);
+        assert_eq!(llm.name(), "chunked-llm");
+
+        let request = LLMRequest {
+            model: "chunked-llm".to_string(),
+            system_instruction: None,
+            contents: vec![Content::new_user_text("Hi")],
+            config: None,
+            tools: Vec::new(),
+        };
+        let items: Vec<_> = llm.generate_content(request, true).await.collect().await;
+
+        assert_eq!(items.len(), 3);
+        for (item, expected) in items.iter().zip(responses()) {
+            assert_eq!(
+                serde_json::to_value(item.as_ref().unwrap()).unwrap(),
+                serde_json::to_value(&expected).unwrap()
+            );
+        }
+        assert!(
+            items[2]
+                .as_ref()
+                .unwrap_err()
+                .to_string()
+                .contains("connection reset")
+        );
+    }
+}
@@ -13,6 +13,7 @@
 //!
 //! - **Gemini**: Google's Gemini models
 //! - **OpenAI**: OpenAI's GPT and other models
+//! - **Anthropic**: Anthropic's Claude models
 //!
 //! # Example
 //!
@@ -34,12 +35,19 @@
 //! ```
 
 pub mod factory;
+pub mod logging;
 pub mod provider;
+pub mod rate_limiter;
+pub mod replay;
+pub mod response_limit;
+pub mod retry;
+pub(crate) mod sse;
 
 // Core utilities (will be added in next milestone)
 // pub mod core;
 
 // Provider implementations
+pub mod anthropic;
 pub mod gemini;
 pub mod openai;
 
@@ -49,8 +57,16 @@ mod provider_tests;
 
 // Re-exports
 pub use factory::{ProviderFactory, ProviderRegistry};
+pub use logging::LoggingLLM;
 pub use provider::{Capability, ModelInfo, Provider, ProviderMetadata};
+pub use rate_limiter::{RateLimiter, estimate_request_tokens};
+pub use replay::{RecordingProvider, ReplayProvider};
+pub use response_limit::{DEFAULT_MAX_RESPONSE_BYTES, TRUNCATED, limit_response_size};
+pub use retry::{
+    DEFAULT_RETRY_BACKOFF, MAX_RETRY_DELAY, RetryingLLM, is_retryable_llm_error, retry_delay,
+};
 
 // Provider re-exports
+pub use anthropic::AnthropicProvider;
 pub use gemini::{GeminiAuth, GeminiProvider};
 pub use openai::OpenAIProvider;
@@ -12,6 +12,13 @@ pub mod types;
 
 pub use provider::OpenAIProvider;
 
+use super::rate_limiter::RateLimiter;
+use super::response_limit::DEFAULT_MAX_RESPONSE_BYTES;
+use std::sync::Arc;
+
+/// Embedding model used when none is configured
+pub const DEFAULT_EMBEDDING_MODEL: &str = "text-embedding-3-small";
+
 /// OpenAI configuration
 #[derive(Clone, Debug)]
 pub struct OpenAIConfig {
@@ -19,8 +26,17 @@ pub struct OpenAIConfig {
     pub model: String,
     /// Base URL for API requests
     pub base_url: String,
-    /// Embedding model name
+    /// Embedding model name (defaults to `text-embedding-3-small` when unset)
     pub embedding_model: Option<String>,
+    /// Vector size of the embedding model, for models OpenAI doesn't list
+    /// (e.g. `nomic-embed-text` served by Ollama)
+    pub embedding_dimensions: Option<usize>,
+    /// Image generation model name
+    pub image_model: Option<String>,
+    /// Text-to-speech model name
+    pub tts_model: Option<String>,
+    /// Hard cap on generated text per response, in bytes
+    pub max_response_bytes: usize,
 }
 
 impl OpenAIConfig {
@@ -29,7 +45,11 @@ impl OpenAIConfig {
         Self {
             model,
             base_url: "https://api.openai.com/v1".to_string(),
-            embedding_model: Some("text-embedding-3-small".to_string()),
+            embedding_model: Some(DEFAULT_EMBEDDING_MODEL.to_string()),
+            embedding_dimensions: None,
+            image_model: Some("dall-e-3".to_string()),
+            tts_model: Some("tts-1".to_string()),
+            max_response_bytes: DEFAULT_MAX_RESPONSE_BYTES,
         }
     }
 
@@ -38,15 +58,31 @@ impl OpenAIConfig {
         Self {
             model,
             base_url,
-            embedding_model: Some("text-embedding-3-small".to_string()),
+            embedding_model: Some(DEFAULT_EMBEDDING_MODEL.to_string()),
+            embedding_dimensions: None,
+            image_model: Some("dall-e-3".to_string()),
+            tts_model: Some("tts-1".to_string()),
+            max_response_bytes: DEFAULT_MAX_RESPONSE_BYTES,
         }
     }
+
+    /// Use a different embedding model, with its vector size if OpenAI doesn't list it
+    pub fn with_embedding_model(
+        mut self,
+        model: impl Into<String>,
+        dimensions: Option<usize>,
+    ) -> Self {
+        self.embedding_model = Some(model.into());
+        self.embedding_dimensions = dimensions;
+        self
+    }
 }
 
 /// Builder for OpenAIProvider
 pub struct OpenAIBuilder {
     api_key: Option<String>,
     config: Option<OpenAIConfig>,
+    rate_limiter: Option<Arc<RateLimiter>>,
 }
 
 impl OpenAIBuilder {
@@ -55,6 +91,7 @@ impl OpenAIBuilder {
         Self {
             api_key: None,
             config: None,
+            rate_limiter: None,
         }
     }
 
@@ -83,6 +120,12 @@ impl OpenAIBuilder {
         self
     }
 
+    /// Share a rate limiter across providers so they respect one quota
+    pub fn rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
+
     /// Build the provider
     pub fn build(self) -> crate::Result<OpenAIProvider> {
         let api_key = self
@@ -92,7 +135,11 @@ impl OpenAIBuilder {
             .config
             .ok_or_else(|| crate::Error::config_error("Configuration is required"))?;
 
-        Ok(OpenAIProvider::new(api_key, config))
+        let provider = OpenAIProvider::new(api_key, config);
+        Ok(match self.rate_limiter {
+            Some(limiter) => provider.with_rate_limiter(limiter),
+            None => provider,
+        })
     }
 }
 
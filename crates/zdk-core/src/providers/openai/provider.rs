@@ -1,19 +1,27 @@
 //! OpenAI provider implementation
 
-use super::{OpenAIConfig, types::*};
+use super::{DEFAULT_EMBEDDING_MODEL, OpenAIConfig, types::*};
 use crate::{
-    AudioInput, EmbeddingVector, LLMRequest, LLMResponse, Result, TranscriptionResult,
+    AudioInput, AudioRequest, AudioResult, EmbeddingVector, FinishReason, GenerateConfig,
+    GeneratedImage, ImageRequest, ImageResult, LLMRequest, LLMResponse, Result, TokenUsage,
+    TranscriptionResult, TranscriptionSegment,
     providers::provider::{Capability, ModelInfo, Provider, ProviderMetadata},
+    providers::rate_limiter::{RateLimiter, estimate_request_tokens},
+    providers::response_limit::limit_response_size,
+    providers::sse::{MAX_SSE_LINE_BYTES, SseBuffer},
 };
 use async_trait::async_trait;
 use futures::stream::Stream;
 use reqwest::Client;
+use std::collections::BTreeMap;
+use std::sync::Arc;
 
 /// OpenAI provider with multi-capability support
 pub struct OpenAIProvider {
     client: Client,
     api_key: String,
     config: OpenAIConfig,
+    rate_limiter: Option<Arc<RateLimiter>>,
 }
 
 impl OpenAIProvider {
@@ -23,60 +31,258 @@ impl OpenAIProvider {
             client: Client::new(),
             api_key,
             config,
+            rate_limiter: None,
         }
     }
 
-    /// Convert ZDK Content format to OpenAI messages format
-    fn convert_contents_to_messages(&self, contents: Vec<crate::Content>) -> Vec<OpenAIMessage> {
-        use crate::Part;
+    /// Share a rate limiter with other providers
+    pub fn with_rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
 
-        contents
-            .into_iter()
-            .map(|content| {
-                let role = match content.role.as_str() {
-                    "user" => "user",
-                    "model" => "assistant",
-                    "system" => "system",
-                    _ => "user",
-                };
+    /// Wait for rate limiter quota, if one is configured
+    async fn throttle(&self, estimated_tokens: u32) {
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.acquire(estimated_tokens).await;
+        }
+    }
+
+    /// Query the models endpoint
+    async fn fetch_models(&self) -> Result<Vec<ModelInfo>> {
+        let url = format!("{}/models", self.config.base_url);
+
+        let response = self
+            .client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .send()
+            .await
+            .map_err(|e| crate::Error::LLMError(format!("List models request failed: {}", e)))?;
 
-                // Extract text from parts
-                let text = content
-                    .parts
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(crate::Error::LLMError(format!(
+                "List models API error {}: {}",
+                status, error_text
+            )));
+        }
+
+        let json: serde_json::Value = response.json().await.map_err(|e| {
+            crate::Error::LLMError(format!("Failed to parse models response: {}", e))
+        })?;
+
+        let known = Self::static_metadata().models;
+        let models = json["data"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|m| m["id"].as_str())
+            .map(|id| {
+                // The endpoint only returns ids; reuse known details where available
+                known
                     .iter()
-                    .filter_map(|part| {
-                        if let Part::Text { text } = part {
-                            Some(text.clone())
-                        } else {
-                            None
-                        }
+                    .find(|m| m.id == id)
+                    .cloned()
+                    .unwrap_or_else(|| ModelInfo {
+                        id: id.to_string(),
+                        display_name: id.to_string(),
+                        capabilities: vec![capability_from_model_id(id)],
+                        context_window: None,
+                        embedding_dimensions: None,
                     })
-                    .collect::<Vec<_>>()
-                    .join("\n");
+            })
+            .collect();
+
+        Ok(models)
+    }
 
-                OpenAIMessage {
-                    role: role.to_string(),
-                    content: text,
+    /// Convert ZDK Content format to OpenAI messages format
+    ///
+    /// Function calls become `tool_calls` on the assistant message, and each
+    /// function response becomes its own `tool` message.
+    fn convert_contents_to_messages(
+        &self,
+        contents: Vec<crate::Content>,
+    ) -> Result<Vec<OpenAIMessage>> {
+        use crate::Part;
+
+        let mut messages = Vec::with_capacity(contents.len());
+        for content in contents {
+            let role = match content.role.as_str() {
+                "user" => "user",
+                "model" => "assistant",
+                "system" => "system",
+                _ => "user",
+            };
+
+            // Tool results must directly follow the assistant message that called them
+            for part in &content.parts {
+                if let Part::FunctionResponse { function_response } = part {
+                    messages.push(OpenAIMessage {
+                        role: "tool".to_string(),
+                        content: Some(OpenAIContent::Text(function_response.response.to_string())),
+                        tool_calls: Vec::new(),
+                        tool_call_id: Some(tool_call_id(
+                            &function_response.id,
+                            &function_response.name,
+                        )?),
+                    });
                 }
+            }
+
+            // Extract text from parts
+            let text = content
+                .parts
+                .iter()
+                .filter_map(|part| {
+                    if let Part::Text { text } = part {
+                        Some(text.clone())
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            let tool_calls = content
+                .parts
+                .iter()
+                .filter_map(|part| match part {
+                    Part::FunctionCall { function_call } => Some(function_call),
+                    _ => None,
+                })
+                .map(|function_call| {
+                    Ok(OpenAIToolCall {
+                        id: tool_call_id(&function_call.id, &function_call.name)?,
+                        call_type: "function".to_string(),
+                        function: OpenAIFunctionCall {
+                            name: function_call.name.clone(),
+                            arguments: function_call.args.to_string(),
+                        },
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            // Inline images are sent as data URLs alongside the text
+            let images: Vec<OpenAIContentPart> = content
+                .parts
+                .iter()
+                .filter_map(|part| match part {
+                    Part::InlineData { inline_data }
+                        if inline_data.mime_type.starts_with("image/") =>
+                    {
+                        Some(OpenAIContentPart::ImageUrl {
+                            image_url: OpenAIImageUrl {
+                                url: format!(
+                                    "data:{};base64,{}",
+                                    inline_data.mime_type, inline_data.data
+                                ),
+                            },
+                        })
+                    }
+                    _ => None,
+                })
+                .collect();
+
+            let has_responses = content
+                .parts
+                .iter()
+                .any(|part| matches!(part, Part::FunctionResponse { .. }));
+            if text.is_empty() && images.is_empty() && tool_calls.is_empty() && has_responses {
+                continue;
+            }
+
+            let message_content = if images.is_empty() {
+                (!text.is_empty() || tool_calls.is_empty()).then_some(OpenAIContent::Text(text))
+            } else {
+                let text_part = (!text.is_empty()).then_some(OpenAIContentPart::Text { text });
+                Some(OpenAIContent::Parts(
+                    text_part.into_iter().chain(images).collect(),
+                ))
+            };
+
+            messages.push(OpenAIMessage {
+                role: role.to_string(),
+                content: message_content,
+                tool_calls,
+                tool_call_id: None,
+            });
+        }
+        Ok(messages)
+    }
+
+    /// Map the structured output options onto `response_format`
+    pub(crate) fn response_format(config: &GenerateConfig) -> Option<serde_json::Value> {
+        if let Some(schema) = &config.response_schema {
+            return Some(serde_json::json!({
+                "type": "json_schema",
+                "json_schema": { "name": "response", "schema": schema },
+            }));
+        }
+        (config.response_mime_type.as_deref() == Some("application/json"))
+            .then(|| serde_json::json!({ "type": "json_object" }))
+    }
+
+    /// Convert ZDK tools to OpenAI function tools
+    fn convert_tools(tools: &[Arc<dyn crate::Tool>]) -> Vec<OpenAITool> {
+        tools
+            .iter()
+            // Gemini built-in tools have no OpenAI equivalent
+            .filter(|tool| tool.gemini_builtin_type().is_none())
+            .map(|tool| OpenAITool {
+                tool_type: "function".to_string(),
+                function: OpenAIFunctionDefinition {
+                    name: tool.name().to_string(),
+                    description: tool.description().to_string(),
+                    parameters: tool.schema(),
+                },
             })
             .collect()
     }
 
     /// Convert OpenAI response to ZDK Content format
-    fn convert_message_to_content(message: &OpenAIMessage) -> crate::Content {
+    fn convert_message_to_content(message: &OpenAIMessage) -> Result<crate::Content> {
         use crate::Part;
 
-        crate::Content {
+        let mut parts = Vec::new();
+        if let Some(text) = message
+            .content
+            .as_ref()
+            .map(OpenAIContent::text)
+            .filter(|text| !text.is_empty())
+        {
+            parts.push(Part::Text { text });
+        }
+        for call in &message.tool_calls {
+            parts.push(tool_call_to_part(
+                call.id.clone(),
+                call.function.name.clone(),
+                &call.function.arguments,
+            )?);
+        }
+
+        Ok(crate::Content {
             role: match message.role.as_str() {
                 "assistant" => "model".to_string(),
                 "user" => "user".to_string(),
                 "system" => "system".to_string(),
                 _ => "model".to_string(),
             },
-            parts: vec![Part::Text {
-                text: message.content.clone(),
-            }],
-        }
+            parts,
+        })
+    }
+
+    /// The configured embedding model, or the default
+    fn embedding_model(&self) -> &str {
+        self.config
+            .embedding_model
+            .as_deref()
+            .unwrap_or(DEFAULT_EMBEDDING_MODEL)
     }
 
     /// Get static metadata (for factory)
@@ -88,8 +294,8 @@ impl OpenAIProvider {
                 Capability::TextGeneration,
                 Capability::Embedding,
                 Capability::Transcription,
-                // Capability::ImageGeneration, // Future
-                // Capability::AudioGeneration,  // Future
+                Capability::ImageGeneration,
+                Capability::AudioGeneration,
             ],
             models: vec![
                 ModelInfo {
@@ -113,6 +319,20 @@ impl OpenAIProvider {
                     context_window: None,
                     embedding_dimensions: Some(1536),
                 },
+                ModelInfo {
+                    id: "text-embedding-3-large".to_string(),
+                    display_name: "Text Embedding 3 Large".to_string(),
+                    capabilities: vec![Capability::Embedding],
+                    context_window: None,
+                    embedding_dimensions: Some(3072),
+                },
+                ModelInfo {
+                    id: "text-embedding-ada-002".to_string(),
+                    display_name: "Text Embedding Ada 002".to_string(),
+                    capabilities: vec![Capability::Embedding],
+                    context_window: None,
+                    embedding_dimensions: Some(1536),
+                },
                 ModelInfo {
                     id: "whisper-1".to_string(),
                     display_name: "Whisper".to_string(),
@@ -120,6 +340,20 @@ impl OpenAIProvider {
                     context_window: None,
                     embedding_dimensions: None,
                 },
+                ModelInfo {
+                    id: "dall-e-3".to_string(),
+                    display_name: "DALL-E 3".to_string(),
+                    capabilities: vec![Capability::ImageGeneration],
+                    context_window: None,
+                    embedding_dimensions: None,
+                },
+                ModelInfo {
+                    id: "tts-1".to_string(),
+                    display_name: "TTS".to_string(),
+                    capabilities: vec![Capability::AudioGeneration],
+                    context_window: None,
+                    embedding_dimensions: None,
+                },
             ],
         }
     }
@@ -131,6 +365,14 @@ impl crate::LLM for OpenAIProvider {
         &self.config.model
     }
 
+    fn capabilities(&self) -> Option<Vec<crate::Capability>> {
+        Self::static_metadata().model_capabilities(&self.config.model)
+    }
+
+    fn context_window(&self) -> Option<usize> {
+        Self::static_metadata().model_context_window(&self.config.model)
+    }
+
     async fn generate_content(
         &self,
         request: crate::LLMRequest,
@@ -149,6 +391,22 @@ impl Provider for OpenAIProvider {
         Self::static_metadata()
     }
 
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        match self.fetch_models().await {
+            Ok(models) if !models.is_empty() => Ok(models),
+            Ok(_) => Ok(Self::static_metadata().models),
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to list OpenAI models, using static list");
+                Ok(Self::static_metadata().models)
+            }
+        }
+    }
+
+    async fn ping(&self) -> Result<()> {
+        // Unlike list_models, surface the failure instead of falling back
+        self.fetch_models().await.map(|_| ())
+    }
+
     async fn generate_content(
         &self,
         request: LLMRequest,
@@ -158,12 +416,19 @@ impl Provider for OpenAIProvider {
         use async_stream::stream;
         use futures::stream::StreamExt;
 
+        self.throttle(estimate_request_tokens(&request)).await;
+
         let url = format!("{}/chat/completions", self.config.base_url);
         let client = self.client.clone();
+        let max_response_bytes = self.config.max_response_bytes;
         let api_key = self.api_key.clone();
 
-        // Convert LLMRequest to OpenAIRequest
-        let messages = self.convert_contents_to_messages(request.contents);
+        // Convert LLMRequest to OpenAIRequest, system prompt first
+        let mut messages = Vec::with_capacity(request.contents.len() + 1);
+        if let Some(instruction) = request.system_instruction {
+            messages.push(OpenAIMessage::text("system", instruction));
+        }
+        messages.extend(self.convert_contents_to_messages(request.contents)?);
         let openai_req = OpenAIRequest {
             model: self.config.model.clone(),
             messages,
@@ -171,11 +436,16 @@ impl Provider for OpenAIProvider {
             max_tokens: request.config.as_ref().and_then(|c| c.max_tokens),
             top_p: request.config.as_ref().and_then(|c| c.top_p),
             stream: Some(do_stream),
+            stream_options: do_stream.then_some(OpenAIStreamOptions {
+                include_usage: true,
+            }),
+            tools: Self::convert_tools(&request.tools),
+            response_format: request.config.as_ref().and_then(Self::response_format),
         };
 
-        if do_stream {
+        let stream: Box<dyn Stream<Item = Result<LLMResponse>> + Send + Unpin> = if do_stream {
             // Streaming response
-            Ok(Box::new(Box::pin(stream! {
+            Box::new(Box::pin(stream! {
                 let response = client
                     .post(&url)
                     .header("Authorization", format!("Bearer {}", api_key))
@@ -194,75 +464,125 @@ impl Provider for OpenAIProvider {
                         }
 
                         let mut stream = resp.bytes_stream();
+                        let mut usage = None;
+                        let mut finish_reason = None;
+                        // Tool calls arrive in fragments keyed by index: (id, name, arguments)
+                        let mut tool_calls: BTreeMap<usize, (String, String, String)> = BTreeMap::new();
+
+                        let mut sse = SseBuffer::new(MAX_SSE_LINE_BYTES);
 
                         while let Some(chunk) = stream.next().await {
                             match chunk {
-                                Ok(bytes) => {
-                                    if let Ok(text) = std::str::from_utf8(&bytes) {
-                                        // Parse SSE format: "data: {json}\n\n"
-                                        for line in text.lines() {
-                                            if let Some(json_str) = line.strip_prefix("data: ") {
-                                                // Check for end of stream
-                                                if json_str.trim() == "[DONE]" {
-                                                    continue;
-                                                }
+                                Ok(bytes) => sse.push(&bytes),
+                                Err(e) => {
+                                    yield Err(crate::Error::LLMError(format!("Stream error: {}", e)));
+                                    return;
+                                }
+                            }
+
+                            // Lines split across chunks wait in the buffer for the rest
+                            loop {
+                                let data = match sse.next_data() {
+                                    Ok(Some(data)) => data,
+                                    Ok(None) => break,
+                                    Err(e) => {
+                                        yield Err(e);
+                                        return;
+                                    }
+                                };
+                                // Check for end of stream
+                                if data == "[DONE]" {
+                                    continue;
+                                }
+
+                                match serde_json::from_str::<OpenAIStreamResponse>(&data) {
+                                    Ok(stream_resp) => {
+                                        if let Some(ref chunk_usage) = stream_resp.usage {
+                                            usage = Some(TokenUsage::from(chunk_usage));
+                                        }
 
-                                                match serde_json::from_str::<OpenAIStreamResponse>(json_str) {
-                                                    Ok(stream_resp) => {
-                                                        if let Some(choice) = stream_resp.choices.first()
-                                                            && let Some(ref content) = choice.delta.content
-                                                        {
-                                                            let finish_reason = choice.finish_reason.clone();
-                                                            let is_done = finish_reason.is_some();
-
-                                                            yield Ok(LLMResponse {
-                                                                content: Some(Content {
-                                                                    role: "model".to_string(),
-                                                                    parts: vec![Part::Text { text: content.clone() }],
-                                                                }),
-                                                                partial: true,
-                                                                turn_complete: is_done,
-                                                                interrupted: false,
-                                                                finish_reason,
-                                                                error_code: None,
-                                                                error_message: None,
-                                                            });
-                                                        }
+                                        if let Some(choice) = stream_resp.choices.first() {
+                                            if choice.finish_reason.is_some() {
+                                                finish_reason = choice.finish_reason.clone();
+                                            }
+                                            for fragment in &choice.delta.tool_calls {
+                                                let call = tool_calls.entry(fragment.index).or_default();
+                                                if let Some(ref id) = fragment.id {
+                                                    call.0 = id.clone();
+                                                }
+                                                if let Some(ref function) = fragment.function {
+                                                    if let Some(ref name) = function.name {
+                                                        call.1.push_str(name);
                                                     }
-                                                    Err(_e) => {
-                                                        // Skip invalid SSE chunks
+                                                    if let Some(ref arguments) = function.arguments {
+                                                        call.2.push_str(arguments);
                                                     }
                                                 }
                                             }
                                         }
+
+                                        if let Some(choice) = stream_resp.choices.first()
+                                            && let Some(ref content) = choice.delta.content
+                                        {
+                                            let finish_reason = choice.finish_reason.as_deref().map(FinishReason::parse);
+                                            let is_done = finish_reason.is_some();
+
+                                            yield Ok(LLMResponse {
+                                                content: Some(Content {
+                                                    role: "model".to_string(),
+                                                    parts: vec![Part::Text { text: content.clone() }],
+                                                }),
+                                                partial: true,
+                                                turn_complete: is_done,
+                                                interrupted: false,
+                                                finish_reason,
+                                                error_code: None,
+                                                error_message: None,
+                                                usage: None,
+                                            });
+                                        }
+                                    }
+                                    Err(_e) => {
+                                        // Skip invalid SSE chunks
                                     }
                                 }
+                            }
+                        }
+
+                        // Final response, carrying any completed tool calls
+                        let mut parts = Vec::with_capacity(tool_calls.len());
+                        for (id, name, arguments) in tool_calls.into_values() {
+                            match tool_call_to_part(id, name, &arguments) {
+                                Ok(part) => parts.push(part),
                                 Err(e) => {
-                                    yield Err(crate::Error::LLMError(format!("Stream error: {}", e)));
+                                    yield Err(e);
                                     return;
                                 }
                             }
                         }
 
-                        // Final response
                         yield Ok(LLMResponse {
-                            content: None,
+                            content: (!parts.is_empty()).then(|| Content {
+                                role: "model".to_string(),
+                                parts,
+                            }),
                             partial: false,
                             turn_complete: true,
                             interrupted: false,
-                            finish_reason: Some("stop".to_string()),
+                            finish_reason: Some(finish_reason.as_deref().map_or(FinishReason::Stop, FinishReason::parse)),
                             error_code: None,
                             error_message: None,
+                            usage,
                         });
                     }
                     Err(e) => {
                         yield Err(crate::Error::LLMError(format!("Request failed: {}", e)));
                     }
                 }
-            })))
+            }))
         } else {
             // Non-streaming response
-            Ok(Box::new(Box::pin(stream! {
+            Box::new(Box::pin(stream! {
                 let response = client
                     .post(&url)
                     .header("Authorization", format!("Bearer {}", api_key))
@@ -283,15 +603,22 @@ impl Provider for OpenAIProvider {
                         match resp.json::<OpenAIResponse>().await {
                             Ok(openai_resp) => {
                                 if let Some(choice) = openai_resp.choices.first() {
-                                    let content = Self::convert_message_to_content(&choice.message);
+                                    let content = match Self::convert_message_to_content(&choice.message) {
+                                        Ok(content) => content,
+                                        Err(e) => {
+                                            yield Err(e);
+                                            return;
+                                        }
+                                    };
                                     yield Ok(LLMResponse {
                                         content: Some(content),
                                         partial: false,
                                         turn_complete: true,
                                         interrupted: false,
-                                        finish_reason: choice.finish_reason.clone(),
+                                        finish_reason: choice.finish_reason.as_deref().map(FinishReason::parse),
                                         error_code: None,
                                         error_message: None,
+                                        usage: openai_resp.usage.as_ref().map(TokenUsage::from),
                                     });
                                 }
                             }
@@ -304,18 +631,19 @@ impl Provider for OpenAIProvider {
                         yield Err(crate::Error::LLMError(format!("Request failed: {}", e)));
                     }
                 }
-            })))
-        }
+            }))
+        };
+
+        Ok(limit_response_size(stream, max_response_bytes))
     }
 
     async fn embed_texts(&self, texts: Vec<String>) -> Result<Vec<EmbeddingVector>> {
         use serde_json::json;
 
-        let embedding_model = self
-            .config
-            .embedding_model
-            .clone()
-            .unwrap_or_else(|| "text-embedding-3-small".to_string());
+        let chars: usize = texts.iter().map(|t| t.len()).sum();
+        self.throttle((chars / 4).max(1) as u32).await;
+
+        let embedding_model = self.embedding_model();
 
         let url = format!("{}/embeddings", self.config.base_url);
 
@@ -375,7 +703,13 @@ impl Provider for OpenAIProvider {
     }
 
     fn embedding_dimensions(&self) -> Option<usize> {
-        Some(1536)
+        self.config.embedding_dimensions.or_else(|| {
+            Self::static_metadata()
+                .models
+                .into_iter()
+                .find(|m| m.id == self.embedding_model())
+                .and_then(|m| m.embedding_dimensions)
+        })
     }
 
     fn max_embedding_batch_size(&self) -> Option<usize> {
@@ -385,6 +719,8 @@ impl Provider for OpenAIProvider {
     async fn transcribe_audio(&self, audio: AudioInput) -> Result<TranscriptionResult> {
         use reqwest::multipart;
 
+        self.throttle(1).await;
+
         let url = format!("{}/audio/transcriptions", self.config.base_url);
 
         // Create multipart form
@@ -402,6 +738,11 @@ impl Provider for OpenAIProvider {
             form = form.text("language", language);
         }
 
+        // Segments are only included in the verbose response format
+        if audio.include_segments {
+            form = form.text("response_format", "verbose_json");
+        }
+
         let response = self
             .client
             .post(&url)
@@ -434,16 +775,240 @@ impl Provider for OpenAIProvider {
 
         let language = json["language"].as_str().map(|s| s.to_string());
         let duration = json["duration"].as_f64().map(|d| d as f32);
+        let segments = match json.get("segments") {
+            Some(segments) => Some(
+                serde_json::from_value::<Vec<TranscriptionSegment>>(segments.clone()).map_err(
+                    |e| crate::Error::LLMError(format!("Failed to parse segments: {}", e)),
+                )?,
+            ),
+            None => None,
+        };
 
         Ok(TranscriptionResult {
             text,
             language,
             duration,
-            segments: None, // OpenAI Whisper API doesn't return segments by default
+            segments,
         })
     }
 
     fn supported_audio_formats(&self) -> Option<&[&str]> {
         Some(&["mp3", "mp4", "mpeg", "mpga", "m4a", "wav", "webm"])
     }
+
+    async fn generate_image(&self, request: ImageRequest) -> Result<ImageResult> {
+        use base64::{Engine as _, engine::general_purpose};
+        use serde_json::json;
+
+        self.throttle(1).await;
+
+        let image_model = self
+            .config
+            .image_model
+            .clone()
+            .unwrap_or_else(|| "dall-e-3".to_string());
+
+        let url = format!("{}/images/generations", self.config.base_url);
+
+        let mut request_body = json!({
+            "model": image_model,
+            "prompt": request.prompt,
+        });
+        for (key, value) in [
+            ("size", request.size.map(serde_json::Value::from)),
+            ("quality", request.quality.map(serde_json::Value::from)),
+            ("style", request.style.map(serde_json::Value::from)),
+            ("n", request.n.map(serde_json::Value::from)),
+        ] {
+            if let Some(value) = value {
+                request_body[key] = value;
+            }
+        }
+
+        let response = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .header("Content-Type", "application/json")
+            .json(&request_body)
+            .send()
+            .await
+            .map_err(|e| {
+                crate::Error::LLMError(format!("Image generation request failed: {}", e))
+            })?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(crate::Error::LLMError(format!(
+                "Image generation API error {}: {}",
+                status, error_text
+            )));
+        }
+
+        let json: serde_json::Value = response.json().await.map_err(|e| {
+            crate::Error::LLMError(format!("Failed to parse image generation response: {}", e))
+        })?;
+
+        let data = json["data"]
+            .as_array()
+            .ok_or_else(|| crate::Error::LLMError("Missing data array".into()))?;
+
+        // Each image comes back either inline as base64 or as a hosted URL
+        let images = data
+            .iter()
+            .map(|item| {
+                let data = match item["b64_json"].as_str() {
+                    Some(encoded) => general_purpose::STANDARD.decode(encoded).map_err(|e| {
+                        crate::Error::LLMError(format!("Invalid base64 image data: {}", e))
+                    })?,
+                    None => Vec::new(),
+                };
+                let url = item["url"].as_str().map(|s| s.to_string());
+                if data.is_empty() && url.is_none() {
+                    return Err(crate::Error::LLMError(
+                        "Image has neither b64_json nor url".into(),
+                    ));
+                }
+
+                Ok(GeneratedImage {
+                    data,
+                    url,
+                    revised_prompt: item["revised_prompt"].as_str().map(|s| s.to_string()),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(ImageResult { images })
+    }
+
+    async fn generate_audio(&self, request: AudioRequest) -> Result<AudioResult> {
+        use serde_json::json;
+
+        self.throttle((request.text.len() / 4).max(1) as u32).await;
+
+        let tts_model = self
+            .config
+            .tts_model
+            .clone()
+            .unwrap_or_else(|| "tts-1".to_string());
+        let format = request.format.unwrap_or_else(|| "mp3".to_string());
+
+        let url = format!("{}/audio/speech", self.config.base_url);
+
+        let mut request_body = json!({
+            "model": tts_model,
+            "input": request.text,
+            "voice": request.voice.unwrap_or_else(|| "alloy".to_string()),
+            "response_format": format,
+        });
+        if let Some(speed) = request.speed {
+            request_body["speed"] = json!(speed);
+        }
+
+        let response = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .header("Content-Type", "application/json")
+            .json(&request_body)
+            .send()
+            .await
+            .map_err(|e| crate::Error::LLMError(format!("Speech request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(crate::Error::LLMError(format!(
+                "Speech API error {}: {}",
+                status, error_text
+            )));
+        }
+
+        let mime_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| mime_type_for_audio_format(&format).to_string());
+
+        let data = response
+            .bytes()
+            .await
+            .map_err(|e| crate::Error::LLMError(format!("Failed to read speech audio: {}", e)))?
+            .to_vec();
+
+        Ok(AudioResult {
+            data,
+            format,
+            mime_type,
+        })
+    }
+}
+
+/// Guess a model's capability from its id, as the models endpoint omits it
+fn capability_from_model_id(id: &str) -> Capability {
+    if id.contains("embedding") {
+        Capability::Embedding
+    } else if id.contains("whisper") || id.contains("transcribe") {
+        Capability::Transcription
+    } else if id.contains("dall-e") || id.contains("image") {
+        Capability::ImageGeneration
+    } else if id.contains("tts") {
+        Capability::AudioGeneration
+    } else {
+        Capability::TextGeneration
+    }
+}
+
+/// MIME type for a speech `response_format`, used when the response omits one
+fn mime_type_for_audio_format(format: &str) -> &'static str {
+    match format {
+        "mp3" => "audio/mpeg",
+        "opus" => "audio/opus",
+        "aac" => "audio/aac",
+        "flac" => "audio/flac",
+        "wav" => "audio/wav",
+        "pcm" => "audio/pcm",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Tool call ID of a call or response, which OpenAI needs to pair them up
+fn tool_call_id(id: &Option<String>, name: &str) -> Result<String> {
+    id.clone().ok_or_else(|| {
+        crate::Error::LLMError(format!(
+            "Tool call {} has no ID; OpenAI requires tool call IDs",
+            name
+        ))
+    })
+}
+
+/// Build a function call part from a tool call's JSON-encoded arguments
+fn tool_call_to_part(id: String, name: String, arguments: &str) -> Result<crate::Part> {
+    // Functions without parameters may come back with no arguments at all
+    let args = if arguments.trim().is_empty() {
+        serde_json::json!({})
+    } else {
+        serde_json::from_str(arguments).map_err(|e| {
+            crate::Error::LLMError(format!(
+                "Invalid arguments JSON for tool call '{}': {}",
+                name, e
+            ))
+        })?
+    };
+
+    Ok(crate::Part::FunctionCall {
+        function_call: crate::FunctionCall {
+            name,
+            args,
+            id: Some(id),
+        },
+    })
 }
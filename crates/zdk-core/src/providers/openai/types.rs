@@ -14,12 +14,113 @@ pub struct OpenAIRequest {
     pub top_p: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stream: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream_options: Option<OpenAIStreamOptions>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub tools: Vec<OpenAITool>,
+    /// Structured output mode, `json_object` or `json_schema`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<serde_json::Value>,
+}
+
+/// Options for streamed completions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenAIStreamOptions {
+    /// Ask for a final chunk carrying token usage
+    pub include_usage: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OpenAIMessage {
     pub role: String,
-    pub content: String,
+    /// Absent on assistant messages that only call tools
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub content: Option<OpenAIContent>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub tool_calls: Vec<OpenAIToolCall>,
+    /// Set on `tool` messages to the id of the call being answered
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub tool_call_id: Option<String>,
+}
+
+impl OpenAIMessage {
+    /// Plain text message with the given role
+    pub fn text(role: impl Into<String>, content: impl Into<String>) -> Self {
+        Self {
+            role: role.into(),
+            content: Some(OpenAIContent::Text(content.into())),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
+        }
+    }
+}
+
+/// Message content: a plain string, or typed parts when images are attached
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum OpenAIContent {
+    Text(String),
+    Parts(Vec<OpenAIContentPart>),
+}
+
+impl OpenAIContent {
+    /// Concatenated text, ignoring image parts
+    pub fn text(&self) -> String {
+        match self {
+            OpenAIContent::Text(text) => text.clone(),
+            OpenAIContent::Parts(parts) => parts
+                .iter()
+                .filter_map(|part| match part {
+                    OpenAIContentPart::Text { text } => Some(text.as_str()),
+                    OpenAIContentPart::ImageUrl { .. } => None,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// One part of a multimodal message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OpenAIContentPart {
+    Text { text: String },
+    ImageUrl { image_url: OpenAIImageUrl },
+}
+
+/// Image reference; inline images use a `data:<mime>;base64,` URL
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenAIImageUrl {
+    pub url: String,
+}
+
+/// Tool definition, always of type `function`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenAITool {
+    #[serde(rename = "type")]
+    pub tool_type: String,
+    pub function: OpenAIFunctionDefinition,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenAIFunctionDefinition {
+    pub name: String,
+    pub description: String,
+    pub parameters: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenAIToolCall {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub call_type: String,
+    pub function: OpenAIFunctionCall,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenAIFunctionCall {
+    pub name: String,
+    /// JSON-encoded arguments
+    pub arguments: String,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -46,6 +147,9 @@ pub struct OpenAIStreamResponse {
     pub created: u64,
     pub model: String,
     pub choices: Vec<OpenAIStreamChoice>,
+    /// Only present on the last chunk when `include_usage` was requested
+    #[serde(default)]
+    pub usage: Option<OpenAIUsage>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -59,6 +163,22 @@ pub struct OpenAIStreamChoice {
 pub struct OpenAIDelta {
     pub role: Option<String>,
     pub content: Option<String>,
+    #[serde(default)]
+    pub tool_calls: Vec<OpenAIToolCallDelta>,
+}
+
+/// Fragment of a streamed tool call; `index` identifies the call across chunks
+#[derive(Debug, Clone, Deserialize)]
+pub struct OpenAIToolCallDelta {
+    pub index: usize,
+    pub id: Option<String>,
+    pub function: Option<OpenAIFunctionCallDelta>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OpenAIFunctionCallDelta {
+    pub name: Option<String>,
+    pub arguments: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -67,3 +187,13 @@ pub struct OpenAIUsage {
     pub completion_tokens: u32,
     pub total_tokens: u32,
 }
+
+impl From<&OpenAIUsage> for crate::TokenUsage {
+    fn from(usage: &OpenAIUsage) -> Self {
+        Self {
+            prompt_tokens: usage.prompt_tokens,
+            completion_tokens: usage.completion_tokens,
+            total_tokens: usage.total_tokens,
+        }
+    }
+}
@@ -35,6 +35,24 @@ pub struct ProviderMetadata {
     pub models: Vec<ModelInfo>,
 }
 
+impl ProviderMetadata {
+    /// Capabilities of the model with the given id, if it's listed
+    pub fn model_capabilities(&self, model: &str) -> Option<Vec<Capability>> {
+        self.models
+            .iter()
+            .find(|info| info.id == model)
+            .map(|info| info.capabilities.clone())
+    }
+
+    /// Context window of the model with the given id, if it's listed with one
+    pub fn model_context_window(&self, model: &str) -> Option<usize> {
+        self.models
+            .iter()
+            .find(|info| info.id == model)
+            .and_then(|info| info.context_window)
+    }
+}
+
 /// Model information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelInfo {
@@ -67,6 +85,23 @@ pub trait Provider: crate::LLM {
         self.metadata().capabilities.contains(&capability)
     }
 
+    /// List the models currently offered by the provider
+    ///
+    /// Providers with a models endpoint query it live so newly released models
+    /// are discoverable. The default returns the static list from
+    /// [`metadata`](Self::metadata).
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        Ok(self.metadata().models)
+    }
+
+    /// Check the provider is reachable and accepts the configured credentials
+    ///
+    /// Used by readiness probes, so implementations should make the cheapest
+    /// authenticated call available. The default assumes the provider is ready.
+    async fn ping(&self) -> Result<()> {
+        Ok(())
+    }
+
     // ===== Text Generation Capability =====
 
     /// Generate text content (LLM completion)
@@ -87,6 +122,25 @@ pub trait Provider: crate::LLM {
         )))
     }
 
+    // ===== File Upload =====
+
+    /// Upload a file for use in later requests
+    ///
+    /// Large documents that exceed inline-data limits can be uploaded once and
+    /// referenced from messages with [`Part::FileUri`](crate::Part::FileUri).
+    ///
+    /// # Arguments
+    /// * `data` - Raw file bytes
+    /// * `mime_type` - MIME type of the file
+    ///
+    /// # Returns
+    /// URI identifying the uploaded file
+    async fn upload_file(&self, _data: Vec<u8>, _mime_type: &str) -> Result<String> {
+        Err(Error::Other(anyhow::anyhow!(
+            "Provider does not support file uploads"
+        )))
+    }
+
     // ===== Embedding Capability =====
 
     /// Embed texts into vector representations
@@ -102,6 +156,42 @@ pub trait Provider: crate::LLM {
         )))
     }
 
+    /// Embed any number of texts, split into requests of at most
+    /// [`max_embedding_batch_size`](Provider::max_embedding_batch_size)
+    ///
+    /// Batches are sent one after another, so a provider's rate limiter paces
+    /// them, and the vectors come back in input order. Prefer this over
+    /// `embed_texts` when the input size isn't known to fit one request.
+    async fn embed_texts_batched(&self, texts: Vec<String>) -> Result<Vec<EmbeddingVector>> {
+        let batch_size = self
+            .max_embedding_batch_size()
+            .unwrap_or(texts.len())
+            .max(1);
+        if texts.len() <= batch_size {
+            return self.embed_texts(texts).await;
+        }
+
+        let mut vectors = Vec::with_capacity(texts.len());
+        let mut remaining = texts.into_iter();
+        loop {
+            let batch: Vec<String> = remaining.by_ref().take(batch_size).collect();
+            if batch.is_empty() {
+                return Ok(vectors);
+            }
+
+            let expected = batch.len();
+            let embedded = self.embed_texts(batch).await?;
+            if embedded.len() != expected {
+                return Err(Error::LLMError(format!(
+                    "Embedding batch returned {} vectors for {} texts",
+                    embedded.len(),
+                    expected
+                )));
+            }
+            vectors.extend(embedded);
+        }
+    }
+
     /// Get embedding dimensions for this provider
     ///
     /// Returns None if provider doesn't support embeddings
@@ -131,6 +221,20 @@ pub trait Provider: crate::LLM {
         )))
     }
 
+    /// Transcribe the first audio part of a content message
+    ///
+    /// Lets callers pass audio built with `Content::new_user_audio` instead of
+    /// constructing an `AudioInput` by hand.
+    async fn transcribe_content(&self, content: &crate::Content) -> Result<TranscriptionResult> {
+        let audio = content
+            .parts
+            .iter()
+            .find_map(AudioInput::from_part)
+            .ok_or_else(|| Error::Other(anyhow::anyhow!("Content has no audio part")))??;
+
+        self.transcribe_audio(audio).await
+    }
+
     /// Get supported audio formats for transcription
     ///
     /// Returns None if provider doesn't support transcription
@@ -4,7 +4,7 @@
 mod tests {
     use crate::{
         ZConfig,
-        providers::{Capability, GeminiProvider, OpenAIProvider, ProviderRegistry},
+        providers::{Capability, GeminiAuth, GeminiProvider, OpenAIProvider, ProviderRegistry},
     };
 
     #[test]
@@ -27,6 +27,8 @@ mod tests {
         assert!(metadata.capabilities.contains(&Capability::TextGeneration));
         assert!(metadata.capabilities.contains(&Capability::Embedding));
         assert!(metadata.capabilities.contains(&Capability::Transcription));
+        assert!(metadata.capabilities.contains(&Capability::ImageGeneration));
+        assert!(metadata.capabilities.contains(&Capability::AudioGeneration));
         assert!(!metadata.models.is_empty());
     }
 
@@ -35,12 +37,13 @@ mod tests {
         let registry = ProviderRegistry::global();
         let providers = registry.list_providers();
 
-        // Should have at least Gemini and OpenAI
-        assert!(providers.len() >= 2);
+        // Should have at least Gemini, OpenAI and Anthropic
+        assert!(providers.len() >= 3);
 
         let names: Vec<String> = providers.iter().map(|p| p.name.clone()).collect();
         assert!(names.contains(&"gemini".to_string()));
         assert!(names.contains(&"openai".to_string()));
+        assert!(names.contains(&"anthropic".to_string()));
     }
 
     #[test]
@@ -123,4 +126,1835 @@ mod tests {
         assert_eq!(result.language, Some("en".to_string()));
         assert_eq!(result.duration, Some(2.5));
     }
+
+    #[tokio::test]
+    async fn test_gemini_upload_file_uri_used_in_request() {
+        use crate::{
+            Content, LLMRequest, Part,
+            providers::{Provider, gemini::GeminiConfig},
+        };
+        use futures::StreamExt;
+
+        let mut server = mockito::Server::new_async().await;
+        let file_uri = format!("{}/v1beta/files/abc123", server.url());
+
+        let upload_mock = server
+            .mock("POST", "/upload/v1beta/files")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("uploadType".into(), "media".into()),
+                mockito::Matcher::UrlEncoded("key".into(), "test-key".into()),
+            ]))
+            .match_header("content-type", "application/pdf")
+            .match_body("%PDF-1.4 fake")
+            .with_status(200)
+            .with_body(
+                serde_json::json!({
+                    "file": {
+                        "name": "files/abc123",
+                        "mimeType": "application/pdf",
+                        "uri": file_uri,
+                    }
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let generate_mock = server
+            .mock("POST", "/v1/models/gemini-test:generateContent")
+            .match_query(mockito::Matcher::Any)
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "contents": [{
+                    "role": "user",
+                    "parts": [
+                        { "text": "Summarize this document" },
+                        { "fileData": { "mimeType": "application/pdf", "fileUri": file_uri } }
+                    ]
+                }]
+            })))
+            .with_status(200)
+            .with_body(
+                serde_json::json!({
+                    "candidates": [{
+                        "content": { "role": "model", "parts": [{ "text": "A summary" }] },
+                        "finishReason": "STOP"
+                    }]
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut config = GeminiConfig::default_api_key("gemini-test".to_string());
+        config.base_url = format!("{}/v1/models", server.url());
+        config.upload_url = Some(format!("{}/upload/v1beta/files", server.url()));
+        let provider = GeminiProvider::new(GeminiAuth::ApiKey("test-key".to_string()), config);
+
+        let uri = provider
+            .upload_file(b"%PDF-1.4 fake".to_vec(), "application/pdf")
+            .await
+            .unwrap();
+        assert_eq!(uri, file_uri);
+
+        let request = LLMRequest {
+            model: "gemini-test".to_string(),
+            system_instruction: None,
+            contents: vec![Content {
+                role: "user".to_string(),
+                parts: vec![
+                    Part::Text {
+                        text: "Summarize this document".to_string(),
+                    },
+                    Part::file_uri(uri, "application/pdf"),
+                ],
+            }],
+            config: None,
+            tools: vec![],
+        };
+
+        let mut stream = Provider::generate_content(&provider, request, false)
+            .await
+            .unwrap();
+        let response = stream.next().await.unwrap().unwrap();
+        assert_eq!(response.finish_reason, Some(crate::FinishReason::Stop));
+
+        upload_mock.assert_async().await;
+        generate_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_system_instruction_mapped_per_provider() {
+        use crate::{
+            Content, LLMRequest,
+            providers::{Provider, gemini::GeminiConfig, openai::OpenAIConfig},
+        };
+        use futures::StreamExt;
+
+        let request = LLMRequest {
+            model: "test-model".to_string(),
+            system_instruction: Some("Be terse.".to_string()),
+            contents: vec![Content::new_user_text("Hi")],
+            config: None,
+            tools: vec![],
+        };
+
+        let mut server = mockito::Server::new_async().await;
+
+        let gemini_mock = server
+            .mock("POST", "/v1/models/test-model:generateContent")
+            .match_query(mockito::Matcher::Any)
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "system_instruction": { "parts": [{ "text": "Be terse." }] }
+            })))
+            .with_status(200)
+            .with_body(
+                serde_json::json!({
+                    "candidates": [{
+                        "content": { "role": "model", "parts": [{ "text": "Hi." }] },
+                        "finishReason": "STOP"
+                    }]
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let openai_mock = server
+            .mock("POST", "/chat/completions")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "messages": [
+                    { "role": "system", "content": "Be terse." },
+                    { "role": "user", "content": "Hi" }
+                ]
+            })))
+            .with_status(200)
+            .with_body(
+                serde_json::json!({
+                    "id": "chatcmpl-1",
+                    "object": "chat.completion",
+                    "created": 0,
+                    "model": "test-model",
+                    "choices": [{
+                        "index": 0,
+                        "message": { "role": "assistant", "content": "Hi." },
+                        "finish_reason": "stop"
+                    }]
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut gemini_config = GeminiConfig::default_api_key("test-model".to_string());
+        gemini_config.base_url = format!("{}/v1/models", server.url());
+        let gemini = GeminiProvider::new(GeminiAuth::ApiKey("key".to_string()), gemini_config);
+        let mut stream = Provider::generate_content(&gemini, request.clone(), false)
+            .await
+            .unwrap();
+        assert!(stream.next().await.unwrap().is_ok());
+
+        let openai = OpenAIProvider::new(
+            "key".to_string(),
+            OpenAIConfig::with_base_url("test-model".to_string(), server.url()),
+        );
+        let mut stream = Provider::generate_content(&openai, request, false)
+            .await
+            .unwrap();
+        assert!(stream.next().await.unwrap().is_ok());
+
+        gemini_mock.assert_async().await;
+        openai_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_openai_rejects_tool_call_without_id() {
+        use crate::{
+            Content, FunctionCall, LLMRequest, Part,
+            providers::{Provider, openai::OpenAIConfig},
+        };
+
+        let openai = OpenAIProvider::new(
+            "key".to_string(),
+            OpenAIConfig::with_base_url("test-model".to_string(), "http://127.0.0.1:9".to_string()),
+        );
+        let request = LLMRequest {
+            model: "test-model".to_string(),
+            system_instruction: None,
+            contents: vec![Content {
+                role: "model".to_string(),
+                parts: vec![Part::FunctionCall {
+                    function_call: FunctionCall {
+                        name: "get_weather".to_string(),
+                        args: serde_json::json!({}),
+                        id: None,
+                    },
+                }],
+            }],
+            config: None,
+            tools: vec![],
+        };
+
+        // Made-up IDs would never match the call, so the request isn't sent
+        let err = Provider::generate_content(&openai, request, false)
+            .await
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("get_weather"));
+    }
+
+    #[tokio::test]
+    async fn test_structured_output_mapped_per_provider() {
+        use crate::{
+            Content, GenerateConfig, LLMRequest,
+            providers::{Provider, gemini::GeminiConfig, openai::OpenAIConfig},
+        };
+        use futures::StreamExt;
+
+        let schema = serde_json::json!({
+            "type": "object",
+            "properties": { "city": { "type": "string" } },
+            "required": ["city"]
+        });
+        let request = LLMRequest {
+            model: "test-model".to_string(),
+            system_instruction: None,
+            contents: vec![Content::new_user_text("Where is the Louvre?")],
+            config: Some(GenerateConfig {
+                response_mime_type: Some("application/json".to_string()),
+                response_schema: Some(schema.clone()),
+                ..Default::default()
+            }),
+            tools: vec![],
+        };
+
+        let mut server = mockito::Server::new_async().await;
+
+        let gemini_mock = server
+            .mock("POST", "/v1/models/test-model:generateContent")
+            .match_query(mockito::Matcher::Any)
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "generation_config": {
+                    "responseMimeType": "application/json",
+                    "responseSchema": schema
+                }
+            })))
+            .with_status(200)
+            .with_body(
+                serde_json::json!({
+                    "candidates": [{
+                        "content": { "role": "model", "parts": [{ "text": "{\"city\":\"Paris\"}" }] },
+                        "finishReason": "STOP"
+                    }]
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let openai_mock = server
+            .mock("POST", "/chat/completions")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "response_format": {
+                    "type": "json_schema",
+                    "json_schema": { "name": "response", "schema": schema }
+                }
+            })))
+            .with_status(200)
+            .with_body(
+                serde_json::json!({
+                    "id": "chatcmpl-1",
+                    "object": "chat.completion",
+                    "created": 0,
+                    "model": "test-model",
+                    "choices": [{
+                        "index": 0,
+                        "message": { "role": "assistant", "content": "{\"city\":\"Paris\"}" },
+                        "finish_reason": "stop"
+                    }]
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut gemini_config = GeminiConfig::default_api_key("test-model".to_string());
+        gemini_config.base_url = format!("{}/v1/models", server.url());
+        let gemini = GeminiProvider::new(GeminiAuth::ApiKey("key".to_string()), gemini_config);
+        let mut stream = Provider::generate_content(&gemini, request.clone(), false)
+            .await
+            .unwrap();
+        assert!(stream.next().await.unwrap().is_ok());
+
+        let openai = OpenAIProvider::new(
+            "key".to_string(),
+            OpenAIConfig::with_base_url("test-model".to_string(), server.url()),
+        );
+        let mut stream = Provider::generate_content(&openai, request, false)
+            .await
+            .unwrap();
+        assert!(stream.next().await.unwrap().is_ok());
+
+        gemini_mock.assert_async().await;
+        openai_mock.assert_async().await;
+
+        // A bare JSON MIME type selects OpenAI's JSON mode
+        let json_mode = GenerateConfig {
+            response_mime_type: Some("application/json".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            OpenAIProvider::response_format(&json_mode),
+            Some(serde_json::json!({ "type": "json_object" }))
+        );
+        assert_eq!(
+            OpenAIProvider::response_format(&GenerateConfig::default()),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_gemini_safety_settings_sent() {
+        use crate::{
+            Content, LLMRequest,
+            providers::{
+                Provider,
+                gemini::{GeminiConfig, HarmBlockThreshold, HarmCategory},
+            },
+        };
+        use futures::StreamExt;
+
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v1/models/test-model:generateContent")
+            .match_query(mockito::Matcher::Any)
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "system_instruction": { "parts": [{ "text": "Be kind." }] },
+                "safety_settings": [
+                    { "category": "HARM_CATEGORY_HARASSMENT", "threshold": "BLOCK_LOW_AND_ABOVE" },
+                    { "category": "HARM_CATEGORY_DANGEROUS_CONTENT", "threshold": "BLOCK_NONE" }
+                ]
+            })))
+            .with_status(200)
+            .with_body(
+                serde_json::json!({
+                    "candidates": [{
+                        "content": { "role": "model", "parts": [{ "text": "Sure." }] },
+                        "finishReason": "STOP"
+                    }]
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut config = GeminiConfig::default_api_key("test-model".to_string())
+            .with_safety_setting(
+                HarmCategory::Harassment,
+                HarmBlockThreshold::BlockLowAndAbove,
+            )
+            .with_safety_setting(
+                HarmCategory::DangerousContent,
+                HarmBlockThreshold::BlockNone,
+            );
+        config.base_url = format!("{}/v1/models", server.url());
+        let gemini = GeminiProvider::new(GeminiAuth::ApiKey("key".to_string()), config);
+
+        let request = LLMRequest {
+            model: "test-model".to_string(),
+            system_instruction: Some("Be kind.".to_string()),
+            contents: vec![Content::new_user_text("Hi")],
+            config: None,
+            tools: vec![],
+        };
+        let mut stream = Provider::generate_content(&gemini, request, false)
+            .await
+            .unwrap();
+        assert!(stream.next().await.unwrap().is_ok());
+        mock.assert_async().await;
+
+        // Without settings the field is left out so the API defaults apply
+        let request = crate::providers::gemini::types::GeminiRequest {
+            contents: vec![],
+            generation_config: None,
+            system_instruction: None,
+            tools: vec![],
+            safety_settings: vec![],
+        };
+        assert!(
+            serde_json::to_value(&request)
+                .unwrap()
+                .get("safety_settings")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_finish_reason_mapped_per_provider() {
+        use crate::FinishReason;
+        use serde_json::json;
+
+        let cases = [
+            // Gemini
+            ("STOP", FinishReason::Stop),
+            ("MAX_TOKENS", FinishReason::Length),
+            ("SAFETY", FinishReason::Safety),
+            ("RECITATION", FinishReason::Safety),
+            ("PROHIBITED_CONTENT", FinishReason::Safety),
+            // OpenAI
+            ("stop", FinishReason::Stop),
+            ("length", FinishReason::Length),
+            ("content_filter", FinishReason::Safety),
+            ("tool_calls", FinishReason::ToolCalls),
+            // Anthropic
+            ("end_turn", FinishReason::Stop),
+            ("stop_sequence", FinishReason::Stop),
+            ("max_tokens", FinishReason::Length),
+            ("refusal", FinishReason::Safety),
+            ("tool_use", FinishReason::ToolCalls),
+        ];
+        for (raw, expected) in cases {
+            assert_eq!(FinishReason::parse(raw), expected, "{}", raw);
+        }
+
+        // Unknown reasons keep their raw text through serialization
+        let other = FinishReason::parse("MALFORMED_FUNCTION_CALL");
+        assert_eq!(
+            other,
+            FinishReason::Other("MALFORMED_FUNCTION_CALL".to_string())
+        );
+        let json = serde_json::to_value(&other).unwrap();
+        assert_eq!(json, "MALFORMED_FUNCTION_CALL");
+        assert_eq!(
+            serde_json::from_value::<FinishReason>(json!("safety")).unwrap(),
+            FinishReason::Safety
+        );
+    }
+
+    #[tokio::test]
+    async fn test_gemini_stream_keeps_last_finish_reason() {
+        use crate::{
+            Content, FinishReason, LLMRequest,
+            providers::{Provider, gemini::GeminiConfig},
+        };
+        use futures::StreamExt;
+        use serde_json::json;
+
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("POST", "/v1/models/test-model:streamGenerateContent")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_body(
+                json!([{
+                    "candidates": [{
+                        "content": { "role": "model", "parts": [{ "text": "Once upon" }] },
+                        "finishReason": "MAX_TOKENS"
+                    }]
+                }])
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut config = GeminiConfig::default_api_key("test-model".to_string());
+        config.base_url = format!("{}/v1/models", server.url());
+        let gemini = GeminiProvider::new(GeminiAuth::ApiKey("key".to_string()), config);
+
+        let request = LLMRequest {
+            model: "test-model".to_string(),
+            system_instruction: None,
+            contents: vec![Content::new_user_text("Tell a story")],
+            config: None,
+            tools: vec![],
+        };
+        let responses: Vec<_> = Provider::generate_content(&gemini, request, true)
+            .await
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect()
+            .await;
+
+        let last = responses.last().unwrap();
+        assert!(last.turn_complete);
+        assert_eq!(last.finish_reason, Some(FinishReason::Length));
+    }
+
+    #[tokio::test]
+    async fn test_inline_image_mapped_per_provider() {
+        use crate::{
+            Content, LLMRequest, Part,
+            providers::{Provider, gemini::GeminiConfig, openai::OpenAIConfig},
+        };
+        use futures::StreamExt;
+
+        let request = LLMRequest {
+            model: "test-model".to_string(),
+            system_instruction: None,
+            contents: vec![Content::new_user_parts(vec![
+                Part::Text {
+                    text: "What is this?".to_string(),
+                },
+                Part::image_from_bytes("image/png", b"abc"),
+            ])],
+            config: None,
+            tools: vec![],
+        };
+
+        let mut server = mockito::Server::new_async().await;
+
+        let gemini_mock = server
+            .mock("POST", "/v1/models/test-model:generateContent")
+            .match_query(mockito::Matcher::Any)
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "contents": [{
+                    "role": "user",
+                    "parts": [
+                        { "text": "What is this?" },
+                        { "inlineData": { "mimeType": "image/png", "data": "YWJj" } }
+                    ]
+                }]
+            })))
+            .with_status(200)
+            .with_body(
+                serde_json::json!({
+                    "candidates": [{
+                        "content": { "role": "model", "parts": [{ "text": "A PNG." }] },
+                        "finishReason": "STOP"
+                    }]
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let openai_mock = server
+            .mock("POST", "/chat/completions")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "messages": [{
+                    "role": "user",
+                    "content": [
+                        { "type": "text", "text": "What is this?" },
+                        {
+                            "type": "image_url",
+                            "image_url": { "url": "data:image/png;base64,YWJj" }
+                        }
+                    ]
+                }]
+            })))
+            .with_status(200)
+            .with_body(
+                serde_json::json!({
+                    "id": "chatcmpl-1",
+                    "object": "chat.completion",
+                    "created": 0,
+                    "model": "test-model",
+                    "choices": [{
+                        "index": 0,
+                        "message": { "role": "assistant", "content": "A PNG." },
+                        "finish_reason": "stop"
+                    }]
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut gemini_config = GeminiConfig::default_api_key("test-model".to_string());
+        gemini_config.base_url = format!("{}/v1/models", server.url());
+        let gemini = GeminiProvider::new(GeminiAuth::ApiKey("key".to_string()), gemini_config);
+        let mut stream = Provider::generate_content(&gemini, request.clone(), false)
+            .await
+            .unwrap();
+        assert!(stream.next().await.unwrap().is_ok());
+
+        let openai = OpenAIProvider::new(
+            "key".to_string(),
+            OpenAIConfig::with_base_url("test-model".to_string(), server.url()),
+        );
+        let mut stream = Provider::generate_content(&openai, request, false)
+            .await
+            .unwrap();
+        let response = stream.next().await.unwrap().unwrap();
+        assert_eq!(response.content.unwrap().text(), "A PNG.");
+
+        gemini_mock.assert_async().await;
+        openai_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_providers_created_from_config_honor_base_url() {
+        use crate::{Content, LLMRequest, ZConfigExt, providers::Provider};
+        use futures::StreamExt;
+
+        let mut server = mockito::Server::new_async().await;
+
+        let openai_mock = server
+            .mock("POST", "/chat/completions")
+            .match_header("authorization", "Bearer test-openai-key")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "model": "test-model"
+            })))
+            .with_status(200)
+            .with_body(
+                serde_json::json!({
+                    "id": "chatcmpl-1",
+                    "object": "chat.completion",
+                    "created": 0,
+                    "model": "test-model",
+                    "choices": [{
+                        "index": 0,
+                        "message": { "role": "assistant", "content": "From OpenAI." },
+                        "finish_reason": "stop"
+                    }]
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let anthropic_mock = server
+            .mock("POST", "/messages")
+            .match_header("x-api-key", "test-anthropic-key")
+            .with_status(200)
+            .with_body(
+                serde_json::json!({
+                    "id": "msg_1",
+                    "type": "message",
+                    "role": "assistant",
+                    "model": "test-model",
+                    "content": [{ "type": "text", "text": "From Anthropic." }],
+                    "stop_reason": "end_turn",
+                    "usage": { "input_tokens": 1, "output_tokens": 3 }
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut config = ZConfig::test_defaults();
+        config.openai_base_url = Some(server.url());
+        config.anthropic_base_url = Some(server.url());
+
+        let request = LLMRequest {
+            model: "test-model".to_string(),
+            system_instruction: None,
+            contents: vec![Content::new_user_text("Hi")],
+            config: None,
+            tools: vec![],
+        };
+
+        for (name, expected) in [("openai", "From OpenAI."), ("anthropic", "From Anthropic.")] {
+            config.model.provider = name.to_string();
+            let provider = config.create_provider().unwrap();
+            assert_eq!(provider.metadata().name, name);
+
+            let mut stream = Provider::generate_content(provider.as_ref(), request.clone(), false)
+                .await
+                .unwrap();
+            let response = stream.next().await.unwrap().unwrap();
+            assert_eq!(response.content.unwrap().text(), expected);
+        }
+
+        openai_mock.assert_async().await;
+        anthropic_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_openai_custom_embedding_model() {
+        use crate::{
+            ZConfigExt,
+            providers::{Provider, openai::OpenAIConfig},
+        };
+
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/embeddings")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "model": "nomic-embed-text"
+            })))
+            .with_status(200)
+            .with_body(
+                serde_json::json!({
+                    "data": [{ "index": 0, "embedding": [0.1, 0.2, 0.3] }]
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        // Ollama-style setup from config: no key, local embedding model
+        let mut config = ZConfig::test_defaults();
+        config.model.provider = "openai".to_string();
+        config.openai_api_key = None;
+        config.openai_base_url = Some(server.url());
+        config.openai_embedding_model = Some("nomic-embed-text".to_string());
+        config.openai_embedding_dimensions = Some(768);
+
+        let provider = config.create_provider().unwrap();
+        assert_eq!(provider.embedding_dimensions(), Some(768));
+        let vectors = provider
+            .embed_texts(vec!["hello".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(vectors[0].dimensions, 3);
+        mock.assert_async().await;
+
+        // Known OpenAI models report their size without an override
+        let large = OpenAIProvider::new(
+            "key".to_string(),
+            OpenAIConfig::default("gpt-4o".to_string())
+                .with_embedding_model("text-embedding-3-large", None),
+        );
+        assert_eq!(large.embedding_dimensions(), Some(3072));
+        let default = OpenAIProvider::new(
+            "key".to_string(),
+            OpenAIConfig::default("gpt-4o".to_string()),
+        );
+        assert_eq!(default.embedding_dimensions(), Some(1536));
+
+        // An unknown model without an override has no known size
+        let unknown = OpenAIProvider::new(
+            "key".to_string(),
+            OpenAIConfig::default("gpt-4o".to_string()).with_embedding_model("mxbai", None),
+        );
+        assert_eq!(unknown.embedding_dimensions(), None);
+    }
+
+    #[test]
+    fn test_provider_config_requires_keys() {
+        let registry = ProviderRegistry::global();
+
+        let mut config = ZConfig::test_defaults();
+        config.openai_api_key = None;
+        config.anthropic_api_key = None;
+        assert!(registry.create("openai", &config).is_err());
+        assert!(registry.create("anthropic", &config).is_err());
+
+        // A custom endpoint such as Ollama doesn't need an OpenAI key
+        config.openai_base_url = Some("http://localhost:11434/v1".to_string());
+        assert!(registry.create("openai", &config).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_shared_rate_limiter_caps_combined_rate() {
+        use crate::{
+            Content, LLMRequest,
+            providers::{Provider, RateLimiter, gemini::GeminiBuilder, gemini::GeminiConfig},
+        };
+        use futures::StreamExt;
+        use std::sync::Arc;
+        use std::time::{Duration, Instant};
+
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v1/models/test-model:generateContent")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_body(
+                serde_json::json!({
+                    "candidates": [{
+                        "content": { "role": "model", "parts": [{ "text": "ok" }] },
+                        "finishReason": "STOP"
+                    }]
+                })
+                .to_string(),
+            )
+            .expect(6)
+            .create_async()
+            .await;
+
+        // 1200 RPM without burst: one request every 50ms across both providers
+        let limiter = Arc::new(RateLimiter::new(1200).with_burst(1));
+
+        let make_provider = || {
+            let mut config = GeminiConfig::default_api_key("test-model".to_string());
+            config.base_url = format!("{}/v1/models", server.url());
+            GeminiBuilder::new()
+                .with_api_key("key".to_string(), "test-model".to_string())
+                .with_config(config)
+                .rate_limiter(limiter.clone())
+                .build()
+                .unwrap()
+        };
+        let first = make_provider();
+        let second = make_provider();
+
+        async fn run(provider: &GeminiProvider) {
+            for _ in 0..3 {
+                let request = LLMRequest {
+                    model: "test-model".to_string(),
+                    system_instruction: None,
+                    contents: vec![Content::new_user_text("ping")],
+                    config: None,
+                    tools: vec![],
+                };
+                let mut stream = Provider::generate_content(provider, request, false)
+                    .await
+                    .unwrap();
+                while stream.next().await.is_some() {}
+            }
+        }
+
+        let start = Instant::now();
+        futures::join!(run(&first), run(&second));
+
+        // Six requests at one per 50ms: the first is free, five more are paced
+        assert!(start.elapsed() >= Duration::from_millis(240));
+        mock.assert_async().await;
+    }
+
+    #[test]
+    fn test_stream_parser_buffer_guard() {
+        use crate::providers::gemini::stream::StreamParser;
+
+        let mut parser = StreamParser::new(64);
+        parser.push(b"data: {\"a\": 1}\r\n\r\ndata: {\"b\": ");
+        assert_eq!(parser.next_object().unwrap().as_deref(), Some("{\"a\": 1}"));
+
+        // An unbalanced fragment that keeps growing must error, not accumulate forever
+        let mut chunks = 0;
+        let err = loop {
+            parser.push(b"[[[[[[[[");
+            chunks += 1;
+            match parser.next_object() {
+                Ok(None) => assert!(chunks < 100, "buffer guard never triggered"),
+                Ok(Some(json)) => panic!("Unexpected object: {}", json),
+                Err(e) => break e,
+            }
+        };
+        assert!(err.to_string().contains("64 bytes"));
+    }
+
+    #[test]
+    fn test_stream_parser_reassembles_split_objects() {
+        use crate::providers::gemini::stream::StreamParser;
+
+        let objects = [
+            r#"{"text": "caf\u00e9 {not a brace}"}"#,
+            r#"{"text": "naïve ☕"}"#,
+        ];
+        let sse = format!("data: {}\r\n\r\ndata: {}\r\n\r\n", objects[0], objects[1]);
+        let array = format!("[{}\n,\r\n{}\n]", objects[0], objects[1]);
+
+        for body in [sse, array] {
+            // Split at every byte offset, including inside `data:`, strings and
+            // multi-byte characters
+            for split in 0..=body.len() {
+                let (head, tail) = body.as_bytes().split_at(split);
+                let mut parser = StreamParser::new(1024);
+                let mut parsed = Vec::new();
+                for chunk in [head, tail] {
+                    parser.push(chunk);
+                    while let Some(object) = parser.next_object().unwrap() {
+                        parsed.push(object);
+                    }
+                }
+                assert_eq!(parsed, objects, "split at byte {}", split);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_gemini_stream_chunk_boundaries() {
+        use crate::{
+            Content, LLMRequest,
+            providers::{Provider, gemini::GeminiConfig},
+        };
+        use futures::StreamExt;
+
+        let body = concat!(
+            "[{\"candidates\": [{\"content\": {\"role\": \"model\", \"parts\": [{\"text\": \"Bonjour \"}]}}]}\n,\r\n",
+            "{\"candidates\": [{\"content\": {\"role\": \"model\", \"parts\": [{\"text\": \"café\"}]}}]}\n]"
+        );
+        // Cut the first object mid-string and the second inside the 'é'
+        let cuts = [40, body.find('é').unwrap() + 1];
+
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("POST", "/v1/models/test-model:streamGenerateContent")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_chunked_body(move |w| {
+                let bytes = body.as_bytes();
+                w.write_all(&bytes[..cuts[0]])?;
+                w.flush()?;
+                std::thread::sleep(std::time::Duration::from_millis(20));
+                w.write_all(&bytes[cuts[0]..cuts[1]])?;
+                w.flush()?;
+                std::thread::sleep(std::time::Duration::from_millis(20));
+                w.write_all(&bytes[cuts[1]..])
+            })
+            .create_async()
+            .await;
+
+        let mut config = GeminiConfig::default_api_key("test-model".to_string());
+        config.base_url = format!("{}/v1/models", server.url());
+        let gemini = GeminiProvider::new(GeminiAuth::ApiKey("key".to_string()), config);
+
+        let request = LLMRequest {
+            model: "test-model".to_string(),
+            system_instruction: None,
+            contents: vec![Content::new_user_text("Hi")],
+            config: None,
+            tools: vec![],
+        };
+        let responses: Vec<_> = Provider::generate_content(&gemini, request, true)
+            .await
+            .unwrap()
+            .collect()
+            .await;
+
+        let text: String = responses
+            .into_iter()
+            .map(|r| r.unwrap())
+            .filter_map(|r| r.content)
+            .map(|c| c.text())
+            .collect();
+        assert_eq!(text, "Bonjour café");
+    }
+
+    #[tokio::test]
+    async fn test_openai_stream_chunk_boundaries() {
+        use crate::{
+            Content, LLMRequest, Part,
+            providers::{Provider, openai::OpenAIConfig},
+        };
+        use futures::StreamExt;
+
+        let chunk = |delta: serde_json::Value, finish_reason: Option<&str>| {
+            let chunk = serde_json::json!({
+                "id": "chatcmpl-1",
+                "object": "chat.completion.chunk",
+                "created": 0,
+                "model": "test-model",
+                "choices": [{ "index": 0, "delta": delta, "finish_reason": finish_reason }]
+            });
+            format!("data: {}\n\n", chunk)
+        };
+        let tool_call =
+            |fragment: serde_json::Value| serde_json::json!({ "tool_calls": [fragment] });
+        let body = [
+            chunk(serde_json::json!({ "content": "Voilà" }), None),
+            chunk(
+                tool_call(serde_json::json!({
+                    "index": 0,
+                    "id": "call_1",
+                    "function": { "name": "get_weather", "arguments": "{\"city\": " }
+                })),
+                None,
+            ),
+            chunk(
+                tool_call(serde_json::json!({
+                    "index": 0,
+                    "function": { "arguments": "\"Paris\"}" }
+                })),
+                None,
+            ),
+            chunk(serde_json::json!({}), Some("tool_calls")),
+            "data: [DONE]\n\n".to_string(),
+        ]
+        .concat();
+        // Cut inside the 'à', then inside each tool call fragment's `data:` line
+        let cuts = [
+            body.find('à').unwrap() + 1,
+            body.find("get_weather").unwrap(),
+            body.find("Paris").unwrap(),
+        ];
+
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("POST", "/chat/completions")
+            .with_status(200)
+            .with_chunked_body(move |w| {
+                let bytes = body.as_bytes();
+                let mut start = 0;
+                for cut in cuts {
+                    w.write_all(&bytes[start..cut])?;
+                    w.flush()?;
+                    std::thread::sleep(std::time::Duration::from_millis(20));
+                    start = cut;
+                }
+                w.write_all(&bytes[start..])
+            })
+            .create_async()
+            .await;
+
+        let openai = OpenAIProvider::new(
+            "key".to_string(),
+            OpenAIConfig::with_base_url("test-model".to_string(), server.url()),
+        );
+        let request = LLMRequest {
+            model: "test-model".to_string(),
+            system_instruction: None,
+            contents: vec![Content::new_user_text("Hi")],
+            config: None,
+            tools: vec![],
+        };
+        let responses: Vec<_> = Provider::generate_content(&openai, request, true)
+            .await
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect()
+            .await;
+
+        assert_eq!(responses[0].content.as_ref().unwrap().text(), "Voilà");
+        let last = responses.last().unwrap();
+        assert!(last.turn_complete);
+        match &last.content.as_ref().unwrap().parts[..] {
+            [Part::FunctionCall { function_call }] => {
+                assert_eq!(function_call.name, "get_weather");
+                assert_eq!(function_call.args, serde_json::json!({ "city": "Paris" }));
+                assert_eq!(function_call.id.as_deref(), Some("call_1"));
+            }
+            other => panic!("expected one function call, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_gemini_stream_errors_on_unbalanced_json() {
+        use crate::{
+            Content, LLMRequest,
+            providers::{Provider, gemini::GeminiConfig},
+        };
+        use futures::StreamExt;
+
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("POST", "/v1/models/test-model:streamGenerateContent")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_chunked_body(|w| {
+                w.write_all(b"data: {\"candidates\": [{\"content\": \"")?;
+                // Never close the object; stop once the client hangs up
+                for _ in 0..10_000 {
+                    w.write_all(&[b'x'; 1024])?;
+                    w.flush()?;
+                }
+                Ok(())
+            })
+            .create_async()
+            .await;
+
+        let mut config = GeminiConfig::default_api_key("test-model".to_string());
+        config.base_url = format!("{}/v1/models", server.url());
+        config.max_stream_buffer_bytes = 16 * 1024;
+        let gemini = GeminiProvider::new(GeminiAuth::ApiKey("key".to_string()), config);
+
+        let request = LLMRequest {
+            model: "test-model".to_string(),
+            system_instruction: None,
+            contents: vec![Content::new_user_text("Hi")],
+            config: None,
+            tools: vec![],
+        };
+        let mut stream = Provider::generate_content(&gemini, request, true)
+            .await
+            .unwrap();
+
+        let first = stream.next().await.expect("stream should yield an error");
+        let err = first.unwrap_err();
+        assert!(err.to_string().contains("without a complete JSON object"));
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_list_models_parses_live_response() {
+        use crate::providers::{Capability, Provider, gemini::GeminiConfig, openai::OpenAIConfig};
+
+        let mut server = mockito::Server::new_async().await;
+
+        let gemini_first_page = server
+            .mock("GET", "/v1/models")
+            .match_query(mockito::Matcher::UrlEncoded("key".into(), "key".into()))
+            .with_status(200)
+            .with_body(
+                serde_json::json!({
+                    "models": [{
+                        "name": "models/gemini-2.5-pro",
+                        "displayName": "Gemini 2.5 Pro",
+                        "inputTokenLimit": 1048576,
+                        "supportedGenerationMethods": ["generateContent", "countTokens"]
+                    }],
+                    "nextPageToken": "page-2"
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let gemini_second_page = server
+            .mock("GET", "/v1/models")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "pageToken".into(),
+                "page-2".into(),
+            ))
+            .with_status(200)
+            .with_body(
+                serde_json::json!({
+                    "models": [{
+                        "name": "models/text-embedding-004",
+                        "displayName": "Text Embedding 004",
+                        "inputTokenLimit": 2048,
+                        "supportedGenerationMethods": ["embedContent"]
+                    }]
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let openai_mock = server
+            .mock("GET", "/models")
+            .match_header("authorization", "Bearer key")
+            .with_status(200)
+            .with_body(
+                serde_json::json!({
+                    "object": "list",
+                    "data": [
+                        { "id": "gpt-4o", "object": "model", "owned_by": "openai" },
+                        { "id": "o3-mini", "object": "model", "owned_by": "openai" },
+                        { "id": "text-embedding-3-large", "object": "model", "owned_by": "openai" }
+                    ]
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut gemini_config = GeminiConfig::default_api_key("test-model".to_string());
+        gemini_config.base_url = format!("{}/v1/models", server.url());
+        let gemini = GeminiProvider::new(GeminiAuth::ApiKey("key".to_string()), gemini_config);
+
+        let models = gemini.list_models().await.unwrap();
+        assert_eq!(models.len(), 2);
+        assert_eq!(models[0].id, "gemini-2.5-pro");
+        assert_eq!(models[0].display_name, "Gemini 2.5 Pro");
+        assert_eq!(models[0].context_window, Some(1_048_576));
+        assert_eq!(models[0].capabilities, vec![Capability::TextGeneration]);
+        assert_eq!(models[1].capabilities, vec![Capability::Embedding]);
+        assert_eq!(models[1].embedding_dimensions, Some(768));
+
+        let openai = OpenAIProvider::new(
+            "key".to_string(),
+            OpenAIConfig::with_base_url("test-model".to_string(), server.url()),
+        );
+
+        let models = openai.list_models().await.unwrap();
+        let ids: Vec<_> = models.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, vec!["gpt-4o", "o3-mini", "text-embedding-3-large"]);
+        // Known models keep their static details
+        assert_eq!(models[0].context_window, Some(128_000));
+        assert_eq!(models[1].capabilities, vec![Capability::TextGeneration]);
+        assert_eq!(models[2].capabilities, vec![Capability::Embedding]);
+
+        gemini_first_page.assert_async().await;
+        gemini_second_page.assert_async().await;
+        openai_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_list_models_falls_back_to_static_list() {
+        use crate::providers::{Provider, openai::OpenAIConfig};
+
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("GET", "/models")
+            .with_status(500)
+            .create_async()
+            .await;
+
+        let openai = OpenAIProvider::new(
+            "key".to_string(),
+            OpenAIConfig::with_base_url("test-model".to_string(), server.url()),
+        );
+
+        let models = openai.list_models().await.unwrap();
+        assert_eq!(models.len(), OpenAIProvider::static_metadata().models.len());
+    }
+
+    #[tokio::test]
+    async fn test_usage_parsed_from_responses() {
+        use crate::{
+            Content, LLMRequest, LLMResponse, TokenUsage,
+            providers::{Provider, gemini::GeminiConfig, openai::OpenAIConfig},
+        };
+        use futures::StreamExt;
+
+        let request = LLMRequest {
+            model: "test-model".to_string(),
+            system_instruction: None,
+            contents: vec![Content::new_user_text("Hi")],
+            config: None,
+            tools: vec![],
+        };
+
+        // The last usage reported in a response stream
+        async fn final_usage(
+            provider: &dyn Provider,
+            request: &LLMRequest,
+            stream: bool,
+        ) -> Option<TokenUsage> {
+            let responses: Vec<LLMResponse> =
+                Provider::generate_content(provider, request.clone(), stream)
+                    .await
+                    .unwrap()
+                    .map(|r| r.unwrap())
+                    .collect()
+                    .await;
+            responses.iter().rev().find_map(|r| r.usage)
+        }
+
+        let mut server = mockito::Server::new_async().await;
+        let gemini_candidate = serde_json::json!({
+            "content": { "role": "model", "parts": [{ "text": "Hi." }] },
+            "finishReason": "STOP"
+        });
+        let _gemini = server
+            .mock("POST", "/v1/models/test-model:generateContent")
+            .match_query(mockito::Matcher::Any)
+            .with_body(
+                serde_json::json!({
+                    "candidates": [gemini_candidate],
+                    "usageMetadata": {
+                        "promptTokenCount": 11,
+                        "candidatesTokenCount": 4,
+                        "totalTokenCount": 15
+                    }
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let _gemini_stream = server
+            .mock("POST", "/v1/models/test-model:streamGenerateContent")
+            .match_query(mockito::Matcher::Any)
+            .with_body(format!(
+                "data: {}\n\ndata: {}\n\n",
+                serde_json::json!({
+                    "candidates": [gemini_candidate],
+                    "usageMetadata": { "promptTokenCount": 11, "candidatesTokenCount": 2 }
+                }),
+                serde_json::json!({
+                    "candidates": [gemini_candidate],
+                    "usageMetadata": {
+                        "promptTokenCount": 11,
+                        "candidatesTokenCount": 6,
+                        "totalTokenCount": 17
+                    }
+                }),
+            ))
+            .create_async()
+            .await;
+
+        let _openai = server
+            .mock("POST", "/chat/completions")
+            .match_body(mockito::Matcher::PartialJson(
+                serde_json::json!({ "stream": false }),
+            ))
+            .with_body(
+                serde_json::json!({
+                    "id": "chatcmpl-1",
+                    "object": "chat.completion",
+                    "created": 0,
+                    "model": "test-model",
+                    "choices": [{
+                        "index": 0,
+                        "message": { "role": "assistant", "content": "Hi." },
+                        "finish_reason": "stop"
+                    }],
+                    "usage": { "prompt_tokens": 9, "completion_tokens": 3, "total_tokens": 12 }
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let openai_chunk = |choices: serde_json::Value, usage: serde_json::Value| {
+            serde_json::json!({
+                "id": "chatcmpl-1",
+                "object": "chat.completion.chunk",
+                "created": 0,
+                "model": "test-model",
+                "choices": choices,
+                "usage": usage
+            })
+        };
+        let _openai_stream = server
+            .mock("POST", "/chat/completions")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "stream": true,
+                "stream_options": { "include_usage": true }
+            })))
+            .with_body(format!(
+                "data: {}\n\ndata: {}\n\ndata: [DONE]\n\n",
+                openai_chunk(
+                    serde_json::json!([{
+                        "index": 0,
+                        "delta": { "content": "Hi." },
+                        "finish_reason": "stop"
+                    }]),
+                    serde_json::Value::Null
+                ),
+                openai_chunk(
+                    serde_json::json!([]),
+                    serde_json::json!({ "prompt_tokens": 9, "completion_tokens": 5, "total_tokens": 14 })
+                ),
+            ))
+            .create_async()
+            .await;
+
+        let mut gemini_config = GeminiConfig::default_api_key("test-model".to_string());
+        gemini_config.base_url = format!("{}/v1/models", server.url());
+        let gemini = GeminiProvider::new(GeminiAuth::ApiKey("key".to_string()), gemini_config);
+        let openai = OpenAIProvider::new(
+            "key".to_string(),
+            OpenAIConfig::with_base_url("test-model".to_string(), server.url()),
+        );
+
+        let usage = |prompt_tokens, completion_tokens, total_tokens| {
+            Some(TokenUsage {
+                prompt_tokens,
+                completion_tokens,
+                total_tokens,
+            })
+        };
+        assert_eq!(
+            final_usage(&gemini, &request, false).await,
+            usage(11, 4, 15)
+        );
+        assert_eq!(final_usage(&gemini, &request, true).await, usage(11, 6, 17));
+        assert_eq!(final_usage(&openai, &request, false).await, usage(9, 3, 12));
+        assert_eq!(final_usage(&openai, &request, true).await, usage(9, 5, 14));
+    }
+
+    #[tokio::test]
+    async fn test_openai_tool_calls_round_trip() {
+        use crate::{
+            Content, FunctionResponse, LLMRequest, Part, Result, Tool, ToolContext, ToolResponse,
+            providers::{Provider, openai::OpenAIConfig},
+        };
+        use async_trait::async_trait;
+        use futures::StreamExt;
+        use serde_json::json;
+        use std::sync::Arc;
+
+        struct WeatherTool;
+
+        #[async_trait]
+        impl Tool for WeatherTool {
+            fn name(&self) -> &str {
+                "get_weather"
+            }
+
+            fn description(&self) -> &str {
+                "Get the weather for a city"
+            }
+
+            fn schema(&self) -> serde_json::Value {
+                json!({ "type": "object", "properties": { "city": { "type": "string" } } })
+            }
+
+            async fn execute(
+                &self,
+                _ctx: Arc<dyn ToolContext>,
+                _params: serde_json::Value,
+            ) -> Result<ToolResponse> {
+                unreachable!()
+            }
+        }
+
+        let mut server = mockito::Server::new_async().await;
+        let openai = OpenAIProvider::new(
+            "key".to_string(),
+            OpenAIConfig::with_base_url("test-model".to_string(), server.url()),
+        );
+        let tools: Vec<Arc<dyn Tool>> = vec![Arc::new(WeatherTool)];
+        let completion = |message: serde_json::Value, finish_reason: &str| {
+            json!({
+                "id": "chatcmpl-1",
+                "object": "chat.completion",
+                "created": 0,
+                "model": "test-model",
+                "choices": [{ "index": 0, "message": message, "finish_reason": finish_reason }]
+            })
+            .to_string()
+        };
+
+        // First turn: the tool is advertised and the model calls it
+        let call_mock = server
+            .mock("POST", "/chat/completions")
+            .match_body(mockito::Matcher::PartialJson(json!({
+                "tools": [{
+                    "type": "function",
+                    "function": {
+                        "name": "get_weather",
+                        "description": "Get the weather for a city",
+                        "parameters": { "type": "object", "properties": { "city": { "type": "string" } } }
+                    }
+                }],
+                "messages": [{ "role": "user", "content": "Weather in Paris?" }]
+            })))
+            .with_body(completion(
+                json!({
+                    "role": "assistant",
+                    "content": null,
+                    "tool_calls": [{
+                        "id": "call_1",
+                        "type": "function",
+                        "function": { "name": "get_weather", "arguments": "{\"city\":\"Paris\"}" }
+                    }]
+                }),
+                "tool_calls",
+            ))
+            .create_async()
+            .await;
+
+        let mut request = LLMRequest {
+            model: "test-model".to_string(),
+            system_instruction: None,
+            contents: vec![Content::new_user_text("Weather in Paris?")],
+            config: None,
+            tools: tools.clone(),
+        };
+        let response = Provider::generate_content(&openai, request.clone(), false)
+            .await
+            .unwrap()
+            .next()
+            .await
+            .unwrap()
+            .unwrap();
+        call_mock.assert_async().await;
+        assert_eq!(response.finish_reason, Some(crate::FinishReason::ToolCalls));
+        let content = response.content.unwrap();
+        match &content.parts[..] {
+            [Part::FunctionCall { function_call }] => {
+                assert_eq!(function_call.name, "get_weather");
+                assert_eq!(function_call.args, json!({ "city": "Paris" }));
+                assert_eq!(function_call.id.as_deref(), Some("call_1"));
+            }
+            other => panic!("expected one function call, got {:?}", other),
+        }
+
+        // Second turn: the call and its result are sent back
+        let result_mock = server
+            .mock("POST", "/chat/completions")
+            .match_body(mockito::Matcher::PartialJson(json!({
+                "messages": [
+                    { "role": "user", "content": "Weather in Paris?" },
+                    {
+                        "role": "assistant",
+                        "tool_calls": [{
+                            "id": "call_1",
+                            "type": "function",
+                            "function": { "name": "get_weather", "arguments": "{\"city\":\"Paris\"}" }
+                        }]
+                    },
+                    { "role": "tool", "tool_call_id": "call_1", "content": "{\"temp\":21}" }
+                ]
+            })))
+            .with_body(completion(
+                json!({ "role": "assistant", "content": "It is 21 degrees." }),
+                "stop",
+            ))
+            .create_async()
+            .await;
+
+        request.contents.push(content);
+        request.contents.push(Content {
+            role: "function".to_string(),
+            parts: vec![Part::FunctionResponse {
+                function_response: FunctionResponse {
+                    name: "get_weather".to_string(),
+                    response: json!({ "temp": 21 }),
+                    id: Some("call_1".to_string()),
+                },
+            }],
+        });
+        let response = Provider::generate_content(&openai, request.clone(), false)
+            .await
+            .unwrap()
+            .next()
+            .await
+            .unwrap()
+            .unwrap();
+        result_mock.assert_async().await;
+        assert!(matches!(
+            &response.content.unwrap().parts[..],
+            [Part::Text { text }] if text == "It is 21 degrees."
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_openai_streamed_tool_call() {
+        use crate::{
+            Content, LLMRequest, LLMResponse, Part,
+            providers::{Provider, openai::OpenAIConfig},
+        };
+        use futures::StreamExt;
+        use serde_json::json;
+
+        let chunk = |delta: serde_json::Value, finish_reason: serde_json::Value| {
+            format!(
+                "data: {}\n\n",
+                json!({
+                    "id": "chatcmpl-1",
+                    "object": "chat.completion.chunk",
+                    "created": 0,
+                    "model": "test-model",
+                    "choices": [{ "index": 0, "delta": delta, "finish_reason": finish_reason }]
+                })
+            )
+        };
+        let body = [
+            chunk(
+                json!({ "role": "assistant", "tool_calls": [{
+                    "index": 0,
+                    "id": "call_1",
+                    "type": "function",
+                    "function": { "name": "get_weather", "arguments": "" }
+                }] }),
+                json!(null),
+            ),
+            chunk(
+                json!({ "tool_calls": [{ "index": 0, "function": { "arguments": "{\"city\":" } }] }),
+                json!(null),
+            ),
+            chunk(
+                json!({ "tool_calls": [{ "index": 0, "function": { "arguments": "\"Paris\"}" } }] }),
+                json!(null),
+            ),
+            chunk(json!({}), json!("tool_calls")),
+            "data: [DONE]\n\n".to_string(),
+        ]
+        .concat();
+
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("POST", "/chat/completions")
+            .with_body(body)
+            .create_async()
+            .await;
+        let openai = OpenAIProvider::new(
+            "key".to_string(),
+            OpenAIConfig::with_base_url("test-model".to_string(), server.url()),
+        );
+        let request = LLMRequest {
+            model: "test-model".to_string(),
+            system_instruction: None,
+            contents: vec![Content::new_user_text("Weather in Paris?")],
+            config: None,
+            tools: vec![],
+        };
+
+        let responses: Vec<LLMResponse> = Provider::generate_content(&openai, request, true)
+            .await
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect()
+            .await;
+        let last = responses.last().unwrap();
+        assert!(last.turn_complete);
+        assert_eq!(last.finish_reason, Some(crate::FinishReason::ToolCalls));
+        match &last.content.as_ref().unwrap().parts[..] {
+            [Part::FunctionCall { function_call }] => {
+                assert_eq!(function_call.name, "get_weather");
+                assert_eq!(function_call.args, json!({ "city": "Paris" }));
+                assert_eq!(function_call.id.as_deref(), Some("call_1"));
+            }
+            other => panic!("expected one function call, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_openai_generate_image() {
+        use crate::{
+            ImageRequest,
+            providers::{Provider, openai::OpenAIConfig},
+        };
+        use base64::{Engine as _, engine::general_purpose};
+        use serde_json::json;
+
+        let png = b"\x89PNG\r\n\x1a\nfake";
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/images/generations")
+            .match_header("authorization", "Bearer key")
+            .match_body(mockito::Matcher::Json(json!({
+                "model": "dall-e-3",
+                "prompt": "A lighthouse at dusk",
+                "size": "1024x1024",
+                "n": 2
+            })))
+            .with_body(
+                json!({
+                    "created": 0,
+                    "data": [
+                        {
+                            "b64_json": general_purpose::STANDARD.encode(png),
+                            "revised_prompt": "A red lighthouse at dusk"
+                        },
+                        { "url": "https://example.com/image.png" }
+                    ]
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let openai = OpenAIProvider::new(
+            "key".to_string(),
+            OpenAIConfig::with_base_url("test-model".to_string(), server.url()),
+        );
+        let result = openai
+            .generate_image(ImageRequest {
+                prompt: "A lighthouse at dusk".to_string(),
+                size: Some("1024x1024".to_string()),
+                quality: None,
+                style: None,
+                n: Some(2),
+            })
+            .await
+            .unwrap();
+        mock.assert_async().await;
+
+        assert_eq!(result.images.len(), 2);
+        assert_eq!(result.images[0].data, png);
+        assert_eq!(
+            result.images[0].revised_prompt.as_deref(),
+            Some("A red lighthouse at dusk")
+        );
+        assert!(result.images[1].data.is_empty());
+        assert_eq!(
+            result.images[1].url.as_deref(),
+            Some("https://example.com/image.png")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_openai_generate_audio() {
+        use crate::{
+            AudioRequest,
+            providers::{Provider, openai::OpenAIConfig},
+        };
+        use serde_json::json;
+
+        let audio = b"ID3\x04\x00fake-mp3";
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/audio/speech")
+            .match_header("authorization", "Bearer key")
+            .match_body(mockito::Matcher::Json(json!({
+                "model": "tts-1",
+                "input": "Hello there",
+                "voice": "nova",
+                "response_format": "mp3",
+                "speed": 1.5
+            })))
+            .with_header("content-type", "audio/mpeg")
+            .with_body(audio)
+            .create_async()
+            .await;
+
+        let openai = OpenAIProvider::new(
+            "key".to_string(),
+            OpenAIConfig::with_base_url("test-model".to_string(), server.url()),
+        );
+        let result = openai
+            .generate_audio(AudioRequest {
+                text: "Hello there".to_string(),
+                voice: Some("nova".to_string()),
+                speed: Some(1.5),
+                format: None,
+            })
+            .await
+            .unwrap();
+        mock.assert_async().await;
+
+        assert_eq!(result.data, audio);
+        assert_eq!(result.format, "mp3");
+        assert_eq!(result.mime_type, "audio/mpeg");
+    }
+
+    #[tokio::test]
+    async fn test_openai_transcription_segments() {
+        use crate::{
+            AudioInput,
+            providers::{Provider, openai::OpenAIConfig},
+        };
+        use serde_json::json;
+
+        let mut server = mockito::Server::new_async().await;
+        let verbose_mock = server
+            .mock("POST", "/audio/transcriptions")
+            .match_body(mockito::Matcher::Regex("verbose_json".to_string()))
+            .with_body(
+                json!({
+                    "task": "transcribe",
+                    "language": "english",
+                    "duration": 4.2,
+                    "text": "Hello there. General Kenobi.",
+                    "segments": [
+                        {
+                            "id": 0, "seek": 0, "start": 0.0, "end": 1.5,
+                            "text": " Hello there.", "tokens": [50364, 2425],
+                            "temperature": 0.0, "avg_logprob": -0.2,
+                            "compression_ratio": 0.9, "no_speech_prob": 0.01
+                        },
+                        {
+                            "id": 1, "seek": 0, "start": 1.5, "end": 4.2,
+                            "text": " General Kenobi.", "tokens": [50439, 6996],
+                            "temperature": 0.0, "avg_logprob": -0.3,
+                            "compression_ratio": 0.9, "no_speech_prob": 0.02
+                        }
+                    ]
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let plain_mock = server
+            .mock("POST", "/audio/transcriptions")
+            .with_body(json!({ "text": "Hello there." }).to_string())
+            .create_async()
+            .await;
+
+        let openai = OpenAIProvider::new(
+            "key".to_string(),
+            OpenAIConfig::with_base_url("test-model".to_string(), server.url()),
+        );
+        let audio = |include_segments| AudioInput {
+            data: b"fake-audio".to_vec(),
+            format: "mp3".to_string(),
+            language: None,
+            include_segments,
+        };
+
+        let result = openai.transcribe_audio(audio(true)).await.unwrap();
+        verbose_mock.assert_async().await;
+        assert_eq!(result.duration, Some(4.2));
+        let segments = result.segments.unwrap();
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[1].text, " General Kenobi.");
+        assert_eq!(segments[1].start, 1.5);
+        assert_eq!(segments[1].end, 4.2);
+
+        // Default requests keep the plain response format
+        let result = openai.transcribe_audio(audio(false)).await.unwrap();
+        plain_mock.assert_async().await;
+        assert_eq!(result.text, "Hello there.");
+        assert!(result.segments.is_none());
+    }
+
+    /// Embeds each text as its numeric value and counts requests
+    struct CountingEmbedder {
+        requests: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl crate::LLM for CountingEmbedder {
+        fn name(&self) -> &str {
+            "counting-embedder"
+        }
+
+        async fn generate_content(
+            &self,
+            _request: crate::LLMRequest,
+            _stream: bool,
+        ) -> Box<dyn futures::Stream<Item = crate::Result<crate::LLMResponse>> + Send + Unpin>
+        {
+            Box::new(futures::stream::empty())
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl crate::providers::Provider for CountingEmbedder {
+        fn metadata(&self) -> crate::providers::ProviderMetadata {
+            crate::providers::ProviderMetadata {
+                name: "counting".to_string(),
+                display_name: "Counting".to_string(),
+                capabilities: vec![Capability::Embedding],
+                models: vec![],
+            }
+        }
+
+        async fn embed_texts(
+            &self,
+            texts: Vec<String>,
+        ) -> crate::Result<Vec<crate::EmbeddingVector>> {
+            assert!(
+                texts.len() <= 100,
+                "batch of {} exceeds the limit",
+                texts.len()
+            );
+            self.requests
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(texts
+                .iter()
+                .map(|t| crate::EmbeddingVector::new(vec![t.parse().unwrap()]))
+                .collect())
+        }
+
+        fn max_embedding_batch_size(&self) -> Option<usize> {
+            Some(100)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_embed_texts_batched_preserves_order() {
+        use crate::providers::Provider;
+
+        let provider = CountingEmbedder {
+            requests: std::sync::atomic::AtomicUsize::new(0),
+        };
+        let texts: Vec<String> = (0..250).map(|i| i.to_string()).collect();
+
+        let vectors = provider.embed_texts_batched(texts).await.unwrap();
+
+        assert_eq!(
+            provider.requests.load(std::sync::atomic::Ordering::SeqCst),
+            3
+        );
+        assert_eq!(vectors.len(), 250);
+        for (i, embedding) in vectors.iter().enumerate() {
+            assert_eq!(embedding.vector, [i as f32]);
+        }
+    }
 }
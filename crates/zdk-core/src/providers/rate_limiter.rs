@@ -0,0 +1,179 @@
+//! Shared rate limiting for outbound provider calls
+//!
+//! A single [`RateLimiter`] can be shared via `Arc` between any number of
+//! providers (and therefore agents), so a whole process respects one quota no
+//! matter how many sub-agents run in parallel.
+
+use crate::{LLMRequest, Part};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Token bucket rate limiter with a requests-per-minute and optional
+/// tokens-per-minute quota
+///
+/// Both buckets start full and refill continuously. By default a bucket holds
+/// a full minute of quota; use [`with_burst`](Self::with_burst) to smooth
+/// traffic out instead.
+#[derive(Debug)]
+pub struct RateLimiter {
+    requests: Mutex<Bucket>,
+    tokens: Option<Mutex<Bucket>>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    capacity: f64,
+    available: f64,
+    per_second: f64,
+    last_refill: Instant,
+}
+
+impl Bucket {
+    fn new(per_minute: u32, capacity: u32) -> Self {
+        Self {
+            capacity: capacity.max(1) as f64,
+            available: capacity.max(1) as f64,
+            per_second: per_minute.max(1) as f64 / 60.0,
+            last_refill: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.available = (self.available + elapsed * self.per_second).min(self.capacity);
+        self.last_refill = now;
+    }
+
+    /// Time until `amount` is available, or `None` if it already is
+    fn wait_for(&mut self, amount: f64) -> Option<Duration> {
+        self.refill();
+        // Requests larger than the bucket only wait for a full bucket
+        let amount = amount.min(self.capacity);
+        if self.available >= amount {
+            None
+        } else {
+            Some(Duration::from_secs_f64(
+                (amount - self.available) / self.per_second,
+            ))
+        }
+    }
+}
+
+impl RateLimiter {
+    /// Create a limiter allowing `requests_per_minute` calls
+    pub fn new(requests_per_minute: u32) -> Self {
+        Self {
+            requests: Mutex::new(Bucket::new(requests_per_minute, requests_per_minute)),
+            tokens: None,
+        }
+    }
+
+    /// Also limit the estimated number of tokens sent per minute
+    pub fn with_tokens_per_minute(mut self, tokens_per_minute: u32) -> Self {
+        self.tokens = Some(Mutex::new(Bucket::new(
+            tokens_per_minute,
+            tokens_per_minute,
+        )));
+        self
+    }
+
+    /// Limit how many requests may be sent back to back before pacing kicks in
+    pub fn with_burst(mut self, burst: u32) -> Self {
+        let bucket = self.requests.get_mut().unwrap();
+        bucket.capacity = burst.max(1) as f64;
+        bucket.available = bucket.available.min(bucket.capacity);
+        self
+    }
+
+    /// Wait until one request carrying `estimated_tokens` may be sent
+    pub async fn acquire(&self, estimated_tokens: u32) {
+        loop {
+            match self.try_acquire(estimated_tokens as f64) {
+                None => return,
+                Some(wait) => tokio::time::sleep(wait).await,
+            }
+        }
+    }
+
+    /// Take quota if both buckets have room, otherwise return how long to wait
+    fn try_acquire(&self, tokens: f64) -> Option<Duration> {
+        let mut requests = self.requests.lock().unwrap();
+        let mut token_bucket = self.tokens.as_ref().map(|t| t.lock().unwrap());
+
+        let request_wait = requests.wait_for(1.0);
+        let token_wait = token_bucket.as_mut().and_then(|t| t.wait_for(tokens));
+
+        match (request_wait, token_wait) {
+            (None, None) => {
+                requests.available -= 1.0;
+                if let Some(t) = token_bucket.as_mut() {
+                    t.available -= tokens.min(t.capacity);
+                }
+                None
+            }
+            (a, b) => a.max(b),
+        }
+    }
+}
+
+/// Rough token estimate for a request (about four characters per token)
+pub fn estimate_request_tokens(request: &LLMRequest) -> u32 {
+    let chars: usize = request
+        .system_instruction
+        .as_ref()
+        .map(|s| s.len())
+        .unwrap_or(0)
+        + request
+            .contents
+            .iter()
+            .flat_map(|c| c.parts.iter())
+            .map(|p| match p {
+                Part::Text { text } => text.len(),
+                _ => 0,
+            })
+            .sum::<usize>();
+
+    (chars / 4).max(1) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_burst_then_paced() {
+        // 1200 RPM = one request every 50ms, no burst
+        let limiter = RateLimiter::new(1200).with_burst(1);
+
+        let start = std::time::Instant::now();
+        for _ in 0..5 {
+            limiter.acquire(1).await;
+        }
+
+        // First request is immediate, the next four are paced
+        assert!(start.elapsed() >= Duration::from_millis(190));
+    }
+
+    #[tokio::test]
+    async fn test_full_bucket_is_not_delayed() {
+        let limiter = RateLimiter::new(60);
+
+        let start = std::time::Instant::now();
+        for _ in 0..10 {
+            limiter.acquire(1).await;
+        }
+
+        assert!(start.elapsed() < Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn test_token_quota_limits_requests() {
+        // Plenty of requests, but only 10 tokens per 50ms
+        let limiter = RateLimiter::new(10_000).with_tokens_per_minute(12_000);
+
+        limiter.acquire(12_000).await;
+        assert!(limiter.try_acquire(10.0).is_some());
+    }
+}
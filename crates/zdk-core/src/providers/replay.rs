@@ -0,0 +1,462 @@
+//! Record and replay LLM calls for deterministic debugging
+//!
+//! [`RecordingProvider`] wraps any model and appends every request and the
+//! responses it produced to a cassette file (one JSON object per line).
+//! [`ReplayProvider`] serves those responses back offline, so a captured run
+//! can be reproduced exactly without network access.
+
+use crate::{Content, Error, GenerateConfig, LLM, LLMRequest, LLMResponse, Result};
+use async_stream::stream;
+use async_trait::async_trait;
+use futures::stream::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tokio::io::AsyncWriteExt;
+
+/// The parts of an [`LLMRequest`] that identify a call on replay
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct RecordedRequest {
+    model: String,
+    system_instruction: Option<String>,
+    contents: serde_json::Value,
+    config: Option<serde_json::Value>,
+    /// Names of the tools offered to the model
+    tools: Vec<String>,
+}
+
+impl RecordedRequest {
+    pub(crate) fn from_request(request: &LLMRequest) -> Result<Self> {
+        Ok(Self {
+            model: request.model.clone(),
+            system_instruction: request.system_instruction.clone(),
+            contents: serde_json::to_value::<&[Content]>(&request.contents)?,
+            config: request
+                .config
+                .as_ref()
+                .map(serde_json::to_value::<&GenerateConfig>)
+                .transpose()?,
+            tools: request.tools.iter().map(|t| t.name().to_string()).collect(),
+        })
+    }
+}
+
+/// Recorded items of one response stream, with errors kept as their messages
+type RecordedResponses = Vec<std::result::Result<LLMResponse, String>>;
+
+/// One recorded call: the request and every item of the response stream
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Interaction {
+    request: RecordedRequest,
+    responses: RecordedResponses,
+}
+
+/// Cassette file shared by every call of a [`RecordingProvider`]
+type Cassette = Arc<tokio::sync::Mutex<tokio::fs::File>>;
+
+/// Decorator that records every call made to the wrapped model
+///
+/// Each call is appended to the cassette as a single JSON line once its
+/// response stream ends, so a run that crashes part-way still leaves every
+/// finished call on disk. A stream dropped before it ends is recorded with the
+/// responses read so far.
+pub struct RecordingProvider {
+    inner: Arc<dyn LLM>,
+    cassette: Cassette,
+}
+
+impl RecordingProvider {
+    /// Record calls to `inner` into a new cassette at `path`, replacing any existing file
+    pub fn new(inner: Arc<dyn LLM>, path: impl AsRef<Path>) -> Result<Self> {
+        let cassette = File::create(path)?;
+        Ok(Self {
+            inner,
+            cassette: Arc::new(tokio::sync::Mutex::new(tokio::fs::File::from_std(cassette))),
+        })
+    }
+
+    /// Record calls to `inner`, appending to the cassette at `path`
+    pub fn append(inner: Arc<dyn LLM>, path: impl AsRef<Path>) -> Result<Self> {
+        let cassette = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            inner,
+            cassette: Arc::new(tokio::sync::Mutex::new(tokio::fs::File::from_std(cassette))),
+        })
+    }
+}
+
+#[async_trait]
+impl LLM for RecordingProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn capabilities(&self) -> Option<Vec<crate::Capability>> {
+        self.inner.capabilities()
+    }
+
+    fn context_window(&self) -> Option<usize> {
+        self.inner.context_window()
+    }
+
+    async fn generate_content(
+        &self,
+        request: LLMRequest,
+        stream: bool,
+    ) -> Box<dyn Stream<Item = Result<LLMResponse>> + Send + Unpin> {
+        let recorded = RecordedRequest::from_request(&request);
+        let mut inner_stream = self.inner.generate_content(request, stream).await;
+        let cassette = self.cassette.clone();
+
+        Box::new(Box::pin(stream! {
+            let recorded = match recorded {
+                Ok(recorded) => recorded,
+                Err(e) => {
+                    yield Err(e);
+                    return;
+                }
+            };
+
+            // Pass items through untouched while keeping a copy for the cassette
+            let mut pending = PendingInteraction {
+                interaction: Some(Interaction { request: recorded, responses: Vec::new() }),
+                cassette,
+            };
+            while let Some(item) = inner_stream.next().await {
+                pending.push(&item);
+                yield item;
+            }
+            pending.finish().await;
+        }))
+    }
+}
+
+/// A call being recorded, written once its stream ends or is dropped
+struct PendingInteraction {
+    interaction: Option<Interaction>,
+    cassette: Cassette,
+}
+
+impl PendingInteraction {
+    fn push(&mut self, item: &Result<LLMResponse>) {
+        if let Some(interaction) = &mut self.interaction {
+            interaction.responses.push(match item {
+                Ok(response) => Ok(response.clone()),
+                Err(e) => Err(e.to_string()),
+            });
+        }
+    }
+
+    /// Write the finished call to the cassette
+    async fn finish(mut self) {
+        if let Some(interaction) = self.interaction.take() {
+            write_interaction(&self.cassette, &interaction).await;
+        }
+    }
+}
+
+impl Drop for PendingInteraction {
+    fn drop(&mut self) {
+        let Some(interaction) = self.interaction.take() else {
+            return;
+        };
+
+        // The stream was dropped mid-call; write what was read in the background
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                let cassette = self.cassette.clone();
+                handle.spawn(async move { write_interaction(&cassette, &interaction).await });
+            }
+            Err(_) => {
+                tracing::warn!("Dropped LLM call not written to cassette outside a Tokio runtime")
+            }
+        }
+    }
+}
+
+/// Append one call to the cassette as a JSON line
+async fn write_interaction(
+    cassette: &tokio::sync::Mutex<tokio::fs::File>,
+    interaction: &Interaction,
+) {
+    let written = async {
+        let mut line = serde_json::to_vec(interaction)?;
+        line.push(b'\n');
+        let mut file = cassette.lock().await;
+        file.write_all(&line).await?;
+        file.flush().await?;
+        Ok::<_, Error>(())
+    }
+    .await;
+    if let Err(e) = written {
+        tracing::warn!(error = %e, "Failed to write LLM call to cassette");
+    }
+}
+
+/// Model that answers from a cassette written by [`RecordingProvider`]
+///
+/// Requests are matched on model, system instruction, contents, generation
+/// config and offered tool names. Identical requests are served in the order
+/// they were recorded. A request with no remaining recording yields an error.
+pub struct ReplayProvider {
+    name: String,
+    interactions: Mutex<Vec<(RecordedRequest, VecDeque<RecordedResponses>)>>,
+}
+
+impl ReplayProvider {
+    /// Load the cassette at `path`
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+
+        let mut interactions: Vec<(RecordedRequest, VecDeque<_>)> = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let interaction: Interaction = serde_json::from_str(&line)?;
+            match interactions
+                .iter_mut()
+                .find(|(request, _)| *request == interaction.request)
+            {
+                Some((_, queue)) => queue.push_back(interaction.responses),
+                None => interactions
+                    .push((interaction.request, VecDeque::from([interaction.responses]))),
+            }
+        }
+
+        // Agents put the model name in every request, so answer to the recorded one
+        let name = interactions
+            .first()
+            .map(|(request, _)| request.model.clone())
+            .unwrap_or_else(|| "replay".to_string());
+
+        Ok(Self {
+            name,
+            interactions: Mutex::new(interactions),
+        })
+    }
+
+    /// Number of recorded calls not yet replayed
+    pub fn remaining(&self) -> usize {
+        self.interactions
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, queue)| queue.len())
+            .sum()
+    }
+}
+
+#[async_trait]
+impl LLM for ReplayProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn generate_content(
+        &self,
+        request: LLMRequest,
+        _stream: bool,
+    ) -> Box<dyn Stream<Item = Result<LLMResponse>> + Send + Unpin> {
+        let responses = RecordedRequest::from_request(&request).and_then(|recorded| {
+            self.interactions
+                .lock()
+                .unwrap()
+                .iter_mut()
+                .find(|(request, _)| *request == recorded)
+                .and_then(|(_, queue)| queue.pop_front())
+                .ok_or_else(|| {
+                    Error::LLMError(format!(
+                        "No recorded response for request to {} with {} messages",
+                        recorded.model,
+                        request.contents.len()
+                    ))
+                })
+        });
+
+        Box::new(Box::pin(stream! {
+            match responses {
+                Ok(responses) => {
+                    for response in responses {
+                        yield response.map_err(Error::LLMError);
+                    }
+                }
+                Err(e) => yield Err(e),
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FinishReason, Part};
+
+    /// Answers every request with a numbered reply, split into two chunks
+    struct CountingLLM {
+        calls: Mutex<u32>,
+    }
+
+    #[async_trait]
+    impl LLM for CountingLLM {
+        fn name(&self) -> &str {
+            "counting-llm"
+        }
+
+        async fn generate_content(
+            &self,
+            _request: LLMRequest,
+            _stream: bool,
+        ) -> Box<dyn Stream<Item = Result<LLMResponse>> + Send + Unpin> {
+            let call = {
+                let mut calls = self.calls.lock().unwrap();
+                *calls += 1;
+                *calls
+            };
+
+            Box::new(Box::pin(stream! {
+                yield Ok(chunk(&format!("reply {} ", call), true));
+                yield Ok(chunk("done", false));
+            }))
+        }
+    }
+
+    fn chunk(text: &str, partial: bool) -> LLMResponse {
+        LLMResponse {
+            content: Some(Content::new_model_text(text)),
+            partial,
+            turn_complete: !partial,
+            interrupted: false,
+            finish_reason: (!partial).then_some(FinishReason::Stop),
+            error_code: None,
+            error_message: None,
+            usage: None,
+        }
+    }
+
+    fn request(model: &str, text: &str) -> LLMRequest {
+        LLMRequest {
+            model: model.to_string(),
+            system_instruction: Some("Be brief".to_string()),
+            contents: vec![Content::new_user_text(text)],
+            config: Some(GenerateConfig {
+                temperature: Some(0.0),
+                ..Default::default()
+            }),
+            tools: Vec::new(),
+        }
+    }
+
+    async fn texts(llm: &dyn LLM, request: LLMRequest) -> Vec<Result<String>> {
+        llm.generate_content(request, true)
+            .await
+            .map(|item| {
+                item.map(|response| match &response.content.unwrap().parts[0] {
+                    Part::Text { text } => text.clone(),
+                    _ => String::new(),
+                })
+            })
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_record_then_replay_is_identical() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.jsonl");
+
+        let recorder = RecordingProvider::new(
+            Arc::new(CountingLLM {
+                calls: Mutex::new(0),
+            }),
+            &path,
+        )
+        .unwrap();
+
+        let mut recorded = Vec::new();
+        for text in ["hello", "again", "hello"] {
+            let output = texts(&recorder, request("counting-llm", text)).await;
+            recorded.push(output.into_iter().map(|r| r.unwrap()).collect::<Vec<_>>());
+        }
+
+        let replay = ReplayProvider::from_file(&path).unwrap();
+        assert_eq!(replay.name(), "counting-llm");
+        assert_eq!(replay.remaining(), 3);
+
+        // Replaying the same calls, even in a different order, reproduces each answer
+        let again = texts(&replay, request("counting-llm", "again")).await;
+        let first = texts(&replay, request("counting-llm", "hello")).await;
+        let second = texts(&replay, request("counting-llm", "hello")).await;
+        let unwrap_all = |items: Vec<Result<String>>| -> Vec<String> {
+            items.into_iter().map(|r| r.unwrap()).collect()
+        };
+        assert_eq!(unwrap_all(first), recorded[0]);
+        assert_eq!(unwrap_all(again), recorded[1]);
+        assert_eq!(unwrap_all(second), recorded[2]);
+        assert_eq!(replay.remaining(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_dropped_stream_is_still_recorded() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.jsonl");
+
+        let recorder = RecordingProvider::new(
+            Arc::new(CountingLLM {
+                calls: Mutex::new(0),
+            }),
+            &path,
+        )
+        .unwrap();
+
+        // Read only the first chunk, then abandon the stream
+        let mut stream = recorder
+            .generate_content(request("counting-llm", "hello"), true)
+            .await;
+        stream.next().await.unwrap().unwrap();
+        drop(stream);
+
+        let mut replay = ReplayProvider::from_file(&path).unwrap();
+        for _ in 0..100 {
+            if replay.remaining() == 1 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            replay = ReplayProvider::from_file(&path).unwrap();
+        }
+        assert_eq!(replay.remaining(), 1);
+
+        let replayed = texts(&replay, request("counting-llm", "hello")).await;
+        assert_eq!(replayed.len(), 1);
+        assert_eq!(replayed[0].as_ref().unwrap(), "reply 1 ");
+    }
+
+    #[tokio::test]
+    async fn test_replay_errors_on_unmatched_request() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.jsonl");
+
+        let recorder = RecordingProvider::new(
+            Arc::new(CountingLLM {
+                calls: Mutex::new(0),
+            }),
+            &path,
+        )
+        .unwrap();
+        texts(&recorder, request("counting-llm", "hello")).await;
+
+        let replay = ReplayProvider::from_file(&path).unwrap();
+        let unmatched = texts(&replay, request("counting-llm", "something else")).await;
+        assert_eq!(unmatched.len(), 1);
+        assert!(matches!(unmatched[0], Err(Error::LLMError(_))));
+
+        // A recorded call is only served once
+        texts(&replay, request("counting-llm", "hello")).await;
+        let exhausted = texts(&replay, request("counting-llm", "hello")).await;
+        assert!(exhausted[0].is_err());
+    }
+}
@@ -0,0 +1,162 @@
+//! Hard cap on the size of generated responses
+//!
+//! A safety backstop independent of the model's `max_tokens`: a misbehaving
+//! provider or runaway stream is cut off once the accumulated text output
+//! exceeds a byte limit.
+
+use crate::{FinishReason, LLMResponse, Part, Result};
+use async_stream::stream;
+use futures::stream::{Stream, StreamExt};
+
+/// Default maximum response size (4 MiB of text)
+pub const DEFAULT_MAX_RESPONSE_BYTES: usize = 4 * 1024 * 1024;
+
+/// Finish reason and error code used when a response is cut off
+pub const TRUNCATED: &str = "TRUNCATED";
+
+/// Wrap a response stream so it ends once `max_bytes` of text have been produced
+///
+/// The response that crosses the limit is trimmed to fit (on a UTF-8 boundary),
+/// then a final response with `finish_reason` and `error_code` set to
+/// [`TRUNCATED`] is emitted and the underlying stream is dropped.
+pub fn limit_response_size(
+    mut inner: Box<dyn Stream<Item = Result<LLMResponse>> + Send + Unpin>,
+    max_bytes: usize,
+) -> Box<dyn Stream<Item = Result<LLMResponse>> + Send + Unpin> {
+    Box::new(Box::pin(stream! {
+        let mut produced = 0usize;
+
+        while let Some(item) = inner.next().await {
+            let mut response = match item {
+                Ok(response) => response,
+                Err(e) => {
+                    yield Err(e);
+                    continue;
+                }
+            };
+
+            let mut exceeded = false;
+            if let Some(ref mut content) = response.content {
+                for part in content.parts.iter_mut() {
+                    if let Part::Text { text } = part {
+                        let remaining = max_bytes.saturating_sub(produced);
+                        if text.len() > remaining {
+                            let mut cut = remaining;
+                            while !text.is_char_boundary(cut) {
+                                cut -= 1;
+                            }
+                            text.truncate(cut);
+                            exceeded = true;
+                        }
+                        produced += text.len();
+                    }
+                }
+            }
+
+            if !exceeded {
+                yield Ok(response);
+                continue;
+            }
+
+            tracing::warn!(max_bytes, "Response exceeded size limit, truncating stream");
+
+            if response.content.as_ref().is_some_and(|c| {
+                c.parts.iter().any(|p| !matches!(p, Part::Text { text } if text.is_empty()))
+            }) {
+                response.turn_complete = false;
+                response.finish_reason = None;
+                yield Ok(response);
+            }
+
+            yield Ok(LLMResponse {
+                content: None,
+                partial: false,
+                turn_complete: true,
+                interrupted: false,
+                finish_reason: Some(FinishReason::Other(TRUNCATED.to_string())),
+                error_code: Some(TRUNCATED.to_string()),
+                error_message: Some(format!("Response exceeded {} bytes", max_bytes)),
+                usage: None,
+            });
+            return;
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Content;
+
+    fn text_chunk(text: &str) -> Result<LLMResponse> {
+        Ok(LLMResponse {
+            content: Some(Content::new_model_text(text)),
+            partial: true,
+            turn_complete: false,
+            interrupted: false,
+            finish_reason: None,
+            error_code: None,
+            error_message: None,
+            usage: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_stream_cut_off_at_limit() {
+        let chunks: Vec<_> = (0..100).map(|_| text_chunk("0123456789")).collect();
+        let inner = Box::new(futures::stream::iter(chunks));
+
+        let mut limited = limit_response_size(inner, 25);
+        let mut responses = Vec::new();
+        while let Some(item) = limited.next().await {
+            responses.push(item.unwrap());
+        }
+
+        let text: String = responses
+            .iter()
+            .filter_map(|r| r.content.as_ref())
+            .flat_map(|c| c.parts.iter())
+            .filter_map(|p| match p {
+                Part::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(text.len(), 25);
+
+        let last = responses.last().unwrap();
+        assert!(last.turn_complete);
+        assert_eq!(
+            last.finish_reason,
+            Some(FinishReason::Other(TRUNCATED.to_string()))
+        );
+        assert_eq!(last.error_code.as_deref(), Some(TRUNCATED));
+        // 2 full chunks, 1 trimmed chunk, then the truncation marker
+        assert_eq!(responses.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_stream_under_limit_untouched() {
+        let chunks = vec![text_chunk("hello"), text_chunk("world")];
+        let inner = Box::new(futures::stream::iter(chunks));
+
+        let mut limited = limit_response_size(inner, DEFAULT_MAX_RESPONSE_BYTES);
+        let mut count = 0;
+        while let Some(item) = limited.next().await {
+            assert!(item.unwrap().finish_reason.is_none());
+            count += 1;
+        }
+        assert_eq!(count, 2);
+    }
+
+    #[tokio::test]
+    async fn test_truncation_respects_char_boundaries() {
+        let inner = Box::new(futures::stream::iter(vec![text_chunk("héllo")]));
+
+        let mut limited = limit_response_size(inner, 2);
+        let first = limited.next().await.unwrap().unwrap();
+        match &first.content.unwrap().parts[0] {
+            Part::Text { text } => assert_eq!(text, "h"),
+            _ => panic!("Expected text part"),
+        }
+    }
+}
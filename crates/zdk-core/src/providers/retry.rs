@@ -0,0 +1,278 @@
+//! Retrying transient model failures at the transport layer
+//!
+//! [`RetryingLLM`] wraps any model and repeats a call that fails with a
+//! transient error before producing anything, so every caller gets the same
+//! retry behavior regardless of which agent or tool issues the request.
+
+use crate::{Error, LLM, LLMRequest, LLMResponse, Result};
+use async_stream::stream;
+use async_trait::async_trait;
+use futures::stream::{Stream, StreamExt};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Initial delay before retrying a failed LLM call; doubles on every attempt
+pub const DEFAULT_RETRY_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Longest delay between two retries, however many attempts came before
+pub const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Delay before retry number `attempt + 1`: `backoff` doubled per earlier attempt
+///
+/// Saturates instead of overflowing and never exceeds [`MAX_RETRY_DELAY`].
+pub fn retry_delay(backoff: Duration, attempt: u32) -> Duration {
+    backoff
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(MAX_RETRY_DELAY)
+}
+
+/// Check whether an LLM error is transient and worth retrying
+///
+/// Providers surface HTTP failures as `Error::LLMError` with the status code in
+/// the message (e.g. `"OpenAI API error 429 ..."` or `"... (code: 503)"`), so
+/// this looks for 429, 500, 502 or 503 as a standalone number in the text.
+pub fn is_retryable_llm_error(error: &Error) -> bool {
+    match error {
+        Error::LLMError(message) => message
+            .split(|c: char| !c.is_ascii_digit())
+            .any(|code| matches!(code, "429" | "500" | "502" | "503")),
+        _ => false,
+    }
+}
+
+/// Decorator that retries calls to the wrapped model on transient errors
+///
+/// A call is retried only while it hasn't yielded a response yet: once a
+/// chunk has been passed on, a later error is forwarded as-is so callers never
+/// see output from two attempts. Delays start at the backoff and double on
+/// every attempt.
+pub struct RetryingLLM {
+    inner: Arc<dyn LLM>,
+    max_retries: u32,
+    backoff: Duration,
+}
+
+impl RetryingLLM {
+    /// Retry calls to `inner` up to `max_retries` times
+    pub fn new(inner: Arc<dyn LLM>, max_retries: u32) -> Self {
+        Self {
+            inner,
+            max_retries,
+            backoff: DEFAULT_RETRY_BACKOFF,
+        }
+    }
+
+    /// Wait `backoff` before the first retry instead of the default
+    pub fn with_backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+}
+
+#[async_trait]
+impl LLM for RetryingLLM {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn capabilities(&self) -> Option<Vec<crate::Capability>> {
+        self.inner.capabilities()
+    }
+
+    fn context_window(&self) -> Option<usize> {
+        self.inner.context_window()
+    }
+
+    async fn generate_content(
+        &self,
+        request: LLMRequest,
+        stream: bool,
+    ) -> Box<dyn Stream<Item = Result<LLMResponse>> + Send + Unpin> {
+        let inner = self.inner.clone();
+        let max_retries = self.max_retries;
+        let backoff = self.backoff;
+
+        Box::new(Box::pin(stream! {
+            let mut attempt = 0;
+
+            'attempt: loop {
+                let mut inner_stream = inner.generate_content(request.clone(), stream).await;
+                let mut started = false;
+
+                while let Some(item) = inner_stream.next().await {
+                    match item {
+                        Err(e) if !started && attempt < max_retries && is_retryable_llm_error(&e) => {
+                            let delay = retry_delay(backoff, attempt);
+                            attempt += 1;
+                            tracing::warn!(
+                                error = %e,
+                                model = %request.model,
+                                attempt = attempt,
+                                delay_ms = delay.as_millis() as u64,
+                                "Retryable LLM error, retrying"
+                            );
+                            tokio::time::sleep(delay).await;
+                            continue 'attempt;
+                        }
+                        item => {
+                            started |= item.is_ok();
+                            yield item;
+                        }
+                    }
+                }
+
+                return;
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Content, FinishReason};
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn test_retry_delay_is_capped() {
+        let backoff = Duration::from_millis(500);
+        assert_eq!(retry_delay(backoff, 0), backoff);
+        assert_eq!(retry_delay(backoff, 3), Duration::from_secs(4));
+        assert_eq!(retry_delay(backoff, 40), MAX_RETRY_DELAY);
+        assert_eq!(retry_delay(Duration::MAX, u32::MAX), MAX_RETRY_DELAY);
+    }
+
+    /// Fails its first `failures` calls, then streams two chunks
+    ///
+    /// With `fail_mid_stream`, every call streams one chunk before failing.
+    struct FlakyLLM {
+        failures: u32,
+        fail_mid_stream: bool,
+        error: &'static str,
+        calls: AtomicU32,
+    }
+
+    impl FlakyLLM {
+        fn new(failures: u32, error: &'static str) -> Self {
+            Self {
+                failures,
+                fail_mid_stream: false,
+                error,
+                calls: AtomicU32::new(0),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl LLM for FlakyLLM {
+        fn name(&self) -> &str {
+            "flaky-llm"
+        }
+
+        async fn generate_content(
+            &self,
+            _request: LLMRequest,
+            stream: bool,
+        ) -> Box<dyn Stream<Item = Result<LLMResponse>> + Send + Unpin> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            let fail = call < self.failures;
+            let fail_mid_stream = self.fail_mid_stream;
+            let error = self.error;
+
+            Box::new(Box::pin(stream! {
+                if fail && !fail_mid_stream {
+                    yield Err(Error::LLMError(error.to_string()));
+                    return;
+                }
+                if stream {
+                    yield Ok(chunk("Hello ", true));
+                }
+                if fail {
+                    yield Err(Error::LLMError(error.to_string()));
+                    return;
+                }
+                yield Ok(chunk(if stream { "world" } else { "Hello world" }, false));
+            }))
+        }
+    }
+
+    fn chunk(text: &str, partial: bool) -> LLMResponse {
+        LLMResponse {
+            content: Some(Content::new_model_text(text)),
+            partial,
+            turn_complete: !partial,
+            interrupted: false,
+            finish_reason: (!partial).then_some(FinishReason::Stop),
+            error_code: None,
+            error_message: None,
+            usage: None,
+        }
+    }
+
+    fn request() -> LLMRequest {
+        LLMRequest {
+            model: "flaky-llm".to_string(),
+            system_instruction: None,
+            contents: vec![Content::new_user_text("Hi")],
+            config: None,
+            tools: Vec::new(),
+        }
+    }
+
+    async fn texts(llm: &dyn LLM, stream: bool) -> Vec<Result<String>> {
+        llm.generate_content(request(), stream)
+            .await
+            .map(|item| item.map(|r| r.content.map(|c| c.text()).unwrap_or_default()))
+            .collect()
+            .await
+    }
+
+    fn retrying(inner: Arc<FlakyLLM>, max_retries: u32) -> RetryingLLM {
+        RetryingLLM::new(inner, max_retries).with_backoff(Duration::from_millis(1))
+    }
+
+    #[tokio::test]
+    async fn test_retries_until_success() {
+        for stream in [false, true] {
+            let flaky = Arc::new(FlakyLLM::new(2, "OpenAI API error 503 Service Unavailable"));
+            let items = texts(&retrying(flaky.clone(), 3), stream).await;
+
+            assert_eq!(flaky.calls.load(Ordering::SeqCst), 3);
+            let texts: Vec<String> = items.into_iter().map(|item| item.unwrap()).collect();
+            assert_eq!(texts.concat(), "Hello world");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_retries() {
+        let flaky = Arc::new(FlakyLLM::new(5, "Gemini API error: overloaded (code: 429)"));
+        let items = texts(&retrying(flaky.clone(), 2), true).await;
+
+        assert_eq!(flaky.calls.load(Ordering::SeqCst), 3);
+        assert_eq!(items.len(), 1);
+        assert!(items[0].as_ref().unwrap_err().to_string().contains("429"));
+    }
+
+    #[tokio::test]
+    async fn test_non_retryable_error_not_retried() {
+        let flaky = Arc::new(FlakyLLM::new(1, "OpenAI API error 400 Bad Request"));
+        let items = texts(&retrying(flaky.clone(), 3), true).await;
+
+        assert_eq!(flaky.calls.load(Ordering::SeqCst), 1);
+        assert!(items[0].is_err());
+    }
+
+    #[tokio::test]
+    async fn test_no_retry_after_first_chunk() {
+        let flaky = Arc::new(FlakyLLM {
+            fail_mid_stream: true,
+            ..FlakyLLM::new(1, "OpenAI API error 502 Bad Gateway")
+        });
+        let items = texts(&retrying(flaky.clone(), 3), true).await;
+
+        assert_eq!(flaky.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].as_ref().unwrap(), "Hello ");
+        assert!(items[1].is_err());
+    }
+}
@@ -0,0 +1,87 @@
+//! Line buffer for server-sent event streams
+//!
+//! Network chunks split SSE lines at arbitrary byte offsets, including inside a
+//! multi-byte character, so bytes are held until a whole line has arrived and
+//! only then decoded.
+
+use crate::Result;
+
+/// Cap on buffered bytes without a complete line (16 MiB)
+pub(crate) const MAX_SSE_LINE_BYTES: usize = 16 * 1024 * 1024;
+
+/// Buffers raw stream bytes and yields one `data:` payload at a time
+pub(crate) struct SseBuffer {
+    buffer: Vec<u8>,
+    max_buffer_bytes: usize,
+}
+
+impl SseBuffer {
+    /// Errors once `max_buffer_bytes` are buffered without a newline, so a
+    /// malformed or adversarial stream cannot grow the buffer without bound
+    pub(crate) fn new(max_buffer_bytes: usize) -> Self {
+        Self {
+            buffer: Vec::new(),
+            max_buffer_bytes,
+        }
+    }
+
+    /// Append a chunk as received from the network
+    pub(crate) fn push(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Take the payload of the next complete `data:` line, or `None` until
+    /// more bytes arrive
+    ///
+    /// Other SSE fields (`event:`, comments, blank lines) are skipped.
+    pub(crate) fn next_data(&mut self) -> Result<Option<String>> {
+        while let Some(newline) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=newline).collect();
+            let line = String::from_utf8(line).map_err(|e| {
+                crate::Error::LLMError(format!("Stream contained invalid UTF-8: {}", e))
+            })?;
+            if let Some(data) = line.trim_end().strip_prefix("data:") {
+                return Ok(Some(data.trim_start().to_string()));
+            }
+        }
+
+        if self.buffer.len() > self.max_buffer_bytes {
+            return Err(crate::Error::LLMError(format!(
+                "Stream buffer exceeded {} bytes without a complete line",
+                self.max_buffer_bytes
+            )));
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multibyte_character_split_across_chunks() {
+        let body = "event: delta\ndata: {\"text\":\"caf\u{e9} \u{1f600}\"}\n\n".as_bytes();
+        let mut sse = SseBuffer::new(MAX_SSE_LINE_BYTES);
+        let mut payloads = Vec::new();
+        // Single bytes split both characters between chunks
+        for byte in body {
+            sse.push(std::slice::from_ref(byte));
+            while let Some(data) = sse.next_data().unwrap() {
+                payloads.push(data);
+            }
+        }
+
+        assert_eq!(payloads, vec!["{\"text\":\"caf\u{e9} \u{1f600}\"}"]);
+    }
+
+    #[test]
+    fn test_overlong_line_errors() {
+        let mut sse = SseBuffer::new(16);
+        sse.push(b"data: 0123456789");
+        assert!(sse.next_data().unwrap().is_none());
+
+        sse.push(b"0123456789");
+        assert!(sse.next_data().is_err());
+    }
+}
@@ -0,0 +1,65 @@
+//! Per-invocation run configuration
+
+use crate::GenerateConfig;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+/// Configuration for a single agent invocation
+///
+/// Passed to `Runner::run` and exposed to agents through
+/// [`InvocationContext::run_config`](crate::InvocationContext::run_config).
+#[derive(Debug, Clone)]
+pub struct RunConfig {
+    /// Whether the response should be streamed
+    pub streaming: bool,
+    /// Whether tool calls requested by the model are executed
+    ///
+    /// When false, agents surface the model's function calls but skip execution
+    /// (plan-only / observe mode).
+    pub execute_tools: bool,
+    /// Token used to stop the invocation early
+    ///
+    /// When cancelled, the runner ends the event stream with a final
+    /// `interrupted` event.
+    pub cancellation_token: Option<CancellationToken>,
+    /// Maximum time to wait for the next event before giving up
+    ///
+    /// Acts as an idle timeout: the clock resets every time an event is
+    /// produced. On expiry the runner emits a `TIMEOUT` error event and stops.
+    pub timeout: Option<Duration>,
+    /// Number of memories from the user's past sessions to add as context
+    ///
+    /// When non-zero and the runner has a memory service, the runner searches
+    /// it for the incoming message and places the top results ahead of the
+    /// conversation history. `0` disables the lookup.
+    pub include_memories: usize,
+}
+
+impl Default for RunConfig {
+    fn default() -> Self {
+        Self {
+            streaming: false,
+            execute_tools: true,
+            cancellation_token: None,
+            timeout: None,
+            include_memories: 0,
+        }
+    }
+}
+
+/// Defaults applied to every agent run through a runner
+///
+/// Set once on the runner to enforce app-wide policy and exposed to agents
+/// through [`InvocationContext::runner_defaults`](crate::InvocationContext::runner_defaults).
+/// Agents fall back to these only for settings they don't configure themselves.
+#[derive(Debug, Clone, Default)]
+pub struct RunnerDefaults {
+    /// Generation parameters for model requests
+    pub generate_config: Option<GenerateConfig>,
+    /// Maximum number of model calls per invocation
+    pub max_tool_iterations: Option<usize>,
+    /// Maximum time a single tool call may run
+    pub tool_timeout: Option<Duration>,
+    /// Maximum size of a tool response passed back to the model, in bytes
+    pub max_tool_response_bytes: Option<usize>,
+}
@@ -0,0 +1,21 @@
+//! Read access to the session of one invocation
+
+use crate::{Event, Result};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Session storage scoped to the app, user and session of one invocation
+///
+/// Implemented on top of a session service by `zdk-session` and handed to
+/// agents through their [`InvocationContext`](crate::InvocationContext).
+/// Unlike [`InvocationContext::state`](crate::InvocationContext::state), reads
+/// go to the service and include events appended during the invocation.
+#[async_trait]
+pub trait SessionStore: Send + Sync {
+    /// All events of the session, in chronological order
+    async fn events(&self) -> Result<Vec<Event>>;
+
+    /// The session's current state
+    async fn state(&self) -> Result<HashMap<String, Value>>;
+}
@@ -1,6 +1,7 @@
 use super::{Content, Event, InvocationContext, Result, ToolContext};
 use async_trait::async_trait;
 use futures::stream::Stream;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 /// Agent trait - the core abstraction for all agents
@@ -33,6 +34,21 @@ pub trait LLM: Send + Sync {
     /// Returns the name of the model
     fn name(&self) -> &str;
 
+    /// Capabilities of the configured model, if known
+    ///
+    /// `None` means the model doesn't report them and capability checks are
+    /// skipped, which is the default for custom implementations.
+    fn capabilities(&self) -> Option<Vec<crate::Capability>> {
+        None
+    }
+
+    /// Context window of the configured model in tokens, if known
+    ///
+    /// Used to trim long conversations before they are sent. Defaults to `None`.
+    fn context_window(&self) -> Option<usize> {
+        None
+    }
+
     /// Generates content based on the request
     async fn generate_content(
         &self,
@@ -106,6 +122,8 @@ pub trait Toolset: Send + Sync {
 #[derive(Clone)]
 pub struct LLMRequest {
     pub model: String,
+    /// System prompt, mapped to each provider's native format
+    pub system_instruction: Option<String>,
     pub contents: Vec<Content>,
     pub config: Option<GenerateConfig>,
     pub tools: Vec<Arc<dyn Tool>>,
@@ -115,6 +133,7 @@ impl std::fmt::Debug for LLMRequest {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("LLMRequest")
             .field("model", &self.model)
+            .field("system_instruction", &self.system_instruction)
             .field("contents", &self.contents)
             .field("config", &self.config)
             .field("tools_count", &self.tools.len())
@@ -123,28 +142,194 @@ impl std::fmt::Debug for LLMRequest {
 }
 
 /// Response from an LLM
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LLMResponse {
     pub content: Option<Content>,
     pub partial: bool,
     pub turn_complete: bool,
     pub interrupted: bool,
-    pub finish_reason: Option<String>,
+    pub finish_reason: Option<FinishReason>,
     pub error_code: Option<String>,
     pub error_message: Option<String>,
+    /// Token counts reported by the provider, usually on the final response
+    #[serde(default)]
+    pub usage: Option<TokenUsage>,
+}
+
+/// Why the model stopped generating, normalized across providers
+///
+/// Serialized as its raw string, so unknown provider reasons survive a round
+/// trip through `Other`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum FinishReason {
+    /// The model finished its answer or hit a stop sequence
+    Stop,
+    /// The output token limit was reached
+    Length,
+    /// Generation was blocked by a safety or content filter
+    Safety,
+    /// The model stopped to call tools
+    ToolCalls,
+    /// Any other provider-specific reason, as reported
+    Other(String),
+}
+
+impl FinishReason {
+    /// Parse a provider's raw finish reason (Gemini, OpenAI or Anthropic)
+    pub fn parse(raw: &str) -> Self {
+        match raw.to_ascii_lowercase().as_str() {
+            "stop" | "end_turn" | "stop_sequence" => FinishReason::Stop,
+            "length" | "max_tokens" => FinishReason::Length,
+            "safety" | "content_filter" | "refusal" | "recitation" | "blocklist"
+            | "prohibited_content" | "spii" | "image_safety" => FinishReason::Safety,
+            "tool_calls" | "function_call" | "tool_use" => FinishReason::ToolCalls,
+            _ => FinishReason::Other(raw.to_string()),
+        }
+    }
+
+    /// Event error code for reasons that cut an answer short
+    pub fn error_code(&self) -> Option<&'static str> {
+        match self {
+            FinishReason::Safety => Some("SAFETY"),
+            FinishReason::Length => Some("MAX_TOKENS"),
+            _ => None,
+        }
+    }
+}
+
+impl From<String> for FinishReason {
+    fn from(raw: String) -> Self {
+        FinishReason::parse(&raw)
+    }
+}
+
+impl From<FinishReason> for String {
+    fn from(reason: FinishReason) -> Self {
+        match reason {
+            FinishReason::Stop => "stop".to_string(),
+            FinishReason::Length => "length".to_string(),
+            FinishReason::Safety => "safety".to_string(),
+            FinishReason::ToolCalls => "tool_calls".to_string(),
+            FinishReason::Other(raw) => raw,
+        }
+    }
+}
+
+/// Token usage reported for an LLM call
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    pub total_tokens: u32,
 }
 
 /// Tool execution response
-#[derive(Debug, Clone)]
+///
+/// Build it with [`ToolResponse::new`] or [`ToolResponse::error`]; struct
+/// literals should end in `..Default::default()` so new fields don't break them.
+#[derive(Debug, Clone, Default)]
 pub struct ToolResponse {
     pub result: serde_json::Value,
+    /// Set when the tool ran but failed, so callers can tell failure
+    /// categories apart without parsing `result`
+    pub error: Option<ToolError>,
+}
+
+impl ToolResponse {
+    /// A successful response carrying `result`
+    pub fn new(result: serde_json::Value) -> Self {
+        Self {
+            result,
+            error: None,
+        }
+    }
+
+    /// A failed response whose result also describes the error for the model
+    pub fn error(kind: ToolErrorKind, message: impl Into<String>) -> Self {
+        let error = ToolError::new(kind, message);
+        Self {
+            result: serde_json::json!({
+                "error": error.message,
+                "kind": kind,
+            }),
+            error: Some(error),
+        }
+    }
+
+    /// Whether the tool ran but failed
+    pub fn is_error(&self) -> bool {
+        self.error.is_some()
+    }
+}
+
+/// Structured error reported by a tool
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolError {
+    pub kind: ToolErrorKind,
+    pub message: String,
+}
+
+impl ToolError {
+    pub fn new(kind: ToolErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for ToolError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.kind.as_str(), self.message)
+    }
+}
+
+/// Category of a tool failure
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolErrorKind {
+    /// The remote service could not be reached
+    Network,
+    /// The arguments were rejected
+    InvalidInput,
+    /// The requested resource does not exist
+    NotFound,
+    /// The remote service is throttling requests
+    RateLimited,
+    /// Any other failure
+    Internal,
+}
+
+impl ToolErrorKind {
+    /// Snake-case name, matching the serialized form
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ToolErrorKind::Network => "network",
+            ToolErrorKind::InvalidInput => "invalid_input",
+            ToolErrorKind::NotFound => "not_found",
+            ToolErrorKind::RateLimited => "rate_limited",
+            ToolErrorKind::Internal => "internal",
+        }
+    }
+
+    /// Event error code for a tool failing with this kind, e.g. `TOOL_ERROR_NOT_FOUND`
+    pub fn error_code(&self) -> String {
+        format!("TOOL_ERROR_{}", self.as_str().to_uppercase())
+    }
 }
 
 /// Generation configuration
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct GenerateConfig {
     pub temperature: Option<f32>,
     pub max_tokens: Option<u32>,
     pub top_p: Option<f32>,
     pub top_k: Option<u32>,
+    /// MIME type the model should answer with, e.g. `application/json`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_mime_type: Option<String>,
+    /// JSON schema the response must conform to; implies a JSON response
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_schema: Option<serde_json::Value>,
 }
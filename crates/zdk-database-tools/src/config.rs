@@ -2,6 +2,9 @@
 
 use std::collections::HashSet;
 
+/// Placeholder substituted for values in redacted columns
+pub const REDACTED: &str = "[REDACTED]";
+
 /// SQL operations that can be performed
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum SqlOperation {
@@ -27,6 +30,10 @@ pub struct DatabaseToolConfig {
     pub timeout_secs: u64,
     /// Allowed SQL operations
     pub allowed_operations: HashSet<SqlOperation>,
+    /// Columns whose values are replaced with `"[REDACTED]"` in query results
+    /// (matched case-insensitively by name). Queries that name them, or could
+    /// return them under another name, are rejected, so only `*` can return them.
+    pub redacted_columns: HashSet<String>,
 }
 
 impl Default for DatabaseToolConfig {
@@ -39,6 +46,7 @@ impl Default for DatabaseToolConfig {
             max_rows: 1000,
             timeout_secs: 30,
             allowed_operations,
+            redacted_columns: HashSet::new(),
         }
     }
 }
@@ -67,4 +75,11 @@ impl DatabaseToolConfig {
         config.allowed_operations.insert(SqlOperation::DropIndex);
         config
     }
+
+    /// Check whether a result column should be redacted
+    pub fn is_redacted(&self, column: &str) -> bool {
+        self.redacted_columns
+            .iter()
+            .any(|redacted| redacted.eq_ignore_ascii_case(column))
+    }
 }
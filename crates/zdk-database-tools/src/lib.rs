@@ -2,14 +2,21 @@
 //!
 //! This crate provides database interaction tools for PostgreSQL and SQLite,
 //! with security features like read-only mode, parameter binding, and query limits.
+//! MySQL tools are available with the `mysql` feature.
 
 pub mod config;
+#[cfg(feature = "mysql")]
+pub mod mysql;
 pub mod postgres;
 pub mod sqlite;
 pub mod types;
+pub mod validation;
 
 // Re-exports
 pub use config::{DatabaseToolConfig, SqlOperation};
+#[cfg(feature = "mysql")]
+pub use mysql::{create_mysql_tools, create_mysql_tools_with_config};
 pub use postgres::{create_postgres_tools, create_postgres_tools_with_config};
 pub use sqlite::{create_sqlite_tools, create_sqlite_tools_with_config};
 pub use types::{ColumnInfo, ConstraintInfo, IndexInfo, TableInfo, TableSchema};
+pub use validation::{validate_read_only_query, Dialect};
@@ -1,8 +1,11 @@
 //! PostgreSQL database tools
 
-use crate::config::DatabaseToolConfig;
+use crate::config::{DatabaseToolConfig, REDACTED};
 use crate::types::{ColumnInfo, TableInfo, TableSchema};
-use sqlx::{postgres::PgPoolOptions, Column, Pool, Postgres, Row};
+use crate::validation::{reject_redacted_references, validate_read_only_query, Dialect};
+use sqlx::postgres::{PgArguments, PgPoolOptions};
+use sqlx::query::Query;
+use sqlx::{Column, Pool, Postgres, Row};
 use std::sync::Arc;
 use std::time::Duration;
 use zdk_core::{Error as ZError, Result as ZResult, Tool, ToolResponse};
@@ -96,11 +99,9 @@ fn create_list_tables_tool(pool: Pool<Postgres>) -> ZResult<FunctionTool> {
                     })
                     .collect();
 
-                Ok(ToolResponse {
-                    result: serde_json::to_value(&tables).map_err(|e| {
-                        ZError::Other(anyhow::anyhow!("Serialization error: {}", e))
-                    })?,
-                })
+                Ok(ToolResponse::new(serde_json::to_value(&tables).map_err(
+                    |e| ZError::Other(anyhow::anyhow!("Serialization error: {}", e)),
+                )?))
             }
         })
         .build()
@@ -171,11 +172,11 @@ fn create_describe_table_tool(pool: Pool<Postgres>) -> ZResult<FunctionTool> {
                     constraints: Vec::new(), // Would need additional queries
                 };
 
-                Ok(ToolResponse {
-                    result: serde_json::to_value(&table_schema).map_err(|e| {
+                Ok(ToolResponse::new(
+                    serde_json::to_value(&table_schema).map_err(|e| {
                         ZError::Other(anyhow::anyhow!("Serialization error: {}", e))
                     })?,
-                })
+                ))
             }
         })
         .build()
@@ -184,7 +185,23 @@ fn create_describe_table_tool(pool: Pool<Postgres>) -> ZResult<FunctionTool> {
 /// Create a tool to execute SELECT queries
 fn create_query_tool(pool: Pool<Postgres>, config: DatabaseToolConfig) -> ZResult<FunctionTool> {
     let schema = ToolSchema::new()
-        .property("sql", "string", "SQL SELECT query to execute")
+        .property(
+            "sql",
+            "string",
+            "SQL SELECT query to execute, using $1, $2, ... for values",
+        )
+        .array_property(
+            "params",
+            serde_json::json!({
+                "anyOf": [
+                    {"type": "string"},
+                    {"type": "number"},
+                    {"type": "boolean"},
+                    {"type": "null"}
+                ]
+            }),
+            "Values bound to the $1, $2, ... placeholders, in order",
+        )
         .required("sql")
         .build();
 
@@ -194,35 +211,64 @@ fn create_query_tool(pool: Pool<Postgres>, config: DatabaseToolConfig) -> ZResul
         .schema(schema)
         .execute(move |ctx, params| {
             let pool = pool.clone();
-            let max_rows = config.max_rows;
+            let config = config.clone();
             async move {
                 let sql = params["sql"]
                     .as_str()
                     .ok_or_else(|| ZError::Other(anyhow::anyhow!("Missing sql parameter")))?;
+                let query_params = match &params["params"] {
+                    serde_json::Value::Null => Vec::new(),
+                    serde_json::Value::Array(values) => values.clone(),
+                    _ => {
+                        return Err(ZError::Other(anyhow::anyhow!(
+                            "params must be an array of values"
+                        )))
+                    }
+                };
 
-                // Basic SQL validation - ensure it's a SELECT query
-                let sql_upper = sql.trim().to_uppercase();
-                if !sql_upper.starts_with("SELECT") {
+                // Reject anything but a single SELECT, including hidden writes
+                let sql = validate_read_only_query(sql, Dialect::Postgres)?;
+                reject_redacted_references(&sql, Dialect::Postgres, &config)?;
+                let sql_upper = sql.to_uppercase();
+
+                let placeholders = count_placeholders(&sql);
+                if placeholders != query_params.len() {
                     return Err(ZError::Other(anyhow::anyhow!(
-                        "Only SELECT queries are allowed in read-only mode"
+                        "Query uses {} placeholder(s) but {} param(s) were provided",
+                        placeholders,
+                        query_params.len()
                     )));
                 }
 
                 tracing::debug!(
                     invocation_id = %ctx.invocation_id(),
                     sql = %sql,
+                    params = query_params.len(),
                     "Executing PostgreSQL query"
                 );
 
                 // Add LIMIT if not present
                 let final_sql = if !sql_upper.contains("LIMIT") {
-                    format!("{} LIMIT {}", sql, max_rows)
+                    format!("{} LIMIT {}", sql, config.max_rows)
                 } else {
                     sql.to_string()
                 };
 
-                let rows = sqlx::query(&final_sql)
-                    .fetch_all(&pool)
+                let mut query = sqlx::query(&final_sql);
+                for value in &query_params {
+                    query = bind_param(query, value)?;
+                }
+
+                // The database enforces read-only too, not just the validator
+                let mut tx = pool
+                    .begin_with("BEGIN READ ONLY")
+                    .await
+                    .map_err(|e| ZError::Other(anyhow::anyhow!("Query failed: {}", e)))?;
+                let rows = query
+                    .fetch_all(&mut *tx)
+                    .await
+                    .map_err(|e| ZError::Other(anyhow::anyhow!("Query failed: {}", e)))?;
+                tx.rollback()
                     .await
                     .map_err(|e| ZError::Other(anyhow::anyhow!("Query failed: {}", e)))?;
 
@@ -232,24 +278,24 @@ fn create_query_tool(pool: Pool<Postgres>, config: DatabaseToolConfig) -> ZResul
                     .map(|row| {
                         let mut map = serde_json::Map::new();
                         for (i, column) in row.columns().iter().enumerate() {
-                            let value: Option<String> = row.try_get(i).ok();
-                            map.insert(
-                                column.name().to_string(),
-                                value
+                            let value = if config.is_redacted(column.name()) {
+                                serde_json::Value::String(REDACTED.to_string())
+                            } else {
+                                row.try_get::<String, _>(i)
+                                    .ok()
                                     .map(serde_json::Value::String)
-                                    .unwrap_or(serde_json::Value::Null),
-                            );
+                                    .unwrap_or(serde_json::Value::Null)
+                            };
+                            map.insert(column.name().to_string(), value);
                         }
                         serde_json::Value::Object(map)
                     })
                     .collect();
 
-                Ok(ToolResponse {
-                    result: serde_json::json!({
-                        "rows": result,
-                        "row_count": result.len(),
-                    }),
-                })
+                Ok(ToolResponse::new(serde_json::json!({
+                    "rows": result,
+                    "row_count": result.len(),
+                })))
             }
         })
         .build()
@@ -300,16 +346,66 @@ fn create_execute_tool(pool: Pool<Postgres>, _config: DatabaseToolConfig) -> ZRe
                     .await
                     .map_err(|e| ZError::Other(anyhow::anyhow!("Execute failed: {}", e)))?;
 
-                Ok(ToolResponse {
-                    result: serde_json::json!({
-                        "rows_affected": result.rows_affected(),
-                    }),
-                })
+                Ok(ToolResponse::new(serde_json::json!({
+                    "rows_affected": result.rows_affected(),
+                })))
             }
         })
         .build()
 }
 
+/// Highest `$N` placeholder in `sql`, ignoring quoted literals and identifiers
+fn count_placeholders(sql: &str) -> usize {
+    let mut max = 0;
+    let mut quote = None;
+    let mut chars = sql.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '$') => {
+                let mut digits = String::new();
+                while let Some(d) = chars.peek().filter(|d| d.is_ascii_digit()) {
+                    digits.push(*d);
+                    chars.next();
+                }
+                if let Ok(n) = digits.parse::<usize>() {
+                    max = max.max(n);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    max
+}
+
+/// Bind a JSON value with the closest matching SQL type
+fn bind_param<'q>(
+    query: Query<'q, Postgres, PgArguments>,
+    value: &serde_json::Value,
+) -> ZResult<Query<'q, Postgres, PgArguments>> {
+    use serde_json::Value;
+
+    Ok(match value {
+        Value::Null => query.bind(None::<String>),
+        Value::Bool(b) => query.bind(*b),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => query.bind(i),
+            None => query.bind(n.as_f64()),
+        },
+        Value::String(s) => query.bind(s.clone()),
+        Value::Array(_) | Value::Object(_) => {
+            return Err(ZError::Other(anyhow::anyhow!(
+            "Unsupported query parameter {}: only strings, numbers, booleans and null can be bound",
+            value
+        )))
+        }
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -321,4 +417,62 @@ mod tests {
         assert_eq!(config.max_rows, 1000);
         assert_eq!(config.timeout_secs, 30);
     }
+
+    #[test]
+    fn test_count_placeholders() {
+        assert_eq!(count_placeholders("SELECT * FROM users"), 0);
+        assert_eq!(
+            count_placeholders("SELECT * FROM users WHERE name = $1 AND age > $2"),
+            2
+        );
+        // Reused placeholders count once; quoted text is not a placeholder
+        assert_eq!(
+            count_placeholders("SELECT '$3', \"$4\" FROM t WHERE a = $1 OR b = $1"),
+            1
+        );
+    }
+
+    /// Runs against a real server, e.g. `DATABASE_URL=postgres://localhost/test`
+    #[tokio::test]
+    async fn test_parameterized_query() {
+        let Ok(url) = std::env::var("DATABASE_URL") else {
+            eprintln!("DATABASE_URL not set, skipping PostgreSQL integration test");
+            return;
+        };
+
+        let tools = create_postgres_tools(&url).await.unwrap();
+        let query_tool = tools
+            .iter()
+            .find(|tool| tool.name() == "postgres_query")
+            .unwrap();
+        let ctx = || {
+            Arc::new(zdk_tool::DefaultToolContext::new(
+                "call-1".to_string(),
+                "inv-1".to_string(),
+            ))
+        };
+        let sql = "SELECT name FROM (VALUES ('alice', 30), ('bob', 25), ('carol', 41)) \
+                   AS users(name, age) WHERE name <> $1 AND age > $2 ORDER BY name";
+
+        let response = query_tool
+            .execute(
+                ctx(),
+                serde_json::json!({ "sql": sql, "params": ["alice", 26] }),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.result["row_count"], 1);
+        assert_eq!(response.result["rows"][0]["name"], "carol");
+
+        let mismatch = query_tool
+            .execute(
+                ctx(),
+                serde_json::json!({ "sql": sql, "params": ["alice"] }),
+            )
+            .await;
+        assert!(mismatch
+            .unwrap_err()
+            .to_string()
+            .contains("2 placeholder(s) but 1 param(s)"));
+    }
 }
@@ -1,7 +1,8 @@
 //! SQLite database tools
 
-use crate::config::DatabaseToolConfig;
+use crate::config::{DatabaseToolConfig, REDACTED};
 use crate::types::{ColumnInfo, TableInfo, TableSchema};
+use crate::validation::{reject_redacted_references, validate_read_only_query, Dialect};
 use sqlx::{sqlite::SqlitePoolOptions, Column, Pool, Row, Sqlite};
 use std::sync::Arc;
 use std::time::Duration;
@@ -82,11 +83,9 @@ fn create_list_tables_tool(pool: Pool<Sqlite>) -> ZResult<FunctionTool> {
                     })
                     .collect();
 
-                Ok(ToolResponse {
-                    result: serde_json::to_value(&tables).map_err(|e| {
-                        ZError::Other(anyhow::anyhow!("Serialization error: {}", e))
-                    })?,
-                })
+                Ok(ToolResponse::new(serde_json::to_value(&tables).map_err(
+                    |e| ZError::Other(anyhow::anyhow!("Serialization error: {}", e)),
+                )?))
             }
         })
         .build()
@@ -140,11 +139,11 @@ fn create_describe_table_tool(pool: Pool<Sqlite>) -> ZResult<FunctionTool> {
                     constraints: Vec::new(), // Would need additional queries
                 };
 
-                Ok(ToolResponse {
-                    result: serde_json::to_value(&table_schema).map_err(|e| {
+                Ok(ToolResponse::new(
+                    serde_json::to_value(&table_schema).map_err(|e| {
                         ZError::Other(anyhow::anyhow!("Serialization error: {}", e))
                     })?,
-                })
+                ))
             }
         })
         .build()
@@ -163,19 +162,16 @@ fn create_query_tool(pool: Pool<Sqlite>, config: DatabaseToolConfig) -> ZResult<
         .schema(schema)
         .execute(move |ctx, params| {
             let pool = pool.clone();
-            let max_rows = config.max_rows;
+            let config = config.clone();
             async move {
                 let sql = params["sql"]
                     .as_str()
                     .ok_or_else(|| ZError::Other(anyhow::anyhow!("Missing sql parameter")))?;
 
-                // Basic SQL validation - ensure it's a SELECT query
-                let sql_upper = sql.trim().to_uppercase();
-                if !sql_upper.starts_with("SELECT") {
-                    return Err(ZError::Other(anyhow::anyhow!(
-                        "Only SELECT queries are allowed in read-only mode"
-                    )));
-                }
+                // Reject anything but a single SELECT, including hidden writes
+                let sql = validate_read_only_query(sql, Dialect::Sqlite)?;
+                reject_redacted_references(&sql, Dialect::Sqlite, &config)?;
+                let sql_upper = sql.to_uppercase();
 
                 tracing::debug!(
                     invocation_id = %ctx.invocation_id(),
@@ -185,15 +181,32 @@ fn create_query_tool(pool: Pool<Sqlite>, config: DatabaseToolConfig) -> ZResult<
 
                 // Add LIMIT if not present
                 let final_sql = if !sql_upper.contains("LIMIT") {
-                    format!("{} LIMIT {}", sql, max_rows)
+                    format!("{} LIMIT {}", sql, config.max_rows)
                 } else {
                     sql.to_string()
                 };
 
-                let rows = sqlx::query(&final_sql)
-                    .fetch_all(&pool)
+                // SQLite has no read-only transactions, so the connection is
+                // made query-only for the duration of the query instead
+                let mut conn = pool
+                    .acquire()
                     .await
                     .map_err(|e| ZError::Other(anyhow::anyhow!("Query failed: {}", e)))?;
+                sqlx::query("PRAGMA query_only = ON")
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| ZError::Other(anyhow::anyhow!("Query failed: {}", e)))?;
+                let rows = sqlx::query(&final_sql).fetch_all(&mut *conn).await;
+                if sqlx::query("PRAGMA query_only = OFF")
+                    .execute(&mut *conn)
+                    .await
+                    .is_err()
+                {
+                    // Don't hand a read-only connection back to the pool
+                    let _ = conn.detach();
+                }
+                let rows =
+                    rows.map_err(|e| ZError::Other(anyhow::anyhow!("Query failed: {}", e)))?;
 
                 // Convert rows to JSON
                 let result: Vec<serde_json::Value> = rows
@@ -201,24 +214,24 @@ fn create_query_tool(pool: Pool<Sqlite>, config: DatabaseToolConfig) -> ZResult<
                     .map(|row| {
                         let mut map = serde_json::Map::new();
                         for (i, column) in row.columns().iter().enumerate() {
-                            let value: Option<String> = row.try_get(i).ok();
-                            map.insert(
-                                column.name().to_string(),
-                                value
+                            let value = if config.is_redacted(column.name()) {
+                                serde_json::Value::String(REDACTED.to_string())
+                            } else {
+                                row.try_get::<String, _>(i)
+                                    .ok()
                                     .map(serde_json::Value::String)
-                                    .unwrap_or(serde_json::Value::Null),
-                            );
+                                    .unwrap_or(serde_json::Value::Null)
+                            };
+                            map.insert(column.name().to_string(), value);
                         }
                         serde_json::Value::Object(map)
                     })
                     .collect();
 
-                Ok(ToolResponse {
-                    result: serde_json::json!({
-                        "rows": result,
-                        "row_count": result.len(),
-                    }),
-                })
+                Ok(ToolResponse::new(serde_json::json!({
+                    "rows": result,
+                    "row_count": result.len(),
+                })))
             }
         })
         .build()
@@ -269,11 +282,9 @@ fn create_execute_tool(pool: Pool<Sqlite>, _config: DatabaseToolConfig) -> ZResu
                     .await
                     .map_err(|e| ZError::Other(anyhow::anyhow!("Execute failed: {}", e)))?;
 
-                Ok(ToolResponse {
-                    result: serde_json::json!({
-                        "rows_affected": result.rows_affected(),
-                    }),
-                })
+                Ok(ToolResponse::new(serde_json::json!({
+                    "rows_affected": result.rows_affected(),
+                })))
             }
         })
         .build()
@@ -292,4 +303,115 @@ mod tests {
         assert!(!tools.is_empty());
         assert_eq!(tools[0].name(), "sqlite_list_tables");
     }
+
+    #[tokio::test]
+    async fn test_query_redacts_configured_columns() {
+        // A single connection keeps the in-memory database shared
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query("CREATE TABLE users (name TEXT, password_hash TEXT, SSN TEXT)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO users VALUES ('alice', 'hash-a', '123'), ('bob', 'hash-b', NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let mut config = DatabaseToolConfig::default();
+        config.redacted_columns.insert("password_hash".to_string());
+        config.redacted_columns.insert("ssn".to_string());
+        let tool = create_query_tool(pool, config).unwrap();
+
+        let ctx = Arc::new(zdk_tool::DefaultToolContext::new(
+            "call-1".to_string(),
+            "inv-1".to_string(),
+        ));
+        let response = tool
+            .execute(
+                ctx.clone(),
+                serde_json::json!({ "sql": "SELECT * FROM users ORDER BY name" }),
+            )
+            .await
+            .unwrap();
+
+        let rows = response.result["rows"].as_array().unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["name"], "alice");
+        assert_eq!(rows[0]["password_hash"], REDACTED);
+        assert_eq!(rows[0]["SSN"], REDACTED);
+        assert_eq!(rows[1]["name"], "bob");
+        assert_eq!(rows[1]["password_hash"], REDACTED);
+        assert_eq!(rows[1]["SSN"], REDACTED);
+
+        // Aliasing a redacted column can't smuggle its values out
+        let aliased = tool
+            .execute(
+                ctx,
+                serde_json::json!({ "sql": "SELECT password_hash AS p FROM users" }),
+            )
+            .await;
+        assert!(aliased.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_query_rejects_hidden_writes() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query("CREATE TABLE users (name TEXT)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO users VALUES ('alice')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let tool = create_query_tool(pool.clone(), DatabaseToolConfig::default()).unwrap();
+        let ctx = Arc::new(zdk_tool::DefaultToolContext::new(
+            "call-1".to_string(),
+            "inv-1".to_string(),
+        ));
+
+        for sql in [
+            "SELECT * FROM users; DROP TABLE users",
+            "SELECT 1; DELETE FROM users",
+            "/* SELECT */ DELETE FROM users",
+            "-- SELECT\nDROP TABLE users",
+        ] {
+            let result = tool
+                .execute(ctx.clone(), serde_json::json!({ "sql": sql }))
+                .await;
+            assert!(result.is_err(), "query should be rejected: {}", sql);
+        }
+
+        // The table and its row survived every attempt
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+
+        // A commented query still runs, with the LIMIT applied after the comment is removed
+        let response = tool
+            .execute(
+                ctx,
+                serde_json::json!({ "sql": "SELECT name FROM users -- everyone" }),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.result["row_count"], 1);
+
+        // The connection is writable again once the query is done
+        sqlx::query("INSERT INTO users VALUES ('bob')")
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
 }
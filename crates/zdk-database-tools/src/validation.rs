@@ -0,0 +1,893 @@
+//! SQL validation for read-only query tools
+
+use crate::config::DatabaseToolConfig;
+use zdk_core::{Error as ZError, Result as ZResult};
+
+/// Keywords that write data or change the schema. None of them may appear
+/// outside a literal in a read-only query, which also catches writes hidden in
+/// CTEs (`WITH d AS (DELETE ...) SELECT ...`) and `SELECT ... INTO`.
+const WRITE_KEYWORDS: &[&str] = &[
+    "INSERT", "UPDATE", "DELETE", "MERGE", "UPSERT", "DROP", "CREATE", "ALTER", "TRUNCATE",
+    "GRANT", "REVOKE", "INTO", "ATTACH", "DETACH", "PRAGMA", "VACUUM", "COPY", "CALL",
+];
+
+/// SQL dialect, which decides how literals and comments are read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    /// `'...'` strings without escapes, `"..."`, `` `...` `` and `[...]` identifiers
+    Sqlite,
+    /// Backslash escapes only in `E'...'` strings, `$tag$...$tag$` dollar
+    /// quoting and nested block comments
+    Postgres,
+    /// Backslash escapes in `'...'` and `"..."` strings, `#` line comments and
+    /// `/*! ... */` comments, whose contents MySQL runs as code
+    MySql,
+}
+
+/// Validate that `sql` is a single read-only statement
+///
+/// Comments are stripped, then the query is split on semicolons. It is rejected
+/// unless exactly one statement remains, starting with `SELECT` or `WITH` and
+/// free of write keywords. Returns the statement without comments or a
+/// trailing semicolon, ready to have a `LIMIT` appended.
+pub fn validate_read_only_query(sql: &str, dialect: Dialect) -> ZResult<String> {
+    let lexed = lex(sql, dialect)?;
+    let statements = split_statements(&lexed);
+
+    let statement = match statements.as_slice() {
+        [statement] => *statement,
+        [] => return Err(ZError::Other(anyhow::anyhow!("Query is empty"))),
+        _ => {
+            return Err(ZError::Other(anyhow::anyhow!(
+                "Only a single statement is allowed, found {}",
+                statements.len()
+            )))
+        }
+    };
+
+    let words = words_outside_literals(statement);
+    if !matches!(words.first().map(String::as_str), Some("SELECT" | "WITH")) {
+        return Err(ZError::Other(anyhow::anyhow!(
+            "Only SELECT queries are allowed in read-only mode"
+        )));
+    }
+
+    if let Some(keyword) = words
+        .iter()
+        .find(|word| WRITE_KEYWORDS.contains(&word.as_str()))
+    {
+        return Err(ZError::Other(anyhow::anyhow!(
+            "{} is not allowed in read-only mode",
+            keyword
+        )));
+    }
+
+    Ok(statement.iter().map(|(c, _)| c).collect())
+}
+
+/// Reject a query that could return redacted values under another name
+///
+/// Redaction matches result columns by name, so any way of renaming a column
+/// would carry its values out unmasked. When columns are redacted, queries are
+/// rejected if they:
+///
+/// - name a redacted column anywhere, as in `SELECT ssn AS id` or
+///   `WHERE ssn LIKE '1%'`
+/// - rename columns with an alias list, as in `FROM users AS u(a, b, c)` or
+///   `WITH t(a, b) AS (...)`
+/// - combine `*` with `UNION`, `INTERSECT` or `EXCEPT`, whose result columns
+///   take the names of the first query
+/// - on Postgres, use a whole row as a value, as in `row_to_json(u)`,
+///   `u::text` or `f(u.*)`
+///
+/// Redacted columns can still be returned, masked, through `*`.
+pub fn reject_redacted_references(
+    sql: &str,
+    dialect: Dialect,
+    config: &DatabaseToolConfig,
+) -> ZResult<()> {
+    if config.redacted_columns.is_empty() {
+        return Ok(());
+    }
+    let tokens = tokens(&lex(sql, dialect)?);
+
+    if let Some(token) = tokens
+        .iter()
+        .find(|token| token.is_name() && config.is_redacted(&token.text))
+    {
+        return Err(ZError::Other(anyhow::anyhow!(
+            "Column {} is redacted and can't be referenced; select it with * or leave it out",
+            token.text
+        )));
+    }
+
+    let relations = Relations::find(&tokens)?;
+    reject_star_in_set_operations(&tokens)?;
+    if dialect == Dialect::Postgres {
+        relations.reject_whole_rows(&tokens)?;
+    }
+    Ok(())
+}
+
+fn redaction_error(what: &str) -> ZError {
+    ZError::Other(anyhow::anyhow!(
+        "{} can't be used while columns are redacted",
+        what
+    ))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenKind {
+    /// A keyword, bare identifier or number
+    Word,
+    /// A quoted identifier
+    QuotedName,
+    Literal,
+    /// A single punctuation character
+    Symbol,
+}
+
+#[derive(Debug)]
+struct Token {
+    /// The name for identifiers, without quotes
+    text: String,
+    kind: TokenKind,
+}
+
+impl Token {
+    fn is_name(&self) -> bool {
+        matches!(self.kind, TokenKind::Word | TokenKind::QuotedName)
+    }
+
+    fn is_keyword(&self, keywords: &[&str]) -> bool {
+        self.kind == TokenKind::Word
+            && keywords
+                .iter()
+                .any(|keyword| keyword.eq_ignore_ascii_case(&self.text))
+    }
+
+    fn is_symbol(&self, symbol: char) -> bool {
+        self.kind == TokenKind::Symbol && self.text.starts_with(symbol)
+    }
+}
+
+/// Group lexed characters into words, quoted spans and symbols
+fn tokens(lexed: &[(char, Lexeme)]) -> Vec<Token> {
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut in_word = false;
+    let mut in_quotes = false;
+
+    for &(c, lexeme) in lexed {
+        let word_char = lexeme == Lexeme::Code && (c.is_alphanumeric() || c == '_');
+        let quoted = lexeme != Lexeme::Code;
+
+        if (word_char && in_word) || (quoted && in_quotes) {
+            let token = tokens.last_mut().expect("a token is open");
+            if lexeme == Lexeme::Identifier {
+                token.kind = TokenKind::QuotedName;
+            }
+            if lexeme != Lexeme::Literal || word_char {
+                token.text.push(c);
+            }
+        } else if word_char {
+            tokens.push(Token {
+                text: c.to_string(),
+                kind: TokenKind::Word,
+            });
+        } else if quoted {
+            let kind = match lexeme {
+                Lexeme::Identifier => TokenKind::QuotedName,
+                _ => TokenKind::Literal,
+            };
+            let text = if lexeme == Lexeme::Identifier {
+                c.to_string()
+            } else {
+                String::new()
+            };
+            tokens.push(Token { text, kind });
+        } else if !c.is_whitespace() {
+            tokens.push(Token {
+                text: c.to_string(),
+                kind: TokenKind::Symbol,
+            });
+        }
+
+        in_word = word_char;
+        in_quotes = quoted;
+    }
+
+    tokens
+}
+
+/// Keywords that can follow a table in `FROM` without being its alias
+const NOT_ALIASES: &[&str] = &[
+    "WHERE",
+    "JOIN",
+    "INNER",
+    "LEFT",
+    "RIGHT",
+    "FULL",
+    "OUTER",
+    "CROSS",
+    "NATURAL",
+    "ON",
+    "USING",
+    "GROUP",
+    "HAVING",
+    "ORDER",
+    "LIMIT",
+    "OFFSET",
+    "FETCH",
+    "FOR",
+    "UNION",
+    "INTERSECT",
+    "EXCEPT",
+    "WINDOW",
+    "TABLESAMPLE",
+    "STRAIGHT_JOIN",
+];
+
+/// Keywords that end a `FROM` list
+const FROM_END: &[&str] = &[
+    "WHERE",
+    "GROUP",
+    "HAVING",
+    "ORDER",
+    "LIMIT",
+    "OFFSET",
+    "FETCH",
+    "FOR",
+    "UNION",
+    "INTERSECT",
+    "EXCEPT",
+    "WINDOW",
+];
+
+/// Tables, subqueries and CTEs a query reads from, with their aliases
+struct Relations {
+    /// Names a relation can be referred to by
+    names: Vec<String>,
+    /// Indices of the tokens declaring those names
+    declarations: Vec<usize>,
+}
+
+impl Relations {
+    /// Collect the relations in `FROM` lists and `WITH` clauses, rejecting
+    /// column alias lists along the way
+    fn find(tokens: &[Token]) -> ZResult<Self> {
+        let mut relations = Self {
+            names: Vec::new(),
+            declarations: Vec::new(),
+        };
+        // Whether a FROM list is open, per level of parentheses
+        let mut in_from = vec![false];
+
+        for (i, token) in tokens.iter().enumerate() {
+            if token.is_symbol('(') {
+                in_from.push(false);
+            } else if token.is_symbol(')') {
+                if in_from.len() > 1 {
+                    in_from.pop();
+                }
+            } else if token.is_keyword(&["FROM", "JOIN", "STRAIGHT_JOIN"]) {
+                *in_from.last_mut().expect("never empty") = true;
+                relations.read_relation(tokens, i + 1)?;
+            } else if token.is_symbol(',') && *in_from.last().expect("never empty") {
+                relations.read_relation(tokens, i + 1)?;
+            } else if token.is_keyword(FROM_END) {
+                *in_from.last_mut().expect("never empty") = false;
+            } else if token.is_name() && starts_cte_body(tokens, i + 1) {
+                relations.declare(tokens, i);
+            } else if token.is_name() && tokens.get(i + 1).is_some_and(|t| t.is_symbol('(')) {
+                // `name(a, b) AS (...)` gives a CTE's columns new names
+                if starts_cte_body(tokens, closing_paren(tokens, i + 1)) {
+                    return Err(redaction_error("Column alias lists"));
+                }
+            }
+        }
+
+        Ok(relations)
+    }
+
+    /// Record the relation starting at `start`, and its alias
+    fn read_relation(&mut self, tokens: &[Token], start: usize) -> ZResult<()> {
+        let mut i = start;
+        while tokens
+            .get(i)
+            .is_some_and(|t| t.is_keyword(&["ONLY", "LATERAL"]))
+        {
+            i += 1;
+        }
+
+        match tokens.get(i) {
+            // A subquery or parenthesized join; its contents are read separately
+            Some(token) if token.is_symbol('(') => i = closing_paren(tokens, i),
+            Some(token) if token.is_name() => {
+                // The last part of `schema.table` names the row
+                while tokens.get(i + 1).is_some_and(|t| t.is_symbol('.'))
+                    && tokens.get(i + 2).is_some_and(Token::is_name)
+                {
+                    self.declarations.push(i);
+                    i += 2;
+                }
+                self.declare(tokens, i);
+                i += 1;
+                // A table function's arguments
+                if tokens.get(i).is_some_and(|t| t.is_symbol('(')) {
+                    i = closing_paren(tokens, i);
+                }
+            }
+            _ => return Ok(()),
+        }
+
+        if tokens.get(i).is_some_and(|t| t.is_keyword(&["AS"])) {
+            i += 1;
+        }
+        if let Some(alias) = tokens.get(i) {
+            if alias.is_name() && !alias.is_keyword(NOT_ALIASES) {
+                self.declare(tokens, i);
+                if tokens.get(i + 1).is_some_and(|t| t.is_symbol('(')) {
+                    return Err(redaction_error("Column alias lists"));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn declare(&mut self, tokens: &[Token], i: usize) {
+        self.names.push(tokens[i].text.to_lowercase());
+        self.declarations.push(i);
+    }
+
+    /// Reject a relation's name used as a value rather than to qualify a column
+    fn reject_whole_rows(&self, tokens: &[Token]) -> ZResult<()> {
+        for (i, token) in tokens.iter().enumerate() {
+            if !token.is_name()
+                || self.declarations.contains(&i)
+                || !self.names.contains(&token.text.to_lowercase())
+            {
+                continue;
+            }
+
+            let qualified = tokens.get(i + 1).is_some_and(|t| t.is_symbol('.'));
+            match tokens.get(i + 2) {
+                Some(next) if qualified && next.is_name() => {}
+                // `u.*` only expands to columns as an item of the select list
+                Some(next) if qualified && next.is_symbol('*') && is_select_item(tokens, i) => {}
+                _ => return Err(redaction_error("Whole-row references")),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether the `t.*` at `start` is an item of a select list
+fn is_select_item(tokens: &[Token], start: usize) -> bool {
+    let after = tokens.get(start + 3);
+    if !after.is_none_or(|t| t.is_symbol(',') || t.is_symbol(')') || t.is_keyword(&["FROM"])) {
+        return false;
+    }
+
+    match start.checked_sub(1).map(|i| &tokens[i]) {
+        Some(prev) if prev.is_keyword(&["SELECT", "DISTINCT", "ALL"]) => true,
+        // After a comma, unless the comma separates function arguments
+        Some(prev) if prev.is_symbol(',') => {
+            let mut depth = 0;
+            for (i, token) in tokens[..start].iter().enumerate().rev() {
+                if token.is_symbol(')') {
+                    depth += 1;
+                } else if token.is_symbol('(') {
+                    if depth == 0 {
+                        return tokens.get(i + 1).is_some_and(|t| t.is_keyword(&["SELECT"]));
+                    }
+                    depth -= 1;
+                }
+            }
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Whether `AS (`, the start of a CTE's query, begins at `start`
+fn starts_cte_body(tokens: &[Token], start: usize) -> bool {
+    let mut rest = tokens[start.min(tokens.len())..].iter();
+    rest.next().is_some_and(|t| t.is_keyword(&["AS"]))
+        && rest
+            .find(|t| !t.is_keyword(&["NOT", "MATERIALIZED"]))
+            .is_some_and(|t| t.is_symbol('('))
+}
+
+/// Index after the parenthesis closing the one at `open`
+fn closing_paren(tokens: &[Token], open: usize) -> usize {
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate().skip(open) {
+        if token.is_symbol('(') {
+            depth += 1;
+        } else if token.is_symbol(')') {
+            depth -= 1;
+            if depth == 0 {
+                return i + 1;
+            }
+        }
+    }
+    tokens.len()
+}
+
+/// Reject `*` in a query combining results with `UNION`, `INTERSECT` or `EXCEPT`
+fn reject_star_in_set_operations(tokens: &[Token]) -> ZResult<()> {
+    if !tokens
+        .iter()
+        .any(|t| t.is_keyword(&["UNION", "INTERSECT", "EXCEPT"]))
+    {
+        return Ok(());
+    }
+
+    let star = tokens.iter().enumerate().any(|(i, token)| {
+        token.is_symbol('*')
+            && i.checked_sub(1).is_some_and(|prev| {
+                let prev = &tokens[prev];
+                prev.is_keyword(&["SELECT", "DISTINCT", "ALL"])
+                    || prev.is_symbol(',')
+                    || prev.is_symbol('.')
+            })
+    });
+    if star {
+        return Err(redaction_error(
+            "* combined with UNION, INTERSECT or EXCEPT",
+        ));
+    }
+    Ok(())
+}
+
+/// What a character of a query belongs to once comments are removed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Lexeme {
+    Code,
+    /// A string literal, or the delimiter of any quoted span
+    Literal,
+    /// The name inside a quoted identifier
+    Identifier,
+}
+
+/// Classify each character of `sql`, replacing comments with a space
+///
+/// Unterminated literals and comments are rejected, since the database and
+/// this reading would disagree on where they end.
+fn lex(sql: &str, dialect: Dialect) -> ZResult<Vec<(char, Lexeme)>> {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut out = Vec::with_capacity(chars.len());
+    // Inside a MySQL `/*! ... */` comment
+    let mut executable_comment = false;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let follows_word = i > 0 && is_word_char(chars[i - 1]);
+
+        match c {
+            // MySQL only starts a comment at `-- ` followed by whitespace
+            '-' if next == Some('-')
+                && (dialect != Dialect::MySql
+                    || chars.get(i + 2).is_none_or(|c| c.is_whitespace())) =>
+            {
+                i = skip_line(&chars, i);
+                out.push((' ', Lexeme::Code));
+            }
+            '#' if dialect == Dialect::MySql => {
+                i = skip_line(&chars, i);
+                out.push((' ', Lexeme::Code));
+            }
+            '/' if next == Some('*') => {
+                if dialect == Dialect::MySql && chars.get(i + 2) == Some(&'!') {
+                    // The optional version number is part of the marker
+                    executable_comment = true;
+                    i += 3;
+                    while chars.get(i).is_some_and(char::is_ascii_digit) {
+                        i += 1;
+                    }
+                } else {
+                    i = skip_block_comment(&chars, i, dialect == Dialect::Postgres)?;
+                }
+                out.push((' ', Lexeme::Code));
+            }
+            '*' if executable_comment && next == Some('/') => {
+                executable_comment = false;
+                i += 2;
+                out.push((' ', Lexeme::Code));
+            }
+            '\'' => {
+                let escapes = dialect == Dialect::MySql;
+                i = read_quoted(&chars, i, '\'', escapes, Lexeme::Literal, &mut out)?;
+            }
+            '"' if dialect == Dialect::MySql => {
+                i = read_quoted(&chars, i, '"', true, Lexeme::Literal, &mut out)?;
+            }
+            '"' => i = read_quoted(&chars, i, '"', false, Lexeme::Identifier, &mut out)?,
+            '`' if dialect != Dialect::Postgres => {
+                i = read_quoted(&chars, i, '`', false, Lexeme::Identifier, &mut out)?;
+            }
+            '[' if dialect == Dialect::Sqlite => {
+                i = read_quoted(&chars, i, ']', false, Lexeme::Identifier, &mut out)?;
+            }
+            'E' | 'e' if dialect == Dialect::Postgres && next == Some('\'') && !follows_word => {
+                out.push((c, Lexeme::Literal));
+                i = read_quoted(&chars, i + 1, '\'', true, Lexeme::Literal, &mut out)?;
+            }
+            '$' if dialect == Dialect::Postgres && !follows_word => match dollar_tag(&chars, i) {
+                Some(tag) => i = read_dollar_quoted(&chars, i, &tag, &mut out)?,
+                None => {
+                    out.push((c, Lexeme::Code));
+                    i += 1;
+                }
+            },
+            _ => {
+                out.push((c, Lexeme::Code));
+                i += 1;
+            }
+        }
+    }
+
+    if executable_comment {
+        return Err(unterminated("comment"));
+    }
+    Ok(out)
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+fn unterminated(what: &str) -> ZError {
+    ZError::Other(anyhow::anyhow!("Query has an unterminated {}", what))
+}
+
+/// Index of the newline ending the line comment at `start`
+fn skip_line(chars: &[char], start: usize) -> usize {
+    chars[start..]
+        .iter()
+        .position(|&c| c == '\n')
+        .map_or(chars.len(), |offset| start + offset)
+}
+
+/// Index after the block comment opening at `start`
+fn skip_block_comment(chars: &[char], start: usize, nested: bool) -> ZResult<usize> {
+    let mut depth = 0;
+    let mut i = start;
+    while i + 1 < chars.len() {
+        match (chars[i], chars[i + 1]) {
+            ('/', '*') if depth == 0 || nested => {
+                depth += 1;
+                i += 2;
+            }
+            ('*', '/') => {
+                depth -= 1;
+                i += 2;
+                if depth == 0 {
+                    return Ok(i);
+                }
+            }
+            _ => i += 1,
+        }
+    }
+    Err(unterminated("comment"))
+}
+
+/// Read the span opened by the quote at `start` into `out`, returning the
+/// index after its closing `close`
+///
+/// A doubled closing quote stands for itself; with `escapes`, so does any
+/// character following a backslash.
+fn read_quoted(
+    chars: &[char],
+    start: usize,
+    close: char,
+    escapes: bool,
+    kind: Lexeme,
+    out: &mut Vec<(char, Lexeme)>,
+) -> ZResult<usize> {
+    out.push((chars[start], Lexeme::Literal));
+    let mut i = start + 1;
+
+    while let Some(&c) = chars.get(i) {
+        if escapes && c == '\\' {
+            let escaped = chars
+                .get(i + 1)
+                .ok_or_else(|| unterminated("quoted string"))?;
+            out.push((c, kind));
+            out.push((*escaped, kind));
+            i += 2;
+        } else if c == close && chars.get(i + 1) == Some(&close) {
+            out.push((c, kind));
+            out.push((c, kind));
+            i += 2;
+        } else if c == close {
+            out.push((c, Lexeme::Literal));
+            return Ok(i + 1);
+        } else {
+            out.push((c, kind));
+            i += 1;
+        }
+    }
+
+    Err(unterminated("quoted string"))
+}
+
+/// The `$tag$` opening a Postgres dollar-quoted string at `start`, if any
+fn dollar_tag(chars: &[char], start: usize) -> Option<String> {
+    let mut tag = String::from("$");
+    for (offset, &c) in chars[start + 1..].iter().enumerate() {
+        match c {
+            '$' => {
+                tag.push('$');
+                return Some(tag);
+            }
+            // Tags follow identifier rules, so `$1` is a placeholder
+            c if c.is_alphabetic() || c == '_' || (offset > 0 && c.is_alphanumeric()) => {
+                tag.push(c)
+            }
+            _ => return None,
+        }
+    }
+    None
+}
+
+/// Read the dollar-quoted string opened by `tag` at `start` into `out`,
+/// returning the index after its closing tag
+fn read_dollar_quoted(
+    chars: &[char],
+    start: usize,
+    tag: &str,
+    out: &mut Vec<(char, Lexeme)>,
+) -> ZResult<usize> {
+    let tag: Vec<char> = tag.chars().collect();
+    let body = start + tag.len();
+    let end = (body..=chars.len().saturating_sub(tag.len()))
+        .find(|&i| chars[i..i + tag.len()] == tag[..])
+        .ok_or_else(|| unterminated("dollar-quoted string"))?;
+
+    out.extend(tag.iter().map(|&c| (c, Lexeme::Literal)));
+    out.extend(chars[body..end].iter().map(|&c| (c, Lexeme::Literal)));
+    out.extend(tag.iter().map(|&c| (c, Lexeme::Literal)));
+    Ok(end + tag.len())
+}
+
+/// Split on semicolons outside literals, dropping empty statements
+fn split_statements(lexed: &[(char, Lexeme)]) -> Vec<&[(char, Lexeme)]> {
+    lexed
+        .split(|&(c, kind)| c == ';' && kind == Lexeme::Code)
+        .map(|statement| {
+            let start = statement
+                .iter()
+                .position(|(c, _)| !c.is_whitespace())
+                .unwrap_or(statement.len());
+            let end = statement
+                .iter()
+                .rposition(|(c, _)| !c.is_whitespace())
+                .map_or(start, |i| i + 1);
+            &statement[start..end]
+        })
+        .filter(|statement| !statement.is_empty())
+        .collect()
+}
+
+/// Uppercased keywords and identifiers outside literals
+fn words_outside_literals(lexed: &[(char, Lexeme)]) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+
+    for &(c, kind) in lexed {
+        if kind == Lexeme::Code && (c.is_ascii_alphanumeric() || c == '_') {
+            current.push(c.to_ascii_uppercase());
+        } else if !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rejected(sql: &str) -> String {
+        rejected_in(sql, Dialect::Sqlite)
+    }
+
+    fn rejected_in(sql: &str, dialect: Dialect) -> String {
+        validate_read_only_query(sql, dialect)
+            .unwrap_err()
+            .to_string()
+    }
+
+    #[test]
+    fn test_accepts_plain_select_and_cte() {
+        assert_eq!(
+            validate_read_only_query("  SELECT * FROM users; ", Dialect::Sqlite).unwrap(),
+            "SELECT * FROM users"
+        );
+        assert!(validate_read_only_query(
+            "WITH recent AS (SELECT * FROM orders) SELECT * FROM recent",
+            Dialect::Sqlite
+        )
+        .is_ok());
+        // Keywords and separators inside literals are just data
+        assert!(validate_read_only_query(
+            "SELECT * FROM notes WHERE body = 'drop; -- delete'",
+            Dialect::Sqlite
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_rejects_chained_statements() {
+        assert!(rejected("SELECT * FROM users; DROP TABLE users").contains("single statement"));
+        assert!(rejected("SELECT 1;DELETE FROM users;").contains("single statement"));
+    }
+
+    #[test]
+    fn test_rejects_comment_obfuscation() {
+        assert!(rejected("/* SELECT */ DELETE FROM users").contains("Only SELECT"));
+        assert!(rejected("-- SELECT\nDROP TABLE users").contains("Only SELECT"));
+        assert!(rejected("SELECT 1 /* ; */; -- x\n DROP TABLE users").contains("single statement"));
+    }
+
+    #[test]
+    fn test_comments_removed_so_limit_applies() {
+        // A trailing line comment would otherwise swallow an appended LIMIT
+        assert_eq!(
+            validate_read_only_query("SELECT * FROM users -- all of them", Dialect::Sqlite)
+                .unwrap(),
+            "SELECT * FROM users"
+        );
+    }
+
+    #[test]
+    fn test_rejects_writes_inside_select() {
+        assert!(
+            rejected("WITH gone AS (DELETE FROM users RETURNING *) SELECT * FROM gone")
+                .contains("DELETE")
+        );
+        assert!(rejected("SELECT * INTO backup FROM users").contains("INTO"));
+    }
+
+    #[test]
+    fn test_backslash_escapes_follow_the_dialect() {
+        // An escaped quote doesn't end the literal, so INTO and the comment are live
+        assert!(rejected_in(
+            "SELECT E'\\'' INTO backup FROM users --'",
+            Dialect::Postgres
+        )
+        .contains("INTO"));
+        assert!(
+            rejected_in("SELECT '\\'' INTO OUTFILE '/tmp/x' -- '", Dialect::MySql).contains("INTO")
+        );
+        assert!(rejected_in("SELECT \"\\\"\" INTO @x -- \"", Dialect::MySql).contains("INTO"));
+
+        // Without escapes a backslash is just data
+        for dialect in [Dialect::Sqlite, Dialect::Postgres] {
+            assert_eq!(
+                validate_read_only_query("SELECT 'C:\\' AS path", dialect).unwrap(),
+                "SELECT 'C:\\' AS path"
+            );
+        }
+        assert!(rejected_in("SELECT 'C:\\' AS path", Dialect::MySql).contains("unterminated"));
+    }
+
+    #[test]
+    fn test_mysql_comments() {
+        assert_eq!(
+            validate_read_only_query("SELECT 1 # ; DROP TABLE users", Dialect::MySql).unwrap(),
+            "SELECT 1"
+        );
+        assert!(
+            rejected_in("SELECT 1 # ; DROP TABLE users", Dialect::Postgres)
+                .contains("single statement")
+        );
+        // MySQL runs the contents of /*! ... */
+        assert!(rejected_in(
+            "SELECT * FROM users /*!50000 INTO OUTFILE '/tmp/x' */",
+            Dialect::MySql
+        )
+        .contains("INTO"));
+        // `--` needs trailing whitespace to start a MySQL comment
+        assert!(rejected_in("SELECT 1 --1 INTO @x", Dialect::MySql).contains("INTO"));
+    }
+
+    #[test]
+    fn test_postgres_dollar_quoting() {
+        assert!(rejected_in(
+            "SELECT $$ ' $$ INTO backup FROM users -- '",
+            Dialect::Postgres
+        )
+        .contains("INTO"));
+        assert_eq!(
+            validate_read_only_query("SELECT $body$ ; DROP $body$ AS s", Dialect::Postgres)
+                .unwrap(),
+            "SELECT $body$ ; DROP $body$ AS s"
+        );
+        assert!(
+            rejected_in("SELECT $tag$ never closed", Dialect::Postgres).contains("unterminated")
+        );
+        // Placeholders aren't dollar quotes
+        assert!(
+            validate_read_only_query("SELECT * FROM users WHERE id = $1", Dialect::Postgres)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_rejects_unterminated_literals_and_comments() {
+        assert!(rejected("SELECT 'abc").contains("unterminated"));
+        assert!(rejected("SELECT 1 /* open").contains("unterminated"));
+        // Postgres block comments nest
+        assert!(
+            rejected_in("SELECT 1 /* a /* b */ INTO x", Dialect::Postgres).contains("unterminated")
+        );
+        assert!(rejected("SELECT 1 /* a /* b */ INTO x").contains("INTO"));
+    }
+
+    #[test]
+    fn test_rejects_references_to_redacted_columns() {
+        let mut config = DatabaseToolConfig::default();
+        config.redacted_columns.insert("ssn".to_string());
+        let check = |sql: &str| reject_redacted_references(sql, Dialect::Sqlite, &config);
+
+        assert!(check("SELECT * FROM users").is_ok());
+        assert!(check("SELECT name FROM users WHERE note = 'ssn'").is_ok());
+        assert!(check("SELECT ssn AS id FROM users").is_err());
+        assert!(check("SELECT u.SSN FROM users u").is_err());
+        assert!(check("SELECT \"ssn\" || '' FROM users").is_err());
+        assert!(check("SELECT name FROM users WHERE `ssn` LIKE '1%'").is_err());
+    }
+
+    #[test]
+    fn test_rejects_renamed_and_whole_row_redacted_columns() {
+        let mut config = DatabaseToolConfig::default();
+        config.redacted_columns.insert("ssn".to_string());
+        let check = |sql: &str| reject_redacted_references(sql, Dialect::Postgres, &config);
+
+        for sql in [
+            "SELECT row_to_json(u) FROM users u",
+            "SELECT u::text FROM users u",
+            "SELECT c FROM users AS u(a,b,c)",
+            "SELECT users FROM users",
+            "SELECT to_json(u.*) FROM users u",
+            "SELECT name FROM users u WHERE u::text LIKE '%123%'",
+            "SELECT c FROM (SELECT * FROM users) s(a, b, c)",
+            "WITH t(a, b, c) AS (SELECT * FROM users) SELECT c FROM t",
+            "SELECT 'a', 'b', 'c' UNION ALL SELECT * FROM users",
+        ] {
+            assert!(
+                check(sql).unwrap_err().to_string().contains("redacted"),
+                "{}",
+                sql
+            );
+        }
+
+        for sql in [
+            "SELECT * FROM users",
+            "SELECT u.* FROM users u",
+            "SELECT u.name, o.total FROM users u JOIN orders o ON o.user_id = u.id",
+            "SELECT COUNT(*), upper(name) AS n FROM public.users",
+            "SELECT * FROM (SELECT u.*, 1 AS one FROM users u) AS s WHERE s.one = 1",
+            "WITH recent AS (SELECT * FROM users) SELECT * FROM recent",
+            "SELECT name FROM users UNION SELECT name FROM admins",
+        ] {
+            assert!(check(sql).is_ok(), "{}", sql);
+        }
+
+        // Whole rows are only a concern with columns to protect
+        assert!(reject_redacted_references(
+            "SELECT row_to_json(u) FROM users u",
+            Dialect::Postgres,
+            &DatabaseToolConfig::default()
+        )
+        .is_ok());
+    }
+}
@@ -1,32 +1,185 @@
 //! Procedural macros for ZDK
 //!
-//! This crate provides the `#[tool]` attribute macro for creating tools ergonomically.
+//! This crate provides the `#[tool]` attribute macro and `#[derive(Tool)]` for
+//! creating tools ergonomically.
 
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{ItemFn, Lit, parse_macro_input};
+use syn::punctuated::Punctuated;
+use syn::{
+    Data, DeriveInput, FnArg, GenericArgument, ImplItemFn, ItemFn, Lit, Meta, Pat, PathArguments,
+    Token, Type, parse_macro_input,
+};
 
 /// Derives the `Tool` trait for a struct
 ///
+/// Lets a tool hold state such as configuration or an HTTP client. The tool
+/// name and description come from the `#[tool(name = "...", description =
+/// "...")]` attribute; the name defaults to the struct name in snake_case and
+/// the description to the struct's doc comment.
+///
+/// Each field becomes a property of the parameter schema, using the same type
+/// mapping as [`macro@tool`] and the field's doc comment as its description.
+/// Mark configuration fields with `#[tool(skip)]` to leave them out.
+///
+/// The `execute` body is the method annotated with [`macro@tool_execute`], which
+/// must take the tool context and the raw parameters.
+///
 /// # Example
 ///
 /// ```ignore
-/// use zdk_macros::Tool;
-/// use zdk_core::Tool;
+/// use zdk_macros::{Tool, tool_execute};
+/// use zdk_core::{ToolContext, Result, ToolResponse};
+/// use std::sync::Arc;
 ///
 /// #[derive(Tool)]
-/// struct MyTool {
-///     name: String,
+/// #[tool(name = "fetch", description = "Fetches a path from the API")]
+/// struct FetchTool {
+///     /// Path to fetch, relative to the base URL
+///     path: String,
+///     #[tool(skip)]
+///     client: reqwest::Client,
+/// }
+///
+/// impl FetchTool {
+///     #[tool_execute]
+///     async fn run(&self, ctx: Arc<dyn ToolContext>, params: serde_json::Value) -> Result<ToolResponse> {
+///         // ...
+///     }
 /// }
 /// ```
 #[proc_macro_derive(Tool, attributes(tool))]
-pub fn derive_tool(_input: TokenStream) -> TokenStream {
-    // For future implementation if needed
-    TokenStream::new()
+pub fn derive_tool(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match expand_derive_tool(&input) {
+        Ok(output) => output.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand_derive_tool(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let struct_name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            input,
+            "Tool can only be derived for structs",
+        ));
+    };
+
+    let mut attrs = std::collections::HashMap::new();
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("tool")) {
+        attrs.extend(string_metas(
+            attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?,
+        ));
+    }
+    let tool_name = attrs
+        .get("name")
+        .cloned()
+        .unwrap_or_else(|| to_snake_case(&struct_name.to_string()));
+    let description = attrs
+        .get("description")
+        .cloned()
+        .or_else(|| doc_comment(&input.attrs))
+        .unwrap_or_else(|| "No description provided".to_string());
+
+    let mut schema_properties = Vec::new();
+    for field in &data.fields {
+        if is_skipped(field)? {
+            continue;
+        }
+        let Some(ident) = &field.ident else {
+            return Err(syn::Error::new_spanned(
+                field,
+                "Tool can only be derived for structs with named fields",
+            ));
+        };
+
+        let (inner, optional) = match option_inner(&field.ty) {
+            Some(inner) => (inner, true),
+            None => (&field.ty, false),
+        };
+        let json_type = json_type(inner).ok_or_else(|| {
+            syn::Error::new_spanned(
+                &field.ty,
+                "unsupported tool parameter type; expected f64, i64, String, bool or Option of those (use #[tool(skip)] for other fields)",
+            )
+        })?;
+
+        let name = ident.to_string();
+        let field_description = doc_comment(&field.attrs).unwrap_or_else(|| name.clone());
+        let required = (!optional).then(|| quote! { .required(#name) });
+        schema_properties.push(quote! {
+            .property(#name, #json_type, #field_description)
+            #required
+        });
+    }
+
+    Ok(quote! {
+        #[::zdk_tool::macro_support::async_trait]
+        impl #impl_generics ::zdk_core::Tool for #struct_name #ty_generics #where_clause {
+            fn name(&self) -> &str {
+                #tool_name
+            }
+
+            fn description(&self) -> &str {
+                #description
+            }
+
+            fn schema(&self) -> ::serde_json::Value {
+                ::zdk_tool::ToolSchema::new()
+                    #(#schema_properties)*
+                    .build()
+            }
+
+            async fn execute(
+                &self,
+                ctx: ::std::sync::Arc<dyn ::zdk_core::ToolContext>,
+                params: ::serde_json::Value,
+            ) -> ::zdk_core::Result<::zdk_core::ToolResponse> {
+                self.__zdk_tool_execute(ctx, params).await
+            }
+        }
+    })
+}
+
+/// Marks the method that implements `execute` for a `#[derive(Tool)]` struct
+///
+/// The method must be `async` and take `&self`, the tool context and the raw
+/// `serde_json::Value` parameters, returning `Result<ToolResponse>`.
+#[proc_macro_attribute]
+pub fn tool_execute(_args: TokenStream, input: TokenStream) -> TokenStream {
+    let method = parse_macro_input!(input as ImplItemFn);
+    let method_name = &method.sig.ident;
+
+    let output = quote! {
+        #method
+
+        #[doc(hidden)]
+        async fn __zdk_tool_execute(
+            &self,
+            ctx: ::std::sync::Arc<dyn ::zdk_core::ToolContext>,
+            params: ::serde_json::Value,
+        ) -> ::zdk_core::Result<::zdk_core::ToolResponse> {
+            self.#method_name(ctx, params).await
+        }
+    };
+
+    TokenStream::from(output)
 }
 
 /// Converts a function into a Tool implementation
 ///
+/// The first argument receives the tool context. Every other argument becomes a
+/// property of the generated schema: `f64` maps to `number`, `i64` to
+/// `integer`, `String` to `string` and `bool` to `boolean`. Arguments not
+/// wrapped in `Option` are marked as required. Incoming parameters are
+/// deserialized into the typed arguments before the function is called.
+///
+/// Without an explicit `description`, the function's doc comment is used.
+///
 /// # Example
 ///
 /// ```ignore
@@ -36,10 +189,10 @@ pub fn derive_tool(_input: TokenStream) -> TokenStream {
 ///
 /// #[tool(description = "Adds two numbers together")]
 /// async fn add(ctx: Arc<dyn ToolContext>, x: f64, y: f64) -> Result<ToolResponse> {
-///     Ok(ToolResponse {
-///         result: serde_json::json!({"sum": x + y}),
-///     })
+///     Ok(ToolResponse::new(serde_json::json!({"sum": x + y})))
 /// }
+///
+/// let tool = create_add_tool()?;
 /// ```
 #[proc_macro_attribute]
 pub fn tool(args: TokenStream, input: TokenStream) -> TokenStream {
@@ -50,32 +203,60 @@ pub fn tool(args: TokenStream, input: TokenStream) -> TokenStream {
     let description = attrs
         .get("description")
         .cloned()
+        .or_else(|| doc_comment(&input_fn.attrs))
         .unwrap_or_else(|| "No description provided".to_string());
 
+    let params = match parse_tool_params(&input_fn) {
+        Ok(params) => params,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
     let fn_name = &input_fn.sig.ident;
     let tool_name = fn_name.to_string();
     let fn_visibility = &input_fn.vis;
-    let fn_body = &input_fn.block;
-    let fn_async = &input_fn.sig.asyncness;
 
     // Generate the tool creation function
     let creator_name = syn::Ident::new(&format!("create_{}_tool", fn_name), fn_name.span());
 
+    let schema_properties = params.iter().map(|p| {
+        let name = p.ident.to_string();
+        let json_type = p.json_type;
+        let required = (!p.optional).then(|| quote! { .required(#name) });
+        quote! {
+            .property(#name, #json_type, #name)
+            #required
+        }
+    });
+
+    let param_bindings = params.iter().map(|p| {
+        let ident = &p.ident;
+        let name = ident.to_string();
+        let ty = &p.ty;
+        if p.optional {
+            quote! {
+                let #ident: #ty =
+                    ::zdk_tool::macro_support::optional_param(&params, #tool_name, #name)?;
+            }
+        } else {
+            quote! {
+                let #ident: #ty =
+                    ::zdk_tool::macro_support::required_param(&params, #tool_name, #name)?;
+            }
+        }
+    });
+
+    let param_idents = params.iter().map(|p| &p.ident);
+
     let output = quote! {
         /// Original function (kept for direct usage if needed)
-        #fn_visibility #fn_async fn #fn_name(
-            ctx: ::std::sync::Arc<dyn ::zdk_core::ToolContext>,
-            params: ::serde_json::Value,
-        ) -> ::zdk_core::Result<::zdk_core::ToolResponse> {
-            #fn_body
-        }
+        #input_fn
 
         /// Tool creator function
         #fn_visibility fn #creator_name() -> ::zdk_core::Result<::zdk_tool::FunctionTool> {
             use ::zdk_tool::ToolSchema;
 
             let schema = ToolSchema::new()
-                .property("params", "object", "Tool parameters")
+                #(#schema_properties)*
                 .build();
 
             ::zdk_tool::FunctionTool::builder()
@@ -83,7 +264,8 @@ pub fn tool(args: TokenStream, input: TokenStream) -> TokenStream {
                 .description(#description)
                 .schema(schema)
                 .execute(|ctx, params| async move {
-                    #fn_name(ctx, params).await
+                    #(#param_bindings)*
+                    #fn_name(ctx, #(#param_idents),*).await
                 })
                 .build()
         }
@@ -92,30 +274,167 @@ pub fn tool(args: TokenStream, input: TokenStream) -> TokenStream {
     TokenStream::from(output)
 }
 
-fn parse_tool_attributes(args: TokenStream) -> std::collections::HashMap<String, String> {
-    let mut attrs = std::collections::HashMap::new();
+/// A typed tool argument exposed in the generated schema
+struct ToolParam {
+    ident: syn::Ident,
+    ty: syn::Type,
+    json_type: &'static str,
+    optional: bool,
+}
+
+/// Collect the typed arguments following the context argument
+fn parse_tool_params(input_fn: &ItemFn) -> syn::Result<Vec<ToolParam>> {
+    let mut inputs = input_fn.sig.inputs.iter();
+
+    if inputs.next().is_none() {
+        return Err(syn::Error::new_spanned(
+            &input_fn.sig,
+            "tool functions must take the tool context as their first argument",
+        ));
+    }
+
+    inputs
+        .map(|arg| {
+            let FnArg::Typed(pat_type) = arg else {
+                return Err(syn::Error::new_spanned(
+                    arg,
+                    "tool functions cannot take self",
+                ));
+            };
+            let Pat::Ident(pat_ident) = pat_type.pat.as_ref() else {
+                return Err(syn::Error::new_spanned(
+                    &pat_type.pat,
+                    "tool arguments must be plain identifiers",
+                ));
+            };
+
+            let (inner, optional) = match option_inner(&pat_type.ty) {
+                Some(inner) => (inner, true),
+                None => (pat_type.ty.as_ref(), false),
+            };
+            let json_type = json_type(inner).ok_or_else(|| {
+                syn::Error::new_spanned(
+                    &pat_type.ty,
+                    "unsupported tool argument type; expected f64, i64, String, bool or Option of those",
+                )
+            })?;
+
+            Ok(ToolParam {
+                ident: pat_ident.ident.clone(),
+                ty: pat_type.ty.as_ref().clone(),
+                json_type,
+                optional,
+            })
+        })
+        .collect()
+}
+
+/// Return `T` if `ty` is `Option<T>`
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        GenericArgument::Type(inner) => Some(inner),
+        _ => None,
+    }
+}
+
+/// Map a supported Rust type to its JSON schema type
+fn json_type(ty: &Type) -> Option<&'static str> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    match segment.ident.to_string().as_str() {
+        "f64" => Some("number"),
+        "i64" => Some("integer"),
+        "String" => Some("string"),
+        "bool" => Some("boolean"),
+        _ => None,
+    }
+}
 
+fn parse_tool_attributes(args: TokenStream) -> std::collections::HashMap<String, String> {
     if args.is_empty() {
-        return attrs;
+        return std::collections::HashMap::new();
     }
 
     // Parse as attribute arguments
-    let parsed = syn::parse::Parser::parse(
-        syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated,
-        args,
-    );
-
-    if let Ok(metas) = parsed {
-        for meta in metas {
-            if let syn::Meta::NameValue(nv) = meta
-                && nv.path.is_ident("description")
-                && let syn::Expr::Lit(expr_lit) = &nv.value
-                && let Lit::Str(s) = &expr_lit.lit
-            {
-                attrs.insert("description".to_string(), s.value());
-            }
+    let parsed = syn::parse::Parser::parse(Punctuated::<Meta, Token![,]>::parse_terminated, args);
+
+    parsed.map(string_metas).unwrap_or_default()
+}
+
+/// Collect `key = "value"` pairs from attribute arguments
+fn string_metas(metas: Punctuated<Meta, Token![,]>) -> std::collections::HashMap<String, String> {
+    let mut attrs = std::collections::HashMap::new();
+
+    for meta in metas {
+        if let Meta::NameValue(nv) = meta
+            && let Some(key) = nv.path.get_ident()
+            && let syn::Expr::Lit(expr_lit) = &nv.value
+            && let Lit::Str(s) = &expr_lit.lit
+        {
+            attrs.insert(key.to_string(), s.value());
         }
     }
 
     attrs
 }
+
+/// Whether a field carries `#[tool(skip)]`
+fn is_skipped(field: &syn::Field) -> syn::Result<bool> {
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("tool")) {
+        let metas = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+        if metas
+            .iter()
+            .any(|m| matches!(m, Meta::Path(path) if path.is_ident("skip")))
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Join a field's `///` doc comment lines into a single description
+fn doc_comment(attrs: &[syn::Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|a| a.path().is_ident("doc"))
+        .filter_map(|a| match &a.meta {
+            Meta::NameValue(nv) => match &nv.value {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: Lit::Str(s), ..
+                }) => Some(s.value().trim().to_string()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    (!lines.is_empty()).then(|| lines.join(" "))
+}
+
+fn to_snake_case(name: &str) -> String {
+    let mut result = String::new();
+    for (i, ch) in name.chars().enumerate() {
+        if ch.is_uppercase() {
+            if i > 0 {
+                result.push('_');
+            }
+            result.extend(ch.to_lowercase());
+        } else {
+            result.push(ch);
+        }
+    }
+    result
+}
@@ -0,0 +1,7 @@
+//! Compile-fail tests for the arguments rejected by the `#[tool]` attribute macro
+
+#[test]
+fn test_tool_macro_rejects_invalid_signatures() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,166 @@
+//! Integration tests for `#[derive(Tool)]`
+
+use serde_json::{Value, json};
+use std::sync::Arc;
+use zdk_core::{Result, Tool, ToolContext, ToolResponse};
+use zdk_macros::{Tool, tool_execute};
+use zdk_tool::DefaultToolContext;
+
+// Parameter fields describe the schema and hold defaults for omitted arguments
+#[derive(Tool)]
+#[tool(
+    name = "fetch_page",
+    description = "Fetches a page from the configured API"
+)]
+struct FetchTool {
+    /// Path to fetch, relative to the base URL
+    path: String,
+    /// Optional query string
+    query: Option<String>,
+    #[tool(skip)]
+    client: reqwest::Client,
+    #[tool(skip)]
+    base_url: String,
+}
+
+impl FetchTool {
+    fn new(base_url: String) -> Self {
+        Self {
+            path: "/".to_string(),
+            query: None,
+            client: reqwest::Client::new(),
+            base_url,
+        }
+    }
+
+    #[tool_execute]
+    async fn fetch(&self, _ctx: Arc<dyn ToolContext>, params: Value) -> Result<ToolResponse> {
+        let path = params["path"].as_str().unwrap_or(&self.path);
+        let mut url = format!("{}{}", self.base_url, path);
+        if let Some(query) = params["query"].as_str().or(self.query.as_deref()) {
+            url = format!("{}?{}", url, query);
+        }
+
+        let body = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| zdk_core::Error::Other(e.into()))?
+            .text()
+            .await
+            .map_err(|e| zdk_core::Error::Other(e.into()))?;
+
+        Ok(ToolResponse::new(json!({ "body": body })))
+    }
+}
+
+#[derive(Tool)]
+struct PingTool {
+    #[tool(skip)]
+    label: String,
+}
+
+impl PingTool {
+    #[tool_execute]
+    async fn ping(&self, _ctx: Arc<dyn ToolContext>, _params: Value) -> Result<ToolResponse> {
+        Ok(ToolResponse::new(json!(format!("{}: pong", self.label))))
+    }
+}
+
+fn ctx() -> Arc<dyn ToolContext> {
+    Arc::new(DefaultToolContext::new(
+        "call-1".to_string(),
+        "inv-1".to_string(),
+    ))
+}
+
+#[test]
+fn test_name_and_description_from_attribute() {
+    let tool = FetchTool::new("http://localhost".to_string());
+
+    assert_eq!(tool.name(), "fetch_page");
+    assert_eq!(tool.description(), "Fetches a page from the configured API");
+    assert!(!tool.is_long_running());
+}
+
+#[test]
+fn test_schema_from_fields() {
+    let schema = FetchTool::new("http://localhost".to_string()).schema();
+
+    assert_eq!(schema["properties"]["path"]["type"], "string");
+    assert_eq!(
+        schema["properties"]["path"]["description"],
+        "Path to fetch, relative to the base URL"
+    );
+    assert_eq!(schema["properties"]["query"]["type"], "string");
+    assert!(schema["properties"].get("client").is_none());
+    assert!(schema["properties"].get("base_url").is_none());
+    assert_eq!(schema["required"], json!(["path"]));
+}
+
+#[test]
+fn test_defaults_without_attribute() {
+    let tool = PingTool {
+        label: "ping".to_string(),
+    };
+
+    assert_eq!(tool.name(), "ping_tool");
+    assert_eq!(tool.description(), "No description provided");
+    assert_eq!(tool.schema()["properties"], json!({}));
+}
+
+#[tokio::test]
+async fn test_execute_round_trip() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("GET", "/greeting")
+        .with_status(200)
+        .with_body("hello from the api")
+        .create_async()
+        .await;
+
+    let tool: Arc<dyn Tool> = Arc::new(FetchTool::new(server.url()));
+    let response = tool
+        .execute(ctx(), json!({ "path": "/greeting" }))
+        .await
+        .unwrap();
+
+    assert_eq!(response.result["body"], "hello from the api");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_execute_falls_back_to_field_defaults() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("GET", "/greeting")
+        .match_query(mockito::Matcher::UrlEncoded(
+            "lang".to_string(),
+            "en".to_string(),
+        ))
+        .with_status(200)
+        .with_body("hello")
+        .create_async()
+        .await;
+
+    let tool = FetchTool {
+        path: "/greeting".to_string(),
+        query: Some("lang=en".to_string()),
+        ..FetchTool::new(server.url())
+    };
+    let response = tool.execute(ctx(), json!({})).await.unwrap();
+
+    assert_eq!(response.result["body"], "hello");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_execute_without_state() {
+    let tool = PingTool {
+        label: "ping".to_string(),
+    };
+
+    let response = tool.execute(ctx(), json!({})).await.unwrap();
+    assert_eq!(response.result, "ping: pong");
+}
@@ -0,0 +1,136 @@
+//! Integration tests for the `#[tool]` attribute macro
+
+use serde_json::json;
+use std::sync::Arc;
+use zdk_core::{Result, Tool, ToolContext, ToolResponse};
+use zdk_macros::tool;
+use zdk_tool::DefaultToolContext;
+
+#[tool(description = "Adds two numbers together")]
+async fn add(_ctx: Arc<dyn ToolContext>, x: f64, y: f64) -> Result<ToolResponse> {
+    Ok(ToolResponse::new(json!({ "sum": x + y })))
+}
+
+#[tool(description = "Greets someone")]
+async fn greet(
+    _ctx: Arc<dyn ToolContext>,
+    name: String,
+    times: Option<i64>,
+    shout: Option<bool>,
+) -> Result<ToolResponse> {
+    let mut greeting = format!("Hello, {}!", name);
+    if shout.unwrap_or(false) {
+        greeting = greeting.to_uppercase();
+    }
+    Ok(ToolResponse::new(
+        json!({ "greeting": greeting.repeat(times.unwrap_or(1) as usize) }),
+    ))
+}
+
+/// Multiplies two numbers.
+/// Returns the product.
+#[tool]
+async fn multiply(_ctx: Arc<dyn ToolContext>, x: f64, y: f64) -> Result<ToolResponse> {
+    Ok(ToolResponse::new(json!({ "product": x * y })))
+}
+
+#[tool]
+async fn noop(_ctx: Arc<dyn ToolContext>) -> Result<ToolResponse> {
+    Ok(ToolResponse::new(json!(null)))
+}
+
+fn ctx() -> Arc<dyn ToolContext> {
+    Arc::new(DefaultToolContext::new(
+        "call-1".to_string(),
+        "inv-1".to_string(),
+    ))
+}
+
+#[test]
+fn test_schema_has_typed_properties() {
+    let tool = create_add_tool().unwrap();
+    let schema = tool.schema();
+
+    assert_eq!(tool.name(), "add");
+    assert_eq!(tool.description(), "Adds two numbers together");
+    assert_eq!(schema["type"], "object");
+    assert_eq!(schema["properties"]["x"]["type"], "number");
+    assert_eq!(schema["properties"]["y"]["type"], "number");
+    assert_eq!(schema["required"], json!(["x", "y"]));
+}
+
+#[test]
+fn test_description_from_doc_comment() {
+    let tool = create_multiply_tool().unwrap();
+    assert_eq!(
+        tool.description(),
+        "Multiplies two numbers. Returns the product."
+    );
+}
+
+#[test]
+fn test_explicit_description_or_default() {
+    assert_eq!(
+        create_add_tool().unwrap().description(),
+        "Adds two numbers together"
+    );
+    assert_eq!(
+        create_noop_tool().unwrap().description(),
+        "No description provided"
+    );
+}
+
+#[test]
+fn test_optional_arguments_not_required() {
+    let schema = create_greet_tool().unwrap().schema();
+
+    assert_eq!(schema["properties"]["name"]["type"], "string");
+    assert_eq!(schema["properties"]["times"]["type"], "integer");
+    assert_eq!(schema["properties"]["shout"]["type"], "boolean");
+    assert_eq!(schema["required"], json!(["name"]));
+}
+
+#[tokio::test]
+async fn test_execute_deserializes_arguments() {
+    let tool = create_add_tool().unwrap();
+
+    let response = tool
+        .execute(ctx(), json!({ "x": 2.5, "y": 4 }))
+        .await
+        .unwrap();
+    assert_eq!(response.result["sum"], 6.5);
+}
+
+#[tokio::test]
+async fn test_execute_with_optional_arguments() {
+    let tool = create_greet_tool().unwrap();
+
+    let response = tool.execute(ctx(), json!({ "name": "Ada" })).await.unwrap();
+    assert_eq!(response.result["greeting"], "Hello, Ada!");
+
+    let response = tool
+        .execute(ctx(), json!({ "name": "Ada", "times": 2, "shout": true }))
+        .await
+        .unwrap();
+    assert_eq!(response.result["greeting"], "HELLO, ADA!HELLO, ADA!");
+}
+
+#[tokio::test]
+async fn test_execute_rejects_missing_or_invalid_arguments() {
+    let tool = create_add_tool().unwrap();
+
+    let missing = tool.execute(ctx(), json!({ "x": 1.0 })).await.unwrap_err();
+    assert!(missing.to_string().contains("add"));
+
+    let invalid = tool
+        .execute(ctx(), json!({ "x": "one", "y": 2.0 }))
+        .await
+        .unwrap_err();
+    assert!(matches!(invalid, zdk_core::Error::ToolFailed { .. }));
+}
+
+#[tokio::test]
+async fn test_original_function_still_callable() {
+    let response = add(ctx(), 1.0, 2.0).await.unwrap();
+    assert_eq!(response.result["sum"], 3.0);
+}
@@ -0,0 +1,6 @@
+#[zdk_macros::tool]
+async fn no_context() -> zdk_core::Result<zdk_core::ToolResponse> {
+    Ok(zdk_core::ToolResponse::new(serde_json::json!(null)))
+}
+
+fn main() {}
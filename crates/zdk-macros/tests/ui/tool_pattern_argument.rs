@@ -0,0 +1,9 @@
+#[zdk_macros::tool]
+async fn swap(
+    _ctx: std::sync::Arc<dyn zdk_core::ToolContext>,
+    (x, y): (f64, f64),
+) -> zdk_core::Result<zdk_core::ToolResponse> {
+    Ok(zdk_core::ToolResponse::new(serde_json::json!([y, x])))
+}
+
+fn main() {}
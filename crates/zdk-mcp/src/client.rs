@@ -1,25 +1,139 @@
 //! MCP client wrapper using rmcp SDK
 
-use crate::connection::StdioConnectionParams;
+use crate::connection::{
+    McpConnectionParams, ReconnectPolicy, SseConnectionParams, StdioConnectionParams,
+};
 use crate::types::{McpToolInfo, ToolContent};
 use anyhow::Result;
 use rmcp::model::CallToolRequestParam;
-use rmcp::service::{RoleClient, RunningService};
-use rmcp::transport::TokioChildProcess;
+use rmcp::service::{RoleClient, RunningService, ServiceError};
+use rmcp::transport::sse_client::SseClientConfig;
+use rmcp::transport::{SseClientTransport, TokioChildProcess};
 use rmcp::ServiceExt;
 use serde_json::Value;
+use std::sync::Arc;
 use tokio::process::Command;
+use tokio::sync::RwLock;
 
 /// MCP client that wraps the rmcp SDK
 ///
 /// This wraps a RunningService from rmcp to provide a simpler API
 pub struct McpClient {
-    service: RunningService<RoleClient, ()>,
+    /// Swapped for a fresh connection when reconnecting
+    ///
+    /// Requests clone the `Arc` and release the lock before awaiting, so a
+    /// reconnect never waits behind in-flight requests.
+    service: RwLock<Arc<RunningService<RoleClient, ()>>>,
+    params: McpConnectionParams,
+    reconnect: ReconnectPolicy,
 }
 
 impl McpClient {
-    /// Create a new MCP client by spawning a subprocess
-    pub async fn new(params: StdioConnectionParams) -> Result<Self> {
+    /// Create a new MCP client, spawning a subprocess or connecting over SSE
+    pub async fn new(params: impl Into<McpConnectionParams>) -> Result<Self> {
+        let params = params.into();
+        let service = Self::connect(&params).await?;
+
+        Ok(Self {
+            service: RwLock::new(Arc::new(service)),
+            params,
+            reconnect: ReconnectPolicy::disabled(),
+        })
+    }
+
+    /// Reconnect according to `policy` when the connection drops mid-request
+    pub fn with_reconnect(mut self, policy: ReconnectPolicy) -> Self {
+        self.reconnect = policy;
+        self
+    }
+
+    /// Establish a new connection to the server
+    async fn connect(params: &McpConnectionParams) -> Result<RunningService<RoleClient, ()>> {
+        let service = match params.clone() {
+            McpConnectionParams::Stdio(params) => Self::serve_stdio(params).await?,
+            McpConnectionParams::Sse(params) => Self::serve_sse(params).await?,
+        };
+
+        tracing::info!(
+            server_info = ?service.peer_info(),
+            "MCP client initialized successfully"
+        );
+
+        Ok(service)
+    }
+
+    /// The current connection
+    async fn service(&self) -> Arc<RunningService<RoleClient, ()>> {
+        self.service.read().await.clone()
+    }
+
+    /// Replace the dead connection `failed`, retrying with backoff per the
+    /// reconnect policy
+    ///
+    /// Does nothing if another request has already replaced `failed`.
+    async fn reconnect(
+        &self,
+        failed: &Arc<RunningService<RoleClient, ()>>,
+        cause: &str,
+    ) -> Result<()> {
+        let mut service = self.service.write().await;
+        if !Arc::ptr_eq(&service, failed) {
+            return Ok(());
+        }
+        tracing::warn!(error = %cause, "MCP connection lost, reconnecting");
+
+        let mut last_error = None;
+        for attempt in 1..=self.reconnect.max_attempts {
+            tokio::time::sleep(self.reconnect.delay(attempt)).await;
+            match Self::connect(&self.params).await {
+                Ok(new_service) => {
+                    tracing::info!(attempt, "Reconnected to MCP server");
+                    *service = Arc::new(new_service);
+                    return Ok(());
+                }
+                Err(e) => {
+                    tracing::warn!(attempt, error = %e, "MCP reconnect attempt failed");
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(anyhow::anyhow!(
+            "Failed to reconnect to MCP server after {} attempt(s): {}",
+            self.reconnect.max_attempts,
+            last_error.map_or_else(|| cause.to_string(), |e| e.to_string())
+        ))
+    }
+
+    /// Reconnect before sending a request if the connection is already known
+    /// to be gone, so the request is never sent to a dead server
+    async fn ensure_connected(&self) -> Result<bool> {
+        let service = self.service().await;
+        if self.reconnect.max_attempts == 0 || !service.is_transport_closed() {
+            return Ok(false);
+        }
+        self.reconnect(&service, "transport closed").await?;
+        Ok(true)
+    }
+
+    /// Whether a request on `service` failed because the connection is gone,
+    /// rather than being rejected by the server
+    fn should_reconnect(
+        &self,
+        service: &RunningService<RoleClient, ()>,
+        error: &ServiceError,
+    ) -> bool {
+        self.reconnect.max_attempts > 0
+            && (matches!(
+                error,
+                ServiceError::TransportSend(_)
+                    | ServiceError::TransportClosed
+                    | ServiceError::Cancelled { .. }
+            ) || service.is_transport_closed())
+    }
+
+    /// Spawn the server as a subprocess and talk to it over stdio
+    async fn serve_stdio(params: StdioConnectionParams) -> Result<RunningService<RoleClient, ()>> {
         tracing::debug!(
             command = %params.command,
             args = ?params.args,
@@ -39,21 +153,51 @@ impl McpClient {
         let transport = TokioChildProcess::new(command)?;
 
         // Initialize the MCP server connection
-        let service = ().serve(transport).await?;
+        Ok(().serve(transport).await?)
+    }
 
-        tracing::info!(
-            server_info = ?service.peer_info(),
-            "MCP client initialized successfully"
-        );
+    /// Connect to a remote server over HTTP+SSE
+    async fn serve_sse(params: SseConnectionParams) -> Result<RunningService<RoleClient, ()>> {
+        tracing::debug!(url = %params.url, "Initializing MCP client over SSE");
 
-        Ok(Self { service })
+        let mut headers = reqwest::header::HeaderMap::new();
+        for (key, value) in &params.headers {
+            headers.insert(
+                reqwest::header::HeaderName::from_bytes(key.as_bytes())?,
+                reqwest::header::HeaderValue::from_str(value)?,
+            );
+        }
+        let client = reqwest::Client::builder()
+            .default_headers(headers)
+            .build()?;
+
+        let transport = SseClientTransport::start_with_client(
+            client,
+            SseClientConfig {
+                sse_endpoint: params.url.into(),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+        Ok(().serve(transport).await?)
     }
 
     /// List all available tools from the MCP server
+    ///
+    /// Listing is safe to repeat, so it is retried once after reconnecting.
     pub async fn list_tools(&self) -> Result<Vec<McpToolInfo>> {
         tracing::debug!("Listing tools from MCP server");
 
-        let response = self.service.list_tools(Default::default()).await?;
+        self.ensure_connected().await?;
+        let service = self.service().await;
+        let response = match service.list_tools(Default::default()).await {
+            Err(e) if self.should_reconnect(&service, &e) => {
+                self.reconnect(&service, &e.to_string()).await?;
+                self.service().await.list_tools(Default::default()).await?
+            }
+            result => result?,
+        };
 
         let tools: Vec<McpToolInfo> = response
             .tools
@@ -71,15 +215,47 @@ impl McpClient {
     }
 
     /// Call a tool on the MCP server
+    ///
+    /// A dead connection is replaced before the call is sent. If the
+    /// connection drops while the call is in flight, the client reconnects
+    /// for later calls but returns the error instead of sending the call
+    /// again, since the tool may already have run.
     pub async fn call_tool(&self, name: &str, arguments: Value) -> Result<Vec<ToolContent>> {
         tracing::debug!(tool = %name, "Calling MCP tool");
 
+        // The restarted server may no longer offer this tool
+        if self.ensure_connected().await?
+            && !self
+                .list_tools()
+                .await?
+                .iter()
+                .any(|tool| tool.name == name)
+        {
+            return Err(anyhow::anyhow!(
+                "MCP tool '{}' is no longer available after reconnecting",
+                name
+            ));
+        }
+
         let params = CallToolRequestParam {
             name: name.to_string().into(),
             arguments: arguments.as_object().cloned(),
         };
 
-        let response = self.service.call_tool(params).await?;
+        let service = self.service().await;
+        let response = match service.call_tool(params).await {
+            Err(e) if self.should_reconnect(&service, &e) => {
+                if let Err(reconnect_error) = self.reconnect(&service, &e.to_string()).await {
+                    tracing::warn!(error = %reconnect_error, "MCP reconnect failed");
+                }
+                return Err(anyhow::anyhow!(
+                    "MCP tool '{}' call was interrupted and not retried, as it may already have run: {}",
+                    name,
+                    e
+                ));
+            }
+            result => result?,
+        };
 
         // Convert content to JSON values
         let content = response
@@ -95,6 +271,7 @@ impl McpClient {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::{free_addr, start_counting_echo_server, start_echo_server};
 
     #[test]
     fn test_connection_params() {
@@ -106,4 +283,126 @@ mod tests {
         assert_eq!(params.args.len(), 1);
         assert_eq!(params.env.len(), 1);
     }
+
+    #[test]
+    fn test_sse_connection_params() {
+        let params = SseConnectionParams::new("http://localhost:8000/sse")
+            .header("Authorization", "Bearer token");
+
+        assert_eq!(params.url, "http://localhost:8000/sse");
+        assert_eq!(params.headers["Authorization"], "Bearer token");
+        assert!(matches!(
+            McpConnectionParams::from(params),
+            McpConnectionParams::Sse(_)
+        ));
+    }
+
+    /// Runs against a live server, e.g. `MCP_SSE_URL=http://localhost:8000/sse`
+    #[tokio::test]
+    async fn test_sse_list_tools() {
+        let Ok(url) = std::env::var("MCP_SSE_URL") else {
+            eprintln!("MCP_SSE_URL not set, skipping MCP SSE integration test");
+            return;
+        };
+
+        let client = McpClient::new(SseConnectionParams::new(url)).await.unwrap();
+        let tools = client.list_tools().await.unwrap();
+        assert!(!tools.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_reconnects_after_server_restart() {
+        // The restarted server comes back at the same address
+        let addr = free_addr();
+        let server = start_echo_server(addr, "a").await;
+
+        let client = McpClient::new(SseConnectionParams::new(format!("http://{}/sse", addr)))
+            .await
+            .unwrap()
+            .with_reconnect(ReconnectPolicy {
+                max_attempts: 10,
+                backoff: std::time::Duration::from_millis(20),
+            });
+        let result = client
+            .call_tool("echo", serde_json::json!({ "n": 1 }))
+            .await
+            .unwrap();
+        assert_eq!(result[0]["text"], "a: {\"n\":1}");
+
+        // Drop the connection by stopping the server, then bring it back
+        server.cancel();
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        let server = start_echo_server(addr, "a").await;
+
+        // Listing is safe to repeat, so it's retried on the new connection
+        assert_eq!(client.list_tools().await.unwrap()[0].name, "echo");
+        let result = client
+            .call_tool("echo", serde_json::json!({ "n": 2 }))
+            .await
+            .unwrap();
+        assert_eq!(result[0]["text"], "a: {\"n\":2}");
+
+        server.cancel();
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        let _server = start_echo_server(addr, "a").await;
+
+        // A call interrupted by the drop isn't sent again, since it may have
+        // run already, but the next call goes through the new connection
+        let error = client
+            .call_tool("echo", serde_json::json!({ "n": 3 }))
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("not retried"), "{}", error);
+        let result = client
+            .call_tool("echo", serde_json::json!({ "n": 4 }))
+            .await
+            .unwrap();
+        assert_eq!(result[0]["text"], "a: {\"n\":4}");
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_failures_reconnect_once() {
+        let addr = free_addr();
+        let server = start_echo_server(addr, "a").await;
+
+        let client = McpClient::new(SseConnectionParams::new(format!("http://{}/sse", addr)))
+            .await
+            .unwrap()
+            .with_reconnect(ReconnectPolicy {
+                max_attempts: 10,
+                backoff: std::time::Duration::from_millis(20),
+            });
+
+        server.cancel();
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        let (_server, connections) = start_counting_echo_server(addr, "a").await;
+
+        // Every request sees the dead connection, but only the first replaces it
+        let (first, second, third) = tokio::join!(
+            client.list_tools(),
+            client.list_tools(),
+            client.list_tools()
+        );
+        for tools in [first, second, third] {
+            assert_eq!(tools.unwrap()[0].name, "echo");
+        }
+        assert_eq!(connections.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_connection_errors_returned_without_reconnect_policy() {
+        let addr = free_addr();
+        let server = start_echo_server(addr, "a").await;
+
+        let client = McpClient::new(SseConnectionParams::new(format!("http://{}/sse", addr)))
+            .await
+            .unwrap();
+        server.cancel();
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+        assert!(client
+            .call_tool("echo", serde_json::json!({}))
+            .await
+            .is_err());
+    }
 }
@@ -1,6 +1,7 @@
 //! Connection parameters for MCP servers
 
 use std::collections::HashMap;
+use std::time::Duration;
 
 /// Parameters for connecting to an MCP server via stdio subprocess
 #[derive(Debug, Clone)]
@@ -32,3 +33,80 @@ impl StdioConnectionParams {
         self
     }
 }
+
+/// Parameters for connecting to a remote MCP server over HTTP+SSE
+#[derive(Debug, Clone)]
+pub struct SseConnectionParams {
+    /// URL of the server's SSE endpoint (e.g. `http://localhost:8000/sse`)
+    pub url: String,
+    /// Headers sent with every request, e.g. for authentication
+    pub headers: HashMap<String, String>,
+}
+
+impl SseConnectionParams {
+    /// Create new connection parameters for the given SSE endpoint
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            headers: HashMap::new(),
+        }
+    }
+
+    /// Add a header sent with every request
+    pub fn header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(key.into(), value.into());
+        self
+    }
+}
+
+/// How to reach an MCP server
+#[derive(Debug, Clone)]
+pub enum McpConnectionParams {
+    /// Local server spawned as a subprocess
+    Stdio(StdioConnectionParams),
+    /// Remote server reached over HTTP+SSE
+    Sse(SseConnectionParams),
+}
+
+impl From<StdioConnectionParams> for McpConnectionParams {
+    fn from(params: StdioConnectionParams) -> Self {
+        Self::Stdio(params)
+    }
+}
+
+impl From<SseConnectionParams> for McpConnectionParams {
+    fn from(params: SseConnectionParams) -> Self {
+        Self::Sse(params)
+    }
+}
+
+/// How `McpClient` recovers when the connection to its server drops
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReconnectPolicy {
+    /// Reconnect attempts per failed request (0 disables reconnecting)
+    pub max_attempts: u32,
+    /// Delay before the first attempt, doubled after each failure
+    pub backoff: Duration,
+}
+
+impl ReconnectPolicy {
+    /// Never reconnect; connection errors are returned as-is
+    pub fn disabled() -> Self {
+        Self {
+            max_attempts: 0,
+            backoff: Duration::ZERO,
+        }
+    }
+
+    /// Delay before the given attempt (1-based)
+    pub fn delay(&self, attempt: u32) -> Duration {
+        self.backoff
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+    }
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self::disabled()
+    }
+}
@@ -9,9 +9,14 @@ pub mod tool_wrapper;
 pub mod toolset;
 pub mod types;
 
+#[cfg(test)]
+mod test_support;
+
 // Re-exports
 pub use client::McpClient;
-pub use connection::StdioConnectionParams;
+pub use connection::{
+    McpConnectionParams, ReconnectPolicy, SseConnectionParams, StdioConnectionParams,
+};
 pub use tool_wrapper::McpToolWrapper;
 pub use toolset::McpToolset;
 pub use types::McpToolInfo;
@@ -0,0 +1,86 @@
+//! In-process MCP server for tests
+
+use rmcp::model::{
+    CallToolRequestParam, CallToolResult, Content, ListToolsResult, PaginatedRequestParam,
+    ServerCapabilities, ServerInfo, Tool,
+};
+use rmcp::service::{RequestContext, RoleServer};
+use rmcp::transport::sse_server::SseServer;
+use rmcp::{ErrorData, ServerHandler};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+
+/// MCP server with a single `echo` tool that replies `"{label}: {arguments}"`
+#[derive(Clone)]
+struct Echo {
+    label: String,
+}
+
+impl ServerHandler for Echo {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            ..Default::default()
+        }
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, ErrorData> {
+        let schema = serde_json::json!({ "type": "object" });
+        Ok(ListToolsResult {
+            tools: vec![Tool::new(
+                "echo",
+                "Echo the arguments back",
+                Arc::new(schema.as_object().unwrap().clone()),
+            )],
+            next_cursor: None,
+        })
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let arguments = serde_json::Value::Object(request.arguments.unwrap_or_default());
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "{}: {}",
+            self.label, arguments
+        ))]))
+    }
+}
+
+/// Address of a port that is currently free
+pub fn free_addr() -> SocketAddr {
+    std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+}
+
+/// Serve the echo server over SSE at `http://{addr}/sse` until cancelled
+pub async fn start_echo_server(addr: SocketAddr, label: &str) -> CancellationToken {
+    start_counting_echo_server(addr, label).await.0
+}
+
+/// Like [`start_echo_server`], also counting the client connections accepted
+pub async fn start_counting_echo_server(
+    addr: SocketAddr,
+    label: &str,
+) -> (CancellationToken, Arc<AtomicUsize>) {
+    let label = label.to_string();
+    let connections = Arc::new(AtomicUsize::new(0));
+    let counter = connections.clone();
+    let token = SseServer::serve(addr).await.unwrap().with_service(move || {
+        counter.fetch_add(1, Ordering::SeqCst);
+        Echo {
+            label: label.clone(),
+        }
+    });
+    (token, connections)
+}
@@ -11,20 +11,33 @@ use zdk_core::{Error, Result, Tool, ToolContext, ToolResponse};
 /// Wrapper that adapts an MCP tool to the ZDK Tool trait
 pub struct McpToolWrapper {
     mcp_tool: McpToolInfo,
+    /// Name exposed to agents, which may carry a toolset prefix
+    name: String,
     client: Arc<Mutex<Option<McpClient>>>,
 }
 
 impl McpToolWrapper {
     /// Create a new MCP tool wrapper
     pub fn new(mcp_tool: McpToolInfo, client: Arc<Mutex<Option<McpClient>>>) -> Self {
-        Self { mcp_tool, client }
+        Self {
+            name: mcp_tool.name.clone(),
+            mcp_tool,
+            client,
+        }
+    }
+
+    /// Expose the tool as `{prefix}__{name}`; the server is still called with
+    /// the original name
+    pub fn with_name_prefix(mut self, prefix: &str) -> Self {
+        self.name = format!("{}__{}", prefix, self.mcp_tool.name);
+        self
     }
 }
 
 #[async_trait]
 impl Tool for McpToolWrapper {
     fn name(&self) -> &str {
-        &self.mcp_tool.name
+        &self.name
     }
 
     fn description(&self) -> &str {
@@ -38,7 +51,7 @@ impl Tool for McpToolWrapper {
     async fn execute(&self, ctx: Arc<dyn ToolContext>, params: Value) -> Result<ToolResponse> {
         tracing::debug!(
             invocation_id = %ctx.invocation_id(),
-            tool = %self.mcp_tool.name,
+            tool = %self.name,
             "Executing MCP tool"
         );
 
@@ -59,9 +72,8 @@ impl Tool for McpToolWrapper {
         );
 
         // Convert MCP result to ToolResponse
-        Ok(ToolResponse {
-            result: serde_json::to_value(&result)
-                .map_err(|e| Error::Other(anyhow::anyhow!("Failed to serialize result: {}", e)))?,
-        })
+        Ok(ToolResponse::new(serde_json::to_value(&result).map_err(
+            |e| Error::Other(anyhow::anyhow!("Failed to serialize result: {}", e)),
+        )?))
     }
 }
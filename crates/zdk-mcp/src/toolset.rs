@@ -1,19 +1,22 @@
 //! MCP Toolset implementation
 
 use crate::client::McpClient;
-use crate::connection::StdioConnectionParams;
+use crate::connection::{McpConnectionParams, ReconnectPolicy};
 use crate::tool_wrapper::McpToolWrapper;
 use async_trait::async_trait;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Mutex;
 use zdk_core::{InvocationContext, Result, Tool, Toolset};
 
 /// A toolset that dynamically loads tools from an MCP server
 pub struct McpToolset {
     name: String,
-    connection_params: StdioConnectionParams,
+    connection_params: McpConnectionParams,
     client: Arc<Mutex<Option<McpClient>>>,
     tool_filter: Option<Vec<String>>,
+    name_prefix: Option<String>,
+    reconnect: ReconnectPolicy,
 }
 
 impl McpToolset {
@@ -21,6 +24,16 @@ impl McpToolset {
     pub fn builder() -> McpToolsetBuilder {
         McpToolsetBuilder::new()
     }
+
+    /// Reconnect to a restarted server up to `max_attempts` times, waiting
+    /// `backoff` before the first attempt and doubling it after each failure
+    pub fn with_reconnect(mut self, max_attempts: u32, backoff: Duration) -> Self {
+        self.reconnect = ReconnectPolicy {
+            max_attempts,
+            backoff,
+        };
+        self
+    }
 }
 
 #[async_trait]
@@ -40,7 +53,7 @@ impl Toolset for McpToolset {
                 .map_err(|e| {
                     zdk_core::Error::Other(anyhow::anyhow!("Failed to create MCP client: {}", e))
                 })?;
-            *client_guard = Some(client);
+            *client_guard = Some(client.with_reconnect(self.reconnect));
         }
 
         let client = client_guard
@@ -73,7 +86,12 @@ impl Toolset for McpToolset {
         let zdk_tools: Vec<Arc<dyn Tool>> = filtered
             .into_iter()
             .map(|mcp_tool| {
-                Arc::new(McpToolWrapper::new(mcp_tool, self.client.clone())) as Arc<dyn Tool>
+                let wrapper = McpToolWrapper::new(mcp_tool, self.client.clone());
+                let wrapper = match &self.name_prefix {
+                    Some(prefix) => wrapper.with_name_prefix(prefix),
+                    None => wrapper,
+                };
+                Arc::new(wrapper) as Arc<dyn Tool>
             })
             .collect();
 
@@ -84,8 +102,9 @@ impl Toolset for McpToolset {
 /// Builder for McpToolset
 pub struct McpToolsetBuilder {
     name: Option<String>,
-    connection_params: Option<StdioConnectionParams>,
+    connection_params: Option<McpConnectionParams>,
     tool_filter: Option<Vec<String>>,
+    name_prefix: Option<String>,
 }
 
 impl McpToolsetBuilder {
@@ -94,6 +113,7 @@ impl McpToolsetBuilder {
             name: None,
             connection_params: None,
             tool_filter: None,
+            name_prefix: None,
         }
     }
 
@@ -103,18 +123,25 @@ impl McpToolsetBuilder {
         self
     }
 
-    /// Set the connection parameters
-    pub fn connection(mut self, params: StdioConnectionParams) -> Self {
-        self.connection_params = Some(params);
+    /// Set the connection parameters (stdio or SSE)
+    pub fn connection(mut self, params: impl Into<McpConnectionParams>) -> Self {
+        self.connection_params = Some(params.into());
         self
     }
 
-    /// Set a filter for which tools to include
+    /// Set a filter for which tools to include (by their names on the server)
     pub fn tool_filter(mut self, filter: Vec<String>) -> Self {
         self.tool_filter = Some(filter);
         self
     }
 
+    /// Expose tools as `{prefix}__{name}`, so tools from different servers
+    /// can't collide
+    pub fn name_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.name_prefix = Some(prefix.into());
+        self
+    }
+
     /// Build the McpToolset
     pub fn build(self) -> Result<McpToolset> {
         let name = self
@@ -129,6 +156,84 @@ impl McpToolsetBuilder {
             connection_params,
             client: Arc::new(Mutex::new(None)),
             tool_filter: self.tool_filter,
+            name_prefix: self.name_prefix,
+            reconnect: ReconnectPolicy::disabled(),
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connection::SseConnectionParams;
+    use crate::test_support::{free_addr, start_echo_server};
+    use zdk_core::{Content, ReadonlyContext, ToolContext};
+
+    struct TestContext;
+
+    impl InvocationContext for TestContext {
+        fn invocation_id(&self) -> &str {
+            "inv-1"
+        }
+
+        fn user_content(&self) -> Option<&Content> {
+            None
+        }
+    }
+
+    impl ReadonlyContext for TestContext {
+        fn app_name(&self) -> &str {
+            "test-app"
+        }
+
+        fn user_id(&self) -> &str {
+            "user1"
+        }
+
+        fn session_id(&self) -> &str {
+            "session1"
+        }
+    }
+
+    impl ToolContext for TestContext {
+        fn function_call_id(&self) -> &str {
+            "call-1"
+        }
+
+        fn invocation_id(&self) -> &str {
+            "inv-1"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_name_prefix_avoids_collisions() {
+        let mut tools = std::collections::HashMap::new();
+        for label in ["alpha", "beta"] {
+            let addr = free_addr();
+            let _server = start_echo_server(addr, label).await;
+            let toolset = McpToolset::builder()
+                .name(label)
+                .connection(SseConnectionParams::new(format!("http://{}/sse", addr)))
+                .name_prefix(label)
+                .build()
+                .unwrap();
+
+            for tool in toolset.get_tools(&TestContext).await.unwrap() {
+                tools.insert(tool.name().to_string(), tool);
+            }
+        }
+
+        // Both servers expose `echo`, but neither overwrites the other
+        assert_eq!(tools.len(), 2);
+        for label in ["alpha", "beta"] {
+            let response = tools[&format!("{}__echo", label)]
+                .execute(Arc::new(TestContext), serde_json::json!({ "n": 1 }))
+                .await
+                .unwrap();
+            assert_eq!(
+                response.result[0]["text"],
+                format!("{}: {{\"n\":1}}", label)
+            );
+        }
+    }
+}
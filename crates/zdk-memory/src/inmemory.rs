@@ -70,10 +70,8 @@ impl MemoryService for InMemoryMemoryService {
 
             // Extract words from content
             let mut words = HashSet::new();
-            for part in &content.parts {
-                if let zdk_core::Part::Text { text } = part {
-                    words.extend(extract_words(text));
-                }
+            for text in content.parts.iter().filter_map(zdk_core::Part::as_text) {
+                words.extend(extract_words(text));
             }
 
             // Skip if no words found
@@ -45,9 +45,11 @@
 //! ```
 
 mod inmemory;
+mod scoped;
 mod service;
 
 pub use inmemory::InMemoryMemoryService;
+pub use scoped::ScopedMemoryStore;
 pub use service::*;
 
 #[cfg(test)]
@@ -0,0 +1,47 @@
+//! Memory service scoped to a single user
+
+use crate::{MemoryEntry, MemoryService, SearchRequest};
+use async_trait::async_trait;
+use std::sync::Arc;
+use zdk_core::{MemoryStore, Result};
+
+/// [`MemoryStore`] that searches one user's memories in a [`MemoryService`]
+///
+/// Handed to each invocation so agents can recall past sessions without
+/// knowing the app or user.
+#[derive(Clone)]
+pub struct ScopedMemoryStore {
+    service: Arc<dyn MemoryService>,
+    app_name: String,
+    user_id: String,
+}
+
+impl ScopedMemoryStore {
+    /// Scope `service` to the given app and user
+    pub fn new(
+        service: Arc<dyn MemoryService>,
+        app_name: impl Into<String>,
+        user_id: impl Into<String>,
+    ) -> Self {
+        Self {
+            service,
+            app_name: app_name.into(),
+            user_id: user_id.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl MemoryStore for ScopedMemoryStore {
+    async fn search_memory(&self, query: &str) -> Result<Vec<MemoryEntry>> {
+        let response = self
+            .service
+            .search(SearchRequest {
+                query: query.to_string(),
+                user_id: self.user_id.clone(),
+                app_name: self.app_name.clone(),
+            })
+            .await?;
+        Ok(response.memories)
+    }
+}
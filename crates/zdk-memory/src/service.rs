@@ -1,11 +1,12 @@
 //! Memory service trait and types
 
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
 use std::sync::Arc;
-use zdk_core::{Content, Result};
+use zdk_core::Result;
 use zdk_session::Session;
 
+pub use zdk_core::MemoryEntry;
+
 /// Memory service trait for long-term knowledge storage.
 ///
 /// The service ingests sessions into memory so that they can be used for
@@ -42,25 +43,3 @@ pub struct SearchResponse {
     /// List of matching memory entries
     pub memories: Vec<MemoryEntry>,
 }
-
-/// A single memory entry
-#[derive(Debug, Clone)]
-pub struct MemoryEntry {
-    /// Content of the memory
-    pub content: Option<Content>,
-    /// Author of the memory
-    pub author: String,
-    /// Timestamp when the original content happened
-    pub timestamp: DateTime<Utc>,
-}
-
-impl MemoryEntry {
-    /// Create a new memory entry
-    pub fn new(content: Option<Content>, author: String, timestamp: DateTime<Utc>) -> Self {
-        Self {
-            content,
-            author,
-            timestamp,
-        }
-    }
-}
@@ -41,4 +41,6 @@ pub use error::{OpenApiError, Result};
 pub use parser::OpenApiParser;
 pub use rest_api_tool::RestApiTool;
 pub use toolset::OpenApiToolset;
-pub use types::{ApiParameter, OperationEndpoint, ParsedOperation};
+pub use types::{
+    ApiParameter, OperationEndpoint, ParsedOperation, SkippedOperation, ToolsetReport,
+};
@@ -6,11 +6,16 @@
 use crate::error::{OpenApiError, Result};
 use crate::types::{
     ApiParameter, OperationEndpoint, ParameterLocation, ParsedOperation, SecurityRequirement,
+    SkippedOperation,
 };
-use openapiv3::{OpenAPI, Operation, Parameter, ParameterSchemaOrContent, ReferenceOr};
+use openapiv3::{OpenAPI, Operation, Parameter, ParameterSchemaOrContent, PathItem, ReferenceOr};
 use serde_json::Value;
+use std::io::Read;
 use tracing::{debug, warn};
 
+/// How many `$ref` hops to follow before giving up, guarding against cycles.
+const MAX_REF_DEPTH: usize = 16;
+
 /// Parser for OpenAPI specifications.
 pub struct OpenApiParser {
     spec: OpenAPI,
@@ -56,9 +61,39 @@ impl OpenApiParser {
         Ok(Self { spec })
     }
 
+    /// Parse an OpenAPI spec from raw bytes.
+    ///
+    /// Automatically detects JSON or YAML format.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        // Try JSON first
+        let spec = serde_json::from_slice(bytes)
+            .or_else(|_| serde_yaml::from_slice(bytes))
+            .map_err(|e| OpenApiError::ParseError(e.to_string()))?;
+
+        Ok(Self { spec })
+    }
+
+    /// Read and parse an OpenAPI spec from any reader.
+    ///
+    /// Automatically detects JSON or YAML format.
+    pub fn from_reader(mut reader: impl Read) -> Result<Self> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        Self::from_bytes(&bytes)
+    }
+
     /// Parse the OpenAPI spec and extract all operations.
     pub fn parse(&self) -> Result<Vec<ParsedOperation>> {
+        self.parse_with_skipped().map(|(operations, _)| operations)
+    }
+
+    /// Parse all operations, also returning those that were skipped.
+    ///
+    /// Skipped operations are reported as `(operation, reason)` pairs, where
+    /// the operation is formatted as `METHOD /path`.
+    pub fn parse_with_skipped(&self) -> Result<(Vec<ParsedOperation>, Vec<SkippedOperation>)> {
         let mut operations = Vec::new();
+        let mut skipped = Vec::new();
 
         // Get base URL from servers
         let base_url = self
@@ -72,12 +107,13 @@ impl OpenApiParser {
 
         // Iterate through all paths
         for (path, path_item_ref) in &self.spec.paths.paths {
-            let path_item = match path_item_ref {
-                ReferenceOr::Item(item) => item,
-                ReferenceOr::Reference { .. } => {
-                    warn!("Path references not yet supported: {}", path);
-                    continue;
-                }
+            let Some(path_item) = self.resolve_path_item(path_item_ref) else {
+                warn!("Could not resolve path reference for {}", path);
+                skipped.push((
+                    format!("* {}", path),
+                    "unresolvable path reference".to_string(),
+                ));
+                continue;
             };
 
             // Check all HTTP methods
@@ -109,6 +145,10 @@ impl OpenApiParser {
                                 path,
                                 e
                             );
+                            skipped.push((
+                                format!("{} {}", method_name.to_uppercase(), path),
+                                e.to_string(),
+                            ));
                         }
                     }
                 }
@@ -116,7 +156,7 @@ impl OpenApiParser {
         }
 
         debug!("Parsed {} operations", operations.len());
-        Ok(operations)
+        Ok((operations, skipped))
     }
 
     fn parse_operation(
@@ -148,22 +188,14 @@ impl OpenApiParser {
         let mut parameters = Vec::new();
 
         // Add path-level parameters
-        for param_ref in path_params {
-            if let ReferenceOr::Item(param) = param_ref
-                && let Some(api_param) = self.parse_parameter(param)?
-            {
-                parameters.push(api_param);
-            }
-        }
-
-        // Add operation-level parameters
-        for param_ref in &operation.parameters {
-            let param = match param_ref {
-                ReferenceOr::Item(p) => p,
-                ReferenceOr::Reference { .. } => {
-                    warn!("Parameter references not yet supported");
-                    continue;
-                }
+        // Add path-level and operation-level parameters
+        for param_ref in path_params.iter().chain(&operation.parameters) {
+            let Some(param) = self.resolve_parameter(param_ref) else {
+                warn!(
+                    "Could not resolve parameter reference in {} {}",
+                    method, path
+                );
+                continue;
             };
 
             if let Some(api_param) = self.parse_parameter(param)? {
@@ -247,6 +279,8 @@ impl OpenApiParser {
 
         Ok(ParsedOperation {
             name,
+            operation_id,
+            tags: operation.tags.clone(),
             description,
             endpoint: OperationEndpoint {
                 base_url: base_url.to_string(),
@@ -259,6 +293,44 @@ impl OpenApiParser {
         })
     }
 
+    /// Resolve a path item, following `#/paths/...` references.
+    ///
+    /// Returns `None` for external references or ones that don't point at a
+    /// path item in this spec.
+    fn resolve_path_item<'a>(&'a self, item: &'a ReferenceOr<PathItem>) -> Option<&'a PathItem> {
+        let mut current = item;
+        for _ in 0..MAX_REF_DEPTH {
+            match current {
+                ReferenceOr::Item(item) => return Some(item),
+                ReferenceOr::Reference { reference } => {
+                    let pointer = reference.strip_prefix("#/paths/")?;
+                    current = self.spec.paths.paths.get(&unescape_pointer(pointer))?;
+                }
+            }
+        }
+        None
+    }
+
+    /// Resolve a parameter, following `#/components/parameters/...` references.
+    fn resolve_parameter<'a>(&'a self, param: &'a ReferenceOr<Parameter>) -> Option<&'a Parameter> {
+        let mut current = param;
+        for _ in 0..MAX_REF_DEPTH {
+            match current {
+                ReferenceOr::Item(param) => return Some(param),
+                ReferenceOr::Reference { reference } => {
+                    let name = reference.strip_prefix("#/components/parameters/")?;
+                    current = self
+                        .spec
+                        .components
+                        .as_ref()?
+                        .parameters
+                        .get(&unescape_pointer(name))?;
+                }
+            }
+        }
+        None
+    }
+
     fn parse_parameter(&self, param: &Parameter) -> Result<Option<ApiParameter>> {
         let param_data = match param {
             Parameter::Query { parameter_data, .. } => (parameter_data, ParameterLocation::Query),
@@ -313,6 +385,11 @@ impl OpenApiParser {
     }
 }
 
+/// Undo JSON pointer escaping (`~1` for `/`, `~0` for `~`) in a reference segment.
+fn unescape_pointer(segment: &str) -> String {
+    segment.replace("~1", "/").replace("~0", "~")
+}
+
 /// Convert a string to snake_case.
 fn to_snake_case(s: &str) -> String {
     let mut result = String::new();
@@ -350,4 +427,74 @@ mod tests {
         assert_eq!(to_snake_case("kebab-case"), "kebab_case");
         assert_eq!(to_snake_case("listUsers"), "list_users");
     }
+
+    #[test]
+    fn test_resolves_parameter_and_path_refs() {
+        let spec = r##"
+openapi: 3.0.0
+info:
+  title: Ref API
+  version: 1.0.0
+paths:
+  /users:
+    get:
+      operationId: listUsers
+      parameters:
+        - $ref: '#/components/parameters/PageSize'
+      responses:
+        '200':
+          description: Success
+  /orders:
+    parameters:
+      - $ref: '#/components/parameters/Tenant'
+    get:
+      operationId: listOrders
+      parameters:
+        - $ref: '#/components/parameters/PageSize'
+      responses:
+        '200':
+          description: Success
+  /v1/orders:
+    $ref: '#/paths/~1orders'
+  /broken:
+    $ref: 'other.yaml#/paths/~1broken'
+components:
+  parameters:
+    PageSize:
+      name: pageSize
+      in: query
+      schema:
+        type: integer
+    Tenant:
+      $ref: '#/components/parameters/TenantHeader'
+    TenantHeader:
+      name: X-Tenant
+      in: header
+      required: true
+      schema:
+        type: string
+"##;
+
+        let parser = OpenApiParser::parse_from_str(spec).unwrap();
+        let (operations, skipped) = parser.parse_with_skipped().unwrap();
+
+        let param_names = |path: &str| -> Vec<String> {
+            operations
+                .iter()
+                .find(|op| op.endpoint.path == path)
+                .unwrap()
+                .parameters
+                .iter()
+                .map(|p| p.original_name.clone())
+                .collect()
+        };
+
+        assert_eq!(operations.len(), 3);
+        assert_eq!(param_names("/users"), vec!["pageSize"]);
+        assert_eq!(param_names("/orders"), vec!["X-Tenant", "pageSize"]);
+        assert_eq!(param_names("/v1/orders"), vec!["X-Tenant", "pageSize"]);
+
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].0, "* /broken");
+    }
 }
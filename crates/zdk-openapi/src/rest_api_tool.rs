@@ -248,7 +248,7 @@ impl Tool for RestApiTool {
                     source: e.into(),
                 })?;
 
-        Ok(ToolResponse { result })
+        Ok(ToolResponse::new(result))
     }
 }
 
@@ -259,6 +259,8 @@ mod tests {
     fn create_test_operation() -> ParsedOperation {
         ParsedOperation {
             name: "get_user".to_string(),
+            operation_id: "getUser".to_string(),
+            tags: vec!["users".to_string()],
             description: "Get user by ID".to_string(),
             endpoint: OperationEndpoint {
                 base_url: "https://api.example.com".to_string(),
@@ -4,9 +4,11 @@ use crate::auth::AuthConfig;
 use crate::error::Result;
 use crate::parser::OpenApiParser;
 use crate::rest_api_tool::RestApiTool;
-use crate::types::ParsedOperation;
+use crate::types::{ParsedOperation, ToolsetReport};
+use std::collections::HashSet;
+use std::io::Read;
 use std::sync::Arc;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 use zdk_tool::Tool;
 
 /// A collection of tools generated from an OpenAPI specification.
@@ -37,6 +39,42 @@ pub struct OpenApiToolset {
     tools: Vec<Arc<dyn Tool>>,
     /// Original parsed operations (for recreating tools with auth)
     operations: Vec<ParsedOperation>,
+    /// How the spec was turned into tools
+    report: ToolsetReport,
+    /// Authentication applied to every tool
+    auth: Option<AuthConfig>,
+    /// Which operations become tools
+    filter: OperationFilter,
+}
+
+/// Operation whitelists and blacklist set through the builder methods.
+#[derive(Debug, Clone, Default)]
+struct OperationFilter {
+    include_operations: HashSet<String>,
+    exclude_operations: HashSet<String>,
+    include_tags: HashSet<String>,
+}
+
+impl OperationFilter {
+    /// Whether `op` should become a tool.
+    ///
+    /// With no whitelist every operation is included; otherwise it must match
+    /// a whitelisted operation ID or tag. Excluded operations always lose.
+    fn allows(&self, op: &ParsedOperation) -> bool {
+        let matches_id =
+            |ids: &HashSet<String>| ids.contains(&op.operation_id) || ids.contains(&op.name);
+
+        if matches_id(&self.exclude_operations) {
+            return false;
+        }
+
+        if self.include_operations.is_empty() && self.include_tags.is_empty() {
+            return true;
+        }
+
+        matches_id(&self.include_operations)
+            || op.tags.iter().any(|tag| self.include_tags.contains(tag))
+    }
 }
 
 impl OpenApiToolset {
@@ -106,22 +144,79 @@ impl OpenApiToolset {
         Self::from_parser(parser)
     }
 
+    /// Parse an OpenAPI spec from raw bytes and generate tools.
+    ///
+    /// Automatically detects JSON or YAML format, which makes it convenient for
+    /// specs embedded with `include_bytes!` or loaded from a database.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use zdk_openapi::OpenApiToolset;
+    ///
+    /// let spec = std::fs::read("./api/openapi.yaml")?;
+    /// let toolset = OpenApiToolset::from_bytes(&spec)?;
+    /// # Ok::<(), zdk_openapi::OpenApiError>(())
+    /// ```
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        debug!("Parsing OpenAPI spec from {} bytes", bytes.len());
+        let parser = OpenApiParser::from_bytes(bytes)?;
+        Self::from_parser(parser)
+    }
+
+    /// Read an OpenAPI spec from any reader and generate tools.
+    ///
+    /// Automatically detects JSON or YAML format.
+    pub fn from_reader(reader: impl Read) -> Result<Self> {
+        debug!("Parsing OpenAPI spec from reader");
+        let parser = OpenApiParser::from_reader(reader)?;
+        Self::from_parser(parser)
+    }
+
     /// Create toolset from an OpenAPI parser.
     fn from_parser(parser: OpenApiParser) -> Result<Self> {
-        let operations = parser.parse()?;
+        let (mut operations, skipped) = parser.parse_with_skipped()?;
         info!("Parsed {} operations from OpenAPI spec", operations.len());
 
-        let tools: Vec<Arc<dyn Tool>> = operations
+        let renamed = disambiguate_names(&mut operations);
+
+        let report = ToolsetReport {
+            total_operations: operations.len() + skipped.len(),
+            generated_tools: 0,
+            skipped,
+            renamed,
+        };
+
+        let mut toolset = Self {
+            tools: Vec::new(),
+            operations,
+            report,
+            auth: None,
+            filter: OperationFilter::default(),
+        };
+        toolset.rebuild_tools();
+
+        Ok(toolset)
+    }
+
+    /// Recreate the tools from the parsed operations, applying the current
+    /// filters and authentication.
+    fn rebuild_tools(&mut self) {
+        self.tools = self
+            .operations
             .iter()
+            .filter(|op| self.filter.allows(op))
             .map(|op| {
-                let tool = RestApiTool::from_parsed_operation(op.clone());
+                let mut tool = RestApiTool::from_parsed_operation(op.clone());
+                if let Some(auth) = &self.auth {
+                    tool = tool.with_auth(auth.clone());
+                }
                 Arc::new(tool) as Arc<dyn Tool>
             })
             .collect();
 
-        debug!("Generated {} tools", tools.len());
-
-        Ok(Self { tools, operations })
+        debug!("Generated {} tools", self.tools.len());
+        self.report.generated_tools = self.tools.len();
     }
 
     /// Configure authentication for all tools in the toolset.
@@ -138,16 +233,64 @@ impl OpenApiToolset {
     pub fn with_auth(mut self, auth: AuthConfig) -> Self {
         info!("Configuring authentication for all tools");
 
-        // Recreate tools from operations with auth
-        self.tools = self
-            .operations
-            .iter()
-            .map(|op| {
-                let tool = RestApiTool::from_parsed_operation(op.clone()).with_auth(auth.clone());
-                Arc::new(tool) as Arc<dyn Tool>
-            })
-            .collect();
+        self.auth = Some(auth);
+        self.rebuild_tools();
+        self
+    }
 
+    /// Only generate tools for the given operations.
+    ///
+    /// Operations are matched by their OpenAPI `operationId` (or the generated
+    /// snake_case tool name). Combined with [`include_tags`](Self::include_tags),
+    /// an operation is kept if it matches either whitelist.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use zdk_openapi::OpenApiToolset;
+    ///
+    /// let toolset = OpenApiToolset::from_file("./api/openapi.yaml")?
+    ///     .include_operations(&["listUsers", "getUser"]);
+    /// # Ok::<(), zdk_openapi::OpenApiError>(())
+    /// ```
+    pub fn include_operations(mut self, operation_ids: &[&str]) -> Self {
+        self.filter
+            .include_operations
+            .extend(operation_ids.iter().map(|id| id.to_string()));
+        self.rebuild_tools();
+        self
+    }
+
+    /// Never generate tools for the given operations.
+    ///
+    /// Operations are matched like in [`include_operations`](Self::include_operations)
+    /// and are dropped even if they are also whitelisted.
+    pub fn exclude_operations(mut self, operation_ids: &[&str]) -> Self {
+        self.filter
+            .exclude_operations
+            .extend(operation_ids.iter().map(|id| id.to_string()));
+        self.rebuild_tools();
+        self
+    }
+
+    /// Only generate tools for operations carrying at least one of the given
+    /// OpenAPI tags.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use zdk_openapi::OpenApiToolset;
+    ///
+    /// let toolset = OpenApiToolset::from_file("./api/openapi.yaml")?
+    ///     .include_tags(&["users"])
+    ///     .exclude_operations(&["deleteUser"]);
+    /// # Ok::<(), zdk_openapi::OpenApiError>(())
+    /// ```
+    pub fn include_tags(mut self, tags: &[&str]) -> Self {
+        self.filter
+            .include_tags
+            .extend(tags.iter().map(|tag| tag.to_string()));
+        self.rebuild_tools();
         self
     }
 
@@ -178,6 +321,26 @@ impl OpenApiToolset {
         self.tools.iter().map(|t| t.name().to_string()).collect()
     }
 
+    /// Get a report of how the spec was turned into tools.
+    ///
+    /// Lists skipped operations and tools renamed to avoid collisions.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use zdk_openapi::OpenApiToolset;
+    ///
+    /// let toolset = OpenApiToolset::from_file("./api/openapi.yaml")?;
+    /// let report = toolset.report();
+    /// for (operation, reason) in &report.skipped {
+    ///     println!("Skipped {}: {}", operation, reason);
+    /// }
+    /// # Ok::<(), zdk_openapi::OpenApiError>(())
+    /// ```
+    pub fn report(&self) -> ToolsetReport {
+        self.report.clone()
+    }
+
     /// Get the number of tools in the toolset.
     pub fn len(&self) -> usize {
         self.tools.len()
@@ -189,9 +352,44 @@ impl OpenApiToolset {
     }
 }
 
+/// Rename operations whose tool names collide with an earlier operation.
+///
+/// A duplicate first gets the HTTP method appended (`get_user_post`), falling
+/// back to a numeric suffix if that is taken too. Returns `(original, new)`
+/// pairs for every renamed tool.
+fn disambiguate_names(operations: &mut [ParsedOperation]) -> Vec<(String, String)> {
+    let mut used = HashSet::new();
+    let mut renamed = Vec::new();
+
+    for op in operations.iter_mut() {
+        if used.insert(op.name.clone()) {
+            continue;
+        }
+
+        let original = op.name.clone();
+        let mut candidate = format!("{}_{}", original, op.endpoint.method.to_lowercase());
+        let mut counter = 2;
+        while used.contains(&candidate) {
+            candidate = format!("{}_{}", original, counter);
+            counter += 1;
+        }
+
+        warn!(
+            "Tool name '{}' already used, renaming to '{}'",
+            original, candidate
+        );
+        used.insert(candidate.clone());
+        op.name = candidate.clone();
+        renamed.push((original, candidate));
+    }
+
+    renamed
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::OpenApiError;
 
     const TEST_SPEC: &str = r#"
 openapi: 3.0.0
@@ -234,6 +432,96 @@ paths:
         assert!(names.contains(&"get_user".to_string()));
     }
 
+    #[test]
+    fn test_toolset_from_bytes() {
+        let from_str = OpenApiToolset::parse_from_str(TEST_SPEC).unwrap();
+        let from_bytes = OpenApiToolset::from_bytes(TEST_SPEC.as_bytes()).unwrap();
+
+        assert_eq!(from_bytes.len(), 2);
+        let mut expected = from_str.tool_names();
+        let mut actual = from_bytes.tool_names();
+        expected.sort();
+        actual.sort();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_toolset_from_reader_json() {
+        let spec: serde_json::Value = serde_yaml::from_str(TEST_SPEC).unwrap();
+        let json = serde_json::to_vec(&spec).unwrap();
+
+        let toolset = OpenApiToolset::from_reader(std::io::Cursor::new(json)).unwrap();
+        assert_eq!(toolset.len(), 2);
+        assert!(toolset.get_tool("get_user").is_some());
+    }
+
+    #[test]
+    fn test_from_bytes_invalid_spec() {
+        let result = OpenApiToolset::from_bytes(b"not: [an openapi spec");
+        assert!(matches!(result, Err(OpenApiError::ParseError(_))));
+    }
+
+    #[test]
+    fn test_colliding_names_are_disambiguated() {
+        let spec = r#"
+openapi: 3.0.0
+info:
+  title: Collision API
+  version: 1.0.0
+paths:
+  /users/{id}:
+    get:
+      operationId: getUser
+      responses:
+        '200':
+          description: Success
+  /v2/users/{id}:
+    get:
+      operationId: get_user
+      responses:
+        '200':
+          description: Success
+    post:
+      operationId: GetUser
+      responses:
+        '200':
+          description: Success
+"#;
+
+        let toolset = OpenApiToolset::parse_from_str(spec).unwrap();
+        assert_eq!(toolset.len(), 3);
+
+        let mut names = toolset.tool_names();
+        names.sort();
+        assert_eq!(names, vec!["get_user", "get_user_get", "get_user_post"]);
+
+        let report = toolset.report();
+        assert_eq!(report.total_operations, 3);
+        assert_eq!(report.generated_tools, 3);
+        assert!(report.skipped.is_empty());
+        assert_eq!(report.renamed.len(), 2);
+        assert!(
+            report
+                .renamed
+                .iter()
+                .all(|(original, _)| original == "get_user")
+        );
+    }
+
+    #[test]
+    fn test_report_without_collisions() {
+        let report = OpenApiToolset::parse_from_str(TEST_SPEC).unwrap().report();
+        assert_eq!(
+            report,
+            ToolsetReport {
+                total_operations: 2,
+                generated_tools: 2,
+                skipped: vec![],
+                renamed: vec![],
+            }
+        );
+    }
+
     #[test]
     fn test_get_tool_by_name() {
         let toolset = OpenApiToolset::parse_from_str(TEST_SPEC).unwrap();
@@ -246,6 +534,86 @@ paths:
         assert!(missing.is_none());
     }
 
+    const MULTI_OPERATION_SPEC: &str = r#"
+openapi: 3.0.0
+info:
+  title: Store API
+  version: 1.0.0
+paths:
+  /users:
+    get:
+      operationId: listUsers
+      tags: [users]
+      responses:
+        '200':
+          description: Success
+    post:
+      operationId: createUser
+      tags: [users, admin]
+      responses:
+        '200':
+          description: Success
+  /users/{id}:
+    delete:
+      operationId: deleteUser
+      tags: [users, admin]
+      parameters:
+        - name: id
+          in: path
+          required: true
+          schema:
+            type: string
+      responses:
+        '200':
+          description: Success
+  /orders:
+    get:
+      operationId: listOrders
+      tags: [orders]
+      responses:
+        '200':
+          description: Success
+"#;
+
+    fn sorted_names(toolset: &OpenApiToolset) -> Vec<String> {
+        let mut names = toolset.tool_names();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_operation_filters() {
+        let toolset = OpenApiToolset::parse_from_str(MULTI_OPERATION_SPEC)
+            .unwrap()
+            .include_operations(&["listUsers", "listOrders"]);
+        assert_eq!(sorted_names(&toolset), vec!["list_orders", "list_users"]);
+        assert_eq!(toolset.report().generated_tools, 2);
+        assert_eq!(toolset.report().total_operations, 4);
+
+        let toolset = OpenApiToolset::parse_from_str(MULTI_OPERATION_SPEC)
+            .unwrap()
+            .include_tags(&["users"])
+            .exclude_operations(&["deleteUser"]);
+        assert_eq!(sorted_names(&toolset), vec!["create_user", "list_users"]);
+
+        // Whitelists combine; auth added afterwards keeps the filter
+        let toolset = OpenApiToolset::parse_from_str(MULTI_OPERATION_SPEC)
+            .unwrap()
+            .include_tags(&["admin"])
+            .include_operations(&["listOrders"])
+            .with_auth(AuthConfig::bearer("test-token"));
+        assert_eq!(
+            sorted_names(&toolset),
+            vec!["create_user", "delete_user", "list_orders"]
+        );
+
+        let toolset = OpenApiToolset::parse_from_str(MULTI_OPERATION_SPEC)
+            .unwrap()
+            .exclude_operations(&["listOrders"]);
+        assert_eq!(toolset.len(), 3);
+        assert!(toolset.get_tool("list_orders").is_none());
+    }
+
     #[test]
     fn test_with_auth() {
         let toolset = OpenApiToolset::parse_from_str(TEST_SPEC)
@@ -64,6 +64,10 @@ impl std::fmt::Display for ParameterLocation {
 pub struct ParsedOperation {
     /// Tool/operation name (snake_case)
     pub name: String,
+    /// Operation ID as written in the spec (generated if missing)
+    pub operation_id: String,
+    /// OpenAPI tags the operation is grouped under
+    pub tags: Vec<String>,
     /// Human-readable description
     pub description: String,
     /// Endpoint information
@@ -84,3 +88,22 @@ pub struct SecurityRequirement {
     /// Required scopes (for OAuth2)
     pub scopes: Vec<String>,
 }
+
+/// An operation that produced no tool, as `(operation, reason)`.
+pub type SkippedOperation = (String, String);
+
+/// Summary of how an OpenAPI spec was turned into tools.
+///
+/// Explains why a spec may have produced fewer tools than it has operations,
+/// or why a tool ended up with a different name than its operation ID.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ToolsetReport {
+    /// Number of operations found in the spec
+    pub total_operations: usize,
+    /// Number of tools generated
+    pub generated_tools: usize,
+    /// Operations that were skipped, as `(operation, reason)`
+    pub skipped: Vec<SkippedOperation>,
+    /// Tools renamed to avoid name collisions, as `(original, new)`
+    pub renamed: Vec<(String, String)>,
+}
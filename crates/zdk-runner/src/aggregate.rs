@@ -0,0 +1,262 @@
+//! Aggregation of streamed partial events
+//!
+//! With streaming enabled an agent emits its answer as many partial events,
+//! each carrying a fragment of text. [`aggregate_text`] collapses each run of
+//! partials into the event that completes it, so callers see one event per
+//! response instead of stitching the fragments together themselves. Events
+//! that aren't partial (tool calls, tool responses, errors) pass through
+//! unchanged.
+
+use async_stream::stream;
+use futures::stream::{Stream, StreamExt};
+use zdk_core::{Content, Event, Part, Result};
+
+/// Collapse partial events of the same author into one consolidated event
+///
+/// Text fragments are concatenated into a single text part. The closing
+/// non-partial event from the same author absorbs them; if that event already
+/// carries text it is taken to be the complete response and the fragments are
+/// dropped. Partials left over when the author changes or the stream ends are
+/// emitted as a standalone non-partial event.
+pub fn aggregate_text<S>(mut events: S) -> Box<dyn Stream<Item = Result<Event>> + Send + Unpin>
+where
+    S: Stream<Item = Result<Event>> + Send + Unpin + 'static,
+{
+    Box::new(Box::pin(stream! {
+        let mut aggregator = TextAggregator::default();
+
+        while let Some(result) = events.next().await {
+            match result {
+                Ok(event) => {
+                    for event in aggregator.push(event) {
+                        yield Ok(event);
+                    }
+                }
+                Err(e) => {
+                    if let Some(event) = aggregator.finish() {
+                        yield Ok(event);
+                    }
+                    yield Err(e);
+                }
+            }
+        }
+
+        if let Some(event) = aggregator.finish() {
+            yield Ok(event);
+        }
+    }))
+}
+
+/// Incremental form of [`aggregate_text`], fed one event at a time
+#[derive(Default)]
+pub(crate) struct TextAggregator {
+    pending: Option<PendingPartials>,
+}
+
+impl TextAggregator {
+    /// Take in one event and return the complete events it releases
+    pub(crate) fn push(&mut self, event: Event) -> Vec<Event> {
+        if event.partial {
+            return match &mut self.pending {
+                Some(partials) if partials.event.author == event.author => {
+                    partials.push(event);
+                    Vec::new()
+                }
+                _ => self
+                    .pending
+                    .replace(PendingPartials::new(event))
+                    .map(PendingPartials::into_event)
+                    .into_iter()
+                    .collect(),
+            };
+        }
+
+        match self.pending.take() {
+            Some(partials) if partials.event.author == event.author => {
+                vec![partials.complete(event)]
+            }
+            Some(partials) => vec![partials.into_event(), event],
+            None => vec![event],
+        }
+    }
+
+    /// Release partials still waiting for a closing event
+    pub(crate) fn finish(&mut self) -> Option<Event> {
+        self.pending.take().map(PendingPartials::into_event)
+    }
+}
+
+/// Partial events of one author awaiting their closing event
+struct PendingPartials {
+    /// First partial, providing the id, author and role of the aggregate
+    event: Event,
+    parts: Vec<Part>,
+}
+
+impl PendingPartials {
+    fn new(mut event: Event) -> Self {
+        let content = event.content.take();
+        let mut partials = Self {
+            event,
+            parts: Vec::new(),
+        };
+        partials.extend(content);
+        partials
+    }
+
+    fn push(&mut self, mut event: Event) {
+        self.extend(event.content.take());
+    }
+
+    fn extend(&mut self, content: Option<Content>) {
+        if self.event.content.is_none()
+            && let Some(content) = &content
+        {
+            // Keep the role; parts are collected separately
+            self.event.content = Some(Content {
+                role: content.role.clone(),
+                parts: Vec::new(),
+            });
+        }
+
+        for part in content.into_iter().flat_map(|c| c.parts) {
+            match (self.parts.last_mut(), part) {
+                (Some(Part::Text { text }), Part::Text { text: fragment }) => {
+                    text.push_str(&fragment)
+                }
+                (_, part) => self.parts.push(part),
+            }
+        }
+    }
+
+    /// Emit the partials on their own, as a complete event
+    fn into_event(mut self) -> Event {
+        self.event.partial = false;
+        if let Some(content) = self.event.content.as_mut() {
+            content.parts = self.parts;
+        }
+        self.event
+    }
+
+    /// Fold the partials into the event that closes them
+    fn complete(self, mut event: Event) -> Event {
+        let has_text = event
+            .content
+            .as_ref()
+            .is_some_and(|c| c.parts.iter().any(|p| matches!(p, Part::Text { .. })));
+        if has_text || self.parts.is_empty() {
+            return event;
+        }
+
+        let aggregated = self.into_event().content;
+        event.content = match (aggregated, event.content.take()) {
+            (Some(mut aggregated), Some(closing)) => {
+                aggregated.parts.extend(closing.parts);
+                Some(aggregated)
+            }
+            (aggregated, closing) => aggregated.or(closing),
+        };
+        event
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zdk_core::FunctionCall;
+
+    fn partial(author: &str, text: &str) -> Event {
+        let mut event = Event::new("inv".to_string(), author.to_string());
+        event.content = Some(Content::new_model_text(text));
+        event.partial = true;
+        event
+    }
+
+    fn text_of(event: &Event) -> String {
+        event
+            .content
+            .as_ref()
+            .map(Content::text)
+            .unwrap_or_default()
+    }
+
+    async fn aggregate(events: Vec<Event>) -> Vec<Event> {
+        aggregate_text(futures::stream::iter(events.into_iter().map(Ok)))
+            .map(|e| e.unwrap())
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_partials_fold_into_closing_event() {
+        let mut closing = Event::new("inv".to_string(), "agent".to_string());
+        closing.turn_complete = true;
+
+        let events = aggregate(vec![
+            partial("agent", "Hel"),
+            partial("agent", "lo "),
+            partial("agent", "world"),
+            closing.clone(),
+        ])
+        .await;
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].id, closing.id);
+        assert!(!events[0].partial);
+        assert!(events[0].is_final_response());
+        assert_eq!(text_of(&events[0]), "Hello world");
+        assert_eq!(events[0].content.as_ref().unwrap().parts.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_tool_events_pass_through() {
+        let mut call = Event::new("inv".to_string(), "agent".to_string());
+        call.content = Some(Content {
+            role: "model".to_string(),
+            parts: vec![Part::FunctionCall {
+                function_call: FunctionCall {
+                    name: "lookup".to_string(),
+                    args: serde_json::json!({}),
+                    id: None,
+                },
+            }],
+        });
+
+        let events = aggregate(vec![
+            partial("agent", "Let me "),
+            partial("agent", "check."),
+            call,
+            partial("agent", "Done"),
+        ])
+        .await;
+
+        assert_eq!(events.len(), 2);
+        // The call closes the partials and keeps its function call after the text
+        assert_eq!(text_of(&events[0]), "Let me check.");
+        assert!(matches!(
+            events[0].content.as_ref().unwrap().parts[1],
+            Part::FunctionCall { .. }
+        ));
+        // Trailing partials without a closing event are still emitted
+        assert_eq!(text_of(&events[1]), "Done");
+        assert!(!events[1].partial);
+    }
+
+    #[tokio::test]
+    async fn test_closing_event_with_full_text_wins() {
+        let mut closing = Event::new("inv".to_string(), "agent".to_string());
+        closing.content = Some(Content::new_model_text("Hello"));
+
+        let events = aggregate(vec![
+            partial("agent", "Hel"),
+            partial("agent", "lo"),
+            closing,
+            partial("other", "Hi"),
+        ])
+        .await;
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(text_of(&events[0]), "Hello");
+        assert_eq!(events[1].author, "other");
+    }
+}
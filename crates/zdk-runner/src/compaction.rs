@@ -0,0 +1,117 @@
+//! Conversation compaction
+//!
+//! Long sessions eventually outgrow the model's context window. Instead of
+//! dropping old turns, a [`Compactor`] condenses them into a single synopsis
+//! message that replaces them in the history sent to the agent. The persisted
+//! session history is never modified.
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::sync::Arc;
+use zdk_core::{Content, Error, Event, LLM, LLMRequest, Result};
+
+/// Default number of history events that triggers compaction
+pub const DEFAULT_COMPACTION_THRESHOLD: usize = 40;
+
+/// Default number of most recent events kept verbatim after compaction
+pub const DEFAULT_COMPACTION_KEEP_RECENT: usize = 10;
+
+/// Summarizes a range of conversation events into a single message
+#[async_trait]
+pub trait Compactor: Send + Sync {
+    /// Produce a synopsis of `events` to stand in for them in later requests
+    async fn compact(&self, events: &[Event]) -> Result<Content>;
+
+    /// Fold `events` that happened after an earlier synopsis into a new one
+    ///
+    /// The default hands the previous synopsis to [`Compactor::compact`] as
+    /// the first message, so only the new events have to be summarized.
+    async fn extend(&self, previous: &Content, events: &[Event]) -> Result<Content> {
+        let mut summary = Event::new(String::new(), "user".to_string());
+        summary.content = Some(previous.clone());
+
+        let mut combined = Vec::with_capacity(events.len() + 1);
+        combined.push(summary);
+        combined.extend_from_slice(events);
+        self.compact(&combined).await
+    }
+}
+
+/// Synopsis of a session's oldest events, reused until newer events need folding in
+#[derive(Clone)]
+pub(crate) struct Checkpoint {
+    pub(crate) summary: Content,
+    /// Number of leading history events the summary stands in for
+    pub(crate) covered: usize,
+    /// ID of the last covered event, to detect a history that changed underneath
+    pub(crate) last_event_id: String,
+}
+
+/// Compactor that asks an LLM to summarize the conversation
+pub struct LLMCompactor {
+    model: Arc<dyn LLM>,
+    instruction: String,
+}
+
+impl LLMCompactor {
+    /// Create a compactor that summarizes with `model`
+    pub fn new(model: Arc<dyn LLM>) -> Self {
+        Self {
+            model,
+            instruction: "Summarize the conversation above into a concise synopsis. \
+                Keep facts, decisions, open questions and any details needed to \
+                continue the conversation."
+                .to_string(),
+        }
+    }
+
+    /// Override the summarization instruction
+    pub fn with_instruction(mut self, instruction: impl Into<String>) -> Self {
+        self.instruction = instruction.into();
+        self
+    }
+}
+
+#[async_trait]
+impl Compactor for LLMCompactor {
+    async fn compact(&self, events: &[Event]) -> Result<Content> {
+        let mut contents: Vec<Content> = events.iter().filter_map(|e| e.content.clone()).collect();
+        contents.push(Content::new_user_text(self.instruction.clone()));
+
+        let request = LLMRequest {
+            model: self.model.name().to_string(),
+            system_instruction: None,
+            contents,
+            config: None,
+            tools: vec![],
+        };
+
+        let mut stream = self.model.generate_content(request, false).await;
+        let mut summary = String::new();
+        while let Some(response) = stream.next().await {
+            let response = response?;
+            if response.partial {
+                continue;
+            }
+            if let Some(content) = response.content {
+                summary.push_str(&content.text());
+            }
+        }
+
+        if summary.is_empty() {
+            return Err(Error::LLMError(
+                "Compaction model returned an empty summary".to_string(),
+            ));
+        }
+
+        Ok(synopsis(summary))
+    }
+}
+
+/// Wrap summary text as the message that replaces compacted turns
+pub fn synopsis(summary: impl AsRef<str>) -> Content {
+    Content::new_user_text(format!(
+        "Summary of the earlier conversation:\n{}",
+        summary.as_ref()
+    ))
+}
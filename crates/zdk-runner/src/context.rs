@@ -1,6 +1,15 @@
 use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::HashMap;
 use std::sync::Arc;
-use zdk_core::{Agent, Content, InvocationContext, ReadonlyContext};
+use uuid::Uuid;
+use zdk_artifact::{ArtifactService, ScopedArtifactStore};
+use zdk_core::{
+    Agent, ArtifactStore, Content, Error, InvocationContext, MemoryStore, ReadonlyContext, Result,
+    RunConfig, RunnerDefaults, SessionStore,
+};
+use zdk_memory::{MemoryService, ScopedMemoryStore};
+use zdk_session::{ScopedSessionStore, SessionService};
 
 pub struct DefaultInvocationContext {
     invocation_id: String,
@@ -8,11 +17,23 @@ pub struct DefaultInvocationContext {
     user_id: String,
     session_id: String,
     user_content: Option<Content>,
+    history: Vec<Content>,
+    state: HashMap<String, Value>,
+    artifacts: Option<Arc<dyn ArtifactStore>>,
+    session: Option<Arc<dyn SessionStore>>,
+    memory: Option<Arc<dyn MemoryStore>>,
+    run_config: RunConfig,
+    runner_defaults: RunnerDefaults,
     #[allow(dead_code)]
     agent: Arc<dyn Agent>,
 }
 
 impl DefaultInvocationContext {
+    /// Start building a context, e.g. for a custom runner
+    pub fn builder() -> DefaultInvocationContextBuilder {
+        DefaultInvocationContextBuilder::new()
+    }
+
     pub fn new(
         invocation_id: String,
         app_name: String,
@@ -27,9 +48,58 @@ impl DefaultInvocationContext {
             user_id,
             session_id,
             user_content,
+            history: Vec::new(),
+            state: HashMap::new(),
+            artifacts: None,
+            session: None,
+            memory: None,
+            run_config: RunConfig::default(),
+            runner_defaults: RunnerDefaults::default(),
             agent,
         }
     }
+
+    /// Set the prior conversation turns passed to the agent
+    pub fn with_history(mut self, history: Vec<Content>) -> Self {
+        self.history = history;
+        self
+    }
+
+    /// Set the session state exposed to the agent
+    pub fn with_state(mut self, state: HashMap<String, Value>) -> Self {
+        self.state = state;
+        self
+    }
+
+    /// Set the artifact storage exposed to the agent's tools
+    pub fn with_artifacts(mut self, artifacts: Arc<dyn ArtifactStore>) -> Self {
+        self.artifacts = Some(artifacts);
+        self
+    }
+
+    /// Set the session storage exposed to the agent
+    pub fn with_session(mut self, session: Arc<dyn SessionStore>) -> Self {
+        self.session = Some(session);
+        self
+    }
+
+    /// Set the memory search exposed to the agent
+    pub fn with_memory(mut self, memory: Arc<dyn MemoryStore>) -> Self {
+        self.memory = Some(memory);
+        self
+    }
+
+    /// Set the run configuration for this invocation
+    pub fn with_run_config(mut self, run_config: RunConfig) -> Self {
+        self.run_config = run_config;
+        self
+    }
+
+    /// Set the runner-level defaults exposed to the agent
+    pub fn with_runner_defaults(mut self, runner_defaults: RunnerDefaults) -> Self {
+        self.runner_defaults = runner_defaults;
+        self
+    }
 }
 
 #[async_trait]
@@ -41,6 +111,34 @@ impl InvocationContext for DefaultInvocationContext {
     fn user_content(&self) -> Option<&Content> {
         self.user_content.as_ref()
     }
+
+    fn history(&self) -> &[Content] {
+        &self.history
+    }
+
+    fn run_config(&self) -> &RunConfig {
+        &self.run_config
+    }
+
+    fn runner_defaults(&self) -> &RunnerDefaults {
+        &self.runner_defaults
+    }
+
+    fn state(&self) -> HashMap<String, Value> {
+        self.state.clone()
+    }
+
+    fn artifacts(&self) -> Option<Arc<dyn ArtifactStore>> {
+        self.artifacts.clone()
+    }
+
+    fn session(&self) -> Option<Arc<dyn SessionStore>> {
+        self.session.clone()
+    }
+
+    fn memory(&self) -> Option<Arc<dyn MemoryStore>> {
+        self.memory.clone()
+    }
 }
 
 impl ReadonlyContext for DefaultInvocationContext {
@@ -56,3 +154,179 @@ impl ReadonlyContext for DefaultInvocationContext {
         &self.session_id
     }
 }
+
+/// Builder for [`DefaultInvocationContext`]
+///
+/// Services are scoped to the context's app, user and session when built, so
+/// agents and tools reach them through the [`InvocationContext`] without
+/// knowing those IDs.
+pub struct DefaultInvocationContextBuilder {
+    invocation_id: Option<String>,
+    app_name: Option<String>,
+    user_id: Option<String>,
+    session_id: Option<String>,
+    user_content: Option<Content>,
+    agent: Option<Arc<dyn Agent>>,
+    history: Vec<Content>,
+    state: HashMap<String, Value>,
+    session_service: Option<Arc<dyn SessionService>>,
+    artifact_service: Option<Arc<dyn ArtifactService>>,
+    memory_service: Option<Arc<dyn MemoryService>>,
+    run_config: RunConfig,
+    runner_defaults: RunnerDefaults,
+}
+
+impl DefaultInvocationContextBuilder {
+    pub fn new() -> Self {
+        Self {
+            invocation_id: None,
+            app_name: None,
+            user_id: None,
+            session_id: None,
+            user_content: None,
+            agent: None,
+            history: Vec::new(),
+            state: HashMap::new(),
+            session_service: None,
+            artifact_service: None,
+            memory_service: None,
+            run_config: RunConfig::default(),
+            runner_defaults: RunnerDefaults::default(),
+        }
+    }
+
+    /// Invocation ID, a fresh UUID if unset
+    pub fn invocation_id(mut self, invocation_id: impl Into<String>) -> Self {
+        self.invocation_id = Some(invocation_id.into());
+        self
+    }
+
+    pub fn app_name(mut self, app_name: impl Into<String>) -> Self {
+        self.app_name = Some(app_name.into());
+        self
+    }
+
+    pub fn user_id(mut self, user_id: impl Into<String>) -> Self {
+        self.user_id = Some(user_id.into());
+        self
+    }
+
+    pub fn session_id(mut self, session_id: impl Into<String>) -> Self {
+        self.session_id = Some(session_id.into());
+        self
+    }
+
+    /// The user message that triggered this invocation
+    pub fn user_content(mut self, content: Content) -> Self {
+        self.user_content = Some(content);
+        self
+    }
+
+    pub fn agent(mut self, agent: Arc<dyn Agent>) -> Self {
+        self.agent = Some(agent);
+        self
+    }
+
+    /// Prior conversation turns passed to the agent
+    pub fn history(mut self, history: Vec<Content>) -> Self {
+        self.history = history;
+        self
+    }
+
+    /// Session state as of the start of the invocation
+    pub fn state(mut self, state: HashMap<String, Value>) -> Self {
+        self.state = state;
+        self
+    }
+
+    /// Session service the agent can reload its session from
+    pub fn session_service(mut self, service: Arc<dyn SessionService>) -> Self {
+        self.session_service = Some(service);
+        self
+    }
+
+    /// Artifact service tools can save files to, scoped to the session
+    pub fn artifact_service(mut self, service: Arc<dyn ArtifactService>) -> Self {
+        self.artifact_service = Some(service);
+        self
+    }
+
+    /// Memory service the agent can search the user's past sessions in
+    pub fn memory_service(mut self, service: Arc<dyn MemoryService>) -> Self {
+        self.memory_service = Some(service);
+        self
+    }
+
+    pub fn run_config(mut self, run_config: RunConfig) -> Self {
+        self.run_config = run_config;
+        self
+    }
+
+    pub fn runner_defaults(mut self, runner_defaults: RunnerDefaults) -> Self {
+        self.runner_defaults = runner_defaults;
+        self
+    }
+
+    pub fn build(self) -> Result<DefaultInvocationContext> {
+        let app_name = self
+            .app_name
+            .ok_or_else(|| Error::Config("App name is required".to_string()))?;
+        let user_id = self
+            .user_id
+            .ok_or_else(|| Error::Config("User ID is required".to_string()))?;
+        let session_id = self
+            .session_id
+            .ok_or_else(|| Error::Config("Session ID is required".to_string()))?;
+        let agent = self
+            .agent
+            .ok_or_else(|| Error::Config("Agent is required".to_string()))?;
+
+        let session = self.session_service.map(|service| {
+            Arc::new(ScopedSessionStore::new(
+                service,
+                app_name.clone(),
+                user_id.clone(),
+                session_id.clone(),
+            )) as Arc<dyn SessionStore>
+        });
+        let artifacts = self.artifact_service.map(|service| {
+            Arc::new(ScopedArtifactStore::new(
+                service,
+                app_name.clone(),
+                user_id.clone(),
+                session_id.clone(),
+            )) as Arc<dyn ArtifactStore>
+        });
+        let memory = self.memory_service.map(|service| {
+            Arc::new(ScopedMemoryStore::new(
+                service,
+                app_name.clone(),
+                user_id.clone(),
+            )) as Arc<dyn MemoryStore>
+        });
+
+        Ok(DefaultInvocationContext {
+            invocation_id: self
+                .invocation_id
+                .unwrap_or_else(|| Uuid::new_v4().to_string()),
+            app_name,
+            user_id,
+            session_id,
+            user_content: self.user_content,
+            history: self.history,
+            state: self.state,
+            artifacts,
+            session,
+            memory,
+            run_config: self.run_config,
+            runner_defaults: self.runner_defaults,
+            agent,
+        })
+    }
+}
+
+impl Default for DefaultInvocationContextBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
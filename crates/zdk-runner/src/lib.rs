@@ -1,10 +1,15 @@
 //! Runner for executing agents
 
+pub mod aggregate;
+pub mod compaction;
 pub mod context;
 pub mod runner;
 
-pub use context::DefaultInvocationContext;
-pub use runner::{RunConfig, Runner, RunnerBuilder};
+pub use aggregate::aggregate_text;
+pub use compaction::{Compactor, LLMCompactor};
+pub use context::{DefaultInvocationContext, DefaultInvocationContextBuilder};
+pub use runner::{Runner, RunnerBuilder};
+pub use zdk_core::{RunConfig, RunnerDefaults};
 
 #[cfg(test)]
 mod tests {
@@ -13,7 +18,7 @@ mod tests {
     use async_trait::async_trait;
     use futures::stream::{Stream, StreamExt};
     use std::sync::Arc;
-    use zdk_core::{Agent, Content, LLM, LLMRequest, LLMResponse, Part, Result};
+    use zdk_core::{Agent, Content, FinishReason, LLM, LLMRequest, LLMResponse, Part, Result};
     use zdk_session::{SessionService, inmemory::InMemorySessionService};
 
     // Mock LLM for testing
@@ -42,9 +47,54 @@ mod tests {
                     partial: false,
                     turn_complete: true,
                     interrupted: false,
-                    finish_reason: Some("STOP".to_string()),
+                    finish_reason: Some(FinishReason::Stop),
                     error_code: None,
                     error_message: None,
+                    usage: None,
+                });
+            }))
+        }
+    }
+
+    // Mock LLM streaming its response in several partial chunks
+    struct StreamingLLM {
+        chunks: Vec<&'static str>,
+    }
+
+    #[async_trait]
+    impl LLM for StreamingLLM {
+        fn name(&self) -> &str {
+            "streaming-llm"
+        }
+
+        async fn generate_content(
+            &self,
+            _request: LLMRequest,
+            _stream: bool,
+        ) -> Box<dyn Stream<Item = Result<LLMResponse>> + Send + Unpin> {
+            let chunks = self.chunks.clone();
+            Box::new(Box::pin(stream! {
+                for chunk in chunks {
+                    yield Ok(LLMResponse {
+                        content: Some(Content::new_model_text(chunk)),
+                        partial: true,
+                        turn_complete: false,
+                        interrupted: false,
+                        finish_reason: None,
+                        error_code: None,
+                        error_message: None,
+                        usage: None,
+                    });
+                }
+                yield Ok(LLMResponse {
+                    content: None,
+                    partial: false,
+                    turn_complete: true,
+                    interrupted: false,
+                    finish_reason: Some(FinishReason::Stop),
+                    error_code: None,
+                    error_message: None,
+                    usage: None,
                 });
             }))
         }
@@ -78,6 +128,7 @@ mod tests {
                 let mut llm_stream = llm.generate_content(
                     LLMRequest {
                         model: "mock".to_string(),
+                        system_instruction: None,
                         contents: vec![],
                         config: None,
                         tools: vec![],
@@ -89,6 +140,7 @@ mod tests {
                     let response = response?;
                     let mut event = zdk_core::Event::new(invocation_id.clone(), name.clone());
                     event.content = response.content;
+                    event.partial = response.partial;
                     event.turn_complete = response.turn_complete;
                     yield Ok(event);
                 }
@@ -100,6 +152,76 @@ mod tests {
         }
     }
 
+    // Agent that emits one event and then hangs until dropped
+    struct HangingAgent;
+
+    #[async_trait]
+    impl Agent for HangingAgent {
+        fn name(&self) -> &str {
+            "hanging-agent"
+        }
+
+        fn description(&self) -> &str {
+            "Never finishes"
+        }
+
+        async fn run(
+            &self,
+            ctx: Arc<dyn zdk_core::InvocationContext>,
+        ) -> Box<dyn Stream<Item = Result<zdk_core::Event>> + Send + Unpin> {
+            let invocation_id = ctx.invocation_id().to_string();
+
+            Box::new(Box::pin(stream! {
+                let mut event = zdk_core::Event::new(invocation_id, "hanging-agent".to_string());
+                event.content = Some(Content::new_model_text("thinking..."));
+                event.partial = true;
+                yield Ok(event);
+
+                futures::future::pending::<()>().await;
+            }))
+        }
+
+        fn sub_agents(&self) -> &[Arc<dyn Agent>] {
+            &[]
+        }
+    }
+
+    // Agent that sleeps before producing its only event
+    struct SlowAgent {
+        delay: std::time::Duration,
+    }
+
+    #[async_trait]
+    impl Agent for SlowAgent {
+        fn name(&self) -> &str {
+            "slow-agent"
+        }
+
+        fn description(&self) -> &str {
+            "Takes its time"
+        }
+
+        async fn run(
+            &self,
+            ctx: Arc<dyn zdk_core::InvocationContext>,
+        ) -> Box<dyn Stream<Item = Result<zdk_core::Event>> + Send + Unpin> {
+            let invocation_id = ctx.invocation_id().to_string();
+            let delay = self.delay;
+
+            Box::new(Box::pin(stream! {
+                tokio::time::sleep(delay).await;
+                let mut event = zdk_core::Event::new(invocation_id, "slow-agent".to_string());
+                event.content = Some(Content::new_model_text("finally"));
+                event.turn_complete = true;
+                yield Ok(event);
+            }))
+        }
+
+        fn sub_agents(&self) -> &[Arc<dyn Agent>] {
+            &[]
+        }
+    }
+
     #[tokio::test]
     async fn test_runner_executes_agent() {
         let llm = Arc::new(MockLLM {
@@ -140,6 +262,42 @@ mod tests {
         assert_eq!(events[0].author, "test-agent");
     }
 
+    #[tokio::test]
+    async fn test_run_aggregated_collapses_partials() {
+        let agent = Arc::new(MockAgent {
+            name: "test-agent".to_string(),
+            llm: Arc::new(StreamingLLM {
+                chunks: vec!["The answer ", "is ", "42."],
+            }),
+        });
+
+        let runner = Runner::builder()
+            .app_name("test-app")
+            .agent(agent)
+            .session_service(Arc::new(InMemorySessionService::new()))
+            .build()
+            .unwrap();
+
+        let events: Vec<_> = runner
+            .run_aggregated(
+                "user1".to_string(),
+                "session1".to_string(),
+                Content::new_user_text("What is the answer?"),
+                RunConfig::default(),
+            )
+            .await
+            .unwrap()
+            .map(|e| e.unwrap())
+            .collect()
+            .await;
+
+        assert_eq!(events.len(), 1);
+        assert!(events[0].is_final_response());
+        let parts = &events[0].content.as_ref().unwrap().parts;
+        assert_eq!(parts.len(), 1);
+        assert!(matches!(&parts[0], Part::Text { text } if text == "The answer is 42."));
+    }
+
     #[tokio::test]
     async fn test_runner_persists_events() {
         let llm = Arc::new(MockLLM {
@@ -187,4 +345,281 @@ mod tests {
         let events = session.events();
         assert!(events.len() >= 2); // User message + agent response
     }
+
+    #[tokio::test]
+    async fn test_runner_persists_streamed_text_once() {
+        let agent = Arc::new(MockAgent {
+            name: "test-agent".to_string(),
+            llm: Arc::new(StreamingLLM {
+                chunks: vec!["The answer ", "is ", "42."],
+            }),
+        });
+        let session_service = Arc::new(InMemorySessionService::new());
+        let runner = Runner::builder()
+            .app_name("test-app")
+            .agent(agent)
+            .session_service(session_service.clone())
+            .build()
+            .unwrap();
+
+        let streamed: Vec<_> = runner
+            .run(
+                "user1".to_string(),
+                "session1".to_string(),
+                Content::new_user_text("What is the answer?"),
+                RunConfig::default(),
+            )
+            .await
+            .unwrap()
+            .map(|e| e.unwrap())
+            .collect()
+            .await;
+        // The caller still sees every chunk
+        assert_eq!(streamed.len(), 4);
+
+        let session = session_service
+            .get(&zdk_session::GetRequest {
+                app_name: "test-app".to_string(),
+                user_id: "user1".to_string(),
+                session_id: "session1".to_string(),
+            })
+            .await
+            .unwrap();
+        let events = session.events();
+
+        // User message plus one complete model response
+        assert_eq!(events.len(), 2);
+        assert!(!events[1].partial);
+        assert_eq!(events[1].id, streamed[3].id);
+        assert_eq!(
+            events[1].content.as_ref().unwrap().text(),
+            "The answer is 42."
+        );
+    }
+
+    #[tokio::test]
+    async fn test_runner_cancellation_interrupts_stream() {
+        let session_service = Arc::new(InMemorySessionService::new());
+
+        let runner = Runner::builder()
+            .app_name("test-app")
+            .agent(Arc::new(HangingAgent))
+            .session_service(session_service)
+            .build()
+            .unwrap();
+
+        let token = tokio_util::sync::CancellationToken::new();
+        let mut stream = runner
+            .run(
+                "user1".to_string(),
+                "session1".to_string(),
+                Content::new_user_text("Hello!"),
+                RunConfig {
+                    cancellation_token: Some(token.clone()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        let first = stream.next().await.unwrap().unwrap();
+        assert_eq!(first.author, "hanging-agent");
+
+        token.cancel();
+
+        let timeout = std::time::Duration::from_secs(1);
+        let last = tokio::time::timeout(timeout, stream.next())
+            .await
+            .expect("stream should end promptly after cancellation")
+            .unwrap()
+            .unwrap();
+        assert!(last.interrupted);
+        assert!(last.turn_complete);
+
+        let end = tokio::time::timeout(timeout, stream.next()).await.unwrap();
+        assert!(end.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_runner_idle_timeout() {
+        let session_service = Arc::new(InMemorySessionService::new());
+
+        let runner = Runner::builder()
+            .app_name("test-app")
+            .agent(Arc::new(SlowAgent {
+                delay: std::time::Duration::from_secs(10),
+            }))
+            .session_service(session_service)
+            .build()
+            .unwrap();
+
+        let mut stream = runner
+            .run(
+                "user1".to_string(),
+                "session1".to_string(),
+                Content::new_user_text("Hello!"),
+                RunConfig {
+                    timeout: Some(std::time::Duration::from_millis(50)),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        let mut events = Vec::new();
+        while let Some(result) = stream.next().await {
+            events.push(result.unwrap());
+        }
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].error_code, "TIMEOUT");
+        assert!(events[0].turn_complete);
+    }
+
+    #[tokio::test]
+    async fn test_runner_idle_timeout_not_triggered_by_fast_agent() {
+        let session_service = Arc::new(InMemorySessionService::new());
+
+        let runner = Runner::builder()
+            .app_name("test-app")
+            .agent(Arc::new(SlowAgent {
+                delay: std::time::Duration::from_millis(10),
+            }))
+            .session_service(session_service)
+            .build()
+            .unwrap();
+
+        let mut stream = runner
+            .run(
+                "user1".to_string(),
+                "session1".to_string(),
+                Content::new_user_text("Hello!"),
+                RunConfig {
+                    timeout: Some(std::time::Duration::from_secs(5)),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        let event = stream.next().await.unwrap().unwrap();
+        assert_eq!(event.author, "slow-agent");
+        assert!(event.error_code.is_empty());
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_runner_shutdown_waits_for_in_flight_runs() {
+        let runner = Arc::new(
+            Runner::builder()
+                .app_name("test-app")
+                .agent(Arc::new(SlowAgent {
+                    delay: std::time::Duration::from_millis(100),
+                }))
+                .session_service(Arc::new(InMemorySessionService::new()))
+                .build()
+                .unwrap(),
+        );
+
+        let stream = runner
+            .run(
+                "user1".to_string(),
+                "session1".to_string(),
+                Content::new_user_text("Hello!"),
+                RunConfig::default(),
+            )
+            .await
+            .unwrap();
+        let consumer = tokio::spawn(stream.collect::<Vec<_>>());
+        assert_eq!(runner.in_flight(), 1);
+
+        runner.shutdown();
+        let refused = runner
+            .run(
+                "user1".to_string(),
+                "session2".to_string(),
+                Content::new_user_text("Too late"),
+                RunConfig::default(),
+            )
+            .await;
+        assert!(refused.is_err());
+
+        assert!(runner.wait_idle(std::time::Duration::from_secs(5)).await);
+        let events = consumer.await.unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].as_ref().unwrap().author, "slow-agent");
+    }
+
+    #[tokio::test]
+    async fn test_context_builder_exposes_services() {
+        use zdk_artifact::InMemoryArtifactService;
+        use zdk_core::{ArtifactPart, Event, InvocationContext};
+        use zdk_memory::{InMemoryMemoryService, MemoryService};
+        use zdk_session::{CreateRequest, GetRequest};
+
+        let sessions = Arc::new(InMemorySessionService::new());
+        sessions
+            .create(&CreateRequest {
+                app_name: "test-app".to_string(),
+                user_id: "user1".to_string(),
+                session_id: Some("session1".to_string()),
+            })
+            .await
+            .unwrap();
+        let mut event = Event::new("inv0".to_string(), "user".to_string());
+        event.content = Some(Content::new_user_text("My favourite colour is teal"));
+        sessions.append_event("session1", event).await.unwrap();
+
+        let memory = Arc::new(InMemoryMemoryService::new());
+        let session = sessions
+            .get(&GetRequest {
+                app_name: "test-app".to_string(),
+                user_id: "user1".to_string(),
+                session_id: "session1".to_string(),
+            })
+            .await
+            .unwrap();
+        memory.add_session(session).await.unwrap();
+
+        let ctx = DefaultInvocationContext::builder()
+            .app_name("test-app")
+            .user_id("user1")
+            .session_id("session1")
+            .user_content(Content::new_user_text("What colour do I like?"))
+            .agent(Arc::new(SlowAgent {
+                delay: std::time::Duration::ZERO,
+            }))
+            .session_service(sessions)
+            .artifact_service(Arc::new(InMemoryArtifactService::new()))
+            .memory_service(memory)
+            .build()
+            .unwrap();
+        let ctx: Arc<dyn InvocationContext> = Arc::new(ctx);
+
+        assert!(!ctx.invocation_id().is_empty());
+        assert_eq!(ctx.user_content().unwrap().text(), "What colour do I like?");
+
+        let events = ctx.session().unwrap().events().await.unwrap();
+        assert_eq!(events.len(), 1);
+
+        let artifacts = ctx.artifacts().unwrap();
+        let version = artifacts
+            .save_artifact("notes.txt", ArtifactPart::text("teal"))
+            .await
+            .unwrap();
+        assert_eq!(version, 1);
+
+        let memories = ctx.memory().unwrap().search_memory("teal").await.unwrap();
+        assert_eq!(memories.len(), 1);
+        assert_eq!(memories[0].author, "user");
+    }
+
+    #[test]
+    fn test_context_builder_requires_ids() {
+        let result = DefaultInvocationContext::builder()
+            .app_name("test-app")
+            .agent(Arc::new(HangingAgent))
+            .build();
+        assert!(result.is_err());
+    }
 }
@@ -1,16 +1,73 @@
+use crate::aggregate::{TextAggregator, aggregate_text};
+use crate::compaction::{
+    Checkpoint, Compactor, DEFAULT_COMPACTION_KEEP_RECENT, DEFAULT_COMPACTION_THRESHOLD,
+};
 use crate::context::DefaultInvocationContext;
 use async_stream::stream;
 use futures::stream::{Stream, StreamExt};
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
 use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
-use zdk_core::{Agent, Content, Error, Event, Result};
-use zdk_session::{CreateRequest, SessionService};
+use zdk_artifact::ArtifactService;
+use zdk_core::{Agent, Content, Error, Event, MemoryEntry, Result, RunConfig, RunnerDefaults};
+use zdk_memory::{MemoryService, SearchRequest};
+use zdk_session::{CreateRequest, GetRequest, SessionService};
+
+/// Reason the runner stopped waiting for the agent's next event
+enum Interruption {
+    Cancelled,
+    TimedOut,
+}
+
+/// Sessions whose compaction synopsis is kept; beyond this an arbitrary one is dropped
+const MAX_CHECKPOINTS: usize = 1024;
 
 pub struct Runner {
     app_name: String,
     agent: Arc<dyn Agent>,
     session_service: Arc<dyn SessionService>,
+    artifact_service: Option<Arc<dyn ArtifactService>>,
+    memory_service: Option<Arc<dyn MemoryService>>,
+    /// Add each successfully completed session to `memory_service`
+    auto_memorize: bool,
+    compactor: Option<Arc<dyn Compactor>>,
+    compaction_threshold: usize,
+    compaction_keep_recent: usize,
+    /// Latest synopsis per session, keyed by `user_id:session_id`
+    checkpoints: Mutex<HashMap<String, Checkpoint>>,
+    defaults: RunnerDefaults,
+    /// Set by [`Runner::shutdown`]; new runs are refused once true
+    shutting_down: AtomicBool,
+    in_flight: Arc<InFlight>,
+}
+
+/// Count of runs whose event stream hasn't finished or been dropped yet
+#[derive(Default)]
+struct InFlight {
+    count: AtomicUsize,
+    idle: Notify,
+}
+
+/// Keeps a run counted as in flight until dropped with its event stream
+struct InFlightGuard(Arc<InFlight>);
+
+impl InFlightGuard {
+    fn new(in_flight: &Arc<InFlight>) -> Self {
+        in_flight.count.fetch_add(1, Ordering::SeqCst);
+        Self(in_flight.clone())
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        if self.0.count.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.0.idle.notify_waiters();
+        }
+    }
 }
 
 impl Runner {
@@ -18,6 +75,52 @@ impl Runner {
         RunnerBuilder::new()
     }
 
+    /// Returns the app name this runner executes agents for
+    pub fn app_name(&self) -> &str {
+        &self.app_name
+    }
+
+    /// Stop accepting new runs
+    ///
+    /// Runs already in flight are unaffected; use [`Runner::wait_idle`] to wait
+    /// for them. Further calls to [`Runner::run`] return an error.
+    pub fn shutdown(&self) {
+        self.shutting_down.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether [`Runner::shutdown`] has been called
+    pub fn is_shutting_down(&self) -> bool {
+        self.shutting_down.load(Ordering::SeqCst)
+    }
+
+    /// Number of runs whose event stream is still being consumed
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.count.load(Ordering::SeqCst)
+    }
+
+    /// Wait until no runs are in flight, giving up after `timeout`
+    ///
+    /// Returns true if the runner became idle in time.
+    pub async fn wait_idle(&self, timeout: Duration) -> bool {
+        let wait = async {
+            loop {
+                let idle = self.in_flight.idle.notified();
+                tokio::pin!(idle);
+                // Register before checking so a run finishing in between isn't missed
+                idle.as_mut().enable();
+                if self.in_flight() == 0 {
+                    return;
+                }
+                idle.await;
+            }
+        };
+        tokio::time::timeout(timeout, wait).await.is_ok()
+    }
+
+    /// Run the agent for a new user message
+    ///
+    /// If `config.cancellation_token` is set, cancelling it ends the stream
+    /// with a final event marked `interrupted`.
     pub async fn run(
         &self,
         user_id: String,
@@ -29,18 +132,41 @@ impl Runner {
             .await
     }
 
+    /// Run the agent, collapsing streamed partial text into one event per response
+    ///
+    /// Tool calls, tool responses and errors are still yielded as they happen.
+    /// See [`aggregate_text`].
+    pub async fn run_aggregated(
+        &self,
+        user_id: String,
+        session_id: String,
+        message: Content,
+        config: RunConfig,
+    ) -> Result<Box<dyn Stream<Item = Result<Event>> + Send + Unpin>> {
+        let events = self.run(user_id, session_id, message, config).await?;
+        Ok(aggregate_text(events))
+    }
+
+    /// Run the agent with an explicit cancellation token
+    ///
+    /// The explicit token takes precedence over `config.cancellation_token`.
     pub async fn run_with_cancellation(
         &self,
         user_id: String,
         session_id: String,
         message: Content,
-        _config: RunConfig,
+        config: RunConfig,
         cancel_token: Option<CancellationToken>,
     ) -> Result<Box<dyn Stream<Item = Result<Event>> + Send + Unpin>> {
+        if self.is_shutting_down() {
+            return Err(Error::Other(anyhow::anyhow!("Runner is shutting down")));
+        }
+        let in_flight = InFlightGuard::new(&self.in_flight);
+
         // Get or create session
-        let _session = match self
+        let session = match self
             .session_service
-            .get(&zdk_session::GetRequest {
+            .get(&GetRequest {
                 app_name: self.app_name.clone(),
                 user_id: user_id.clone(),
                 session_id: session_id.clone(),
@@ -60,16 +186,41 @@ impl Runner {
             }
         };
 
+        let events = session.events();
+        let recalled = self
+            .recall(&user_id, &message, config.include_memories, &events)
+            .await?;
+        let mut history = self
+            .build_history(&format!("{user_id}:{session_id}"), events)
+            .await?;
+        if let Some(memories) = recalled {
+            history.insert(0, memories);
+        }
+
+        let cancel_token = cancel_token.or_else(|| config.cancellation_token.clone());
+        let idle_timeout = config.timeout;
+
         // Create invocation context
         let invocation_id = Uuid::new_v4().to_string();
-        let ctx = Arc::new(DefaultInvocationContext::new(
-            invocation_id.clone(),
-            self.app_name.clone(),
-            user_id,
-            session_id.clone(),
-            Some(message.clone()),
-            self.agent.clone(),
-        ));
+        let mut ctx = DefaultInvocationContext::builder()
+            .invocation_id(invocation_id.clone())
+            .app_name(self.app_name.clone())
+            .user_id(user_id.clone())
+            .session_id(session_id.clone())
+            .user_content(message.clone())
+            .agent(self.agent.clone())
+            .history(history)
+            .state(session.state())
+            .session_service(self.session_service.clone())
+            .run_config(config)
+            .runner_defaults(self.defaults.clone());
+        if let Some(service) = &self.artifact_service {
+            ctx = ctx.artifact_service(service.clone());
+        }
+        if let Some(service) = &self.memory_service {
+            ctx = ctx.memory_service(service.clone());
+        }
+        let ctx = Arc::new(ctx.build()?);
 
         // Add user message to session as an event
         let mut user_event = Event::new(invocation_id.clone(), "user".to_string());
@@ -84,58 +235,319 @@ impl Runner {
         let agent = self.agent.clone();
         let session_service = self.session_service.clone();
         let session_id_clone = session_id.clone();
+        let pending_memorize = PendingMemorize {
+            memory: self.memory_service.clone().filter(|_| self.auto_memorize),
+            sessions: self.session_service.clone(),
+            request: GetRequest {
+                app_name: self.app_name.clone(),
+                user_id: user_id.clone(),
+                session_id: session_id.clone(),
+            },
+        };
 
         Ok(Box::new(Box::pin(stream! {
+            let _in_flight = in_flight;
+            let mut pending_memorize = pending_memorize;
             let mut event_stream = agent.run(ctx).await;
+            // Streamed text is persisted once, folded into the event that completes it
+            let mut transcript = TextAggregator::default();
 
             loop {
-                // Check cancellation
-                if let Some(ref token) = cancel_token
-                    && token.is_cancelled()
-                {
-                    // Create cancellation event
-                    let mut cancel_event = Event::new(invocation_id.clone(), "system".to_string());
-                    cancel_event.error_message = "Invocation cancelled".to_string();
-                    cancel_event.turn_complete = true;
-                    yield Ok(cancel_event);
-                    return;
+                let next = next_event(&mut event_stream, cancel_token.as_ref(), idle_timeout).await;
+                if !matches!(next, Ok(Some(Ok(_))) | Ok(None)) {
+                    // Failed or interrupted runs aren't worth remembering
+                    pending_memorize.disarm();
                 }
 
-                // Get next event
-                match event_stream.next().await {
-                    Some(event_result) => {
-                        match event_result {
-                            Ok(event) => {
-                                // Append non-partial events to session
-                                if !event.partial
-                                    && let Err(e) = session_service.append_event(&session_id_clone, event.clone()).await
-                                {
-                                    yield Err(e);
-                                    return;
-                                }
-
-                                yield Ok(event);
-                            }
-                            Err(e) => {
-                                yield Err(e);
-                                return;
-                            }
-                        }
+                // Events to persist, what to hand the caller, and whether the run is over
+                let (record, outcome, done) = match next {
+                    Ok(Some(Ok(event))) => (transcript.push(event.clone()), Some(Ok(event)), false),
+                    Ok(Some(Err(e))) => {
+                        let mut error_event = Event::new(invocation_id.clone(), "system".to_string());
+                        error_event.error_code = "AGENT_ERROR".to_string();
+                        error_event.error_message = e.to_string();
+                        error_event.turn_complete = true;
+
+                        let mut record: Vec<Event> = transcript.finish().into_iter().collect();
+                        record.push(error_event);
+                        (record, Some(Err(e)), true)
                     }
-                    None => {
-                        // Stream ended normally
+                    // Stream ended normally
+                    Ok(None) => (transcript.finish().into_iter().collect(), None, true),
+                    Err(Interruption::Cancelled) => {
+                        // Create cancellation event
+                        let mut cancel_event = Event::new(invocation_id.clone(), "system".to_string());
+                        cancel_event.error_message = "Invocation cancelled".to_string();
+                        cancel_event.interrupted = true;
+                        cancel_event.turn_complete = true;
+
+                        let mut record: Vec<Event> = transcript.finish().into_iter().collect();
+                        record.push(cancel_event.clone());
+                        (record, Some(Ok(cancel_event)), true)
+                    }
+                    Err(Interruption::TimedOut) => {
+                        let mut timeout_event = Event::new(invocation_id.clone(), "system".to_string());
+                        timeout_event.error_code = "TIMEOUT".to_string();
+                        timeout_event.error_message = format!(
+                            "No event received within {:?}",
+                            idle_timeout.unwrap_or_default()
+                        );
+                        timeout_event.turn_complete = true;
+
+                        let mut record: Vec<Event> = transcript.finish().into_iter().collect();
+                        record.push(timeout_event.clone());
+                        (record, Some(Ok(timeout_event)), true)
+                    }
+                };
+
+                for event in record {
+                    if let Err(e) = session_service.append_event(&session_id_clone, event).await {
+                        pending_memorize.disarm();
+                        yield Err(e);
                         return;
                     }
                 }
+                if let Some(outcome) = outcome {
+                    yield outcome;
+                }
+                if done {
+                    pending_memorize.finish().await;
+                    return;
+                }
             }
         })))
     }
+
+    /// Search memory for up to `limit` entries relevant to `message`
+    ///
+    /// Entries recorded from `current`, the session's own events, are skipped
+    /// since they are already part of the history. Returns `None` when
+    /// disabled, without a memory service, or on no match.
+    async fn recall(
+        &self,
+        user_id: &str,
+        message: &Content,
+        limit: usize,
+        current: &[Event],
+    ) -> Result<Option<Content>> {
+        let Some(ref memory) = self.memory_service else {
+            return Ok(None);
+        };
+        let query = message.text();
+        if limit == 0 || query.trim().is_empty() {
+            return Ok(None);
+        }
+
+        let response = memory
+            .search(SearchRequest {
+                query,
+                user_id: user_id.to_string(),
+                app_name: self.app_name.clone(),
+            })
+            .await?;
+        let memories: Vec<MemoryEntry> = response
+            .memories
+            .into_iter()
+            .filter(|memory| !current.iter().any(|event| recorded_from(memory, event)))
+            .take(limit)
+            .collect();
+
+        tracing::debug!(recalled = memories.len(), "Searched memory for context");
+
+        Ok(recollection(&memories))
+    }
+
+    /// Turn persisted session events into conversation history for the agent
+    ///
+    /// When a compactor is configured and the history exceeds the threshold,
+    /// everything but the most recent events is replaced by a single synopsis.
+    /// The synopsis is kept per session and later runs only fold in the events
+    /// that have aged out of the recent window since.
+    async fn build_history(&self, key: &str, events: Vec<Event>) -> Result<Vec<Content>> {
+        let events: Vec<Event> = events
+            .into_iter()
+            .filter(|e| !e.partial && e.content.is_some())
+            .collect();
+
+        let Some(ref compactor) = self.compactor else {
+            return Ok(events.into_iter().filter_map(|e| e.content).collect());
+        };
+        let split = events.len().saturating_sub(self.compaction_keep_recent);
+        if events.len() <= self.compaction_threshold || split == 0 {
+            return Ok(events.into_iter().filter_map(|e| e.content).collect());
+        }
+
+        let (old, recent) = events.split_at(split);
+        let checkpoint = self
+            .checkpoints
+            .lock()
+            .unwrap()
+            .get(key)
+            .filter(|c| {
+                c.covered > 0 && c.covered <= split && old[c.covered - 1].id == c.last_event_id
+            })
+            .cloned();
+
+        let summary = match checkpoint {
+            Some(c) if c.covered == split => c.summary,
+            Some(c) => compactor.extend(&c.summary, &old[c.covered..]).await?,
+            None => compactor.compact(old).await?,
+        };
+
+        {
+            let mut checkpoints = self.checkpoints.lock().unwrap();
+            if checkpoints.len() >= MAX_CHECKPOINTS
+                && !checkpoints.contains_key(key)
+                && let Some(evicted) = checkpoints.keys().next().cloned()
+            {
+                checkpoints.remove(&evicted);
+            }
+            checkpoints.insert(
+                key.to_string(),
+                Checkpoint {
+                    summary: summary.clone(),
+                    covered: split,
+                    last_event_id: old[split - 1].id.clone(),
+                },
+            );
+        }
+
+        tracing::debug!(
+            compacted = old.len(),
+            kept = recent.len(),
+            "Compacted conversation history"
+        );
+
+        let mut history = Vec::with_capacity(recent.len() + 1);
+        history.push(summary);
+        history.extend(recent.iter().filter_map(|e| e.content.clone()));
+        Ok(history)
+    }
+}
+
+/// Whether `memory` was recorded from `event`
+fn recorded_from(memory: &MemoryEntry, event: &Event) -> bool {
+    memory.author == event.author
+        && memory.timestamp.timestamp() == event.time
+        && memory.content.as_ref().map(Content::text) == event.content.as_ref().map(Content::text)
+}
+
+/// Memorizes a run's session once its stream ends
+///
+/// Callers commonly stop reading after the final event, before the stream
+/// reports its end, so dropping the stream memorizes whatever the run has
+/// persisted so far in the background.
+struct PendingMemorize {
+    /// Unset when auto-memorize is off or the run failed
+    memory: Option<Arc<dyn MemoryService>>,
+    sessions: Arc<dyn SessionService>,
+    request: GetRequest,
+}
+
+impl PendingMemorize {
+    fn disarm(&mut self) {
+        self.memory = None;
+    }
+
+    async fn finish(&mut self) {
+        if let Some(memory) = self.memory.take() {
+            memorize(memory.as_ref(), self.sessions.as_ref(), &self.request).await;
+        }
+    }
+}
+
+impl Drop for PendingMemorize {
+    fn drop(&mut self) {
+        let Some(memory) = self.memory.take() else {
+            return;
+        };
+        let sessions = self.sessions.clone();
+        let request = self.request.clone();
+
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(async move {
+                    memorize(memory.as_ref(), sessions.as_ref(), &request).await
+                });
+            }
+            Err(_) => tracing::warn!(
+                session_id = %request.session_id,
+                "Session not added to memory outside a Tokio runtime"
+            ),
+        }
+    }
+}
+
+/// Add a finished session to memory so later runs can recall it
+///
+/// The run itself has already succeeded, so failures are logged rather than
+/// surfaced to the caller.
+async fn memorize(memory: &dyn MemoryService, sessions: &dyn SessionService, request: &GetRequest) {
+    let result = async { memory.add_session(sessions.get(request).await?).await }.await;
+    if let Err(e) = result {
+        tracing::warn!(
+            session_id = %request.session_id,
+            error = %e,
+            "Failed to add session to memory"
+        );
+    }
+}
+
+/// Wrap recalled memories as a message placed ahead of the conversation
+fn recollection(memories: &[MemoryEntry]) -> Option<Content> {
+    let lines: Vec<String> = memories
+        .iter()
+        .filter_map(|memory| {
+            let text = memory.content.as_ref()?.text();
+            (!text.is_empty()).then(|| format!("- [{}] {}", memory.author, text))
+        })
+        .collect();
+    if lines.is_empty() {
+        return None;
+    }
+
+    Some(Content::new_user_text(format!(
+        "Relevant memories from earlier conversations:\n{}",
+        lines.join("\n")
+    )))
+}
+
+/// Wait for the next agent event, honoring cancellation and the idle timeout
+async fn next_event(
+    event_stream: &mut (dyn Stream<Item = Result<Event>> + Send + Unpin),
+    cancel_token: Option<&CancellationToken>,
+    idle_timeout: Option<Duration>,
+) -> std::result::Result<Option<Result<Event>>, Interruption> {
+    let wait = async {
+        match idle_timeout {
+            Some(duration) => tokio::time::timeout(duration, event_stream.next())
+                .await
+                .map_err(|_| Interruption::TimedOut),
+            None => Ok(event_stream.next().await),
+        }
+    };
+
+    match cancel_token {
+        Some(token) => tokio::select! {
+            biased;
+            _ = token.cancelled() => Err(Interruption::Cancelled),
+            next = wait => next,
+        },
+        None => wait.await,
+    }
 }
 
 pub struct RunnerBuilder {
     app_name: Option<String>,
     agent: Option<Arc<dyn Agent>>,
     session_service: Option<Arc<dyn SessionService>>,
+    artifact_service: Option<Arc<dyn ArtifactService>>,
+    memory_service: Option<Arc<dyn MemoryService>>,
+    auto_memorize: bool,
+    compactor: Option<Arc<dyn Compactor>>,
+    compaction_threshold: usize,
+    compaction_keep_recent: usize,
+    defaults: RunnerDefaults,
 }
 
 impl RunnerBuilder {
@@ -144,6 +556,13 @@ impl RunnerBuilder {
             app_name: None,
             agent: None,
             session_service: None,
+            artifact_service: None,
+            memory_service: None,
+            auto_memorize: false,
+            compactor: None,
+            compaction_threshold: DEFAULT_COMPACTION_THRESHOLD,
+            compaction_keep_recent: DEFAULT_COMPACTION_KEEP_RECENT,
+            defaults: RunnerDefaults::default(),
         }
     }
 
@@ -162,6 +581,54 @@ impl RunnerBuilder {
         self
     }
 
+    /// Artifact service tools can save files to, scoped to the run's session
+    pub fn artifact_service(mut self, service: Arc<dyn ArtifactService>) -> Self {
+        self.artifact_service = Some(service);
+        self
+    }
+
+    /// Memory service searched for context when a run sets
+    /// [`RunConfig::include_memories`], and exposed to agents
+    pub fn memory_service(mut self, service: Arc<dyn MemoryService>) -> Self {
+        self.memory_service = Some(service);
+        self
+    }
+
+    /// Add the session to the memory service whenever a run completes
+    ///
+    /// Runs that fail, are cancelled or time out are not memorized. Requires
+    /// a [`memory_service`](Self::memory_service).
+    pub fn auto_memorize(mut self, enabled: bool) -> Self {
+        self.auto_memorize = enabled;
+        self
+    }
+
+    /// Summarize old turns with `compactor` once the session grows too long
+    pub fn compactor(mut self, compactor: Arc<dyn Compactor>) -> Self {
+        self.compactor = Some(compactor);
+        self
+    }
+
+    /// Number of history events above which compaction kicks in
+    pub fn compaction_threshold(mut self, threshold: usize) -> Self {
+        self.compaction_threshold = threshold;
+        self
+    }
+
+    /// Number of most recent events kept verbatim when compacting
+    pub fn compaction_keep_recent(mut self, keep_recent: usize) -> Self {
+        self.compaction_keep_recent = keep_recent;
+        self
+    }
+
+    /// Defaults for every agent run through this runner
+    ///
+    /// Agents only fall back to these for settings they leave unset.
+    pub fn defaults(mut self, defaults: RunnerDefaults) -> Self {
+        self.defaults = defaults;
+        self
+    }
+
     pub fn build(self) -> Result<Runner> {
         let app_name = self
             .app_name
@@ -172,11 +639,26 @@ impl RunnerBuilder {
         let session_service = self
             .session_service
             .ok_or_else(|| Error::Other(anyhow::anyhow!("Session service is required")))?;
+        if self.auto_memorize && self.memory_service.is_none() {
+            return Err(Error::Other(anyhow::anyhow!(
+                "Auto-memorize requires a memory service"
+            )));
+        }
 
         Ok(Runner {
             app_name,
             agent,
             session_service,
+            artifact_service: self.artifact_service,
+            memory_service: self.memory_service,
+            auto_memorize: self.auto_memorize,
+            compactor: self.compactor,
+            compaction_threshold: self.compaction_threshold,
+            compaction_keep_recent: self.compaction_keep_recent,
+            checkpoints: Mutex::new(HashMap::new()),
+            defaults: self.defaults,
+            shutting_down: AtomicBool::new(false),
+            in_flight: Arc::new(InFlight::default()),
         })
     }
 }
@@ -186,8 +668,3 @@ impl Default for RunnerBuilder {
         Self::new()
     }
 }
-
-#[derive(Debug, Clone, Default)]
-pub struct RunConfig {
-    pub streaming: bool,
-}
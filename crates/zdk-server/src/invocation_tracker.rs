@@ -1,48 +1,135 @@
-//! Invocation tracking for cancellation support
+//! Invocation tracking for cancellation and resume support
 
-use dashmap::DashMap;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use dashmap::{DashMap, mapref::entry::Entry};
+use tokio::sync::Notify;
+use tokio::time::Instant;
 use tokio_util::sync::CancellationToken;
+use tracing::warn;
 use uuid::Uuid;
+use zdk_core::Event;
 
 use crate::ws_types::InvocationStatus;
 
+/// Default number of updates buffered per invocation
+pub const DEFAULT_BUFFER_CAPACITY: usize = 256;
+
+/// How long a finished invocation's undelivered updates wait for a resume
+pub const DEFAULT_RESUME_TTL: Duration = Duration::from_secs(300);
+
+/// Number of finished invocations whose final status stays queryable
+pub const FINISHED_HISTORY: usize = 1024;
+
 /// Tracks active invocations and provides cancellation support
+///
+/// Every update an invocation produces is kept in a bounded ring buffer until
+/// it has been delivered, so a client that disconnects mid-run can resume and
+/// receive what it missed.
 #[derive(Clone)]
 pub struct InvocationTracker {
-    /// Map of invocation_id to cancellation token and status
+    /// Map of invocation_id to cancellation token, status and buffered updates
     active: DashMap<String, InvocationEntry>,
+    /// Maximum number of updates buffered per invocation
+    buffer_capacity: usize,
+    /// How long a finished invocation is kept for a client to resume
+    resume_ttl: Duration,
+    /// Final status of recently finished invocations, oldest first
+    finished: Arc<Mutex<VecDeque<(String, InvocationStatus)>>>,
+}
+
+/// An update produced by a running invocation
+#[derive(Debug, Clone)]
+pub enum InvocationUpdate {
+    /// An event from the agent execution
+    Event(Box<Event>),
+    /// The agent failed
+    Error(String),
+    /// The invocation finished; always the last update
+    Completed,
 }
 
 #[derive(Clone)]
 struct InvocationEntry {
     token: CancellationToken,
     status: InvocationStatus,
+    /// Ring buffer of `(sequence, update)` pairs
+    updates: VecDeque<(u64, InvocationUpdate)>,
+    /// Sequence number of the next published update
+    next_seq: u64,
+    /// Sequence number of the first update not yet delivered to a client
+    delivered: u64,
+    /// When [`InvocationUpdate::Completed`] was published, if it has been
+    finished_at: Option<Instant>,
+    /// Wakes receivers waiting for new updates
+    notify: Arc<Notify>,
 }
 
 impl InvocationTracker {
     /// Create a new invocation tracker
     pub fn new() -> Self {
+        Self::with_buffer_capacity(DEFAULT_BUFFER_CAPACITY)
+    }
+
+    /// Create a tracker buffering at most `capacity` updates per invocation
+    ///
+    /// When a disconnected client falls further behind than this, the oldest
+    /// updates are dropped.
+    pub fn with_buffer_capacity(capacity: usize) -> Self {
         Self {
             active: DashMap::new(),
+            buffer_capacity: capacity.max(1),
+            resume_ttl: DEFAULT_RESUME_TTL,
+            finished: Arc::new(Mutex::new(VecDeque::new())),
         }
     }
 
+    /// Keep finished invocations with undelivered updates for `ttl` instead
+    /// of [`DEFAULT_RESUME_TTL`]
+    ///
+    /// After that their updates are dropped, so a client that disconnected
+    /// and never came back doesn't keep them in memory forever.
+    pub fn with_resume_ttl(mut self, ttl: Duration) -> Self {
+        self.resume_ttl = ttl;
+        self
+    }
+
     /// Register a new invocation and return its ID and cancellation token
     pub fn register(&self) -> (String, CancellationToken) {
         let id = Uuid::new_v4().to_string();
-        let token = CancellationToken::new();
-
-        self.active.insert(
-            id.clone(),
-            InvocationEntry {
-                token: token.clone(),
-                status: InvocationStatus::Active,
-            },
-        );
-
+        let token = self
+            .register_with_id(id.clone())
+            .expect("freshly generated invocation IDs are unique");
         (id, token)
     }
 
+    /// Register a new invocation under a caller-chosen ID
+    ///
+    /// Returns `None` if an invocation with that ID is already running.
+    /// Expired invocations are swept first, so stale entries go away as new
+    /// runs start.
+    pub fn register_with_id(&self, invocation_id: String) -> Option<CancellationToken> {
+        self.sweep();
+        let token = CancellationToken::new();
+        match self.active.entry(invocation_id) {
+            Entry::Occupied(_) => return None,
+            Entry::Vacant(entry) => {
+                entry.insert(InvocationEntry {
+                    token: token.clone(),
+                    status: InvocationStatus::Active,
+                    updates: VecDeque::new(),
+                    next_seq: 0,
+                    delivered: 0,
+                    finished_at: None,
+                    notify: Arc::new(Notify::new()),
+                });
+            }
+        }
+        Some(token)
+    }
+
     /// Cancel an invocation by its ID
     ///
     /// Returns true if the invocation was found and cancelled, false otherwise
@@ -65,21 +152,181 @@ impl InvocationTracker {
     }
 
     /// Get the status of an invocation
+    ///
+    /// The final status of the last [`FINISHED_HISTORY`] finished invocations
+    /// stays available after they stop being tracked.
     pub fn status(&self, invocation_id: &str) -> InvocationStatus {
-        self.active
-            .get(invocation_id)
-            .map(|entry| entry.status.clone())
+        if let Some(entry) = self.active.get(invocation_id) {
+            return entry.status.clone();
+        }
+        self.finished
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|(id, _)| id == invocation_id)
+            .map(|(_, status)| status.clone())
             .unwrap_or(InvocationStatus::NotFound)
     }
 
-    /// Mark an invocation as completed and remove it from tracking
+    /// Mark an invocation as completed
+    ///
+    /// Publishes [`InvocationUpdate::Completed`]. The invocation is removed from
+    /// tracking right away if every earlier update has been delivered, otherwise
+    /// once a resumed client has caught up (see [`ack`](Self::ack)).
     pub fn complete(&self, invocation_id: &str) {
         if let Some(mut entry) = self.active.get_mut(invocation_id) {
-            entry.status = InvocationStatus::Completed;
+            // A cancelled or failed run still finishes, but keeps reporting
+            // why it ended
+            if entry.status == InvocationStatus::Active {
+                entry.status = InvocationStatus::Completed;
+            }
+            entry.finished_at = Some(Instant::now());
         }
-        // Remove after a short delay to allow status queries
-        // In production, this might use a TTL cache or background cleanup task
-        self.active.remove(invocation_id);
+        self.publish(invocation_id, InvocationUpdate::Completed);
+        self.remove_if_drained(invocation_id);
+    }
+
+    /// Record that an invocation ended with an error
+    ///
+    /// The invocation still has to be [`complete`](Self::complete)d; it then
+    /// keeps reporting [`InvocationStatus::Errored`]. A cancelled invocation
+    /// stays cancelled.
+    pub fn fail(&self, invocation_id: &str) {
+        if let Some(mut entry) = self.active.get_mut(invocation_id)
+            && entry.status == InvocationStatus::Active
+        {
+            entry.status = InvocationStatus::Errored;
+        }
+    }
+
+    /// Buffer an update for an invocation and wake its receivers
+    ///
+    /// Returns false if the invocation is not tracked.
+    pub fn publish(&self, invocation_id: &str, update: InvocationUpdate) -> bool {
+        let Some(mut entry) = self.active.get_mut(invocation_id) else {
+            return false;
+        };
+
+        let seq = entry.next_seq;
+        entry.next_seq += 1;
+        entry.updates.push_back((seq, update));
+        if entry.updates.len() > self.buffer_capacity {
+            entry.updates.pop_front();
+        }
+        entry.notify.notify_waiters();
+        true
+    }
+
+    /// Sequence number a resuming client should continue from
+    ///
+    /// This is the first update not yet acknowledged, or `None` if the
+    /// invocation is not tracked.
+    pub fn resume_cursor(&self, invocation_id: &str) -> Option<u64> {
+        self.active.get(invocation_id).map(|entry| entry.delivered)
+    }
+
+    /// Wait for the next update at or after `cursor` and advance the cursor
+    /// past it
+    ///
+    /// Returns `None` once the invocation is no longer tracked, which for a
+    /// completed invocation happens as soon as everything before
+    /// [`InvocationUpdate::Completed`] is acknowledged. Updates already
+    /// dropped from the ring buffer are skipped with a warning.
+    pub async fn recv(&self, invocation_id: &str, cursor: &mut u64) -> Option<InvocationUpdate> {
+        loop {
+            let notify = self.active.get(invocation_id)?.notify.clone();
+            let notified = notify.notified();
+            tokio::pin!(notified);
+            // Register before checking the buffer so a publish in between isn't missed
+            notified.as_mut().enable();
+
+            {
+                let entry = self.active.get(invocation_id)?;
+                if let Some((seq, update)) = entry.updates.iter().find(|(seq, _)| *seq >= *cursor) {
+                    if *seq > *cursor {
+                        warn!(
+                            "Invocation {}: {} buffered update(s) dropped before delivery",
+                            invocation_id,
+                            seq - *cursor
+                        );
+                    }
+                    *cursor = seq + 1;
+                    return Some(update.clone());
+                }
+            }
+
+            notified.await;
+        }
+    }
+
+    /// Record that every update before `cursor` reached a client
+    ///
+    /// Completed invocations are removed from tracking once fully delivered.
+    pub fn ack(&self, invocation_id: &str, cursor: u64) {
+        if let Some(mut entry) = self.active.get_mut(invocation_id) {
+            entry.delivered = entry.delivered.max(cursor);
+            while entry
+                .updates
+                .front()
+                .is_some_and(|(seq, _)| *seq < entry.delivered)
+            {
+                entry.updates.pop_front();
+            }
+        }
+        self.remove_if_drained(invocation_id);
+    }
+
+    /// Stop tracking finished invocations nobody resumed within the resume TTL,
+    /// returning how many were dropped
+    pub fn sweep(&self) -> usize {
+        let expired: Vec<String> = self
+            .active
+            .iter()
+            .filter(|entry| self.is_expired(entry.value()))
+            .map(|entry| entry.key().clone())
+            .collect();
+
+        let mut dropped = 0;
+        for id in expired {
+            // Re-check in case it was resumed and drained meanwhile
+            if let Some((id, entry)) = self.active.remove_if(&id, |_, e| self.is_expired(e)) {
+                warn!(
+                    "Invocation {}: dropping {} undelivered update(s) after the resume TTL",
+                    id,
+                    entry.next_seq - entry.delivered
+                );
+                self.remember(id, entry.status);
+                dropped += 1;
+            }
+        }
+        dropped
+    }
+
+    fn is_expired(&self, entry: &InvocationEntry) -> bool {
+        entry
+            .finished_at
+            .is_some_and(|at| at.elapsed() > self.resume_ttl)
+    }
+
+    /// Stop tracking a finished invocation once only its `Completed` marker is
+    /// left undelivered
+    fn remove_if_drained(&self, invocation_id: &str) {
+        let removed = self.active.remove_if(invocation_id, |_, entry| {
+            entry.finished_at.is_some() && entry.delivered + 1 >= entry.next_seq
+        });
+        if let Some((id, entry)) = removed {
+            self.remember(id, entry.status);
+        }
+    }
+
+    /// Keep the final status of a finished invocation queryable
+    fn remember(&self, invocation_id: String, status: InvocationStatus) {
+        let mut finished = self.finished.lock().unwrap();
+        if finished.len() >= FINISHED_HISTORY {
+            finished.pop_front();
+        }
+        finished.push_back((invocation_id, status));
     }
 
     /// Unregister an invocation (cleanup)
@@ -132,7 +379,145 @@ mod tests {
         let (id, _token) = tracker.register();
 
         tracker.complete(&id);
-        // After complete, status should be NotFound (removed)
-        assert!(matches!(tracker.status(&id), InvocationStatus::NotFound));
+        // No longer tracked, but the final status is remembered
+        assert!(tracker.resume_cursor(&id).is_none());
+        assert_eq!(tracker.status(&id), InvocationStatus::Completed);
+    }
+
+    #[test]
+    fn test_cancelled_status_survives_completion() {
+        let tracker = InvocationTracker::new();
+        let (id, _token) = tracker.register();
+        tracker.publish(&id, event(&id, 0));
+
+        tracker.cancel(&id);
+        tracker.complete(&id);
+        // Kept for the undelivered event, still reported as cancelled
+        assert_eq!(tracker.status(&id), InvocationStatus::Cancelled);
+    }
+
+    #[test]
+    fn test_errored_status_survives_completion() {
+        let tracker = InvocationTracker::new();
+        let (id, _token) = tracker.register();
+
+        tracker.fail(&id);
+        assert_eq!(tracker.status(&id), InvocationStatus::Errored);
+        tracker.complete(&id);
+        assert_eq!(tracker.status(&id), InvocationStatus::Errored);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_abandoned_invocation_swept_after_ttl() {
+        let tracker = InvocationTracker::new().with_resume_ttl(Duration::from_secs(60));
+        let (id, _token) = tracker.register();
+        tracker.publish(&id, event(&id, 0));
+        // The client went away before the event was delivered
+        tracker.complete(&id);
+
+        tokio::time::advance(Duration::from_secs(30)).await;
+        assert_eq!(tracker.sweep(), 0);
+        assert!(tracker.resume_cursor(&id).is_some());
+
+        tokio::time::advance(Duration::from_secs(31)).await;
+        // Starting another run sweeps the expired one
+        tracker.register();
+        assert!(tracker.resume_cursor(&id).is_none());
+        assert_eq!(tracker.status(&id), InvocationStatus::Completed);
+    }
+
+    fn event(id: &str, n: usize) -> InvocationUpdate {
+        InvocationUpdate::Event(Box::new(Event::new(id.to_string(), format!("agent-{}", n))))
+    }
+
+    fn author(update: &InvocationUpdate) -> &str {
+        match update {
+            InvocationUpdate::Event(event) => &event.author,
+            other => panic!("expected an event, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resume_after_disconnect() {
+        let tracker = Arc::new(InvocationTracker::new());
+        let (id, _token) = tracker.register();
+
+        // First connection receives and acknowledges two events
+        tracker.publish(&id, event(&id, 0));
+        tracker.publish(&id, event(&id, 1));
+        let mut cursor = tracker.resume_cursor(&id).unwrap();
+        for n in 0..2 {
+            let update = tracker.recv(&id, &mut cursor).await.unwrap();
+            assert_eq!(author(&update), format!("agent-{}", n));
+            tracker.ack(&id, cursor);
+        }
+
+        // It drops after receiving a third event it never got to send
+        tracker.publish(&id, event(&id, 2));
+        let mut lost = cursor;
+        tracker.recv(&id, &mut lost).await.unwrap();
+        tracker.publish(&id, event(&id, 3));
+
+        // A reconnecting client replays from the last acknowledged event...
+        let mut cursor = tracker.resume_cursor(&id).unwrap();
+        assert_eq!(cursor, 2);
+        for n in 2..4 {
+            let update = tracker.recv(&id, &mut cursor).await.unwrap();
+            assert_eq!(author(&update), format!("agent-{}", n));
+            tracker.ack(&id, cursor);
+        }
+
+        // ...and then follows live updates
+        let live = {
+            let tracker = tracker.clone();
+            let id = id.clone();
+            tokio::spawn(async move {
+                let mut received = Vec::new();
+                while let Some(update) = tracker.recv(&id, &mut cursor).await {
+                    received.push(author(&update).to_string());
+                    tracker.ack(&id, cursor);
+                }
+                received
+            })
+        };
+        tokio::task::yield_now().await;
+        tracker.publish(&id, event(&id, 4));
+        tracker.complete(&id);
+
+        assert_eq!(live.await.unwrap(), vec!["agent-4"]);
+        assert!(tracker.resume_cursor(&id).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_completed_invocation_kept_until_delivered() {
+        let tracker = InvocationTracker::new();
+        let (id, _token) = tracker.register();
+
+        tracker.publish(&id, event(&id, 0));
+        tracker.complete(&id);
+        assert_eq!(tracker.status(&id), InvocationStatus::Completed);
+
+        let mut cursor = tracker.resume_cursor(&id).unwrap();
+        let update = tracker.recv(&id, &mut cursor).await.unwrap();
+        assert_eq!(author(&update), "agent-0");
+        tracker.ack(&id, cursor);
+        assert!(tracker.resume_cursor(&id).is_none());
+        assert!(tracker.recv(&id, &mut cursor).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_buffer_is_bounded() {
+        let tracker = InvocationTracker::with_buffer_capacity(2);
+        let (id, _token) = tracker.register();
+
+        for n in 0..5 {
+            tracker.publish(&id, event(&id, n));
+        }
+
+        // Only the newest two events survive
+        let mut cursor = 0;
+        let update = tracker.recv(&id, &mut cursor).await.unwrap();
+        assert_eq!(author(&update), "agent-3");
+        assert_eq!(cursor, 4);
     }
 }
@@ -1,13 +1,24 @@
 //! Server implementations for ZDK
 
 pub mod invocation_tracker;
+pub mod rate_limit;
+pub mod readiness;
 pub mod rest;
+pub mod shutdown;
+pub mod transcript;
 pub mod types;
 pub mod websocket;
 pub mod ws_types;
 
-pub use invocation_tracker::InvocationTracker;
-pub use rest::create_router;
+pub use invocation_tracker::{InvocationTracker, InvocationUpdate};
+pub use rate_limit::{RateLimitConfig, RateLimiter};
+pub use readiness::ProviderReadiness;
+pub use rest::{
+    RouterConfig, create_router, create_router_with_auth, create_router_with_config,
+    create_router_with_cors,
+};
+pub use shutdown::serve_with_shutdown;
+pub use transcript::{Transcript, TranscriptFormat};
 pub use types::*;
 pub use websocket::ws_handler;
 pub use ws_types::{InvocationStatus, WsClientMessage, WsServerMessage};
@@ -0,0 +1,170 @@
+//! Token-bucket rate limiting for the REST router
+//!
+//! Each client gets a bucket holding up to `burst` tokens that refills at
+//! `requests_per_minute`. A request spends one token; with the bucket empty
+//! the request is rejected with `429 Too Many Requests` and a `Retry-After`
+//! header.
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use dashmap::DashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Buckets above this count trigger pruning of idle (full) buckets
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// Rate limit settings for [`RouterConfig::rate_limit`](crate::RouterConfig::rate_limit)
+#[derive(Debug, Clone)]
+pub struct RateLimitConfig {
+    /// Sustained requests allowed per client per minute
+    pub requests_per_minute: u32,
+    /// Requests a client may make in a burst before being throttled
+    pub burst: u32,
+    /// Header identifying the client (e.g. `x-forwarded-for` or an API key
+    /// header) instead of the peer IP address
+    pub client_key_header: Option<String>,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            requests_per_minute: 60,
+            burst: 10,
+            client_key_header: None,
+        }
+    }
+}
+
+/// Shared per-client token buckets
+pub struct RateLimiter {
+    buckets: DashMap<String, Bucket>,
+    /// Tokens added per second
+    refill_rate: f64,
+    capacity: f64,
+    client_key_header: Option<String>,
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl RateLimiter {
+    /// Create a limiter from the rate limit settings
+    pub fn new(config: &RateLimitConfig) -> Self {
+        Self {
+            buckets: DashMap::new(),
+            refill_rate: f64::from(config.requests_per_minute.max(1)) / 60.0,
+            capacity: f64::from(config.burst.max(1)),
+            client_key_header: config.client_key_header.clone(),
+        }
+    }
+
+    /// Spend a token for `key`
+    ///
+    /// Returns how long to wait before retrying if the bucket is empty.
+    pub fn check(&self, key: &str) -> Result<(), Duration> {
+        let now = Instant::now();
+        if self.buckets.len() > MAX_TRACKED_CLIENTS {
+            self.buckets
+                .retain(|_, bucket| self.refilled(bucket, now) < self.capacity);
+        }
+
+        let mut bucket = self.buckets.entry(key.to_string()).or_insert(Bucket {
+            tokens: self.capacity,
+            updated: now,
+        });
+        bucket.tokens = self.refilled(&bucket, now);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - bucket.tokens) / self.refill_rate,
+            ))
+        }
+    }
+
+    fn refilled(&self, bucket: &Bucket, now: Instant) -> f64 {
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        (bucket.tokens + elapsed * self.refill_rate).min(self.capacity)
+    }
+
+    /// Key identifying the client that sent `request`
+    ///
+    /// Uses the configured header when present, otherwise the peer IP address.
+    /// The peer address is only known when the server is started with
+    /// `into_make_service_with_connect_info::<SocketAddr>()`, as
+    /// [`serve_with_shutdown`](crate::serve_with_shutdown) does; without it
+    /// all clients share one bucket.
+    fn client_key(&self, request: &Request) -> String {
+        if let Some(value) = self
+            .client_key_header
+            .as_deref()
+            .and_then(|name| request.headers().get(name))
+            .and_then(|value| value.to_str().ok())
+        {
+            return value.to_string();
+        }
+
+        request
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip().to_string())
+            .unwrap_or_else(|| "unknown".to_string())
+    }
+}
+
+/// Middleware rejecting requests from clients that exhausted their bucket
+pub(crate) async fn rate_limit(
+    State(limiter): State<Arc<RateLimiter>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let key = limiter.client_key(&request);
+    match limiter.check(&key) {
+        Ok(()) => next.run(request).await,
+        Err(retry_after) => {
+            tracing::debug!("Rate limit exceeded for {}", key);
+            let seconds = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+            let json = serde_json::json!({ "error": "Rate limit exceeded" });
+            (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(header::RETRY_AFTER, seconds.to_string())],
+                Json(json),
+            )
+                .into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_allows_burst_then_throttles() {
+        let limiter = RateLimiter::new(&RateLimitConfig {
+            requests_per_minute: 30,
+            burst: 2,
+            client_key_header: None,
+        });
+
+        assert!(limiter.check("a").is_ok());
+        assert!(limiter.check("a").is_ok());
+        let retry_after = limiter.check("a").unwrap_err();
+        // One token every two seconds
+        assert!(retry_after > Duration::from_secs(1) && retry_after <= Duration::from_secs(2));
+
+        // Other clients have their own bucket
+        assert!(limiter.check("b").is_ok());
+    }
+}
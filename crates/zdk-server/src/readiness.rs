@@ -0,0 +1,126 @@
+//! Provider readiness checks for the `/readiness` endpoint
+//!
+//! A readiness probe pings the configured provider so an instance with an
+//! invalid API key or no route to the API is taken out of rotation. Results
+//! are cached for a short time so frequent probes don't hammer the provider.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use zdk_core::Provider;
+
+/// How long a ping result is reused before the provider is pinged again
+pub const DEFAULT_READINESS_TTL: Duration = Duration::from_secs(10);
+
+/// How long a ping may take before the provider counts as unreachable
+pub const DEFAULT_PING_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Cached outcome of pinging a provider
+pub struct ProviderReadiness {
+    provider: Arc<dyn Provider>,
+    ttl: Duration,
+    ping_timeout: Duration,
+    last: Mutex<Option<(Instant, Result<(), String>)>>,
+}
+
+impl ProviderReadiness {
+    /// Check `provider`, caching results for [`DEFAULT_READINESS_TTL`]
+    pub fn new(provider: Arc<dyn Provider>) -> Self {
+        Self::with_ttl(provider, DEFAULT_READINESS_TTL)
+    }
+
+    /// Check `provider`, caching results for `ttl`
+    pub fn with_ttl(provider: Arc<dyn Provider>, ttl: Duration) -> Self {
+        Self {
+            provider,
+            ttl,
+            ping_timeout: DEFAULT_PING_TIMEOUT,
+            last: Mutex::new(None),
+        }
+    }
+
+    /// Give up on a ping after `timeout` instead of [`DEFAULT_PING_TIMEOUT`]
+    pub fn with_ping_timeout(mut self, timeout: Duration) -> Self {
+        self.ping_timeout = timeout;
+        self
+    }
+
+    /// Whether the provider is ready, with the failure reason if not
+    ///
+    /// Concurrent callers wait on the same ping rather than each sending one,
+    /// which is bounded by the ping timeout so a hung provider can't stall
+    /// every probe. The reason may contain provider details, so keep it out
+    /// of unauthenticated responses.
+    pub async fn check(&self) -> Result<(), String> {
+        let mut last = self.last.lock().await;
+        if let Some((checked, result)) = last.as_ref()
+            && checked.elapsed() < self.ttl
+        {
+            return result.clone();
+        }
+
+        let result = match tokio::time::timeout(self.ping_timeout, self.provider.ping()).await {
+            Ok(result) => result.map_err(|e| e.to_string()),
+            Err(_) => Err(format!("Ping timed out after {:?}", self.ping_timeout)),
+        };
+        if let Err(reason) = &result {
+            tracing::warn!(reason = %reason, "Provider readiness check failed");
+        }
+        *last = Some((Instant::now(), result.clone()));
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use futures::stream::{self, Stream};
+    use zdk_core::{LLM, LLMRequest, LLMResponse, ProviderMetadata};
+
+    /// Provider whose ping never answers
+    struct HangingProvider;
+
+    #[async_trait]
+    impl LLM for HangingProvider {
+        fn name(&self) -> &str {
+            "hanging"
+        }
+
+        async fn generate_content(
+            &self,
+            _request: LLMRequest,
+            _stream: bool,
+        ) -> Box<dyn Stream<Item = zdk_core::Result<LLMResponse>> + Send + Unpin> {
+            Box::new(stream::empty())
+        }
+    }
+
+    #[async_trait]
+    impl Provider for HangingProvider {
+        fn metadata(&self) -> ProviderMetadata {
+            ProviderMetadata {
+                name: "hanging".to_string(),
+                display_name: "Hanging".to_string(),
+                capabilities: vec![],
+                models: vec![],
+            }
+        }
+
+        async fn ping(&self) -> zdk_core::Result<()> {
+            std::future::pending().await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hung_ping_times_out() {
+        let readiness = ProviderReadiness::new(Arc::new(HangingProvider))
+            .with_ping_timeout(Duration::from_millis(50));
+
+        let reason = tokio::time::timeout(Duration::from_secs(1), readiness.check())
+            .await
+            .expect("check should not wait on the hung ping")
+            .unwrap_err();
+        assert!(reason.contains("timed out"), "{}", reason);
+    }
+}
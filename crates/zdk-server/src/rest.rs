@@ -1,58 +1,267 @@
 use crate::invocation_tracker::InvocationTracker;
+use crate::rate_limit::{RateLimitConfig, RateLimiter, rate_limit};
+use crate::readiness::ProviderReadiness;
+use crate::transcript::{Transcript, TranscriptFormat, render_markdown};
 use crate::types::*;
 use crate::websocket::ws_handler;
+use crate::ws_types::InvocationStatus;
 use axum::{
     Router,
-    extract::{Json, Path, State},
-    http::StatusCode,
+    extract::{Json, Path, Query, Request, State},
+    http::{HeaderValue, Method, StatusCode, header},
+    middleware::{self, Next},
     response::{
         IntoResponse, Response,
-        sse::{Event as SseEvent, Sse},
+        sse::{Event as SseEvent, KeepAlive, Sse},
     },
     routing::{get, post},
 };
-use futures::stream::{Stream, StreamExt};
+use futures::stream::StreamExt;
 use std::convert::Infallible;
 use std::sync::Arc;
-use tower_http::cors::CorsLayer;
+use tokio_util::sync::CancellationToken;
+use tower_http::cors::{AllowOrigin, CorsLayer};
 use tower_http::trace::{DefaultMakeSpan, DefaultOnResponse, TraceLayer};
 use tracing::Level;
+use zdk_core::Provider;
 use zdk_runner::{RunConfig, Runner};
-use zdk_session::{CreateRequest, SessionService};
+use zdk_session::{CreateRequest, GetRequest, SessionService};
+
+/// Response header carrying the tracker ID of a run
+const INVOCATION_ID_HEADER: &str = "x-invocation-id";
 
 #[derive(Clone)]
 pub struct AppState {
     pub runner: Arc<Runner>,
     pub session_service: Arc<dyn SessionService>,
     pub invocation_tracker: Arc<InvocationTracker>,
+    /// Provider whose models are listed by `GET /api/v1/models`
+    pub provider: Option<Arc<dyn Provider>>,
+    /// Pings the provider on `/readiness`
+    pub provider_readiness: Option<Arc<ProviderReadiness>>,
+}
+
+/// Optional features of the router, combined as needed
+///
+/// ```no_run
+/// # use std::sync::Arc;
+/// # fn example(runner: Arc<zdk_runner::Runner>, session_service: Arc<dyn zdk_session::SessionService>, provider: Arc<dyn zdk_core::Provider>) {
+/// let config = zdk_server::RouterConfig::new()
+///     .auth_token("s3cret")
+///     .rate_limit(zdk_server::RateLimitConfig::default())
+///     .provider(provider);
+/// let router = zdk_server::create_router_with_config(runner, session_service, config);
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct RouterConfig {
+    auth_token: Option<String>,
+    rate_limit: Option<RateLimitConfig>,
+    provider: Option<Arc<dyn Provider>>,
+    cors_origins: Option<Vec<String>>,
 }
 
+impl RouterConfig {
+    /// No authentication, rate limiting or provider, and permissive CORS
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require `Authorization: Bearer <token>` on every API endpoint
+    ///
+    /// Requests without a matching token get `401 Unauthorized`. `/health` and
+    /// `/readiness` stay open so probes keep working.
+    pub fn auth_token(mut self, token: impl Into<String>) -> Self {
+        self.auth_token = Some(token.into());
+        self
+    }
+
+    /// Limit each client's requests to the API endpoints
+    ///
+    /// Clients over their limit get `429 Too Many Requests` with a
+    /// `Retry-After` header. `/health` and `/readiness` are not limited.
+    pub fn rate_limit(mut self, config: RateLimitConfig) -> Self {
+        self.rate_limit = Some(config);
+        self
+    }
+
+    /// Back `GET /api/v1/models` with `provider`
+    ///
+    /// `/readiness` also pings the provider, reporting `503 Service
+    /// Unavailable` while it is unreachable or rejects the credentials.
+    pub fn provider(mut self, provider: Arc<dyn Provider>) -> Self {
+        self.provider = Some(provider);
+        self
+    }
+
+    /// Only allow browser clients from `origins`
+    ///
+    /// Credentialed requests are allowed for the methods and headers the API
+    /// uses. Passing `"*"` as an origin allows any origin without
+    /// credentials, which is meant for local development only.
+    pub fn cors_origins(mut self, origins: Vec<String>) -> Self {
+        self.cors_origins = Some(origins);
+        self
+    }
+}
+
+/// Create the router without authentication, for local development
 pub fn create_router(runner: Arc<Runner>, session_service: Arc<dyn SessionService>) -> Router {
-    let state = AppState {
+    create_router_with_config(runner, session_service, RouterConfig::default())
+}
+
+/// Create the router requiring `Authorization: Bearer <token>` on the API
+///
+/// Shorthand for [`create_router_with_config`] with
+/// [`RouterConfig::auth_token`].
+pub fn create_router_with_auth(
+    runner: Arc<Runner>,
+    session_service: Arc<dyn SessionService>,
+    token: String,
+) -> Router {
+    create_router_with_config(
         runner,
         session_service,
-        invocation_tracker: Arc::new(InvocationTracker::new()),
-    };
+        RouterConfig::default().auth_token(token),
+    )
+}
 
-    Router::new()
-        // Health check endpoints
-        .route("/health", get(health_check))
-        .route("/readiness", get(readiness_check))
-        // API endpoints
+/// Create the router only allowing browser clients from `allowed_origins`
+///
+/// Shorthand for [`create_router_with_config`] with
+/// [`RouterConfig::cors_origins`].
+pub fn create_router_with_cors(
+    runner: Arc<Runner>,
+    session_service: Arc<dyn SessionService>,
+    allowed_origins: Vec<String>,
+) -> Router {
+    create_router_with_config(
+        runner,
+        session_service,
+        RouterConfig::default().cors_origins(allowed_origins),
+    )
+}
+
+/// Create the router with the features enabled in `config`
+pub fn create_router_with_config(
+    runner: Arc<Runner>,
+    session_service: Arc<dyn SessionService>,
+    config: RouterConfig,
+) -> Router {
+    build_router(
+        AppState {
+            runner,
+            session_service,
+            invocation_tracker: Arc::new(InvocationTracker::new()),
+            provider_readiness: config
+                .provider
+                .clone()
+                .map(|provider| Arc::new(ProviderReadiness::new(provider))),
+            provider: config.provider,
+        },
+        config.auth_token.map(Arc::from),
+        config
+            .rate_limit
+            .map(|rate_limit| Arc::new(RateLimiter::new(&rate_limit))),
+        config
+            .cors_origins
+            .map_or_else(CorsLayer::permissive, |origins| cors_layer(&origins)),
+    )
+}
+
+/// CORS layer allowing requests from `allowed_origins`
+fn cors_layer(allowed_origins: &[String]) -> CorsLayer {
+    let layer = CorsLayer::new()
+        .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
+        .allow_headers([header::AUTHORIZATION, header::CONTENT_TYPE]);
+
+    // Browsers reject `*` on credentialed requests, and echoing every origin
+    // back would let any site make them, so the wildcard goes without
+    if allowed_origins.iter().any(|origin| origin == "*") {
+        return layer.allow_origin(AllowOrigin::any());
+    }
+
+    let origins = allowed_origins.iter().filter_map(|origin| {
+        HeaderValue::from_str(origin)
+            .inspect_err(|_| tracing::warn!(origin = %origin, "Ignoring invalid CORS origin"))
+            .ok()
+    });
+    layer
+        .allow_origin(AllowOrigin::list(origins))
+        .allow_credentials(true)
+}
+
+fn build_router(
+    state: AppState,
+    token: Option<Arc<str>>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    cors: CorsLayer,
+) -> Router {
+    // API endpoints
+    let mut api = Router::new()
+        .route("/api/v1/models", get(list_models))
         .route("/api/v1/sessions", post(create_session))
         .route("/api/v1/sessions/:id/run", post(run_agent_batch))
         .route("/api/v1/sessions/:id/run/sse", post(run_agent_sse))
         .route("/api/v1/sessions/:id/run/ws", get(ws_handler))
+        .route("/api/v1/sessions/:id/transcript", get(get_transcript))
+        .route("/api/v1/invocations/:id", get(get_invocation_status));
+    if let Some(token) = token {
+        api = api.route_layer(middleware::from_fn_with_state(token, require_bearer_token));
+    }
+    // Added last so it runs first, throttling unauthenticated floods too
+    if let Some(limiter) = rate_limiter {
+        api = api.route_layer(middleware::from_fn_with_state(limiter, rate_limit));
+    }
+
+    Router::new()
+        // Health check endpoints
+        .route("/health", get(health_check))
+        .route("/readiness", get(readiness_check))
+        .merge(api)
         // Middleware layers (applied in reverse order)
         .layer(
             TraceLayer::new_for_http()
                 .make_span_with(DefaultMakeSpan::new().level(Level::INFO))
                 .on_response(DefaultOnResponse::new().level(Level::INFO)),
         )
-        .layer(CorsLayer::permissive())
+        .layer(cors)
         .with_state(state)
 }
 
+/// Reject requests whose `Authorization` header doesn't carry the expected bearer token
+async fn require_bearer_token(
+    State(token): State<Arc<str>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let provided = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    match provided {
+        Some(provided) if constant_time_eq(provided.as_bytes(), token.as_bytes()) => {
+            next.run(request).await
+        }
+        _ => {
+            let json = serde_json::json!({ "error": "Missing or invalid bearer token" });
+            (
+                StatusCode::UNAUTHORIZED,
+                [(header::WWW_AUTHENTICATE, "Bearer")],
+                Json(json),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Compare two byte strings without short-circuiting on the first mismatch
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
 /// Health check endpoint - returns OK if the service is running
 async fn health_check() -> impl IntoResponse {
     tracing::debug!("Health check requested");
@@ -60,17 +269,34 @@ async fn health_check() -> impl IntoResponse {
 }
 
 /// Readiness check endpoint - verifies service dependencies
-async fn readiness_check(State(_state): State<AppState>) -> impl IntoResponse {
-    // Check if services are available
-    // For now, just return OK as services are injected at startup
+async fn readiness_check(State(state): State<AppState>) -> Response {
     tracing::debug!("Readiness check requested");
 
-    // Could add checks like:
-    // - Database connectivity (if using database session service)
-    // - Runner availability
-    // - etc.
+    // Take the instance out of rotation while it drains
+    if state.runner.is_shutting_down() {
+        return (StatusCode::SERVICE_UNAVAILABLE, "SHUTTING DOWN").into_response();
+    }
+
+    // The reason is only logged: the route is unauthenticated and provider
+    // errors can carry request details
+    if let Some(readiness) = &state.provider_readiness
+        && readiness.check().await.is_err()
+    {
+        return (StatusCode::SERVICE_UNAVAILABLE, "PROVIDER UNAVAILABLE").into_response();
+    }
 
-    (StatusCode::OK, "READY")
+    (StatusCode::OK, "READY").into_response()
+}
+
+/// List the models offered by the configured provider
+async fn list_models(State(state): State<AppState>) -> Result<Response, AppError> {
+    let Some(provider) = state.provider else {
+        let json = serde_json::json!({ "error": "No provider configured" });
+        return Ok((StatusCode::NOT_FOUND, Json(json)).into_response());
+    };
+
+    let models = provider.list_models().await?;
+    Ok(Json(ListModelsResponse { models }).into_response())
 }
 
 async fn create_session(
@@ -93,48 +319,186 @@ async fn create_session(
     }))
 }
 
+/// A REST run registered with the invocation tracker, marked complete when
+/// dropped so runs abandoned by a disconnecting client don't stay active
+struct TrackedInvocation {
+    tracker: Arc<InvocationTracker>,
+    invocation_id: String,
+}
+
+impl TrackedInvocation {
+    /// Report the run as errored once it completes
+    fn fail(&self) {
+        self.tracker.fail(&self.invocation_id);
+    }
+}
+
+impl Drop for TrackedInvocation {
+    fn drop(&mut self) {
+        self.tracker.complete(&self.invocation_id);
+    }
+}
+
+/// A requested invocation ID that belongs to a running invocation
+struct InvocationConflict(String);
+
+impl IntoResponse for InvocationConflict {
+    fn into_response(self) -> Response {
+        let json = serde_json::json!({
+            "error": format!("Invocation {} is already running", self.0)
+        });
+        (StatusCode::CONFLICT, Json(json)).into_response()
+    }
+}
+
+/// Track a REST run in the invocation tracker so its status can be polled
+///
+/// A client-supplied ID is scoped to the session as `{session_id}:{id}`, so
+/// clients can't collide with or poll each other's runs by guessing IDs.
+/// Fails with `409 Conflict` if the scoped ID belongs to a running invocation.
+fn register_invocation(
+    state: &AppState,
+    session_id: &str,
+    requested: Option<String>,
+) -> Result<(TrackedInvocation, CancellationToken), InvocationConflict> {
+    let tracked = |invocation_id| TrackedInvocation {
+        tracker: state.invocation_tracker.clone(),
+        invocation_id,
+    };
+    let Some(requested) = requested else {
+        let (invocation_id, token) = state.invocation_tracker.register();
+        return Ok((tracked(invocation_id), token));
+    };
+    let invocation_id = format!("{}:{}", session_id, requested);
+
+    match state
+        .invocation_tracker
+        .register_with_id(invocation_id.clone())
+    {
+        Some(token) => Ok((tracked(invocation_id), token)),
+        None => Err(InvocationConflict(invocation_id)),
+    }
+}
+
 async fn run_agent_batch(
     Path(session_id): Path<String>,
     State(state): State<AppState>,
     Json(req): Json<RunAgentRequest>,
-) -> Result<Json<RunAgentResponse>, AppError> {
+) -> Result<Response, AppError> {
     // Extract user_id from session (simplified - in production, parse from session)
     let user_id = "user".to_string(); // TODO: Get from session
 
-    let config = RunConfig { streaming: false };
+    let config = RunConfig {
+        streaming: false,
+        ..Default::default()
+    };
+
+    let (invocation, cancel_token) =
+        match register_invocation(&state, &session_id, req.invocation_id) {
+            Ok(registered) => registered,
+            Err(conflict) => return Ok(conflict.into_response()),
+        };
+    let invocation_header = invocation.invocation_id.clone();
 
-    let mut event_stream = state
+    let mut event_stream = match state
         .runner
-        .run(user_id, session_id, req.new_message, config)
-        .await?;
+        .run_with_cancellation(
+            user_id,
+            session_id,
+            req.new_message,
+            config,
+            Some(cancel_token),
+        )
+        .await
+    {
+        Ok(stream) => stream,
+        Err(e) => {
+            invocation.fail();
+            return Err(AppError::from(e));
+        }
+    };
 
     let mut events = Vec::new();
     while let Some(event_result) = event_stream.next().await {
         match event_result {
             Ok(event) => events.push(event),
-            Err(e) => return Err(AppError::from(e)),
+            Err(e) => {
+                invocation.fail();
+                return Err(AppError::from(e));
+            }
         }
     }
+    drop(invocation);
 
-    Ok(Json(RunAgentResponse { events }))
+    Ok((
+        [(INVOCATION_ID_HEADER, invocation_header)],
+        Json(RunAgentResponse { events }),
+    )
+        .into_response())
 }
 
+/// Run the agent, sending each event (partial text included) as soon as it is produced
+///
+/// Keep-alive comments hold the connection open through idle stretches such as
+/// long tool calls. The `x-invocation-id` response header carries the ID to
+/// poll with `GET /api/v1/invocations/:id`.
 async fn run_agent_sse(
     Path(session_id): Path<String>,
     State(state): State<AppState>,
     Json(req): Json<RunAgentRequest>,
-) -> Result<Sse<impl Stream<Item = Result<SseEvent, Infallible>>>, AppError> {
+) -> Result<Response, AppError> {
     // Extract user_id from session (simplified)
     let user_id = "user".to_string(); // TODO: Get from session
 
-    let config = RunConfig { streaming: true };
+    let config = RunConfig {
+        streaming: true,
+        ..Default::default()
+    };
+
+    let (invocation, cancel_token) =
+        match register_invocation(&state, &session_id, req.invocation_id) {
+            Ok(registered) => registered,
+            Err(conflict) => return Ok(conflict.into_response()),
+        };
+    let invocation_header = invocation.invocation_id.clone();
 
-    let event_stream = state
+    let event_stream = match state
         .runner
-        .run(user_id, session_id, req.new_message, config)
-        .await?;
+        .run_with_cancellation(
+            user_id,
+            session_id,
+            req.new_message,
+            config,
+            Some(cancel_token),
+        )
+        .await
+    {
+        Ok(stream) => stream,
+        Err(e) => {
+            invocation.fail();
+            return Err(AppError::from(e));
+        }
+    };
+
+    // The stream owns the tracked invocation, completing it once the run ends
+    // or the client goes away
+    let sse_stream = event_stream.map(move |event_result| {
+        if event_result.is_err() {
+            invocation.fail();
+        }
+        sse_event(event_result)
+    });
+
+    Ok((
+        [(INVOCATION_ID_HEADER, invocation_header)],
+        Sse::new(sse_stream).keep_alive(KeepAlive::default()),
+    )
+        .into_response())
+}
 
-    let sse_stream = event_stream.map(|event_result| match event_result {
+/// Encode an agent event, or the error that ended the run, as an SSE event
+fn sse_event(event_result: zdk_core::Result<zdk_core::Event>) -> Result<SseEvent, Infallible> {
+    match event_result {
         Ok(event) => {
             let json = serde_json::to_string(&event).unwrap_or_else(|_| "{}".to_string());
             Ok(SseEvent::default().data(json))
@@ -145,9 +509,67 @@ async fn run_agent_sse(
             });
             Ok(SseEvent::default().data(error_json.to_string()))
         }
-    });
+    }
+}
+
+/// Report whether an invocation is still running, finished or was cancelled
+async fn get_invocation_status(
+    Path(invocation_id): Path<String>,
+    State(state): State<AppState>,
+) -> Response {
+    let status = state.invocation_tracker.status(&invocation_id);
+    let code = match status {
+        InvocationStatus::NotFound => StatusCode::NOT_FOUND,
+        _ => StatusCode::OK,
+    };
+    let body = InvocationStatusResponse {
+        invocation_id,
+        status,
+    };
+    (code, Json(body)).into_response()
+}
+
+/// Download a session's full event history as JSON or Markdown
+async fn get_transcript(
+    Path(session_id): Path<String>,
+    State(state): State<AppState>,
+    Query(query): Query<TranscriptQuery>,
+) -> Result<Response, AppError> {
+    // Same fixed user as the run endpoints until sessions carry auth, so a
+    // caller can't read transcripts belonging to another user
+    let session = state
+        .session_service
+        .get(&GetRequest {
+            app_name: state.runner.app_name().to_string(),
+            user_id: "user".to_string(),
+            session_id: session_id.clone(),
+        })
+        .await?;
+
+    let transcript = Transcript::from_session(session.as_ref());
+    let body = match query.format {
+        TranscriptFormat::Json => serde_json::to_string_pretty(&transcript)
+            .map_err(|e| AppError(anyhow::anyhow!("Failed to serialize transcript: {}", e)))?,
+        TranscriptFormat::Markdown => render_markdown(&transcript),
+    };
+
+    let disposition = format!(
+        "attachment; filename=\"session-{}.{}\"",
+        session_id.replace(['"', '\\', '/'], "_"),
+        query.format.extension()
+    );
 
-    Ok(Sse::new(sse_stream))
+    Ok((
+        [
+            (
+                header::CONTENT_TYPE,
+                query.format.content_type().to_string(),
+            ),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    )
+        .into_response())
 }
 
 // Error handling
@@ -167,10 +589,14 @@ impl From<anyhow::Error> for AppError {
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
+        let status = match self.0.downcast_ref::<zdk_core::Error>() {
+            Some(zdk_core::Error::SessionNotFound(_)) => StatusCode::NOT_FOUND,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
         let error_message = self.0.to_string();
         let json = serde_json::json!({
             "error": error_message
         });
-        (StatusCode::INTERNAL_SERVER_ERROR, Json(json)).into_response()
+        (status, Json(json)).into_response()
     }
 }
@@ -0,0 +1,91 @@
+//! Graceful server shutdown
+//!
+//! On shutdown the server stops accepting connections, the runner refuses new
+//! runs, and in-flight invocations get a grace period to finish before the
+//! server exits.
+
+use axum::Router;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+use zdk_runner::Runner;
+
+/// Serve `router` until `shutdown` is cancelled, then drain in-flight runs
+///
+/// Once the token is cancelled, new connections are refused and `runner`
+/// rejects new runs. The server then waits up to `grace` for in-flight runs
+/// (batch, SSE and WebSocket) to finish and for their responses to be
+/// written. Connections still open after the grace period are dropped.
+///
+/// Each connection's peer address is made available to the rate limiter, so
+/// clients are limited separately.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::sync::Arc;
+/// # use std::time::Duration;
+/// # use tokio_util::sync::CancellationToken;
+/// # async fn example(runner: Arc<zdk_runner::Runner>, session_service: Arc<dyn zdk_session::SessionService>) -> std::io::Result<()> {
+/// let listener = tokio::net::TcpListener::bind("127.0.0.1:8080").await?;
+/// let router = zdk_server::create_router(runner.clone(), session_service);
+///
+/// let shutdown = CancellationToken::new();
+/// let signal = shutdown.clone();
+/// tokio::spawn(async move {
+///     tokio::signal::ctrl_c().await.ok();
+///     signal.cancel();
+/// });
+///
+/// zdk_server::serve_with_shutdown(listener, router, runner, shutdown, Duration::from_secs(30)).await
+/// # }
+/// ```
+pub async fn serve_with_shutdown(
+    listener: TcpListener,
+    router: Router,
+    runner: Arc<Runner>,
+    shutdown: CancellationToken,
+    grace: Duration,
+) -> std::io::Result<()> {
+    let signal = shutdown.clone();
+    // Connect info gives the rate limiter each client's address
+    let server = axum::serve(
+        listener,
+        router.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(async move { signal.cancelled().await })
+    .into_future();
+    tokio::pin!(server);
+
+    tokio::select! {
+        result = &mut server => return result,
+        _ = shutdown.cancelled() => {}
+    }
+
+    info!(
+        "Shutting down, waiting for {} in-flight run(s)",
+        runner.in_flight()
+    );
+    runner.shutdown();
+
+    let deadline = tokio::time::Instant::now() + grace;
+    if !runner.wait_idle(grace).await {
+        warn!(
+            "Grace period elapsed with {} run(s) still in flight",
+            runner.in_flight()
+        );
+        return Ok(());
+    }
+
+    // Let connections finish writing their responses
+    match tokio::time::timeout_at(deadline, server).await {
+        Ok(result) => result,
+        Err(_) => {
+            warn!("Grace period elapsed with connections still open");
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,177 @@
+//! Session transcript rendering
+//!
+//! Turns a session's persisted event history into a downloadable document,
+//! either as JSON or as human-readable Markdown.
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use zdk_core::{Event, Part};
+use zdk_session::Session;
+
+/// Output format for a session transcript
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptFormat {
+    /// Full event history as JSON
+    #[default]
+    Json,
+    /// Conversation rendered as Markdown
+    Markdown,
+}
+
+impl TranscriptFormat {
+    /// MIME type used for the response body
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            TranscriptFormat::Json => "application/json",
+            TranscriptFormat::Markdown => "text/markdown; charset=utf-8",
+        }
+    }
+
+    /// File extension used in the download file name
+    pub fn extension(&self) -> &'static str {
+        match self {
+            TranscriptFormat::Json => "json",
+            TranscriptFormat::Markdown => "md",
+        }
+    }
+}
+
+/// JSON transcript document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Transcript {
+    #[serde(rename = "sessionId")]
+    pub session_id: String,
+    #[serde(rename = "appName")]
+    pub app_name: String,
+    #[serde(rename = "userId")]
+    pub user_id: String,
+    pub events: Vec<Event>,
+}
+
+impl Transcript {
+    /// Build a transcript from a session's full event history
+    pub fn from_session(session: &dyn Session) -> Self {
+        Self {
+            session_id: session.id().to_string(),
+            app_name: session.app_name().to_string(),
+            user_id: session.user_id().to_string(),
+            events: session.events(),
+        }
+    }
+}
+
+/// Render a session transcript as Markdown
+///
+/// Partial (streaming) events are skipped since their content is repeated by
+/// the final event of the same turn.
+pub fn render_markdown(transcript: &Transcript) -> String {
+    let mut out = String::new();
+
+    let _ = writeln!(out, "# Session {}", transcript.session_id);
+    let _ = writeln!(out);
+    let _ = writeln!(out, "- **App:** {}", transcript.app_name);
+    let _ = writeln!(out, "- **User:** {}", transcript.user_id);
+
+    for event in transcript.events.iter().filter(|e| !e.partial) {
+        let _ = writeln!(out);
+        let timestamp = chrono::DateTime::from_timestamp(event.time, 0)
+            .map(|t| t.to_rfc3339())
+            .unwrap_or_else(|| event.time.to_string());
+        let _ = writeln!(out, "## {} ({})", event.author, timestamp);
+
+        if let Some(ref content) = event.content {
+            for part in &content.parts {
+                let _ = writeln!(out);
+                render_part(&mut out, part);
+            }
+        }
+
+        if !event.error_message.is_empty() {
+            let _ = writeln!(out);
+            if event.error_code.is_empty() {
+                let _ = writeln!(out, "> **Error:** {}", event.error_message);
+            } else {
+                let _ = writeln!(
+                    out,
+                    "> **Error ({}):** {}",
+                    event.error_code, event.error_message
+                );
+            }
+        }
+    }
+
+    out
+}
+
+fn render_part(out: &mut String, part: &Part) {
+    match part {
+        Part::Text { text } => {
+            let _ = writeln!(out, "{}", text);
+        }
+        Part::InlineData { inline_data } => {
+            let _ = writeln!(out, "_[inline data: {}]_", inline_data.mime_type);
+        }
+        Part::FileUri { file_data } => {
+            let _ = writeln!(
+                out,
+                "_[file: {} ({})]_",
+                file_data.file_uri, file_data.mime_type
+            );
+        }
+        Part::FunctionCall { function_call } => {
+            let args = serde_json::to_string_pretty(&function_call.args).unwrap_or_default();
+            let _ = writeln!(out, "**Function call:** `{}`", function_call.name);
+            let _ = writeln!(out);
+            let _ = writeln!(out, "```json\n{}\n```", args);
+        }
+        Part::FunctionResponse { function_response } => {
+            let response =
+                serde_json::to_string_pretty(&function_response.response).unwrap_or_default();
+            let _ = writeln!(out, "**Function response:** `{}`", function_response.name);
+            let _ = writeln!(out);
+            let _ = writeln!(out, "```json\n{}\n```", response);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zdk_core::{Content, FunctionCall};
+
+    #[test]
+    fn test_render_markdown_skips_partial_events() {
+        let mut user = Event::new("inv1".to_string(), "user".to_string());
+        user.content = Some(Content::new_user_text("What is 2 + 2?"));
+
+        let mut partial = Event::new("inv1".to_string(), "assistant".to_string());
+        partial.partial = true;
+        partial.content = Some(Content::new_model_text("streaming chunk"));
+
+        let mut call = Event::new("inv1".to_string(), "assistant".to_string());
+        call.content = Some(Content {
+            role: "model".to_string(),
+            parts: vec![Part::FunctionCall {
+                function_call: FunctionCall {
+                    name: "calculator".to_string(),
+                    args: serde_json::json!({"expression": "2 + 2"}),
+                    id: None,
+                },
+            }],
+        });
+
+        let transcript = Transcript {
+            session_id: "s1".to_string(),
+            app_name: "app".to_string(),
+            user_id: "u1".to_string(),
+            events: vec![user, partial, call],
+        };
+
+        let markdown = render_markdown(&transcript);
+        assert!(markdown.starts_with("# Session s1"));
+        assert!(markdown.contains("What is 2 + 2?"));
+        assert!(markdown.contains("**Function call:** `calculator`"));
+        assert!(!markdown.contains("streaming chunk"));
+    }
+}
@@ -1,5 +1,7 @@
+use crate::transcript::TranscriptFormat;
+use crate::ws_types::InvocationStatus;
 use serde::{Deserialize, Serialize};
-use zdk_core::{Content, Event};
+use zdk_core::{Content, Event, ModelInfo};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateSessionRequest {
@@ -26,9 +28,31 @@ pub struct RunAgentRequest {
     #[serde(rename = "newMessage")]
     pub new_message: Content,
     pub streaming: bool,
+    /// ID for the run, scoped to the session as `{sessionId}:{invocationId}`
+    /// when polling `GET /api/v1/invocations/:id`; generated when absent
+    #[serde(rename = "invocationId", default)]
+    pub invocation_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RunAgentResponse {
     pub events: Vec<Event>,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvocationStatusResponse {
+    #[serde(rename = "invocationId")]
+    pub invocation_id: String,
+    pub status: InvocationStatus,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListModelsResponse {
+    pub models: Vec<ModelInfo>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TranscriptQuery {
+    #[serde(default)]
+    pub format: TranscriptFormat,
+}
@@ -1,5 +1,6 @@
 //! WebSocket handler for bidirectional communication
 
+use crate::invocation_tracker::InvocationUpdate;
 use crate::rest::AppState;
 use crate::ws_types::{WsClientMessage, WsServerMessage};
 use axum::{
@@ -9,11 +10,18 @@ use axum::{
     },
     response::IntoResponse,
 };
+use futures::stream::SplitSink;
 use futures::{SinkExt, StreamExt};
+use std::sync::Arc;
+use tokio::sync::Mutex;
 use tracing::{error, info, warn};
 use zdk_core::Content;
 use zdk_runner::RunConfig;
 
+/// Sending half of a WebSocket, shared between the connection and its
+/// invocation forwarders
+type WsSender = Arc<Mutex<SplitSink<WebSocket, Message>>>;
+
 /// WebSocket handler for agent interaction
 pub async fn ws_handler(ws: WebSocketUpgrade, State(state): State<AppState>) -> impl IntoResponse {
     ws.on_upgrade(|socket| handle_socket(socket, state))
@@ -21,7 +29,8 @@ pub async fn ws_handler(ws: WebSocketUpgrade, State(state): State<AppState>) ->
 
 /// Handle a WebSocket connection
 async fn handle_socket(socket: WebSocket, state: AppState) {
-    let (mut sender, mut receiver) = socket.split();
+    let (sender, mut receiver) = socket.split();
+    let sender: WsSender = Arc::new(Mutex::new(sender));
 
     info!("WebSocket connection established");
 
@@ -32,16 +41,19 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
                     session_id,
                     new_message,
                 }) => {
-                    handle_run(&session_id, new_message, &state, &mut sender).await;
+                    handle_run(&session_id, new_message, &state, &sender).await;
                 }
                 Ok(WsClientMessage::Cancel { invocation_id }) => {
-                    handle_cancel(&invocation_id, &state, &mut sender).await;
+                    handle_cancel(&invocation_id, &state, &sender).await;
                 }
                 Ok(WsClientMessage::Status { invocation_id }) => {
-                    handle_status(&invocation_id, &state, &mut sender).await;
+                    handle_status(&invocation_id, &state, &sender).await;
+                }
+                Ok(WsClientMessage::Resume { invocation_id }) => {
+                    handle_resume(&invocation_id, &state, &sender).await;
                 }
                 Err(e) => {
-                    send_error(&mut sender, format!("Invalid message format: {}", e)).await;
+                    send_error(&sender, format!("Invalid message format: {}", e)).await;
                 }
             },
             Ok(Message::Close(_)) => {
@@ -52,7 +64,7 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
                 // Handle ping/pong for keep-alive
             }
             Ok(Message::Binary(_)) => {
-                send_error(&mut sender, "Binary messages not supported".to_string()).await;
+                send_error(&sender, "Binary messages not supported".to_string()).await;
             }
             Err(e) => {
                 error!("WebSocket error: {}", e);
@@ -65,12 +77,11 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
 }
 
 /// Handle a run command
-async fn handle_run(
-    session_id: &str,
-    new_message: Content,
-    state: &AppState,
-    sender: &mut futures::stream::SplitSink<WebSocket, Message>,
-) {
+///
+/// The agent runs in a background task that buffers its events in the
+/// invocation tracker, so it keeps going if this connection drops and a new
+/// connection can pick it up with a resume command.
+async fn handle_run(session_id: &str, new_message: Content, state: &AppState, sender: &WsSender) {
     // Register invocation
     let (invocation_id, cancel_token) = state.invocation_tracker.register();
 
@@ -88,7 +99,10 @@ async fn handle_run(
     let user_id = "user".to_string(); // TODO: Get from session or auth
 
     // Run agent with cancellation support
-    let config = RunConfig { streaming: true };
+    let config = RunConfig {
+        streaming: true,
+        ..Default::default()
+    };
     let event_stream = match state
         .runner
         .run_with_cancellation(
@@ -108,45 +122,112 @@ async fn handle_run(
         }
     };
 
-    // Stream events
-    let mut pinned_stream = Box::pin(event_stream);
-    while let Some(event_result) = pinned_stream.next().await {
-        match event_result {
-            Ok(event) => {
-                let event_msg = WsServerMessage::Event {
-                    invocation_id: invocation_id.clone(),
-                    data: Box::new(event),
-                };
-                if let Err(e) = send_message(sender, &event_msg).await {
-                    error!("Failed to send event: {}", e);
+    // Buffer events in the tracker
+    let tracker = state.invocation_tracker.clone();
+    let run_id = invocation_id.clone();
+    tokio::spawn(async move {
+        let mut pinned_stream = Box::pin(event_stream);
+        while let Some(event_result) = pinned_stream.next().await {
+            match event_result {
+                Ok(event) => {
+                    tracker.publish(&run_id, InvocationUpdate::Event(Box::new(event)));
+                }
+                Err(e) => {
+                    tracker.fail(&run_id);
+                    tracker.publish(&run_id, InvocationUpdate::Error(e.to_string()));
                     break;
                 }
             }
-            Err(e) => {
-                send_error(sender, format!("Agent error: {}", e)).await;
-                break;
-            }
         }
+
+        // Mark as complete; the tracker keeps undelivered events for a resume
+        tracker.complete(&run_id);
+    });
+
+    spawn_forwarder(invocation_id, state, sender);
+}
+
+/// Handle a resume command from a reconnecting client
+async fn handle_resume(invocation_id: &str, state: &AppState, sender: &WsSender) {
+    if state
+        .invocation_tracker
+        .resume_cursor(invocation_id)
+        .is_none()
+    {
+        warn!("Attempted to resume unknown invocation: {}", invocation_id);
+        send_error(
+            sender,
+            format!(
+                "Invocation {} not found or already completed",
+                invocation_id
+            ),
+        )
+        .await;
+        return;
     }
 
-    // Send completed message
-    let completed_msg = WsServerMessage::Completed {
-        invocation_id: invocation_id.clone(),
+    info!("Invocation {} resumed", invocation_id);
+    let msg = WsServerMessage::Resumed {
+        invocation_id: invocation_id.to_string(),
     };
-    if let Err(e) = send_message(sender, &completed_msg).await {
-        error!("Failed to send completed message: {}", e);
+    if let Err(e) = send_message(sender, &msg).await {
+        error!("Failed to send resumed message: {}", e);
+        return;
     }
 
-    // Mark as complete and unregister
-    state.invocation_tracker.complete(&invocation_id);
+    spawn_forwarder(invocation_id.to_string(), state, sender);
+}
+
+/// Stream an invocation's updates to the client, starting from the first one
+/// it has not acknowledged
+///
+/// Stops quietly if sending fails; the updates stay buffered for a resume.
+fn spawn_forwarder(invocation_id: String, state: &AppState, sender: &WsSender) {
+    let tracker = state.invocation_tracker.clone();
+    let sender = sender.clone();
+
+    tokio::spawn(async move {
+        let Some(mut cursor) = tracker.resume_cursor(&invocation_id) else {
+            return;
+        };
+
+        loop {
+            let msg = match tracker.recv(&invocation_id, &mut cursor).await {
+                Some(InvocationUpdate::Event(event)) => WsServerMessage::Event {
+                    invocation_id: invocation_id.clone(),
+                    data: event,
+                },
+                Some(InvocationUpdate::Error(message)) => WsServerMessage::Error {
+                    message: format!("Agent error: {}", message),
+                },
+                // Either the completion marker or an invocation that finished
+                // once everything before the marker was acknowledged
+                Some(InvocationUpdate::Completed) | None => {
+                    let completed_msg = WsServerMessage::Completed {
+                        invocation_id: invocation_id.clone(),
+                    };
+                    if let Err(e) = send_message(&sender, &completed_msg).await {
+                        error!("Failed to send completed message: {}", e);
+                    }
+                    tracker.ack(&invocation_id, cursor);
+                    return;
+                }
+            };
+
+            if let Err(e) = send_message(&sender, &msg).await {
+                warn!(
+                    "Client disconnected from invocation {}: {}",
+                    invocation_id, e
+                );
+                return;
+            }
+            tracker.ack(&invocation_id, cursor);
+        }
+    });
 }
 
 /// Handle a cancel command
-async fn handle_cancel(
-    invocation_id: &str,
-    state: &AppState,
-    sender: &mut futures::stream::SplitSink<WebSocket, Message>,
-) {
+async fn handle_cancel(invocation_id: &str, state: &AppState, sender: &WsSender) {
     if state.invocation_tracker.cancel(invocation_id) {
         info!("Invocation {} cancelled", invocation_id);
         let msg = WsServerMessage::Cancelled {
@@ -169,11 +250,7 @@ async fn handle_cancel(
 }
 
 /// Handle a status query
-async fn handle_status(
-    invocation_id: &str,
-    state: &AppState,
-    sender: &mut futures::stream::SplitSink<WebSocket, Message>,
-) {
+async fn handle_status(invocation_id: &str, state: &AppState, sender: &WsSender) {
     let status = state.invocation_tracker.status(invocation_id);
     let msg = WsServerMessage::Status {
         invocation_id: invocation_id.to_string(),
@@ -185,7 +262,7 @@ async fn handle_status(
 }
 
 /// Send an error message
-async fn send_error(sender: &mut futures::stream::SplitSink<WebSocket, Message>, message: String) {
+async fn send_error(sender: &WsSender, message: String) {
     let error_msg = WsServerMessage::Error { message };
     if let Err(e) = send_message(sender, &error_msg).await {
         error!("Failed to send error message: {}", e);
@@ -193,13 +270,10 @@ async fn send_error(sender: &mut futures::stream::SplitSink<WebSocket, Message>,
 }
 
 /// Send a WebSocket message
-async fn send_message(
-    sender: &mut futures::stream::SplitSink<WebSocket, Message>,
-    msg: &WsServerMessage,
-) -> Result<(), axum::Error> {
+async fn send_message(sender: &WsSender, msg: &WsServerMessage) -> Result<(), axum::Error> {
     let json = serde_json::to_string(msg).map_err(|e| {
         error!("Failed to serialize message: {}", e);
         axum::Error::new(e)
     })?;
-    sender.send(Message::Text(json)).await
+    sender.lock().await.send(Message::Text(json)).await
 }
@@ -24,6 +24,11 @@ pub enum WsClientMessage {
         #[serde(rename = "invocationId")]
         invocation_id: String,
     },
+    /// Reattach to an invocation after reconnecting, replaying buffered events
+    Resume {
+        #[serde(rename = "invocationId")]
+        invocation_id: String,
+    },
 }
 
 /// Messages sent from server to client
@@ -54,6 +59,11 @@ pub enum WsServerMessage {
         #[serde(rename = "invocationId")]
         invocation_id: String,
     },
+    /// Reattached to an invocation; buffered events follow
+    Resumed {
+        #[serde(rename = "invocationId")]
+        invocation_id: String,
+    },
     /// Invocation completed
     Completed {
         #[serde(rename = "invocationId")]
@@ -71,6 +81,8 @@ pub enum InvocationStatus {
     Completed,
     /// Invocation was cancelled
     Cancelled,
+    /// Invocation ended with an error
+    Errored,
     /// Invocation not found (may have expired)
     NotFound,
 }
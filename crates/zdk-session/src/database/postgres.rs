@@ -1,10 +1,11 @@
 //! PostgreSQL-backed session service
 
 use super::models::{AppStateRow, EventRow, SessionRow, UserStateRow};
-use crate::{CreateRequest, GetRequest, Session, SessionService};
+use crate::{CreateRequest, EventFilter, GetRequest, Session, SessionService};
 use anyhow::anyhow;
 use async_trait::async_trait;
-use sqlx::{Pool, Postgres};
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres, QueryBuilder};
 use std::collections::HashMap;
 use std::sync::Arc;
 use uuid::Uuid;
@@ -134,6 +135,10 @@ impl Session for DatabaseSession {
     fn state(&self) -> HashMap<String, serde_json::Value> {
         self.state.clone()
     }
+
+    fn events_filtered(&self, filter: EventFilter) -> Vec<Event> {
+        filter.apply(&self.events)
+    }
 }
 
 #[async_trait]
@@ -148,7 +153,10 @@ impl SessionService for PostgresSessionService {
         .bind(&req.session_id)
         .fetch_one(&self.pool)
         .await
-        .map_err(|e| ZError::Other(anyhow!("Failed to fetch session: {}", e)))?;
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => ZError::SessionNotFound(req.session_id.clone()),
+            e => ZError::Other(anyhow!("Failed to fetch session: {}", e)),
+        })?;
 
         // Parse session state
         let session_state: HashMap<String, serde_json::Value> =
@@ -235,15 +243,26 @@ impl SessionService for PostgresSessionService {
     }
 
     async fn append_event(&self, session_id: &str, event: Event) -> ZResult<()> {
-        // First, find the session to get app_name and user_id
-        let session_row: (String, String) =
-            sqlx::query_as("SELECT app_name, user_id FROM sessions WHERE id = $1 LIMIT 1")
-                .bind(session_id)
-                .fetch_one(&self.pool)
-                .await
-                .map_err(|e| ZError::Other(anyhow!("Failed to find session: {}", e)))?;
+        // The event and its state changes are written in one transaction
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| ZError::Other(anyhow!("Failed to start transaction: {}", e)))?;
 
-        let (app_name, user_id) = session_row;
+        // First, find the session to get app_name, user_id and its current state
+        let session_row: (String, String, String) = sqlx::query_as(
+            "SELECT app_name, user_id, state FROM sessions WHERE id = $1 LIMIT 1 FOR UPDATE",
+        )
+        .bind(session_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => ZError::SessionNotFound(session_id.to_string()),
+            e => ZError::Other(anyhow!("Failed to find session: {}", e)),
+        })?;
+
+        let (app_name, user_id, state) = session_row;
 
         // Convert event to EventRow
         let event_row = EventRow::from_event(&event, &app_name, &user_id, session_id)
@@ -280,10 +299,126 @@ impl SessionService for PostgresSessionService {
         .bind(&event_row.error_code)
         .bind(&event_row.error_message)
         .bind(&event_row.interrupted)
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await
         .map_err(|e| ZError::Other(anyhow!("Failed to insert event: {}", e)))?;
 
+        if !event.actions.state_delta.is_empty() {
+            let mut state: HashMap<String, serde_json::Value> = serde_json::from_str(&state)
+                .map_err(|e| ZError::Other(anyhow!("Failed to parse session state: {}", e)))?;
+            state.extend(
+                event
+                    .actions
+                    .state_delta
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone())),
+            );
+            let state = serde_json::to_string(&state)
+                .map_err(|e| ZError::Other(anyhow!("Failed to serialize session state: {}", e)))?;
+
+            sqlx::query(
+                "UPDATE sessions SET state = $1, update_time = CURRENT_TIMESTAMP WHERE app_name = $2 AND user_id = $3 AND id = $4",
+            )
+            .bind(&state)
+            .bind(&app_name)
+            .bind(&user_id)
+            .bind(session_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ZError::Other(anyhow!("Failed to update session state: {}", e)))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| ZError::Other(anyhow!("Failed to commit transaction: {}", e)))?;
+
         Ok(())
     }
+
+    async fn delete(&self, req: &GetRequest) -> ZResult<()> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| ZError::Other(anyhow!("Failed to start transaction: {}", e)))?;
+
+        sqlx::query("DELETE FROM events WHERE app_name = $1 AND user_id = $2 AND session_id = $3")
+            .bind(&req.app_name)
+            .bind(&req.user_id)
+            .bind(&req.session_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ZError::Other(anyhow!("Failed to delete events: {}", e)))?;
+
+        let result =
+            sqlx::query("DELETE FROM sessions WHERE app_name = $1 AND user_id = $2 AND id = $3")
+                .bind(&req.app_name)
+                .bind(&req.user_id)
+                .bind(&req.session_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| ZError::Other(anyhow!("Failed to delete session: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(ZError::SessionNotFound(req.session_id.clone()));
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| ZError::Other(anyhow!("Failed to commit transaction: {}", e)))
+    }
+
+    async fn list(&self, app_name: &str, user_id: &str) -> ZResult<Vec<String>> {
+        sqlx::query_scalar(
+            "SELECT id FROM sessions WHERE app_name = $1 AND user_id = $2 ORDER BY id",
+        )
+        .bind(app_name)
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| ZError::Other(anyhow!("Failed to list sessions: {}", e)))
+    }
+
+    async fn list_events(&self, req: &GetRequest, filter: EventFilter) -> ZResult<Vec<Event>> {
+        let mut query = QueryBuilder::<Postgres>::new("SELECT * FROM events WHERE app_name = ");
+        query
+            .push_bind(&req.app_name)
+            .push(" AND user_id = ")
+            .push_bind(&req.user_id)
+            .push(" AND session_id = ")
+            .push_bind(&req.session_id);
+
+        if let Some(after_time) = filter.after_time {
+            let after = DateTime::<Utc>::from_timestamp(after_time, 0)
+                .ok_or_else(|| ZError::Other(anyhow!("Invalid after_time: {}", after_time)))?;
+            query.push(" AND timestamp > ").push_bind(after);
+        }
+        if !filter.exclude_ids.is_empty() {
+            query.push(" AND id NOT IN (");
+            let mut ids = query.separated(", ");
+            for id in &filter.exclude_ids {
+                ids.push_bind(id);
+            }
+            query.push(")");
+        }
+        if let Some(author) = &filter.author {
+            query.push(" AND author = ").push_bind(author);
+        }
+        query.push(" ORDER BY timestamp ASC");
+        if let Some(limit) = filter.limit {
+            query.push(" LIMIT ").push_bind(limit as i64);
+        }
+
+        let event_rows: Vec<EventRow> = query
+            .build_query_as()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| ZError::Other(anyhow!("Failed to fetch events: {}", e)))?;
+
+        event_rows
+            .iter()
+            .map(|row| row.to_event())
+            .collect::<Result<Vec<Event>, _>>()
+            .map_err(|e| ZError::Other(anyhow!("Failed to parse events: {}", e)))
+    }
 }
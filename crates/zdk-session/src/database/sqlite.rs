@@ -1,10 +1,11 @@
 //! SQLite-backed session service
 
 use super::models::{AppStateRow, EventRow, SessionRow, UserStateRow};
-use crate::{CreateRequest, GetRequest, Session, SessionService};
+use crate::{CreateRequest, EventFilter, GetRequest, Session, SessionService};
 use anyhow::anyhow;
 use async_trait::async_trait;
-use sqlx::{Pool, Sqlite};
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, QueryBuilder, Sqlite};
 use std::collections::HashMap;
 use std::sync::Arc;
 use uuid::Uuid;
@@ -132,6 +133,10 @@ impl Session for DatabaseSession {
     fn state(&self) -> HashMap<String, serde_json::Value> {
         self.state.clone()
     }
+
+    fn events_filtered(&self, filter: EventFilter) -> Vec<Event> {
+        filter.apply(&self.events)
+    }
 }
 
 #[async_trait]
@@ -145,7 +150,10 @@ impl SessionService for SqliteSessionService {
                 .bind(&req.session_id)
                 .fetch_one(&self.pool)
                 .await
-                .map_err(|e| ZError::Other(anyhow!("Failed to fetch session: {}", e)))?;
+                .map_err(|e| match e {
+                    sqlx::Error::RowNotFound => ZError::SessionNotFound(req.session_id.clone()),
+                    e => ZError::Other(anyhow!("Failed to fetch session: {}", e)),
+                })?;
 
         // Parse session state
         let session_state: HashMap<String, serde_json::Value> =
@@ -232,15 +240,25 @@ impl SessionService for SqliteSessionService {
     }
 
     async fn append_event(&self, session_id: &str, event: Event) -> ZResult<()> {
-        // First, find the session to get app_name and user_id
-        let session_row: (String, String) =
-            sqlx::query_as("SELECT app_name, user_id FROM sessions WHERE id = ? LIMIT 1")
+        // The event and its state changes are written in one transaction
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| ZError::Other(anyhow!("Failed to start transaction: {}", e)))?;
+
+        // First, find the session to get app_name, user_id and its current state
+        let session_row: (String, String, String) =
+            sqlx::query_as("SELECT app_name, user_id, state FROM sessions WHERE id = ? LIMIT 1")
                 .bind(session_id)
-                .fetch_one(&self.pool)
+                .fetch_one(&mut *tx)
                 .await
-                .map_err(|e| ZError::Other(anyhow!("Failed to find session: {}", e)))?;
+                .map_err(|e| match e {
+                    sqlx::Error::RowNotFound => ZError::SessionNotFound(session_id.to_string()),
+                    e => ZError::Other(anyhow!("Failed to find session: {}", e)),
+                })?;
 
-        let (app_name, user_id) = session_row;
+        let (app_name, user_id, state) = session_row;
 
         // Convert event to EventRow
         let event_row = EventRow::from_event(&event, &app_name, &user_id, session_id)
@@ -277,10 +295,247 @@ impl SessionService for SqliteSessionService {
         .bind(&event_row.error_code)
         .bind(&event_row.error_message)
         .bind(&event_row.interrupted)
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await
         .map_err(|e| ZError::Other(anyhow!("Failed to insert event: {}", e)))?;
 
+        if !event.actions.state_delta.is_empty() {
+            let mut state: HashMap<String, serde_json::Value> = serde_json::from_str(&state)
+                .map_err(|e| ZError::Other(anyhow!("Failed to parse session state: {}", e)))?;
+            state.extend(
+                event
+                    .actions
+                    .state_delta
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone())),
+            );
+            let state = serde_json::to_string(&state)
+                .map_err(|e| ZError::Other(anyhow!("Failed to serialize session state: {}", e)))?;
+
+            sqlx::query(
+                "UPDATE sessions SET state = ?, update_time = CURRENT_TIMESTAMP WHERE app_name = ? AND user_id = ? AND id = ?",
+            )
+            .bind(&state)
+            .bind(&app_name)
+            .bind(&user_id)
+            .bind(session_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ZError::Other(anyhow!("Failed to update session state: {}", e)))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| ZError::Other(anyhow!("Failed to commit transaction: {}", e)))?;
+
         Ok(())
     }
+
+    async fn delete(&self, req: &GetRequest) -> ZResult<()> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| ZError::Other(anyhow!("Failed to start transaction: {}", e)))?;
+
+        sqlx::query("DELETE FROM events WHERE app_name = ? AND user_id = ? AND session_id = ?")
+            .bind(&req.app_name)
+            .bind(&req.user_id)
+            .bind(&req.session_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ZError::Other(anyhow!("Failed to delete events: {}", e)))?;
+
+        let result =
+            sqlx::query("DELETE FROM sessions WHERE app_name = ? AND user_id = ? AND id = ?")
+                .bind(&req.app_name)
+                .bind(&req.user_id)
+                .bind(&req.session_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| ZError::Other(anyhow!("Failed to delete session: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(ZError::SessionNotFound(req.session_id.clone()));
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| ZError::Other(anyhow!("Failed to commit transaction: {}", e)))
+    }
+
+    async fn list(&self, app_name: &str, user_id: &str) -> ZResult<Vec<String>> {
+        sqlx::query_scalar("SELECT id FROM sessions WHERE app_name = ? AND user_id = ? ORDER BY id")
+            .bind(app_name)
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| ZError::Other(anyhow!("Failed to list sessions: {}", e)))
+    }
+
+    async fn list_events(&self, req: &GetRequest, filter: EventFilter) -> ZResult<Vec<Event>> {
+        let mut query = QueryBuilder::<Sqlite>::new("SELECT * FROM events WHERE app_name = ");
+        query
+            .push_bind(&req.app_name)
+            .push(" AND user_id = ")
+            .push_bind(&req.user_id)
+            .push(" AND session_id = ")
+            .push_bind(&req.session_id);
+
+        if let Some(after_time) = filter.after_time {
+            let after = DateTime::<Utc>::from_timestamp(after_time, 0)
+                .ok_or_else(|| ZError::Other(anyhow!("Invalid after_time: {}", after_time)))?;
+            query.push(" AND timestamp > ").push_bind(after);
+        }
+        if !filter.exclude_ids.is_empty() {
+            query.push(" AND id NOT IN (");
+            let mut ids = query.separated(", ");
+            for id in &filter.exclude_ids {
+                ids.push_bind(id);
+            }
+            query.push(")");
+        }
+        if let Some(author) = &filter.author {
+            query.push(" AND author = ").push_bind(author);
+        }
+        query.push(" ORDER BY timestamp ASC");
+        if let Some(limit) = filter.limit {
+            query.push(" LIMIT ").push_bind(limit as i64);
+        }
+
+        let event_rows: Vec<EventRow> = query
+            .build_query_as()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| ZError::Other(anyhow!("Failed to fetch events: {}", e)))?;
+
+        event_rows
+            .iter()
+            .map(|row| row.to_event())
+            .collect::<Result<Vec<Event>, _>>()
+            .map_err(|e| ZError::Other(anyhow!("Failed to parse events: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_list_events_filters_in_query() {
+        // A single connection keeps the in-memory database alive and shared
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        super::super::migrations::run_sqlite_migrations(&pool)
+            .await
+            .unwrap();
+        let service = SqliteSessionService::from_pool(pool);
+
+        service
+            .create(&CreateRequest {
+                app_name: "app".to_string(),
+                user_id: "user1".to_string(),
+                session_id: Some("session1".to_string()),
+            })
+            .await
+            .unwrap();
+        for (author, time) in [
+            ("user", 100),
+            ("agent", 110),
+            ("agent", 110),
+            ("user", 120),
+            ("agent", 130),
+        ] {
+            let mut event = Event::new("inv1".to_string(), author.to_string());
+            event.time = time;
+            service.append_event("session1", event).await.unwrap();
+        }
+
+        let req = GetRequest {
+            app_name: "app".to_string(),
+            user_id: "user1".to_string(),
+            session_id: "session1".to_string(),
+        };
+        let times = |events: &[Event]| events.iter().map(|e| e.time).collect::<Vec<_>>();
+
+        let after = service
+            .list_events(
+                &req,
+                EventFilter {
+                    after_time: Some(110),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(times(&after), vec![120, 130]);
+
+        let agent_filter = EventFilter {
+            author: Some("agent".to_string()),
+            limit: Some(1),
+            ..Default::default()
+        };
+        let agent = service
+            .list_events(&req, agent_filter.clone())
+            .await
+            .unwrap();
+        assert_eq!(times(&agent), vec![110]);
+
+        // The second page picks up the other event from the same second
+        let second_page = service
+            .list_events(&req, agent_filter.next_page(&agent))
+            .await
+            .unwrap();
+        assert_eq!(times(&second_page), vec![110]);
+        assert_ne!(second_page[0].id, agent[0].id);
+    }
+
+    #[tokio::test]
+    async fn test_list_and_delete_sessions() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        super::super::migrations::run_sqlite_migrations(&pool)
+            .await
+            .unwrap();
+        let service = SqliteSessionService::from_pool(pool);
+
+        for id in ["session1", "session2", "session3"] {
+            service
+                .create(&CreateRequest {
+                    app_name: "app".to_string(),
+                    user_id: "user1".to_string(),
+                    session_id: Some(id.to_string()),
+                })
+                .await
+                .unwrap();
+        }
+        service
+            .append_event(
+                "session2",
+                Event::new("inv1".to_string(), "user".to_string()),
+            )
+            .await
+            .unwrap();
+
+        let ids = service.list("app", "user1").await.unwrap();
+        assert_eq!(ids, vec!["session1", "session2", "session3"]);
+
+        let req = GetRequest {
+            app_name: "app".to_string(),
+            user_id: "user1".to_string(),
+            session_id: "session2".to_string(),
+        };
+        service.delete(&req).await.unwrap();
+
+        let ids = service.list("app", "user1").await.unwrap();
+        assert_eq!(ids, vec!["session1", "session3"]);
+        assert!(service.get(&req).await.is_err());
+        assert!(service.delete(&req).await.is_err());
+    }
 }
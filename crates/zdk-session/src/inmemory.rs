@@ -1,17 +1,66 @@
 use super::*;
 use std::collections::HashMap as StdHashMap;
-use std::sync::RwLock;
+use std::sync::{Mutex, RwLock};
+use std::time::Duration;
+use tokio::time::Instant;
 use uuid::Uuid;
 use zdk_core::Error;
 
 pub struct InMemorySessionService {
     sessions: Arc<RwLock<StdHashMap<String, Arc<InMemorySession>>>>,
+    ttl: Option<Duration>,
 }
 
 impl InMemorySessionService {
     pub fn new() -> Self {
         Self {
             sessions: Arc::new(RwLock::new(StdHashMap::new())),
+            ttl: None,
+        }
+    }
+
+    /// Create a service whose sessions expire after `ttl` without access
+    ///
+    /// Expired sessions are evicted lazily when accessed, or proactively with
+    /// [`sweep`](Self::sweep).
+    pub fn with_ttl(ttl: Duration) -> Self {
+        Self {
+            ttl: Some(ttl),
+            ..Self::new()
+        }
+    }
+
+    /// Remove all expired sessions, returning how many were evicted
+    pub fn sweep(&self) -> usize {
+        let mut sessions = self.sessions.write().unwrap();
+        let before = sessions.len();
+        sessions.retain(|_, session| !self.is_expired(session));
+        before - sessions.len()
+    }
+
+    fn is_expired(&self, session: &InMemorySession) -> bool {
+        self.ttl
+            .is_some_and(|ttl| session.last_accessed.lock().unwrap().elapsed() > ttl)
+    }
+
+    /// Look up a live session, refreshing its access time and evicting it if expired
+    fn touch(&self, session_id: &str) -> Result<Arc<InMemorySession>> {
+        let session = self.sessions.read().unwrap().get(session_id).cloned();
+
+        match session {
+            Some(session) if !self.is_expired(&session) => {
+                *session.last_accessed.lock().unwrap() = Instant::now();
+                Ok(session)
+            }
+            Some(_) => {
+                let mut sessions = self.sessions.write().unwrap();
+                // Re-check under the write lock in case it was touched meanwhile
+                if sessions.get(session_id).is_some_and(|s| self.is_expired(s)) {
+                    sessions.remove(session_id);
+                }
+                Err(Error::SessionNotFound(session_id.to_string()))
+            }
+            None => Err(Error::SessionNotFound(session_id.to_string())),
         }
     }
 }
@@ -25,12 +74,12 @@ impl Default for InMemorySessionService {
 #[async_trait]
 impl SessionService for InMemorySessionService {
     async fn get(&self, req: &GetRequest) -> Result<Arc<dyn Session>> {
-        let sessions = self.sessions.read().unwrap();
-        sessions
-            .get(&req.session_id)
-            .cloned()
-            .map(|s| s as Arc<dyn Session>)
-            .ok_or_else(|| Error::SessionError(format!("Session {} not found", req.session_id)))
+        let session = self.touch(&req.session_id)?;
+        // Another app's or user's session looks the same as a missing one
+        if session.app_name != req.app_name || session.user_id != req.user_id {
+            return Err(Error::SessionNotFound(req.session_id.clone()));
+        }
+        Ok(session as Arc<dyn Session>)
     }
 
     async fn create(&self, req: &CreateRequest) -> Result<Arc<dyn Session>> {
@@ -45,6 +94,7 @@ impl SessionService for InMemorySessionService {
             user_id: req.user_id.clone(),
             events: RwLock::new(Vec::new()),
             state: RwLock::new(HashMap::new()),
+            last_accessed: Mutex::new(Instant::now()),
         });
 
         let mut sessions = self.sessions.write().unwrap();
@@ -54,18 +104,56 @@ impl SessionService for InMemorySessionService {
     }
 
     async fn append_event(&self, session_id: &str, event: Event) -> Result<()> {
-        let sessions = self.sessions.read().unwrap();
-        if let Some(session) = sessions.get(session_id) {
-            let mut events = session.events.write().unwrap();
-            events.push(event);
-            Ok(())
-        } else {
-            Err(Error::SessionError(format!(
-                "Session {} not found",
-                session_id
-            )))
+        let session = self.touch(session_id)?;
+        session.apply_state_delta(&event);
+        session.events.write().unwrap().push(event);
+        Ok(())
+    }
+
+    async fn append_event_checked(
+        &self,
+        session_id: &str,
+        event: Event,
+        expected_event_count: Option<usize>,
+    ) -> Result<()> {
+        let session = self.touch(session_id)?;
+        // Check and push under one write lock so concurrent appends can't interleave
+        let mut events = session.events.write().unwrap();
+        if let Some(expected) = expected_event_count
+            && events.len() != expected
+        {
+            return Err(Error::SessionConflict {
+                session_id: session_id.to_string(),
+                expected,
+                actual: events.len(),
+            });
+        }
+        session.apply_state_delta(&event);
+        events.push(event);
+        Ok(())
+    }
+
+    async fn delete(&self, req: &GetRequest) -> Result<()> {
+        let mut sessions = self.sessions.write().unwrap();
+        match sessions.get(&req.session_id) {
+            Some(s) if s.app_name == req.app_name && s.user_id == req.user_id => {
+                sessions.remove(&req.session_id);
+                Ok(())
+            }
+            _ => Err(Error::SessionNotFound(req.session_id.clone())),
         }
     }
+
+    async fn list(&self, app_name: &str, user_id: &str) -> Result<Vec<String>> {
+        let sessions = self.sessions.read().unwrap();
+        let mut ids: Vec<String> = sessions
+            .values()
+            .filter(|s| s.app_name == app_name && s.user_id == user_id && !self.is_expired(s))
+            .map(|s| s.id.clone())
+            .collect();
+        ids.sort();
+        Ok(ids)
+    }
 }
 
 pub struct InMemorySession {
@@ -74,6 +162,22 @@ pub struct InMemorySession {
     user_id: String,
     events: RwLock<Vec<Event>>,
     state: RwLock<HashMap<String, serde_json::Value>>,
+    last_accessed: Mutex<Instant>,
+}
+
+impl InMemorySession {
+    /// Merge the event's state changes into the session state
+    fn apply_state_delta(&self, event: &Event) {
+        if !event.actions.state_delta.is_empty() {
+            self.state.write().unwrap().extend(
+                event
+                    .actions
+                    .state_delta
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone())),
+            );
+        }
+    }
 }
 
 impl Session for InMemorySession {
@@ -96,4 +200,9 @@ impl Session for InMemorySession {
     fn state(&self) -> HashMap<String, serde_json::Value> {
         self.state.read().unwrap().clone()
     }
+
+    fn events_filtered(&self, filter: EventFilter) -> Vec<Event> {
+        // Filter under the lock instead of cloning the whole history
+        filter.apply(self.events.read().unwrap().iter())
+    }
 }
@@ -3,15 +3,20 @@
 use async_trait::async_trait;
 use std::collections::HashMap;
 use std::sync::Arc;
-use zdk_core::{Event, Result};
+use zdk_core::{Error, Event, Result};
 
 pub mod inmemory;
+pub mod scoped;
 pub mod types;
 
 #[cfg(feature = "sqlx")]
 pub mod database;
 
-pub use types::{CreateRequest, GetRequest};
+#[cfg(feature = "redis")]
+pub mod redis;
+
+pub use scoped::ScopedSessionStore;
+pub use types::{CreateRequest, EventFilter, GetRequest};
 
 #[cfg(feature = "postgres")]
 pub use database::PostgresSessionService;
@@ -19,12 +24,63 @@ pub use database::PostgresSessionService;
 #[cfg(feature = "sqlite")]
 pub use database::SqliteSessionService;
 
+#[cfg(feature = "redis")]
+pub use self::redis::RedisSessionService;
+
 /// Session service trait
 #[async_trait]
 pub trait SessionService: Send + Sync {
     async fn get(&self, req: &GetRequest) -> Result<Arc<dyn Session>>;
     async fn create(&self, req: &CreateRequest) -> Result<Arc<dyn Session>>;
     async fn append_event(&self, session_id: &str, event: Event) -> Result<()>;
+
+    /// Append an event only if the session holds exactly `expected_event_count` events
+    ///
+    /// Fails with [`Error::SessionConflict`] when another writer appended first,
+    /// so callers can reload the session and retry. `None` appends unconditionally
+    /// like [`append_event`](Self::append_event). Services without atomic
+    /// check-and-append support reject checked appends.
+    async fn append_event_checked(
+        &self,
+        session_id: &str,
+        event: Event,
+        expected_event_count: Option<usize>,
+    ) -> Result<()> {
+        match expected_event_count {
+            None => self.append_event(session_id, event).await,
+            Some(_) => Err(Error::SessionError(
+                "Checked appends are not supported by this session service".to_string(),
+            )),
+        }
+    }
+
+    /// Delete a session and its events
+    ///
+    /// Services without delete support reject the request.
+    async fn delete(&self, req: &GetRequest) -> Result<()> {
+        let _ = req;
+        Err(Error::SessionError(
+            "Deleting sessions is not supported by this session service".to_string(),
+        ))
+    }
+
+    /// List the IDs of a user's sessions within an app
+    ///
+    /// Services without listing support reject the request.
+    async fn list(&self, app_name: &str, user_id: &str) -> Result<Vec<String>> {
+        let _ = (app_name, user_id);
+        Err(Error::SessionError(
+            "Listing sessions is not supported by this session service".to_string(),
+        ))
+    }
+
+    /// Fetch the events of a session that match `filter`
+    ///
+    /// Database-backed services filter in the query instead of loading the
+    /// whole history.
+    async fn list_events(&self, req: &GetRequest, filter: EventFilter) -> Result<Vec<Event>> {
+        Ok(self.get(req).await?.events_filtered(filter))
+    }
 }
 
 /// Session trait
@@ -34,12 +90,18 @@ pub trait Session: Send + Sync {
     fn user_id(&self) -> &str;
     fn events(&self) -> Vec<Event>;
     fn state(&self) -> HashMap<String, serde_json::Value>;
+
+    /// Events matching `filter`, in chronological order
+    fn events_filtered(&self, filter: EventFilter) -> Vec<Event> {
+        filter.apply(&self.events())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::inmemory::InMemorySessionService;
+    use std::time::Duration;
 
     #[tokio::test]
     async fn test_create_session() {
@@ -121,4 +183,268 @@ mod tests {
         assert_eq!(events[0].author, "user");
         assert_eq!(events[1].author, "agent");
     }
+
+    fn get_request(session_id: &str) -> GetRequest {
+        GetRequest {
+            app_name: "test-app".to_string(),
+            user_id: "user1".to_string(),
+            session_id: session_id.to_string(),
+        }
+    }
+
+    async fn create_with_id(service: &InMemorySessionService, session_id: &str) {
+        service
+            .create(&CreateRequest {
+                app_name: "test-app".to_string(),
+                user_id: "user1".to_string(),
+                session_id: Some(session_id.to_string()),
+            })
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_session_expires_after_ttl() {
+        let service = InMemorySessionService::with_ttl(Duration::from_secs(60));
+        create_with_id(&service, "session1").await;
+
+        tokio::time::advance(Duration::from_secs(30)).await;
+        assert!(service.get(&get_request("session1")).await.is_ok());
+
+        tokio::time::advance(Duration::from_secs(61)).await;
+        let err = service.get(&get_request("session1")).await.err().unwrap();
+        assert!(err.to_string().contains("not found"));
+
+        let event = zdk_core::Event::new("inv1".to_string(), "user".to_string());
+        assert!(service.append_event("session1", event).await.is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_access_refreshes_ttl() {
+        let service = InMemorySessionService::with_ttl(Duration::from_secs(60));
+        create_with_id(&service, "session1").await;
+
+        // Each access within the TTL keeps the session alive
+        for _ in 0..3 {
+            tokio::time::advance(Duration::from_secs(45)).await;
+            let event = zdk_core::Event::new("inv1".to_string(), "user".to_string());
+            service.append_event("session1", event).await.unwrap();
+        }
+
+        let session = service.get(&get_request("session1")).await.unwrap();
+        assert_eq!(session.events().len(), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_sweep_removes_expired_sessions() {
+        let service = InMemorySessionService::with_ttl(Duration::from_secs(60));
+        create_with_id(&service, "old").await;
+
+        tokio::time::advance(Duration::from_secs(50)).await;
+        create_with_id(&service, "new").await;
+
+        tokio::time::advance(Duration::from_secs(20)).await;
+        assert_eq!(service.sweep(), 1);
+        assert!(service.get(&get_request("old")).await.is_err());
+        assert!(service.get(&get_request("new")).await.is_ok());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_sessions_without_ttl_never_expire() {
+        let service = InMemorySessionService::new();
+        create_with_id(&service, "session1").await;
+
+        tokio::time::advance(Duration::from_secs(365 * 24 * 3600)).await;
+        assert_eq!(service.sweep(), 0);
+        assert!(service.get(&get_request("session1")).await.is_ok());
+    }
+
+    fn event_at(author: &str, time: i64) -> Event {
+        let mut event = Event::new("inv1".to_string(), author.to_string());
+        event.time = time;
+        event
+    }
+
+    async fn session_with_events(service: &InMemorySessionService) {
+        create_with_id(service, "session1").await;
+        for (author, time) in [("user", 100), ("agent", 110), ("user", 120), ("agent", 130)] {
+            service
+                .append_event("session1", event_at(author, time))
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_events_filtered_by_time_and_author() {
+        let service = InMemorySessionService::new();
+        session_with_events(&service).await;
+        let session = service.get(&get_request("session1")).await.unwrap();
+
+        let after = session.events_filtered(EventFilter {
+            after_time: Some(110),
+            ..Default::default()
+        });
+        assert_eq!(
+            after.iter().map(|e| e.time).collect::<Vec<_>>(),
+            vec![120, 130]
+        );
+
+        let agent = session.events_filtered(EventFilter {
+            author: Some("agent".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(
+            agent.iter().map(|e| e.time).collect::<Vec<_>>(),
+            vec![110, 130]
+        );
+
+        let combined = session.events_filtered(EventFilter {
+            after_time: Some(100),
+            author: Some("user".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(combined.len(), 1);
+        assert_eq!(combined[0].time, 120);
+
+        assert_eq!(session.events_filtered(EventFilter::default()).len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_events_filtered_pages_with_limit() {
+        let service = InMemorySessionService::new();
+        session_with_events(&service).await;
+        let session = service.get(&get_request("session1")).await.unwrap();
+
+        let filter = EventFilter {
+            limit: Some(2),
+            ..Default::default()
+        };
+        let first_page = session.events_filtered(filter.clone());
+        assert_eq!(
+            first_page.iter().map(|e| e.time).collect::<Vec<_>>(),
+            vec![100, 110]
+        );
+
+        let second_page = session.events_filtered(filter.next_page(&first_page));
+        assert_eq!(
+            second_page.iter().map(|e| e.time).collect::<Vec<_>>(),
+            vec![120, 130]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_paging_within_one_second_loses_no_events() {
+        let service = InMemorySessionService::new();
+        create_with_id(&service, "session1").await;
+        for time in [100, 100, 100, 100, 101] {
+            service
+                .append_event("session1", event_at("agent", time))
+                .await
+                .unwrap();
+        }
+        let session = service.get(&get_request("session1")).await.unwrap();
+
+        let mut filter = EventFilter {
+            limit: Some(2),
+            ..Default::default()
+        };
+        let mut seen = Vec::new();
+        loop {
+            let page = session.events_filtered(filter.clone());
+            if page.is_empty() {
+                break;
+            }
+            filter = filter.next_page(&page);
+            seen.extend(page);
+        }
+
+        let ids = |events: &[Event]| events.iter().map(|e| e.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&seen), ids(&session.events()));
+    }
+
+    #[tokio::test]
+    async fn test_list_and_delete_sessions() {
+        let service = InMemorySessionService::new();
+        for id in ["session1", "session2", "session3"] {
+            create_with_id(&service, id).await;
+        }
+        service
+            .create(&CreateRequest {
+                app_name: "test-app".to_string(),
+                user_id: "user2".to_string(),
+                session_id: Some("other".to_string()),
+            })
+            .await
+            .unwrap();
+
+        let ids = service.list("test-app", "user1").await.unwrap();
+        assert_eq!(ids, vec!["session1", "session2", "session3"]);
+
+        service.delete(&get_request("session2")).await.unwrap();
+
+        let ids = service.list("test-app", "user1").await.unwrap();
+        assert_eq!(ids, vec!["session1", "session3"]);
+        assert!(service.get(&get_request("session2")).await.is_err());
+        assert!(service.delete(&get_request("session2")).await.is_err());
+
+        // Another user's session can't be deleted by ID alone
+        assert!(matches!(
+            service.delete(&get_request("other")).await,
+            Err(Error::SessionNotFound(_))
+        ));
+        let ids = service.list("test-app", "user2").await.unwrap();
+        assert_eq!(ids, vec!["other"]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_append_event_checked_detects_conflict() {
+        let service = Arc::new(InMemorySessionService::new());
+        create_with_id(&service, "session1").await;
+        service
+            .append_event("session1", event_at("user", 100))
+            .await
+            .unwrap();
+
+        // Both writers saw one event and race to append the second
+        let barrier = Arc::new(tokio::sync::Barrier::new(2));
+        let writers: Vec<_> = ["agent-a", "agent-b"]
+            .into_iter()
+            .map(|author| {
+                let service = service.clone();
+                let barrier = barrier.clone();
+                tokio::spawn(async move {
+                    barrier.wait().await;
+                    service
+                        .append_event_checked("session1", event_at(author, 110), Some(1))
+                        .await
+                })
+            })
+            .collect();
+
+        let mut results = Vec::new();
+        for writer in writers {
+            results.push(writer.await.unwrap());
+        }
+
+        assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 1);
+        let conflict = results.into_iter().find_map(|r| r.err()).unwrap();
+        assert!(matches!(
+            conflict,
+            Error::SessionConflict {
+                expected: 1,
+                actual: 2,
+                ..
+            }
+        ));
+
+        let session = service.get(&get_request("session1")).await.unwrap();
+        assert_eq!(session.events().len(), 2);
+
+        // Unchecked appends still always succeed
+        service
+            .append_event_checked("session1", event_at("user", 120), None)
+            .await
+            .unwrap();
+    }
 }
@@ -0,0 +1,278 @@
+//! Redis-backed session service
+//!
+//! Each session is stored under the base key `{app}:{user}:{session}`:
+//!
+//! - `{base}:events` - list of JSON-encoded events, appended with `RPUSH`
+//! - `{base}:state` - hash of state keys to JSON-encoded values
+//!
+//! `\` and `:` in the app name and IDs are escaped as `\\` and `\:`, so IDs
+//! containing `:` can't map two sessions to the same key.
+//!
+//! A user's session IDs are kept in the set `{app}:{user}:sessions`, and the
+//! `session_index` hash maps each session ID to its base key so events can be
+//! appended by ID alone. Creating a session never overwrites another app's or
+//! user's entry in the index.
+
+use crate::{CreateRequest, GetRequest, Session, SessionService};
+use ::redis::AsyncCommands;
+use ::redis::aio::ConnectionManager;
+use anyhow::anyhow;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+use zdk_core::{Error as ZError, Event, Result as ZResult};
+
+/// Hash mapping session IDs to their base keys
+const SESSION_INDEX_KEY: &str = "session_index";
+
+/// Redis-backed session service
+///
+/// Sessions live in Redis rather than process memory, so every server
+/// instance pointed at the same Redis sees the same sessions.
+#[derive(Clone)]
+pub struct RedisSessionService {
+    conn: ConnectionManager,
+}
+
+impl RedisSessionService {
+    /// Connect to Redis at `redis_url` (e.g. `redis://127.0.0.1/`)
+    pub async fn new(redis_url: &str) -> Result<Self, ::redis::RedisError> {
+        let client = ::redis::Client::open(redis_url)?;
+        let conn = ConnectionManager::new(client).await?;
+        Ok(Self { conn })
+    }
+
+    /// Create from an existing connection manager
+    pub fn from_connection(conn: ConnectionManager) -> Self {
+        Self { conn }
+    }
+
+    fn base_key(app_name: &str, user_id: &str, session_id: &str) -> String {
+        format!(
+            "{}:{}:{}",
+            key_part(app_name),
+            key_part(user_id),
+            key_part(session_id)
+        )
+    }
+
+    fn sessions_key(app_name: &str, user_id: &str) -> String {
+        format!("{}:{}:sessions", key_part(app_name), key_part(user_id))
+    }
+
+    /// Resolve the base key of a session, checking it belongs to the request's app and user
+    async fn find_session(&self, req: &GetRequest) -> ZResult<String> {
+        let base = Self::base_key(&req.app_name, &req.user_id, &req.session_id);
+        let indexed: Option<String> = self
+            .conn
+            .clone()
+            .hget(SESSION_INDEX_KEY, &req.session_id)
+            .await
+            .map_err(|e| ZError::Other(anyhow!("Failed to find session: {}", e)))?;
+
+        match indexed {
+            Some(indexed) if indexed == base => Ok(base),
+            _ => Err(ZError::SessionNotFound(req.session_id.clone())),
+        }
+    }
+}
+
+/// Escape a key part so it never contains a bare `:` separator
+fn key_part(part: &str) -> String {
+    part.replace('\\', "\\\\").replace(':', "\\:")
+}
+
+/// Snapshot of a session read from Redis
+struct RedisSession {
+    id: String,
+    app_name: String,
+    user_id: String,
+    events: Vec<Event>,
+    state: HashMap<String, serde_json::Value>,
+}
+
+impl Session for RedisSession {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn app_name(&self) -> &str {
+        &self.app_name
+    }
+
+    fn user_id(&self) -> &str {
+        &self.user_id
+    }
+
+    fn events(&self) -> Vec<Event> {
+        self.events.clone()
+    }
+
+    fn state(&self) -> HashMap<String, serde_json::Value> {
+        self.state.clone()
+    }
+}
+
+#[async_trait]
+impl SessionService for RedisSessionService {
+    async fn get(&self, req: &GetRequest) -> ZResult<Arc<dyn Session>> {
+        let base = self.find_session(req).await?;
+
+        let (raw_events, raw_state): (Vec<String>, HashMap<String, String>) = ::redis::pipe()
+            .lrange(format!("{}:events", base), 0, -1)
+            .hgetall(format!("{}:state", base))
+            .query_async(&mut self.conn.clone())
+            .await
+            .map_err(|e| ZError::Other(anyhow!("Failed to fetch session: {}", e)))?;
+
+        let events = raw_events
+            .iter()
+            .map(|raw| serde_json::from_str(raw))
+            .collect::<Result<Vec<Event>, _>>()
+            .map_err(|e| ZError::Other(anyhow!("Failed to parse events: {}", e)))?;
+
+        let state = raw_state
+            .into_iter()
+            .map(|(key, raw)| serde_json::from_str(&raw).map(|value| (key, value)))
+            .collect::<Result<HashMap<_, _>, _>>()
+            .map_err(|e| ZError::Other(anyhow!("Failed to parse session state: {}", e)))?;
+
+        Ok(Arc::new(RedisSession {
+            id: req.session_id.clone(),
+            app_name: req.app_name.clone(),
+            user_id: req.user_id.clone(),
+            events,
+            state,
+        }))
+    }
+
+    async fn create(&self, req: &CreateRequest) -> ZResult<Arc<dyn Session>> {
+        let session_id = req
+            .session_id
+            .clone()
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+        let base = Self::base_key(&req.app_name, &req.user_id, &session_id);
+
+        // Session IDs are global in the index, so claim the ID without
+        // overwriting a session that belongs to another app or user
+        let mut conn = self.conn.clone();
+        let claimed: bool = conn
+            .hset_nx(SESSION_INDEX_KEY, &session_id, &base)
+            .await
+            .map_err(|e| ZError::Other(anyhow!("Failed to create session: {}", e)))?;
+        if !claimed {
+            let indexed: Option<String> = conn
+                .hget(SESSION_INDEX_KEY, &session_id)
+                .await
+                .map_err(|e| ZError::Other(anyhow!("Failed to create session: {}", e)))?;
+            if indexed.as_deref() != Some(base.as_str()) {
+                return Err(ZError::SessionError(format!(
+                    "Session {} already exists",
+                    session_id
+                )));
+            }
+        }
+
+        let _: () = conn
+            .sadd(Self::sessions_key(&req.app_name, &req.user_id), &session_id)
+            .await
+            .map_err(|e| ZError::Other(anyhow!("Failed to create session: {}", e)))?;
+
+        Ok(Arc::new(RedisSession {
+            id: session_id,
+            app_name: req.app_name.clone(),
+            user_id: req.user_id.clone(),
+            events: Vec::new(),
+            state: HashMap::new(),
+        }))
+    }
+
+    async fn append_event(&self, session_id: &str, event: Event) -> ZResult<()> {
+        let base: Option<String> = self
+            .conn
+            .clone()
+            .hget(SESSION_INDEX_KEY, session_id)
+            .await
+            .map_err(|e| ZError::Other(anyhow!("Failed to find session: {}", e)))?;
+        let base = base.ok_or_else(|| ZError::SessionNotFound(session_id.to_string()))?;
+
+        let raw_event = serde_json::to_string(&event)
+            .map_err(|e| ZError::Other(anyhow!("Failed to serialize event: {}", e)))?;
+
+        // Push the event and apply its state delta in one transaction
+        let mut pipe = ::redis::pipe();
+        pipe.atomic()
+            .rpush(format!("{}:events", base), raw_event)
+            .ignore();
+        if !event.actions.state_delta.is_empty() {
+            let delta = event
+                .actions
+                .state_delta
+                .iter()
+                .map(|(key, value)| serde_json::to_string(value).map(|raw| (key.clone(), raw)))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| ZError::Other(anyhow!("Failed to serialize state: {}", e)))?;
+            pipe.hset_multiple(format!("{}:state", base), &delta)
+                .ignore();
+        }
+
+        let _: () = pipe
+            .query_async(&mut self.conn.clone())
+            .await
+            .map_err(|e| ZError::Other(anyhow!("Failed to append event: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, req: &GetRequest) -> ZResult<()> {
+        let base = self.find_session(req).await?;
+
+        let _: () = ::redis::pipe()
+            .atomic()
+            .del(format!("{}:events", base))
+            .del(format!("{}:state", base))
+            .hdel(SESSION_INDEX_KEY, &req.session_id)
+            .srem(
+                Self::sessions_key(&req.app_name, &req.user_id),
+                &req.session_id,
+            )
+            .query_async(&mut self.conn.clone())
+            .await
+            .map_err(|e| ZError::Other(anyhow!("Failed to delete session: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn list(&self, app_name: &str, user_id: &str) -> ZResult<Vec<String>> {
+        let mut ids: Vec<String> = self
+            .conn
+            .clone()
+            .smembers(Self::sessions_key(app_name, user_id))
+            .await
+            .map_err(|e| ZError::Other(anyhow!("Failed to list sessions: {}", e)))?;
+        ids.sort();
+        Ok(ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_key_escapes_separators() {
+        assert_eq!(
+            RedisSessionService::base_key("app", "user", "s1"),
+            "app:user:s1"
+        );
+        assert_ne!(
+            RedisSessionService::base_key("a", "b:c", "d"),
+            RedisSessionService::base_key("a", "b", "c:d")
+        );
+        assert_ne!(
+            RedisSessionService::base_key("a", "b\\", ":c"),
+            RedisSessionService::base_key("a", "b\\:", "c")
+        );
+    }
+}
@@ -0,0 +1,48 @@
+//! Session service scoped to a single session
+
+use crate::{GetRequest, SessionService};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use zdk_core::{Event, Result, SessionStore};
+
+/// [`SessionStore`] that reads one session of a [`SessionService`]
+///
+/// Handed to each invocation so agents can reload their session without
+/// knowing the app, user or session ID.
+#[derive(Clone)]
+pub struct ScopedSessionStore {
+    service: Arc<dyn SessionService>,
+    request: GetRequest,
+}
+
+impl ScopedSessionStore {
+    /// Scope `service` to the given app, user and session
+    pub fn new(
+        service: Arc<dyn SessionService>,
+        app_name: impl Into<String>,
+        user_id: impl Into<String>,
+        session_id: impl Into<String>,
+    ) -> Self {
+        Self {
+            service,
+            request: GetRequest {
+                app_name: app_name.into(),
+                user_id: user_id.into(),
+                session_id: session_id.into(),
+            },
+        }
+    }
+}
+
+#[async_trait]
+impl SessionStore for ScopedSessionStore {
+    async fn events(&self) -> Result<Vec<Event>> {
+        Ok(self.service.get(&self.request).await?.events())
+    }
+
+    async fn state(&self) -> Result<HashMap<String, Value>> {
+        Ok(self.service.get(&self.request).await?.state())
+    }
+}
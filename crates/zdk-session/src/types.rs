@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use zdk_core::Event;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GetRequest {
@@ -13,3 +14,67 @@ pub struct CreateRequest {
     pub user_id: String,
     pub session_id: Option<String>,
 }
+
+/// Criteria for selecting a subset of a session's events
+///
+/// All set criteria must match. Events are returned in chronological order and
+/// `limit` keeps the earliest matches, so [`EventFilter::next_page`] can be
+/// used to page through long conversations.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EventFilter {
+    /// Only events strictly after this Unix timestamp (seconds)
+    pub after_time: Option<i64>,
+    /// Skip these events, e.g. ones already returned on a previous page
+    #[serde(default)]
+    pub exclude_ids: Vec<String>,
+    /// Only events written by this author
+    pub author: Option<String>,
+    /// Maximum number of events to return
+    pub limit: Option<usize>,
+}
+
+impl EventFilter {
+    /// Whether an event satisfies the time, ID and author criteria
+    pub fn matches(&self, event: &Event) -> bool {
+        self.after_time.is_none_or(|t| event.time > t)
+            && !self.exclude_ids.contains(&event.id)
+            && self.author.as_ref().is_none_or(|a| &event.author == a)
+    }
+
+    /// Apply the filter to events in chronological order
+    pub fn apply<'a>(&self, events: impl IntoIterator<Item = &'a Event>) -> Vec<Event> {
+        events
+            .into_iter()
+            .filter(|e| self.matches(e))
+            .take(self.limit.unwrap_or(usize::MAX))
+            .cloned()
+            .collect()
+    }
+
+    /// Filter for the events following `page`, which this filter returned
+    ///
+    /// Event times have one-second resolution, so the next page starts after
+    /// the second before the last event's and skips the events of the last
+    /// event's second already seen.
+    pub fn next_page(&self, page: &[Event]) -> Self {
+        let Some(last) = page.last() else {
+            return self.clone();
+        };
+        let after_time = last.time - 1;
+
+        let mut exclude_ids: Vec<String> = page
+            .iter()
+            .filter(|e| e.time == last.time)
+            .map(|e| e.id.clone())
+            .collect();
+        if self.after_time == Some(after_time) {
+            exclude_ids.extend(self.exclude_ids.iter().cloned());
+        }
+
+        Self {
+            after_time: Some(after_time),
+            exclude_ids,
+            ..self.clone()
+        }
+    }
+}
@@ -0,0 +1,230 @@
+//! Integration tests for `RedisSessionService`
+//!
+//! These need a running Redis and are skipped unless `REDIS_URL` is set:
+//!
+//! ```sh
+//! REDIS_URL=redis://127.0.0.1/ cargo test -p zdk-session --features redis
+//! ```
+
+#![cfg(feature = "redis")]
+
+use serde_json::json;
+use zdk_core::Event;
+use zdk_session::{CreateRequest, GetRequest, RedisSessionService, SessionService};
+
+/// Two independent services sharing the Redis at `REDIS_URL`, like two server instances
+async fn service_pair() -> Option<(RedisSessionService, RedisSessionService)> {
+    let Ok(url) = std::env::var("REDIS_URL") else {
+        eprintln!("REDIS_URL not set, skipping Redis session test");
+        return None;
+    };
+
+    let first = RedisSessionService::new(&url).await.unwrap();
+    let second = RedisSessionService::new(&url).await.unwrap();
+    Some((first, second))
+}
+
+/// App name unique to this run so tests don't see each other's sessions
+fn unique_app() -> String {
+    format!("zdk-test-{}", uuid::Uuid::new_v4())
+}
+
+/// Session IDs are global in Redis, so derive them from the unique app name
+fn session_id(app_name: &str, n: u32) -> String {
+    format!("{}-session{}", app_name, n)
+}
+
+#[tokio::test]
+async fn test_sessions_shared_across_instances() {
+    let Some((first, second)) = service_pair().await else {
+        return;
+    };
+    let app_name = unique_app();
+    let id = session_id(&app_name, 1);
+
+    first
+        .create(&CreateRequest {
+            app_name: app_name.clone(),
+            user_id: "user1".to_string(),
+            session_id: Some(id.clone()),
+        })
+        .await
+        .unwrap();
+
+    first
+        .append_event(&id, Event::new("inv1".to_string(), "user".to_string()))
+        .await
+        .unwrap();
+
+    let mut reply = Event::new("inv1".to_string(), "agent".to_string());
+    reply
+        .actions
+        .state_delta
+        .insert("topic".to_string(), json!("weather"));
+    second.append_event(&id, reply).await.unwrap();
+
+    let req = GetRequest {
+        app_name: app_name.clone(),
+        user_id: "user1".to_string(),
+        session_id: id,
+    };
+    for service in [&first, &second] {
+        let session = service.get(&req).await.unwrap();
+        let authors: Vec<_> = session.events().into_iter().map(|e| e.author).collect();
+        assert_eq!(authors, vec!["user", "agent"]);
+        assert_eq!(session.state()["topic"], json!("weather"));
+    }
+
+    second.delete(&req).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_list_and_delete_across_instances() {
+    let Some((first, second)) = service_pair().await else {
+        return;
+    };
+    let app_name = unique_app();
+    let ids = vec![session_id(&app_name, 1), session_id(&app_name, 2)];
+
+    for id in &ids {
+        first
+            .create(&CreateRequest {
+                app_name: app_name.clone(),
+                user_id: "user1".to_string(),
+                session_id: Some(id.clone()),
+            })
+            .await
+            .unwrap();
+    }
+    assert_eq!(second.list(&app_name, "user1").await.unwrap(), ids);
+
+    let req = GetRequest {
+        app_name: app_name.clone(),
+        user_id: "user1".to_string(),
+        session_id: ids[0].clone(),
+    };
+    second.delete(&req).await.unwrap();
+
+    assert!(first.get(&req).await.is_err());
+    assert_eq!(
+        first.list(&app_name, "user1").await.unwrap(),
+        vec![ids[1].clone()]
+    );
+
+    first
+        .delete(&GetRequest {
+            session_id: ids[1].clone(),
+            ..req
+        })
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_get_checks_app_and_user() {
+    let Some((first, _)) = service_pair().await else {
+        return;
+    };
+    let app_name = unique_app();
+    let id = session_id(&app_name, 1);
+
+    first
+        .create(&CreateRequest {
+            app_name: app_name.clone(),
+            user_id: "user1".to_string(),
+            session_id: Some(id.clone()),
+        })
+        .await
+        .unwrap();
+
+    let other_user = GetRequest {
+        app_name: app_name.clone(),
+        user_id: "user2".to_string(),
+        session_id: id,
+    };
+    assert!(first.get(&other_user).await.is_err());
+
+    first
+        .delete(&GetRequest {
+            user_id: "user1".to_string(),
+            ..other_user
+        })
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_create_does_not_take_over_another_users_session() {
+    let Some((first, second)) = service_pair().await else {
+        return;
+    };
+    let app_name = unique_app();
+    let id = session_id(&app_name, 1);
+    let create = |user_id: &str| CreateRequest {
+        app_name: app_name.clone(),
+        user_id: user_id.to_string(),
+        session_id: Some(id.clone()),
+    };
+
+    first.create(&create("user1")).await.unwrap();
+    // Re-creating your own session is fine, claiming someone else's isn't
+    first.create(&create("user1")).await.unwrap();
+    assert!(second.create(&create("user2")).await.is_err());
+
+    let owner = GetRequest {
+        app_name: app_name.clone(),
+        user_id: "user1".to_string(),
+        session_id: id,
+    };
+    assert!(first.get(&owner).await.is_ok());
+    assert!(second.list(&app_name, "user2").await.unwrap().is_empty());
+
+    first.delete(&owner).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_ids_containing_colons_get_separate_keys() {
+    let Some((first, second)) = service_pair().await else {
+        return;
+    };
+    let app_name = unique_app();
+    let suffix = session_id(&app_name, 1);
+    // Unescaped, both would be stored under `{app}:b:c:{suffix}`
+    let colliding = [
+        GetRequest {
+            app_name: app_name.clone(),
+            user_id: "b:c".to_string(),
+            session_id: suffix.clone(),
+        },
+        GetRequest {
+            app_name: app_name.clone(),
+            user_id: "b".to_string(),
+            session_id: format!("c:{}", suffix),
+        },
+    ];
+    for req in &colliding {
+        first
+            .create(&CreateRequest {
+                app_name: req.app_name.clone(),
+                user_id: req.user_id.clone(),
+                session_id: Some(req.session_id.clone()),
+            })
+            .await
+            .unwrap();
+    }
+
+    first
+        .append_event(
+            &colliding[0].session_id,
+            Event::new("inv1".to_string(), "user".to_string()),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(second.get(&colliding[0]).await.unwrap().events().len(), 1);
+    assert!(second.get(&colliding[1]).await.unwrap().events().is_empty());
+
+    for req in &colliding {
+        first.delete(req).await.unwrap();
+    }
+}
@@ -0,0 +1,100 @@
+//! Every session backend applies an event's state delta when appending it
+//!
+//! The SQLite check runs against an in-memory database; Postgres and Redis
+//! need their features enabled and are skipped unless `DATABASE_URL` /
+//! `REDIS_URL` point at a running server:
+//!
+//! ```sh
+//! DATABASE_URL=postgres://localhost/zdk REDIS_URL=redis://127.0.0.1/ \
+//!     cargo test -p zdk-session --features sqlite,postgres,redis --test state_delta
+//! ```
+
+use serde_json::json;
+use zdk_core::Event;
+use zdk_session::inmemory::InMemorySessionService;
+use zdk_session::{CreateRequest, GetRequest, SessionService};
+
+/// Append events carrying state deltas and check the session state reflects them
+async fn check_state_delta_applied(service: &dyn SessionService) {
+    // Session IDs are global in some backends, so keep them unique per run
+    let session_id = format!("state-delta-{}", uuid::Uuid::new_v4());
+    service
+        .create(&CreateRequest {
+            app_name: "state-app".to_string(),
+            user_id: "user1".to_string(),
+            session_id: Some(session_id.clone()),
+        })
+        .await
+        .unwrap();
+
+    let mut first = Event::new("inv1".to_string(), "agent".to_string());
+    first
+        .actions
+        .state_delta
+        .insert("topic".to_string(), json!("weather"));
+    first
+        .actions
+        .state_delta
+        .insert("count".to_string(), json!(1));
+    service.append_event(&session_id, first).await.unwrap();
+
+    let mut second = Event::new("inv1".to_string(), "agent".to_string());
+    second
+        .actions
+        .state_delta
+        .insert("count".to_string(), json!(2));
+    service.append_event(&session_id, second).await.unwrap();
+
+    let req = GetRequest {
+        app_name: "state-app".to_string(),
+        user_id: "user1".to_string(),
+        session_id,
+    };
+    let session = service.get(&req).await.unwrap();
+    assert_eq!(session.events().len(), 2);
+    assert_eq!(session.state()["topic"], json!("weather"));
+    assert_eq!(session.state()["count"], json!(2));
+
+    service.delete(&req).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_in_memory_applies_state_delta() {
+    check_state_delta_applied(&InMemorySessionService::new()).await;
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_applies_state_delta() {
+    // A named shared-cache database so every pooled connection sees the same data
+    let url = format!(
+        "sqlite:file:{}?mode=memory&cache=shared",
+        uuid::Uuid::new_v4()
+    );
+    let service = zdk_session::SqliteSessionService::new(&url).await.unwrap();
+    check_state_delta_applied(&service).await;
+}
+
+#[cfg(feature = "postgres")]
+#[tokio::test]
+async fn test_postgres_applies_state_delta() {
+    let Ok(url) = std::env::var("DATABASE_URL") else {
+        eprintln!("DATABASE_URL not set, skipping Postgres session test");
+        return;
+    };
+    let service = zdk_session::PostgresSessionService::new(&url)
+        .await
+        .unwrap();
+    check_state_delta_applied(&service).await;
+}
+
+#[cfg(feature = "redis")]
+#[tokio::test]
+async fn test_redis_applies_state_delta() {
+    let Ok(url) = std::env::var("REDIS_URL") else {
+        eprintln!("REDIS_URL not set, skipping Redis session test");
+        return;
+    };
+    let service = zdk_session::RedisSessionService::new(&url).await.unwrap();
+    check_state_delta_applied(&service).await;
+}
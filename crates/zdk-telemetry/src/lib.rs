@@ -5,10 +5,15 @@
 //! This crate provides automatic tracing for LLM calls, tool executions, and agent runs
 //! using OpenTelemetry standards. It includes structured span attributes compatible with
 //! GCP Vertex AI Agent telemetry format for seamless cloud integration.
+//!
+//! Alongside spans, token usage, tool invocations, and agent run durations are
+//! recorded as OpenTelemetry metrics once [`init_metrics`] has been called.
 
+mod metrics;
 mod spans;
 mod tracer;
 
+pub use metrics::{init_metrics, record_agent_run, record_llm_tokens, record_tool_invocation};
 pub use spans::{LLMSpanAttributes, ToolSpanAttributes, trace_llm_call, trace_tool_call};
 pub use tracer::{init_telemetry, register_span_processor};
 
@@ -23,12 +28,21 @@ pub mod attributes {
     pub const GEN_AI_REQUEST_MODEL: &str = "gen_ai.request.model";
     pub const GEN_AI_REQUEST_TOP_P: &str = "gen_ai.request.top_p";
     pub const GEN_AI_REQUEST_MAX_TOKENS: &str = "gen_ai.request.max_tokens";
+    pub const GEN_AI_USAGE_INPUT_TOKENS: &str = "gen_ai.usage.input_tokens";
+    pub const GEN_AI_USAGE_OUTPUT_TOKENS: &str = "gen_ai.usage.output_tokens";
+    pub const GEN_AI_TOKEN_TYPE: &str = "gen_ai.token.type";
+    pub const GEN_AI_AGENT_NAME: &str = "gen_ai.agent.name";
 
     // Tool-specific attributes
     pub const GEN_AI_TOOL_NAME: &str = "gen_ai.tool.name";
     pub const GEN_AI_TOOL_DESCRIPTION: &str = "gen_ai.tool.description";
     pub const GEN_AI_TOOL_CALL_ID: &str = "gen_ai.tool.call.id";
 
+    // Metric names
+    pub const GEN_AI_REQUEST_TOKENS: &str = "gen_ai.request.tokens";
+    pub const GEN_AI_TOOL_INVOCATIONS: &str = "gen_ai.tool.invocations";
+    pub const GEN_AI_AGENT_RUN_DURATION: &str = "gen_ai.agent.run.duration";
+
     // GCP Vertex Agent attributes
     pub const GCP_VERTEX_AGENT_LLM_REQUEST: &str = "gcp.vertex.agent.llm_request";
     pub const GCP_VERTEX_AGENT_LLM_RESPONSE: &str = "gcp.vertex.agent.llm_response";
@@ -0,0 +1,272 @@
+//! Aggregate metrics for LLM calls, tool executions, and agent runs
+
+use crate::attributes::*;
+use opentelemetry::KeyValue;
+use opentelemetry::metrics::{Counter, Histogram, MeterProvider as _};
+use opentelemetry_sdk::metrics::SdkMeterProvider;
+use opentelemetry_sdk::metrics::reader::MetricReader;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Global meter provider holder
+static METER_PROVIDER: OnceLock<SdkMeterProvider> = OnceLock::new();
+
+/// Instruments created from the global meter provider
+static INSTRUMENTS: OnceLock<Instruments> = OnceLock::new();
+
+struct Instruments {
+    llm_tokens: Counter<u64>,
+    tool_invocations: Counter<u64>,
+    agent_run_duration: Histogram<f64>,
+}
+
+/// Initialize metrics, exporting through `reader`.
+///
+/// Until this is called the `record_*` functions do nothing. The reader decides
+/// where metrics go: a `PeriodicReader` with an exporter for dashboards, or a
+/// `ManualReader` to collect them on demand. Only the first call takes effect.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use opentelemetry_sdk::metrics::ManualReader;
+/// use zdk_telemetry::{init_metrics, init_telemetry};
+///
+/// init_telemetry();
+/// init_metrics(ManualReader::builder().build());
+/// ```
+pub fn init_metrics<R: MetricReader>(reader: R) {
+    if METER_PROVIDER.get().is_some() {
+        tracing::warn!("Metrics are already initialized");
+        return;
+    }
+
+    let provider = SdkMeterProvider::builder().with_reader(reader).build();
+    let meter = provider.meter(SYSTEM_NAME);
+
+    let instruments = Instruments {
+        llm_tokens: meter
+            .u64_counter(GEN_AI_REQUEST_TOKENS)
+            .with_description("Tokens used by LLM requests")
+            .with_unit("{token}")
+            .init(),
+        tool_invocations: meter
+            .u64_counter(GEN_AI_TOOL_INVOCATIONS)
+            .with_description("Number of tool executions")
+            .with_unit("{invocation}")
+            .init(),
+        agent_run_duration: meter
+            .f64_histogram(GEN_AI_AGENT_RUN_DURATION)
+            .with_description("Duration of agent runs")
+            .with_unit("s")
+            .init(),
+    };
+
+    if METER_PROVIDER.set(provider).is_ok() {
+        let _ = INSTRUMENTS.set(instruments);
+    }
+}
+
+/// Count tokens used by one LLM call, split into input and output tokens
+pub fn record_llm_tokens(model: &str, input_tokens: u64, output_tokens: u64) {
+    let Some(instruments) = INSTRUMENTS.get() else {
+        return;
+    };
+
+    for (token_type, count) in [("input", input_tokens), ("output", output_tokens)] {
+        instruments.llm_tokens.add(
+            count,
+            &[
+                KeyValue::new(GEN_AI_REQUEST_MODEL, model.to_string()),
+                KeyValue::new(GEN_AI_TOKEN_TYPE, token_type),
+            ],
+        );
+    }
+}
+
+/// Count one execution of `tool_name`
+pub fn record_tool_invocation(tool_name: &str) {
+    if let Some(instruments) = INSTRUMENTS.get() {
+        instruments
+            .tool_invocations
+            .add(1, &[KeyValue::new(GEN_AI_TOOL_NAME, tool_name.to_string())]);
+    }
+}
+
+/// Record how long a run of `agent_name` took
+pub fn record_agent_run(agent_name: &str, duration: Duration) {
+    if let Some(instruments) = INSTRUMENTS.get() {
+        instruments.agent_run_duration.record(
+            duration.as_secs_f64(),
+            &[KeyValue::new(GEN_AI_AGENT_NAME, agent_name.to_string())],
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LLMSpanAttributes, ToolSpanAttributes, trace_llm_call, trace_tool_call};
+    use opentelemetry::metrics::Result as MetricsResult;
+    use opentelemetry_sdk::Resource;
+    use opentelemetry_sdk::metrics::data::{self, ResourceMetrics, Temporality};
+    use opentelemetry_sdk::metrics::reader::{AggregationSelector, TemporalitySelector};
+    use opentelemetry_sdk::metrics::{Aggregation, InstrumentKind, ManualReader, Pipeline};
+    use std::sync::{Arc, Weak};
+
+    /// Lets the test collect from the reader owned by the meter provider
+    #[derive(Debug, Clone)]
+    struct SharedReader(Arc<ManualReader>);
+
+    impl TemporalitySelector for SharedReader {
+        fn temporality(&self, kind: InstrumentKind) -> Temporality {
+            self.0.temporality(kind)
+        }
+    }
+
+    impl AggregationSelector for SharedReader {
+        fn aggregation(&self, kind: InstrumentKind) -> Aggregation {
+            self.0.aggregation(kind)
+        }
+    }
+
+    impl MetricReader for SharedReader {
+        fn register_pipeline(&self, pipeline: Weak<Pipeline>) {
+            self.0.register_pipeline(pipeline)
+        }
+
+        fn collect(&self, rm: &mut ResourceMetrics) -> MetricsResult<()> {
+            self.0.collect(rm)
+        }
+
+        fn force_flush(&self) -> MetricsResult<()> {
+            self.0.force_flush()
+        }
+
+        fn shutdown(&self) -> MetricsResult<()> {
+            self.0.shutdown()
+        }
+    }
+
+    fn collect(reader: &SharedReader) -> ResourceMetrics {
+        let mut rm = ResourceMetrics {
+            resource: Resource::empty(),
+            scope_metrics: Vec::new(),
+        };
+        reader.collect(&mut rm).unwrap();
+        rm
+    }
+
+    fn find<'a, T: 'static>(rm: &'a ResourceMetrics, name: &str) -> &'a T {
+        rm.scope_metrics
+            .iter()
+            .flat_map(|scope| &scope.metrics)
+            .find(|metric| metric.name == name)
+            .and_then(|metric| metric.data.as_any().downcast_ref::<T>())
+            .unwrap_or_else(|| panic!("metric {} not recorded", name))
+    }
+
+    fn has_attr(attributes: &[KeyValue], key: &str, value: &str) -> bool {
+        attributes
+            .iter()
+            .any(|kv| kv.key.as_str() == key && kv.value.as_str() == value)
+    }
+
+    /// Sum of the data points carrying every `(key, value)` in `attrs`
+    fn counter_value(rm: &ResourceMetrics, name: &str, attrs: &[(&str, &str)]) -> u64 {
+        find::<data::Sum<u64>>(rm, name)
+            .data_points
+            .iter()
+            .filter(|point| {
+                attrs
+                    .iter()
+                    .all(|(key, value)| has_attr(&point.attributes, key, value))
+            })
+            .map(|point| point.value)
+            .sum()
+    }
+
+    fn tool_call(tool_name: &str) -> ToolSpanAttributes {
+        ToolSpanAttributes {
+            tool_name: tool_name.to_string(),
+            tool_description: "Calculate math".to_string(),
+            tool_call_id: "call-1".to_string(),
+            invocation_id: "inv-1".to_string(),
+            session_id: "sess-1".to_string(),
+            event_id: "event-1".to_string(),
+            args_json: "{}".to_string(),
+            response_json: "{}".to_string(),
+        }
+    }
+
+    // Only this test initializes metrics, and it uses names no other test records
+    #[test]
+    fn test_metrics_counters_increment() {
+        let reader = SharedReader(Arc::new(ManualReader::builder().build()));
+        init_metrics(reader.clone());
+
+        trace_llm_call(LLMSpanAttributes {
+            model: "metrics-test-model".to_string(),
+            invocation_id: "inv-1".to_string(),
+            session_id: "sess-1".to_string(),
+            event_id: "event-1".to_string(),
+            request_json: "{}".to_string(),
+            response_json: "{}".to_string(),
+            top_p: None,
+            max_tokens: None,
+            input_tokens: Some(12),
+            output_tokens: Some(30),
+        });
+        record_llm_tokens("metrics-test-model", 8, 10);
+
+        trace_tool_call(tool_call("metrics_test_tool"));
+        trace_tool_call(tool_call("metrics_test_tool"));
+        record_tool_invocation("metrics_other_tool");
+
+        record_agent_run("metrics_test_agent", Duration::from_millis(1500));
+
+        let rm = collect(&reader);
+        let model = (GEN_AI_REQUEST_MODEL, "metrics-test-model");
+        assert_eq!(
+            counter_value(
+                &rm,
+                GEN_AI_REQUEST_TOKENS,
+                &[model, (GEN_AI_TOKEN_TYPE, "input")]
+            ),
+            20
+        );
+        assert_eq!(
+            counter_value(
+                &rm,
+                GEN_AI_REQUEST_TOKENS,
+                &[model, (GEN_AI_TOKEN_TYPE, "output")]
+            ),
+            40
+        );
+        assert_eq!(
+            counter_value(
+                &rm,
+                GEN_AI_TOOL_INVOCATIONS,
+                &[(GEN_AI_TOOL_NAME, "metrics_test_tool")]
+            ),
+            2
+        );
+        assert_eq!(
+            counter_value(
+                &rm,
+                GEN_AI_TOOL_INVOCATIONS,
+                &[(GEN_AI_TOOL_NAME, "metrics_other_tool")]
+            ),
+            1
+        );
+
+        let runs = find::<data::Histogram<f64>>(&rm, GEN_AI_AGENT_RUN_DURATION);
+        let run = runs
+            .data_points
+            .iter()
+            .find(|point| has_attr(&point.attributes, GEN_AI_AGENT_NAME, "metrics_test_agent"))
+            .unwrap();
+        assert_eq!(run.count, 1);
+        assert_eq!(run.sum, 1.5);
+    }
+}
@@ -1,6 +1,7 @@
 //! Span creation helpers for LLM calls and tool executions
 
 use crate::attributes::*;
+use crate::metrics::{record_llm_tokens, record_tool_invocation};
 
 /// Attributes for tracing an LLM call
 #[derive(Debug, Clone)]
@@ -13,6 +14,10 @@ pub struct LLMSpanAttributes {
     pub response_json: String,
     pub top_p: Option<f64>,
     pub max_tokens: Option<i64>,
+    /// Prompt tokens, when the provider reports usage
+    pub input_tokens: Option<u64>,
+    /// Generated tokens, when the provider reports usage
+    pub output_tokens: Option<u64>,
 }
 
 /// Attributes for tracing a tool call
@@ -33,6 +38,8 @@ pub struct ToolSpanAttributes {
 /// Records comprehensive telemetry including the model name, request/response payloads,
 /// invocation context, and optional parameters like top_p and max_tokens. The span
 /// follows OpenTelemetry semantic conventions for generative AI operations.
+///
+/// Token counts, when known, are also added to the `gen_ai.request.tokens` metric.
 pub fn trace_llm_call(attrs: LLMSpanAttributes) {
     let span = tracing::info_span!(
         "call_llm",
@@ -43,6 +50,11 @@ pub fn trace_llm_call(attrs: LLMSpanAttributes) {
         { GCP_VERTEX_AGENT_EVENT_ID } = %attrs.event_id,
         { GCP_VERTEX_AGENT_LLM_REQUEST } = %attrs.request_json,
         { GCP_VERTEX_AGENT_LLM_RESPONSE } = %attrs.response_json,
+        // Optional fields must be declared up front for `record` to set them
+        { GEN_AI_REQUEST_TOP_P } = tracing::field::Empty,
+        { GEN_AI_REQUEST_MAX_TOKENS } = tracing::field::Empty,
+        { GEN_AI_USAGE_INPUT_TOKENS } = tracing::field::Empty,
+        { GEN_AI_USAGE_OUTPUT_TOKENS } = tracing::field::Empty,
     );
 
     // Add optional attributes if present
@@ -52,6 +64,20 @@ pub fn trace_llm_call(attrs: LLMSpanAttributes) {
     if let Some(max_tokens) = attrs.max_tokens {
         span.record(GEN_AI_REQUEST_MAX_TOKENS, max_tokens);
     }
+    if let Some(input_tokens) = attrs.input_tokens {
+        span.record(GEN_AI_USAGE_INPUT_TOKENS, input_tokens);
+    }
+    if let Some(output_tokens) = attrs.output_tokens {
+        span.record(GEN_AI_USAGE_OUTPUT_TOKENS, output_tokens);
+    }
+
+    if attrs.input_tokens.is_some() || attrs.output_tokens.is_some() {
+        record_llm_tokens(
+            &attrs.model,
+            attrs.input_tokens.unwrap_or(0),
+            attrs.output_tokens.unwrap_or(0),
+        );
+    }
 
     // Enter and immediately exit the span (it's recorded)
     let _guard = span.enter();
@@ -62,6 +88,8 @@ pub fn trace_llm_call(attrs: LLMSpanAttributes) {
 /// Records tool invocation details including tool name, description, call ID,
 /// arguments, and response. This enables distributed tracing of tool calls
 /// throughout the agent execution flow.
+///
+/// Each call also increments the `gen_ai.tool.invocations` metric.
 pub fn trace_tool_call(attrs: ToolSpanAttributes) {
     let span = tracing::info_span!(
         "execute_tool",
@@ -79,6 +107,8 @@ pub fn trace_tool_call(attrs: ToolSpanAttributes) {
         { GCP_VERTEX_AGENT_LLM_RESPONSE } = "{}",
     );
 
+    record_tool_invocation(&attrs.tool_name);
+
     // Enter and immediately exit the span (it's recorded)
     let _guard = span.enter();
 }
@@ -118,6 +148,8 @@ mod tests {
             response_json: "{}".to_string(),
             top_p: Some(0.95),
             max_tokens: Some(1024),
+            input_tokens: None,
+            output_tokens: None,
         };
 
         // Just verify we can create and use the attributes
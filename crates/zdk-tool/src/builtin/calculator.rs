@@ -1,13 +1,25 @@
 use crate::{FunctionTool, ToolSchema};
-use zdk_core::{Error, Result, ToolResponse};
+use serde_json::Value;
+use zdk_core::{Error, Result, ToolError, ToolErrorKind, ToolResponse};
+
+/// Comparison operators, two-character ones first so `>=` isn't read as `>`
+const COMPARISONS: [&str; 5] = [">=", "<=", "==", ">", "<"];
 
 /// Creates a calculator tool that evaluates mathematical expressions
+///
+/// Expressions may reference numeric `variables` and compare two sides with
+/// `>`, `<`, `>=`, `<=` or `==`, which yields a boolean result.
 pub fn create_calculator_tool() -> Result<FunctionTool> {
     let schema = ToolSchema::new()
         .property(
             "expression",
             "string",
-            "Mathematical expression to evaluate (e.g., '2 + 2', '10 * 5')",
+            "Mathematical expression to evaluate (e.g., '2 + 2', 'price * qty > 100')",
+        )
+        .property(
+            "variables",
+            "object",
+            "Optional map of variable names to numbers used in the expression",
         )
         .required("expression")
         .build();
@@ -15,7 +27,8 @@ pub fn create_calculator_tool() -> Result<FunctionTool> {
     FunctionTool::builder()
         .name("calculator")
         .description(
-            "Evaluates mathematical expressions. Supports +, -, *, /, parentheses, and numbers.",
+            "Evaluates mathematical expressions. Supports +, -, *, /, parentheses, numbers, \
+             named variables, and comparisons (>, <, >=, <=, ==) that return true or false.",
         )
         .schema(schema)
         .execute(|ctx, params| async move {
@@ -30,8 +43,12 @@ pub fn create_calculator_tool() -> Result<FunctionTool> {
                 "Calculating expression"
             );
 
-            // Simple expression evaluator
-            let result = evaluate_expression(expression)?;
+            let result = match variables_context(&params["variables"])
+                .and_then(|variables| evaluate_expression(expression, &variables))
+            {
+                Ok(result) => result,
+                Err(error) => return Ok(ToolResponse::error(error.kind, error.message)),
+            };
 
             tracing::debug!(
                 invocation_id = %ctx.invocation_id(),
@@ -40,29 +57,106 @@ pub fn create_calculator_tool() -> Result<FunctionTool> {
                 "Calculation completed"
             );
 
-            Ok(ToolResponse {
-                result: serde_json::json!({
-                    "result": result,
-                    "expression": expression
-                }),
-            })
+            Ok(ToolResponse::new(serde_json::json!({
+                "result": result,
+                "expression": expression
+            })))
         })
         .build()
 }
 
-/// Simple expression evaluator
-/// Supports: +, -, *, /, parentheses, and numbers
-fn evaluate_expression(expr: &str) -> Result<f64> {
+/// Build the evaluation context from the optional `variables` parameter
+fn variables_context(variables: &Value) -> std::result::Result<meval::Context<'static>, ToolError> {
+    let mut context = meval::Context::new();
+    match variables {
+        Value::Null => {}
+        Value::Object(map) => {
+            for (name, value) in map {
+                let value = value.as_f64().ok_or_else(|| {
+                    ToolError::new(
+                        ToolErrorKind::InvalidInput,
+                        format!("Variable '{}' must be a number, got {}", name, value),
+                    )
+                })?;
+                context.var(name.as_str(), value);
+            }
+        }
+        other => {
+            return Err(ToolError::new(
+                ToolErrorKind::InvalidInput,
+                format!("'variables' must be an object, got {}", other),
+            ));
+        }
+    }
+    Ok(context)
+}
+
+/// Evaluate an arithmetic expression or a comparison of two of them
+fn evaluate_expression(
+    expr: &str,
+    variables: &meval::Context,
+) -> std::result::Result<Value, ToolError> {
+    let Some((lhs, op, rhs)) = split_comparison(expr) else {
+        return evaluate_arithmetic(expr, variables).map(Value::from);
+    };
+
+    let lhs = evaluate_arithmetic(lhs, variables)?;
+    let rhs = evaluate_arithmetic(rhs, variables)?;
+    let result = match op {
+        ">=" => lhs >= rhs,
+        "<=" => lhs <= rhs,
+        "==" => lhs == rhs,
+        ">" => lhs > rhs,
+        _ => lhs < rhs,
+    };
+    Ok(Value::Bool(result))
+}
+
+/// Split at the first comparison operator outside parentheses
+fn split_comparison(expr: &str) -> Option<(&str, &'static str, &str)> {
+    let mut depth = 0i32;
+    for (i, c) in expr.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ if depth == 0 => {
+                if let Some(op) = COMPARISONS.iter().find(|op| expr[i..].starts_with(*op)) {
+                    return Some((&expr[..i], op, &expr[i + op.len()..]));
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Evaluate a numeric expression
+/// Supports: +, -, *, /, parentheses, numbers, and variables from `variables`
+fn evaluate_arithmetic(
+    expr: &str,
+    variables: &meval::Context,
+) -> std::result::Result<f64, ToolError> {
     let expr = expr.trim().replace(" ", "");
 
     // Use meval for expression evaluation
-    match meval::eval_str(&expr) {
-        Ok(result) => Ok(result),
-        Err(e) => Err(Error::Other(anyhow::anyhow!(
-            "Failed to evaluate expression: {}",
-            e
-        ))),
+    let result = expr
+        .parse::<meval::Expr>()
+        .and_then(|parsed| parsed.eval_with_context(variables))
+        .map_err(|e| {
+            ToolError::new(
+                ToolErrorKind::InvalidInput,
+                format!("Failed to evaluate expression: {}", e),
+            )
+        })?;
+
+    // Division by zero yields infinity or NaN rather than failing
+    if !result.is_finite() {
+        return Err(ToolError::new(
+            ToolErrorKind::InvalidInput,
+            format!("Division by zero or undefined result in '{}'", expr),
+        ));
     }
+    Ok(result)
 }
 
 #[cfg(test)]
@@ -97,4 +191,82 @@ mod tests {
         let response = tool.execute(ctx, params).await.unwrap();
         assert_eq!(response.result["result"], 30.0);
     }
+
+    async fn calculate(params: serde_json::Value) -> ToolResponse {
+        let tool = create_calculator_tool().unwrap();
+        let ctx = Arc::new(DefaultToolContext::new(
+            "call-1".to_string(),
+            "inv-1".to_string(),
+        ));
+        tool.execute(ctx, params).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_calculator_variables() {
+        let response = calculate(serde_json::json!({
+            "expression": "price * qty + 2",
+            "variables": {"price": 2.5, "qty": 4}
+        }))
+        .await;
+        assert!(response.error.is_none());
+        assert_eq!(response.result["result"], 12.0);
+
+        let response = calculate(serde_json::json!({"expression": "missing + 1"})).await;
+        assert_eq!(response.error.unwrap().kind, ToolErrorKind::InvalidInput);
+
+        let response = calculate(serde_json::json!({
+            "expression": "x + 1",
+            "variables": {"x": "three"}
+        }))
+        .await;
+        assert!(
+            response
+                .error
+                .unwrap()
+                .message
+                .contains("'x' must be a number")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_calculator_comparisons() {
+        let cases = [
+            ("x > 3", true),
+            ("x > 5", false),
+            ("x < 10", true),
+            ("x < 5", false),
+            ("x >= 5", true),
+            ("x >= 6", false),
+            ("x <= 5", true),
+            ("x <= 4", false),
+            ("x == 5", true),
+            ("(x + 1) * 2 == 12", true),
+            ("x == 4", false),
+        ];
+
+        for (expression, expected) in cases {
+            let response = calculate(serde_json::json!({
+                "expression": expression,
+                "variables": {"x": 5}
+            }))
+            .await;
+            assert!(response.error.is_none(), "{} failed", expression);
+            assert_eq!(response.result["result"], expected, "{}", expression);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_calculator_division_by_zero() {
+        for expression in ["1 / 0", "0 / 0", "10 / (x - 2) > 1"] {
+            let response = calculate(serde_json::json!({
+                "expression": expression,
+                "variables": {"x": 2}
+            }))
+            .await;
+            let error = response.error.expect(expression);
+            assert_eq!(error.kind, ToolErrorKind::InvalidInput);
+            assert!(error.message.contains("Division by zero"), "{}", error);
+            assert!(response.result["error"].is_string());
+        }
+    }
 }
@@ -24,13 +24,11 @@ pub fn create_echo_tool() -> Result<FunctionTool> {
                 "Echo tool called"
             );
 
-            Ok(ToolResponse {
-                result: serde_json::json!({
-                    "message": message,
-                    "invocation_id": ctx.invocation_id(),
-                    "function_call_id": ctx.function_call_id()
-                }),
-            })
+            Ok(ToolResponse::new(serde_json::json!({
+                "message": message,
+                "invocation_id": ctx.invocation_id(),
+                "function_call_id": ctx.function_call_id()
+            })))
         })
         .build()
 }
@@ -0,0 +1,480 @@
+//! Sandboxed file system tool
+
+use async_trait::async_trait;
+use base64::{Engine as _, engine::general_purpose};
+use serde_json::{Value, json};
+use std::io::ErrorKind;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use zdk_core::{Error, Result, Tool, ToolContext, ToolError, ToolErrorKind, ToolResponse};
+
+/// Largest file `read_file` will return
+const MAX_READ_BYTES: u64 = 10 * 1024 * 1024;
+
+/// File system tool confined to a sandbox directory
+///
+/// Exposes `read_file`, `write_file` and `list_dir`. Paths are relative to
+/// the sandbox root; `..` components are rejected and every path is
+/// canonicalized and checked against the root, so symlinks can't lead
+/// outside it either.
+///
+/// File contents use the same convention as `ArtifactPart`: UTF-8 text is
+/// passed as is, anything else as base64 with `"encoding": "base64"`.
+///
+/// ## Example
+///
+/// ```rust,no_run
+/// use zdk_tool::builtin::FileSystemTool;
+/// use std::sync::Arc;
+///
+/// let tool = Arc::new(FileSystemTool::new("./workspace").unwrap());
+/// ```
+pub struct FileSystemTool {
+    name: String,
+    description: String,
+    /// Canonical sandbox root
+    root: PathBuf,
+}
+
+impl FileSystemTool {
+    /// Create a tool confined to `sandbox_root`, which must be an existing directory
+    pub fn new(sandbox_root: impl AsRef<Path>) -> Result<Self> {
+        let sandbox_root = sandbox_root.as_ref();
+        let root = sandbox_root.canonicalize().map_err(|e| {
+            Error::Config(format!(
+                "Invalid sandbox root '{}': {}",
+                sandbox_root.display(),
+                e
+            ))
+        })?;
+        if !root.is_dir() {
+            return Err(Error::Config(format!(
+                "Sandbox root '{}' is not a directory",
+                sandbox_root.display()
+            )));
+        }
+
+        Ok(Self {
+            name: "file_system".to_string(),
+            description: "Read, write and list files inside the sandbox directory. Paths are relative to the sandbox root. Binary file contents are base64 encoded.".to_string(),
+            root,
+        })
+    }
+
+    /// The canonical sandbox root
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Resolve `path` to a canonical path inside the sandbox
+    ///
+    /// With `may_not_exist`, the final component doesn't have to exist yet
+    /// (for new files); its parent directory must.
+    fn resolve(&self, path: &str, may_not_exist: bool) -> std::result::Result<PathBuf, ToolError> {
+        let relative = Path::new(path);
+        if relative
+            .components()
+            .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+        {
+            return Err(escape_error(path));
+        }
+
+        let joined = self.root.join(relative);
+        let resolved = match joined.canonicalize() {
+            Ok(resolved) => resolved,
+            Err(e) if may_not_exist && e.kind() == ErrorKind::NotFound => {
+                let Some(name) = joined.file_name() else {
+                    return Err(escape_error(path));
+                };
+                let parent = joined
+                    .parent()
+                    .unwrap_or(&self.root)
+                    .canonicalize()
+                    .map_err(|e| io_error(path, e))?;
+                let resolved = parent.join(name);
+                // A dangling symlink doesn't canonicalize, but writing
+                // through it would create its target wherever it points
+                if std::fs::symlink_metadata(&resolved).is_ok() {
+                    return Err(escape_error(path));
+                }
+                resolved
+            }
+            Err(e) => return Err(io_error(path, e)),
+        };
+
+        if resolved.starts_with(&self.root) {
+            Ok(resolved)
+        } else {
+            Err(escape_error(path))
+        }
+    }
+
+    /// Path relative to the sandbox root, as shown to the model
+    fn display_path(&self, path: &Path) -> String {
+        let relative = path.strip_prefix(&self.root).unwrap_or(path);
+        let display = relative.to_string_lossy().replace('\\', "/");
+        if display.is_empty() {
+            ".".to_string()
+        } else {
+            display
+        }
+    }
+
+    async fn read_file(&self, params: &Value) -> std::result::Result<Value, ToolError> {
+        let path = required_str(params, "path")?;
+        let resolved = self.resolve(path, false)?;
+
+        let metadata = tokio::fs::metadata(&resolved)
+            .await
+            .map_err(|e| io_error(path, e))?;
+        if !metadata.is_file() {
+            return Err(ToolError::new(
+                ToolErrorKind::InvalidInput,
+                format!("'{}' is not a file", path),
+            ));
+        }
+        if metadata.len() > MAX_READ_BYTES {
+            return Err(ToolError::new(
+                ToolErrorKind::InvalidInput,
+                format!(
+                    "'{}' is {} bytes, larger than the {} byte limit",
+                    path,
+                    metadata.len(),
+                    MAX_READ_BYTES
+                ),
+            ));
+        }
+
+        let bytes = tokio::fs::read(&resolved)
+            .await
+            .map_err(|e| io_error(path, e))?;
+        let size = bytes.len();
+        let (encoding, content) = match String::from_utf8(bytes) {
+            Ok(text) => ("text", text),
+            Err(e) => ("base64", general_purpose::STANDARD.encode(e.into_bytes())),
+        };
+
+        Ok(json!({
+            "path": self.display_path(&resolved),
+            "encoding": encoding,
+            "content": content,
+            "size": size,
+        }))
+    }
+
+    async fn write_file(&self, params: &Value) -> std::result::Result<Value, ToolError> {
+        let path = required_str(params, "path")?;
+        let content = required_str(params, "content")?;
+        let bytes = match params["encoding"].as_str().unwrap_or("text") {
+            "text" => content.as_bytes().to_vec(),
+            "base64" => general_purpose::STANDARD.decode(content).map_err(|e| {
+                ToolError::new(
+                    ToolErrorKind::InvalidInput,
+                    format!("Invalid base64 content: {}", e),
+                )
+            })?,
+            other => {
+                return Err(ToolError::new(
+                    ToolErrorKind::InvalidInput,
+                    format!("Unsupported encoding '{}'", other),
+                ));
+            }
+        };
+
+        let resolved = self.resolve(path, true)?;
+        if resolved.is_dir() {
+            return Err(ToolError::new(
+                ToolErrorKind::InvalidInput,
+                format!("'{}' is a directory", path),
+            ));
+        }
+        tokio::fs::write(&resolved, &bytes)
+            .await
+            .map_err(|e| io_error(path, e))?;
+
+        Ok(json!({
+            "path": self.display_path(&resolved),
+            "bytes_written": bytes.len(),
+        }))
+    }
+
+    async fn list_dir(&self, params: &Value) -> std::result::Result<Value, ToolError> {
+        let path = params["path"].as_str().unwrap_or(".");
+        let resolved = self.resolve(path, false)?;
+
+        let mut reader = tokio::fs::read_dir(&resolved)
+            .await
+            .map_err(|e| io_error(path, e))?;
+        let mut entries = Vec::new();
+        while let Some(entry) = reader.next_entry().await.map_err(|e| io_error(path, e))? {
+            // Symlinks are reported as such, not followed
+            let file_type = entry.file_type().await.map_err(|e| io_error(path, e))?;
+            let kind = if file_type.is_symlink() {
+                "symlink"
+            } else if file_type.is_dir() {
+                "dir"
+            } else {
+                "file"
+            };
+            let mut item = json!({
+                "name": entry.file_name().to_string_lossy(),
+                "type": kind,
+            });
+            if file_type.is_file() {
+                let metadata = entry.metadata().await.map_err(|e| io_error(path, e))?;
+                item["size"] = json!(metadata.len());
+            }
+            entries.push(item);
+        }
+        entries.sort_by(|a, b| a["name"].as_str().cmp(&b["name"].as_str()));
+
+        Ok(json!({
+            "path": self.display_path(&resolved),
+            "entries": entries,
+        }))
+    }
+}
+
+fn required_str<'a>(params: &'a Value, name: &str) -> std::result::Result<&'a str, ToolError> {
+    params[name].as_str().ok_or_else(|| {
+        ToolError::new(
+            ToolErrorKind::InvalidInput,
+            format!("Missing required parameter: {}", name),
+        )
+    })
+}
+
+fn escape_error(path: &str) -> ToolError {
+    ToolError::new(
+        ToolErrorKind::InvalidInput,
+        format!("Path '{}' is outside the sandbox", path),
+    )
+}
+
+fn io_error(path: &str, e: std::io::Error) -> ToolError {
+    let kind = match e.kind() {
+        ErrorKind::NotFound => ToolErrorKind::NotFound,
+        ErrorKind::PermissionDenied => ToolErrorKind::InvalidInput,
+        _ => ToolErrorKind::Internal,
+    };
+    ToolError::new(kind, format!("'{}': {}", path, e))
+}
+
+#[async_trait]
+impl Tool for FileSystemTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "operation": {
+                    "type": "string",
+                    "description": "Operation to perform",
+                    "enum": ["read_file", "write_file", "list_dir"]
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Path relative to the sandbox root (default for list_dir: \".\")"
+                },
+                "content": {
+                    "type": "string",
+                    "description": "File contents for write_file"
+                },
+                "encoding": {
+                    "type": "string",
+                    "description": "Encoding of 'content' for write_file (default: text)",
+                    "enum": ["text", "base64"]
+                }
+            },
+            "required": ["operation"]
+        })
+    }
+
+    async fn execute(&self, ctx: Arc<dyn ToolContext>, params: Value) -> Result<ToolResponse> {
+        let operation = params["operation"].as_str().unwrap_or_default();
+        tracing::debug!(
+            invocation_id = %ctx.invocation_id(),
+            tool_call_id = %ctx.function_call_id(),
+            operation = %operation,
+            "File system operation"
+        );
+
+        let result = match operation {
+            "read_file" => self.read_file(&params).await,
+            "write_file" => self.write_file(&params).await,
+            "list_dir" => self.list_dir(&params).await,
+            other => Err(ToolError::new(
+                ToolErrorKind::InvalidInput,
+                format!("Unknown operation '{}'", other),
+            )),
+        };
+
+        match result {
+            Ok(result) => Ok(ToolResponse::new(result)),
+            Err(e) => {
+                tracing::warn!("File system operation failed: {}", e);
+                Ok(ToolResponse::error(e.kind, e.message))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::DefaultToolContext;
+
+    fn ctx() -> Arc<dyn ToolContext> {
+        Arc::new(DefaultToolContext::new(
+            "call-1".to_string(),
+            "inv-1".to_string(),
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_in_sandbox_operations() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        let tool = FileSystemTool::new(dir.path()).unwrap();
+
+        let written = tool
+            .execute(
+                ctx(),
+                json!({"operation": "write_file", "path": "sub/notes.txt", "content": "hello"}),
+            )
+            .await
+            .unwrap();
+        assert!(written.error.is_none());
+        assert_eq!(written.result["bytes_written"], 5);
+
+        let read = tool
+            .execute(
+                ctx(),
+                json!({"operation": "read_file", "path": "./sub/notes.txt"}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(read.result["encoding"], "text");
+        assert_eq!(read.result["content"], "hello");
+        assert_eq!(read.result["path"], "sub/notes.txt");
+
+        // Binary round trip through base64
+        let data = general_purpose::STANDARD.encode([0u8, 159, 146, 150]);
+        tool.execute(
+            ctx(),
+            json!({"operation": "write_file", "path": "blob.bin", "content": data, "encoding": "base64"}),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            std::fs::read(dir.path().join("blob.bin")).unwrap(),
+            [0u8, 159, 146, 150]
+        );
+        let read = tool
+            .execute(ctx(), json!({"operation": "read_file", "path": "blob.bin"}))
+            .await
+            .unwrap();
+        assert_eq!(read.result["encoding"], "base64");
+        assert_eq!(read.result["content"], data);
+
+        let listed = tool
+            .execute(ctx(), json!({"operation": "list_dir"}))
+            .await
+            .unwrap();
+        let entries = listed.result["entries"].as_array().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0]["name"], "blob.bin");
+        assert_eq!(entries[0]["size"], 4);
+        assert_eq!(entries[1]["name"], "sub");
+        assert_eq!(entries[1]["type"], "dir");
+    }
+
+    #[tokio::test]
+    async fn test_path_traversal_is_blocked() {
+        let outside = tempfile::tempdir().unwrap();
+        std::fs::write(outside.path().join("secret.txt"), "secret").unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let tool = FileSystemTool::new(dir.path()).unwrap();
+
+        let secret = outside.path().join("secret.txt");
+        let attempts = [
+            json!({"operation": "read_file", "path": "../secret.txt"}),
+            json!({"operation": "read_file", "path": secret.to_str().unwrap()}),
+            json!({"operation": "list_dir", "path": ".."}),
+            json!({"operation": "write_file", "path": "a/../../evil.txt", "content": "x"}),
+        ];
+        for params in attempts {
+            let response = tool.execute(ctx(), params.clone()).await.unwrap();
+            let error = response.error.expect("traversal should fail");
+            assert_eq!(error.kind, ToolErrorKind::InvalidInput, "{}", params);
+            assert!(error.message.contains("outside the sandbox"), "{}", params);
+        }
+        assert!(!outside.path().join("evil.txt").exists());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_symlink_escape_is_blocked() {
+        let outside = tempfile::tempdir().unwrap();
+        std::fs::write(outside.path().join("secret.txt"), "secret").unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        std::os::unix::fs::symlink(outside.path(), dir.path().join("link")).unwrap();
+        std::os::unix::fs::symlink(
+            outside.path().join("secret.txt"),
+            dir.path().join("secret.txt"),
+        )
+        .unwrap();
+        let tool = FileSystemTool::new(dir.path()).unwrap();
+
+        let attempts = [
+            json!({"operation": "read_file", "path": "link/secret.txt"}),
+            json!({"operation": "read_file", "path": "secret.txt"}),
+            json!({"operation": "list_dir", "path": "link"}),
+            json!({"operation": "write_file", "path": "link/evil.txt", "content": "x"}),
+            json!({"operation": "write_file", "path": "secret.txt", "content": "x"}),
+        ];
+        for params in attempts {
+            let response = tool.execute(ctx(), params.clone()).await.unwrap();
+            let error = response.error.expect("symlink escape should fail");
+            assert!(error.message.contains("outside the sandbox"), "{}", params);
+        }
+        assert!(!outside.path().join("evil.txt").exists());
+        assert_eq!(
+            std::fs::read_to_string(outside.path().join("secret.txt")).unwrap(),
+            "secret"
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_dangling_symlink_escape_is_blocked() {
+        let outside = tempfile::tempdir().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        std::os::unix::fs::symlink(outside.path().join("new.txt"), dir.path().join("new.txt"))
+            .unwrap();
+        let tool = FileSystemTool::new(dir.path()).unwrap();
+
+        let response = tool
+            .execute(
+                ctx(),
+                json!({"operation": "write_file", "path": "new.txt", "content": "x"}),
+            )
+            .await
+            .unwrap();
+        let error = response.error.expect("dangling symlink should fail");
+        assert!(error.message.contains("outside the sandbox"));
+        assert!(!outside.path().join("new.txt").exists());
+    }
+
+    #[test]
+    fn test_sandbox_root_must_exist() {
+        assert!(FileSystemTool::new("/definitely/not/a/real/dir").is_err());
+    }
+}
@@ -0,0 +1,195 @@
+//! JSONPath query tool
+
+use async_trait::async_trait;
+use serde_json::{Value, json};
+use serde_json_path::JsonPath;
+use std::sync::Arc;
+use zdk_core::{Result, Tool, ToolContext, ToolError, ToolErrorKind, ToolResponse};
+
+/// Tool that extracts parts of a JSON document with a JSONPath query
+///
+/// Lets the model slice large tool outputs (e.g. API responses) instead of
+/// re-emitting them whole. Queries follow RFC 9535, including filters such
+/// as `$.items[?@.price < 10].name`. The matches are returned as an array in
+/// document order.
+///
+/// ## Example
+///
+/// ```rust
+/// use zdk_tool::builtin::JsonQueryTool;
+/// use std::sync::Arc;
+///
+/// let tool = Arc::new(JsonQueryTool::new());
+/// ```
+pub struct JsonQueryTool {
+    name: String,
+    description: String,
+}
+
+impl JsonQueryTool {
+    pub fn new() -> Self {
+        Self {
+            name: "json_query".to_string(),
+            description: "Extract fields from JSON data with a JSONPath query (e.g. '$.items[*].name' or '$.items[?@.price < 10]'). Returns the matching values.".to_string(),
+        }
+    }
+
+    fn query(params: &Value) -> std::result::Result<Value, ToolError> {
+        let query = params["query"].as_str().ok_or_else(|| {
+            ToolError::new(ToolErrorKind::InvalidInput, "Missing 'query' parameter")
+        })?;
+
+        // Accept JSON passed as a string, as models often do with tool output
+        let parsed;
+        let data = match &params["data"] {
+            Value::String(text) => {
+                parsed = serde_json::from_str::<Value>(text).map_err(|e| {
+                    ToolError::new(
+                        ToolErrorKind::InvalidInput,
+                        format!("'data' is a string but not valid JSON: {}", e),
+                    )
+                })?;
+                &parsed
+            }
+            Value::Null => {
+                return Err(ToolError::new(
+                    ToolErrorKind::InvalidInput,
+                    "Missing 'data' parameter",
+                ));
+            }
+            data => data,
+        };
+
+        let path = JsonPath::parse(query).map_err(|e| {
+            ToolError::new(
+                ToolErrorKind::InvalidInput,
+                format!("Invalid JSONPath query '{}': {}", query, e),
+            )
+        })?;
+        let matches: Vec<Value> = path.query(data).all().into_iter().cloned().collect();
+
+        Ok(json!({
+            "query": query,
+            "count": matches.len(),
+            "matches": matches,
+        }))
+    }
+}
+
+impl Default for JsonQueryTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Tool for JsonQueryTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "data": {
+                    "description": "JSON object or array to query"
+                },
+                "query": {
+                    "type": "string",
+                    "description": "JSONPath expression starting at '$'"
+                }
+            },
+            "required": ["data", "query"]
+        })
+    }
+
+    async fn execute(&self, ctx: Arc<dyn ToolContext>, params: Value) -> Result<ToolResponse> {
+        tracing::debug!(
+            invocation_id = %ctx.invocation_id(),
+            tool_call_id = %ctx.function_call_id(),
+            query = %params["query"],
+            "JSON query"
+        );
+
+        match Self::query(&params) {
+            Ok(result) => Ok(ToolResponse::new(result)),
+            Err(e) => Ok(ToolResponse::error(e.kind, e.message)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::DefaultToolContext;
+
+    fn catalog() -> Value {
+        json!({
+            "store": "corner shop",
+            "items": [
+                {"name": "apple", "price": 3, "tags": ["fruit"]},
+                {"name": "bread", "price": 12, "tags": ["bakery"]},
+                {"name": "cherry", "price": 8, "tags": ["fruit"]}
+            ]
+        })
+    }
+
+    async fn run(data: Value, query: &str) -> ToolResponse {
+        let ctx = Arc::new(DefaultToolContext::new(
+            "call-1".to_string(),
+            "inv-1".to_string(),
+        ));
+        JsonQueryTool::new()
+            .execute(ctx, json!({"data": data, "query": query}))
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_field_extraction() {
+        let response = run(catalog(), "$.store").await;
+        assert!(response.error.is_none());
+        assert_eq!(response.result["matches"], json!(["corner shop"]));
+
+        let response = run(catalog(), "$.items[*].name").await;
+        assert_eq!(response.result["count"], 3);
+        assert_eq!(
+            response.result["matches"],
+            json!(["apple", "bread", "cherry"])
+        );
+
+        // Stringified JSON is accepted too
+        let response = run(Value::String(catalog().to_string()), "$.items[1].price").await;
+        assert_eq!(response.result["matches"], json!([12]));
+    }
+
+    #[tokio::test]
+    async fn test_array_filtering() {
+        let response = run(catalog(), "$.items[?@.price < 10].name").await;
+        assert_eq!(response.result["matches"], json!(["apple", "cherry"]));
+
+        let response = run(catalog(), "$.items[?@.name == 'bread']").await;
+        assert_eq!(response.result["matches"][0]["price"], 12);
+
+        let response = run(catalog(), "$.items[?@.price > 100]").await;
+        assert!(response.error.is_none());
+        assert_eq!(response.result["count"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_query() {
+        let response = run(catalog(), "$.items[?@.price <").await;
+        let error = response.error.unwrap();
+        assert_eq!(error.kind, ToolErrorKind::InvalidInput);
+        assert!(error.message.contains("Invalid JSONPath query"));
+        assert!(response.result["error"].is_string());
+
+        let response = run(Value::String("not json".to_string()), "$").await;
+        assert_eq!(response.error.unwrap().kind, ToolErrorKind::InvalidInput);
+    }
+}
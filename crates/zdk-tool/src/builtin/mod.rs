@@ -2,6 +2,10 @@
 
 pub mod calculator;
 pub mod echo;
+pub mod filesystem;
+pub mod json_query;
 
 pub use calculator::create_calculator_tool;
 pub use echo::create_echo_tool;
+pub use filesystem::FileSystemTool;
+pub use json_query::JsonQueryTool;
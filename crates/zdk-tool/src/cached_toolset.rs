@@ -0,0 +1,144 @@
+//! Caching wrapper for toolsets
+//!
+//! Agents call `Toolset::get_tools` on every invocation. For toolsets whose
+//! tools are expensive to discover (OpenAPI specs, MCP servers) but rarely
+//! change, wrapping them in a [`CachedToolset`] loads the tools once and reuses
+//! them until [`CachedToolset::refresh`] is called.
+
+use async_trait::async_trait;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use zdk_core::{InvocationContext, Result, Tool, Toolset};
+
+/// Toolset wrapper that loads tools once and serves them from cache
+pub struct CachedToolset {
+    inner: Arc<dyn Toolset>,
+    cache: Mutex<Option<Vec<Arc<dyn Tool>>>>,
+}
+
+impl CachedToolset {
+    /// Wrap a toolset so its tools are only loaded on first use
+    pub fn new(inner: Arc<dyn Toolset>) -> Self {
+        Self {
+            inner,
+            cache: Mutex::new(None),
+        }
+    }
+
+    /// Drop the cached tools so the next `get_tools` reloads them
+    pub async fn refresh(&self) {
+        *self.cache.lock().await = None;
+    }
+
+    /// Whether tools are currently cached
+    pub async fn is_cached(&self) -> bool {
+        self.cache.lock().await.is_some()
+    }
+}
+
+#[async_trait]
+impl Toolset for CachedToolset {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn get_tools(&self, ctx: &dyn InvocationContext) -> Result<Vec<Arc<dyn Tool>>> {
+        // Hold the lock while loading so concurrent callers don't load twice
+        let mut cache = self.cache.lock().await;
+        if let Some(tools) = cache.as_ref() {
+            return Ok(tools.clone());
+        }
+
+        let tools = self.inner.get_tools(ctx).await?;
+        tracing::debug!(
+            toolset = %self.inner.name(),
+            count = tools.len(),
+            "Cached toolset tools"
+        );
+        *cache = Some(tools.clone());
+        Ok(tools)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builtin::create_echo_tool;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use zdk_core::{Content, ReadonlyContext};
+
+    struct CountingToolset {
+        loads: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Toolset for CountingToolset {
+        fn name(&self) -> &str {
+            "counting"
+        }
+
+        async fn get_tools(&self, _ctx: &dyn InvocationContext) -> Result<Vec<Arc<dyn Tool>>> {
+            self.loads.fetch_add(1, Ordering::SeqCst);
+            Ok(vec![Arc::new(create_echo_tool()?)])
+        }
+    }
+
+    struct TestContext;
+
+    impl InvocationContext for TestContext {
+        fn invocation_id(&self) -> &str {
+            "inv-1"
+        }
+
+        fn user_content(&self) -> Option<&Content> {
+            None
+        }
+    }
+
+    impl ReadonlyContext for TestContext {
+        fn app_name(&self) -> &str {
+            "test-app"
+        }
+
+        fn user_id(&self) -> &str {
+            "user1"
+        }
+
+        fn session_id(&self) -> &str {
+            "session1"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tools_loaded_once() {
+        let inner = Arc::new(CountingToolset {
+            loads: AtomicUsize::new(0),
+        });
+        let cached = CachedToolset::new(inner.clone());
+
+        let first = cached.get_tools(&TestContext).await.unwrap();
+        let second = cached.get_tools(&TestContext).await.unwrap();
+
+        assert_eq!(inner.loads.load(Ordering::SeqCst), 1);
+        assert_eq!(first.len(), 1);
+        assert_eq!(second[0].name(), first[0].name());
+        assert_eq!(cached.name(), "counting");
+    }
+
+    #[tokio::test]
+    async fn test_refresh_reloads_tools() {
+        let inner = Arc::new(CountingToolset {
+            loads: AtomicUsize::new(0),
+        });
+        let cached = CachedToolset::new(inner.clone());
+
+        cached.get_tools(&TestContext).await.unwrap();
+        assert!(cached.is_cached().await);
+
+        cached.refresh().await;
+        assert!(!cached.is_cached().await);
+
+        cached.get_tools(&TestContext).await.unwrap();
+        assert_eq!(inner.loads.load(Ordering::SeqCst), 2);
+    }
+}
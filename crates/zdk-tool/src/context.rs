@@ -1,10 +1,35 @@
-use zdk_core::ToolContext;
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use zdk_core::{ArtifactPart, ArtifactStore, Error, Result, ToolContext};
+
+/// Session state shared by the tools of one invocation
+pub type SharedState = Arc<RwLock<HashMap<String, Value>>>;
 
 /// Default implementation of ToolContext
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct DefaultToolContext {
     function_call_id: String,
     invocation_id: String,
+    state: SharedState,
+    /// Keys written through this context, to be persisted with the tool's event
+    state_delta: Arc<Mutex<HashMap<String, Value>>>,
+    artifacts: Option<Arc<dyn ArtifactStore>>,
+    escalated: Arc<AtomicBool>,
+}
+
+impl fmt::Debug for DefaultToolContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DefaultToolContext")
+            .field("function_call_id", &self.function_call_id)
+            .field("invocation_id", &self.invocation_id)
+            .field("state", &self.state)
+            .field("has_artifacts", &self.artifacts.is_some())
+            .finish()
+    }
 }
 
 impl DefaultToolContext {
@@ -12,10 +37,46 @@ impl DefaultToolContext {
         Self {
             function_call_id,
             invocation_id,
+            state: SharedState::default(),
+            state_delta: Arc::default(),
+            artifacts: None,
+            escalated: Arc::default(),
         }
     }
+
+    /// Back the context with session state shared across tool calls
+    pub fn with_state(mut self, state: SharedState) -> Self {
+        self.state = state;
+        self
+    }
+
+    /// Let tools save and load artifacts through `artifacts`
+    pub fn with_artifacts(mut self, artifacts: Arc<dyn ArtifactStore>) -> Self {
+        self.artifacts = Some(artifacts);
+        self
+    }
+
+    fn artifact_store(&self, file_name: &str) -> Result<&Arc<dyn ArtifactStore>> {
+        self.artifacts.as_ref().ok_or_else(|| {
+            Error::ArtifactError(format!(
+                "Cannot access '{}': no artifact service configured",
+                file_name
+            ))
+        })
+    }
+
+    /// Take the state writes made since the last call
+    pub fn take_state_delta(&self) -> HashMap<String, Value> {
+        std::mem::take(&mut *self.state_delta.lock().unwrap())
+    }
+
+    /// Whether a tool asked to escalate through this context
+    pub fn escalated(&self) -> bool {
+        self.escalated.load(Ordering::SeqCst)
+    }
 }
 
+#[async_trait]
 impl ToolContext for DefaultToolContext {
     fn function_call_id(&self) -> &str {
         &self.function_call_id
@@ -24,6 +85,37 @@ impl ToolContext for DefaultToolContext {
     fn invocation_id(&self) -> &str {
         &self.invocation_id
     }
+
+    fn state(&self) -> HashMap<String, Value> {
+        self.state.read().unwrap().clone()
+    }
+
+    fn set_state(&self, key: &str, value: Value) {
+        self.state
+            .write()
+            .unwrap()
+            .insert(key.to_string(), value.clone());
+        self.state_delta
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), value);
+    }
+
+    async fn save_artifact(&self, file_name: &str, part: ArtifactPart) -> Result<i64> {
+        self.artifact_store(file_name)?
+            .save_artifact(file_name, part)
+            .await
+    }
+
+    async fn load_artifact(&self, file_name: &str, version: Option<i64>) -> Result<ArtifactPart> {
+        self.artifact_store(file_name)?
+            .load_artifact(file_name, version)
+            .await
+    }
+
+    fn escalate(&self) {
+        self.escalated.store(true, Ordering::SeqCst);
+    }
 }
 
 #[cfg(test)]
@@ -36,5 +128,25 @@ mod tests {
 
         assert_eq!(ctx.function_call_id(), "call-123");
         assert_eq!(ctx.invocation_id(), "inv-456");
+        assert!(!ctx.escalated());
+
+        ctx.escalate();
+        assert!(ctx.escalated());
+    }
+
+    #[test]
+    fn test_state_is_shared_and_tracked() {
+        let state = SharedState::default();
+        let first = DefaultToolContext::new("call-1".to_string(), "inv".to_string())
+            .with_state(state.clone());
+        let second =
+            DefaultToolContext::new("call-2".to_string(), "inv".to_string()).with_state(state);
+
+        first.set_state("draft", serde_json::json!("v1"));
+        assert_eq!(second.state()["draft"], "v1");
+
+        assert_eq!(first.take_state_delta().len(), 1);
+        assert!(first.take_state_delta().is_empty());
+        assert!(second.take_state_delta().is_empty());
     }
 }
@@ -160,9 +160,7 @@ mod tests {
                 let y = params["y"].as_f64().unwrap_or(0.0);
                 let result = x + y;
 
-                Ok(ToolResponse {
-                    result: serde_json::json!({"sum": result}),
-                })
+                Ok(ToolResponse::new(serde_json::json!({"sum": result})))
             })
             .build()
             .unwrap();
@@ -3,16 +3,21 @@
 //! This crate provides the tool execution framework, including:
 //! - Tool trait and utilities
 //! - Function tools with automatic schema generation
-//! - Built-in tools (calculator, search, etc.)
+//! - Built-in tools (calculator, sandboxed file system, etc.)
 //! - Tool context management
+//! - Toolset caching
 
 pub mod builtin;
+pub mod cached_toolset;
 pub mod context;
 pub mod function_tool;
+#[doc(hidden)]
+pub mod macro_support;
 pub mod schema;
 
 // Re-exports
-pub use context::DefaultToolContext;
+pub use cached_toolset::CachedToolset;
+pub use context::{DefaultToolContext, SharedState};
 pub use function_tool::FunctionTool;
 pub use schema::{ToolSchema, generate_schema};
 
@@ -0,0 +1,39 @@
+//! Runtime helpers used by code generated from the `zdk-macros` crate
+//!
+//! Not part of the public API; signatures may change without notice.
+
+pub use async_trait::async_trait;
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use zdk_core::{Error, Result};
+
+/// Extract and deserialize a required argument from the tool parameters
+pub fn required_param<T: DeserializeOwned>(params: &Value, tool: &str, name: &str) -> Result<T> {
+    match params.get(name) {
+        None | Some(Value::Null) => Err(Error::ToolFailed {
+            tool: tool.to_string(),
+            source: anyhow::anyhow!("Missing required parameter '{}'", name),
+        }),
+        Some(value) => deserialize(value, tool, name),
+    }
+}
+
+/// Extract and deserialize an optional argument, treating `null` as absent
+pub fn optional_param<T: DeserializeOwned>(
+    params: &Value,
+    tool: &str,
+    name: &str,
+) -> Result<Option<T>> {
+    match params.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => deserialize(value, tool, name).map(Some),
+    }
+}
+
+fn deserialize<T: DeserializeOwned>(value: &Value, tool: &str, name: &str) -> Result<T> {
+    T::deserialize(value).map_err(|e| Error::ToolFailed {
+        tool: tool.to_string(),
+        source: anyhow::anyhow!("Invalid value for parameter '{}': {}", name, e),
+    })
+}
@@ -38,6 +38,22 @@ impl ToolSchema {
         self
     }
 
+    /// Add an array property whose elements match the `items` schema
+    pub fn array_property(
+        mut self,
+        name: impl Into<String>,
+        items: Value,
+        description: impl Into<String>,
+    ) -> Self {
+        let mut prop = serde_json::Map::new();
+        prop.insert("type".to_string(), Value::String("array".to_string()));
+        prop.insert("items".to_string(), items);
+        prop.insert("description".to_string(), Value::String(description.into()));
+
+        self.properties.insert(name.into(), Value::Object(prop));
+        self
+    }
+
     pub fn required(mut self, name: impl Into<String>) -> Self {
         self.required.push(name.into());
         self
@@ -96,4 +112,19 @@ mod tests {
             Value::Array(vec![Value::String("expression".to_string())])
         );
     }
+
+    #[test]
+    fn test_array_property_has_items() {
+        let schema = ToolSchema::new()
+            .array_property(
+                "tags",
+                serde_json::json!({"type": "string"}),
+                "Tags to apply",
+            )
+            .build();
+
+        assert_eq!(schema["properties"]["tags"]["type"], "array");
+        assert_eq!(schema["properties"]["tags"]["items"]["type"], "string");
+        assert_eq!(schema["properties"]["tags"]["description"], "Tags to apply");
+    }
 }
@@ -139,13 +139,11 @@ impl Tool for GeminiGoogleSearchTool {
         //
         // If this is called, it means the tool was treated as a regular function call
         // instead of being passed to Gemini as a built-in tool.
-        Ok(ToolResponse {
-            result: json!({
-                "error": "This is a Gemini built-in tool that must be configured at the API level, not executed locally.",
-                "hint": "Ensure you're using a Gemini provider that supports built-in tools.",
-                "tool_type": "google_search"
-            }),
-        })
+        Ok(ToolResponse::new(json!({
+            "error": "This is a Gemini built-in tool that must be configured at the API level, not executed locally.",
+            "hint": "Ensure you're using a Gemini provider that supports built-in tools.",
+            "tool_type": "google_search"
+        })))
     }
 }
 
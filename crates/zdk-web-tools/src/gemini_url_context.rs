@@ -160,13 +160,11 @@ impl Tool for GeminiUrlContextTool {
         //
         // If this is called, it means the tool was treated as a regular function call
         // instead of being passed to Gemini as a built-in tool.
-        Ok(ToolResponse {
-            result: json!({
-                "error": "This is a Gemini built-in tool that must be configured at the API level, not executed locally.",
-                "hint": "Ensure you're using a Gemini provider that supports built-in tools.",
-                "tool_type": "url_context"
-            }),
-        })
+        Ok(ToolResponse::new(json!({
+            "error": "This is a Gemini built-in tool that must be configured at the API level, not executed locally.",
+            "hint": "Ensure you're using a Gemini provider that supports built-in tools.",
+            "tool_type": "url_context"
+        })))
     }
 }
 
@@ -0,0 +1,352 @@
+//! General-purpose HTTP request tool for calling JSON APIs
+
+use async_trait::async_trait;
+use reqwest::Method;
+use reqwest::header::{CONTENT_TYPE, HeaderMap, HeaderName, HeaderValue};
+use serde_json::{Map, Value, json};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, warn};
+use zdk_core::{Result as ZResult, Tool, ToolContext, ToolError, ToolErrorKind, ToolResponse};
+
+/// Largest response body returned to the model
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// HTTP request tool
+///
+/// Sends an arbitrary HTTP request and returns the status, response headers and
+/// body. JSON responses are parsed; anything else is returned as text.
+///
+/// ## 🔑 API Keys Required
+///
+/// **✅ ZERO API keys needed!** Credentials for the APIs being called are
+/// passed by the agent as request headers.
+///
+/// ## Security
+///
+/// Without an allowlist the agent can reach any host the process can,
+/// including internal services. Restrict it with
+/// [`with_allowed_hosts`](Self::with_allowed_hosts). Redirects are never
+/// followed, so a redirect can't lead outside the allowlist; the `3xx`
+/// response is returned as is.
+///
+/// ## Example
+///
+/// ```rust,no_run
+/// use zdk_web_tools::HttpRequestTool;
+/// use std::sync::Arc;
+///
+/// let tool = Arc::new(
+///     HttpRequestTool::new()
+///         .unwrap()
+///         .with_allowed_hosts(["api.example.com"]),
+/// );
+/// ```
+pub struct HttpRequestTool {
+    name: String,
+    description: String,
+    client: reqwest::Client,
+    /// Hosts (optionally `host:port`) the tool may call; `None` allows all
+    allowed_hosts: Option<Vec<String>>,
+}
+
+impl HttpRequestTool {
+    /// Create a new HTTP request tool with a 30 second timeout
+    pub fn new() -> anyhow::Result<Self> {
+        Self::with_timeout(Duration::from_secs(30))
+    }
+
+    /// Create with a custom request timeout
+    pub fn with_timeout(timeout: Duration) -> anyhow::Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .redirect(reqwest::redirect::Policy::none())
+            .build()?;
+
+        Ok(Self {
+            name: "http_request".to_string(),
+            description: "Send an HTTP request to a URL, typically a JSON API. Supports any method, custom headers and a string or JSON body. Returns the status code, response headers and the response body (parsed when it is JSON).".to_string(),
+            client,
+            allowed_hosts: None,
+        })
+    }
+
+    /// Only allow requests to these hosts
+    ///
+    /// Entries match the URL host case-insensitively. An entry with a port
+    /// (`localhost:8080`) only matches that port.
+    pub fn with_allowed_hosts<I, S>(mut self, hosts: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allowed_hosts = Some(
+            hosts
+                .into_iter()
+                .map(|host| host.into().to_ascii_lowercase())
+                .collect(),
+        );
+        self
+    }
+
+    fn is_allowed(&self, url: &url::Url) -> bool {
+        let Some(allowed) = &self.allowed_hosts else {
+            return true;
+        };
+        let Some(host) = url.host_str().map(str::to_ascii_lowercase) else {
+            return false;
+        };
+        let host_port = url
+            .port_or_known_default()
+            .map(|port| format!("{}:{}", host, port));
+
+        allowed
+            .iter()
+            .any(|entry| *entry == host || Some(entry) == host_port.as_ref())
+    }
+
+    /// Send the request described by `params` and describe the response
+    async fn send(&self, params: &Value) -> Result<Value, ToolError> {
+        let invalid = |message: String| ToolError::new(ToolErrorKind::InvalidInput, message);
+
+        let url = params["url"]
+            .as_str()
+            .ok_or_else(|| invalid("Missing required parameter: url".to_string()))?;
+        let parsed_url =
+            url::Url::parse(url).map_err(|e| invalid(format!("Invalid URL '{}': {}", url, e)))?;
+        if !matches!(parsed_url.scheme(), "http" | "https") {
+            return Err(invalid(format!(
+                "Unsupported URL scheme '{}'",
+                parsed_url.scheme()
+            )));
+        }
+        if !self.is_allowed(&parsed_url) {
+            return Err(invalid(format!(
+                "Host '{}' is not in the allowed hosts list",
+                parsed_url.host_str().unwrap_or_default()
+            )));
+        }
+
+        let method = params["method"].as_str().unwrap_or("GET").to_uppercase();
+        let method = Method::from_bytes(method.as_bytes())
+            .map_err(|_| invalid(format!("Invalid HTTP method '{}'", method)))?;
+
+        let mut headers = HeaderMap::new();
+        if let Some(object) = params["headers"].as_object() {
+            for (name, value) in object {
+                let value = match value {
+                    Value::String(value) => value.clone(),
+                    other => other.to_string(),
+                };
+                let name = HeaderName::from_bytes(name.as_bytes())
+                    .map_err(|_| invalid(format!("Invalid header name '{}'", name)))?;
+                let value = HeaderValue::from_str(&value)
+                    .map_err(|_| invalid(format!("Invalid value for header '{}'", name)))?;
+                headers.insert(name, value);
+            }
+        }
+
+        let body = match &params["body"] {
+            Value::Null => None,
+            Value::String(text) => Some(text.clone()),
+            json_body => {
+                headers
+                    .entry(CONTENT_TYPE)
+                    .or_insert(HeaderValue::from_static("application/json"));
+                Some(json_body.to_string())
+            }
+        };
+
+        debug!("Sending {} {}", method, parsed_url);
+        let mut request = self.client.request(method, parsed_url).headers(headers);
+        if let Some(body) = body {
+            request = request.body(body);
+        }
+        let mut response = request.send().await.map_err(|e| {
+            ToolError::new(
+                ToolErrorKind::Network,
+                format!("Failed to send request: {}", e),
+            )
+        })?;
+
+        let status = response.status().as_u16();
+        let response_headers: Map<String, Value> = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), json!(value.to_str().ok()?))))
+            .collect();
+        let is_json = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.contains("json"));
+
+        // Stop downloading once the limit is reached
+        let mut bytes = Vec::new();
+        let mut truncated = false;
+        while let Some(chunk) = response.chunk().await.map_err(|e| {
+            ToolError::new(
+                ToolErrorKind::Network,
+                format!("Failed to read response body: {}", e),
+            )
+        })? {
+            if bytes.len() + chunk.len() > MAX_BODY_BYTES {
+                bytes.extend_from_slice(&chunk[..MAX_BODY_BYTES - bytes.len()]);
+                truncated = true;
+                break;
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+        let bytes = bytes.as_slice();
+
+        let text = || json!(String::from_utf8_lossy(bytes));
+        let body = if is_json && !truncated {
+            serde_json::from_slice(bytes).unwrap_or_else(|_| text())
+        } else {
+            text()
+        };
+
+        let mut result = json!({
+            "status": status,
+            "headers": response_headers,
+            "body": body,
+        });
+        if truncated {
+            result["truncated"] = json!(true);
+        }
+        Ok(result)
+    }
+}
+
+#[async_trait]
+impl Tool for HttpRequestTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "url": {
+                    "type": "string",
+                    "description": "The URL to send the request to"
+                },
+                "method": {
+                    "type": "string",
+                    "description": "HTTP method (default: GET)",
+                    "enum": ["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS"]
+                },
+                "headers": {
+                    "type": "object",
+                    "description": "Request headers as name/value pairs",
+                    "additionalProperties": { "type": "string" }
+                },
+                "body": {
+                    "description": "Request body: a string is sent as is, an object or array is sent as JSON"
+                }
+            },
+            "required": ["url"]
+        })
+    }
+
+    async fn execute(&self, _ctx: Arc<dyn ToolContext>, params: Value) -> ZResult<ToolResponse> {
+        match self.send(&params).await {
+            Ok(result) => Ok(ToolResponse::new(result)),
+            Err(e) => {
+                warn!("HTTP request failed: {}", e);
+                Ok(ToolResponse::error(e.kind, e.message))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_get_json() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/users/1")
+            .match_header("authorization", "Bearer token")
+            .with_header("content-type", "application/json")
+            .with_header("x-request-id", "abc")
+            .with_body(r#"{"id": 1, "name": "Ada"}"#)
+            .create_async()
+            .await;
+
+        let result = HttpRequestTool::new()
+            .unwrap()
+            .send(&json!({
+                "url": format!("{}/users/1", server.url()),
+                "headers": { "Authorization": "Bearer token" },
+            }))
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(result["status"], 200);
+        assert_eq!(result["headers"]["x-request-id"], "abc");
+        assert_eq!(result["body"], json!({"id": 1, "name": "Ada"}));
+    }
+
+    #[tokio::test]
+    async fn test_post_json_body() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/users")
+            .match_header("content-type", "application/json")
+            .match_body(mockito::Matcher::Json(json!({"name": "Grace"})))
+            .with_status(201)
+            .with_body("created")
+            .create_async()
+            .await;
+
+        let result = HttpRequestTool::new()
+            .unwrap()
+            .send(&json!({
+                "url": format!("{}/users", server.url()),
+                "method": "post",
+                "body": { "name": "Grace" },
+            }))
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(result["status"], 201);
+        assert_eq!(result["body"], "created");
+    }
+
+    #[tokio::test]
+    async fn test_allowed_hosts() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/").with_body("ok").create_async().await;
+        let url = server.url();
+        let host_port = url.trim_start_matches("http://").to_string();
+
+        let allowed = HttpRequestTool::new()
+            .unwrap()
+            .with_allowed_hosts([host_port]);
+        assert!(allowed.send(&json!({ "url": url })).await.is_ok());
+
+        let denied = HttpRequestTool::new()
+            .unwrap()
+            .with_allowed_hosts(["api.example.com"]);
+        let err = denied.send(&json!({ "url": url })).await.unwrap_err();
+        assert_eq!(err.kind, ToolErrorKind::InvalidInput);
+        assert!(err.message.contains("not in the allowed hosts list"));
+
+        // Non-HTTP schemes are always rejected
+        let err = allowed
+            .send(&json!({ "url": "file:///etc/passwd" }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind, ToolErrorKind::InvalidInput);
+    }
+}
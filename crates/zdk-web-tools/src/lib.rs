@@ -20,6 +20,7 @@
 //! **✅ ZERO API keys needed!**
 //!
 //! - **WebScraperTool** - No keys required, fetches and parses HTML directly
+//! - **HttpRequestTool** - No keys required, calls HTTP/JSON APIs directly
 //!
 //! **Requirements**:
 //! - Internet connection
@@ -69,8 +70,17 @@
 //! - ✅ CSS selector support for targeted extraction
 //! - ✅ Link extraction
 //! - ✅ Automatic text cleaning
+//! - ✅ Optional robots.txt compliance
 //! - ✅ Works with all models
 //!
+//! ### HttpRequestTool
+//!
+//! Sends arbitrary HTTP requests, typically to JSON APIs. Works with any model.
+//!
+//! - ✅ Any method, custom headers, string or JSON body
+//! - ✅ Returns status, headers and parsed JSON body
+//! - ✅ Host allowlist to keep agents away from internal endpoints
+//!
 //! ## Future Extensions
 //!
 //! This crate currently focuses on Gemini's built-in capabilities. Future versions may add:
@@ -82,11 +92,14 @@
 
 mod gemini_google_search;
 mod gemini_url_context;
+mod http_request;
+mod robots;
 mod web_scraper;
 
 pub use gemini_google_search::GeminiGoogleSearchTool;
 pub use gemini_url_context::GeminiUrlContextTool;
-pub use web_scraper::WebScraperTool;
+pub use http_request::HttpRequestTool;
+pub use web_scraper::{WebScraperConfig, WebScraperTool};
 
 /// Result type for web tools
 pub type Result<T> = std::result::Result<T, anyhow::Error>;
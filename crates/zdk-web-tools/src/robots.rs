@@ -0,0 +1,170 @@
+//! Minimal robots.txt parsing for the web scraper
+
+/// Allow and disallow rules that apply to one user agent
+#[derive(Debug, Default)]
+pub(crate) struct RobotsRules {
+    /// `(allow, pattern)` pairs in file order
+    rules: Vec<(bool, String)>,
+}
+
+impl RobotsRules {
+    /// Rules that allow everything, used when a site has no usable robots.txt
+    pub(crate) fn allow_all() -> Self {
+        Self::default()
+    }
+
+    /// Parse `robots.txt`, keeping the group that best matches `user_agent`
+    ///
+    /// A group applies when its `User-agent` token appears (case-insensitively)
+    /// in `user_agent`. The longest matching token wins, and `*` is used when no
+    /// named group matches.
+    pub(crate) fn parse(robots_txt: &str, user_agent: &str) -> Self {
+        let user_agent = user_agent.to_ascii_lowercase();
+
+        // (token length, rules) for the best group seen so far; `*` counts as length 0
+        let mut best: Option<(usize, Vec<(bool, String)>)> = None;
+
+        let mut agents: Vec<String> = Vec::new();
+        let mut rules: Vec<(bool, String)> = Vec::new();
+        let mut in_rules = false;
+
+        let mut finish_group = |agents: &[String], rules: &[(bool, String)]| {
+            for agent in agents {
+                let score = if agent == "*" {
+                    Some(0)
+                } else if user_agent.contains(agent.as_str()) {
+                    Some(agent.len())
+                } else {
+                    None
+                };
+                if let Some(score) = score
+                    && best
+                        .as_ref()
+                        .is_none_or(|(best_score, _)| score > *best_score)
+                {
+                    best = Some((score, rules.to_vec()));
+                }
+            }
+        };
+
+        for line in robots_txt.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+
+            match key.trim().to_ascii_lowercase().as_str() {
+                "user-agent" => {
+                    // A user-agent line after rules starts a new group
+                    if in_rules {
+                        finish_group(&agents, &rules);
+                        agents.clear();
+                        rules.clear();
+                        in_rules = false;
+                    }
+                    agents.push(value.to_ascii_lowercase());
+                }
+                "allow" | "disallow" => {
+                    in_rules = true;
+                    // An empty Disallow allows everything, so it adds no rule
+                    if !value.is_empty() {
+                        rules.push((key.trim().eq_ignore_ascii_case("allow"), value.to_string()));
+                    }
+                }
+                _ => {}
+            }
+        }
+        finish_group(&agents, &rules);
+
+        Self {
+            rules: best.map(|(_, rules)| rules).unwrap_or_default(),
+        }
+    }
+
+    /// Whether `path` (including any query string) may be fetched
+    ///
+    /// The longest matching pattern decides; on a tie `Allow` wins.
+    pub(crate) fn is_allowed(&self, path: &str) -> bool {
+        self.rules
+            .iter()
+            .filter(|(_, pattern)| pattern_matches(pattern, path))
+            .max_by_key(|(allow, pattern)| (pattern.len(), *allow))
+            .is_none_or(|(allow, _)| *allow)
+    }
+}
+
+/// Match a robots.txt path pattern, supporting `*` wildcards and a trailing `$` anchor
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+
+    let mut pieces = pattern.split('*');
+    let first = pieces.next().unwrap_or_default();
+    let Some(mut rest) = path.strip_prefix(first) else {
+        return false;
+    };
+
+    let pieces: Vec<&str> = pieces.collect();
+    for (i, piece) in pieces.iter().enumerate() {
+        // The last piece of an anchored pattern has to sit at the very end
+        if anchored && i == pieces.len() - 1 {
+            return rest.ends_with(piece);
+        }
+        match rest.find(piece) {
+            Some(pos) => rest = &rest[pos + piece.len()..],
+            None => return false,
+        }
+    }
+
+    !anchored || rest.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROBOTS: &str = "
+# Example rules
+User-agent: *
+Disallow: /private/
+Allow: /private/public.html
+
+User-agent: BadBot
+Disallow: /
+
+User-agent: ZDK-Web-Tools
+Disallow: /no-zdk
+Disallow: /*.pdf$
+";
+
+    #[test]
+    fn test_wildcard_group() {
+        let rules = RobotsRules::parse(ROBOTS, "SomeCrawler/1.0");
+        assert!(rules.is_allowed("/"));
+        assert!(!rules.is_allowed("/private/secret.html"));
+        assert!(rules.is_allowed("/private/public.html"));
+        assert!(rules.is_allowed("/no-zdk"));
+    }
+
+    #[test]
+    fn test_named_group_overrides_wildcard() {
+        let rules = RobotsRules::parse(ROBOTS, "Mozilla/5.0 (compatible; ZDK-Web-Tools/0.1.0)");
+        assert!(!rules.is_allowed("/no-zdk/page"));
+        assert!(!rules.is_allowed("/docs/report.pdf"));
+        assert!(rules.is_allowed("/docs/report.pdf?download=1"));
+        assert!(rules.is_allowed("/private/secret.html"));
+
+        let rules = RobotsRules::parse(ROBOTS, "BadBot/2.0");
+        assert!(!rules.is_allowed("/anything"));
+    }
+
+    #[test]
+    fn test_empty_or_missing_rules_allow_everything() {
+        assert!(RobotsRules::parse("", "ZDK").is_allowed("/any"));
+        assert!(RobotsRules::parse("User-agent: *\nDisallow:", "ZDK").is_allowed("/any"));
+        assert!(RobotsRules::allow_all().is_allowed("/any"));
+    }
+}
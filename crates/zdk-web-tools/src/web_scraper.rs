@@ -2,12 +2,22 @@
 
 use anyhow::anyhow;
 use async_trait::async_trait;
-use scraper::{Html, Selector};
-use serde_json::{Value, json};
-use std::sync::Arc;
-use std::time::Duration;
+use encoding_rs::{Encoding, UTF_8};
+use scraper::{ElementRef, Html, Selector};
+use serde_json::{Map, Value, json};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tracing::{debug, warn};
-use zdk_core::{Result as ZResult, Tool, ToolContext, ToolResponse};
+use zdk_core::{Result as ZResult, Tool, ToolContext, ToolError, ToolErrorKind, ToolResponse};
+
+use crate::robots::RobotsRules;
+
+/// How long fetched robots.txt rules are reused before being fetched again
+const ROBOTS_CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Most origins whose robots.txt rules are cached at once
+const ROBOTS_CACHE_MAX_ORIGINS: usize = 1024;
 
 /// Web Scraper tool
 ///
@@ -24,6 +34,7 @@ use zdk_core::{Result as ZResult, Tool, ToolContext, ToolResponse};
 /// - Fetch raw HTML content and extract text
 /// - Extract specific elements using CSS selectors
 /// - Extract all links from pages
+/// - Extract `<table>` contents as row objects and `<meta>`/OpenGraph tags
 /// - Automatic text cleaning
 /// - Works with any LLM model (Gemini, Claude, GPT, etc.)
 ///
@@ -42,67 +53,286 @@ pub struct WebScraperTool {
     name: String,
     description: String,
     client: reqwest::Client,
+    max_body_bytes: usize,
+    max_output_chars: usize,
+    max_links: usize,
+    user_agent: String,
+    max_redirects: usize,
+    respect_robots_txt: bool,
+    /// Parsed robots.txt rules and when they were fetched, keyed by origin
+    /// (scheme, host and port)
+    robots_cache: Mutex<HashMap<String, (Instant, Arc<RobotsRules>)>>,
+}
+
+/// HTTP settings for [`WebScraperTool`]
+#[derive(Debug, Clone)]
+pub struct WebScraperConfig {
+    /// Total time allowed for a request, including reading the body
+    pub timeout: Duration,
+    /// Largest response body that will be downloaded and parsed
+    pub max_body_bytes: usize,
+    /// Sent with every request and matched against robots.txt groups
+    pub user_agent: String,
+    /// Most redirects followed for one request before giving up
+    pub max_redirects: usize,
+    /// Fetch each host's robots.txt and refuse paths it disallows, including
+    /// every URL a request is redirected to
+    pub respect_robots_txt: bool,
+    /// Longest page text returned to the model, in characters (0 = no limit)
+    pub max_output_chars: usize,
+    /// Most links returned when `extract_links` is set (0 = no limit)
+    pub max_links: usize,
+}
+
+impl Default for WebScraperConfig {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(30),
+            max_body_bytes: 10 * 1024 * 1024,
+            user_agent: "Mozilla/5.0 (compatible; ZDK-Web-Tools/0.1.0)".to_string(),
+            max_redirects: 10,
+            respect_robots_txt: false,
+            max_output_chars: 5000,
+            max_links: 0,
+        }
+    }
 }
 
 impl WebScraperTool {
     /// Create a new Web Scraper tool with default configuration
     pub fn new() -> anyhow::Result<Self> {
-        let client = reqwest::Client::builder()
-            .user_agent("Mozilla/5.0 (compatible; ZDK-Web-Tools/0.1.0)")
-            .timeout(Duration::from_secs(30))
-            .redirect(reqwest::redirect::Policy::limited(10))
-            .build()?;
+        Self::with_full_config(WebScraperConfig::default())
+    }
 
+    /// Create with custom name and description
+    pub fn with_config(name: String, description: String) -> anyhow::Result<Self> {
         Ok(Self {
-            name: "web_scraper".to_string(),
-            description: "Fetch and parse HTML content from web pages. Can extract specific elements using CSS selectors (e.g., 'h1', '.article', '#content'), get all text content, or retrieve all links. Returns structured data from web pages.".to_string(),
-            client,
+            name,
+            description,
+            ..Self::new()?
         })
     }
 
-    /// Create with custom name and description
-    pub fn with_config(name: String, description: String) -> anyhow::Result<Self> {
+    /// Create with custom HTTP settings
+    pub fn with_full_config(config: WebScraperConfig) -> anyhow::Result<Self> {
         let client = reqwest::Client::builder()
-            .user_agent("Mozilla/5.0 (compatible; ZDK-Web-Tools/0.1.0)")
-            .timeout(Duration::from_secs(30))
-            .redirect(reqwest::redirect::Policy::limited(10))
+            .user_agent(config.user_agent.clone())
+            .timeout(config.timeout)
+            // Redirects are followed by `get` so each hop can be checked
+            .redirect(reqwest::redirect::Policy::none())
             .build()?;
 
         Ok(Self {
-            name,
-            description,
+            name: "web_scraper".to_string(),
+            description: "Fetch and parse HTML content from web pages. Can extract specific elements using CSS selectors (e.g., 'h1', '.article', '#content'), get all text content, or retrieve all links. Returns structured data from web pages.".to_string(),
             client,
+            max_body_bytes: config.max_body_bytes,
+            max_output_chars: config.max_output_chars,
+            max_links: config.max_links,
+            user_agent: config.user_agent,
+            max_redirects: config.max_redirects,
+            respect_robots_txt: config.respect_robots_txt,
+            robots_cache: Mutex::new(HashMap::new()),
         })
     }
 
+    /// GET `url`, following up to `max_redirects` redirects
+    ///
+    /// With `check_robots`, the original URL and every redirect target are
+    /// checked against their host's robots.txt before being requested.
+    async fn get(&self, url: url::Url, check_robots: bool) -> Result<reqwest::Response, ToolError> {
+        let mut url = url;
+        for _ in 0..=self.max_redirects {
+            if check_robots {
+                self.check_robots(&url).await?;
+            }
+
+            let response = self.client.get(url.clone()).send().await.map_err(|e| {
+                ToolError::new(
+                    ToolErrorKind::Network,
+                    format!("Failed to fetch URL: {}", e),
+                )
+            })?;
+            if !response.status().is_redirection() {
+                return Ok(response);
+            }
+            let Some(location) = response
+                .headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|value| value.to_str().ok())
+            else {
+                return Ok(response);
+            };
+
+            url = url.join(location).map_err(|e| {
+                ToolError::new(
+                    ToolErrorKind::Network,
+                    format!("Invalid redirect location '{}': {}", location, e),
+                )
+            })?;
+            debug!("Following redirect to {}", url);
+        }
+
+        Err(ToolError::new(
+            ToolErrorKind::Network,
+            format!("Too many redirects (more than {})", self.max_redirects),
+        ))
+    }
+
+    /// Refuse `url` if its host's robots.txt disallows it for our user agent
+    async fn check_robots(&self, url: &url::Url) -> Result<(), ToolError> {
+        let path = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
+        if self.robots_rules(url).await.is_allowed(&path) {
+            return Ok(());
+        }
+
+        Err(ToolError::new(
+            ToolErrorKind::InvalidInput,
+            format!(
+                "Blocked by robots.txt: {} disallows '{}' for user agent '{}'",
+                url.origin().ascii_serialization(),
+                path,
+                self.user_agent
+            ),
+        ))
+    }
+
+    /// robots.txt rules for the host of `url`, cached per origin for
+    /// [`ROBOTS_CACHE_TTL`]
+    async fn robots_rules(&self, url: &url::Url) -> Arc<RobotsRules> {
+        let origin = url.origin().ascii_serialization();
+        if let Some((fetched, rules)) = self.robots_cache.lock().unwrap().get(&origin)
+            && fetched.elapsed() < ROBOTS_CACHE_TTL
+        {
+            return rules.clone();
+        }
+
+        let robots_url = format!("{}/robots.txt", origin);
+        debug!("Fetching robots.txt: {}", robots_url);
+
+        // A missing or unreadable robots.txt places no restrictions
+        let response = match url::Url::parse(&robots_url) {
+            Ok(robots_url) => Box::pin(self.get(robots_url, false)).await,
+            Err(e) => Err(ToolError::new(ToolErrorKind::InvalidInput, e.to_string())),
+        };
+        let rules = match response {
+            Ok(response) if response.status().is_success() => {
+                match self.read_body(response).await {
+                    Ok(body) => RobotsRules::parse(&body, &self.user_agent),
+                    Err(e) => {
+                        warn!("Failed to read {}: {}", robots_url, e);
+                        RobotsRules::allow_all()
+                    }
+                }
+            }
+            Ok(_) => RobotsRules::allow_all(),
+            Err(e) => {
+                warn!("Failed to fetch {}: {}", robots_url, e);
+                RobotsRules::allow_all()
+            }
+        };
+
+        let rules = Arc::new(rules);
+        let mut cache = self.robots_cache.lock().unwrap();
+        cache.retain(|_, (fetched, _)| fetched.elapsed() < ROBOTS_CACHE_TTL);
+        if cache.len() >= ROBOTS_CACHE_MAX_ORIGINS
+            && let Some(oldest) = cache
+                .iter()
+                .min_by_key(|(_, (fetched, _))| *fetched)
+                .map(|(origin, _)| origin.clone())
+        {
+            cache.remove(&oldest);
+        }
+        cache.insert(origin, (Instant::now(), rules.clone()));
+        rules
+    }
+
+    /// Read the response body, giving up as soon as it exceeds `max_body_bytes`
+    ///
+    /// The body is decoded with the charset from the `Content-Type` header,
+    /// falling back to a byte order mark and then UTF-8.
+    async fn read_body(&self, mut response: reqwest::Response) -> Result<String, ToolError> {
+        let too_large = || {
+            ToolError::new(
+                ToolErrorKind::InvalidInput,
+                format!(
+                    "Response body exceeds the {} byte limit",
+                    self.max_body_bytes
+                ),
+            )
+        };
+
+        if let Some(length) = response.content_length()
+            && length > self.max_body_bytes as u64
+        {
+            return Err(too_large());
+        }
+
+        let encoding = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| {
+                value.split(';').skip(1).find_map(|param| {
+                    let (name, charset) = param.split_once('=')?;
+                    if !name.trim().eq_ignore_ascii_case("charset") {
+                        return None;
+                    }
+                    Encoding::for_label(charset.trim().trim_matches('"').as_bytes())
+                })
+            })
+            .unwrap_or(UTF_8);
+
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(|e| {
+            ToolError::new(
+                ToolErrorKind::Network,
+                format!("Failed to read response body: {}", e),
+            )
+        })? {
+            if body.len() + chunk.len() > self.max_body_bytes {
+                return Err(too_large());
+            }
+            body.extend_from_slice(&chunk);
+        }
+
+        let (text, _, _) = encoding.decode(&body);
+        Ok(text.into_owned())
+    }
+
     async fn fetch_and_parse(
         &self,
         url: &str,
         selector: Option<&str>,
-        extract_links: bool,
-    ) -> anyhow::Result<ScrapedContent> {
+        extract: Extract,
+    ) -> Result<ScrapedContent, ToolError> {
         debug!("Fetching URL: {}", url);
 
         // Validate URL
-        let parsed_url =
-            url::Url::parse(url).map_err(|e| anyhow!("Invalid URL '{}': {}", url, e))?;
+        let parsed_url = url::Url::parse(url).map_err(|e| {
+            ToolError::new(
+                ToolErrorKind::InvalidInput,
+                format!("Invalid URL '{}': {}", url, e),
+            )
+        })?;
 
         // Fetch content
         let response = self
-            .client
-            .get(url)
-            .send()
-            .await
-            .map_err(|e| anyhow!("Failed to fetch URL: {}", e))?;
+            .get(parsed_url.clone(), self.respect_robots_txt)
+            .await?;
 
-        if !response.status().is_success() {
-            return Err(anyhow!("HTTP error {}: {}", response.status(), url));
+        let status = response.status();
+        if !status.is_success() {
+            return Err(ToolError::new(
+                status_error_kind(status),
+                format!("HTTP error {}: {}", status, url),
+            ));
         }
 
-        let html = response
-            .text()
-            .await
-            .map_err(|e| anyhow!("Failed to read response body: {}", e))?;
+        let html = self.read_body(response).await?;
 
         // Parse HTML
         let document = Html::parse_document(&html);
@@ -117,8 +347,12 @@ impl WebScraperTool {
         // Extract content based on parameters
         let text = if let Some(css_selector) = selector {
             // Extract specific elements
-            let selector = Selector::parse(css_selector)
-                .map_err(|e| anyhow!("Invalid CSS selector '{}': {:?}", css_selector, e))?;
+            let selector = Selector::parse(css_selector).map_err(|e| {
+                ToolError::new(
+                    ToolErrorKind::InvalidInput,
+                    format!("Invalid CSS selector '{}': {:?}", css_selector, e),
+                )
+            })?;
 
             let elements: Vec<String> = document
                 .select(&selector)
@@ -151,7 +385,7 @@ impl WebScraperTool {
         };
 
         // Extract links if requested
-        let links = if extract_links {
+        let links = if extract.links {
             let link_selector = Selector::parse("a[href]").unwrap();
             let links: Vec<LinkInfo> = document
                 .select(&link_selector)
@@ -171,13 +405,61 @@ impl WebScraperTool {
             None
         };
 
+        let tables = extract.tables.then(|| {
+            document
+                .select(&Selector::parse("table").unwrap())
+                .map(table_rows)
+                .collect()
+        });
+        let metadata = extract.metadata.then(|| page_metadata(&document));
+
         Ok(ScrapedContent {
             url: url.to_string(),
             title,
             text,
             links,
+            tables,
+            metadata,
         })
     }
+
+    /// Build the tool result, truncating text and links to the configured limits
+    fn render(&self, content: ScrapedContent) -> Value {
+        let original_length = content.text.chars().count();
+        let truncated = self.max_output_chars > 0 && original_length > self.max_output_chars;
+        let text = if truncated {
+            content.text.chars().take(self.max_output_chars).collect()
+        } else {
+            content.text
+        };
+
+        let mut result = json!({
+            "url": content.url,
+            "title": content.title,
+            "text": text,
+            "truncated": truncated,
+            "original_length": original_length,
+        });
+
+        if let Some(mut links) = content.links {
+            let link_count = links.len();
+            let links_truncated = self.max_links > 0 && link_count > self.max_links;
+            if links_truncated {
+                links.truncate(self.max_links);
+            }
+            result["links"] = json!(links);
+            result["link_count"] = json!(link_count);
+            result["links_truncated"] = json!(links_truncated);
+        }
+        if let Some(tables) = content.tables {
+            result["tables"] = json!(tables);
+        }
+        if let Some(metadata) = content.metadata {
+            result["metadata"] = json!(metadata);
+        }
+
+        result
+    }
 }
 
 #[async_trait]
@@ -205,6 +487,14 @@ impl Tool for WebScraperTool {
                 "extract_links": {
                     "type": "boolean",
                     "description": "Whether to extract all links from the page (default: false)"
+                },
+                "extract_tables": {
+                    "type": "boolean",
+                    "description": "Whether to return HTML tables as arrays of row objects keyed by column header (default: false)"
+                },
+                "extract_metadata": {
+                    "type": "boolean",
+                    "description": "Whether to return <meta> and OpenGraph tags as a key-value map (default: false)"
                 }
             },
             "required": ["url"]
@@ -218,54 +508,130 @@ impl Tool for WebScraperTool {
             .ok_or_else(|| zdk_core::Error::Other(anyhow!("Missing required parameter: url")))?;
 
         let selector = params["selector"].as_str();
-        let extract_links = params["extract_links"].as_bool().unwrap_or(false);
+        let flag = |name: &str| params[name].as_bool().unwrap_or(false);
+        let extract = Extract {
+            links: flag("extract_links"),
+            tables: flag("extract_tables"),
+            metadata: flag("extract_metadata"),
+        };
 
         // Perform scraping
-        match self.fetch_and_parse(url, selector, extract_links).await {
-            Ok(content) => {
-                let mut result = json!({
-                    "url": content.url,
-                    "title": content.title,
-                });
-
-                // Truncate text to avoid overwhelming LLM (5000 chars max)
-                let truncated = if content.text.len() > 5000 {
-                    format!(
-                        "{}... (truncated from {} chars)",
-                        &content.text[..5000],
-                        content.text.len()
-                    )
-                } else {
-                    content.text
-                };
-                result["text"] = json!(truncated);
-
-                if let Some(links) = content.links {
-                    result["links"] = json!(links);
-                    result["link_count"] = json!(links.len());
-                }
-
-                Ok(ToolResponse { result })
-            }
+        match self.fetch_and_parse(url, selector, extract).await {
+            Ok(content) => Ok(ToolResponse::new(self.render(content))),
             Err(e) => {
                 warn!("Web scraping failed: {}", e);
                 Ok(ToolResponse {
                     result: json!({
-                        "error": format!("Failed to scrape URL: {}", e),
+                        "error": format!("Failed to scrape URL: {}", e.message),
+                        "kind": e.kind,
                         "url": url,
                     }),
+                    error: Some(e),
                 })
             }
         }
     }
 }
 
+/// Classify an unsuccessful HTTP status
+fn status_error_kind(status: reqwest::StatusCode) -> ToolErrorKind {
+    match status.as_u16() {
+        404 | 410 => ToolErrorKind::NotFound,
+        429 => ToolErrorKind::RateLimited,
+        400..=499 => ToolErrorKind::InvalidInput,
+        _ => ToolErrorKind::Internal,
+    }
+}
+
+/// Convert a table into row objects keyed by its header cells
+///
+/// The header is the first row if it consists of `<th>` cells; otherwise
+/// columns are named `column_1`, `column_2`, and so on.
+fn table_rows(table: ElementRef) -> Vec<Map<String, Value>> {
+    // Direct rows and rows of thead/tbody/tfoot, skipping nested tables
+    let mut rows = Vec::new();
+    for child in table.children().filter_map(ElementRef::wrap) {
+        match child.value().name() {
+            "tr" => rows.push(child),
+            "thead" | "tbody" | "tfoot" => rows.extend(
+                child
+                    .children()
+                    .filter_map(ElementRef::wrap)
+                    .filter(|row| row.value().name() == "tr"),
+            ),
+            _ => {}
+        }
+    }
+
+    let cells = |row: ElementRef| -> Vec<(bool, String)> {
+        row.children()
+            .filter_map(ElementRef::wrap)
+            .filter(|cell| matches!(cell.value().name(), "td" | "th"))
+            .map(|cell| {
+                let text = cell.text().collect::<Vec<_>>().join(" ");
+                let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                (cell.value().name() == "th", text)
+            })
+            .collect()
+    };
+
+    let mut rows = rows.into_iter().map(cells).peekable();
+    let headers: Vec<String> = match rows.peek() {
+        Some(first) if !first.is_empty() && first.iter().all(|(is_header, _)| *is_header) => rows
+            .next()
+            .unwrap()
+            .into_iter()
+            .map(|(_, text)| text)
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    rows.filter(|row| !row.is_empty())
+        .map(|row| {
+            row.into_iter()
+                .enumerate()
+                .map(|(i, (_, text))| {
+                    let key = headers
+                        .get(i)
+                        .filter(|header| !header.is_empty())
+                        .cloned()
+                        .unwrap_or_else(|| format!("column_{}", i + 1));
+                    (key, Value::String(text))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Collect `<meta>` tags keyed by `name` or `property` (e.g. `og:title`)
+fn page_metadata(document: &Html) -> BTreeMap<String, String> {
+    let selector = Selector::parse("meta[content]").unwrap();
+    document
+        .select(&selector)
+        .filter_map(|meta| {
+            let attrs = meta.value();
+            let key = attrs.attr("property").or_else(|| attrs.attr("name"))?;
+            Some((key.to_string(), attrs.attr("content")?.to_string()))
+        })
+        .collect()
+}
+
+/// Structured data to extract alongside the page text
+#[derive(Debug, Clone, Copy, Default)]
+struct Extract {
+    links: bool,
+    tables: bool,
+    metadata: bool,
+}
+
 #[derive(Debug)]
 struct ScrapedContent {
     url: String,
     title: Option<String>,
     text: String,
     links: Option<Vec<LinkInfo>>,
+    tables: Option<Vec<Vec<Map<String, Value>>>>,
+    metadata: Option<BTreeMap<String, String>>,
 }
 
 #[derive(Debug, serde::Serialize)]
@@ -354,4 +720,408 @@ mod tests {
         let links: Vec<_> = document.select(&link_selector).collect();
         assert_eq!(links.len(), 2);
     }
+
+    #[tokio::test]
+    async fn test_body_size_limit() {
+        let mut server = mockito::Server::new_async().await;
+        let page = format!("<html><body><p>{}</p></body></html>", "word ".repeat(1000));
+        server
+            .mock("GET", "/big")
+            .with_header("content-type", "text/html")
+            .with_body(&page)
+            .create_async()
+            .await;
+        let url = format!("{}/big", server.url());
+
+        let limited = WebScraperTool::with_full_config(WebScraperConfig {
+            max_body_bytes: 1024,
+            ..Default::default()
+        })
+        .unwrap();
+        let err = limited
+            .fetch_and_parse(&url, None, Extract::default())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("exceeds the 1024 byte limit"));
+
+        // The same page parses under the default limit
+        let content = WebScraperTool::new()
+            .unwrap()
+            .fetch_and_parse(&url, None, Extract::default())
+            .await
+            .unwrap();
+        assert!(content.text.starts_with("word word"));
+    }
+
+    #[tokio::test]
+    async fn test_body_decoded_with_declared_charset() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/latin1")
+            .with_header("content-type", "text/html; charset=ISO-8859-1")
+            .with_body(b"<html><body><p>Un caf\xe9 cr\xe8me, merci</p></body></html>")
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/utf8")
+            .with_header("content-type", "text/html")
+            .with_body("<html><body><p>Un caf\u{e9} cr\u{e8}me, merci</p></body></html>")
+            .create_async()
+            .await;
+
+        let tool = WebScraperTool::new().unwrap();
+        for path in ["latin1", "utf8"] {
+            let content = tool
+                .fetch_and_parse(
+                    &format!("{}/{}", server.url(), path),
+                    None,
+                    Extract::default(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(content.text, "Un caf\u{e9} cr\u{e8}me, merci");
+        }
+    }
+
+    const LINKS: Extract = Extract {
+        links: true,
+        tables: false,
+        metadata: false,
+    };
+
+    #[tokio::test]
+    async fn test_structured_extraction() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/product")
+            .with_header("content-type", "text/html")
+            .with_body(
+                r#"<html>
+                <head>
+                    <title>Widget</title>
+                    <meta name="description" content="A very useful widget">
+                    <meta property="og:title" content="Widget Pro">
+                    <meta property="og:image" content="https://example.com/widget.png">
+                    <meta charset="utf-8">
+                </head>
+                <body>
+                    <table id="prices">
+                        <thead><tr><th>Size</th><th>Price</th></tr></thead>
+                        <tbody>
+                            <tr><td>Small</td><td>$10</td></tr>
+                            <tr><td>Large</td><td>$ 18</td></tr>
+                        </tbody>
+                    </table>
+                    <table><tr><td>a</td><td>b</td></tr></table>
+                </body>
+                </html>"#,
+            )
+            .create_async()
+            .await;
+        let url = format!("{}/product", server.url());
+
+        let tool = WebScraperTool::new().unwrap();
+        let content = tool
+            .fetch_and_parse(
+                &url,
+                None,
+                Extract {
+                    tables: true,
+                    metadata: true,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        let result = tool.render(content);
+
+        assert_eq!(
+            result["tables"],
+            json!([
+                [
+                    {"Size": "Small", "Price": "$10"},
+                    {"Size": "Large", "Price": "$ 18"}
+                ],
+                [{"column_1": "a", "column_2": "b"}]
+            ])
+        );
+        assert_eq!(
+            result["metadata"],
+            json!({
+                "description": "A very useful widget",
+                "og:title": "Widget Pro",
+                "og:image": "https://example.com/widget.png"
+            })
+        );
+        assert!(result.get("links").is_none());
+
+        // Nothing structured is returned unless asked for
+        let result = tool.render(
+            tool.fetch_and_parse(&url, None, Extract::default())
+                .await
+                .unwrap(),
+        );
+        assert!(result.get("tables").is_none());
+        assert!(result.get("metadata").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_output_limits() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/page")
+            .with_header("content-type", "text/html")
+            .with_body(
+                "<html><body><p>The quick brown fox jumps over the lazy dog</p>\
+                 <a href=\"/one\">One</a><a href=\"/two\">Two</a><a href=\"/three\">Three</a>\
+                 </body></html>",
+            )
+            .create_async()
+            .await;
+        let url = format!("{}/page", server.url());
+
+        let limited = WebScraperTool::with_full_config(WebScraperConfig {
+            max_output_chars: 9,
+            max_links: 2,
+            ..Default::default()
+        })
+        .unwrap();
+        let content = limited.fetch_and_parse(&url, None, LINKS).await.unwrap();
+        let original_length = content.text.chars().count();
+        let result = limited.render(content);
+
+        assert_eq!(result["text"], "The quick");
+        assert_eq!(result["truncated"], true);
+        assert_eq!(result["original_length"], original_length);
+        assert_eq!(result["links"].as_array().unwrap().len(), 2);
+        assert_eq!(result["link_count"], 3);
+        assert_eq!(result["links_truncated"], true);
+
+        // 0 disables both limits
+        let unlimited = WebScraperTool::with_full_config(WebScraperConfig {
+            max_output_chars: 0,
+            max_links: 0,
+            ..Default::default()
+        })
+        .unwrap();
+        let result = unlimited.render(unlimited.fetch_and_parse(&url, None, LINKS).await.unwrap());
+
+        assert!(
+            result["text"]
+                .as_str()
+                .unwrap()
+                .contains("jumps over the lazy dog")
+        );
+        assert_eq!(result["truncated"], false);
+        assert_eq!(result["original_length"], original_length);
+        assert_eq!(result["links"].as_array().unwrap().len(), 3);
+        assert_eq!(result["links_truncated"], false);
+    }
+
+    #[tokio::test]
+    async fn test_timeout_is_applied() {
+        // Accept connections but never answer them
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let mut connections = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                connections.push(stream);
+            }
+        });
+
+        let tool = WebScraperTool::with_full_config(WebScraperConfig {
+            timeout: Duration::from_millis(200),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let started = std::time::Instant::now();
+        let result = tool
+            .fetch_and_parse(&format!("http://{}/", addr), None, Extract::default())
+            .await;
+        assert!(result.is_err());
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn test_respects_robots_txt() {
+        let mut server = mockito::Server::new_async().await;
+        let robots = server
+            .mock("GET", "/robots.txt")
+            .with_body("User-agent: *\nDisallow: /\n\nUser-agent: PoliteBot\nDisallow: /private\n")
+            .expect(1)
+            .create_async()
+            .await;
+        for path in ["/public", "/private/page"] {
+            server
+                .mock("GET", path)
+                .with_header("content-type", "text/html")
+                .with_body("<html><body><p>Some page text with enough words.</p></body></html>")
+                .create_async()
+                .await;
+        }
+
+        let tool = WebScraperTool::with_full_config(WebScraperConfig {
+            user_agent: "PoliteBot/1.0".to_string(),
+            respect_robots_txt: true,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let allowed = tool
+            .fetch_and_parse(
+                &format!("{}/public", server.url()),
+                None,
+                Extract::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(allowed.text, "Some page text with enough words.");
+
+        let err = tool
+            .fetch_and_parse(
+                &format!("{}/private/page", server.url()),
+                None,
+                Extract::default(),
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Blocked by robots.txt"));
+        assert!(err.to_string().contains("PoliteBot/1.0"));
+
+        // robots.txt is fetched once per host
+        robots.assert_async().await;
+
+        // Without the option the same path is fetched
+        let ignoring = WebScraperTool::with_full_config(WebScraperConfig {
+            user_agent: "PoliteBot/1.0".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(
+            ignoring
+                .fetch_and_parse(
+                    &format!("{}/private/page", server.url()),
+                    None,
+                    Extract::default()
+                )
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_checks_robots_txt_on_redirects() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/robots.txt")
+            .with_body("User-agent: *\nDisallow: /private\n")
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/public")
+            .with_status(302)
+            .with_header("location", "/private/page")
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/private/page")
+            .with_header("content-type", "text/html")
+            .with_body("<html><body><p>Some page text with enough words.</p></body></html>")
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/loop")
+            .with_status(302)
+            .with_header("location", "/loop")
+            .create_async()
+            .await;
+
+        let url = |path: &str| format!("{}{}", server.url(), path);
+        let tool = WebScraperTool::with_full_config(WebScraperConfig {
+            respect_robots_txt: true,
+            max_redirects: 2,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let err = tool
+            .fetch_and_parse(&url("/public"), None, Extract::default())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Blocked by robots.txt"), "{}", err);
+        assert!(err.to_string().contains("/private/page"), "{}", err);
+
+        let err = tool
+            .fetch_and_parse(&url("/loop"), None, Extract::default())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Too many redirects"), "{}", err);
+
+        // Without the option the redirect is followed
+        let ignoring = WebScraperTool::new().unwrap();
+        let followed = ignoring
+            .fetch_and_parse(&url("/public"), None, Extract::default())
+            .await
+            .unwrap();
+        assert_eq!(followed.text, "Some page text with enough words.");
+    }
+
+    #[tokio::test]
+    async fn test_error_kinds() {
+        let mut server = mockito::Server::new_async().await;
+        for (path, status) in [
+            ("/missing", 404),
+            ("/busy", 429),
+            ("/denied", 403),
+            ("/broken", 500),
+        ] {
+            server
+                .mock("GET", path)
+                .with_status(status)
+                .create_async()
+                .await;
+        }
+        server
+            .mock("GET", "/page")
+            .with_header("content-type", "text/html")
+            .with_body("<html><body><p>Hello</p></body></html>")
+            .create_async()
+            .await;
+
+        let tool = WebScraperTool::new().unwrap();
+        let base = server.url();
+        let cases = [
+            (format!("{}/missing", base), None, ToolErrorKind::NotFound),
+            (format!("{}/busy", base), None, ToolErrorKind::RateLimited),
+            (
+                format!("{}/denied", base),
+                None,
+                ToolErrorKind::InvalidInput,
+            ),
+            (format!("{}/broken", base), None, ToolErrorKind::Internal),
+            (
+                format!("{}/page", base),
+                Some("[["),
+                ToolErrorKind::InvalidInput,
+            ),
+            ("not a url".to_string(), None, ToolErrorKind::InvalidInput),
+            // Nothing listens on the discard port locally
+            (
+                "http://127.0.0.1:9/".to_string(),
+                None,
+                ToolErrorKind::Network,
+            ),
+        ];
+        for (url, selector, expected) in cases {
+            let err = tool
+                .fetch_and_parse(&url, selector, Extract::default())
+                .await
+                .unwrap_err();
+            assert_eq!(err.kind, expected, "{}", url);
+        }
+    }
 }
@@ -269,7 +269,6 @@ where
     S: futures::Stream<Item = Result<zdk_core::Event, zdk_core::Error>> + Unpin,
 {
     use futures::StreamExt;
-    use zdk_core::Part;
 
     let mut response = String::new();
 
@@ -277,11 +276,7 @@ where
         match result {
             Ok(event) => {
                 if let Some(content) = &event.content {
-                    for part in &content.parts {
-                        if let Part::Text { text } = part {
-                            response.push_str(text);
-                        }
-                    }
+                    response.push_str(&content.text());
                 }
             }
             Err(e) => {
@@ -308,7 +303,6 @@ where
     S: futures::Stream<Item = Result<zdk_core::Event, zdk_core::Error>> + Unpin,
 {
     use futures::StreamExt;
-    use zdk_core::Part;
 
     let mut response = String::new();
 
@@ -316,13 +310,10 @@ where
         match result {
             Ok(event) => {
                 if let Some(content) = &event.content {
-                    for part in &content.parts {
-                        if let Part::Text { text } = part {
-                            print!("{}", text);
-                            std::io::Write::flush(&mut std::io::stdout()).ok();
-                            response.push_str(text);
-                        }
-                    }
+                    let text = content.text();
+                    print!("{}", text);
+                    std::io::Write::flush(&mut std::io::stdout()).ok();
+                    response.push_str(&text);
                 }
                 if event.is_final_response() {
                     println!();
@@ -349,16 +340,13 @@ where
 /// }
 /// ```
 pub fn tool_was_called(events: &[zdk_core::Event], tool_name: &str) -> bool {
-    use zdk_core::Part;
-
     events.iter().any(|event| {
-        if let Some(content) = &event.content {
-            content.parts.iter().any(|part| {
-                matches!(part, Part::FunctionCall { function_call } if function_call.name == tool_name)
-            })
-        } else {
-            false
-        }
+        event.content.as_ref().is_some_and(|content| {
+            content
+                .function_calls()
+                .iter()
+                .any(|call| call.name == tool_name)
+        })
     })
 }
 
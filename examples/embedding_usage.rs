@@ -99,11 +99,8 @@ async fn main() -> anyhow::Result<()> {
     let mut similarities: Vec<(usize, f32)> = doc_embeddings
         .iter()
         .enumerate()
-        .map(|(i, emb)| {
-            let sim = cosine_similarity(&query_embedding.vector, &emb.vector);
-            (i, sim)
-        })
-        .collect();
+        .map(|(i, emb)| Ok((i, query_embedding.cosine_similarity(emb)?)))
+        .collect::<zdk_core::Result<_>>()?;
 
     // Sort by similarity (descending)
     similarities.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
@@ -138,7 +135,7 @@ async fn main() -> anyhow::Result<()> {
     println!("\nProcessing {} documents...", large_corpus.len());
 
     let texts: Vec<String> = large_corpus.iter().map(|s| s.to_string()).collect();
-    let batch_embeddings = provider.embed_texts(texts).await?;
+    let batch_embeddings = provider.embed_texts_batched(texts).await?;
 
     println!("✓ Generated {} embeddings", batch_embeddings.len());
 
@@ -180,20 +177,3 @@ async fn main() -> anyhow::Result<()> {
 
     Ok(())
 }
-
-/// Calculate cosine similarity between two vectors
-fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
-    if a.len() != b.len() {
-        return 0.0;
-    }
-
-    let dot_product: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
-    let magnitude_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
-    let magnitude_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
-
-    if magnitude_a == 0.0 || magnitude_b == 0.0 {
-        return 0.0;
-    }
-
-    dot_product / (magnitude_a * magnitude_b)
-}
@@ -59,6 +59,7 @@ async fn main() -> anyhow::Result<()> {
 
         let request = LLMRequest {
             model: metadata.name.clone(),
+            system_instruction: None,
             contents: vec![Content {
                 role: "user".to_string(),
                 parts: vec![Part::Text {
@@ -130,8 +131,7 @@ async fn main() -> anyhow::Result<()> {
 
                 // Calculate similarity between first two embeddings
                 if embeddings.len() >= 2 {
-                    let similarity =
-                        cosine_similarity(&embeddings[0].vector, &embeddings[1].vector);
+                    let similarity = embeddings[0].cosine_similarity(&embeddings[1])?;
                     println!("Cosine similarity between text 1 and 2: {:.4}", similarity);
                 }
             }
@@ -171,20 +171,3 @@ async fn main() -> anyhow::Result<()> {
 
     Ok(())
 }
-
-/// Calculate cosine similarity between two vectors
-fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
-    if a.len() != b.len() {
-        return 0.0;
-    }
-
-    let dot_product: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
-    let magnitude_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
-    let magnitude_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
-
-    if magnitude_a == 0.0 || magnitude_b == 0.0 {
-        return 0.0;
-    }
-
-    dot_product / (magnitude_a * magnitude_b)
-}
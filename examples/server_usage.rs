@@ -73,10 +73,12 @@ use std::env;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::time::{Duration, sleep, timeout};
+use tokio_util::sync::CancellationToken;
 use zdk_agent::LLMAgent;
 use zdk_core::{AuthCredentials, Provider, ZConfig};
 use zdk_runner::Runner;
 use zdk_server::rest::create_router;
+use zdk_server::serve_with_shutdown;
 use zdk_session::inmemory::InMemorySessionService;
 use zdk_tool::Tool;
 
@@ -127,7 +129,7 @@ async fn main() -> Result<()> {
     println!("✓ Runner created");
 
     // Build router
-    let app = create_router(runner, session_service);
+    let app = create_router(runner.clone(), session_service);
     println!("✓ Routes configured");
 
     // Start server
@@ -148,7 +150,7 @@ async fn main() -> Result<()> {
     println!("   GET  /api/v1/sessions/:id/run/ws                - WebSocket connection");
 
     println!("\n🧪 Running example workflow...");
-    run_example_workflow(server_url, app, listener).await?;
+    run_example_workflow(server_url, app, listener, runner).await?;
 
     Ok(())
 }
@@ -158,15 +160,19 @@ async fn run_example_workflow(
     server_url: String,
     app: axum::Router,
     listener: tokio::net::TcpListener,
+    runner: Arc<Runner>,
 ) -> Result<()> {
     use serde_json::json;
 
-    // Start server in background
-    let server_handle = tokio::spawn(async move {
-        axum::serve(listener, app)
-            .await
-            .context("Server failed to start")
-    });
+    // Start server in background, stopping gracefully when `shutdown` is cancelled
+    let shutdown = CancellationToken::new();
+    let server_handle = tokio::spawn(serve_with_shutdown(
+        listener,
+        app,
+        runner,
+        shutdown.clone(),
+        Duration::from_secs(10),
+    ));
 
     // Wait for server to be ready
     println!("⏳ Waiting for server to start...");
@@ -275,7 +281,11 @@ async fn run_example_workflow(
 
     // Step 4: Shutdown
     println!("\n📋 Step 4: Cleanup");
-    server_handle.abort();
+    shutdown.cancel();
+    server_handle
+        .await
+        .context("Server task panicked")?
+        .context("Server failed")?;
     println!("   ✅ Server shutdown gracefully");
 
     println!("\n╔═══════════════════════════════════════════════════════════╗");
@@ -178,6 +178,9 @@ async fn run_websocket_client() -> Result<()> {
                                 println!();
                             }
                         }
+                        WsServerMessage::Resumed { invocation_id } => {
+                            println!("   ✓ Invocation resumed: {}", invocation_id);
+                        }
                         WsServerMessage::Completed { invocation_id } => {
                             println!("\n   ✓ Invocation completed: {}", invocation_id);
                         }
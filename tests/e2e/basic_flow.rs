@@ -1,5 +1,5 @@
 use zdk_agent::LLMAgent;
-use zdk_core::{Content, LLM, LLMRequest, LLMResponse, Part, Result};
+use zdk_core::{Content, FinishReason, LLM, LLMRequest, LLMResponse, Part, Result};
 use zdk_runner::Runner;
 use zdk_session::inmemory::InMemorySessionService;
 use async_trait::async_trait;
@@ -53,6 +53,7 @@ impl LLM for MockLLM {
                         finish_reason: None,
                         error_code: None,
                         error_message: None,
+                        usage: None,
                     });
                 }
             }
@@ -68,9 +69,10 @@ impl LLM for MockLLM {
                 partial: false,
                 turn_complete: true,
                 interrupted: false,
-                finish_reason: Some("STOP".to_string()),
+                finish_reason: Some(FinishReason::Stop),
                 error_code: None,
                 error_message: None,
+                usage: None,
             });
         }))
     }
@@ -11,8 +11,10 @@ use futures::stream::{Stream, StreamExt};
 use std::sync::Arc;
 use tower::ServiceExt;
 use zdk_agent::LLMAgent;
-use zdk_core::{Content, Event, LLM, LLMRequest, LLMResponse, Part, Result};
-use zdk_runner::{RunConfig, Runner};
+use zdk_core::{
+    Content, Event, FinishReason, GenerateConfig, LLM, LLMRequest, LLMResponse, Part, Result,
+};
+use zdk_runner::{Compactor, RunConfig, Runner, RunnerDefaults};
 use zdk_session::{SessionService, inmemory::InMemorySessionService};
 
 // Mock LLM for deterministic testing
@@ -57,6 +59,7 @@ impl LLM for TestLLM {
                             finish_reason: None,
                             error_code: None,
                             error_message: None,
+                            usage: None,
                         });
                     }
                 }
@@ -72,14 +75,65 @@ impl LLM for TestLLM {
                 partial: false,
                 turn_complete: true,
                 interrupted: false,
-                finish_reason: Some("STOP".to_string()),
+                finish_reason: Some(FinishReason::Stop),
                 error_code: None,
                 error_message: None,
+                usage: None,
             });
         }))
     }
 }
 
+// Mock LLM that takes a while before answering
+struct DelayedLLM {
+    delay: std::time::Duration,
+    inner: TestLLM,
+}
+
+#[async_trait]
+impl LLM for DelayedLLM {
+    fn name(&self) -> &str {
+        "delayed-llm"
+    }
+
+    async fn generate_content(
+        &self,
+        request: LLMRequest,
+        stream_mode: bool,
+    ) -> Box<dyn Stream<Item = Result<LLMResponse>> + Send + Unpin> {
+        tokio::time::sleep(self.delay).await;
+        self.inner.generate_content(request, stream_mode).await
+    }
+}
+
+/// Wraps an LLM, pausing before each chunk it streams
+struct PacedLLM {
+    interval: std::time::Duration,
+    inner: TestLLM,
+}
+
+#[async_trait]
+impl LLM for PacedLLM {
+    fn name(&self) -> &str {
+        "paced-llm"
+    }
+
+    async fn generate_content(
+        &self,
+        request: LLMRequest,
+        stream_mode: bool,
+    ) -> Box<dyn Stream<Item = Result<LLMResponse>> + Send + Unpin> {
+        let mut inner = self.inner.generate_content(request, stream_mode).await;
+        let interval = self.interval;
+        Box::new(Box::pin(stream! {
+            while let Some(item) = inner.next().await {
+                tokio::time::sleep(interval).await;
+                yield item;
+            }
+        }))
+    }
+}
+
 #[tokio::test]
 async fn test_e2e_session_and_agent_execution() {
     // Setup
@@ -120,7 +174,10 @@ async fn test_e2e_session_and_agent_execution() {
             "test-user".to_string(),
             session.id().to_string(),
             message,
-            RunConfig { streaming: false },
+            RunConfig {
+                streaming: false,
+                ..Default::default()
+            },
         )
         .await
         .unwrap();
@@ -189,7 +246,10 @@ async fn test_e2e_streaming_events() {
             "user1".to_string(),
             "session1".to_string(),
             message,
-            RunConfig { streaming: true },
+            RunConfig {
+                streaming: true,
+                ..Default::default()
+            },
         )
         .await
         .unwrap();
@@ -318,3 +378,1411 @@ async fn test_content_serialization() {
     assert_eq!(parsed.role, "user");
     assert_eq!(parsed.parts.len(), 1);
 }
+
+#[tokio::test]
+async fn test_session_transcript_download() {
+    let llm = Arc::new(TestLLM::new(vec!["Transcript reply"]));
+
+    let agent = LLMAgent::builder()
+        .name("transcript-agent")
+        .description("Transcript agent")
+        .model(llm)
+        .build()
+        .unwrap();
+
+    let session_service = Arc::new(InMemorySessionService::new());
+
+    let runner = Arc::new(
+        Runner::builder()
+            .app_name("transcript-app")
+            .agent(Arc::new(agent))
+            .session_service(session_service.clone())
+            .build()
+            .unwrap(),
+    );
+
+    let session_id = "transcript-session";
+    for message in ["First question", "Second question"] {
+        let mut stream = runner
+            .run(
+                "user".to_string(),
+                session_id.to_string(),
+                Content::new_user_text(message),
+                RunConfig::default(),
+            )
+            .await
+            .unwrap();
+        while stream.next().await.is_some() {}
+    }
+
+    let app = zdk_server::create_router(runner, session_service.clone());
+
+    // JSON format (default)
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/sessions/{}/transcript", session_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()["content-disposition"],
+        "attachment; filename=\"session-transcript-session.json\""
+    );
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let transcript: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(transcript["sessionId"], session_id);
+    assert_eq!(transcript["appName"], "transcript-app");
+    assert_eq!(transcript["events"].as_array().unwrap().len(), 4);
+
+    // Markdown format
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!(
+                    "/api/v1/sessions/{}/transcript?format=markdown",
+                    session_id
+                ))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()["content-type"],
+        "text/markdown; charset=utf-8"
+    );
+    assert_eq!(
+        response.headers()["content-disposition"],
+        "attachment; filename=\"session-transcript-session.md\""
+    );
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let markdown = String::from_utf8(body.to_vec()).unwrap();
+    assert!(markdown.contains("# Session transcript-session"));
+    assert!(markdown.contains("First question"));
+    assert!(markdown.contains("Second question"));
+    assert!(markdown.contains("Transcript reply"));
+
+    // Unknown sessions are a 404, and other users' sessions look unknown
+    session_service
+        .create(&zdk_session::CreateRequest {
+            app_name: "transcript-app".to_string(),
+            user_id: "someone-else".to_string(),
+            session_id: Some("private-session".to_string()),
+        })
+        .await
+        .unwrap();
+    for uri in [
+        "/api/v1/sessions/missing-session/transcript",
+        "/api/v1/sessions/private-session/transcript?userId=someone-else",
+    ] {
+        let response = app
+            .clone()
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND, "{}", uri);
+    }
+}
+
+// Records every request it receives and answers with a fixed reply
+struct RecordingLLM {
+    requests: std::sync::Mutex<Vec<LLMRequest>>,
+}
+
+#[async_trait]
+impl LLM for RecordingLLM {
+    fn name(&self) -> &str {
+        "recording-llm"
+    }
+
+    async fn generate_content(
+        &self,
+        request: LLMRequest,
+        _stream: bool,
+    ) -> Box<dyn Stream<Item = Result<LLMResponse>> + Send + Unpin> {
+        let turn = request.contents.len();
+        self.requests.lock().unwrap().push(request);
+
+        Box::new(Box::pin(stream! {
+            yield Ok(LLMResponse {
+                content: Some(Content::new_model_text(format!("reply to request with {} messages", turn))),
+                partial: false,
+                turn_complete: true,
+                interrupted: false,
+                finish_reason: Some(FinishReason::Stop),
+                error_code: None,
+                error_message: None,
+                usage: None,
+            });
+        }))
+    }
+}
+
+// Compactor that replaces old turns with a fixed synopsis
+struct StaticCompactor {
+    /// Text of the events handed to each compaction
+    compacted: std::sync::Mutex<Vec<Vec<String>>>,
+}
+
+#[async_trait]
+impl Compactor for StaticCompactor {
+    async fn compact(&self, events: &[Event]) -> Result<Content> {
+        let texts = events
+            .iter()
+            .filter_map(|e| e.content.as_ref().map(|c| c.text()))
+            .collect();
+        self.compacted.lock().unwrap().push(texts);
+        Ok(zdk_runner::compaction::synopsis(
+            "the user said hello twice",
+        ))
+    }
+}
+
+#[tokio::test]
+async fn test_runner_compacts_old_turns() {
+    let llm = Arc::new(RecordingLLM {
+        requests: std::sync::Mutex::new(Vec::new()),
+    });
+    let compactor = Arc::new(StaticCompactor {
+        compacted: std::sync::Mutex::new(Vec::new()),
+    });
+
+    let agent = LLMAgent::builder()
+        .name("assistant")
+        .model(llm.clone())
+        .build()
+        .unwrap();
+
+    let session_service = Arc::new(InMemorySessionService::new());
+    let runner = Runner::builder()
+        .app_name("compaction-app")
+        .agent(Arc::new(agent))
+        .session_service(session_service.clone())
+        .compactor(compactor.clone())
+        .compaction_threshold(3)
+        .compaction_keep_recent(1)
+        .build()
+        .unwrap();
+
+    for message in ["hello 1", "hello 2", "hello 3", "hello 4"] {
+        let mut stream = runner
+            .run(
+                "user1".to_string(),
+                "session1".to_string(),
+                Content::new_user_text(message),
+                RunConfig::default(),
+            )
+            .await
+            .unwrap();
+        while let Some(result) = stream.next().await {
+            result.unwrap();
+        }
+    }
+
+    let requests = llm.requests.lock().unwrap().clone();
+    assert_eq!(requests.len(), 4);
+
+    // Below the threshold the full history is sent
+    assert_eq!(requests[1].contents.len(), 3);
+
+    // Four prior events exceed the threshold: three are summarized, one kept
+    let compacted = compactor.compacted.lock().unwrap().clone();
+    assert_eq!(
+        compacted[0],
+        vec!["hello 1", "reply to request with 1 messages", "hello 2"]
+    );
+    let contents = &requests[2].contents;
+    assert_eq!(contents.len(), 3);
+    let texts: Vec<&str> = contents
+        .iter()
+        .map(|c| match &c.parts[0] {
+            Part::Text { text } => text.as_str(),
+            _ => "",
+        })
+        .collect();
+    assert!(texts[0].contains("the user said hello twice"));
+    assert!(texts.iter().all(|t| !t.contains("hello 1")));
+    assert_eq!(texts[1], "reply to request with 3 messages");
+    assert_eq!(texts[2], "hello 3");
+
+    // The next run only folds the newly aged-out events into the stored synopsis
+    assert_eq!(compacted.len(), 2);
+    assert_eq!(compacted[1].len(), 3);
+    assert!(compacted[1][0].contains("the user said hello twice"));
+    assert_eq!(
+        compacted[1][1..],
+        ["reply to request with 3 messages", "hello 3"]
+    );
+    assert_eq!(requests[3].contents.len(), 3);
+
+    // The persisted history is left intact
+    let session = session_service
+        .get(&zdk_session::GetRequest {
+            app_name: "compaction-app".to_string(),
+            user_id: "user1".to_string(),
+            session_id: "session1".to_string(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(session.events().len(), 8);
+}
+
+async fn run_once(runner: &Runner) {
+    let mut stream = runner
+        .run(
+            "user1".to_string(),
+            "session1".to_string(),
+            Content::new_user_text("hello"),
+            RunConfig::default(),
+        )
+        .await
+        .unwrap();
+    while let Some(result) = stream.next().await {
+        result.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_runner_defaults_reach_model_request() {
+    let defaults = RunnerDefaults {
+        generate_config: Some(GenerateConfig {
+            temperature: Some(0.1),
+            ..Default::default()
+        }),
+        max_tool_iterations: Some(2),
+        ..Default::default()
+    };
+
+    // The agent sets nothing, so the runner default applies
+    let llm = Arc::new(RecordingLLM {
+        requests: std::sync::Mutex::new(Vec::new()),
+    });
+    let agent = LLMAgent::builder()
+        .name("assistant")
+        .model(llm.clone())
+        .build()
+        .unwrap();
+    let runner = Runner::builder()
+        .app_name("defaults-app")
+        .agent(Arc::new(agent))
+        .session_service(Arc::new(InMemorySessionService::new()))
+        .defaults(defaults.clone())
+        .build()
+        .unwrap();
+
+    run_once(&runner).await;
+    {
+        let requests = llm.requests.lock().unwrap();
+        let config = requests[0].config.as_ref().expect("runner default applied");
+        assert_eq!(config.temperature, Some(0.1));
+    }
+
+    // An agent-level setting overrides the runner default
+    let llm = Arc::new(RecordingLLM {
+        requests: std::sync::Mutex::new(Vec::new()),
+    });
+    let agent = LLMAgent::builder()
+        .name("assistant")
+        .model(llm.clone())
+        .generate_config(GenerateConfig {
+            temperature: Some(0.9),
+            ..Default::default()
+        })
+        .build()
+        .unwrap();
+    let runner = Runner::builder()
+        .app_name("defaults-app")
+        .agent(Arc::new(agent))
+        .session_service(Arc::new(InMemorySessionService::new()))
+        .defaults(defaults)
+        .build()
+        .unwrap();
+
+    run_once(&runner).await;
+    let requests = llm.requests.lock().unwrap();
+    assert_eq!(requests[0].config.as_ref().unwrap().temperature, Some(0.9));
+}
+
+#[tokio::test]
+async fn test_runner_injects_recalled_memories() {
+    use zdk_memory::{InMemoryMemoryService, MemoryService};
+
+    // Seed memory with an earlier session
+    let session_service = Arc::new(InMemorySessionService::new());
+    let past = session_service
+        .create(&zdk_session::CreateRequest {
+            app_name: "memory-app".to_string(),
+            user_id: "user1".to_string(),
+            session_id: Some("past".to_string()),
+        })
+        .await
+        .unwrap();
+    let mut event = Event::new("inv0".to_string(), "user".to_string());
+    event.content = Some(Content::new_user_text("my cat is named Biscuit"));
+    session_service
+        .append_event(past.id(), event)
+        .await
+        .unwrap();
+    let past = session_service
+        .get(&zdk_session::GetRequest {
+            app_name: "memory-app".to_string(),
+            user_id: "user1".to_string(),
+            session_id: "past".to_string(),
+        })
+        .await
+        .unwrap();
+    let memory = Arc::new(InMemoryMemoryService::new());
+    memory.add_session(past).await.unwrap();
+
+    let llm = Arc::new(RecordingLLM {
+        requests: std::sync::Mutex::new(Vec::new()),
+    });
+    let agent = LLMAgent::builder()
+        .name("assistant")
+        .model(llm.clone())
+        .build()
+        .unwrap();
+    let runner = Runner::builder()
+        .app_name("memory-app")
+        .agent(Arc::new(agent))
+        .session_service(session_service)
+        .memory_service(memory)
+        .build()
+        .unwrap();
+
+    // The last run continues the remembered session itself
+    for (session_id, include_memories) in [("session-0", 0), ("session-3", 3), ("past", 3)] {
+        let mut stream = runner
+            .run(
+                "user1".to_string(),
+                session_id.to_string(),
+                Content::new_user_text("what is my cat called"),
+                RunConfig {
+                    include_memories,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        while let Some(result) = stream.next().await {
+            result.unwrap();
+        }
+    }
+
+    let requests = llm.requests.lock().unwrap();
+    // Memory lookup is off by default
+    assert_eq!(requests[0].contents.len(), 1);
+
+    let contents = &requests[1].contents;
+    assert_eq!(contents.len(), 2);
+    assert!(contents[0].text().contains("my cat is named Biscuit"));
+    assert_eq!(contents[1].text(), "what is my cat called");
+
+    // Turns already in the session's history aren't recalled a second time
+    let contents = &requests[2].contents;
+    assert_eq!(contents.len(), 2);
+    assert_eq!(contents[0].text(), "my cat is named Biscuit");
+    assert_eq!(contents[1].text(), "what is my cat called");
+}
+
+#[tokio::test]
+async fn test_runner_auto_memorizes_completed_runs() {
+    use zdk_memory::{InMemoryMemoryService, MemoryService, SearchRequest};
+
+    let memory = Arc::new(InMemoryMemoryService::new());
+    let agent = LLMAgent::builder()
+        .name("assistant")
+        .model(Arc::new(TestLLM::new(vec!["Noted, your cat is Biscuit"])))
+        .build()
+        .unwrap();
+    let runner = Runner::builder()
+        .app_name("memory-app")
+        .agent(Arc::new(agent))
+        .session_service(Arc::new(InMemorySessionService::new()))
+        .memory_service(memory.clone())
+        .auto_memorize(true)
+        .build()
+        .unwrap();
+
+    let mut stream = runner
+        .run(
+            "user1".to_string(),
+            "session1".to_string(),
+            Content::new_user_text("my cat is named Biscuit"),
+            RunConfig::default(),
+        )
+        .await
+        .unwrap();
+    while let Some(result) = stream.next().await {
+        result.unwrap();
+    }
+
+    let found = memory
+        .search(SearchRequest {
+            query: "biscuit".to_string(),
+            user_id: "user1".to_string(),
+            app_name: "memory-app".to_string(),
+        })
+        .await
+        .unwrap();
+    let authors: Vec<&str> = found.memories.iter().map(|m| m.author.as_str()).collect();
+    assert_eq!(authors.len(), 2);
+    assert!(authors.contains(&"user"));
+    assert!(authors.contains(&"assistant"));
+
+    // Stopping after the reply, without waiting for the stream to end, still memorizes
+    let mut stream = runner
+        .run(
+            "user1".to_string(),
+            "session2".to_string(),
+            Content::new_user_text("my dog is named Rex"),
+            RunConfig::default(),
+        )
+        .await
+        .unwrap();
+    stream.next().await.unwrap().unwrap();
+    drop(stream);
+
+    let search = || {
+        memory.search(SearchRequest {
+            query: "rex".to_string(),
+            user_id: "user1".to_string(),
+            app_name: "memory-app".to_string(),
+        })
+    };
+    let mut found = search().await.unwrap();
+    for _ in 0..50 {
+        if !found.memories.is_empty() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        found = search().await.unwrap();
+    }
+    assert_eq!(found.memories.len(), 1);
+    assert_eq!(found.memories[0].author, "user");
+
+    // Memorizing needs somewhere to store the session
+    let result = Runner::builder()
+        .app_name("memory-app")
+        .agent(Arc::new(
+            LLMAgent::builder()
+                .name("assistant")
+                .model(Arc::new(TestLLM::new(vec!["unused"])))
+                .build()
+                .unwrap(),
+        ))
+        .session_service(Arc::new(InMemorySessionService::new()))
+        .auto_memorize(true)
+        .build();
+    assert!(result.is_err());
+}
+
+#[async_trait]
+impl zdk_core::Provider for TestLLM {
+    fn metadata(&self) -> zdk_core::ProviderMetadata {
+        zdk_core::ProviderMetadata {
+            name: "test".to_string(),
+            display_name: "Test".to_string(),
+            capabilities: vec![zdk_core::Capability::TextGeneration],
+            models: vec![zdk_core::ModelInfo {
+                id: "test-llm".to_string(),
+                display_name: "Test LLM".to_string(),
+                capabilities: vec![zdk_core::Capability::TextGeneration],
+                context_window: Some(4096),
+                embedding_dimensions: None,
+            }],
+        }
+    }
+
+    // Reports what it was handed rather than transcribing
+    async fn transcribe_audio(
+        &self,
+        audio: zdk_core::AudioInput,
+    ) -> Result<zdk_core::TranscriptionResult> {
+        Ok(zdk_core::TranscriptionResult {
+            text: format!("{} bytes of {}", audio.data.len(), audio.format),
+            language: audio.language,
+            duration: None,
+            segments: None,
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_provider_transcribes_audio_content() {
+    use zdk_core::Provider;
+
+    let llm = TestLLM::new(vec!["unused"]);
+    let content = Content::new_user_audio([0x49, 0x44, 0x33, 0x04], "audio/mpeg");
+    let result = llm.transcribe_content(&content).await.unwrap();
+    assert_eq!(result.text, "4 bytes of mp3");
+
+    let result = llm
+        .transcribe_content(&Content::new_user_text("no audio here"))
+        .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_models_endpoint() {
+    let llm = Arc::new(TestLLM::new(vec!["unused"]));
+
+    let agent = LLMAgent::builder()
+        .name("models-agent")
+        .model(llm.clone())
+        .build()
+        .unwrap();
+
+    let session_service = Arc::new(InMemorySessionService::new());
+    let runner = Arc::new(
+        Runner::builder()
+            .app_name("models-app")
+            .agent(Arc::new(agent))
+            .session_service(session_service.clone())
+            .build()
+            .unwrap(),
+    );
+
+    let request = || {
+        Request::builder()
+            .uri("/api/v1/models")
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    // Without a provider the endpoint is unavailable
+    let app = zdk_server::create_router(runner.clone(), session_service.clone());
+    let response = app.oneshot(request()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let app = zdk_server::create_router_with_config(
+        runner,
+        session_service,
+        zdk_server::RouterConfig::new().provider(llm),
+    );
+    let response = app.oneshot(request()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["models"][0]["id"], "test-llm");
+    assert_eq!(json["models"][0]["context_window"], 4096);
+}
+
+/// Provider whose credentials are rejected, counting the pings it receives
+struct UnauthorizedProvider {
+    inner: TestLLM,
+    pings: std::sync::atomic::AtomicUsize,
+}
+
+#[async_trait]
+impl LLM for UnauthorizedProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn generate_content(
+        &self,
+        request: LLMRequest,
+        stream: bool,
+    ) -> Box<dyn Stream<Item = Result<LLMResponse>> + Send + Unpin> {
+        self.inner.generate_content(request, stream).await
+    }
+}
+
+#[async_trait]
+impl zdk_core::Provider for UnauthorizedProvider {
+    fn metadata(&self) -> zdk_core::ProviderMetadata {
+        zdk_core::Provider::metadata(&self.inner)
+    }
+
+    async fn ping(&self) -> Result<()> {
+        self.pings.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        Err(zdk_core::Error::LLMError(
+            "List models API error 401 Unauthorized: invalid API key".to_string(),
+        ))
+    }
+}
+
+#[tokio::test]
+async fn test_readiness_pings_provider() {
+    let provider = Arc::new(UnauthorizedProvider {
+        inner: TestLLM::new(vec!["unused"]),
+        pings: Default::default(),
+    });
+
+    let agent = LLMAgent::builder()
+        .name("readiness-agent")
+        .model(provider.clone())
+        .build()
+        .unwrap();
+
+    let session_service = Arc::new(InMemorySessionService::new());
+    let runner = Arc::new(
+        Runner::builder()
+            .app_name("readiness-app")
+            .agent(Arc::new(agent))
+            .session_service(session_service.clone())
+            .build()
+            .unwrap(),
+    );
+
+    let request = || {
+        Request::builder()
+            .uri("/readiness")
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let app = zdk_server::create_router_with_config(
+        runner.clone(),
+        session_service.clone(),
+        zdk_server::RouterConfig::new().provider(provider.clone()),
+    );
+    for _ in 0..2 {
+        let response = app.clone().oneshot(request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        // The provider's error stays in the server log
+        assert_eq!(body, "PROVIDER UNAVAILABLE");
+    }
+    // The second probe is answered from the cache
+    assert_eq!(provider.pings.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+    // A provider that answers its ping is ready
+    let app = zdk_server::create_router_with_config(
+        runner,
+        session_service,
+        zdk_server::RouterConfig::new().provider(Arc::new(TestLLM::new(vec!["unused"]))),
+    );
+    let response = app.oneshot(request()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_cors_preflight() {
+    use axum::http::header;
+
+    let llm = Arc::new(TestLLM::new(vec!["unused"]));
+    let agent = LLMAgent::builder()
+        .name("cors-agent")
+        .model(llm)
+        .build()
+        .unwrap();
+
+    let session_service = Arc::new(InMemorySessionService::new());
+    let runner = Arc::new(
+        Runner::builder()
+            .app_name("cors-app")
+            .agent(Arc::new(agent))
+            .session_service(session_service.clone())
+            .build()
+            .unwrap(),
+    );
+
+    let preflight = |origin: &str| {
+        Request::builder()
+            .method("OPTIONS")
+            .uri("/api/v1/sessions")
+            .header(header::ORIGIN, origin)
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+            .header(header::ACCESS_CONTROL_REQUEST_HEADERS, "content-type")
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let app = zdk_server::create_router_with_cors(
+        runner.clone(),
+        session_service.clone(),
+        vec!["https://app.example.com".to_string()],
+    );
+    let response = app
+        .clone()
+        .oneshot(preflight("https://app.example.com"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let headers = response.headers();
+    assert_eq!(
+        headers[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+        "https://app.example.com"
+    );
+    assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_CREDENTIALS], "true");
+    let methods = headers[header::ACCESS_CONTROL_ALLOW_METHODS]
+        .to_str()
+        .unwrap();
+    assert!(methods.contains("POST"), "{}", methods);
+    let allowed_headers = headers[header::ACCESS_CONTROL_ALLOW_HEADERS]
+        .to_str()
+        .unwrap();
+    assert!(
+        allowed_headers.contains("content-type"),
+        "{}",
+        allowed_headers
+    );
+
+    // Other origins get no CORS grant
+    let response = app
+        .oneshot(preflight("https://evil.example.com"))
+        .await
+        .unwrap();
+    assert!(
+        !response
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+    );
+
+    // The wildcard allows any origin, but never with credentials
+    let app = zdk_server::create_router_with_config(
+        runner,
+        session_service,
+        zdk_server::RouterConfig::new().cors_origins(vec!["*".to_string()]),
+    );
+    let response = app
+        .oneshot(preflight("http://localhost:3000"))
+        .await
+        .unwrap();
+    let headers = response.headers();
+    assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+    assert!(!headers.contains_key(header::ACCESS_CONTROL_ALLOW_CREDENTIALS));
+}
+
+#[tokio::test]
+async fn test_bearer_auth() {
+    let llm = Arc::new(TestLLM::new(vec!["unused"]));
+
+    let agent = LLMAgent::builder()
+        .name("auth-agent")
+        .model(llm)
+        .build()
+        .unwrap();
+
+    let session_service = Arc::new(InMemorySessionService::new());
+    let runner = Arc::new(
+        Runner::builder()
+            .app_name("auth-app")
+            .agent(Arc::new(agent))
+            .session_service(session_service.clone())
+            .build()
+            .unwrap(),
+    );
+
+    let app = zdk_server::create_router_with_auth(runner, session_service, "s3cret".to_string());
+    let create_session = |authorization: Option<&str>| {
+        let mut builder = Request::builder()
+            .method("POST")
+            .uri("/api/v1/sessions")
+            .header("content-type", "application/json");
+        if let Some(authorization) = authorization {
+            builder = builder.header("authorization", authorization);
+        }
+        builder
+            .body(Body::from(r#"{"appName": "auth-app", "userId": "user"}"#))
+            .unwrap()
+    };
+
+    let response = app.clone().oneshot(create_session(None)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = app
+        .clone()
+        .oneshot(create_session(Some("Bearer wrong")))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = app
+        .clone()
+        .oneshot(create_session(Some("Bearer s3cret")))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Health checks stay open
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/health")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_rate_limit() {
+    let llm = Arc::new(TestLLM::new(vec!["unused"]));
+
+    let agent = LLMAgent::builder()
+        .name("limited-agent")
+        .model(llm)
+        .build()
+        .unwrap();
+
+    let session_service = Arc::new(InMemorySessionService::new());
+    let runner = Arc::new(
+        Runner::builder()
+            .app_name("limited-app")
+            .agent(Arc::new(agent))
+            .session_service(session_service.clone())
+            .build()
+            .unwrap(),
+    );
+
+    let config = zdk_server::RouterConfig::new().rate_limit(zdk_server::RateLimitConfig {
+        requests_per_minute: 1,
+        burst: 2,
+        client_key_header: Some("x-client-id".to_string()),
+    });
+    let app = zdk_server::create_router_with_config(runner, session_service, config);
+    let create_session = |client: &str| {
+        Request::builder()
+            .method("POST")
+            .uri("/api/v1/sessions")
+            .header("content-type", "application/json")
+            .header("x-client-id", client)
+            .body(Body::from(
+                r#"{"appName": "limited-app", "userId": "user"}"#,
+            ))
+            .unwrap()
+    };
+
+    for _ in 0..2 {
+        let response = app.clone().oneshot(create_session("a")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    // The bucket is empty, so the next request is throttled
+    let response = app.clone().oneshot(create_session("a")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    let retry_after: u64 = response.headers()["retry-after"]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!((1..=60).contains(&retry_after));
+
+    // Other clients are unaffected, and health checks are never limited
+    let response = app.clone().oneshot(create_session("b")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    for _ in 0..3 {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/health")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
+
+#[tokio::test]
+async fn test_router_config_combines_features() {
+    let llm = Arc::new(TestLLM::new(vec!["unused"]));
+
+    let agent = LLMAgent::builder()
+        .name("combined-agent")
+        .model(llm.clone())
+        .build()
+        .unwrap();
+
+    let session_service = Arc::new(InMemorySessionService::new());
+    let runner = Arc::new(
+        Runner::builder()
+            .app_name("combined-app")
+            .agent(Arc::new(agent))
+            .session_service(session_service.clone())
+            .build()
+            .unwrap(),
+    );
+
+    let config = zdk_server::RouterConfig::new()
+        .auth_token("s3cret")
+        .rate_limit(zdk_server::RateLimitConfig {
+            requests_per_minute: 1,
+            burst: 2,
+            client_key_header: None,
+        })
+        .provider(llm);
+    let app = zdk_server::create_router_with_config(runner, session_service, config);
+    let list_models = |authorization: Option<&str>| {
+        let mut builder = Request::builder().uri("/api/v1/models");
+        if let Some(authorization) = authorization {
+            builder = builder.header("authorization", authorization);
+        }
+        builder.body(Body::empty()).unwrap()
+    };
+
+    let response = app.clone().oneshot(list_models(None)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let response = app
+        .clone()
+        .oneshot(list_models(Some("Bearer s3cret")))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = app
+        .oneshot(list_models(Some("Bearer s3cret")))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[tokio::test]
+async fn test_rate_limit_by_peer_address() {
+    let llm = Arc::new(TestLLM::new(vec!["unused"]));
+
+    let agent = LLMAgent::builder()
+        .name("limited-agent")
+        .model(llm)
+        .build()
+        .unwrap();
+
+    let session_service = Arc::new(InMemorySessionService::new());
+    let runner = Arc::new(
+        Runner::builder()
+            .app_name("limited-app")
+            .agent(Arc::new(agent))
+            .session_service(session_service.clone())
+            .build()
+            .unwrap(),
+    );
+
+    let config = zdk_server::RouterConfig::new().rate_limit(zdk_server::RateLimitConfig {
+        requests_per_minute: 1,
+        burst: 1,
+        client_key_header: None,
+    });
+    let router = zdk_server::create_router_with_config(runner.clone(), session_service, config);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let shutdown = tokio_util::sync::CancellationToken::new();
+    let server = tokio::spawn(zdk_server::serve_with_shutdown(
+        listener,
+        router,
+        runner,
+        shutdown.clone(),
+        std::time::Duration::from_secs(5),
+    ));
+
+    // Clients on different loopback addresses get their own buckets
+    let create_session = |local: [u8; 4]| async move {
+        reqwest::Client::builder()
+            .local_address(std::net::IpAddr::from(local))
+            .build()
+            .unwrap()
+            .post(format!("http://{}/api/v1/sessions", addr))
+            .header("content-type", "application/json")
+            .body(r#"{"appName": "limited-app", "userId": "user"}"#)
+            .send()
+            .await
+            .unwrap()
+            .status()
+    };
+    assert_eq!(create_session([127, 0, 0, 1]).await, StatusCode::OK);
+    assert_eq!(
+        create_session([127, 0, 0, 1]).await,
+        StatusCode::TOO_MANY_REQUESTS
+    );
+    assert_eq!(create_session([127, 0, 0, 2]).await, StatusCode::OK);
+
+    shutdown.cancel();
+    server.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn test_graceful_shutdown_finishes_in_flight_run() {
+    let llm = Arc::new(DelayedLLM {
+        delay: std::time::Duration::from_millis(300),
+        inner: TestLLM::new(vec!["Finished in time"]),
+    });
+
+    let agent = LLMAgent::builder()
+        .name("slow-agent")
+        .model(llm)
+        .build()
+        .unwrap();
+
+    let session_service = Arc::new(InMemorySessionService::new());
+    let runner = Arc::new(
+        Runner::builder()
+            .app_name("shutdown-app")
+            .agent(Arc::new(agent))
+            .session_service(session_service.clone())
+            .build()
+            .unwrap(),
+    );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let router = zdk_server::create_router(runner.clone(), session_service);
+    let shutdown = tokio_util::sync::CancellationToken::new();
+    let server = tokio::spawn(zdk_server::serve_with_shutdown(
+        listener,
+        router,
+        runner.clone(),
+        shutdown.clone(),
+        std::time::Duration::from_secs(5),
+    ));
+
+    let request = tokio::spawn(async move {
+        reqwest::Client::new()
+            .post(format!("http://{}/api/v1/sessions/s1/run", addr))
+            .json(&serde_json::json!({
+                "newMessage": Content::new_user_text("Hello"),
+                "streaming": false,
+            }))
+            .send()
+            .await
+            .unwrap()
+    });
+
+    // Trigger shutdown while the run is in flight
+    while runner.in_flight() == 0 {
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    shutdown.cancel();
+
+    let response = request.await.unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    let text = body["events"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|event| event["content"]["parts"][0]["text"].as_str())
+        .collect::<String>();
+    assert!(text.contains("Finished in time"));
+
+    server.await.unwrap().unwrap();
+    assert!(runner.is_shutting_down());
+    assert_eq!(runner.in_flight(), 0);
+}
+
+#[tokio::test]
+async fn test_sse_streams_partials_progressively() {
+    let interval = std::time::Duration::from_millis(100);
+    let llm = Arc::new(PacedLLM {
+        interval,
+        inner: TestLLM::new(vec!["one two three four"]),
+    });
+
+    let agent = LLMAgent::builder()
+        .name("sse-agent")
+        .model(llm)
+        .build()
+        .unwrap();
+
+    let session_service = Arc::new(InMemorySessionService::new());
+    let runner = Arc::new(
+        Runner::builder()
+            .app_name("sse-app")
+            .agent(Arc::new(agent))
+            .session_service(session_service.clone())
+            .build()
+            .unwrap(),
+    );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let router = zdk_server::create_router(runner.clone(), session_service);
+    let shutdown = tokio_util::sync::CancellationToken::new();
+    let server = tokio::spawn(zdk_server::serve_with_shutdown(
+        listener,
+        router,
+        runner,
+        shutdown.clone(),
+        std::time::Duration::from_secs(5),
+    ));
+
+    let mut response = reqwest::Client::new()
+        .post(format!("http://{}/api/v1/sessions/s1/run/sse", addr))
+        .json(&serde_json::json!({
+            "newMessage": Content::new_user_text("Count to four"),
+            "streaming": true,
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    // Note when each partial event reaches the client
+    let mut buffer = String::new();
+    let mut partials = Vec::new();
+    while let Some(chunk) = response.chunk().await.unwrap() {
+        let received = std::time::Instant::now();
+        buffer.push_str(std::str::from_utf8(&chunk).unwrap());
+        while let Some(end) = buffer.find("\n\n") {
+            let frame: String = buffer.drain(..end + 2).collect();
+            let Some(data) = frame.trim().strip_prefix("data:") else {
+                continue;
+            };
+            let event: serde_json::Value = serde_json::from_str(data.trim()).unwrap();
+            if event["partial"] == true {
+                partials.push(received);
+            }
+        }
+    }
+    let closed = std::time::Instant::now();
+
+    assert_eq!(partials.len(), 4);
+    // Each partial arrives as it is produced, not all at once when the run ends
+    for pair in partials.windows(2) {
+        assert!(pair[1] - pair[0] >= interval / 2, "{:?}", partials);
+    }
+    assert!(closed - partials[0] >= interval * 3 / 2);
+
+    shutdown.cancel();
+    server.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn test_websocket_cancel_stops_run() {
+    use futures::SinkExt;
+    use tokio_tungstenite::tungstenite::Message;
+    use zdk_server::WsServerMessage;
+
+    // Twenty words at 100ms each would take two seconds to finish
+    let words = vec!["word"; 20].join(" ");
+    let llm = Arc::new(PacedLLM {
+        interval: std::time::Duration::from_millis(100),
+        inner: TestLLM::new(vec![words.as_str()]),
+    });
+
+    let agent = LLMAgent::builder()
+        .name("ws-agent")
+        .model(llm)
+        .build()
+        .unwrap();
+
+    let session_service = Arc::new(InMemorySessionService::new());
+    let runner = Arc::new(
+        Runner::builder()
+            .app_name("ws-app")
+            .agent(Arc::new(agent))
+            .session_service(session_service.clone())
+            .build()
+            .unwrap(),
+    );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let router = zdk_server::create_router(runner.clone(), session_service);
+    let shutdown = tokio_util::sync::CancellationToken::new();
+    let server = tokio::spawn(zdk_server::serve_with_shutdown(
+        listener,
+        router,
+        runner,
+        shutdown.clone(),
+        std::time::Duration::from_secs(5),
+    ));
+
+    let (mut socket, _) =
+        tokio_tungstenite::connect_async(format!("ws://{}/api/v1/sessions/s1/run/ws", addr))
+            .await
+            .unwrap();
+    let run = serde_json::json!({
+        "type": "run",
+        "sessionId": "s1",
+        "newMessage": Content::new_user_text("Keep talking"),
+    });
+    socket
+        .send(Message::Text(run.to_string().into()))
+        .await
+        .unwrap();
+
+    let started = std::time::Instant::now();
+    let mut invocation_id = None;
+    let mut cancelled = false;
+    let mut partials = 0;
+    loop {
+        let msg = tokio::time::timeout(std::time::Duration::from_secs(5), socket.next())
+            .await
+            .expect("run did not finish")
+            .unwrap()
+            .unwrap();
+        let Message::Text(text) = msg else {
+            continue;
+        };
+        match serde_json::from_str::<WsServerMessage>(&text).unwrap() {
+            WsServerMessage::Started { invocation_id: id } => invocation_id = Some(id),
+            WsServerMessage::Event { data, .. } if data.partial => {
+                partials += 1;
+                // Interrupt once the generation is under way
+                if partials == 2 {
+                    let cancel = serde_json::json!({
+                        "type": "cancel",
+                        "invocationId": invocation_id.clone().unwrap(),
+                    });
+                    socket
+                        .send(Message::Text(cancel.to_string().into()))
+                        .await
+                        .unwrap();
+                }
+            }
+            WsServerMessage::Cancelled { invocation_id: id } => {
+                assert_eq!(Some(id), invocation_id);
+                cancelled = true;
+            }
+            WsServerMessage::Completed { .. } => break,
+            _ => {}
+        }
+    }
+
+    assert!(cancelled);
+    // The run stopped early instead of streaming every word
+    assert!(partials < 20, "{} partials", partials);
+    assert!(started.elapsed() < std::time::Duration::from_millis(1500));
+
+    socket.close(None).await.unwrap();
+    shutdown.cancel();
+    server.await.unwrap().unwrap();
+}
+
+/// Mock LLM whose generation fails partway through
+struct FailingLLM;
+
+#[async_trait]
+impl LLM for FailingLLM {
+    fn name(&self) -> &str {
+        "failing-llm"
+    }
+
+    async fn generate_content(
+        &self,
+        _request: LLMRequest,
+        _stream_mode: bool,
+    ) -> Box<dyn Stream<Item = Result<LLMResponse>> + Send + Unpin> {
+        Box::new(futures::stream::iter(vec![Err(zdk_core::Error::LLMError(
+            "model overloaded".to_string(),
+        ))]))
+    }
+}
+
+#[tokio::test]
+async fn test_invocation_status_endpoint() {
+    let llm = Arc::new(PacedLLM {
+        interval: std::time::Duration::from_millis(100),
+        inner: TestLLM::new(vec!["Slow and steady"]),
+    });
+
+    let app_with = |model: Arc<dyn LLM>| {
+        let agent = LLMAgent::builder()
+            .name("status-agent")
+            .model(model)
+            .build()
+            .unwrap();
+        let session_service = Arc::new(InMemorySessionService::new());
+        let runner = Arc::new(
+            Runner::builder()
+                .app_name("status-app")
+                .agent(Arc::new(agent))
+                .session_service(session_service.clone())
+                .build()
+                .unwrap(),
+        );
+        zdk_server::create_router(runner, session_service)
+    };
+    let app = app_with(llm);
+
+    // Client-supplied IDs are scoped to their session
+    let status = |app: axum::Router, invocation_id: &'static str| async move {
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/api/v1/invocations/{}", invocation_id))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let code = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["invocationId"], invocation_id);
+        (code, json["status"].as_str().unwrap().to_string())
+    };
+    let run = |session_id: &str| {
+        Request::builder()
+            .method("POST")
+            .uri(format!("/api/v1/sessions/{}/run", session_id))
+            .header("content-type", "application/json")
+            .body(Body::from(
+                serde_json::json!({
+                    "newMessage": Content::new_user_text("Hello"),
+                    "streaming": false,
+                    "invocationId": "inv-1",
+                })
+                .to_string(),
+            ))
+            .unwrap()
+    };
+
+    let (code, body) = status(app.clone(), "s1:inv-1").await;
+    assert_eq!(code, StatusCode::NOT_FOUND);
+    assert_eq!(body, "not_found");
+
+    let batch = tokio::spawn(app.clone().oneshot(run("s1")));
+
+    // Poll while the batch request is still waiting on the model
+    loop {
+        let (code, body) = status(app.clone(), "s1:inv-1").await;
+        if code == StatusCode::OK {
+            assert_eq!(body, "active");
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    assert!(!batch.is_finished());
+
+    // The ID can't be reused in the same session while the run is in progress
+    let response = app.clone().oneshot(run("s1")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    // but another session's runs don't collide with it
+    let other = tokio::spawn(app.clone().oneshot(run("s2")));
+
+    let response = batch.await.unwrap().unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["x-invocation-id"], "s1:inv-1");
+    assert_eq!(
+        status(app.clone(), "s1:inv-1").await,
+        (StatusCode::OK, "completed".to_string())
+    );
+    assert_eq!(other.await.unwrap().unwrap().status(), StatusCode::OK);
+
+    // A run that fails is reported as errored, not completed
+    let failing = app_with(Arc::new(FailingLLM));
+    let response = failing.clone().oneshot(run("s1")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(
+        status(failing, "s1:inv-1").await,
+        (StatusCode::OK, "errored".to_string())
+    );
+}
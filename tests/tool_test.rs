@@ -4,10 +4,11 @@ use futures::stream::{Stream, StreamExt};
 use std::sync::Arc;
 use zdk_agent::LLMAgent;
 use zdk_core::{
-    Agent, Content, FunctionCall, InvocationContext, LLM, LLMRequest, LLMResponse, Part, Tool,
+    Agent, Content, FinishReason, FunctionCall, InvocationContext, LLM, LLMRequest, LLMResponse,
+    Part, RunConfig, Tool, ToolResponse,
 };
-use zdk_tool::DefaultToolContext;
 use zdk_tool::builtin::{create_calculator_tool, create_echo_tool};
+use zdk_tool::{DefaultToolContext, FunctionTool};
 
 // Mock LLM for testing
 struct MockLLM {
@@ -42,6 +43,7 @@ impl MockLLM {
                     finish_reason: None,
                     error_code: None,
                     error_message: None,
+                    usage: None,
                 }]
             }
             _ => {
@@ -56,9 +58,10 @@ impl MockLLM {
                     partial: false,
                     turn_complete: true,
                     interrupted: false,
-                    finish_reason: Some("STOP".to_string()),
+                    finish_reason: Some(FinishReason::Stop),
                     error_code: None,
                     error_message: None,
+                    usage: None,
                 }]
             }
         }
@@ -94,6 +97,7 @@ impl LLM for MockLLM {
 struct MockContext {
     invocation_id: String,
     user_content: Option<Content>,
+    run_config: RunConfig,
 }
 
 impl MockContext {
@@ -101,8 +105,14 @@ impl MockContext {
         Self {
             invocation_id: "test-inv-1".to_string(),
             user_content: Some(Content::new_user_text("Calculate 2 + 2")),
+            run_config: RunConfig::default(),
         }
     }
+
+    fn with_run_config(mut self, run_config: RunConfig) -> Self {
+        self.run_config = run_config;
+        self
+    }
 }
 
 #[async_trait]
@@ -114,6 +124,10 @@ impl InvocationContext for MockContext {
     fn user_content(&self) -> Option<&Content> {
         self.user_content.as_ref()
     }
+
+    fn run_config(&self) -> &RunConfig {
+        &self.run_config
+    }
 }
 
 impl zdk_core::ReadonlyContext for MockContext {
@@ -191,6 +205,123 @@ async fn test_tool_execution() {
     assert!(has_text_response, "Should have text response with result");
 }
 
+#[tokio::test]
+async fn test_tool_execution_disabled() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let model = Arc::new(MockLLM::new());
+
+    // Calculator stand-in that records every execution
+    let executions = Arc::new(AtomicUsize::new(0));
+    let counter = executions.clone();
+    let calculator = FunctionTool::builder()
+        .name("calculator")
+        .description("Counts executions")
+        .execute(move |_ctx, _params| {
+            let counter = counter.clone();
+            async move {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Ok(ToolResponse::new(serde_json::json!({"result": 4})))
+            }
+        })
+        .build()
+        .unwrap();
+
+    let agent = LLMAgent::builder()
+        .name("test-agent")
+        .description("Plan-only agent")
+        .model(model)
+        .tool(Arc::new(calculator))
+        .build()
+        .unwrap();
+
+    let ctx = Arc::new(MockContext::new().with_run_config(RunConfig {
+        execute_tools: false,
+        ..Default::default()
+    }));
+    let mut stream = agent.run(ctx).await;
+
+    let mut events = Vec::new();
+    while let Some(result) = stream.next().await {
+        events.push(result.unwrap());
+    }
+
+    assert_eq!(executions.load(Ordering::SeqCst), 0, "Tool must not run");
+
+    // The model's function call is still surfaced
+    assert!(events.iter().any(|e| {
+        e.content.as_ref().is_some_and(|c| {
+            c.parts
+                .iter()
+                .any(|p| matches!(p, Part::FunctionCall { .. }))
+        })
+    }));
+
+    // The run ends on the placeholder response instead of calling the model again
+    let last = events.last().unwrap();
+    assert!(last.turn_complete);
+    match &last.content.as_ref().unwrap().parts[0] {
+        Part::FunctionResponse { function_response } => {
+            assert_eq!(function_response.name, "calculator");
+            assert_eq!(function_response.response["status"], "skipped");
+        }
+        other => panic!("Expected placeholder function response, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_tool_timeout() {
+    let model = Arc::new(MockLLM::new());
+
+    // Calculator stand-in that hangs far longer than the timeout
+    let calculator = FunctionTool::builder()
+        .name("calculator")
+        .description("Never finishes in time")
+        .execute(|_ctx, _params| async move {
+            tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+            Ok(ToolResponse::new(serde_json::json!({"result": 4})))
+        })
+        .build()
+        .unwrap();
+
+    let agent = LLMAgent::builder()
+        .name("test-agent")
+        .description("Agent with a slow tool")
+        .model(model)
+        .tool(Arc::new(calculator))
+        .tool_timeout(std::time::Duration::from_millis(50))
+        .build()
+        .unwrap();
+
+    let ctx = Arc::new(MockContext::new());
+    let mut stream = agent.run(ctx).await;
+
+    let mut events = Vec::new();
+    let collect = async {
+        while let Some(result) = stream.next().await {
+            events.push(result.unwrap());
+        }
+    };
+    tokio::time::timeout(std::time::Duration::from_secs(5), collect)
+        .await
+        .expect("agent should not block on the slow tool");
+
+    let timeout_event = events
+        .iter()
+        .find(|e| e.error_code == "TOOL_TIMEOUT")
+        .expect("Should emit a tool timeout event");
+    assert!(timeout_event.error_message.contains("calculator"));
+
+    // The model still gets to answer after the timed-out call
+    let last = events.last().unwrap();
+    assert!(last.is_final_response());
+    assert!(last.content.as_ref().is_some_and(|c| {
+        c.parts
+            .iter()
+            .any(|p| matches!(p, Part::Text { text } if text.contains("result")))
+    }));
+}
+
 #[tokio::test]
 async fn test_calculator_tool() {
     let tool = create_calculator_tool().unwrap();
@@ -223,3 +354,320 @@ async fn test_echo_tool() {
     assert_eq!(response.result["message"], "Hello, tools!");
     assert_eq!(response.result["invocation_id"], "inv-2");
 }
+
+// Mock LLM that calls `tool_names` in one turn, then answers
+struct StateLLM {
+    call_count: std::sync::Mutex<usize>,
+    tool_names: Vec<&'static str>,
+}
+
+#[async_trait]
+impl LLM for StateLLM {
+    fn name(&self) -> &str {
+        "state-llm"
+    }
+
+    async fn generate_content(
+        &self,
+        _request: LLMRequest,
+        _stream: bool,
+    ) -> Box<dyn Stream<Item = zdk_core::Result<LLMResponse>> + Send + Unpin> {
+        let mut count = self.call_count.lock().unwrap();
+        let call_num = *count;
+        *count += 1;
+        drop(count);
+
+        let (parts, turn_complete) = if call_num == 0 {
+            let call = |name: &str| Part::FunctionCall {
+                function_call: FunctionCall {
+                    name: name.to_string(),
+                    args: serde_json::json!({}),
+                    id: None,
+                },
+            };
+            (
+                self.tool_names.iter().map(|name| call(name)).collect(),
+                false,
+            )
+        } else {
+            (
+                vec![Part::Text {
+                    text: "Done".to_string(),
+                }],
+                true,
+            )
+        };
+
+        Box::new(Box::pin(stream! {
+            yield Ok(LLMResponse {
+                content: Some(Content {
+                    role: "model".to_string(),
+                    parts,
+                }),
+                partial: false,
+                turn_complete,
+                interrupted: false,
+                finish_reason: None,
+                error_code: None,
+                error_message: None,
+                usage: None,
+            });
+        }))
+    }
+}
+
+#[tokio::test]
+async fn test_tool_state_shared_within_invocation() {
+    use zdk_session::{GetRequest, SessionService, inmemory::InMemorySessionService};
+
+    let remember = FunctionTool::builder()
+        .name("remember")
+        .description("Stores a note in session state")
+        .execute(|ctx, _params| async move {
+            ctx.set_state("note", serde_json::json!("intermediate result"));
+            Ok(ToolResponse::new(serde_json::json!({"stored": true})))
+        })
+        .build()
+        .unwrap();
+    let recall = FunctionTool::builder()
+        .name("recall")
+        .description("Reads the note back")
+        .execute(|ctx, _params| async move {
+            Ok(ToolResponse::new(
+                serde_json::json!({"note": ctx.state().get("note")}),
+            ))
+        })
+        .build()
+        .unwrap();
+
+    let agent = LLMAgent::builder()
+        .name("test-agent")
+        .model(Arc::new(StateLLM {
+            call_count: std::sync::Mutex::new(0),
+            tool_names: vec!["remember", "recall"],
+        }))
+        .tool(Arc::new(remember))
+        .tool(Arc::new(recall))
+        .build()
+        .unwrap();
+
+    let session_service = Arc::new(InMemorySessionService::new());
+    let runner = zdk_runner::Runner::builder()
+        .app_name("test-app")
+        .agent(Arc::new(agent))
+        .session_service(session_service.clone())
+        .build()
+        .unwrap();
+
+    let mut stream = runner
+        .run(
+            "test-user".to_string(),
+            "state-session".to_string(),
+            Content::new_user_text("Remember and recall"),
+            RunConfig::default(),
+        )
+        .await
+        .unwrap();
+    let mut events = Vec::new();
+    while let Some(result) = stream.next().await {
+        events.push(result.unwrap());
+    }
+
+    // The later tool saw the earlier tool's write
+    let recalled = events
+        .iter()
+        .filter_map(|e| e.content.as_ref())
+        .flat_map(|c| c.parts.iter())
+        .find_map(|p| match p {
+            Part::FunctionResponse { function_response } if function_response.name == "recall" => {
+                Some(function_response.response.clone())
+            }
+            _ => None,
+        })
+        .expect("recall response");
+    assert_eq!(recalled["note"], "intermediate result");
+
+    // The write is persisted to the session
+    let session = session_service
+        .get(&GetRequest {
+            app_name: "test-app".to_string(),
+            user_id: "test-user".to_string(),
+            session_id: "state-session".to_string(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(session.state()["note"], "intermediate result");
+}
+
+#[tokio::test]
+async fn test_tool_calls_persisted_in_order() {
+    use zdk_session::{GetRequest, SessionService, inmemory::InMemorySessionService};
+
+    let lookup = FunctionTool::builder()
+        .name("lookup")
+        .description("Looks something up")
+        .execute(|_ctx, _params| async move {
+            Ok(ToolResponse::new(serde_json::json!({"found": "42"})))
+        })
+        .build()
+        .unwrap();
+
+    let agent = LLMAgent::builder()
+        .name("test-agent")
+        .model(Arc::new(StateLLM {
+            call_count: std::sync::Mutex::new(0),
+            tool_names: vec!["lookup", "missing"],
+        }))
+        .tool(Arc::new(lookup))
+        .build()
+        .unwrap();
+
+    let session_service = Arc::new(InMemorySessionService::new());
+    let runner = zdk_runner::Runner::builder()
+        .app_name("test-app")
+        .agent(Arc::new(agent))
+        .session_service(session_service.clone())
+        .build()
+        .unwrap();
+
+    let mut stream = runner
+        .run(
+            "test-user".to_string(),
+            "transcript-session".to_string(),
+            Content::new_user_text("Look it up"),
+            RunConfig::default(),
+        )
+        .await
+        .unwrap();
+    while let Some(result) = stream.next().await {
+        result.unwrap();
+    }
+
+    let session = session_service
+        .get(&GetRequest {
+            app_name: "test-app".to_string(),
+            user_id: "test-user".to_string(),
+            session_id: "transcript-session".to_string(),
+        })
+        .await
+        .unwrap();
+
+    // Describe each persisted event by what it carries
+    let transcript: Vec<String> = session
+        .events()
+        .iter()
+        // Skip the model's bookkeeping event once its calls were split out
+        .filter(|event| event.content.is_some() || !event.error_code.is_empty())
+        .map(|event| {
+            let content = event.content.as_ref();
+            let calls: Vec<&str> = content
+                .map(|c| c.function_calls().iter().map(|f| f.name.as_str()).collect())
+                .unwrap_or_default();
+            let responses: Vec<&str> = content
+                .into_iter()
+                .flat_map(|c| c.parts.iter())
+                .filter_map(|p| match p {
+                    Part::FunctionResponse { function_response } => {
+                        Some(function_response.name.as_str())
+                    }
+                    _ => None,
+                })
+                .collect();
+            if !calls.is_empty() {
+                format!("call:{}", calls.join(","))
+            } else if !responses.is_empty() {
+                let error = if event.error_code.is_empty() {
+                    String::new()
+                } else {
+                    format!(" ({})", event.error_code)
+                };
+                format!("response:{}{}", responses.join(","), error)
+            } else {
+                format!(
+                    "{}:{}",
+                    event.author,
+                    content.map(|c| c.text()).unwrap_or_default()
+                )
+            }
+        })
+        .collect();
+
+    assert_eq!(
+        transcript,
+        vec![
+            "user:Look it up",
+            "call:lookup,missing",
+            "response:lookup",
+            "response:missing (TOOL_NOT_FOUND)",
+            "test-agent:Done",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_tool_saves_artifact() {
+    use zdk_artifact::{ArtifactPart, ArtifactService, InMemoryArtifactService, LoadRequest};
+    use zdk_session::inmemory::InMemorySessionService;
+
+    let chart = FunctionTool::builder()
+        .name("chart")
+        .description("Renders a chart")
+        .execute(|ctx, _params| async move {
+            let png = vec![0x89, b'P', b'N', b'G'];
+            let version = ctx
+                .save_artifact("chart.png", ArtifactPart::binary("image/png", png))
+                .await?;
+            Ok(ToolResponse::new(
+                serde_json::json!({"file": "chart.png", "version": version}),
+            ))
+        })
+        .build()
+        .unwrap();
+
+    let agent = LLMAgent::builder()
+        .name("test-agent")
+        .model(Arc::new(StateLLM {
+            call_count: std::sync::Mutex::new(0),
+            tool_names: vec!["chart"],
+        }))
+        .tool(Arc::new(chart))
+        .build()
+        .unwrap();
+
+    let artifact_service = Arc::new(InMemoryArtifactService::new());
+    let runner = zdk_runner::Runner::builder()
+        .app_name("test-app")
+        .agent(Arc::new(agent))
+        .session_service(Arc::new(InMemorySessionService::new()))
+        .artifact_service(artifact_service.clone())
+        .build()
+        .unwrap();
+
+    let mut stream = runner
+        .run(
+            "test-user".to_string(),
+            "artifact-session".to_string(),
+            Content::new_user_text("Draw a chart"),
+            RunConfig::default(),
+        )
+        .await
+        .unwrap();
+    while let Some(result) = stream.next().await {
+        let event = result.unwrap();
+        assert!(event.error_code.is_empty(), "{}", event.error_message);
+    }
+
+    // Saved under the run's app, user and session
+    let loaded = artifact_service
+        .load(LoadRequest {
+            app_name: "test-app".to_string(),
+            user_id: "test-user".to_string(),
+            session_id: "artifact-session".to_string(),
+            file_name: "chart.png".to_string(),
+            version: None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(loaded.part.mime_type(), "image/png");
+    assert_eq!(loaded.part.size_bytes(), 4);
+}